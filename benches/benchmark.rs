@@ -42,7 +42,7 @@ fn bench_push_null(bench: &mut Bencher) {
     })
 }
 
-fn create_object(bytes: &mut Vec<u8>) -> Object {
+fn create_object(bytes: &mut Vec<u8>) -> Object<'_> {
     bytes.clear();
     let mut builder = ObjectRefBuilder::try_new(bytes, 6, true).unwrap();
     // {key1: string, key2: 123, key3: true: key4: null, key5: [abc, false], key6: {key: true}}
@@ -112,7 +112,7 @@ fn bench_object_read_object(bench: &mut Bencher) {
     })
 }
 
-fn create_array(bytes: &mut Vec<u8>) -> Array {
+fn create_array(bytes: &mut Vec<u8>) -> Array<'_> {
     bytes.clear();
     let mut builder = ArrayRefBuilder::try_new(bytes, 6).unwrap();
     // [string, 123, true, null, [abc, false], {key: true}]
@@ -200,12 +200,36 @@ fn sort_init() -> (Vec<String>, Vec<u8>) {
 fn sort_test(keys: &[String], bytes: &mut Vec<u8>, key_sorted: bool) {
     bytes.clear();
     let mut builder = ObjectRefBuilder::try_new(bytes, KEYS_COUNT as u16, key_sorted).unwrap();
-    for key in keys.iter().take(KEYS_COUNT as usize) {
+    for key in keys.iter().take(KEYS_COUNT) {
         builder.push_null(key.as_str()).unwrap();
     }
     builder.finish().unwrap();
 }
 
+fn bench_push_numbers_one_by_one(bench: &mut Bencher) {
+    let numbers: Vec<Number> = (0..100).map(Number::from).collect();
+    let mut bytes = Vec::with_capacity(4096);
+    bench.iter(|| {
+        bytes.clear();
+        let mut builder = ArrayRefBuilder::try_new(&mut bytes, numbers.len() as u16).unwrap();
+        for number in numbers.iter() {
+            builder.push_number(number).unwrap();
+        }
+        black_box(builder.finish().unwrap());
+    })
+}
+
+fn bench_push_numbers_bulk(bench: &mut Bencher) {
+    let numbers: Vec<Number> = (0..100).map(Number::from).collect();
+    let mut bytes = Vec::with_capacity(4096);
+    bench.iter(|| {
+        bytes.clear();
+        let mut builder = ArrayRefBuilder::try_new(&mut bytes, numbers.len() as u16).unwrap();
+        builder.push_numbers(&numbers).unwrap();
+        black_box(builder.finish().unwrap());
+    })
+}
+
 fn bench_sort_no(bench: &mut Bencher) {
     let (mut keys, mut bytes) = sort_init();
 
@@ -258,12 +282,72 @@ fn bench_format(bench: &mut Bencher) {
     bench.iter(|| format!("{}", yason.format(true)))
 }
 
+fn bench_format_numbers(bench: &mut Bencher) {
+    let numbers: Vec<String> = (0..1000).map(|i| format!("{}.{:03}e-{}", i, i % 997, i % 50)).collect();
+    let input = format!("[{}]", numbers.join(","));
+    let yason_buf = YasonBuf::parse(&input).unwrap();
+    let yason = yason_buf.as_ref();
+
+    bench.iter(|| format!("{}", yason.format(false)))
+}
+
+fn scalar_inputs() -> Vec<YasonBuf> {
+    ["123", "-45.678", "1.5e-30", r#""short string""#, "true", "null"]
+        .iter()
+        .map(|input| YasonBuf::parse(input).unwrap())
+        .collect()
+}
+
+fn bench_format_scalars(bench: &mut Bencher) {
+    let scalars = scalar_inputs();
+    let mut buf = String::new();
+
+    bench.iter(|| {
+        for scalar in scalars.iter() {
+            buf.clear();
+            scalar.format_to(false, &mut buf).unwrap();
+            black_box(&buf);
+        }
+    })
+}
+
+fn bench_format_compact_scalar(bench: &mut Bencher) {
+    let scalars = scalar_inputs();
+    let mut buf = String::new();
+
+    bench.iter(|| {
+        for scalar in scalars.iter() {
+            buf.clear();
+            scalar.format_compact_scalar(&mut buf).unwrap();
+            black_box(&buf);
+        }
+    })
+}
+
+fn bench_equals(bench: &mut Bencher) {
+    let input = r#"{"key1": 123, "key2": true, "key3": null, "key4": [456, false, null, {"key1": true, "key2": 789, "key3": {"key6": 123}}, [10, false, null]], "key5": {"key1": true, "key2": 789, "key3": null}}"#;
+    let left = YasonBuf::parse(input).unwrap();
+    let right = YasonBuf::parse(input).unwrap();
+
+    bench.iter(|| left.equals(&right).unwrap())
+}
+
+fn bench_equals_structural(bench: &mut Bencher) {
+    let input = r#"{"key1": 123, "key2": true, "key3": null, "key4": [456, false, null, {"key1": true, "key2": 789, "key3": {"key6": 123}}, [10, false, null]], "key5": {"key1": true, "key2": 789, "key3": null}}"#;
+    let left = YasonBuf::parse(input).unwrap();
+    let right = YasonBuf::parse(input).unwrap();
+
+    bench.iter(|| left.equals_structural(&right).unwrap())
+}
+
 benchmark_group!(
     yason_benches,
     bench_push_string,
     bench_push_number,
     bench_push_bool,
     bench_push_null,
+    bench_push_numbers_one_by_one,
+    bench_push_numbers_bulk,
     bench_sort_no,
     bench_sort_insert,
     bench_sort_new_builder,
@@ -282,6 +366,11 @@ benchmark_group!(
     bench_query,
     bench_path_parse,
     bench_format,
+    bench_format_numbers,
+    bench_format_scalars,
+    bench_format_compact_scalar,
+    bench_equals,
+    bench_equals_structural,
 );
 
 benchmark_main!(yason_benches);
@@ -0,0 +1,52 @@
+//! Scans a memory-mapped file of concatenated length-prefixed yason documents.
+//!
+//! Each document is stored as its size in 4 little-endian bytes followed by the yason bytes.
+//!
+//! ```text
+//! cargo run --example mmap_scan --features memmap2
+//! ```
+
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use yason::{frame, MmapYason, Yason, YasonBuf};
+
+fn write_documents(path: &std::path::Path) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    for json in [r#"{"id": 1, "name": "a"}"#, r#"[1, 2, 3]"#, r#""text""#] {
+        let yason = YasonBuf::parse(json).unwrap();
+        frame::write(&mut file, &yason).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    }
+    file.sync_all()
+}
+
+fn scan(bytes: &[u8]) -> Result<Vec<&Yason>, frame::FrameError> {
+    // every document is validated before it is read
+    frame::iter_slice(bytes).collect()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir();
+
+    let path = dir.join(format!("yason-mmap-scan-{}.bin", std::process::id()));
+    write_documents(&path)?;
+    let file = File::open(&path)?;
+    // SAFETY: the file is private to this process and is not modified while it is mapped.
+    let mmap = unsafe { Mmap::map(&file)? };
+    for doc in scan(&mmap)? {
+        println!("{}", doc);
+    }
+    drop(mmap);
+    std::fs::remove_file(&path)?;
+
+    // a file holding a single document can be mapped as a whole
+    let path = dir.join(format!("yason-mmap-single-{}.bin", std::process::id()));
+    std::fs::write(&path, YasonBuf::parse(r#"{"single": true}"#)?.as_bytes())?;
+    let file = File::open(&path)?;
+    let yason = unsafe { MmapYason::map(&file)? };
+    println!("{}", yason.format(true));
+    drop(yason);
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+//! Renders a yason as YAML in block style by implementing `yason::Formatter`.
+//!
+//! ```text
+//! cargo run --example yaml
+//! ```
+
+use std::fmt;
+use yason::{format_escaped_str, FormatResult, Formatter, Number, YasonBuf};
+
+const INDENT: usize = 2;
+
+/// Formatter which writes YAML in block style, strings are written as double-quoted scalars.
+#[derive(Default)]
+struct YamlFormatter {
+    level: usize,
+    started: bool,
+    empty: bool,
+    pending_space: bool,
+}
+
+impl YamlFormatter {
+    /// Starts a new line for a key or an array item, nested containers are moved to their own lines.
+    fn new_line<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.empty = false;
+        self.pending_space = false;
+        if self.started {
+            writer.write_char('\n')?;
+        }
+        self.started = true;
+        for _ in 0..(self.level - 1) * INDENT {
+            writer.write_char(' ')?;
+        }
+        Ok(())
+    }
+
+    /// Writes the space between `:` or `-` and a scalar written on the same line.
+    fn begin_scalar<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        if self.pending_space {
+            writer.write_char(' ')?;
+            self.pending_space = false;
+        }
+        self.started = true;
+        Ok(())
+    }
+
+    fn end_container<W: fmt::Write>(&mut self, empty: &str, writer: &mut W) -> FormatResult<()> {
+        self.level -= 1;
+        if self.empty {
+            self.begin_scalar(writer)?;
+            writer.write_str(empty)?;
+            self.empty = false;
+        }
+        Ok(())
+    }
+}
+
+impl Formatter for YamlFormatter {
+    fn write_null<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.begin_scalar(writer)?;
+        writer.write_str("null")?;
+        Ok(())
+    }
+
+    fn write_bool<W: fmt::Write>(&mut self, value: bool, writer: &mut W) -> FormatResult<()> {
+        self.begin_scalar(writer)?;
+        writer.write_str(if value { "true" } else { "false" })?;
+        Ok(())
+    }
+
+    fn write_number<W: fmt::Write>(&mut self, value: &Number, writer: &mut W) -> FormatResult<()> {
+        self.begin_scalar(writer)?;
+        write!(writer, "{}", value)?;
+        Ok(())
+    }
+
+    fn write_string<W: fmt::Write>(&mut self, value: &str, writer: &mut W) -> FormatResult<()> {
+        // YAML double-quoted scalars accept the JSON escape sequences.
+        self.begin_scalar(writer)?;
+        writer.write_char('"')?;
+        format_escaped_str(value, writer)?;
+        writer.write_char('"')?;
+        Ok(())
+    }
+
+    fn begin_array<W: fmt::Write>(&mut self, _writer: &mut W) -> FormatResult<()> {
+        self.level += 1;
+        self.empty = true;
+        Ok(())
+    }
+
+    fn end_array<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.end_container("[]", writer)
+    }
+
+    fn begin_array_value<W: fmt::Write>(&mut self, _first: bool, writer: &mut W) -> FormatResult<()> {
+        self.new_line(writer)?;
+        writer.write_char('-')?;
+        self.pending_space = true;
+        Ok(())
+    }
+
+    fn begin_object<W: fmt::Write>(&mut self, _writer: &mut W) -> FormatResult<()> {
+        self.level += 1;
+        self.empty = true;
+        Ok(())
+    }
+
+    fn end_object<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.end_container("{}", writer)
+    }
+
+    fn begin_object_key<W: fmt::Write>(&mut self, _first: bool, writer: &mut W) -> FormatResult<()> {
+        self.new_line(writer)
+    }
+
+    fn begin_object_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        writer.write_char(':')?;
+        self.pending_space = true;
+        Ok(())
+    }
+}
+
+fn main() {
+    let yason = YasonBuf::parse(
+        r#"{"name": "yason", "version": 2, "tags": ["json", "binary"], "owner": {"id": 1, "alias": null},
+            "empty": {}, "matrix": [[1, 2], [], {"x": true}]}"#,
+    )
+    .unwrap();
+
+    let mut yaml = String::new();
+    YamlFormatter::default().format(&yason, &mut yaml).unwrap();
+    println!("{}", yaml);
+}
@@ -0,0 +1,445 @@
+//! Statistics of the keys and values of many documents.
+
+use crate::binary::{
+    BOOL_SIZE, DATA_TYPE_SIZE, DATE_SIZE, INTERVAL_DT_SIZE, INTERVAL_YM_SIZE, NUMBER_LENGTH_SIZE, SHORT_DATE_SIZE,
+    TIMESTAMP_SIZE, TIME_SIZE,
+};
+use crate::format::format_escaped_str;
+use crate::json::Json;
+use crate::util::varint_size;
+use crate::yason::YasonResult;
+use crate::{BuildError, DataType, Number, Value, Yason, YasonBuf, YasonError};
+use decimal_rs::MAX_BINARY_SIZE;
+use std::borrow::Cow;
+use std::collections::btree_map::{self, BTreeMap};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+const DATA_TYPES: [DataType; 13] = [
+    DataType::Object,
+    DataType::Array,
+    DataType::String,
+    DataType::Number,
+    DataType::Bool,
+    DataType::Null,
+    DataType::Binary,
+    DataType::Timestamp,
+    DataType::Date,
+    DataType::ShortDate,
+    DataType::Time,
+    DataType::IntervalYearMonth,
+    DataType::IntervalDayTime,
+];
+
+/// Statistics of the values found at a key path.
+#[derive(Clone, Debug, Default)]
+pub struct KeyStats {
+    count: usize,
+    documents: usize,
+    objects: usize,
+    duplicates: usize,
+    total_size: usize,
+    types: [usize; DATA_TYPES.len()],
+    min_number: Option<Number>,
+    max_number: Option<Number>,
+    keys: BTreeSet<String>,
+    last_document: Option<usize>,
+}
+
+impl KeyStats {
+    /// Returns the number of values found at the key path.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the number of documents containing the key path.
+    #[inline]
+    pub fn documents(&self) -> usize {
+        self.documents
+    }
+
+    /// Returns the number of objects containing the key of the key path. An object is counted once
+    /// even if the key is duplicated in it. It is zero for the root and the elements of arrays.
+    #[inline]
+    pub fn objects(&self) -> usize {
+        self.objects
+    }
+
+    /// Returns the number of values found at the key path under a key which was already found in
+    /// the same object, i.e. values of duplicate keys.
+    #[inline]
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+
+    /// Returns the total size in bytes of the encoded values.
+    #[inline]
+    pub fn total_size(&self) -> usize {
+        self.total_size
+    }
+
+    /// Returns the average size in bytes of the encoded values.
+    #[inline]
+    pub fn average_size(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total_size as f64 / self.count as f64
+        }
+    }
+
+    /// Returns the number of values of the specified type.
+    #[inline]
+    pub fn type_count(&self, data_type: DataType) -> usize {
+        self.types[type_index(data_type)]
+    }
+
+    /// Returns the types of the values with their counts, types which are never found are skipped.
+    #[inline]
+    pub fn types(&self) -> impl Iterator<Item = (DataType, usize)> + '_ {
+        DATA_TYPES
+            .iter()
+            .zip(self.types.iter())
+            .filter(|(_, &count)| count > 0)
+            .map(|(&data_type, &count)| (data_type, count))
+    }
+
+    /// Returns the minimum of the numbers found at the key path.
+    #[inline]
+    pub fn min_number(&self) -> Option<&Number> {
+        self.min_number.as_ref()
+    }
+
+    /// Returns the maximum of the numbers found at the key path.
+    #[inline]
+    pub fn max_number(&self) -> Option<&Number> {
+        self.max_number.as_ref()
+    }
+
+    #[inline]
+    fn record(&mut self, value: &Value, document: usize) {
+        self.count += 1;
+        if self.last_document != Some(document) {
+            self.last_document = Some(document);
+            self.documents += 1;
+        }
+        self.total_size += encoded_size(value);
+        self.types[type_index(value.data_type())] += 1;
+        if let Value::Number(number) = value {
+            if self.min_number.as_ref().map_or(true, |min| number < min) {
+                self.min_number = Some(*number);
+            }
+            if self.max_number.as_ref().map_or(true, |max| number > max) {
+                self.max_number = Some(*number);
+            }
+        }
+    }
+}
+
+/// Statistics of the key paths found in many documents, see [`key_histogram`].
+///
+/// Key paths are written as path expressions, the root is `$`, object members are `$.key` or
+/// `$."quoted key"` and the elements of arrays are `$.key[*]`.
+#[derive(Clone, Debug, Default)]
+pub struct KeyHistogram {
+    documents: usize,
+    paths: BTreeMap<String, KeyStats>,
+}
+
+impl KeyHistogram {
+    /// Returns the number of analyzed documents.
+    #[inline]
+    pub fn documents(&self) -> usize {
+        self.documents
+    }
+
+    /// Returns the statistics of the key path.
+    #[inline]
+    pub fn get(&self, path: &str) -> Option<&KeyStats> {
+        self.paths.get(path)
+    }
+
+    /// Returns the number of key paths.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns true if there are no key paths.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns an iterator over the key paths and their statistics, ordered by key path.
+    #[inline]
+    pub fn iter(&self) -> btree_map::Iter<'_, String, KeyStats> {
+        self.paths.iter()
+    }
+
+    /// Adds a document to the statistics.
+    #[inline]
+    pub fn add(&mut self, doc: &Yason) -> YasonResult<()> {
+        let document = self.documents;
+        self.documents += 1;
+
+        let mut path = String::from("$");
+        let value = Value::try_from(doc)?;
+        self.add_value(&mut path, &value, document)
+    }
+
+    fn add_value(&mut self, path: &mut String, value: &Value, document: usize) -> YasonResult<()> {
+        match self.paths.get_mut(path.as_str()) {
+            Some(stats) => stats.record(value, document),
+            None => {
+                let mut stats = KeyStats::default();
+                stats.record(value, document);
+                self.paths.insert(path.clone(), stats);
+            }
+        }
+
+        let len = path.len();
+        match value {
+            Value::Object(object) => {
+                let mut object_keys = BTreeSet::new();
+                for entry in object.iter()? {
+                    let (key, value) = entry?;
+                    if let Some(stats) = self.paths.get_mut(path.as_str()) {
+                        if !stats.keys.contains(key) {
+                            stats.keys.insert(key.to_string());
+                        }
+                    }
+                    let duplicate = !object_keys.insert(key);
+                    push_key(path, key);
+                    self.add_value(path, &value, document)?;
+                    if let Some(stats) = self.paths.get_mut(path.as_str()) {
+                        if duplicate {
+                            stats.duplicates += 1;
+                        } else {
+                            stats.objects += 1;
+                        }
+                    }
+                    path.truncate(len);
+                }
+            }
+            Value::Array(array) => {
+                path.push_str("[*]");
+                for value in array.iter()? {
+                    self.add_value(path, &value?, document)?;
+                }
+                path.truncate(len);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Possible errors that can arise when inferring a schema.
+#[derive(Debug)]
+pub enum SchemaError {
+    YasonError(YasonError),
+    BuildError(BuildError),
+}
+
+impl Display for SchemaError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SchemaError::YasonError(e) => write!(f, "{}", e),
+            SchemaError::BuildError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+impl From<YasonError> for SchemaError {
+    #[inline]
+    fn from(e: YasonError) -> Self {
+        SchemaError::YasonError(e)
+    }
+}
+
+impl From<BuildError> for SchemaError {
+    #[inline]
+    fn from(e: BuildError) -> Self {
+        SchemaError::BuildError(e)
+    }
+}
+
+pub type SchemaResult<T> = std::result::Result<T, SchemaError>;
+
+impl KeyHistogram {
+    /// Builds a JSON Schema describing the analyzed documents, see [`infer_schema`].
+    #[inline]
+    pub fn to_schema(&self) -> SchemaResult<YasonBuf> {
+        let mut schema = vec![(
+            Cow::Borrowed("$schema"),
+            Json::String(Cow::Borrowed("https://json-schema.org/draft/2020-12/schema")),
+        )];
+        if let Some(stats) = self.paths.get("$") {
+            schema.extend(self.schema_of("$", stats));
+        }
+        Ok(Json::Object(schema).to_yason()?)
+    }
+
+    fn schema_of(&self, path: &str, stats: &KeyStats) -> Vec<(Cow<'static, str>, Json<'static>)> {
+        let mut schema = Vec::new();
+
+        let mut types: Vec<&'static str> = Vec::new();
+        for (data_type, _) in stats.types() {
+            // binaries and temporal values are strings in json as well
+            let ty = schema_type(data_type);
+            if !types.contains(&ty) {
+                types.push(ty);
+            }
+        }
+        let types = if types.len() == 1 {
+            Json::String(Cow::Borrowed(types[0]))
+        } else {
+            Json::Array(types.into_iter().map(|ty| Json::String(Cow::Borrowed(ty))).collect())
+        };
+        schema.push((Cow::Borrowed("type"), types));
+
+        if !stats.keys.is_empty() {
+            let objects = stats.type_count(DataType::Object);
+            let mut properties = Vec::new();
+            let mut required = Vec::new();
+            let mut child_path = path.to_string();
+            for key in stats.keys.iter() {
+                push_key(&mut child_path, key);
+                if let Some(child) = self.paths.get(&child_path) {
+                    properties.push((
+                        Cow::Owned(key.clone()),
+                        Json::Object(self.schema_of(&child_path, child)),
+                    ));
+                    // a key is required if every object contains it, however many times
+                    if child.objects == objects {
+                        required.push(Json::String(Cow::Owned(key.clone())));
+                    }
+                }
+                child_path.truncate(path.len());
+            }
+            schema.push((Cow::Borrowed("properties"), Json::Object(properties)));
+            schema.push((Cow::Borrowed("required"), Json::Array(required)));
+        }
+
+        if stats.type_count(DataType::Array) > 0 {
+            let items_path = format!("{}[*]", path);
+            if let Some(items) = self.paths.get(&items_path) {
+                schema.push((Cow::Borrowed("items"), Json::Object(self.schema_of(&items_path, items))));
+            }
+        }
+
+        if let (Some(min), Some(max)) = (stats.min_number(), stats.max_number()) {
+            // numbers are written as text, so they are exact
+            schema.push((Cow::Borrowed("minimum"), Json::Number(Cow::Owned(min.to_string()))));
+            schema.push((Cow::Borrowed("maximum"), Json::Number(Cow::Owned(max.to_string()))));
+        }
+
+        schema
+    }
+}
+
+/// Infers a JSON Schema from the documents, capturing the observed types of every key path, the
+/// keys found in all objects as `required`, and the ranges of numbers as `minimum` and `maximum`.
+#[inline]
+pub fn infer_schema<'a, I>(docs: I) -> SchemaResult<YasonBuf>
+where
+    I: IntoIterator<Item = &'a Yason>,
+{
+    key_histogram(docs)?.to_schema()
+}
+
+/// Walks the documents and collects the frequency, the average value size and the type
+/// distribution of every key path.
+#[inline]
+pub fn key_histogram<'a, I>(docs: I) -> YasonResult<KeyHistogram>
+where
+    I: IntoIterator<Item = &'a Yason>,
+{
+    let mut histogram = KeyHistogram::default();
+    for doc in docs {
+        histogram.add(doc)?;
+    }
+    Ok(histogram)
+}
+
+#[inline]
+fn schema_type(data_type: DataType) -> &'static str {
+    match data_type {
+        DataType::Object => "object",
+        DataType::Array => "array",
+        DataType::String => "string",
+        DataType::Number => "number",
+        DataType::Bool => "boolean",
+        DataType::Null => "null",
+        DataType::Binary
+        | DataType::Timestamp
+        | DataType::Date
+        | DataType::ShortDate
+        | DataType::Time
+        | DataType::IntervalYearMonth
+        | DataType::IntervalDayTime => "string",
+    }
+}
+
+#[inline]
+fn type_index(data_type: DataType) -> usize {
+    match data_type {
+        DataType::Object => 0,
+        DataType::Array => 1,
+        DataType::String => 2,
+        DataType::Number => 3,
+        DataType::Bool => 4,
+        DataType::Null => 5,
+        DataType::Binary => 6,
+        DataType::Timestamp => 7,
+        DataType::Date => 8,
+        DataType::ShortDate => 9,
+        DataType::Time => 10,
+        DataType::IntervalYearMonth => 11,
+        DataType::IntervalDayTime => 12,
+    }
+}
+
+#[inline]
+fn push_key(path: &mut String, key: &str) {
+    path.push('.');
+    let mut chars = key.chars();
+    let unquoted =
+        matches!(chars.next(), Some(c) if c.is_ascii_alphabetic()) && chars.all(|c| c.is_ascii_alphanumeric());
+    if unquoted {
+        path.push_str(key);
+    } else {
+        path.push('"');
+        format_escaped_str(key, path).expect("writing to a string never fails");
+        path.push('"');
+    }
+}
+
+#[inline]
+fn encoded_size(value: &Value) -> usize {
+    match value {
+        Value::Object(object) => object.yason().as_bytes().len(),
+        Value::Array(array) => array.yason().as_bytes().len(),
+        Value::String(s) => DATA_TYPE_SIZE + varint_size(s.len() as u32) + s.len(),
+        Value::Number(number) => {
+            let mut buf = [0u8; MAX_BINARY_SIZE];
+            let size = number.compact_encode(&mut buf[..]).expect("failed to encode number");
+            DATA_TYPE_SIZE + NUMBER_LENGTH_SIZE + size
+        }
+        Value::Bool(_) => DATA_TYPE_SIZE + BOOL_SIZE,
+        Value::Null => DATA_TYPE_SIZE,
+        Value::Binary(b) => DATA_TYPE_SIZE + varint_size(b.len() as u32) + b.len(),
+        Value::Timestamp(_) => DATA_TYPE_SIZE + TIMESTAMP_SIZE,
+        Value::Date(_) => DATA_TYPE_SIZE + DATE_SIZE,
+        Value::ShortDate(_) => DATA_TYPE_SIZE + SHORT_DATE_SIZE,
+        Value::Time(_) => DATA_TYPE_SIZE + TIME_SIZE,
+        Value::IntervalYearMonth(_) => DATA_TYPE_SIZE + INTERVAL_YM_SIZE,
+        Value::IntervalDayTime(_) => DATA_TYPE_SIZE + INTERVAL_DT_SIZE,
+    }
+}
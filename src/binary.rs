@@ -1,16 +1,67 @@
 //! Yason binary format.
+//!
+//! All multi-byte fields are little-endian. They are always accessed through `to_le_bytes` and
+//! `from_le_bytes`, so the encoding does not depend on the endianness of the host.
+//!
+//! A document may start with a header made of a magic, the format version and flags, see
+//! [`crate::Yason::format_version`]. Bytes without a header are read as the legacy version 0.
+//!
+//! A document may be followed by a footer holding the CRC32C of the bytes before it, see
+//! [`crate::Yason::verify_checksum`].
 
 use crate::DataType;
 use std::mem::size_of;
 
+/// Size of the type of a value.
 pub const DATA_TYPE_SIZE: usize = size_of::<DataType>();
+/// Size of the size field of an object.
 pub const OBJECT_SIZE: usize = size_of::<i32>();
+/// Size of the size field of an array.
 pub const ARRAY_SIZE: usize = OBJECT_SIZE;
+/// Size of a bool value.
 pub const BOOL_SIZE: usize = size_of::<u8>();
+/// Size of the element count of an object or an array.
 pub const ELEMENT_COUNT_SIZE: usize = size_of::<u16>();
+/// Size of an entry of the key-offset table of an object.
 pub const KEY_OFFSET_SIZE: usize = size_of::<u32>();
+/// Type byte of a compact object, whose key-offset table holds `u16` offsets. It is read as
+/// [`DataType::Object`].
+pub const COMPACT_OBJECT_TYPE: u8 = 24;
+/// Size of an entry of the key-offset table of a compact object.
+pub const COMPACT_KEY_OFFSET_SIZE: usize = size_of::<u16>();
+/// Maximum size of a compact object, so that every key offset fits in a `u16`.
+pub const MAX_COMPACT_OBJECT_SIZE: usize = u16::MAX as usize;
+/// Size of a value entry of an array, the type followed by an offset or an inlined value.
 pub const VALUE_ENTRY_SIZE: usize = DATA_TYPE_SIZE + size_of::<u32>();
+/// Size of the length of a key.
 pub const KEY_LENGTH_SIZE: usize = size_of::<u16>();
+/// Maximum size of the data length of a string or binary value.
 pub const MAX_DATA_LENGTH_SIZE: usize = size_of::<u32>();
+/// Maximum length of a string or binary value, the largest data length.
 pub const MAX_STRING_SIZE: usize = 268435455; // 2^28 - 1
+/// Size of the length of a number.
 pub const NUMBER_LENGTH_SIZE: usize = size_of::<u8>();
+/// Size of a timestamp value.
+pub const TIMESTAMP_SIZE: usize = size_of::<i64>();
+/// Size of a date value.
+pub const DATE_SIZE: usize = size_of::<i64>();
+/// Size of a short date value.
+pub const SHORT_DATE_SIZE: usize = size_of::<i32>();
+/// Size of a time value.
+pub const TIME_SIZE: usize = size_of::<i64>();
+/// Size of an interval year-month value.
+pub const INTERVAL_YM_SIZE: usize = size_of::<i32>();
+/// Size of an interval day-time value.
+pub const INTERVAL_DT_SIZE: usize = size_of::<i64>();
+/// Size of the CRC32C footer.
+pub const CHECKSUM_SIZE: usize = size_of::<u32>();
+/// Magic at the start of a version header.
+pub const HEADER_MAGIC: [u8; 3] = *b"YSN"; // never a valid data type
+/// Size of a version header.
+pub const HEADER_SIZE: usize = HEADER_MAGIC.len() + size_of::<u8>() + size_of::<u8>(); // magic, version, flags
+/// Version of bytes without a header.
+pub const LEGACY_FORMAT_VERSION: u8 = 0;
+/// Current version of the binary format.
+pub const FORMAT_VERSION: u8 = 1;
+/// Header flag of a document followed by a CRC32C footer.
+pub const FLAG_CHECKSUM: u8 = 0x01;
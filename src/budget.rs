@@ -0,0 +1,49 @@
+//! Step budget for cooperative cancellation.
+
+use crate::yason::{YasonError, YasonResult};
+
+/// A budget of nodes that an operation may visit before it is aborted with
+/// [`YasonError::Cancelled`].
+///
+/// A budget can be shared by several operations, e.g. all the queries of a statement, to bound
+/// their total work. The remaining budget is decreased by one for each visited value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StepBudget {
+    remaining: usize,
+}
+
+impl StepBudget {
+    /// Creates a `StepBudget` allowing at most `max_steps` visited nodes.
+    #[inline]
+    pub const fn new(max_steps: usize) -> Self {
+        Self { remaining: max_steps }
+    }
+
+    /// Creates a `StepBudget` which is never exhausted.
+    #[inline]
+    pub const fn unlimited() -> Self {
+        Self::new(usize::MAX)
+    }
+
+    /// Returns the number of nodes that can still be visited.
+    #[inline]
+    pub const fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    #[inline]
+    pub(crate) fn step(&mut self) -> YasonResult<()> {
+        if self.remaining == 0 {
+            return Err(YasonError::Cancelled);
+        }
+        self.remaining -= 1;
+        Ok(())
+    }
+}
+
+impl Default for StepBudget {
+    #[inline]
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
@@ -1,14 +1,64 @@
 //! Array builder.
 
 use crate::binary::{
-    ARRAY_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, MAX_DATA_LENGTH_SIZE, NUMBER_LENGTH_SIZE, VALUE_ENTRY_SIZE,
+    ARRAY_SIZE, BOOL_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, MAX_DATA_LENGTH_SIZE, NUMBER_LENGTH_SIZE,
+    VALUE_ENTRY_SIZE,
 };
 use crate::builder::object::InnerObjectBuilder;
-use crate::builder::{BuildResult, Depth, DEFAULT_SIZE, MAX_NESTED_DEPTH};
+use crate::builder::{
+    element_count, push_encoded_value, BuildResult, CapacityHint, Depth, EstimateSize, NumberConstraint, TryIntoNumber,
+};
+use crate::limits::MAX_NESTED_DEPTH;
+use crate::util::read_fixed;
 use crate::vec::VecExt;
-use crate::yason::{Yason, YasonBuf};
-use crate::{BuildError, DataType, Number, ObjectRefBuilder};
+use crate::yason::{push_checksum, Yason, YasonBuf};
+use crate::{
+    BuildError, DataType, Date, DtInterval, Number, ObjectRefBuilder, ShortDate, Time, Timestamp, Value, YmInterval,
+};
 use decimal_rs::MAX_BINARY_SIZE;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::mem::size_of;
+use std::ops::Range;
+
+/// The scalars pushed into an array built as a set, see [`ArrayBuilder::try_new_set`].
+struct ScalarSet {
+    ignore_duplicates: bool,
+    state: RandomState,
+    /// The positions of the encoded scalars in the buffer, by the hashes of their bytes.
+    scalars: HashMap<u64, Vec<Range<usize>>>,
+}
+
+impl ScalarSet {
+    #[inline]
+    fn try_new(element_count: u16, ignore_duplicates: bool) -> BuildResult<Self> {
+        let mut scalars = HashMap::new();
+        scalars.try_reserve(element_count as usize)?;
+        Ok(Self {
+            ignore_duplicates,
+            state: RandomState::new(),
+            scalars,
+        })
+    }
+
+    /// Adds the encoded scalar at `scalar` of the bytes, returns false if an equal one has been added.
+    #[inline]
+    fn insert(&mut self, bytes: &[u8], scalar: Range<usize>) -> BuildResult<bool> {
+        let mut hasher = self.state.build_hasher();
+        hasher.write(&bytes[scalar.clone()]);
+        let scalars = self.scalars.entry(hasher.finish()).or_default();
+        if scalars
+            .iter()
+            .any(|added| bytes[added.clone()] == bytes[scalar.clone()])
+        {
+            return Ok(false);
+        }
+        scalars.try_reserve(1)?;
+        scalars.push(scalar);
+        Ok(true)
+    }
+}
 
 pub(crate) struct InnerArrayBuilder<'a, B: AsMut<Vec<u8>>> {
     bytes: B,
@@ -19,6 +69,11 @@ pub(crate) struct InnerArrayBuilder<'a, B: AsMut<Vec<u8>>> {
     bytes_init_len: usize,
     current_depth: usize,
     total_nested_depth: Depth<'a>,
+    checksum: bool,
+    /// Whether the objects are finished as compact objects if they fit, inherited by the nested
+    /// builders.
+    pub(crate) compact_objects: bool,
+    set: Option<Box<ScalarSet>>,
 }
 
 impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
@@ -32,7 +87,7 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         let bytes_init_len = bs.len();
 
         let size = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE * element_count as usize;
-        bs.try_reserve(size)?;
+        bs.try_reserve_bytes(size)?;
 
         bs.push_data_type(DataType::Array); // type
         bs.skip_size(); // size
@@ -52,6 +107,9 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
             bytes_init_len,
             current_depth: total_depth.depth(),
             total_nested_depth: total_depth,
+            checksum: false,
+            compact_objects: false,
+            set: None,
         })
     }
 
@@ -60,12 +118,16 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         if self.current_depth != self.total_nested_depth.depth() {
             return Err(BuildError::InnerUncompletedError);
         }
-        if self.value_count != self.element_count {
+        let ignore_duplicates = self.set.as_ref().map_or(false, |set| set.ignore_duplicates);
+        if self.value_count != self.element_count && !(ignore_duplicates && self.value_count < self.element_count) {
             return Err(BuildError::InconsistentElementCount {
                 expected: self.element_count,
                 actual: self.value_count,
             });
         }
+        if self.value_count < self.element_count {
+            self.remove_unused_entries();
+        }
 
         let bytes = self.bytes.as_mut();
         let total_size = bytes.len() - self.start_pos;
@@ -76,6 +138,30 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         Ok(self.bytes_init_len)
     }
 
+    /// Removes the value entries left unused by ignored duplicates, the outlined values are moved
+    /// forward and their offsets are updated.
+    fn remove_unused_entries(&mut self) {
+        let bytes = self.bytes.as_mut();
+        let unused = (self.element_count - self.value_count) as usize * VALUE_ENTRY_SIZE;
+        bytes.drain(self.value_entry_pos..self.value_entry_pos + unused);
+
+        let entries_pos = self.start_pos + ELEMENT_COUNT_SIZE;
+        for entry_pos in (entries_pos..self.value_entry_pos).step_by(VALUE_ENTRY_SIZE) {
+            // bool and null are inlined
+            if !matches!(
+                DataType::try_from(bytes[entry_pos]),
+                Ok(DataType::Bool | DataType::Null)
+            ) {
+                let offset_pos = entry_pos + DATA_TYPE_SIZE;
+                if let Some(offset) = read_fixed(bytes, offset_pos).map(u32::from_le_bytes) {
+                    bytes.write_offset(offset - unused as u32, offset_pos);
+                }
+            }
+        }
+        bytes[self.start_pos..entries_pos].copy_from_slice(&self.value_count.to_le_bytes());
+        self.element_count = self.value_count;
+    }
+
     #[inline]
     fn push_value<F>(&mut self, data_type: DataType, f: F) -> BuildResult<()>
     where
@@ -87,17 +173,45 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
 
         let bytes = self.bytes.as_mut();
         bytes.write_data_type_by_pos(data_type, self.value_entry_pos);
-        let offset = bytes.len() - self.start_pos;
+        let value_pos = bytes.len();
+        let offset = value_pos - self.start_pos;
 
         f(bytes, offset as u32, self.value_entry_pos)?;
 
+        if let Some(set) = self.set.as_mut() {
+            let entry_pos = self.value_entry_pos;
+            let scalar = match data_type {
+                // nested objects and arrays are not finished yet, they are not checked
+                DataType::Object | DataType::Array => None,
+                // bool and null are inlined, the bool is the lowest byte of the offset
+                DataType::Bool => Some(entry_pos..entry_pos + DATA_TYPE_SIZE + BOOL_SIZE),
+                DataType::Null => Some(entry_pos..entry_pos + DATA_TYPE_SIZE),
+                _ => Some(value_pos..bytes.len()),
+            };
+            if let Some(scalar) = scalar {
+                if !set.insert(bytes, scalar)? {
+                    bytes.truncate(value_pos);
+                    bytes.write_offset(0, entry_pos + DATA_TYPE_SIZE);
+                    return if set.ignore_duplicates {
+                        Ok(())
+                    } else {
+                        Err(BuildError::DuplicateValue)
+                    };
+                }
+            }
+        }
+
         self.value_entry_pos += VALUE_ENTRY_SIZE;
         self.value_count += 1;
         Ok(())
     }
 
     #[inline]
-    fn push_object(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<InnerObjectBuilder<&mut Vec<u8>>> {
+    fn push_object(
+        &mut self,
+        element_count: u16,
+        key_sorted: bool,
+    ) -> BuildResult<InnerObjectBuilder<'_, &mut Vec<u8>>> {
         let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
             bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
             Ok(())
@@ -105,11 +219,14 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         self.push_value(DataType::Object, f)?;
 
         let bytes = self.bytes.as_mut();
-        InnerObjectBuilder::try_new(bytes, element_count, key_sorted, self.total_nested_depth.borrow_mut())
+        let mut builder =
+            InnerObjectBuilder::try_new(bytes, element_count, key_sorted, self.total_nested_depth.borrow_mut())?;
+        builder.compact_objects = self.compact_objects;
+        Ok(builder)
     }
 
     #[inline]
-    fn push_array(&mut self, element_count: u16) -> BuildResult<InnerArrayBuilder<&mut Vec<u8>>> {
+    fn push_array(&mut self, element_count: u16) -> BuildResult<InnerArrayBuilder<'_, &mut Vec<u8>>> {
         let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
             bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
             Ok(())
@@ -117,7 +234,9 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         self.push_value(DataType::Array, f)?;
 
         let bytes = self.bytes.as_mut();
-        InnerArrayBuilder::try_new(bytes, element_count, self.total_nested_depth.borrow_mut())
+        let mut builder = InnerArrayBuilder::try_new(bytes, element_count, self.total_nested_depth.borrow_mut())?;
+        builder.compact_objects = self.compact_objects;
+        Ok(builder)
     }
 
     #[inline]
@@ -125,7 +244,7 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         let size = DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + value.len();
         let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
             bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
-            bytes.try_reserve(size)?;
+            bytes.try_reserve_bytes(size)?;
             bytes.push_data_type(DataType::String);
             bytes.push_string(value)?;
             Ok(())
@@ -133,12 +252,59 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         self.push_value(DataType::String, f)
     }
 
+    #[inline]
+    fn push_binary(&mut self, value: &[u8]) -> BuildResult<()> {
+        let size = DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + value.len();
+        let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
+            bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
+            bytes.try_reserve_bytes(size)?;
+            bytes.push_data_type(DataType::Binary);
+            bytes.push_binary(value)?;
+            Ok(())
+        };
+        self.push_value(DataType::Binary, f)
+    }
+
+    #[inline]
+    fn push_i32_value(&mut self, data_type: DataType, value: i32) -> BuildResult<()> {
+        let size = DATA_TYPE_SIZE + size_of::<i32>();
+        let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
+            bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
+            bytes.try_reserve_bytes(size)?;
+            bytes.push_data_type(data_type);
+            bytes.push_i32(value);
+            Ok(())
+        };
+        self.push_value(data_type, f)
+    }
+
+    #[inline]
+    fn push_i64_value(&mut self, data_type: DataType, value: i64) -> BuildResult<()> {
+        let size = DATA_TYPE_SIZE + size_of::<i64>();
+        let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
+            bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
+            bytes.try_reserve_bytes(size)?;
+            bytes.push_data_type(data_type);
+            bytes.push_i64(value);
+            Ok(())
+        };
+        self.push_value(data_type, f)
+    }
+
     #[inline]
     fn push_number(&mut self, value: &Number) -> BuildResult<()> {
+        // equal numbers of different scales only have the same encoding after normalization
+        let normalized;
+        let value = if self.set.is_some() {
+            normalized = value.normalize();
+            &normalized
+        } else {
+            value
+        };
         let size = DATA_TYPE_SIZE + MAX_BINARY_SIZE + NUMBER_LENGTH_SIZE;
         let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
             bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
-            bytes.try_reserve(size)?;
+            bytes.try_reserve_bytes(size)?;
             bytes.push_data_type(DataType::Number);
             bytes.push_number(value);
             Ok(())
@@ -168,12 +334,105 @@ impl<'a, B: AsMut<Vec<u8>>> InnerArrayBuilder<'a, B> {
         let size = value.len();
         let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize| {
             bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
-            bytes.try_reserve(size)?;
+            bytes.try_reserve_bytes(size)?;
             bytes.extend_from_slice(value);
             Ok(())
         };
         self.push_value(data_type, f)
     }
+
+    #[inline]
+    fn push_yason(&mut self, yason: &Yason) -> BuildResult<()> {
+        // only the header and the checksum are left out
+        let value = yason.value().map_err(BuildError::InvalidYason)?;
+        match value.data_type().map_err(BuildError::InvalidYason)? {
+            // bool and null are inlined in value entries
+            DataType::Bool => self.push_bool(value.bool().map_err(BuildError::InvalidYason)?),
+            DataType::Null => self.push_null(),
+            // numbers of a set are normalized
+            DataType::Number if self.set.is_some() => {
+                self.push_number(&value.number().map_err(BuildError::InvalidYason)?)
+            }
+            data_type => unsafe { self.push_object_or_array(value, data_type) },
+        }
+    }
+
+    /// Pushes values of the same type in a tight loop, the depth and the element count are checked
+    /// and the bytes are reserved only once. `size` returns the reserved size of a value and `f`
+    /// writes the value entry and the value, like the closures of `push_value`.
+    ///
+    /// A set checks every value, so it must push them one by one instead.
+    #[inline]
+    fn push_values<V, S, F>(&mut self, data_type: DataType, values: &[V], size: S, mut f: F) -> BuildResult<()>
+    where
+        S: Fn(&V) -> usize,
+        F: FnMut(&mut Vec<u8>, u32, usize, &V) -> BuildResult<()>,
+    {
+        debug_assert!(self.set.is_none());
+        if self.current_depth != self.total_nested_depth.depth() {
+            return Err(BuildError::InnerUncompletedError);
+        }
+        let count = self.value_count as usize + values.len();
+        if count > self.element_count as usize {
+            return Err(BuildError::InconsistentElementCount {
+                expected: self.element_count,
+                actual: count.min(u16::MAX as usize) as u16,
+            });
+        }
+
+        let bytes = self.bytes.as_mut();
+        bytes.try_reserve_bytes(values.iter().map(size).sum())?;
+        for value in values {
+            bytes.write_data_type_by_pos(data_type, self.value_entry_pos);
+            let offset = bytes.len() - self.start_pos;
+            f(bytes, offset as u32, self.value_entry_pos, value)?;
+            self.value_entry_pos += VALUE_ENTRY_SIZE;
+            self.value_count += 1;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn push_strings<Val: AsRef<str>>(&mut self, values: &[Val]) -> BuildResult<()> {
+        if self.set.is_some() {
+            return values.iter().try_for_each(|value| self.push_string(value.as_ref()));
+        }
+        let size = |value: &Val| DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + value.as_ref().len();
+        let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize, value: &Val| {
+            bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
+            bytes.push_data_type(DataType::String);
+            bytes.push_string(value.as_ref())
+        };
+        self.push_values(DataType::String, values, size, f)
+    }
+
+    #[inline]
+    fn push_numbers(&mut self, values: &[Number]) -> BuildResult<()> {
+        if self.set.is_some() {
+            return values.iter().try_for_each(|value| self.push_number(value));
+        }
+        let size = |_: &Number| DATA_TYPE_SIZE + MAX_BINARY_SIZE + NUMBER_LENGTH_SIZE;
+        let f = |bytes: &mut Vec<u8>, offset: u32, value_entry_pos: usize, value: &Number| {
+            bytes.write_offset(offset, value_entry_pos + DATA_TYPE_SIZE);
+            bytes.push_data_type(DataType::Number);
+            bytes.push_number(value);
+            Ok(())
+        };
+        self.push_values(DataType::Number, values, size, f)
+    }
+
+    #[inline]
+    fn push_bools(&mut self, values: &[bool]) -> BuildResult<()> {
+        if self.set.is_some() {
+            return values.iter().try_for_each(|value| self.push_bool(*value));
+        }
+        // bool can be inlined
+        let f = |bytes: &mut Vec<u8>, _offset: u32, value_entry_pos: usize, value: &bool| {
+            bytes.write_offset(*value as u32, value_entry_pos + DATA_TYPE_SIZE);
+            Ok(())
+        };
+        self.push_values(DataType::Bool, values, |_| 0, f)
+    }
 }
 
 /// Builder for encoding an array.
@@ -182,19 +441,166 @@ pub struct ArrayBuilder<'a>(InnerArrayBuilder<'a, Vec<u8>>);
 
 impl ArrayBuilder<'_> {
     /// Creates `ArrayBuilder` with specified element count.
+    ///
+    /// The buffer is allocated with the capacity of the default [`CapacityHint`](crate::CapacityHint).
     #[inline]
     pub fn try_new(element_count: u16) -> BuildResult<Self> {
-        let bytes = Vec::try_with_capacity(DEFAULT_SIZE)?;
+        let capacity = CapacityHint::new().array_capacity(element_count);
+        Self::try_new_with_capacity(element_count, capacity)
+    }
+
+    /// Creates `ArrayBuilder` with specified element count and a hint of the encoded size in bytes.
+    ///
+    /// The buffer is allocated with at least `capacity` bytes, see [`EstimateSize`].
+    #[inline]
+    pub fn try_new_with_capacity(element_count: u16, capacity: usize) -> BuildResult<Self> {
+        let bytes: Vec<u8> = VecExt::try_with_capacity(capacity)?;
         let builder = InnerArrayBuilder::try_new(bytes, element_count, Depth::new())?;
         Ok(Self(builder))
     }
 
+    /// Creates `ArrayBuilder` with specified element count, which builds an array without duplicate
+    /// scalars, e.g. a list of tags.
+    ///
+    /// Scalars are compared by the equality of [`Value::equals`]. If `ignore_duplicates` is false,
+    /// pushing a scalar equal to a pushed one returns `BuildError::DuplicateValue` and the value is
+    /// not pushed. Otherwise the scalar is skipped, and the element count becomes the upper bound of
+    /// the number of elements: the unused value entries are removed when finishing. Numbers are stored
+    /// normalized, e.g. `1.50` as `1.5`. Nested objects and arrays are always pushed without checks.
+    ///
+    /// Every scalar is encoded first and the hash of its encoded bytes is looked up in a hash table,
+    /// so a push costs one hash of the scalar plus a byte comparison with the pushed scalars of the
+    /// same hash, and the table takes a few words per element. Ignored duplicates are removed from
+    /// the buffer right away, removing unused value entries moves the outlined values once.
+    ///
+    /// ```rust
+    /// use yason::{ArrayBuilder, BuildError};
+    ///
+    /// let mut builder = ArrayBuilder::try_new_set(3, true).unwrap();
+    /// builder.push_string("red").unwrap().push_string("blue").unwrap().push_string("red").unwrap();
+    /// assert_eq!(builder.finish().unwrap().format(false).to_string(), r#"["red","blue"]"#);
+    ///
+    /// let mut builder = ArrayBuilder::try_new_set(2, false).unwrap();
+    /// builder.push_bool(true).unwrap();
+    /// assert!(matches!(builder.push_bool(true), Err(BuildError::DuplicateValue)));
+    /// ```
+    #[inline]
+    pub fn try_new_set(element_count: u16, ignore_duplicates: bool) -> BuildResult<Self> {
+        let mut builder = Self::try_new(element_count)?;
+        builder.0.set = Some(Box::new(ScalarSet::try_new(element_count, ignore_duplicates)?));
+        Ok(builder)
+    }
+
+    /// Encodes an array from the given values.
+    ///
+    /// The total size is computed before encoding so that the buffer is allocated only once.
+    #[inline]
+    pub fn from_values(values: &[Value]) -> BuildResult<YasonBuf> {
+        let count = element_count(values.len())?;
+
+        let mut bytes: Vec<u8> = VecExt::try_with_capacity(values.estimate_size())?;
+        bytes.push_data_type(DataType::Array); // type
+        bytes.skip_size(); // size
+        let start_pos = bytes.len();
+        bytes.push_u16(count); // element-count
+        let mut value_entry_pos = bytes.len();
+        bytes.skip_value_entry(values.len()); // value-entry
+
+        for value in values {
+            bytes.write_data_type_by_pos(value.data_type(), value_entry_pos);
+            match value {
+                // bool and null can be inlined
+                Value::Bool(b) => bytes.write_offset(*b as u32, value_entry_pos + DATA_TYPE_SIZE),
+                Value::Null => bytes.write_offset(0, value_entry_pos + DATA_TYPE_SIZE),
+                _ => {
+                    let offset = bytes.len() - start_pos;
+                    bytes.write_offset(offset as u32, value_entry_pos + DATA_TYPE_SIZE);
+                    push_encoded_value(&mut bytes, value)?;
+                }
+            }
+            value_entry_pos += VALUE_ENTRY_SIZE;
+        }
+
+        let total_size = bytes.len() - start_pos;
+        bytes.write_total_size(total_size as i32, start_pos - ARRAY_SIZE);
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Creates `ArrayBuilder` with specified element count, reusing the given buffer.
+    ///
+    /// The buffer is cleared first, only its capacity is kept.
+    #[inline]
+    pub fn try_new_in(mut bytes: Vec<u8>, element_count: u16) -> BuildResult<Self> {
+        bytes.clear();
+        let builder = InnerArrayBuilder::try_new(bytes, element_count, Depth::new())?;
+        Ok(Self(builder))
+    }
+
+    /// Resets the builder to encode a new array with specified element count, the internal buffer is
+    /// reused without reallocation.
+    ///
+    /// If an error is returned, the builder must be reset successfully before it can be used again.
+    /// A builder created by [`ArrayBuilder::try_new_set`] still builds a set after the reset.
+    #[inline]
+    pub fn reset(&mut self, element_count: u16) -> BuildResult<()> {
+        let mut bytes = std::mem::take(&mut self.0.bytes);
+        bytes.clear();
+        let checksum = self.0.checksum;
+        let compact_objects = self.0.compact_objects;
+        let set = match self.0.set.take() {
+            Some(set) => Some(Box::new(ScalarSet::try_new(element_count, set.ignore_duplicates)?)),
+            None => None,
+        };
+        self.0 = InnerArrayBuilder::try_new(bytes, element_count, Depth::new())?;
+        self.0.checksum = checksum;
+        self.0.compact_objects = compact_objects;
+        self.0.set = set;
+        Ok(())
+    }
+
+    /// Appends a CRC32C footer to the finished array, see [`Yason::verify_checksum`].
+    ///
+    /// The option is kept by [`ArrayBuilder::reset`].
+    #[inline]
+    pub fn with_checksum(mut self) -> Self {
+        self.0.checksum = true;
+        self
+    }
+
+    /// Encodes the nested objects with 2-byte key offsets if they fit, see
+    /// [`COMPACT_OBJECT_TYPE`](crate::raw::COMPACT_OBJECT_TYPE). Readers of the legacy format
+    /// cannot decode such objects, so they are only written if asked for.
+    ///
+    /// The option is kept by [`ArrayBuilder::reset`].
+    #[inline]
+    pub fn with_compact_objects(mut self) -> Self {
+        self.0.compact_objects = true;
+        self
+    }
+
     /// Finishes building the array.
     #[inline]
     pub fn finish(mut self) -> BuildResult<YasonBuf> {
-        self.0.finish()?;
+        self.finish_bytes()?;
         Ok(unsafe { YasonBuf::new_unchecked(self.0.bytes) })
     }
+
+    /// Finishes building the array without giving up the internal buffer, the builder can be
+    /// reused by [`ArrayBuilder::reset`] after the returned yason is dropped.
+    #[inline]
+    pub fn finish_ref(&mut self) -> BuildResult<&Yason> {
+        self.finish_bytes()?;
+        Ok(unsafe { Yason::new_unchecked(&self.0.bytes) })
+    }
+
+    #[inline]
+    fn finish_bytes(&mut self) -> BuildResult<()> {
+        self.0.finish()?;
+        if self.0.checksum {
+            push_checksum(&mut self.0.bytes, 0)?;
+        }
+        Ok(())
+    }
 }
 
 /// Builder for encoding an array.
@@ -228,36 +634,205 @@ impl<'a> ArrayRefBuilder<'a> {
 
 pub trait ArrBuilder {
     /// Pushes an embedded object with specified element count and a flag which indicates whether the embedded object is sorted by key.
-    fn push_object(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<ObjectRefBuilder>;
+    fn push_object(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<ObjectRefBuilder<'_>>;
 
     /// Pushes an embedded array with specified element count.
-    fn push_array(&mut self, element_count: u16) -> BuildResult<ArrayRefBuilder>;
+    fn push_array(&mut self, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>>;
 
     /// Pushes a string value.
     fn push_string<Val: AsRef<str>>(&mut self, value: Val) -> BuildResult<&mut Self>;
 
     /// Pushes a number value.
-    fn push_number<Num: AsRef<Number>>(&mut self, value: Num) -> BuildResult<&mut Self>;
+    fn push_number<Num: TryIntoNumber>(&mut self, value: Num) -> BuildResult<&mut Self>;
+
+    /// Pushes a number value rounded to the scale of the constraint, returns
+    /// `BuildError::NumberOutOfRange` if it exceeds the precision of the constraint.
+    fn push_number_with<Num: TryIntoNumber>(
+        &mut self,
+        value: Num,
+        constraint: &NumberConstraint,
+    ) -> BuildResult<&mut Self>;
 
     /// Pushes a bool value.
     fn push_bool(&mut self, value: bool) -> BuildResult<&mut Self>;
 
     /// Pushes a null value.
     fn push_null(&mut self) -> BuildResult<&mut Self>;
+
+    /// Pushes a binary value.
+    fn push_binary<Val: AsRef<[u8]>>(&mut self, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes a timestamp value.
+    fn push_timestamp<Val: Into<Timestamp>>(&mut self, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes a date value.
+    fn push_date<Val: Into<Date>>(&mut self, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes a short date value.
+    fn push_short_date<Val: Into<ShortDate>>(&mut self, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes a time value.
+    fn push_time<Val: Into<Time>>(&mut self, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes an interval year-month value.
+    fn push_interval_year_month<Val: Into<YmInterval>>(&mut self, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes an interval day-time value.
+    fn push_interval_day_time<Val: Into<DtInterval>>(&mut self, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes a value of any type by copying its encoded bytes, without decoding it.
+    ///
+    /// Returns `BuildError::InvalidYason` if the data type of the value cannot be read.
+    fn push_yason(&mut self, value: &Yason) -> BuildResult<&mut Self>;
+}
+
+/// Object-safe variant of [`ArrBuilder`], which can be used as `dyn ArrBuilderDyn` to build
+/// nested containers without monomorphizing the callers for each builder type.
+///
+/// The methods are suffixed with `_dyn`, so that they do not clash with the methods of
+/// [`ArrBuilder`] when both traits are in scope.
+pub trait ArrBuilderDyn {
+    /// Pushes an embedded object with specified element count and a flag which indicates whether the embedded object is sorted by key.
+    fn push_object_dyn(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<ObjectRefBuilder<'_>>;
+
+    /// Pushes an embedded array with specified element count.
+    fn push_array_dyn(&mut self, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>>;
+
+    /// Pushes a string value.
+    fn push_string_dyn(&mut self, value: &str) -> BuildResult<()>;
+
+    /// Pushes a number value.
+    fn push_number_dyn(&mut self, value: &Number) -> BuildResult<()>;
+
+    /// Pushes a bool value.
+    fn push_bool_dyn(&mut self, value: bool) -> BuildResult<()>;
+
+    /// Pushes a null value.
+    fn push_null_dyn(&mut self) -> BuildResult<()>;
+
+    /// Pushes a binary value.
+    fn push_binary_dyn(&mut self, value: &[u8]) -> BuildResult<()>;
+
+    /// Pushes a timestamp value.
+    fn push_timestamp_dyn(&mut self, value: Timestamp) -> BuildResult<()>;
+
+    /// Pushes a date value.
+    fn push_date_dyn(&mut self, value: Date) -> BuildResult<()>;
+
+    /// Pushes a short date value.
+    fn push_short_date_dyn(&mut self, value: ShortDate) -> BuildResult<()>;
+
+    /// Pushes a time value.
+    fn push_time_dyn(&mut self, value: Time) -> BuildResult<()>;
+
+    /// Pushes an interval year-month value.
+    fn push_interval_year_month_dyn(&mut self, value: YmInterval) -> BuildResult<()>;
+
+    /// Pushes an interval day-time value.
+    fn push_interval_day_time_dyn(&mut self, value: DtInterval) -> BuildResult<()>;
+
+    /// Pushes a value of any type by copying its encoded bytes, without decoding it.
+    ///
+    /// Returns `BuildError::InvalidYason` if the data type of the value cannot be read.
+    fn push_yason_dyn(&mut self, value: &Yason) -> BuildResult<()>;
+}
+
+impl<T: ArrBuilder> ArrBuilderDyn for T {
+    #[inline]
+    fn push_object_dyn(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<ObjectRefBuilder<'_>> {
+        ArrBuilder::push_object(self, element_count, key_sorted)
+    }
+
+    #[inline]
+    fn push_array_dyn(&mut self, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>> {
+        ArrBuilder::push_array(self, element_count)
+    }
+
+    #[inline]
+    fn push_string_dyn(&mut self, value: &str) -> BuildResult<()> {
+        ArrBuilder::push_string(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_number_dyn(&mut self, value: &Number) -> BuildResult<()> {
+        ArrBuilder::push_number(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_bool_dyn(&mut self, value: bool) -> BuildResult<()> {
+        ArrBuilder::push_bool(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_null_dyn(&mut self) -> BuildResult<()> {
+        ArrBuilder::push_null(self)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_binary_dyn(&mut self, value: &[u8]) -> BuildResult<()> {
+        ArrBuilder::push_binary(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_timestamp_dyn(&mut self, value: Timestamp) -> BuildResult<()> {
+        ArrBuilder::push_timestamp(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_date_dyn(&mut self, value: Date) -> BuildResult<()> {
+        ArrBuilder::push_date(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_short_date_dyn(&mut self, value: ShortDate) -> BuildResult<()> {
+        ArrBuilder::push_short_date(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_time_dyn(&mut self, value: Time) -> BuildResult<()> {
+        ArrBuilder::push_time(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_interval_year_month_dyn(&mut self, value: YmInterval) -> BuildResult<()> {
+        ArrBuilder::push_interval_year_month(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_interval_day_time_dyn(&mut self, value: DtInterval) -> BuildResult<()> {
+        ArrBuilder::push_interval_day_time(self, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_yason_dyn(&mut self, value: &Yason) -> BuildResult<()> {
+        ArrBuilder::push_yason(self, value)?;
+        Ok(())
+    }
 }
 
 macro_rules! impl_push_methods {
     ($v: vis,) => {
         /// Pushes an embedded object with specified element count and a flag which indicates whether the embedded object is sorted by key.
         #[inline]
-        $v fn push_object(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<ObjectRefBuilder> {
+        $v fn push_object(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<ObjectRefBuilder<'_>> {
             let obj_builder = self.0.push_object(element_count, key_sorted)?;
             Ok(ObjectRefBuilder(obj_builder))
         }
 
         /// Pushes an embedded array with specified element count.
         #[inline]
-        $v fn push_array(&mut self, element_count: u16) -> BuildResult<ArrayRefBuilder> {
+        $v fn push_array(&mut self, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>> {
             let array_builder = self.0.push_array(element_count)?;
             Ok(ArrayRefBuilder(array_builder))
         }
@@ -272,11 +847,23 @@ macro_rules! impl_push_methods {
 
         /// Pushes a number value.
         #[inline]
-        $v fn push_number<Num: AsRef<Number>>(&mut self, value: Num) -> BuildResult<&mut Self> {
-            self.0.push_number(value.as_ref())?;
+        $v fn push_number<Num: TryIntoNumber>(&mut self, value: Num) -> BuildResult<&mut Self> {
+            self.0.push_number(&value.try_into_number()?)?;
             Ok(self)
         }
 
+        /// Pushes a number value rounded to the scale of the constraint, returns
+        /// `BuildError::NumberOutOfRange` if it exceeds the precision of the constraint.
+        #[inline]
+        $v fn push_number_with<Num: TryIntoNumber>(
+            &mut self,
+            value: Num,
+            constraint: &NumberConstraint,
+        ) -> BuildResult<&mut Self> {
+            let value = constraint.apply(&value.try_into_number()?)?;
+            self.push_number(value)
+        }
+
         /// Pushes a bool value.
         #[inline]
         $v fn push_bool(&mut self, value: bool) -> BuildResult<&mut Self> {
@@ -290,6 +877,64 @@ macro_rules! impl_push_methods {
             self.0.push_null()?;
             Ok(self)
         }
+
+        /// Pushes a binary value.
+        #[inline]
+        $v fn push_binary<Val: AsRef<[u8]>>(&mut self, value: Val) -> BuildResult<&mut Self> {
+            self.0.push_binary(value.as_ref())?;
+            Ok(self)
+        }
+
+        /// Pushes a timestamp value.
+        #[inline]
+        $v fn push_timestamp<Val: Into<Timestamp>>(&mut self, value: Val) -> BuildResult<&mut Self> {
+            self.0.push_i64_value(DataType::Timestamp, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes a date value.
+        #[inline]
+        $v fn push_date<Val: Into<Date>>(&mut self, value: Val) -> BuildResult<&mut Self> {
+            self.0.push_i64_value(DataType::Date, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes a short date value.
+        #[inline]
+        $v fn push_short_date<Val: Into<ShortDate>>(&mut self, value: Val) -> BuildResult<&mut Self> {
+            self.0.push_i32_value(DataType::ShortDate, value.into().days())?;
+            Ok(self)
+        }
+
+        /// Pushes a time value.
+        #[inline]
+        $v fn push_time<Val: Into<Time>>(&mut self, value: Val) -> BuildResult<&mut Self> {
+            self.0.push_i64_value(DataType::Time, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes an interval year-month value.
+        #[inline]
+        $v fn push_interval_year_month<Val: Into<YmInterval>>(&mut self, value: Val) -> BuildResult<&mut Self> {
+            self.0.push_i32_value(DataType::IntervalYearMonth, value.into().months())?;
+            Ok(self)
+        }
+
+        /// Pushes an interval day-time value.
+        #[inline]
+        $v fn push_interval_day_time<Val: Into<DtInterval>>(&mut self, value: Val) -> BuildResult<&mut Self> {
+            self.0.push_i64_value(DataType::IntervalDayTime, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes a value of any type by copying its encoded bytes, without decoding it.
+        ///
+        /// Returns `BuildError::InvalidYason` if the data type of the value cannot be read.
+        #[inline]
+        $v fn push_yason(&mut self, value: &Yason) -> BuildResult<&mut Self> {
+            self.0.push_yason(value)?;
+            Ok(self)
+        }
     };
 }
 
@@ -297,6 +942,39 @@ macro_rules! impl_builder {
     ($builder: ty) => {
         impl $builder {
             impl_push_methods!(pub,);
+
+            /// Pushes string values, reserving the bytes for all of them at once. Returns
+            /// `BuildError::InconsistentElementCount` without pushing any value if there are more values
+            /// than the elements left.
+            #[inline]
+            pub fn push_strings<Val: AsRef<str>>(&mut self, values: &[Val]) -> BuildResult<&mut Self> {
+                self.0.push_strings(values)?;
+                Ok(self)
+            }
+
+            /// Pushes number values, reserving the bytes for all of them at once. Returns
+            /// `BuildError::InconsistentElementCount` without pushing any value if there are more values
+            /// than the elements left.
+            #[inline]
+            pub fn push_numbers(&mut self, values: &[Number]) -> BuildResult<&mut Self> {
+                self.0.push_numbers(values)?;
+                Ok(self)
+            }
+
+            /// Pushes bool values. Returns `BuildError::InconsistentElementCount` without pushing any
+            /// value if there are more values than the elements left.
+            #[inline]
+            pub fn push_bools(&mut self, values: &[bool]) -> BuildResult<&mut Self> {
+                self.0.push_bools(values)?;
+                Ok(self)
+            }
+
+            /// Returns the nesting depth of the array being built, 1 for a top-level array. Nested objects
+            /// and arrays can be pushed while it is less than [`MAX_NESTED_DEPTH`].
+            #[inline]
+            pub fn depth(&self) -> usize {
+                self.0.current_depth
+            }
         }
 
         impl ArrBuilder for $builder {
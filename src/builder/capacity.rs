@@ -0,0 +1,98 @@
+//! Initial capacity of the owned builders.
+
+use crate::binary::{
+    ARRAY_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, OBJECT_SIZE, VALUE_ENTRY_SIZE,
+};
+
+/// The average key length of the default hint, in bytes.
+const DEFAULT_KEY_SIZE: usize = 8;
+/// The average encoded size of a value of the default hint, in bytes, e.g. a small number or a short
+/// string with its type.
+const DEFAULT_VALUE_SIZE: usize = 8;
+
+/// A heuristic of the number of bytes to reserve for an object or an array, from its element count
+/// and the average sizes of its keys and values.
+///
+/// [`ObjectBuilder::try_new`] and [`ArrayBuilder::try_new`] reserve the capacity of the default
+/// hint, so that tiny documents do not waste memory and medium ones do not reallocate while they are
+/// built. Documents whose values are known to be larger or smaller can be built with a tuned hint
+/// passed to `try_new_with_capacity`, see also [`EstimateSize`] for values at hand.
+///
+/// ```rust
+/// use yason::{CapacityHint, ObjectBuilder};
+///
+/// let hint = CapacityHint::new().with_key_size(4).with_value_size(32);
+/// let mut builder = ObjectBuilder::try_new_with_capacity(2, false, hint.object_capacity(2)).unwrap();
+/// builder.push_string("name", "a string of about thirty bytes").unwrap();
+/// builder.push_string("city", "another string of thirty bytes").unwrap();
+/// let yason = builder.finish().unwrap();
+/// assert!(yason.as_bytes().len() <= hint.object_capacity(2));
+/// ```
+///
+/// [`ObjectBuilder::try_new`]: crate::ObjectBuilder::try_new
+/// [`ArrayBuilder::try_new`]: crate::ArrayBuilder::try_new
+/// [`EstimateSize`]: crate::EstimateSize
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityHint {
+    key_size: usize,
+    value_size: usize,
+}
+
+impl CapacityHint {
+    /// Creates the default hint, which assumes keys of 8 bytes and values of 8 bytes.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            key_size: DEFAULT_KEY_SIZE,
+            value_size: DEFAULT_VALUE_SIZE,
+        }
+    }
+
+    /// Sets the average length of the keys in bytes.
+    #[inline]
+    pub const fn with_key_size(mut self, key_size: usize) -> Self {
+        self.key_size = key_size;
+        self
+    }
+
+    /// Sets the average encoded size of the values in bytes, including their types. Bools and nulls
+    /// of arrays are inlined in their value entries and take no extra bytes.
+    #[inline]
+    pub const fn with_value_size(mut self, value_size: usize) -> Self {
+        self.value_size = value_size;
+        self
+    }
+
+    #[inline]
+    pub const fn key_size(&self) -> usize {
+        self.key_size
+    }
+
+    #[inline]
+    pub const fn value_size(&self) -> usize {
+        self.value_size
+    }
+
+    /// Returns the number of bytes to reserve for an object with the element count: the header, the
+    /// key-offset table and the estimated key-values.
+    #[inline]
+    pub const fn object_capacity(&self, element_count: u16) -> usize {
+        let entry_size = KEY_OFFSET_SIZE + KEY_LENGTH_SIZE + self.key_size + self.value_size;
+        DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + entry_size * element_count as usize
+    }
+
+    /// Returns the number of bytes to reserve for an array with the element count: the header, the
+    /// value entries and the estimated values.
+    #[inline]
+    pub const fn array_capacity(&self, element_count: u16) -> usize {
+        let entry_size = VALUE_ENTRY_SIZE + self.value_size;
+        DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + entry_size * element_count as usize
+    }
+}
+
+impl Default for CapacityHint {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
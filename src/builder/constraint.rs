@@ -0,0 +1,74 @@
+//! Precision and scale constraints of numbers.
+
+use crate::builder::{BuildError, BuildResult};
+use crate::Number;
+
+/// The maximum precision of a number.
+const MAX_PRECISION: u8 = decimal_rs::MAX_PRECISION as u8;
+
+/// A `(precision, scale)` constraint of a number, like the `NUMBER(precision, scale)` type of the
+/// database.
+///
+/// A number satisfies the constraint if it has at most `precision` significant digits after it is
+/// rounded to `scale` digits after the decimal point. A negative scale rounds before the decimal
+/// point.
+///
+/// ```rust
+/// use yason::{BuildError, Number, NumberConstraint, ObjectBuilder};
+/// use std::str::FromStr;
+///
+/// let constraint = NumberConstraint::new(5, 2);
+/// let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+/// builder.push_number_with("price", Number::from_str("123.456").unwrap(), &constraint).unwrap();
+/// assert_eq!(builder.finish().unwrap().format(false).to_string(), r#"{"price":123.46}"#);
+///
+/// let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+/// let result = builder.push_number_with("price", Number::from(1000), &constraint);
+/// assert!(matches!(result, Err(BuildError::NumberOutOfRange { precision: 5, scale: 2 })));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberConstraint {
+    precision: u8,
+    scale: i16,
+}
+
+impl NumberConstraint {
+    /// Creates a `NumberConstraint` with the given precision and scale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 1 and 38.
+    #[inline]
+    pub fn new(precision: u8, scale: i16) -> Self {
+        assert!(
+            precision > 0 && precision <= MAX_PRECISION,
+            "precision must be between 1 and {}",
+            MAX_PRECISION
+        );
+        Self { precision, scale }
+    }
+
+    #[inline]
+    pub const fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    #[inline]
+    pub const fn scale(&self) -> i16 {
+        self.scale
+    }
+
+    /// Rounds the number to the scale of the constraint, returns
+    /// `BuildError::NumberOutOfRange` if the rounded number has more digits than the precision.
+    #[inline]
+    pub fn apply(&self, value: &Number) -> BuildResult<Number> {
+        let mut value = *value;
+        if value.round_with_precision(self.precision, self.scale) {
+            return Err(BuildError::NumberOutOfRange {
+                precision: self.precision,
+                scale: self.scale,
+            });
+        }
+        Ok(value)
+    }
+}
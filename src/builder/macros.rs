@@ -0,0 +1,238 @@
+//! Builder macros.
+
+use crate::builder::{ArrBuilder, BuildResult, ObjBuilder};
+use crate::{Date, DtInterval, Number, ShortDate, Time, Timestamp, YmInterval};
+
+/// Values which can be pushed by [`yason_object!`] and [`yason_array!`], the push method is picked
+/// by the type of the value.
+#[doc(hidden)]
+pub trait PushValue {
+    fn push_to_object<B: ObjBuilder>(self, builder: &mut B, key: &str) -> BuildResult<()>;
+
+    fn push_to_array<B: ArrBuilder>(self, builder: &mut B) -> BuildResult<()>;
+}
+
+macro_rules! impl_push_value {
+    ($ty: ty, $method: ident, |$value: ident| $convert: expr) => {
+        impl PushValue for $ty {
+            #[inline]
+            fn push_to_object<B: ObjBuilder>(self, builder: &mut B, key: &str) -> BuildResult<()> {
+                let $value = self;
+                builder.$method(key, $convert)?;
+                Ok(())
+            }
+
+            #[inline]
+            fn push_to_array<B: ArrBuilder>(self, builder: &mut B) -> BuildResult<()> {
+                let $value = self;
+                builder.$method($convert)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_push_value!(&str, push_string, |value| value);
+impl_push_value!(String, push_string, |value| value);
+impl_push_value!(&String, push_string, |value| value);
+impl_push_value!(Number, push_number, |value| value);
+impl_push_value!(&Number, push_number, |value| value);
+impl_push_value!(bool, push_bool, |value| value);
+impl_push_value!(Timestamp, push_timestamp, |value| value);
+impl_push_value!(Date, push_date, |value| value);
+impl_push_value!(ShortDate, push_short_date, |value| value);
+impl_push_value!(Time, push_time, |value| value);
+impl_push_value!(YmInterval, push_interval_year_month, |value| value);
+impl_push_value!(DtInterval, push_interval_day_time, |value| value);
+
+macro_rules! impl_push_integer {
+    ($($ty: ty),*) => {
+        $(impl_push_value!($ty, push_number, |value| Number::from(value));)*
+    };
+}
+
+impl_push_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl<T: PushValue> PushValue for Option<T> {
+    #[inline]
+    fn push_to_object<B: ObjBuilder>(self, builder: &mut B, key: &str) -> BuildResult<()> {
+        match self {
+            Some(value) => value.push_to_object(builder, key),
+            None => {
+                builder.push_null(key)?;
+                Ok(())
+            }
+        }
+    }
+
+    #[inline]
+    fn push_to_array<B: ArrBuilder>(self, builder: &mut B) -> BuildResult<()> {
+        match self {
+            Some(value) => value.push_to_array(builder),
+            None => {
+                builder.push_null()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Builds an object from `key => value` pairs and returns `Result<YasonBuf, BuildError>`.
+///
+/// The element counts of the object and of the nested objects and arrays are counted when the
+/// macro is expanded, so no intermediate values are collected. A value is one of:
+///
+/// - `null`,
+/// - a nested object `{ key => value, ... }` or array `[value, ...]`,
+/// - an expression of type `&str`, `String`, `Number`, `bool`, an integer, or `Option` of them,
+///   in which `None` is pushed as null.
+///
+/// Keys are expressions of any type which implements `AsRef<str>`, and the key-values are inserted
+/// in the given order like [`ObjectBuilder`].
+///
+/// ```rust
+/// use yason::{yason_object, Number};
+///
+/// let name = String::from("foo");
+/// let yason = yason_object! {
+///     "name" => name,
+///     "age" => 42,
+///     "score" => Number::from(95),
+///     "email" => None::<&str>,
+///     "tags" => ["a", "b"],
+///     "address" => { "city" => "bar", "zip" => null },
+/// }
+/// .unwrap();
+/// assert_eq!(
+///     yason.format(false).to_string(),
+///     r#"{"age":42,"name":"foo","tags":["a","b"],"email":null,"score":95,"address":{"zip":null,"city":"bar"}}"#
+/// );
+/// ```
+///
+/// [`ObjectBuilder`]: crate::ObjectBuilder
+#[macro_export]
+macro_rules! yason_object {
+    ($($entries: tt)*) => {{
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| -> ::std::result::Result<$crate::YasonBuf, $crate::BuildError> {
+            let mut builder = $crate::ObjectBuilder::try_new($crate::__yason_count!(@object () $($entries)*), false)?;
+            $crate::__yason_push!(@object builder; $($entries)*);
+            builder.finish()
+        })();
+        result
+    }};
+}
+
+/// Builds an array from values and returns `Result<YasonBuf, BuildError>`.
+///
+/// The values are written like those of [`yason_object!`](crate::yason_object).
+///
+/// ```rust
+/// use yason::yason_array;
+///
+/// let yason = yason_array![1, "foo", true, null, [2, 3], { "key" => Some("bar") }].unwrap();
+/// assert_eq!(yason.format(false).to_string(), r#"[1,"foo",true,null,[2,3],{"key":"bar"}]"#);
+/// ```
+#[macro_export]
+macro_rules! yason_array {
+    ($($values: tt)*) => {{
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| -> ::std::result::Result<$crate::YasonBuf, $crate::BuildError> {
+            let mut builder = $crate::ArrayBuilder::try_new($crate::__yason_count!(@array () $($values)*))?;
+            $crate::__yason_push!(@array builder; $($values)*);
+            builder.finish()
+        })();
+        result
+    }};
+}
+
+/// Counts the entries of an object or the values of an array, with one `()` per element collected
+/// in the parentheses.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __yason_count {
+    (@object ($($units: tt)*)) => {
+        <[()]>::len(&[$($units),*]) as u16
+    };
+    (@object ($($units: tt)*) $key: expr => { $($value: tt)* } $(, $($rest: tt)*)?) => {
+        $crate::__yason_count!(@object ($($units)* ()) $($($rest)*)?)
+    };
+    (@object ($($units: tt)*) $key: expr => [ $($value: tt)* ] $(, $($rest: tt)*)?) => {
+        $crate::__yason_count!(@object ($($units)* ()) $($($rest)*)?)
+    };
+    (@object ($($units: tt)*) $key: expr => $value: expr $(, $($rest: tt)*)?) => {
+        $crate::__yason_count!(@object ($($units)* ()) $($($rest)*)?)
+    };
+    (@array ($($units: tt)*)) => {
+        <[()]>::len(&[$($units),*]) as u16
+    };
+    (@array ($($units: tt)*) { $($value: tt)* } $(, $($rest: tt)*)?) => {
+        $crate::__yason_count!(@array ($($units)* ()) $($($rest)*)?)
+    };
+    (@array ($($units: tt)*) [ $($value: tt)* ] $(, $($rest: tt)*)?) => {
+        $crate::__yason_count!(@array ($($units)* ()) $($($rest)*)?)
+    };
+    (@array ($($units: tt)*) $value: expr $(, $($rest: tt)*)?) => {
+        $crate::__yason_count!(@array ($($units)* ()) $($($rest)*)?)
+    };
+}
+
+/// Pushes the entries of an object or the values of an array into a builder.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __yason_push {
+    (@object $builder: ident;) => {};
+    (@object $builder: ident; $key: expr => { $($value: tt)* } $(, $($rest: tt)*)?) => {
+        {
+            let mut builder = $builder.push_object(
+                $key,
+                $crate::__yason_count!(@object () $($value)*),
+                false,
+            )?;
+            $crate::__yason_push!(@object builder; $($value)*);
+            builder.finish()?;
+        }
+        $crate::__yason_push!(@object $builder; $($($rest)*)?);
+    };
+    (@object $builder: ident; $key: expr => [ $($value: tt)* ] $(, $($rest: tt)*)?) => {
+        {
+            let mut builder = $builder.push_array($key, $crate::__yason_count!(@array () $($value)*))?;
+            $crate::__yason_push!(@array builder; $($value)*);
+            builder.finish()?;
+        }
+        $crate::__yason_push!(@object $builder; $($($rest)*)?);
+    };
+    (@object $builder: ident; $key: expr => null $(, $($rest: tt)*)?) => {
+        $builder.push_null($key)?;
+        $crate::__yason_push!(@object $builder; $($($rest)*)?);
+    };
+    (@object $builder: ident; $key: expr => $value: expr $(, $($rest: tt)*)?) => {
+        $crate::PushValue::push_to_object($value, &mut $builder, ::std::convert::AsRef::<str>::as_ref(&$key))?;
+        $crate::__yason_push!(@object $builder; $($($rest)*)?);
+    };
+    (@array $builder: ident;) => {};
+    (@array $builder: ident; { $($value: tt)* } $(, $($rest: tt)*)?) => {
+        {
+            let mut builder = $builder.push_object($crate::__yason_count!(@object () $($value)*), false)?;
+            $crate::__yason_push!(@object builder; $($value)*);
+            builder.finish()?;
+        }
+        $crate::__yason_push!(@array $builder; $($($rest)*)?);
+    };
+    (@array $builder: ident; [ $($value: tt)* ] $(, $($rest: tt)*)?) => {
+        {
+            let mut builder = $builder.push_array($crate::__yason_count!(@array () $($value)*))?;
+            $crate::__yason_push!(@array builder; $($value)*);
+            builder.finish()?;
+        }
+        $crate::__yason_push!(@array $builder; $($($rest)*)?);
+    };
+    (@array $builder: ident; null $(, $($rest: tt)*)?) => {
+        $builder.push_null()?;
+        $crate::__yason_push!(@array $builder; $($($rest)*)?);
+    };
+    (@array $builder: ident; $value: expr $(, $($rest: tt)*)?) => {
+        $crate::PushValue::push_to_array($value, &mut $builder)?;
+        $crate::__yason_push!(@array $builder; $($($rest)*)?);
+    };
+}
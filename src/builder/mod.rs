@@ -1,25 +1,47 @@
 //! Yason builder.
 
 mod array;
+mod capacity;
+mod constraint;
+mod macros;
+mod number;
 mod object;
+mod patch;
 mod scalar;
+mod template;
 
-pub use array::{ArrBuilder, ArrayBuilder, ArrayRefBuilder};
-pub use object::{ObjBuilder, ObjectBuilder, ObjectRefBuilder};
+pub use array::{ArrBuilder, ArrBuilderDyn, ArrayBuilder, ArrayRefBuilder};
+pub use capacity::CapacityHint;
+pub use constraint::NumberConstraint;
+#[doc(hidden)]
+pub use macros::PushValue;
+pub use number::TryIntoNumber;
+pub use object::{ObjBuilder, ObjBuilderDyn, ObjectBuilder, ObjectRefBuilder};
+pub use patch::ObjectPatchBuilder;
 pub use scalar::Scalar;
+pub use template::DocumentTemplate;
 
+use crate::binary::{
+    ARRAY_SIZE, BOOL_SIZE, COMPACT_KEY_OFFSET_SIZE, COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, DATE_SIZE, ELEMENT_COUNT_SIZE,
+    INTERVAL_DT_SIZE, INTERVAL_YM_SIZE, KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, MAX_COMPACT_OBJECT_SIZE,
+    MAX_DATA_LENGTH_SIZE, NUMBER_LENGTH_SIZE, OBJECT_SIZE, SHORT_DATE_SIZE, TIMESTAMP_SIZE, TIME_SIZE,
+    VALUE_ENTRY_SIZE,
+};
+use crate::limits::MAX_ELEMENT_COUNT;
+use crate::vec::VecExt;
+use crate::{DataType, Value, YasonError};
+use decimal_rs::MAX_BINARY_SIZE;
 use std::collections::TryReserveError;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
-const DEFAULT_SIZE: usize = 128;
-const MAX_NESTED_DEPTH: usize = 100;
-
 /// Possible errors that can arise during dealing with number.
 #[derive(Debug)]
 pub enum NumberError {
     Overflow,
     FormatError,
+    /// A float is NaN or infinite.
+    NotFinite,
 }
 
 impl Display for NumberError {
@@ -28,6 +50,7 @@ impl Display for NumberError {
         match self {
             NumberError::Overflow => write!(f, "numeric overflow"),
             NumberError::FormatError => write!(f, "an error occurred when formatting a number"),
+            NumberError::NotFinite => write!(f, "number is NaN or infinite"),
         }
     }
 }
@@ -36,6 +59,7 @@ impl Error for NumberError {}
 
 /// Possible errors that can arise during building.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum BuildError {
     TryReserveError(TryReserveError),
     InnerUncompletedError,
@@ -44,6 +68,13 @@ pub enum BuildError {
     JsonError(serde_json::Error),
     NumberError(NumberError),
     NestedTooDeeply,
+    TooManyElements(usize),
+    NumberOutOfRange { precision: u8, scale: i16 },
+    InconsistentSlotCount { expected: usize, actual: usize },
+    UnsortedKeys,
+    DuplicateValue,
+    InvalidKeyOffset,
+    InvalidYason(YasonError),
 }
 
 impl Display for BuildError {
@@ -61,6 +92,17 @@ impl Display for BuildError {
             BuildError::JsonError(e) => write!(f, "{}", e),
             BuildError::NumberError(e) => write!(f, "{}", e),
             BuildError::NestedTooDeeply => write!(f, "nested too many depth"),
+            BuildError::TooManyElements(e) => write!(f, "too many elements, count is {}", e),
+            BuildError::NumberOutOfRange { precision, scale } => {
+                write!(f, "number out of range of precision {} and scale {}", precision, scale)
+            }
+            BuildError::InconsistentSlotCount { expected, actual } => {
+                write!(f, "inconsistent slot count, expected {}, actual {}", expected, actual)
+            }
+            BuildError::UnsortedKeys => write!(f, "keys are not pushed in strictly ascending key order"),
+            BuildError::DuplicateValue => write!(f, "duplicate value in a set"),
+            BuildError::InvalidKeyOffset => write!(f, "key offset does not point to a key of the object"),
+            BuildError::InvalidYason(e) => write!(f, "invalid yason to push: {}", e),
         }
     }
 }
@@ -91,7 +133,7 @@ impl<'a> Depth<'a> {
     fn borrow_mut(&mut self) -> Depth<'_> {
         match self {
             Depth::Owned(d) => Depth::Borrowed(d),
-            Depth::Borrowed(d) => Depth::Borrowed(*d),
+            Depth::Borrowed(d) => Depth::Borrowed(d),
         }
     }
 
@@ -119,3 +161,145 @@ impl<'a> Depth<'a> {
         }
     }
 }
+
+#[inline]
+fn element_count(len: usize) -> BuildResult<u16> {
+    if len > MAX_ELEMENT_COUNT {
+        return Err(BuildError::TooManyElements(len));
+    }
+    Ok(len as u16)
+}
+
+/// Writes the size of the object whose element count is at `start_pos` and which ends at the end
+/// of the bytes. Given `compact`, a non-empty object which fits is turned into a compact object
+/// first, by narrowing its key offsets to `u16` and moving the key-values down.
+pub(crate) fn finish_object(bytes: &mut Vec<u8>, start_pos: usize, element_count: usize, compact: bool) {
+    let saved = element_count * (KEY_OFFSET_SIZE - COMPACT_KEY_OFFSET_SIZE);
+    let mut total_size = bytes.len() - start_pos;
+    if compact && element_count > 0 && total_size - saved <= MAX_COMPACT_OBJECT_SIZE {
+        let table_pos = start_pos + ELEMENT_COUNT_SIZE;
+        for i in 0..element_count {
+            // a narrowed entry never overlaps the wider entries after it
+            let pos = table_pos + i * KEY_OFFSET_SIZE;
+            let offset = u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]]) as usize;
+            let compact_pos = table_pos + i * COMPACT_KEY_OFFSET_SIZE;
+            bytes[compact_pos..compact_pos + COMPACT_KEY_OFFSET_SIZE]
+                .copy_from_slice(&((offset - saved) as u16).to_le_bytes());
+        }
+        let key_values_pos = table_pos + element_count * KEY_OFFSET_SIZE;
+        bytes.copy_within(key_values_pos.., key_values_pos - saved);
+        bytes.truncate(bytes.len() - saved);
+        bytes[start_pos - OBJECT_SIZE - DATA_TYPE_SIZE] = COMPACT_OBJECT_TYPE;
+        total_size -= saved;
+    }
+    bytes.write_total_size(total_size as i32, start_pos - OBJECT_SIZE);
+}
+
+/// Estimates the number of bytes needed to encode a value.
+///
+/// The estimation is an upper bound of the encoded size, which can be passed to
+/// `ObjectBuilder::try_new_with_capacity` or `ArrayBuilder::try_new_with_capacity`
+/// to avoid reallocations during building.
+pub trait EstimateSize {
+    /// Returns the estimated number of bytes needed to encode `self` with its type.
+    fn estimate_size(&self) -> usize;
+}
+
+impl EstimateSize for Value<'_> {
+    #[inline]
+    fn estimate_size(&self) -> usize {
+        match self {
+            Value::Object(object) => object.yason().as_bytes().len(),
+            Value::Array(array) => array.yason().as_bytes().len(),
+            Value::String(s) => DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + s.len(),
+            Value::Number(_) => DATA_TYPE_SIZE + NUMBER_LENGTH_SIZE + MAX_BINARY_SIZE,
+            Value::Bool(_) => DATA_TYPE_SIZE + BOOL_SIZE,
+            Value::Null => DATA_TYPE_SIZE,
+            Value::Binary(b) => DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + b.len(),
+            Value::Timestamp(_) => DATA_TYPE_SIZE + TIMESTAMP_SIZE,
+            Value::Date(_) => DATA_TYPE_SIZE + DATE_SIZE,
+            Value::ShortDate(_) => DATA_TYPE_SIZE + SHORT_DATE_SIZE,
+            Value::Time(_) => DATA_TYPE_SIZE + TIME_SIZE,
+            Value::IntervalYearMonth(_) => DATA_TYPE_SIZE + INTERVAL_YM_SIZE,
+            Value::IntervalDayTime(_) => DATA_TYPE_SIZE + INTERVAL_DT_SIZE,
+        }
+    }
+}
+
+/// Estimates the size of an array consisting of the values.
+impl EstimateSize for [Value<'_>] {
+    #[inline]
+    fn estimate_size(&self) -> usize {
+        let mut size = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE * self.len();
+        for value in self {
+            // bool and null are inlined in value entries
+            if !matches!(value, Value::Bool(_) | Value::Null) {
+                size += value.estimate_size();
+            }
+        }
+        size
+    }
+}
+
+/// Estimates the size of an object consisting of the key-value pairs.
+impl EstimateSize for [(&str, Value<'_>)] {
+    #[inline]
+    fn estimate_size(&self) -> usize {
+        let mut size = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + KEY_OFFSET_SIZE * self.len();
+        for (key, value) in self {
+            size += KEY_LENGTH_SIZE + key.len() + value.estimate_size();
+        }
+        size
+    }
+}
+
+/// Encodes the value with its type, the memory must have been reserved by `EstimateSize`.
+#[inline]
+fn push_encoded_value(bytes: &mut Vec<u8>, value: &Value) -> BuildResult<()> {
+    match value {
+        Value::Object(object) => bytes.push_bytes(object.yason().as_bytes()),
+        Value::Array(array) => bytes.push_bytes(array.yason().as_bytes()),
+        Value::String(s) => {
+            bytes.push_data_type(DataType::String);
+            bytes.push_string(s)?;
+        }
+        Value::Number(number) => {
+            bytes.push_data_type(DataType::Number);
+            bytes.push_number(number);
+        }
+        Value::Bool(b) => {
+            bytes.push_data_type(DataType::Bool);
+            bytes.push_u8(*b as u8);
+        }
+        Value::Null => bytes.push_data_type(DataType::Null),
+        Value::Binary(b) => {
+            bytes.push_data_type(DataType::Binary);
+            bytes.push_binary(b)?;
+        }
+        Value::Timestamp(timestamp) => {
+            bytes.push_data_type(DataType::Timestamp);
+            bytes.push_i64(timestamp.micros());
+        }
+        Value::Date(date) => {
+            bytes.push_data_type(DataType::Date);
+            bytes.push_i64(date.micros());
+        }
+        Value::ShortDate(short_date) => {
+            bytes.push_data_type(DataType::ShortDate);
+            bytes.push_i32(short_date.days());
+        }
+        Value::Time(time) => {
+            bytes.push_data_type(DataType::Time);
+            bytes.push_i64(time.micros());
+        }
+        Value::IntervalYearMonth(interval) => {
+            bytes.push_data_type(DataType::IntervalYearMonth);
+            bytes.push_i32(interval.months());
+        }
+        Value::IntervalDayTime(interval) => {
+            bytes.push_data_type(DataType::IntervalDayTime);
+            bytes.push_i64(interval.micros());
+        }
+    }
+    Ok(())
+}
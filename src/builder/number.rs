@@ -0,0 +1,78 @@
+//! Conversions of native numbers for the builders.
+
+use crate::builder::{BuildError, BuildResult, NumberError};
+use crate::Number;
+use std::str::FromStr;
+
+/// A value which can be pushed as a number, e.g. by [`ObjBuilder::push_number`].
+///
+/// Integers are converted exactly, an `i128` or `u128` with more than 38 digits fails with
+/// `NumberError::Overflow`. Floats are converted to the shortest decimal which reads back to the
+/// same value in their own precision, so `0.1f32` and `0.1f64` both become `0.1`. NaN and
+/// infinities fail with `NumberError::NotFinite`, and floats beyond the range of a number with
+/// `NumberError::Overflow`.
+///
+/// ```rust
+/// use yason::{ObjBuilder, ObjectBuilder};
+///
+/// let mut builder = ObjectBuilder::try_new(3, false).unwrap();
+/// builder.push_number("a", 1u8).unwrap();
+/// builder.push_number("b", -2i64).unwrap();
+/// builder.push_number("c", 0.1f32).unwrap();
+/// let yason = builder.finish().unwrap();
+/// assert_eq!(yason.format(false).to_string(), r#"{"a":1,"b":-2,"c":0.1}"#);
+/// ```
+///
+/// [`ObjBuilder::push_number`]: crate::ObjBuilder::push_number
+pub trait TryIntoNumber {
+    /// Converts the value into a number.
+    fn try_into_number(self) -> BuildResult<Number>;
+}
+
+impl TryIntoNumber for Number {
+    #[inline]
+    fn try_into_number(self) -> BuildResult<Number> {
+        Ok(self)
+    }
+}
+
+impl TryIntoNumber for &Number {
+    #[inline]
+    fn try_into_number(self) -> BuildResult<Number> {
+        Ok(*self)
+    }
+}
+
+macro_rules! impl_try_into_number {
+    ($($ty: ty),*) => {
+        $(
+            impl TryIntoNumber for $ty {
+                #[inline]
+                fn try_into_number(self) -> BuildResult<Number> {
+                    Number::try_from(self).map_err(|_| BuildError::NumberError(NumberError::Overflow))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_into_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_float_try_into_number {
+    ($($ty: ty),*) => {
+        $(
+            impl TryIntoNumber for $ty {
+                #[inline]
+                fn try_into_number(self) -> BuildResult<Number> {
+                    if !self.is_finite() {
+                        return Err(BuildError::NumberError(NumberError::NotFinite));
+                    }
+                    // `{:e}` writes the shortest representation which reads back to the same value
+                    Number::from_str(&format!("{:e}", self)).map_err(|_| BuildError::NumberError(NumberError::Overflow))
+                }
+            }
+        )*
+    };
+}
+
+impl_float_try_into_number!(f32, f64);
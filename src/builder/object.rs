@@ -5,13 +5,18 @@ use crate::binary::{
     NUMBER_LENGTH_SIZE, OBJECT_SIZE,
 };
 use crate::builder::array::{ArrayRefBuilder, InnerArrayBuilder};
-use crate::builder::{BuildResult, Depth, DEFAULT_SIZE, MAX_NESTED_DEPTH};
-use crate::util::cmp_key;
+use crate::builder::{
+    element_count, finish_object, push_encoded_value, BuildResult, CapacityHint, Depth, EstimateSize, NumberConstraint,
+    TryIntoNumber,
+};
+use crate::limits::MAX_NESTED_DEPTH;
+use crate::util::{key_bytes_cmp, key_cmp, read_fixed};
 use crate::vec::VecExt;
-use crate::yason::{Yason, YasonBuf};
-use crate::{BuildError, DataType, Number};
+use crate::yason::{push_checksum, Yason, YasonBuf};
+use crate::{BuildError, DataType, Date, DtInterval, Number, ShortDate, Time, Timestamp, Value, YmInterval};
 use decimal_rs::MAX_BINARY_SIZE;
-use std::ptr;
+use std::cmp::Ordering;
+use std::mem::size_of;
 
 pub(crate) struct InnerObjectBuilder<'a, B: AsMut<Vec<u8>>> {
     bytes: B,
@@ -23,6 +28,10 @@ pub(crate) struct InnerObjectBuilder<'a, B: AsMut<Vec<u8>>> {
     key_sorted: bool,
     current_depth: usize,
     total_nested_depth: Depth<'a>,
+    checksum: bool,
+    /// Whether the objects are finished as compact objects if they fit, inherited by the nested
+    /// builders.
+    pub(crate) compact_objects: bool,
 }
 
 impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
@@ -41,7 +50,7 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
         let bytes_init_len = bs.len();
 
         let size = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + KEY_OFFSET_SIZE * (element_count as usize);
-        bs.try_reserve(size)?;
+        bs.try_reserve_bytes(size)?;
 
         bs.push_data_type(DataType::Object); // type
         bs.skip_size(); // size
@@ -62,30 +71,36 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
             key_sorted,
             current_depth: total_depth.depth(),
             total_nested_depth: total_depth,
+            checksum: false,
+            compact_objects: false,
         })
     }
 
+    /// Verifies that the key offsets of the pushed key-values point to UTF-8 keys inside the
+    /// written bytes, and that the keys are sorted, without duplicates given `key_sorted = true`.
     #[inline]
-    fn key_sorted(&mut self) -> bool {
-        if self.element_count <= 1 {
-            return true;
-        }
-
-        let begin = self.start_pos + ELEMENT_COUNT_SIZE;
-        let end = begin + self.element_count as usize * KEY_OFFSET_SIZE;
-
+    fn check_key_offsets(&mut self) -> BuildResult<()> {
+        let min_offset = ELEMENT_COUNT_SIZE + self.element_count as usize * KEY_OFFSET_SIZE;
         let bytes = self.bytes.as_mut();
-        let key_offsets_bytes = bytes[begin..end].as_mut_ptr() as *mut u32;
-        let key_offsets = unsafe { std::slice::from_raw_parts(key_offsets_bytes, (end - begin) / 4) };
-
-        for i in 0..key_offsets.len() - 1 {
-            let cur_key = Self::read_key_by_offset(bytes, key_offsets[i] as usize, self.start_pos);
-            let next_key = Self::read_key_by_offset(bytes, key_offsets[i + 1] as usize, self.start_pos);
-            if cur_key.len() > next_key.len() || (cur_key.len() == next_key.len() && cur_key > next_key) {
-                return false;
+
+        let mut prev_key: Option<&str> = None;
+        for index in 0..self.value_count as usize {
+            let offset_pos = self.start_pos + ELEMENT_COUNT_SIZE + index * KEY_OFFSET_SIZE;
+            let key_offset = read_fixed(bytes, offset_pos).map(u32::from_le_bytes);
+            if !matches!(key_offset, Some(offset) if offset as usize >= min_offset) {
+                return Err(BuildError::InvalidKeyOffset);
             }
+            let key = Self::key_at(bytes, self.start_pos, index).ok_or(BuildError::InvalidKeyOffset)?;
+            let key = std::str::from_utf8(key).map_err(|_| BuildError::InvalidKeyOffset)?;
+            if let Some(prev_key) = prev_key {
+                let ordering = key_cmp(prev_key, key);
+                if ordering == Ordering::Greater || (self.key_sorted && ordering == Ordering::Equal) {
+                    return Err(BuildError::UnsortedKeys);
+                }
+            }
+            prev_key = Some(key);
         }
-        true
+        Ok(())
     }
 
     #[inline]
@@ -100,13 +115,15 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
             });
         }
 
-        let bytes = self.bytes.as_mut();
-        let total_size = bytes.len() - self.start_pos;
-        bytes.write_total_size(total_size as i32, self.start_pos - OBJECT_SIZE);
+        debug_assert!(self.check_key_offsets().is_ok());
+        finish_object(
+            self.bytes.as_mut(),
+            self.start_pos,
+            self.element_count as usize,
+            self.compact_objects,
+        );
 
         self.total_nested_depth.decrease();
-
-        debug_assert!(self.key_sorted());
         Ok(self.bytes_init_len)
     }
 
@@ -119,25 +136,38 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
             return Err(BuildError::InnerUncompletedError);
         }
 
-        let bytes = self.bytes.as_mut();
-        bytes.try_reserve(reserved_size)?;
+        self.bytes.as_mut().try_reserve_bytes(reserved_size)?;
+        self.write_key_value(key, f)
+    }
 
+    /// Writes the key and the value written by `f`, the depth must have been checked and the bytes
+    /// must have been reserved.
+    #[inline]
+    fn write_key_value<F>(&mut self, key: &str, f: F) -> BuildResult<()>
+    where
+        F: FnOnce(&mut Vec<u8>) -> BuildResult<()>,
+    {
+        let bytes = self.bytes.as_mut();
         if !self.key_sorted {
-            let pos = Self::binary_search(key, bytes, self.start_pos, self.value_count as usize);
+            let pos = Self::binary_search(key, bytes, self.start_pos, self.value_count as usize)?;
 
             let key_offset = bytes.len() - self.start_pos;
             let offset_pos = self.start_pos + ELEMENT_COUNT_SIZE + pos * KEY_OFFSET_SIZE;
 
             if pos < self.value_count as usize {
                 let count = (self.value_count as usize - pos) * KEY_OFFSET_SIZE;
-                let src = bytes[offset_pos..offset_pos + count].as_mut_ptr();
-                let dst = unsafe { src.add(KEY_OFFSET_SIZE) };
-
-                unsafe { ptr::copy(src, dst, count) }
+                bytes.copy_within(offset_pos..offset_pos + count, offset_pos + KEY_OFFSET_SIZE);
             }
             bytes.write_offset(key_offset as u32, offset_pos);
             bytes.push_key(key);
         } else {
+            if self.value_count > 0 {
+                let prev_key = Self::key_at(bytes, self.start_pos, self.value_count as usize - 1)
+                    .ok_or(BuildError::InvalidKeyOffset)?;
+                if key_bytes_cmp(prev_key, key.as_bytes()) != Ordering::Less {
+                    return Err(BuildError::UnsortedKeys);
+                }
+            }
             let key_offset = bytes.len() - self.start_pos;
             bytes.write_offset(key_offset as u32, self.key_offset_pos);
             bytes.push_key(key);
@@ -151,34 +181,33 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
         Ok(())
     }
 
+    /// Returns the position of `target` in the sorted key offsets of the first `value_count`
+    /// key-values, or the position to insert it at.
     #[inline]
-    fn binary_search(target: &str, bytes: &[u8], start_pos: usize, value_count: usize) -> usize {
-        let begin = start_pos + ELEMENT_COUNT_SIZE;
-        let end = begin + value_count * KEY_OFFSET_SIZE;
-
-        let key_offsets_bytes = bytes[begin..end].as_ptr() as *mut u32;
-        let key_offsets = unsafe { std::slice::from_raw_parts(key_offsets_bytes, (end - begin) / KEY_OFFSET_SIZE) };
-
-        let found = key_offsets.binary_search_by(|key_offset| {
-            let key = Self::read_key_by_offset(bytes, *key_offset as usize, start_pos);
-            cmp_key(key, target)
-        });
-
-        match found {
-            Ok(v) => v,
-            Err(v) => v,
+    fn binary_search(target: &str, bytes: &[u8], start_pos: usize, value_count: usize) -> BuildResult<usize> {
+        let mut left = 0;
+        let mut right = value_count;
+        while left < right {
+            let mid = left + (right - left) / 2;
+            let key = Self::key_at(bytes, start_pos, mid).ok_or(BuildError::InvalidKeyOffset)?;
+            match key_bytes_cmp(key, target.as_bytes()) {
+                Ordering::Less => left = mid + 1,
+                Ordering::Greater => right = mid,
+                Ordering::Equal => return Ok(mid),
+            }
         }
+        Ok(left)
     }
 
+    /// Returns the bytes of the key of the `index`-th key offset, or `None` if it is out of the
+    /// written bytes. Offsets and lengths are read with `from_le_bytes` from byte arrays, so the
+    /// key-offset table needs no alignment.
     #[inline]
-    fn read_key_by_offset(bytes: &[u8], key_offset: usize, start_pos: usize) -> &str {
-        let key_index = key_offset + start_pos;
-        let key_length_bytes = &bytes[key_index..key_index + KEY_LENGTH_SIZE];
-        // SAFETY: The `key_length_bytes` must be valid because the slice operation always takes 2 bytes.
-        let key_length = u16::from_le_bytes(key_length_bytes.try_into().unwrap()) as usize;
-
-        let key_bytes = &bytes[key_index + KEY_LENGTH_SIZE..key_index + KEY_LENGTH_SIZE + key_length];
-        unsafe { std::str::from_utf8_unchecked(key_bytes) }
+    fn key_at(bytes: &[u8], start_pos: usize, index: usize) -> Option<&[u8]> {
+        let offset_pos = start_pos + ELEMENT_COUNT_SIZE + index * KEY_OFFSET_SIZE;
+        let key_pos = start_pos.checked_add(u32::from_le_bytes(read_fixed(bytes, offset_pos)?) as usize)?;
+        let key_length = u16::from_le_bytes(read_fixed(bytes, key_pos)?) as usize;
+        bytes.get(key_pos + KEY_LENGTH_SIZE..key_pos + KEY_LENGTH_SIZE + key_length)
     }
 
     #[inline]
@@ -187,19 +216,24 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
         key: &str,
         element_count: u16,
         key_sorted: bool,
-    ) -> BuildResult<InnerObjectBuilder<&mut Vec<u8>>> {
+    ) -> BuildResult<InnerObjectBuilder<'_, &mut Vec<u8>>> {
         let size = key.len() + KEY_LENGTH_SIZE;
         self.push_key_value_by(key, size, |_| Ok(()))?;
         let bytes = self.bytes.as_mut();
-        InnerObjectBuilder::try_new(bytes, element_count, key_sorted, self.total_nested_depth.borrow_mut())
+        let mut builder =
+            InnerObjectBuilder::try_new(bytes, element_count, key_sorted, self.total_nested_depth.borrow_mut())?;
+        builder.compact_objects = self.compact_objects;
+        Ok(builder)
     }
 
     #[inline]
-    fn push_array(&mut self, key: &str, element_count: u16) -> BuildResult<InnerArrayBuilder<&mut Vec<u8>>> {
+    fn push_array(&mut self, key: &str, element_count: u16) -> BuildResult<InnerArrayBuilder<'_, &mut Vec<u8>>> {
         let size = key.len() + KEY_LENGTH_SIZE;
         self.push_key_value_by(key, size, |_| Ok(()))?;
         let bytes = self.bytes.as_mut();
-        InnerArrayBuilder::try_new(bytes, element_count, self.total_nested_depth.borrow_mut())
+        let mut builder = InnerArrayBuilder::try_new(bytes, element_count, self.total_nested_depth.borrow_mut())?;
+        builder.compact_objects = self.compact_objects;
+        Ok(builder)
     }
 
     #[inline]
@@ -213,6 +247,39 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
         self.push_key_value_by(key, size, f)
     }
 
+    #[inline]
+    fn push_binary(&mut self, key: &str, value: &[u8]) -> BuildResult<()> {
+        let size = KEY_LENGTH_SIZE + key.len() + DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + value.len();
+        let f = |bytes: &mut Vec<u8>| {
+            bytes.push_data_type(DataType::Binary);
+            bytes.push_binary(value)?;
+            Ok(())
+        };
+        self.push_key_value_by(key, size, f)
+    }
+
+    #[inline]
+    fn push_i32_value(&mut self, key: &str, data_type: DataType, value: i32) -> BuildResult<()> {
+        let size = KEY_LENGTH_SIZE + key.len() + DATA_TYPE_SIZE + size_of::<i32>();
+        let f = |bytes: &mut Vec<u8>| {
+            bytes.push_data_type(data_type);
+            bytes.push_i32(value);
+            Ok(())
+        };
+        self.push_key_value_by(key, size, f)
+    }
+
+    #[inline]
+    fn push_i64_value(&mut self, key: &str, data_type: DataType, value: i64) -> BuildResult<()> {
+        let size = KEY_LENGTH_SIZE + key.len() + DATA_TYPE_SIZE + size_of::<i64>();
+        let f = |bytes: &mut Vec<u8>| {
+            bytes.push_data_type(data_type);
+            bytes.push_i64(value);
+            Ok(())
+        };
+        self.push_key_value_by(key, size, f)
+    }
+
     #[inline]
     fn push_number(&mut self, key: &str, value: &Number) -> BuildResult<()> {
         let size = KEY_LENGTH_SIZE + key.len() + DATA_TYPE_SIZE + NUMBER_LENGTH_SIZE + MAX_BINARY_SIZE;
@@ -244,6 +311,88 @@ impl<'a, B: AsMut<Vec<u8>>> InnerObjectBuilder<'a, B> {
         };
         self.push_key_value_by(key, size, f)
     }
+
+    #[inline]
+    unsafe fn push_object_or_array(&mut self, key: &str, yason: &Yason) -> BuildResult<()> {
+        let value = yason.as_bytes();
+        let size = KEY_LENGTH_SIZE + key.len() + value.len();
+        let f = |bytes: &mut Vec<u8>| {
+            bytes.extend_from_slice(value);
+            Ok(())
+        };
+        self.push_key_value_by(key, size, f)
+    }
+
+    #[inline]
+    fn push_yason(&mut self, key: &str, yason: &Yason) -> BuildResult<()> {
+        // a value of an object is encoded as it is, only the header and the checksum are left out
+        let value = yason.value().map_err(BuildError::InvalidYason)?;
+        unsafe { self.push_object_or_array(key, value) }
+    }
+
+    /// Pushes key-values of the same type in a tight loop, the depth and the element count are
+    /// checked and the bytes are reserved only once. `size` returns the reserved size of a value and
+    /// `f` writes a value.
+    #[inline]
+    fn push_key_values<Key, V, S, F>(&mut self, pairs: &[(Key, V)], size: S, mut f: F) -> BuildResult<()>
+    where
+        Key: AsRef<str>,
+        S: Fn(&V) -> usize,
+        F: FnMut(&mut Vec<u8>, &V) -> BuildResult<()>,
+    {
+        if self.current_depth != self.total_nested_depth.depth() {
+            return Err(BuildError::InnerUncompletedError);
+        }
+        let count = self.value_count as usize + pairs.len();
+        if count > self.element_count as usize {
+            return Err(BuildError::InconsistentElementCount {
+                expected: self.element_count,
+                actual: count.min(u16::MAX as usize) as u16,
+            });
+        }
+
+        let reserved_size = pairs
+            .iter()
+            .map(|(key, value)| KEY_LENGTH_SIZE + key.as_ref().len() + size(value))
+            .sum();
+        self.bytes.as_mut().try_reserve_bytes(reserved_size)?;
+        for (key, value) in pairs {
+            self.write_key_value(key.as_ref(), |bytes| f(bytes, value))?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn push_strings<Key: AsRef<str>, Val: AsRef<str>>(&mut self, pairs: &[(Key, Val)]) -> BuildResult<()> {
+        let size = |value: &Val| DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + value.as_ref().len();
+        let f = |bytes: &mut Vec<u8>, value: &Val| {
+            bytes.push_data_type(DataType::String);
+            bytes.push_string(value.as_ref())
+        };
+        self.push_key_values(pairs, size, f)
+    }
+
+    #[inline]
+    fn push_numbers<Key: AsRef<str>>(&mut self, pairs: &[(Key, Number)]) -> BuildResult<()> {
+        let size = |_: &Number| DATA_TYPE_SIZE + NUMBER_LENGTH_SIZE + MAX_BINARY_SIZE;
+        let f = |bytes: &mut Vec<u8>, value: &Number| {
+            bytes.push_data_type(DataType::Number);
+            bytes.push_number(value);
+            Ok(())
+        };
+        self.push_key_values(pairs, size, f)
+    }
+
+    #[inline]
+    fn push_bools<Key: AsRef<str>>(&mut self, pairs: &[(Key, bool)]) -> BuildResult<()> {
+        let size = |_: &bool| DATA_TYPE_SIZE + BOOL_SIZE;
+        let f = |bytes: &mut Vec<u8>, value: &bool| {
+            bytes.push_data_type(DataType::Bool);
+            bytes.push_u8(*value as u8);
+            Ok(())
+        };
+        self.push_key_values(pairs, size, f)
+    }
 }
 
 /// Builder for encoding an object.
@@ -252,20 +401,143 @@ pub struct ObjectBuilder<'a>(InnerObjectBuilder<'a, Vec<u8>>);
 
 impl ObjectBuilder<'_> {
     /// Creates `ObjectBuilder` with specified element count.
-    /// `key_sorted` indicates whether the object is sorted by key.
+    /// `key_sorted` indicates whether the keys are pushed in the order of [`key_cmp`](crate::key_cmp),
+    /// otherwise `BuildError::UnsortedKeys` is returned when a key is out of order.
+    ///
+    /// The buffer is allocated with the capacity of the default [`CapacityHint`](crate::CapacityHint).
     #[inline]
     pub fn try_new(element_count: u16, key_sorted: bool) -> BuildResult<Self> {
-        let bytes = Vec::try_with_capacity(DEFAULT_SIZE)?;
+        let capacity = CapacityHint::new().object_capacity(element_count);
+        Self::try_new_with_capacity(element_count, key_sorted, capacity)
+    }
+
+    /// Creates `ObjectBuilder` with specified element count and a hint of the encoded size in bytes.
+    /// `key_sorted` indicates whether the keys are pushed in the order of [`key_cmp`](crate::key_cmp),
+    /// otherwise `BuildError::UnsortedKeys` is returned when a key is out of order.
+    ///
+    /// The buffer is allocated with at least `capacity` bytes, see [`EstimateSize`].
+    #[inline]
+    pub fn try_new_with_capacity(element_count: u16, key_sorted: bool, capacity: usize) -> BuildResult<Self> {
+        let bytes: Vec<u8> = VecExt::try_with_capacity(capacity)?;
         let builder = InnerObjectBuilder::try_new(bytes, element_count, key_sorted, Depth::new())?;
         Ok(Self(builder))
     }
 
+    /// Encodes an object from the given key-value pairs.
+    ///
+    /// The total size is computed before encoding so that the buffer is allocated only once,
+    /// and the keys are sorted once instead of being inserted into the key-offset table one by one.
+    /// The order of `pairs` is kept as the insertion order, see [`crate::Object::iter_insertion_order`].
+    #[inline]
+    pub fn from_pairs(pairs: &[(&str, Value)]) -> BuildResult<YasonBuf> {
+        let count = element_count(pairs.len())?;
+
+        let mut sorted = Vec::new();
+        sorted.try_reserve(pairs.len())?;
+        sorted.extend(0..pairs.len());
+        sorted.sort_by(|l, r| key_cmp(pairs[*l].0, pairs[*r].0));
+
+        // the position of each pair in the key-offset table
+        let mut positions = Vec::new();
+        positions.try_reserve(pairs.len())?;
+        positions.resize(pairs.len(), 0);
+        for (pos, index) in sorted.into_iter().enumerate() {
+            positions[index] = pos;
+        }
+
+        let mut bytes: Vec<u8> = VecExt::try_with_capacity(pairs.estimate_size())?;
+        bytes.push_data_type(DataType::Object); // type
+        bytes.skip_size(); // size
+        let start_pos = bytes.len();
+        bytes.push_u16(count); // element-count
+        let key_offset_pos = bytes.len();
+        bytes.skip_key_offset(pairs.len()); // key-offset
+
+        // key-values are stored in the given order, which is kept as the insertion order
+        for ((key, value), pos) in pairs.iter().zip(positions) {
+            let key_offset = bytes.len() - start_pos;
+            bytes.write_offset(key_offset as u32, key_offset_pos + pos * KEY_OFFSET_SIZE);
+            bytes.push_key(key);
+            push_encoded_value(&mut bytes, value)?;
+        }
+
+        finish_object(&mut bytes, start_pos, pairs.len(), false);
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Creates `ObjectBuilder` with specified element count, reusing the given buffer.
+    /// `key_sorted` indicates whether the keys are pushed in the order of [`key_cmp`](crate::key_cmp),
+    /// otherwise `BuildError::UnsortedKeys` is returned when a key is out of order.
+    ///
+    /// The buffer is cleared first, only its capacity is kept.
+    #[inline]
+    pub fn try_new_in(mut bytes: Vec<u8>, element_count: u16, key_sorted: bool) -> BuildResult<Self> {
+        bytes.clear();
+        let builder = InnerObjectBuilder::try_new(bytes, element_count, key_sorted, Depth::new())?;
+        Ok(Self(builder))
+    }
+
+    /// Resets the builder to encode a new object with specified element count, the internal buffer is
+    /// reused without reallocation.
+    /// `key_sorted` indicates whether the keys are pushed in the order of [`key_cmp`](crate::key_cmp),
+    /// otherwise `BuildError::UnsortedKeys` is returned when a key is out of order.
+    ///
+    /// If an error is returned, the builder must be reset successfully before it can be used again.
+    #[inline]
+    pub fn reset(&mut self, element_count: u16, key_sorted: bool) -> BuildResult<()> {
+        let mut bytes = std::mem::take(&mut self.0.bytes);
+        bytes.clear();
+        let checksum = self.0.checksum;
+        let compact_objects = self.0.compact_objects;
+        self.0 = InnerObjectBuilder::try_new(bytes, element_count, key_sorted, Depth::new())?;
+        self.0.checksum = checksum;
+        self.0.compact_objects = compact_objects;
+        Ok(())
+    }
+
+    /// Appends a CRC32C footer to the finished object, see [`Yason::verify_checksum`].
+    ///
+    /// The option is kept by [`ObjectBuilder::reset`].
+    #[inline]
+    pub fn with_checksum(mut self) -> Self {
+        self.0.checksum = true;
+        self
+    }
+
+    /// Encodes the object and its nested objects with 2-byte key offsets if they fit, see
+    /// [`COMPACT_OBJECT_TYPE`](crate::raw::COMPACT_OBJECT_TYPE). Readers of the legacy format
+    /// cannot decode such objects, so they are only written if asked for.
+    ///
+    /// The option is kept by [`ObjectBuilder::reset`].
+    #[inline]
+    pub fn with_compact_objects(mut self) -> Self {
+        self.0.compact_objects = true;
+        self
+    }
+
     /// Finishes building the object.
     #[inline]
     pub fn finish(mut self) -> BuildResult<YasonBuf> {
-        self.0.finish()?;
+        self.finish_bytes()?;
         Ok(unsafe { YasonBuf::new_unchecked(self.0.bytes) })
     }
+
+    /// Finishes building the object without giving up the internal buffer, the builder can be
+    /// reused by [`ObjectBuilder::reset`] after the returned yason is dropped.
+    #[inline]
+    pub fn finish_ref(&mut self) -> BuildResult<&Yason> {
+        self.finish_bytes()?;
+        Ok(unsafe { Yason::new_unchecked(&self.0.bytes) })
+    }
+
+    #[inline]
+    fn finish_bytes(&mut self) -> BuildResult<()> {
+        self.0.finish()?;
+        if self.0.checksum {
+            push_checksum(&mut self.0.bytes, 0)?;
+        }
+        Ok(())
+    }
 }
 
 /// Builder for encoding an object.
@@ -274,7 +546,8 @@ pub struct ObjectRefBuilder<'a>(pub(crate) InnerObjectBuilder<'a, &'a mut Vec<u8
 
 impl<'a> ObjectRefBuilder<'a> {
     /// Creates `ObjectRefBuilder` with specified element count.
-    /// `key_sorted` indicates whether the object is sorted by key.
+    /// `key_sorted` indicates whether the keys are pushed in the order of [`key_cmp`](crate::key_cmp),
+    /// otherwise `BuildError::UnsortedKeys` is returned when a key is out of order.
     #[inline]
     pub fn try_new(bytes: &'a mut Vec<u8>, element_count: u16, key_sorted: bool) -> BuildResult<Self> {
         let obj_builder = InnerObjectBuilder::try_new(bytes, element_count, key_sorted, Depth::new())?;
@@ -288,6 +561,19 @@ impl<'a> ObjectRefBuilder<'a> {
         let bytes = self.0.bytes;
         Ok(unsafe { Yason::new_unchecked(&bytes[bytes_init_len..]) })
     }
+
+    #[inline]
+    pub(crate) unsafe fn push_object_or_array<Key: AsRef<str>>(
+        &mut self,
+        key: Key,
+        yason: &Yason,
+        data_type: DataType,
+    ) -> BuildResult<&mut Self> {
+        debug_assert!(matches!(yason.data_type().unwrap(), DataType::Object | DataType::Array));
+        debug_assert!(yason.data_type().unwrap() == data_type);
+        self.0.push_object_or_array(key.as_ref(), yason)?;
+        Ok(self)
+    }
 }
 
 pub trait ObjBuilder {
@@ -297,22 +583,211 @@ pub trait ObjBuilder {
         key: Key,
         element_count: u16,
         key_sorted: bool,
-    ) -> BuildResult<ObjectRefBuilder>;
+    ) -> BuildResult<ObjectRefBuilder<'_>>;
 
     /// Pushes an embedded array with specified element count.
-    fn push_array<Key: AsRef<str>>(&mut self, key: Key, element_count: u16) -> BuildResult<ArrayRefBuilder>;
+    fn push_array<Key: AsRef<str>>(&mut self, key: Key, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>>;
 
     /// Pushes a string value.
     fn push_string<Key: AsRef<str>, Val: AsRef<str>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self>;
 
     /// Pushes a number value.
-    fn push_number<Key: AsRef<str>, Num: AsRef<Number>>(&mut self, key: Key, value: Num) -> BuildResult<&mut Self>;
+    fn push_number<Key: AsRef<str>, Num: TryIntoNumber>(&mut self, key: Key, value: Num) -> BuildResult<&mut Self>;
+
+    /// Pushes a number value rounded to the scale of the constraint, returns
+    /// `BuildError::NumberOutOfRange` if it exceeds the precision of the constraint.
+    fn push_number_with<Key: AsRef<str>, Num: TryIntoNumber>(
+        &mut self,
+        key: Key,
+        value: Num,
+        constraint: &NumberConstraint,
+    ) -> BuildResult<&mut Self>;
 
     /// Pushes a bool value.
     fn push_bool<Key: AsRef<str>>(&mut self, key: Key, value: bool) -> BuildResult<&mut Self>;
 
     /// Pushes a null value.
     fn push_null<Key: AsRef<str>>(&mut self, key: Key) -> BuildResult<&mut Self>;
+
+    /// Pushes a binary value.
+    fn push_binary<Key: AsRef<str>, Val: AsRef<[u8]>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes a timestamp value.
+    fn push_timestamp<Key: AsRef<str>, Val: Into<Timestamp>>(&mut self, key: Key, value: Val)
+        -> BuildResult<&mut Self>;
+
+    /// Pushes a date value.
+    fn push_date<Key: AsRef<str>, Val: Into<Date>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes a short date value.
+    fn push_short_date<Key: AsRef<str>, Val: Into<ShortDate>>(
+        &mut self,
+        key: Key,
+        value: Val,
+    ) -> BuildResult<&mut Self>;
+
+    /// Pushes a time value.
+    fn push_time<Key: AsRef<str>, Val: Into<Time>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self>;
+
+    /// Pushes an interval year-month value.
+    fn push_interval_year_month<Key: AsRef<str>, Val: Into<YmInterval>>(
+        &mut self,
+        key: Key,
+        value: Val,
+    ) -> BuildResult<&mut Self>;
+
+    /// Pushes an interval day-time value.
+    fn push_interval_day_time<Key: AsRef<str>, Val: Into<DtInterval>>(
+        &mut self,
+        key: Key,
+        value: Val,
+    ) -> BuildResult<&mut Self>;
+
+    /// Pushes a value of any type by copying its encoded bytes, without decoding it.
+    ///
+    /// Returns `BuildError::InvalidYason` if the data type of the value cannot be read.
+    fn push_yason<Key: AsRef<str>>(&mut self, key: Key, value: &Yason) -> BuildResult<&mut Self>;
+}
+
+/// Object-safe variant of [`ObjBuilder`], which can be used as `dyn ObjBuilderDyn` to build
+/// nested containers without monomorphizing the callers for each builder type.
+///
+/// The methods are suffixed with `_dyn`, so that they do not clash with the methods of
+/// [`ObjBuilder`] when both traits are in scope.
+pub trait ObjBuilderDyn {
+    /// Pushes an embedded object with specified element count and a flag which indicates whether the embedded object is sorted by key.
+    fn push_object_dyn(&mut self, key: &str, element_count: u16, key_sorted: bool)
+        -> BuildResult<ObjectRefBuilder<'_>>;
+
+    /// Pushes an embedded array with specified element count.
+    fn push_array_dyn(&mut self, key: &str, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>>;
+
+    /// Pushes a string value.
+    fn push_string_dyn(&mut self, key: &str, value: &str) -> BuildResult<()>;
+
+    /// Pushes a number value.
+    fn push_number_dyn(&mut self, key: &str, value: &Number) -> BuildResult<()>;
+
+    /// Pushes a bool value.
+    fn push_bool_dyn(&mut self, key: &str, value: bool) -> BuildResult<()>;
+
+    /// Pushes a null value.
+    fn push_null_dyn(&mut self, key: &str) -> BuildResult<()>;
+
+    /// Pushes a binary value.
+    fn push_binary_dyn(&mut self, key: &str, value: &[u8]) -> BuildResult<()>;
+
+    /// Pushes a timestamp value.
+    fn push_timestamp_dyn(&mut self, key: &str, value: Timestamp) -> BuildResult<()>;
+
+    /// Pushes a date value.
+    fn push_date_dyn(&mut self, key: &str, value: Date) -> BuildResult<()>;
+
+    /// Pushes a short date value.
+    fn push_short_date_dyn(&mut self, key: &str, value: ShortDate) -> BuildResult<()>;
+
+    /// Pushes a time value.
+    fn push_time_dyn(&mut self, key: &str, value: Time) -> BuildResult<()>;
+
+    /// Pushes an interval year-month value.
+    fn push_interval_year_month_dyn(&mut self, key: &str, value: YmInterval) -> BuildResult<()>;
+
+    /// Pushes an interval day-time value.
+    fn push_interval_day_time_dyn(&mut self, key: &str, value: DtInterval) -> BuildResult<()>;
+
+    /// Pushes a value of any type by copying its encoded bytes, without decoding it.
+    ///
+    /// Returns `BuildError::InvalidYason` if the data type of the value cannot be read.
+    fn push_yason_dyn(&mut self, key: &str, value: &Yason) -> BuildResult<()>;
+}
+
+impl<T: ObjBuilder> ObjBuilderDyn for T {
+    #[inline]
+    fn push_object_dyn(
+        &mut self,
+        key: &str,
+        element_count: u16,
+        key_sorted: bool,
+    ) -> BuildResult<ObjectRefBuilder<'_>> {
+        ObjBuilder::push_object(self, key, element_count, key_sorted)
+    }
+
+    #[inline]
+    fn push_array_dyn(&mut self, key: &str, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>> {
+        ObjBuilder::push_array(self, key, element_count)
+    }
+
+    #[inline]
+    fn push_string_dyn(&mut self, key: &str, value: &str) -> BuildResult<()> {
+        ObjBuilder::push_string(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_number_dyn(&mut self, key: &str, value: &Number) -> BuildResult<()> {
+        ObjBuilder::push_number(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_bool_dyn(&mut self, key: &str, value: bool) -> BuildResult<()> {
+        ObjBuilder::push_bool(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_null_dyn(&mut self, key: &str) -> BuildResult<()> {
+        ObjBuilder::push_null(self, key)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_binary_dyn(&mut self, key: &str, value: &[u8]) -> BuildResult<()> {
+        ObjBuilder::push_binary(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_timestamp_dyn(&mut self, key: &str, value: Timestamp) -> BuildResult<()> {
+        ObjBuilder::push_timestamp(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_date_dyn(&mut self, key: &str, value: Date) -> BuildResult<()> {
+        ObjBuilder::push_date(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_short_date_dyn(&mut self, key: &str, value: ShortDate) -> BuildResult<()> {
+        ObjBuilder::push_short_date(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_time_dyn(&mut self, key: &str, value: Time) -> BuildResult<()> {
+        ObjBuilder::push_time(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_interval_year_month_dyn(&mut self, key: &str, value: YmInterval) -> BuildResult<()> {
+        ObjBuilder::push_interval_year_month(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_interval_day_time_dyn(&mut self, key: &str, value: DtInterval) -> BuildResult<()> {
+        ObjBuilder::push_interval_day_time(self, key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn push_yason_dyn(&mut self, key: &str, value: &Yason) -> BuildResult<()> {
+        ObjBuilder::push_yason(self, key, value)?;
+        Ok(())
+    }
 }
 
 macro_rules! impl_push_methods {
@@ -324,7 +799,7 @@ macro_rules! impl_push_methods {
             key: Key,
             element_count: u16,
             key_sorted: bool,
-        ) -> BuildResult<ObjectRefBuilder> {
+        ) -> BuildResult<ObjectRefBuilder<'_>> {
             let key = key.as_ref();
             let obj_builder = self.0.push_object(key, element_count, key_sorted)?;
             Ok(ObjectRefBuilder(obj_builder))
@@ -332,7 +807,7 @@ macro_rules! impl_push_methods {
 
         /// Pushes an embedded array with specified element count.
         #[inline]
-        $v fn push_array<Key: AsRef<str>>(&mut self, key: Key, element_count: u16) -> BuildResult<ArrayRefBuilder> {
+        $v fn push_array<Key: AsRef<str>>(&mut self, key: Key, element_count: u16) -> BuildResult<ArrayRefBuilder<'_>> {
             let key = key.as_ref();
             let array_builder = self.0.push_array(key, element_count)?;
             Ok(ArrayRefBuilder(array_builder))
@@ -353,12 +828,25 @@ macro_rules! impl_push_methods {
 
         /// Pushes a number value.
         #[inline]
-        $v fn push_number<Key: AsRef<str>, Num: AsRef<Number>>(&mut self, key: Key, value: Num) -> BuildResult<&mut Self> {
+        $v fn push_number<Key: AsRef<str>, Num: TryIntoNumber>(&mut self, key: Key, value: Num) -> BuildResult<&mut Self> {
             let key = key.as_ref();
-            self.0.push_number(key, value.as_ref())?;
+            self.0.push_number(key, &value.try_into_number()?)?;
             Ok(self)
         }
 
+        /// Pushes a number value rounded to the scale of the constraint, returns
+        /// `BuildError::NumberOutOfRange` if it exceeds the precision of the constraint.
+        #[inline]
+        $v fn push_number_with<Key: AsRef<str>, Num: TryIntoNumber>(
+            &mut self,
+            key: Key,
+            value: Num,
+            constraint: &NumberConstraint,
+        ) -> BuildResult<&mut Self> {
+            let value = constraint.apply(&value.try_into_number()?)?;
+            self.push_number(key, value)
+        }
+
         /// Pushes a bool value.
         #[inline]
         $v fn push_bool<Key: AsRef<str>>(&mut self, key: Key, value: bool) -> BuildResult<&mut Self> {
@@ -374,6 +862,79 @@ macro_rules! impl_push_methods {
             self.0.push_null(key)?;
             Ok(self)
         }
+
+        /// Pushes a binary value.
+        #[inline]
+        $v fn push_binary<Key: AsRef<str>, Val: AsRef<[u8]>>(
+            &mut self,
+            key: Key,
+            value: Val,
+        ) -> BuildResult<&mut Self> {
+            let key = key.as_ref();
+            self.0.push_binary(key, value.as_ref())?;
+            Ok(self)
+        }
+
+        /// Pushes a timestamp value.
+        #[inline]
+        $v fn push_timestamp<Key: AsRef<str>, Val: Into<Timestamp>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self> {
+            let key = key.as_ref();
+            self.0.push_i64_value(key, DataType::Timestamp, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes a date value.
+        #[inline]
+        $v fn push_date<Key: AsRef<str>, Val: Into<Date>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self> {
+            let key = key.as_ref();
+            self.0.push_i64_value(key, DataType::Date, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes a short date value.
+        #[inline]
+        $v fn push_short_date<Key: AsRef<str>, Val: Into<ShortDate>>(
+            &mut self,
+            key: Key,
+            value: Val,
+        ) -> BuildResult<&mut Self> {
+            let key = key.as_ref();
+            self.0.push_i32_value(key, DataType::ShortDate, value.into().days())?;
+            Ok(self)
+        }
+
+        /// Pushes a time value.
+        #[inline]
+        $v fn push_time<Key: AsRef<str>, Val: Into<Time>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self> {
+            let key = key.as_ref();
+            self.0.push_i64_value(key, DataType::Time, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes an interval year-month value.
+        #[inline]
+        $v fn push_interval_year_month<Key: AsRef<str>, Val: Into<YmInterval>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self> {
+            let key = key.as_ref();
+            self.0.push_i32_value(key, DataType::IntervalYearMonth, value.into().months())?;
+            Ok(self)
+        }
+
+        /// Pushes an interval day-time value.
+        #[inline]
+        $v fn push_interval_day_time<Key: AsRef<str>, Val: Into<DtInterval>>(&mut self, key: Key, value: Val) -> BuildResult<&mut Self> {
+            let key = key.as_ref();
+            self.0.push_i64_value(key, DataType::IntervalDayTime, value.into().micros())?;
+            Ok(self)
+        }
+
+        /// Pushes a value of any type by copying its encoded bytes, without decoding it.
+        ///
+        /// Returns `BuildError::InvalidYason` if the data type of the value cannot be read.
+        #[inline]
+        $v fn push_yason<Key: AsRef<str>>(&mut self, key: Key, value: &Yason) -> BuildResult<&mut Self> {
+            self.0.push_yason(key.as_ref(), value)?;
+            Ok(self)
+        }
     };
 }
 
@@ -381,6 +942,55 @@ macro_rules! impl_builder {
     ($builder: ty) => {
         impl $builder {
             impl_push_methods!(pub,);
+
+            /// Pushes key-values with string values, reserving the bytes for all of them at once.
+            /// Returns `BuildError::InconsistentElementCount` without pushing any key-value if there
+            /// are more key-values than the elements left.
+            #[inline]
+            pub fn push_strings<Key: AsRef<str>, Val: AsRef<str>>(
+                &mut self,
+                pairs: &[(Key, Val)],
+            ) -> BuildResult<&mut Self> {
+                self.0.push_strings(pairs)?;
+                Ok(self)
+            }
+
+            /// Pushes key-values with number values, reserving the bytes for all of them at once.
+            /// Returns `BuildError::InconsistentElementCount` without pushing any key-value if there
+            /// are more key-values than the elements left.
+            #[inline]
+            pub fn push_numbers<Key: AsRef<str>>(&mut self, pairs: &[(Key, Number)]) -> BuildResult<&mut Self> {
+                self.0.push_numbers(pairs)?;
+                Ok(self)
+            }
+
+            /// Pushes key-values with bool values, reserving the bytes for all of them at once.
+            /// Returns `BuildError::InconsistentElementCount` without pushing any key-value if there
+            /// are more key-values than the elements left.
+            #[inline]
+            pub fn push_bools<Key: AsRef<str>>(&mut self, pairs: &[(Key, bool)]) -> BuildResult<&mut Self> {
+                self.0.push_bools(pairs)?;
+                Ok(self)
+            }
+
+            /// Verifies the key-offset table of the key-values pushed so far: every offset must point to
+            /// a UTF-8 key inside the written bytes, and the keys must be sorted, strictly if the builder
+            /// was created with `key_sorted = true`. Offsets and keys are
+            /// read with bounds checks and without unaligned pointer reads, so unlike the debug
+            /// assertion of `finish`, it can be used in release builds, e.g. by tests of custom pushes.
+            ///
+            /// Returns `BuildError::InvalidKeyOffset` or `BuildError::UnsortedKeys` if it is corrupt.
+            #[inline]
+            pub fn check_key_offsets(&mut self) -> BuildResult<()> {
+                self.0.check_key_offsets()
+            }
+
+            /// Returns the nesting depth of the object being built, 1 for a top-level object. Nested objects
+            /// and arrays can be pushed while it is less than [`MAX_NESTED_DEPTH`].
+            #[inline]
+            pub fn depth(&self) -> usize {
+                self.0.current_depth
+            }
         }
 
         impl ObjBuilder for $builder {
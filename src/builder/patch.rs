@@ -0,0 +1,199 @@
+//! Object patch builder.
+
+use crate::binary::{DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, OBJECT_SIZE};
+use crate::builder::{element_count, finish_object, push_encoded_value, BuildResult, EstimateSize};
+use crate::util::key_cmp;
+use crate::vec::VecExt;
+use crate::yason::{YasonBuf, YasonResult};
+use crate::{DataType, Object, Value, YasonError};
+use std::collections::HashMap;
+
+/// Where the value of an entry comes from.
+enum Source<'a> {
+    /// The value is copied from the base object, the key is copied along unless it is changed.
+    Base {
+        key_pos: usize,
+        value_pos: usize,
+        end: usize,
+    },
+    /// The value is encoded.
+    Value(Value<'a>),
+}
+
+struct Entry<'a> {
+    key: &'a str,
+    key_changed: bool,
+    source: Source<'a>,
+}
+
+/// Builder for encoding a modified copy of an object.
+///
+/// Key changes are applied in order by [`set`], [`delete`] and [`rename`], and the patched object
+/// is encoded by [`finish`]. The key-values which are not touched are copied from the base object byte by byte
+/// without being decoded, so updating a few keys of a large object is much cheaper than building
+/// it again. The insertion order of the base object is kept, and new keys are inserted at the end.
+///
+/// ```rust
+/// use yason::{ObjectPatchBuilder, Value, YasonBuf};
+///
+/// let yason = YasonBuf::parse(r#"{"id": 1, "name": "foo", "tmp": true}"#).unwrap();
+/// let object = yason.object().unwrap();
+/// let mut builder = ObjectPatchBuilder::try_new(&object).unwrap();
+/// builder.set("name", Value::String("bar")).unwrap();
+/// builder.delete("tmp").unwrap();
+/// builder.rename("id", "key").unwrap();
+/// let patched = builder.finish().unwrap();
+/// assert_eq!(patched.format(false).to_string(), r#"{"key":1,"name":"bar"}"#);
+/// ```
+///
+/// [`set`]: ObjectPatchBuilder::set
+/// [`delete`]: ObjectPatchBuilder::delete
+/// [`rename`]: ObjectPatchBuilder::rename
+/// [`finish`]: ObjectPatchBuilder::finish
+pub struct ObjectPatchBuilder<'a> {
+    base: Object<'a>,
+    // entries in insertion order, `None` if deleted
+    entries: Vec<Option<Entry<'a>>>,
+    // the index of each live key in `entries`
+    index: HashMap<&'a str, usize>,
+}
+
+impl<'a> ObjectPatchBuilder<'a> {
+    /// Creates `ObjectPatchBuilder` with the object to patch.
+    #[inline]
+    pub fn try_new(base: &Object<'a>) -> YasonResult<Self> {
+        let order = base.insertion_order()?;
+        let mut entries = Vec::new();
+        entries.try_reserve(order.len()).map_err(YasonError::TryReserveError)?;
+        let mut index = HashMap::new();
+        index.try_reserve(order.len()).map_err(YasonError::TryReserveError)?;
+        for i in order {
+            let (key, key_pos, value_pos, end) = base.nth_entry_range(i)?;
+            index.insert(key, entries.len());
+            entries.push(Some(Entry {
+                key,
+                key_changed: false,
+                source: Source::Base {
+                    key_pos,
+                    value_pos,
+                    end,
+                },
+            }));
+        }
+        Ok(Self {
+            base: base.clone(),
+            entries,
+            index,
+        })
+    }
+
+    /// Sets the value of a key, the key is inserted at the end if it does not exist.
+    #[inline]
+    pub fn set(&mut self, key: &'a str, value: Value<'a>) -> BuildResult<&mut Self> {
+        match self.index.get(key) {
+            Some(&i) => {
+                let entry = self.entries[i].as_mut().expect("indexed entry should be live");
+                entry.source = Source::Value(value);
+            }
+            None => {
+                self.entries.try_reserve(1)?;
+                self.index.try_reserve(1)?;
+                self.index.insert(key, self.entries.len());
+                self.entries.push(Some(Entry {
+                    key,
+                    key_changed: true,
+                    source: Source::Value(value),
+                }));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Deletes a key, nothing is done if it does not exist.
+    #[inline]
+    pub fn delete(&mut self, key: &str) -> BuildResult<&mut Self> {
+        if let Some(i) = self.index.remove(key) {
+            self.entries[i] = None;
+        }
+        Ok(self)
+    }
+
+    /// Renames a key and keeps its value and position, an existing key with the new name is
+    /// replaced. Nothing is done if the key does not exist.
+    #[inline]
+    pub fn rename(&mut self, from: &str, to: &'a str) -> BuildResult<&mut Self> {
+        if from == to {
+            return Ok(self);
+        }
+        if let Some(i) = self.index.remove(from) {
+            if let Some(replaced) = self.index.remove(to) {
+                self.entries[replaced] = None;
+            }
+            self.index.insert(to, i);
+            let entry = self.entries[i].as_mut().expect("indexed entry should be live");
+            entry.key = to;
+            entry.key_changed = true;
+        }
+        Ok(self)
+    }
+
+    /// Finishes building the patched object.
+    #[inline]
+    pub fn finish(&self) -> BuildResult<YasonBuf> {
+        let base = self.base.yason().as_bytes();
+        let mut entries = Vec::new();
+        entries.try_reserve(self.index.len())?;
+        entries.extend(self.entries.iter().flatten());
+        let count = element_count(entries.len())?;
+
+        let mut sorted = Vec::new();
+        sorted.try_reserve(entries.len())?;
+        sorted.extend(0..entries.len());
+        sorted.sort_by(|l, r| key_cmp(entries[*l].key, entries[*r].key));
+
+        // the position of each entry in the key-offset table
+        let mut positions = Vec::new();
+        positions.try_reserve(entries.len())?;
+        positions.resize(entries.len(), 0);
+        for (pos, index) in sorted.into_iter().enumerate() {
+            positions[index] = pos;
+        }
+
+        let mut size = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + KEY_OFFSET_SIZE * entries.len();
+        for entry in &entries {
+            size += KEY_LENGTH_SIZE + entry.key.len();
+            size += match &entry.source {
+                Source::Base { value_pos, end, .. } => end - value_pos,
+                Source::Value(value) => value.estimate_size(),
+            };
+        }
+
+        let mut bytes: Vec<u8> = VecExt::try_with_capacity(size)?;
+        bytes.push_data_type(DataType::Object); // type
+        bytes.skip_size(); // size
+        let start_pos = bytes.len();
+        bytes.push_u16(count); // element-count
+        let key_offset_pos = bytes.len();
+        bytes.skip_key_offset(entries.len()); // key-offset
+
+        for (entry, pos) in entries.iter().zip(positions) {
+            let key_offset = bytes.len() - start_pos;
+            bytes.write_offset(key_offset as u32, key_offset_pos + pos * KEY_OFFSET_SIZE);
+            match &entry.source {
+                Source::Base { key_pos, end, .. } if !entry.key_changed => bytes.push_bytes(&base[*key_pos..*end]),
+                Source::Base { value_pos, end, .. } => {
+                    bytes.push_key(entry.key);
+                    bytes.push_bytes(&base[*value_pos..*end]);
+                }
+                Source::Value(value) => {
+                    bytes.push_key(entry.key);
+                    push_encoded_value(&mut bytes, value)?;
+                }
+            }
+        }
+
+        // the layout of the base object is kept
+        finish_object(&mut bytes, start_pos, entries.len(), self.base.is_compact());
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+}
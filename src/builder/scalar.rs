@@ -1,10 +1,13 @@
 //! Scalar builder.
 
-use crate::binary::{BOOL_SIZE, DATA_TYPE_SIZE, MAX_DATA_LENGTH_SIZE, NUMBER_LENGTH_SIZE};
-use crate::builder::BuildResult;
+use crate::binary::{
+    BOOL_SIZE, DATA_TYPE_SIZE, DATE_SIZE, INTERVAL_DT_SIZE, INTERVAL_YM_SIZE, MAX_DATA_LENGTH_SIZE, NUMBER_LENGTH_SIZE,
+    SHORT_DATE_SIZE, TIMESTAMP_SIZE, TIME_SIZE,
+};
+use crate::builder::{BuildResult, TryIntoNumber};
 use crate::vec::VecExt;
 use crate::yason::{Yason, YasonBuf};
-use crate::{DataType, Number};
+use crate::{DataType, Date, DtInterval, ShortDate, Time, Timestamp, YmInterval};
 use decimal_rs::MAX_BINARY_SIZE;
 
 /// Builder for encoding a scalar value.
@@ -26,15 +29,35 @@ impl Scalar {
         let init_len = bytes.len();
         let s = s.as_ref();
         let size = DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + s.len();
-        bytes.try_reserve(size)?;
+        bytes.try_reserve_bytes(size)?;
         bytes.push_data_type(DataType::String);
         bytes.push_string(s)?;
         Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
     }
 
+    /// Encodes a binary value.
+    #[inline]
+    pub fn binary<T: AsRef<[u8]>>(value: T) -> BuildResult<YasonBuf> {
+        let mut bytes = Vec::new();
+        Scalar::binary_with_vec(value, &mut bytes)?;
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Encodes a binary value into the provided vector.
+    #[inline]
+    pub fn binary_with_vec<T: AsRef<[u8]>>(value: T, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+        let init_len = bytes.len();
+        let value = value.as_ref();
+        let size = DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + value.len();
+        bytes.try_reserve_bytes(size)?;
+        bytes.push_data_type(DataType::Binary);
+        bytes.push_binary(value)?;
+        Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
+    }
+
     /// Encodes a number value.
     #[inline]
-    pub fn number<Num: AsRef<Number>>(value: Num) -> BuildResult<YasonBuf> {
+    pub fn number<Num: TryIntoNumber>(value: Num) -> BuildResult<YasonBuf> {
         let mut bytes = Vec::new();
         Scalar::number_with_vec(value, &mut bytes)?;
         Ok(unsafe { YasonBuf::new_unchecked(bytes) })
@@ -42,12 +65,12 @@ impl Scalar {
 
     /// Encodes a number value into the provided vector.
     #[inline]
-    pub fn number_with_vec<Num: AsRef<Number>>(value: Num, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+    pub fn number_with_vec<Num: TryIntoNumber>(value: Num, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
         let init_len = bytes.len();
         let size = DATA_TYPE_SIZE + NUMBER_LENGTH_SIZE + MAX_BINARY_SIZE;
-        bytes.try_reserve(size)?;
+        bytes.try_reserve_bytes(size)?;
         bytes.push_data_type(DataType::Number);
-        bytes.push_number(value.as_ref());
+        bytes.push_number(&value.try_into_number()?);
         Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
     }
 
@@ -64,12 +87,126 @@ impl Scalar {
     pub fn bool_with_vec(value: bool, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
         let init_len = bytes.len();
         let size = DATA_TYPE_SIZE + BOOL_SIZE;
-        bytes.try_reserve(size)?;
+        bytes.try_reserve_bytes(size)?;
         bytes.push_data_type(DataType::Bool);
         bytes.push_u8(value as u8);
         Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
     }
 
+    /// Encodes a timestamp value.
+    #[inline]
+    pub fn timestamp(value: Timestamp) -> BuildResult<YasonBuf> {
+        let mut bytes = Vec::new();
+        Scalar::timestamp_with_vec(value, &mut bytes)?;
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Encodes a timestamp value into the provided vector.
+    #[inline]
+    pub fn timestamp_with_vec(value: Timestamp, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+        let init_len = bytes.len();
+        let size = DATA_TYPE_SIZE + TIMESTAMP_SIZE;
+        bytes.try_reserve_bytes(size)?;
+        bytes.push_data_type(DataType::Timestamp);
+        bytes.push_i64(value.micros());
+        Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
+    }
+
+    /// Encodes a date value.
+    #[inline]
+    pub fn date(value: Date) -> BuildResult<YasonBuf> {
+        let mut bytes = Vec::new();
+        Scalar::date_with_vec(value, &mut bytes)?;
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Encodes a date value into the provided vector.
+    #[inline]
+    pub fn date_with_vec(value: Date, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+        let init_len = bytes.len();
+        let size = DATA_TYPE_SIZE + DATE_SIZE;
+        bytes.try_reserve_bytes(size)?;
+        bytes.push_data_type(DataType::Date);
+        bytes.push_i64(value.micros());
+        Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
+    }
+
+    /// Encodes a short date value.
+    #[inline]
+    pub fn short_date(value: ShortDate) -> BuildResult<YasonBuf> {
+        let mut bytes = Vec::new();
+        Scalar::short_date_with_vec(value, &mut bytes)?;
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Encodes a short date value into the provided vector.
+    #[inline]
+    pub fn short_date_with_vec(value: ShortDate, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+        let init_len = bytes.len();
+        let size = DATA_TYPE_SIZE + SHORT_DATE_SIZE;
+        bytes.try_reserve_bytes(size)?;
+        bytes.push_data_type(DataType::ShortDate);
+        bytes.push_i32(value.days());
+        Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
+    }
+
+    /// Encodes a time value.
+    #[inline]
+    pub fn time(value: Time) -> BuildResult<YasonBuf> {
+        let mut bytes = Vec::new();
+        Scalar::time_with_vec(value, &mut bytes)?;
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Encodes a time value into the provided vector.
+    #[inline]
+    pub fn time_with_vec(value: Time, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+        let init_len = bytes.len();
+        let size = DATA_TYPE_SIZE + TIME_SIZE;
+        bytes.try_reserve_bytes(size)?;
+        bytes.push_data_type(DataType::Time);
+        bytes.push_i64(value.micros());
+        Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
+    }
+
+    /// Encodes an interval year-month value.
+    #[inline]
+    pub fn interval_year_month(value: YmInterval) -> BuildResult<YasonBuf> {
+        let mut bytes = Vec::new();
+        Scalar::interval_year_month_with_vec(value, &mut bytes)?;
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Encodes an interval year-month value into the provided vector.
+    #[inline]
+    pub fn interval_year_month_with_vec(value: YmInterval, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+        let init_len = bytes.len();
+        let size = DATA_TYPE_SIZE + INTERVAL_YM_SIZE;
+        bytes.try_reserve_bytes(size)?;
+        bytes.push_data_type(DataType::IntervalYearMonth);
+        bytes.push_i32(value.months());
+        Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
+    }
+
+    /// Encodes an interval day-time value.
+    #[inline]
+    pub fn interval_day_time(value: DtInterval) -> BuildResult<YasonBuf> {
+        let mut bytes = Vec::new();
+        Scalar::interval_day_time_with_vec(value, &mut bytes)?;
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+
+    /// Encodes an interval day-time value into the provided vector.
+    #[inline]
+    pub fn interval_day_time_with_vec(value: DtInterval, bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
+        let init_len = bytes.len();
+        let size = DATA_TYPE_SIZE + INTERVAL_DT_SIZE;
+        bytes.try_reserve_bytes(size)?;
+        bytes.push_data_type(DataType::IntervalDayTime);
+        bytes.push_i64(value.micros());
+        Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
+    }
+
     /// Encodes a null value.
     #[inline]
     pub fn null() -> BuildResult<YasonBuf> {
@@ -82,7 +219,7 @@ impl Scalar {
     #[inline]
     pub fn null_with_vec(bytes: &mut Vec<u8>) -> BuildResult<&Yason> {
         let init_len = bytes.len();
-        bytes.try_reserve(DATA_TYPE_SIZE)?;
+        bytes.try_reserve_bytes(DATA_TYPE_SIZE)?;
         bytes.push_data_type(DataType::Null);
         Ok(unsafe { Yason::new_unchecked(&bytes[init_len..]) })
     }
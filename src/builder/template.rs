@@ -0,0 +1,234 @@
+//! Document template.
+
+use crate::binary::{
+    ARRAY_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, OBJECT_SIZE, VALUE_ENTRY_SIZE,
+};
+use crate::builder::{finish_object, push_encoded_value, BuildResult, EstimateSize};
+use crate::vec::VecExt;
+use crate::yason::{YasonBuf, YasonResult};
+use crate::{BuildError, DataType, Value, Yason, YasonError};
+use std::ops::Range;
+
+/// A step of filling a template.
+#[derive(Clone, Debug)]
+enum Op {
+    /// Begins an object or an array, whose header is copied from the skeleton with the size and
+    /// the key offsets or value entries left to be written.
+    Begin { data_type: DataType, header: Range<usize> },
+    /// Writes the offset of the key into the key-offset table of the current object at `rank`,
+    /// and copies the key from the skeleton.
+    Key { rank: usize, key: Range<usize> },
+    /// The next value is the element of the current array at `index`.
+    Element { index: usize },
+    /// Encodes the next scalar value.
+    Slot,
+    /// Writes the size of the current object, whose key offsets are narrowed if the object of the
+    /// sample is compact, see [`COMPACT_OBJECT_TYPE`](crate::raw::COMPACT_OBJECT_TYPE).
+    EndObject { element_count: usize, compact: bool },
+    /// Writes the size of the current array.
+    EndArray,
+}
+
+/// A precompiled shape of documents, for building many documents with the same keys and nesting
+/// which only differ in their scalar values.
+///
+/// The template is compiled once from a sample document: the headers of objects and arrays, the
+/// keys and the order of the key-offset tables are computed up front, so that [`fill`] only
+/// encodes the scalars and writes the offsets, without sorting or searching keys. Every scalar of
+/// the sample is a slot, numbered depth-first with the keys of objects in insertion order, see
+/// [`Object::iter_insertion_order`], and the elements of arrays by index. A slot can be filled with
+/// a value of any type.
+///
+/// ```rust
+/// use yason::{DocumentTemplate, Number, Value, YasonBuf};
+///
+/// let sample = YasonBuf::parse(r#"{"id": 0, "ok": true, "tags": ["", ""]}"#).unwrap();
+/// let template = DocumentTemplate::try_new(&sample).unwrap();
+/// assert_eq!(template.slot_count(), 4);
+///
+/// let values = [Value::Number(Number::from(7)), Value::Null, Value::String("a"), Value::String("b")];
+/// let yason = template.fill(&values).unwrap();
+/// assert_eq!(yason.format(false).to_string(), r#"{"id":7,"ok":null,"tags":["a","b"]}"#);
+/// ```
+///
+/// [`fill`]: DocumentTemplate::fill
+/// [`Object::iter_insertion_order`]: crate::Object::iter_insertion_order
+#[derive(Clone, Debug)]
+pub struct DocumentTemplate {
+    skeleton: Vec<u8>,
+    ops: Vec<Op>,
+    slot_count: usize,
+    max_depth: usize,
+}
+
+impl DocumentTemplate {
+    /// Compiles a template with the shape of the sample.
+    #[inline]
+    pub fn try_new(sample: &Yason) -> YasonResult<Self> {
+        let mut template = Self {
+            skeleton: Vec::new(),
+            ops: Vec::new(),
+            slot_count: 0,
+            max_depth: 0,
+        };
+        template.compile(&Value::try_from(sample)?, 0)?;
+        Ok(template)
+    }
+
+    fn compile(&mut self, value: &Value, depth: usize) -> YasonResult<()> {
+        match value {
+            Value::Object(object) => {
+                let order = object.insertion_order()?;
+                let start = self.skeleton.len();
+                let size = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + KEY_OFFSET_SIZE * order.len();
+                self.skeleton.try_reserve(size).map_err(YasonError::TryReserveError)?;
+                self.skeleton.push_data_type(DataType::Object);
+                self.skeleton.skip_size();
+                self.skeleton.push_u16(order.len() as u16);
+                self.skeleton.skip_key_offset(order.len());
+                self.begin(DataType::Object, start..self.skeleton.len(), depth)?;
+
+                let element_count = order.len();
+                for rank in order {
+                    let (key, value) = object.get_nth(rank)?;
+                    let start = self.skeleton.len();
+                    self.skeleton
+                        .try_reserve(KEY_LENGTH_SIZE + key.len())
+                        .map_err(YasonError::TryReserveError)?;
+                    self.skeleton.push_key(key);
+                    self.push_op(Op::Key {
+                        rank,
+                        key: start..self.skeleton.len(),
+                    })?;
+                    self.compile(&value, depth + 1)?;
+                }
+                self.push_op(Op::EndObject {
+                    element_count,
+                    compact: object.is_compact(),
+                })
+            }
+            Value::Array(array) => {
+                let len = array.len()?;
+                let start = self.skeleton.len();
+                let size = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE * len;
+                self.skeleton.try_reserve(size).map_err(YasonError::TryReserveError)?;
+                self.skeleton.push_data_type(DataType::Array);
+                self.skeleton.skip_size();
+                self.skeleton.push_u16(len as u16);
+                self.skeleton.skip_value_entry(len);
+                self.begin(DataType::Array, start..self.skeleton.len(), depth)?;
+
+                for (index, value) in array.iter()?.enumerate() {
+                    self.push_op(Op::Element { index })?;
+                    self.compile(&value?, depth + 1)?;
+                }
+                self.push_op(Op::EndArray)
+            }
+            _ => {
+                self.slot_count += 1;
+                self.push_op(Op::Slot)
+            }
+        }
+    }
+
+    #[inline]
+    fn begin(&mut self, data_type: DataType, header: Range<usize>, depth: usize) -> YasonResult<()> {
+        self.max_depth = self.max_depth.max(depth + 1);
+        self.push_op(Op::Begin { data_type, header })
+    }
+
+    #[inline]
+    fn push_op(&mut self, op: Op) -> YasonResult<()> {
+        self.ops.try_reserve(1).map_err(YasonError::TryReserveError)?;
+        self.ops.push(op);
+        Ok(())
+    }
+
+    /// Returns the number of values needed to fill the template.
+    #[inline]
+    pub fn slot_count(&self) -> usize {
+        self.slot_count
+    }
+
+    /// Builds a document with the shape of the template, whose slots are filled with the values
+    /// in order.
+    ///
+    /// Returns `BuildError::InconsistentSlotCount` if the number of values is not
+    /// [`slot_count`](DocumentTemplate::slot_count).
+    pub fn fill(&self, values: &[Value]) -> BuildResult<YasonBuf> {
+        if values.len() != self.slot_count {
+            return Err(BuildError::InconsistentSlotCount {
+                expected: self.slot_count,
+                actual: values.len(),
+            });
+        }
+
+        let mut size = self.skeleton.len();
+        for value in values {
+            size += value.estimate_size();
+        }
+        let mut bytes: Vec<u8> = VecExt::try_with_capacity(size)?;
+        // the positions after the sizes of the open objects and arrays
+        let mut starts = Vec::new();
+        starts.try_reserve(self.max_depth)?;
+        // the position of the value entry of the next value, if it is an array element
+        let mut entry_pos = None;
+        let mut values = values.iter();
+
+        for op in self.ops.iter() {
+            match op {
+                Op::Begin { data_type, header } => {
+                    if let Some(entry_pos) = entry_pos.take() {
+                        let offset = bytes.len() - starts[starts.len() - 1];
+                        bytes.write_data_type_by_pos(*data_type, entry_pos);
+                        bytes.write_offset(offset as u32, entry_pos + DATA_TYPE_SIZE);
+                    }
+                    starts.push(bytes.len() + DATA_TYPE_SIZE + OBJECT_SIZE);
+                    bytes.push_bytes(&self.skeleton[header.clone()]);
+                }
+                Op::Key { rank, key } => {
+                    let start = starts[starts.len() - 1];
+                    let offset = bytes.len() - start;
+                    bytes.write_offset(offset as u32, start + ELEMENT_COUNT_SIZE + rank * KEY_OFFSET_SIZE);
+                    bytes.push_bytes(&self.skeleton[key.clone()]);
+                }
+                Op::Element { index } => {
+                    entry_pos = Some(starts[starts.len() - 1] + ELEMENT_COUNT_SIZE + index * VALUE_ENTRY_SIZE);
+                }
+                Op::Slot => {
+                    // SAFETY: The number of slots has been checked.
+                    let value = values.next().unwrap();
+                    match entry_pos.take() {
+                        Some(entry_pos) => {
+                            bytes.write_data_type_by_pos(value.data_type(), entry_pos);
+                            match value {
+                                // bool and null are inlined in value entries
+                                Value::Bool(b) => bytes.write_offset(*b as u32, entry_pos + DATA_TYPE_SIZE),
+                                Value::Null => {}
+                                _ => {
+                                    let offset = bytes.len() - starts[starts.len() - 1];
+                                    bytes.write_offset(offset as u32, entry_pos + DATA_TYPE_SIZE);
+                                    push_encoded_value(&mut bytes, value)?;
+                                }
+                            }
+                        }
+                        None => push_encoded_value(&mut bytes, value)?,
+                    }
+                }
+                Op::EndObject { element_count, compact } => {
+                    // SAFETY: Every `EndObject` follows a `Begin`.
+                    let start = starts.pop().unwrap();
+                    finish_object(&mut bytes, start, *element_count, *compact);
+                }
+                Op::EndArray => {
+                    // SAFETY: Every `EndArray` follows a `Begin`.
+                    let start = starts.pop().unwrap();
+                    let total_size = bytes.len() - start;
+                    bytes.write_total_size(total_size as i32, start - OBJECT_SIZE);
+                }
+            }
+        }
+
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+}
@@ -0,0 +1,165 @@
+//! Evaluation of path expressions over batches of documents.
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use yason::{bulk, PathExpression, YasonBuf};
+//!
+//! let docs = [r#"{"a": 1}"#, r#"{"b": 2}"#, r#"{"a": null}"#]
+//!     .iter()
+//!     .map(|json| YasonBuf::parse(json).unwrap())
+//!     .collect::<Vec<_>>();
+//! let path = PathExpression::from_str("$.a").unwrap();
+//! let bitmap = bulk::exists_bitmap(&path, &docs).unwrap();
+//! assert_eq!(bitmap.iter_ones().collect::<Vec<_>>(), vec![0, 2]);
+//! ```
+
+use crate::yason::YasonResult;
+use crate::{PathExpression, StepBudget, Value, Yason};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-size vector of bits, stored in 64-bit words with bit `i` in word `i / 64` at position
+/// `i % 64`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    /// Creates a `BitVec` of `len` bits, all unset.
+    #[inline]
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; (len + WORD_BITS - 1) / WORD_BITS],
+            len,
+        }
+    }
+
+    /// Returns the number of bits.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no bits.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bit at the given index, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.len {
+            return None;
+        }
+        Some(self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0)
+    }
+
+    /// Sets the bit at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[inline]
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(
+            index < self.len,
+            "index out of bounds: the len is {} but the index is {}",
+            self.len,
+            index
+        );
+        let mask = 1 << (index % WORD_BITS);
+        if value {
+            self.words[index / WORD_BITS] |= mask;
+        } else {
+            self.words[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    /// Returns the number of set bits.
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Returns an iterator over the indexes of the set bits, in ascending order.
+    #[inline]
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * WORD_BITS + bit)
+            })
+        })
+    }
+
+    /// Returns the underlying words, the bits after `len` in the last word are unset.
+    #[inline]
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+/// Evaluates [`PathExpression::exists`] for every document and returns a bitmap in which bit `i`
+/// is set if the path matches document `i`.
+///
+/// The path is compiled once and the scratch space of the evaluation is reused for all documents.
+/// With the `rayon` feature, the documents are evaluated in parallel in chunks of 64.
+///
+/// Returns the first error if evaluating a document fails.
+#[inline]
+pub fn exists_bitmap<D>(path: &PathExpression, docs: &[D]) -> YasonResult<BitVec>
+where
+    D: AsRef<Yason> + Sync,
+{
+    path.check_exists()?;
+    let words = exists_words(path, docs)?;
+    Ok(BitVec { words, len: docs.len() })
+}
+
+#[cfg(not(feature = "rayon"))]
+#[inline]
+fn exists_words<D>(path: &PathExpression, docs: &[D]) -> YasonResult<Vec<u64>>
+where
+    D: AsRef<Yason> + Sync,
+{
+    let mut query_buf = Vec::new();
+    docs.chunks(WORD_BITS)
+        .map(|chunk| exists_word(path, chunk, &mut query_buf))
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+#[inline]
+fn exists_words<D>(path: &PathExpression, docs: &[D]) -> YasonResult<Vec<u64>>
+where
+    D: AsRef<Yason> + Sync,
+{
+    use rayon::prelude::*;
+
+    docs.par_chunks(WORD_BITS)
+        .map_init(Vec::new, |query_buf, chunk| exists_word(path, chunk, query_buf))
+        .collect()
+}
+
+#[inline]
+fn exists_word<'a, D: AsRef<Yason>>(
+    path: &PathExpression,
+    chunk: &'a [D],
+    query_buf: &mut Vec<Value<'a>>,
+) -> YasonResult<u64> {
+    let mut word = 0;
+    for (i, doc) in chunk.iter().enumerate() {
+        if path.exists_in(doc.as_ref(), query_buf, &mut StepBudget::unlimited())? {
+            word |= 1 << i;
+        }
+    }
+    Ok(word)
+}
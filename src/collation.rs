@@ -0,0 +1,30 @@
+//! Collation of strings.
+
+use std::cmp::Ordering;
+
+/// Defines the order of strings when comparing or searching yason values.
+///
+/// Keys of objects are always stored in binary order, a collation only affects lookups and
+/// comparisons that accept one.
+pub trait Collation {
+    /// Compares two strings.
+    fn compare(&self, left: &str, right: &str) -> Ordering;
+}
+
+/// The default collation, which compares strings byte by byte.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BinaryCollation;
+
+impl Collation for BinaryCollation {
+    #[inline]
+    fn compare(&self, left: &str, right: &str) -> Ordering {
+        left.cmp(right)
+    }
+}
+
+impl<C: Collation + ?Sized> Collation for &C {
+    #[inline]
+    fn compare(&self, left: &str, right: &str) -> Ordering {
+        (**self).compare(left, right)
+    }
+}
@@ -1,11 +1,17 @@
 //! Data type.
 
+use crate::binary::COMPACT_OBJECT_TYPE;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
 /// Possible yason types.
+///
+/// The discriminants are the type bytes of the yason binary format, so they are stable and can be
+/// used across an FFI boundary as is. The type byte of a compact object,
+/// [`COMPACT_OBJECT_TYPE`](crate::raw::COMPACT_OBJECT_TYPE), is converted to `Object` as well.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum DataType {
     Object = 1,
     Array = 2,
@@ -13,14 +19,33 @@ pub enum DataType {
     Number = 4,
     Bool = 5,
     Null = 6,
+    Binary = 17,
+    Timestamp = 18,
+    Date = 19,
+    ShortDate = 20,
+    Time = 21,
+    IntervalYearMonth = 22,
+    IntervalDayTime = 23,
 }
 
-const DATA_TYPE_NAME: [&str; 7] = ["invalid", "object", "array", "string", "number", "boolean", "null"];
-
 impl DataType {
     #[inline]
     pub const fn name(self) -> &'static str {
-        DATA_TYPE_NAME[self as usize]
+        match self {
+            DataType::Object => "object",
+            DataType::Array => "array",
+            DataType::String => "string",
+            DataType::Number => "number",
+            DataType::Bool => "boolean",
+            DataType::Null => "null",
+            DataType::Binary => "binary",
+            DataType::Timestamp => "timestamp",
+            DataType::Date => "date",
+            DataType::ShortDate => "shortDate",
+            DataType::Time => "time",
+            DataType::IntervalYearMonth => "yearmonthInterval",
+            DataType::IntervalDayTime => "daysecondInterval",
+        }
     }
 }
 
@@ -41,6 +66,13 @@ impl Display for DataType {
             DataType::Number => write!(f, "Number"),
             DataType::Bool => write!(f, "Bool"),
             DataType::Null => write!(f, "Null"),
+            DataType::Binary => write!(f, "Binary"),
+            DataType::Timestamp => write!(f, "Timestamp"),
+            DataType::Date => write!(f, "Date"),
+            DataType::ShortDate => write!(f, "ShortDate"),
+            DataType::Time => write!(f, "Time"),
+            DataType::IntervalYearMonth => write!(f, "IntervalYearMonth"),
+            DataType::IntervalDayTime => write!(f, "IntervalDayTime"),
         }
     }
 }
@@ -62,6 +94,14 @@ impl TryFrom<u8> for DataType {
             4 => Ok(DataType::Number),
             5 => Ok(DataType::Bool),
             6 => Ok(DataType::Null),
+            17 => Ok(DataType::Binary),
+            18 => Ok(DataType::Timestamp),
+            19 => Ok(DataType::Date),
+            20 => Ok(DataType::ShortDate),
+            21 => Ok(DataType::Time),
+            22 => Ok(DataType::IntervalYearMonth),
+            23 => Ok(DataType::IntervalDayTime),
+            COMPACT_OBJECT_TYPE => Ok(DataType::Object),
             v => Err(InvalidDataType(v)),
         }
     }
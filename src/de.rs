@@ -0,0 +1,301 @@
+//! Deserializing selected fields of a yason with serde.
+
+use crate::num::{equals, to_f64, to_i64};
+use crate::yason::{ArrayIter, ObjectIter};
+use crate::{PathExpression, Value, Yason, YasonError};
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use std::error::Error;
+use std::fmt;
+use std::slice;
+
+/// Possible errors that can arise during deserializing.
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[derive(Debug)]
+pub enum DeserializeError {
+    /// The yason could not be read or a path could not be evaluated.
+    Yason(YasonError),
+    /// The selected values do not match the fields, reported by the deserialized type.
+    Custom(String),
+}
+
+impl fmt::Display for DeserializeError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeserializeError::Yason(e) => write!(f, "{}", e),
+            DeserializeError::Custom(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    #[inline]
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeserializeError::Custom(msg.to_string())
+    }
+}
+
+impl From<YasonError> for DeserializeError {
+    #[inline]
+    fn from(e: YasonError) -> Self {
+        DeserializeError::Yason(e)
+    }
+}
+
+/// A precomputed map from the names of struct fields to the path expressions selecting their
+/// values, see [`from_yason_paths`].
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[derive(Debug, Default)]
+pub struct FieldMap {
+    fields: Vec<(String, PathExpression)>,
+}
+
+impl FieldMap {
+    /// Creates an empty map.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a field whose value is selected by the path expression. The path expression must select
+    /// at most one value and must not have an item method or refer to variables.
+    #[inline]
+    pub fn with_field<S: Into<String>>(mut self, name: S, path: PathExpression) -> Self {
+        self.fields.push((name.into(), path));
+        self
+    }
+
+    /// Returns the number of fields.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns true if there is no field.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// Deserializes a struct from the values selected by the path expressions of the fields, e.g. to
+/// hydrate a small struct from a wide document.
+///
+/// Only the selected values are decoded, the rest of the document is skipped by the key lookups
+/// and array offsets of the paths. A field whose path selects nothing is missing, so that an
+/// `Option` field is `None` and a field with `#[serde(default)]` gets its default value.
+///
+/// Returns `DeserializeError::Yason` if a path selects more than one value, and
+/// `DeserializeError::Custom` if a value does not match its field.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use std::str::FromStr;
+/// use yason::{from_yason_paths, FieldMap, PathExpression, YasonBuf};
+///
+/// #[derive(Deserialize)]
+/// struct User<'a> {
+///     name: &'a str,
+///     city: Option<String>,
+///     first_tag: u8,
+/// }
+///
+/// let fields = FieldMap::new()
+///     .with_field("name", PathExpression::from_str("$.user.name").unwrap())
+///     .with_field("city", PathExpression::from_str("$.user.address.city").unwrap())
+///     .with_field("first_tag", PathExpression::from_str("$.tags[0]").unwrap());
+///
+/// let yason = YasonBuf::parse(r#"{"user": {"id": 1, "name": "foo"}, "tags": [3, 4], "bio": "..."}"#).unwrap();
+/// let user: User = from_yason_paths(&yason, &fields).unwrap();
+/// assert_eq!(user.name, "foo");
+/// assert_eq!(user.city, None);
+/// assert_eq!(user.first_tag, 3);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[inline]
+pub fn from_yason_paths<'a, T: de::Deserialize<'a>>(
+    yason: &'a Yason,
+    fields: &FieldMap,
+) -> Result<T, DeserializeError> {
+    T::deserialize(ProjectionDeserializer { yason, fields })
+}
+
+struct ProjectionDeserializer<'a, 'b> {
+    yason: &'a Yason,
+    fields: &'b FieldMap,
+}
+
+impl<'de> de::Deserializer<'de> for ProjectionDeserializer<'de, '_> {
+    type Error = DeserializeError;
+
+    #[inline]
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(ProjectionAccess {
+            yason: self.yason,
+            fields: self.fields.fields.iter(),
+            value: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Yields the fields whose paths select a value.
+struct ProjectionAccess<'a, 'b> {
+    yason: &'a Yason,
+    fields: slice::Iter<'b, (String, PathExpression)>,
+    value: Option<Value<'a>>,
+}
+
+impl<'de> de::MapAccess<'de> for ProjectionAccess<'de, '_> {
+    type Error = DeserializeError;
+
+    #[inline]
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        for (name, path) in self.fields.by_ref() {
+            if let Some(value) = path.get(self.yason)? {
+                self.value = Some(value);
+                return seed.deserialize(name.as_str().into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    #[inline]
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Deserializes a selected value.
+struct ValueDeserializer<'a>(Value<'a>);
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Object(object) => visitor.visit_map(ObjectAccess {
+                iter: object.iter()?,
+                value: None,
+            }),
+            Value::Array(array) => visitor.visit_seq(ArrayAccess { iter: array.iter()? }),
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Number(number) => {
+                if let Some(value) = to_i64(&number) {
+                    return visitor.visit_i64(value);
+                }
+                if equals(&number.round(0), &number) {
+                    if let Ok(value) = u64::try_from(&number) {
+                        return visitor.visit_u64(value);
+                    }
+                }
+                visitor.visit_f64(to_f64(&number))
+            }
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Null => visitor.visit_unit(),
+            Value::Binary(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Value::Timestamp(value) => visitor.visit_string(value.to_string()),
+            Value::Date(value) => visitor.visit_string(value.to_string()),
+            Value::ShortDate(value) => visitor.visit_string(value.to_string()),
+            Value::Time(value) => visitor.visit_string(value.to_string()),
+            Value::IntervalYearMonth(value) => visitor.visit_string(value.to_string()),
+            Value::IntervalDayTime(value) => visitor.visit_string(value.to_string()),
+        }
+    }
+
+    #[inline]
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            // unit variants are encoded as strings
+            Value::String(s) => visitor.visit_enum(BorrowedStrDeserializer::new(s)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct ObjectAccess<'a> {
+    iter: ObjectIter<'a>,
+    value: Option<Value<'a>>,
+}
+
+impl<'de> de::MapAccess<'de> for ObjectAccess<'de> {
+    type Error = DeserializeError;
+
+    #[inline]
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        match self.iter.next() {
+            Some(entry) => {
+                let (key, value) = entry?;
+                self.value = Some(value);
+                seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+}
+
+struct ArrayAccess<'a> {
+    iter: ArrayIter<'a>,
+}
+
+impl<'de> de::SeqAccess<'de> for ArrayAccess<'de> {
+    type Error = DeserializeError;
+
+    #[inline]
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(ValueDeserializer(value?)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Stable error codes.
+
+use crate::analyze::SchemaError;
+use crate::frame::FrameError;
+use crate::ndjson::NdjsonError;
+use crate::{BuildError, FormatError, NumberError, YasonError};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Stable codes of the errors of this crate, so that FFI consumers can match errors without
+/// parsing messages.
+///
+/// The discriminant of a code never changes once released and new codes are only appended, so the
+/// values can be hardcoded on the other side of the FFI boundary. The codes are grouped by the
+/// hundred: the 0s are shared by all errors, the 100s are accessing errors, the 200s building
+/// errors, the 300s formatting errors, the 400s path parsing errors and the 500s framing errors.
+///
+/// ```rust
+/// use yason::{ErrorCode, YasonBuf};
+///
+/// let err = YasonBuf::parse(r#"{"a": "#).unwrap_err();
+/// assert_eq!(err.code(), ErrorCode::InvalidJson);
+/// assert_eq!(i32::from(err.code()), 203);
+/// assert_eq!(ErrorCode::try_from(203).unwrap(), ErrorCode::InvalidJson);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[repr(C)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    OutOfMemory = 1,
+    Io = 2,
+
+    IndexOutOfBounds = 100,
+    UnexpectedType = 101,
+    InvalidDataType = 102,
+    MultiValuesWithoutWrapper = 103,
+    InvalidPathExpression = 104,
+    EmptyQueryResult = 105,
+    PathTypeMismatch = 106,
+    Cancelled = 107,
+    InvalidFormat = 108,
+    ChecksumMismatch = 109,
+    InvalidInteger = 110,
+    UnboundVariable = 111,
+    InvalidVariable = 112,
+    DuplicateKey = 113,
+    KeyTooLong = 114,
+    ItemMethodNotSupported = 115,
+
+    InnerUncompleted = 200,
+    InconsistentElementCount = 201,
+    StringTooLong = 202,
+    InvalidJson = 203,
+    NumberOverflow = 204,
+    NumberFormat = 205,
+    NestedTooDeeply = 206,
+    TooManyElements = 207,
+    NumberOutOfRange = 208,
+    InconsistentSlotCount = 209,
+    UnsortedKeys = 210,
+    DuplicateValue = 211,
+    NumberNotFinite = 212,
+    InvalidKeyOffset = 213,
+    InvalidYason = 214,
+
+    Fmt = 300,
+    UnsupportedBinary = 301,
+
+    PathNotStartWithDollar = 400,
+    PathMissingSquareBracket = 401,
+    PathArrayStepSyntax = 402,
+    PathArrayIndexTooLong = 403,
+    PathInvalidEscapeSequence = 404,
+    PathUnclosedQuotedStep = 405,
+    PathInvalidKeyStep = 406,
+    PathInvalidFunction = 407,
+    PathUnexpectedCharacterAtEnd = 408,
+    PathInvalidCharacterAtStepStart = 409,
+    PathEmptyArrayStep = 410,
+    PathInvalidVariableName = 411,
+
+    FrameTruncated = 500,
+    FrameTooLarge = 501,
+}
+
+impl From<ErrorCode> for i32 {
+    #[inline]
+    fn from(code: ErrorCode) -> Self {
+        code as i32
+    }
+}
+
+/// Invalid error code.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct InvalidErrorCode(i32);
+
+impl TryFrom<i32> for ErrorCode {
+    type Error = InvalidErrorCode;
+
+    #[inline]
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ErrorCode::OutOfMemory),
+            2 => Ok(ErrorCode::Io),
+            100 => Ok(ErrorCode::IndexOutOfBounds),
+            101 => Ok(ErrorCode::UnexpectedType),
+            102 => Ok(ErrorCode::InvalidDataType),
+            103 => Ok(ErrorCode::MultiValuesWithoutWrapper),
+            104 => Ok(ErrorCode::InvalidPathExpression),
+            105 => Ok(ErrorCode::EmptyQueryResult),
+            106 => Ok(ErrorCode::PathTypeMismatch),
+            107 => Ok(ErrorCode::Cancelled),
+            108 => Ok(ErrorCode::InvalidFormat),
+            109 => Ok(ErrorCode::ChecksumMismatch),
+            110 => Ok(ErrorCode::InvalidInteger),
+            111 => Ok(ErrorCode::UnboundVariable),
+            112 => Ok(ErrorCode::InvalidVariable),
+            113 => Ok(ErrorCode::DuplicateKey),
+            114 => Ok(ErrorCode::KeyTooLong),
+            115 => Ok(ErrorCode::ItemMethodNotSupported),
+            200 => Ok(ErrorCode::InnerUncompleted),
+            201 => Ok(ErrorCode::InconsistentElementCount),
+            202 => Ok(ErrorCode::StringTooLong),
+            203 => Ok(ErrorCode::InvalidJson),
+            204 => Ok(ErrorCode::NumberOverflow),
+            205 => Ok(ErrorCode::NumberFormat),
+            206 => Ok(ErrorCode::NestedTooDeeply),
+            207 => Ok(ErrorCode::TooManyElements),
+            208 => Ok(ErrorCode::NumberOutOfRange),
+            209 => Ok(ErrorCode::InconsistentSlotCount),
+            210 => Ok(ErrorCode::UnsortedKeys),
+            211 => Ok(ErrorCode::DuplicateValue),
+            212 => Ok(ErrorCode::NumberNotFinite),
+            213 => Ok(ErrorCode::InvalidKeyOffset),
+            214 => Ok(ErrorCode::InvalidYason),
+            300 => Ok(ErrorCode::Fmt),
+            301 => Ok(ErrorCode::UnsupportedBinary),
+            400 => Ok(ErrorCode::PathNotStartWithDollar),
+            401 => Ok(ErrorCode::PathMissingSquareBracket),
+            402 => Ok(ErrorCode::PathArrayStepSyntax),
+            403 => Ok(ErrorCode::PathArrayIndexTooLong),
+            404 => Ok(ErrorCode::PathInvalidEscapeSequence),
+            405 => Ok(ErrorCode::PathUnclosedQuotedStep),
+            406 => Ok(ErrorCode::PathInvalidKeyStep),
+            407 => Ok(ErrorCode::PathInvalidFunction),
+            408 => Ok(ErrorCode::PathUnexpectedCharacterAtEnd),
+            409 => Ok(ErrorCode::PathInvalidCharacterAtStepStart),
+            410 => Ok(ErrorCode::PathEmptyArrayStep),
+            411 => Ok(ErrorCode::PathInvalidVariableName),
+            500 => Ok(ErrorCode::FrameTruncated),
+            501 => Ok(ErrorCode::FrameTooLarge),
+            v => Err(InvalidErrorCode(v)),
+        }
+    }
+}
+
+impl Display for InvalidErrorCode {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "invalid error code '{}'", self.0)
+    }
+}
+
+impl Error for InvalidErrorCode {}
+
+impl YasonError {
+    /// Returns the stable code of the error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            YasonError::IndexOutOfBounds { .. } => ErrorCode::IndexOutOfBounds,
+            YasonError::UnexpectedType { .. } => ErrorCode::UnexpectedType,
+            YasonError::InvalidDataType(_) => ErrorCode::InvalidDataType,
+            YasonError::MultiValuesWithoutWrapper => ErrorCode::MultiValuesWithoutWrapper,
+            YasonError::TryReserveError(_) => ErrorCode::OutOfMemory,
+            YasonError::InvalidPathExpression => ErrorCode::InvalidPathExpression,
+            YasonError::EmptyQueryResult => ErrorCode::EmptyQueryResult,
+            YasonError::PathTypeMismatch => ErrorCode::PathTypeMismatch,
+            YasonError::Cancelled => ErrorCode::Cancelled,
+            YasonError::InvalidFormat(_) => ErrorCode::InvalidFormat,
+            YasonError::ChecksumMismatch { .. } => ErrorCode::ChecksumMismatch,
+            YasonError::InvalidInteger(_) => ErrorCode::InvalidInteger,
+            YasonError::UnboundVariable(_) => ErrorCode::UnboundVariable,
+            YasonError::InvalidVariable(_) => ErrorCode::InvalidVariable,
+            YasonError::DuplicateKey(_) => ErrorCode::DuplicateKey,
+            YasonError::KeyTooLong(_) => ErrorCode::KeyTooLong,
+            YasonError::ItemMethodNotSupported => ErrorCode::ItemMethodNotSupported,
+        }
+    }
+}
+
+impl BuildError {
+    /// Returns the stable code of the error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            BuildError::TryReserveError(_) => ErrorCode::OutOfMemory,
+            BuildError::InnerUncompletedError => ErrorCode::InnerUncompleted,
+            BuildError::InconsistentElementCount { .. } => ErrorCode::InconsistentElementCount,
+            BuildError::StringTooLong(_) => ErrorCode::StringTooLong,
+            BuildError::JsonError(_) => ErrorCode::InvalidJson,
+            BuildError::NumberError(NumberError::Overflow) => ErrorCode::NumberOverflow,
+            BuildError::NumberError(NumberError::FormatError) => ErrorCode::NumberFormat,
+            BuildError::NumberError(NumberError::NotFinite) => ErrorCode::NumberNotFinite,
+            BuildError::NestedTooDeeply => ErrorCode::NestedTooDeeply,
+            BuildError::TooManyElements(_) => ErrorCode::TooManyElements,
+            BuildError::NumberOutOfRange { .. } => ErrorCode::NumberOutOfRange,
+            BuildError::InconsistentSlotCount { .. } => ErrorCode::InconsistentSlotCount,
+            BuildError::UnsortedKeys => ErrorCode::UnsortedKeys,
+            BuildError::DuplicateValue => ErrorCode::DuplicateValue,
+            BuildError::InvalidKeyOffset => ErrorCode::InvalidKeyOffset,
+            BuildError::InvalidYason(_) => ErrorCode::InvalidYason,
+        }
+    }
+}
+
+impl FormatError {
+    /// Returns the stable code of the error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FormatError::FmtError(_) => ErrorCode::Fmt,
+            FormatError::NumberFormatError(_) => ErrorCode::NumberFormat,
+            FormatError::YasonError(e) => e.code(),
+            FormatError::UnsupportedBinary => ErrorCode::UnsupportedBinary,
+        }
+    }
+}
+
+impl FrameError {
+    /// Returns the stable code of the error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FrameError::IoError(_) => ErrorCode::Io,
+            FrameError::YasonError(e) => e.code(),
+            FrameError::Truncated => ErrorCode::FrameTruncated,
+            FrameError::TooLarge(_) => ErrorCode::FrameTooLarge,
+        }
+    }
+}
+
+impl NdjsonError {
+    /// Returns the stable code of the error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            NdjsonError::IoError(_) => ErrorCode::Io,
+            NdjsonError::BuildError { error, .. } => error.code(),
+            NdjsonError::FormatError(e) => e.code(),
+        }
+    }
+}
+
+impl SchemaError {
+    /// Returns the stable code of the error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SchemaError::YasonError(e) => e.code(),
+            SchemaError::BuildError(e) => e.code(),
+        }
+    }
+}
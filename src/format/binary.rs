@@ -0,0 +1,90 @@
+//! BinaryFormatter
+
+use crate::format::{BinaryFormat, EscapeProfile, FormatError, FormatResult, Formatter};
+use std::fmt;
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Formatter which writes binary values with the given format.
+pub struct BinaryFormatter<F: Formatter> {
+    inner: F,
+    format: BinaryFormat,
+}
+
+impl<F: Formatter> BinaryFormatter<F> {
+    #[inline]
+    pub(crate) const fn new(inner: F, format: BinaryFormat) -> Self {
+        Self { inner, format }
+    }
+}
+
+impl<F: Formatter> Formatter for BinaryFormatter<F> {
+    #[inline]
+    fn begin_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.inner.begin_value(writer)
+    }
+
+    forward_hooks!(inner);
+
+    #[inline]
+    fn escape_profile(&self) -> EscapeProfile {
+        self.inner.escape_profile()
+    }
+
+    #[inline]
+    fn binary_format(&self) -> BinaryFormat {
+        self.format
+    }
+}
+
+/// Writes the bytes encoded by the given format, without the surrounding quotes.
+pub(crate) fn format_encoded_binary<W: fmt::Write>(
+    value: &[u8],
+    format: BinaryFormat,
+    writer: &mut W,
+) -> FormatResult<()> {
+    match format {
+        BinaryFormat::Base64 => write_base64(value, writer),
+        BinaryFormat::Hex => write_hex(value, writer),
+        BinaryFormat::Error => Err(FormatError::UnsupportedBinary),
+    }
+}
+
+#[inline]
+fn write_base64<W: fmt::Write>(value: &[u8], writer: &mut W) -> FormatResult<()> {
+    // 48 bytes are encoded into 64 chars at a time
+    let mut buf = [0u8; 64];
+    for chunk in value.chunks(48) {
+        let mut len = 0;
+        for group in chunk.chunks(3) {
+            let b0 = group[0] as usize;
+            let b1 = group.get(1).copied().unwrap_or(0) as usize;
+            let b2 = group.get(2).copied().unwrap_or(0) as usize;
+            buf[len] = BASE64_CHARS[b0 >> 2];
+            buf[len + 1] = BASE64_CHARS[((b0 & 0x03) << 4) | (b1 >> 4)];
+            buf[len + 2] = if group.len() > 1 {
+                BASE64_CHARS[((b1 & 0x0f) << 2) | (b2 >> 6)]
+            } else {
+                b'='
+            };
+            buf[len + 3] = if group.len() > 2 { BASE64_CHARS[b2 & 0x3f] } else { b'=' };
+            len += 4;
+        }
+        writer.write_str(unsafe { std::str::from_utf8_unchecked(&buf[..len]) })?;
+    }
+    Ok(())
+}
+
+#[inline]
+fn write_hex<W: fmt::Write>(value: &[u8], writer: &mut W) -> FormatResult<()> {
+    let mut buf = [0u8; 64];
+    for chunk in value.chunks(32) {
+        for (i, byte) in chunk.iter().enumerate() {
+            buf[i * 2] = HEX_CHARS[(byte >> 4) as usize];
+            buf[i * 2 + 1] = HEX_CHARS[(byte & 0x0f) as usize];
+        }
+        writer.write_str(unsafe { std::str::from_utf8_unchecked(&buf[..chunk.len() * 2]) })?;
+    }
+    Ok(())
+}
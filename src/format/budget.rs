@@ -0,0 +1,38 @@
+//! BudgetFormatter
+
+use crate::format::{BinaryFormat, EscapeProfile, FormatResult, Formatter};
+use crate::StepBudget;
+use std::fmt;
+
+/// Formatter which aborts with `YasonError::Cancelled` once the written values exceed the budget.
+pub struct BudgetFormatter<'b, F: Formatter> {
+    inner: F,
+    budget: &'b mut StepBudget,
+}
+
+impl<'b, F: Formatter> BudgetFormatter<'b, F> {
+    #[inline]
+    pub(crate) fn new(inner: F, budget: &'b mut StepBudget) -> Self {
+        Self { inner, budget }
+    }
+}
+
+impl<F: Formatter> Formatter for BudgetFormatter<'_, F> {
+    #[inline]
+    fn begin_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.budget.step()?;
+        self.inner.begin_value(writer)
+    }
+
+    #[inline]
+    fn escape_profile(&self) -> EscapeProfile {
+        self.inner.escape_profile()
+    }
+
+    #[inline]
+    fn binary_format(&self) -> BinaryFormat {
+        self.inner.binary_format()
+    }
+
+    forward_hooks!(inner);
+}
@@ -0,0 +1,36 @@
+//! EscapeFormatter
+
+use crate::format::{BinaryFormat, EscapeProfile, FormatResult, Formatter};
+use std::fmt;
+
+/// Formatter which escapes strings with the given profile.
+pub struct EscapeFormatter<F: Formatter> {
+    inner: F,
+    profile: EscapeProfile,
+}
+
+impl<F: Formatter> EscapeFormatter<F> {
+    #[inline]
+    pub(crate) const fn new(inner: F, profile: EscapeProfile) -> Self {
+        Self { inner, profile }
+    }
+}
+
+impl<F: Formatter> Formatter for EscapeFormatter<F> {
+    #[inline]
+    fn begin_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.inner.begin_value(writer)
+    }
+
+    forward_hooks!(inner);
+
+    #[inline]
+    fn escape_profile(&self) -> EscapeProfile {
+        self.profile
+    }
+
+    #[inline]
+    fn binary_format(&self) -> BinaryFormat {
+        self.inner.binary_format()
+    }
+}
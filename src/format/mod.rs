@@ -1,21 +1,114 @@
 //! Formatter.
 
 use crate::yason::LazyValue;
-use crate::{Array, DataType, Number, Object, Value, Yason, YasonError};
+use crate::{
+    Array, DataType, Date, DtInterval, Number, Object, ShortDate, Time, Timestamp, Value, Yason, YasonError, YmInterval,
+};
+use binary::format_encoded_binary;
+pub use binary::BinaryFormatter;
+pub use budget::BudgetFormatter;
 use decimal_rs::DecimalFormatError;
+pub use escape::EscapeFormatter;
+pub use ordered::InsertionOrderFormatter;
 pub use pretty::PrettyFormatter;
+use std::cell::Cell;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Display;
+pub use truncate::{TruncateOptions, TruncatedFormatter};
+
+/// Implements the begin/end hooks of `Formatter` by forwarding them to the wrapped formatter, and
+/// `write_number`, so that the scratch buffer of the wrapped formatter is used.
+macro_rules! forward_hooks {
+    ($inner: ident) => {
+        #[inline]
+        fn begin_string<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.begin_string(writer)
+        }
+
+        #[inline]
+        fn end_string<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.end_string(writer)
+        }
+
+        #[inline]
+        fn begin_array<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.begin_array(writer)
+        }
+
+        #[inline]
+        fn end_array<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.end_array(writer)
+        }
+
+        #[inline]
+        fn begin_array_value<W: fmt::Write>(&mut self, first: bool, writer: &mut W) -> FormatResult<()> {
+            self.$inner.begin_array_value(first, writer)
+        }
+
+        #[inline]
+        fn end_array_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.end_array_value(writer)
+        }
+
+        #[inline]
+        fn begin_object<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.begin_object(writer)
+        }
+
+        #[inline]
+        fn end_object<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.end_object(writer)
+        }
+
+        #[inline]
+        fn begin_object_key<W: fmt::Write>(&mut self, first: bool, writer: &mut W) -> FormatResult<()> {
+            self.$inner.begin_object_key(first, writer)
+        }
+
+        #[inline]
+        fn end_object_key<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.end_object_key(writer)
+        }
+
+        #[inline]
+        fn begin_object_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.begin_object_value(writer)
+        }
+
+        #[inline]
+        fn end_object_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.end_object_value(writer)
+        }
+
+        #[inline]
+        fn begin_nested_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+            self.$inner.begin_nested_value(writer)
+        }
 
+        #[inline]
+        fn write_number<W: fmt::Write>(&mut self, value: &crate::Number, writer: &mut W) -> FormatResult<()> {
+            self.$inner.write_number(value, writer)
+        }
+    };
+}
+
+mod binary;
+mod budget;
+mod escape;
+mod ordered;
 mod pretty;
+mod truncate;
 
 /// Possible errors that can arise during formatting.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum FormatError {
     FmtError(fmt::Error),
     NumberFormatError(DecimalFormatError),
     YasonError(YasonError),
+    /// A binary value is formatted with `BinaryFormat::Error`.
+    UnsupportedBinary,
 }
 
 impl Display for FormatError {
@@ -25,6 +118,7 @@ impl Display for FormatError {
             FormatError::FmtError(e) => write!(f, "{}", e),
             FormatError::NumberFormatError(e) => write!(f, "{}", e),
             FormatError::YasonError(e) => write!(f, "{}", e),
+            FormatError::UnsupportedBinary => write!(f, "binary value cannot be formatted as json"),
         }
     }
 }
@@ -47,19 +141,49 @@ impl From<YasonError> for FormatError {
     }
 }
 
+mod private {
+    /// A token taken by the internal methods of [`Formatter`](super::Formatter). It cannot be named
+    /// outside of the crate, so those methods can be neither called nor overridden by other crates.
+    #[derive(Clone, Copy)]
+    pub struct Sealed;
+}
+
+pub(crate) use private::Sealed;
+
+/// Renders a yason to text.
+///
+/// The default methods write compact JSON. A formatter for another textual format overrides the
+/// scalar writers and the begin/end hooks, which are called in document order while the yason is
+/// walked, see [`CompactFormatter`] and [`PrettyFormatter`].
+///
+/// Methods taking a lazily decoded value are internal and hidden from the documentation. They take
+/// a token which cannot be named outside of this crate, so they cannot be called or overridden.
 pub trait Formatter {
+    /// Formats the yason to the writer.
     #[inline]
     fn format<W: fmt::Write>(&mut self, yason: &Yason, writer: &mut W) -> FormatResult<()> {
         let lazy_value = LazyValue::try_from(yason)?;
-        self.write_lazy_value(&lazy_value, writer)
+        #[cfg(feature = "metrics")]
+        let writer = &mut crate::metrics::CountingWriter::new(writer);
+        self.write_lazy_value(&lazy_value, writer, Sealed)
     }
 
+    /// Formats the yason by appending the UTF-8 text to the bytes, without a `String` in between.
+    /// The bytes written before an error are kept.
+    #[inline]
+    fn format_to_vec(&mut self, yason: &Yason, bytes: &mut Vec<u8>) -> FormatResult<()> {
+        self.format(yason, &mut VecWriter(bytes))
+    }
+
+    #[doc(hidden)]
     #[inline]
     fn write_lazy_value<W: fmt::Write, const IN_ARRAY: bool>(
         &mut self,
         value: &LazyValue<IN_ARRAY>,
         writer: &mut W,
+        _: Sealed,
     ) -> FormatResult<()> {
+        self.begin_value(writer)?;
         match value.data_type() {
             DataType::Object => {
                 let object = unsafe { value.object()? };
@@ -82,6 +206,16 @@ pub trait Formatter {
                 self.write_bool(bool, writer)
             }
             DataType::Null => self.write_null(writer),
+            DataType::Binary => {
+                let binary = unsafe { value.binary()? };
+                self.write_binary(binary, writer)
+            }
+            DataType::Timestamp
+            | DataType::Date
+            | DataType::ShortDate
+            | DataType::Time
+            | DataType::IntervalYearMonth
+            | DataType::IntervalDayTime => self.write_temporal(&value.value()?, writer, Sealed),
         }
     }
 
@@ -98,18 +232,112 @@ pub trait Formatter {
         Ok(())
     }
 
+    /// Writes a number, integers without a fractional part and within the range of `i64` are
+    /// written by [`write_i64`], the others are formatted by `Decimal`.
+    ///
+    /// [`write_i64`]: Formatter::write_i64
     #[inline]
     fn write_number<W: fmt::Write>(&mut self, value: &Number, writer: &mut W) -> FormatResult<()> {
-        value.format_to_json(writer).map_err(FormatError::NumberFormatError)
+        match as_i64(value) {
+            Some(value) => self.write_i64(value, writer),
+            None => value.format_to_json(writer).map_err(FormatError::NumberFormatError),
+        }
+    }
+
+    /// Writes a 64-bit signed integer, with `itoa` if the optional dependency is enabled.
+    #[inline]
+    fn write_i64<W: fmt::Write>(&mut self, value: i64, writer: &mut W) -> FormatResult<()> {
+        #[cfg(feature = "itoa")]
+        writer.write_str(itoa::Buffer::new().format(value))?;
+        #[cfg(not(feature = "itoa"))]
+        write!(writer, "{}", value)?;
+        Ok(())
     }
 
     #[inline]
     fn write_string<W: fmt::Write>(&mut self, value: &str, writer: &mut W) -> FormatResult<()> {
         self.begin_string(writer)?;
-        format_escaped_str(value, writer)?;
+        format_escaped_str_with(value, self.escape_profile(), writer)?;
+        self.end_string(writer)
+    }
+
+    /// Writes a binary value as a string encoded by [`binary_format`], or fails with
+    /// `FormatError::UnsupportedBinary` if the format is `BinaryFormat::Error`.
+    ///
+    /// [`binary_format`]: Formatter::binary_format
+    #[inline]
+    fn write_binary<W: fmt::Write>(&mut self, value: &[u8], writer: &mut W) -> FormatResult<()> {
+        let format = self.binary_format();
+        if format == BinaryFormat::Error {
+            return Err(FormatError::UnsupportedBinary);
+        }
+        self.begin_string(writer)?;
+        format_encoded_binary(value, format, writer)?;
+        self.end_string(writer)
+    }
+
+    /// Writes a timestamp value as an ISO-8601 string, e.g. `"2024-02-29T13:45:30.5"`.
+    #[inline]
+    fn write_timestamp<W: fmt::Write>(&mut self, value: Timestamp, writer: &mut W) -> FormatResult<()> {
+        self.begin_string(writer)?;
+        write!(writer, "{}", value)?;
+        self.end_string(writer)
+    }
+
+    /// Writes a date value as an ISO-8601 string, e.g. `"2024-02-29T13:45:30"`.
+    #[inline]
+    fn write_date<W: fmt::Write>(&mut self, value: Date, writer: &mut W) -> FormatResult<()> {
+        self.begin_string(writer)?;
+        write!(writer, "{}", value)?;
         self.end_string(writer)
     }
 
+    /// Writes a short date value as an ISO-8601 string, e.g. `"2024-02-29"`.
+    #[inline]
+    fn write_short_date<W: fmt::Write>(&mut self, value: ShortDate, writer: &mut W) -> FormatResult<()> {
+        self.begin_string(writer)?;
+        write!(writer, "{}", value)?;
+        self.end_string(writer)
+    }
+
+    /// Writes a time value as an ISO-8601 string, e.g. `"13:45:30.5"`.
+    #[inline]
+    fn write_time<W: fmt::Write>(&mut self, value: Time, writer: &mut W) -> FormatResult<()> {
+        self.begin_string(writer)?;
+        write!(writer, "{}", value)?;
+        self.end_string(writer)
+    }
+
+    /// Writes an interval year-month value as an ISO-8601 duration string, e.g. `"P1Y2M"`.
+    #[inline]
+    fn write_interval_year_month<W: fmt::Write>(&mut self, value: YmInterval, writer: &mut W) -> FormatResult<()> {
+        self.begin_string(writer)?;
+        write!(writer, "{}", value)?;
+        self.end_string(writer)
+    }
+
+    /// Writes an interval day-time value as an ISO-8601 duration string, e.g. `"P1DT2H3M4.5S"`.
+    #[inline]
+    fn write_interval_day_time<W: fmt::Write>(&mut self, value: DtInterval, writer: &mut W) -> FormatResult<()> {
+        self.begin_string(writer)?;
+        write!(writer, "{}", value)?;
+        self.end_string(writer)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn write_temporal<W: fmt::Write>(&mut self, value: &Value, writer: &mut W, _: Sealed) -> FormatResult<()> {
+        match value {
+            Value::Timestamp(timestamp) => self.write_timestamp(*timestamp, writer),
+            Value::Date(date) => self.write_date(*date, writer),
+            Value::ShortDate(short_date) => self.write_short_date(*short_date, writer),
+            Value::Time(time) => self.write_time(*time, writer),
+            Value::IntervalYearMonth(interval) => self.write_interval_year_month(*interval, writer),
+            Value::IntervalDayTime(interval) => self.write_interval_day_time(*interval, writer),
+            _ => unreachable!(),
+        }
+    }
+
     #[inline]
     fn write_object<W: fmt::Write>(&mut self, value: &Object, writer: &mut W) -> FormatResult<()> {
         self.begin_object(writer)?;
@@ -117,16 +345,17 @@ pub trait Formatter {
         let mut iter = value.lazy_iter()?;
         if let Some(entry) = iter.next() {
             let (key, value) = entry?;
-            self.write_object_value(key, &value, true, writer)?;
+            self.write_object_value(key, &value, true, writer, Sealed)?;
         }
         for entry in iter {
             let (key, value) = entry?;
-            self.write_object_value(key, &value, false, writer)?;
+            self.write_object_value(key, &value, false, writer, Sealed)?;
         }
 
         self.end_object(writer)
     }
 
+    #[doc(hidden)]
     #[inline]
     fn write_object_value<W: fmt::Write, const IN_ARRAY: bool>(
         &mut self,
@@ -134,12 +363,16 @@ pub trait Formatter {
         value: &LazyValue<IN_ARRAY>,
         first: bool,
         writer: &mut W,
+        _: Sealed,
     ) -> FormatResult<()> {
         self.begin_object_key(first, writer)?;
         self.write_string(key, writer)?;
         self.end_object_key(writer)?;
         self.begin_object_value(writer)?;
-        self.write_lazy_value(value, writer)?;
+        if matches!(value.data_type(), DataType::Object | DataType::Array) {
+            self.begin_nested_value(writer)?;
+        }
+        self.write_lazy_value(value, writer, Sealed)?;
         self.end_object_value(writer)
     }
 
@@ -149,24 +382,26 @@ pub trait Formatter {
 
         let mut iter = value.lazy_iter()?;
         if let Some(val) = iter.next() {
-            self.write_array_value(&val?, true, writer)?;
+            self.write_array_value(&val?, true, writer, Sealed)?;
         }
         for val in iter {
-            self.write_array_value(&val?, false, writer)?;
+            self.write_array_value(&val?, false, writer, Sealed)?;
         }
 
         self.end_array(writer)
     }
 
+    #[doc(hidden)]
     #[inline]
     fn write_array_value<W: fmt::Write, const IN_ARRAY: bool>(
         &mut self,
         value: &LazyValue<IN_ARRAY>,
         first: bool,
         writer: &mut W,
+        _: Sealed,
     ) -> FormatResult<()> {
         self.begin_array_value(first, writer)?;
-        self.write_lazy_value(value, writer)?;
+        self.write_lazy_value(value, writer, Sealed)?;
         self.end_array_value(writer)
     }
 
@@ -243,76 +478,266 @@ pub trait Formatter {
         Ok(())
     }
 
+    /// Called after `begin_object_value` if the value is an object or an array.
     #[inline]
-    unsafe fn write_values<W: fmt::Write>(&mut self, values: &[Value], writer: &mut W) -> FormatResult<()> {
-        debug_assert!(!values.is_empty());
-        self.begin_array(writer)?;
+    fn begin_nested_value<W: fmt::Write>(&mut self, _writer: &mut W) -> FormatResult<()> {
+        Ok(())
+    }
 
-        self.write_value(&values[0], true, writer)?;
+    /// Called before a value in the yason is written.
+    #[inline]
+    fn begin_value<W: fmt::Write>(&mut self, _writer: &mut W) -> FormatResult<()> {
+        Ok(())
+    }
 
-        for val in values.iter().skip(1) {
-            self.write_value(val, false, writer)?;
-        }
+    /// Returns the profile used to escape strings.
+    #[inline]
+    fn escape_profile(&self) -> EscapeProfile {
+        EscapeProfile::JsonSafe
+    }
 
-        self.end_array(writer)
+    /// Returns the format used to write binary values.
+    #[inline]
+    fn binary_format(&self) -> BinaryFormat {
+        BinaryFormat::Base64
     }
 
+    /// Formats the values as an array.
     #[inline]
-    fn write_value<W: fmt::Write>(&mut self, value: &Value, first: bool, writer: &mut W) -> FormatResult<()> {
-        self.begin_array_value(first, writer)?;
+    fn write_values<W: fmt::Write>(&mut self, values: &[Value], writer: &mut W) -> FormatResult<()> {
+        self.begin_array(writer)?;
+        for (i, val) in values.iter().enumerate() {
+            self.begin_array_value(i == 0, writer)?;
+            self.write_value(val, writer)?;
+            self.end_array_value(writer)?;
+        }
+        self.end_array(writer)
+    }
 
+    /// Formats a single value.
+    #[inline]
+    fn write_value<W: fmt::Write>(&mut self, value: &Value, writer: &mut W) -> FormatResult<()> {
         match value {
-            Value::Object(object) => {
-                let lazy_value = LazyValue::try_from(object.yason())?;
-                self.write_lazy_value(&lazy_value, writer)
+            Value::Object(object) => self.format(object.yason(), writer),
+            Value::Array(array) => self.format(array.yason(), writer),
+            Value::String(string) => {
+                self.begin_value(writer)?;
+                self.write_string(string, writer)
             }
-            Value::Array(array) => {
-                let lazy_value = LazyValue::try_from(array.yason())?;
-                self.write_lazy_value(&lazy_value, writer)
+            Value::Number(number) => {
+                self.begin_value(writer)?;
+                self.write_number(number, writer)
             }
-            Value::String(string) => self.write_string(string, writer),
-            Value::Number(number) => self.write_number(number, writer),
-            Value::Bool(bool) => self.write_bool(*bool, writer),
-            Value::Null => self.write_null(writer),
-        }?;
-
-        self.end_array_value(writer)
+            Value::Bool(bool) => {
+                self.begin_value(writer)?;
+                self.write_bool(*bool, writer)
+            }
+            Value::Null => {
+                self.begin_value(writer)?;
+                self.write_null(writer)
+            }
+            Value::Binary(binary) => {
+                self.begin_value(writer)?;
+                self.write_binary(binary, writer)
+            }
+            Value::Timestamp(_)
+            | Value::Date(_)
+            | Value::ShortDate(_)
+            | Value::Time(_)
+            | Value::IntervalYearMonth(_)
+            | Value::IntervalDayTime(_) => {
+                self.begin_value(writer)?;
+                self.write_temporal(value, writer, Sealed)
+            }
+        }
     }
 }
 
-pub struct CompactFormatter;
+/// Formatter which writes compact JSON.
+#[derive(Default)]
+pub struct CompactFormatter {
+    number_buf: String,
+}
 
 impl CompactFormatter {
+    /// Creates a `CompactFormatter`.
     #[inline]
-    pub(crate) const fn new() -> Self {
-        Self
+    pub const fn new() -> Self {
+        Self {
+            number_buf: String::new(),
+        }
     }
 }
 
-impl Formatter for CompactFormatter {}
+impl Formatter for CompactFormatter {
+    #[inline]
+    fn write_number<W: fmt::Write>(&mut self, value: &Number, writer: &mut W) -> FormatResult<()> {
+        match as_i64(value) {
+            Some(value) => self.write_i64(value, writer),
+            None => write_decimal(value, &mut self.number_buf, writer),
+        }
+    }
+}
+
+/// The capacity reserved by the scratch buffer of a formatter for decimals, enough for any number
+/// formatted as JSON, which has at most 40 digits before it switches to scientific notation.
+const NUMBER_BUF_CAPACITY: usize = 64;
+
+/// Writes scalars with the default methods of [`Formatter`], which keep no state.
+struct ScalarFormatter;
+
+impl Formatter for ScalarFormatter {}
 
+/// Formats a yason as compact JSON, a scalar is written directly without a formatter and without
+/// the scratch buffer of decimals, see [`Yason::format_compact_scalar`].
+#[inline]
+pub(crate) fn format_compact_scalar<W: fmt::Write>(yason: &Yason, writer: &mut W) -> FormatResult<()> {
+    let value = Value::try_from(yason)?;
+    #[cfg(feature = "metrics")]
+    let writer = &mut crate::metrics::CountingWriter::new(writer);
+    let mut fmt = ScalarFormatter;
+    match value {
+        Value::Object(_) | Value::Array(_) => {
+            CompactFormatter::new().write_lazy_value(&LazyValue::try_from(yason)?, writer, Sealed)
+        }
+        Value::String(value) => fmt.write_string(value, writer),
+        Value::Number(value) => fmt.write_number(&value, writer),
+        Value::Bool(value) => fmt.write_bool(value, writer),
+        Value::Null => fmt.write_null(writer),
+        Value::Binary(value) => fmt.write_binary(value, writer),
+        _ => fmt.write_temporal(&value, writer, Sealed),
+    }
+}
+
+/// Returns the number as an integer if it has no fractional part and fits in `i64`.
+#[inline]
+fn as_i64(value: &Number) -> Option<i64> {
+    if value.scale() == 0 {
+        i64::try_from(value).ok()
+    } else {
+        None
+    }
+}
+
+/// Formats a decimal into the scratch buffer, and writes it to the writer in one call instead of
+/// digit groups, signs and exponents separately.
+#[inline]
+fn write_decimal<W: fmt::Write>(value: &Number, buf: &mut String, writer: &mut W) -> FormatResult<()> {
+    buf.clear();
+    buf.try_reserve(NUMBER_BUF_CAPACITY)
+        .map_err(|e| FormatError::YasonError(YasonError::TryReserveError(e)))?;
+    value
+        .format_to_json(&mut *buf)
+        .map_err(FormatError::NumberFormatError)?;
+    writer.write_str(buf)?;
+    Ok(())
+}
+
+/// A yason which is formatted when it is displayed.
+///
+/// `fmt::Display` can only report `fmt::Error`, the error which caused the formatting to fail can be
+/// retrieved by [`LazyFormat::take_error`]. For that reason it is `Send` but not `Sync`.
 pub struct LazyFormat<'a> {
     yason: &'a Yason,
     pretty: bool,
+    truncate: Option<TruncateOptions>,
+    insertion_order: bool,
+    escape: EscapeProfile,
+    binary: BinaryFormat,
+    error: Cell<Option<FormatError>>,
 }
 
 impl<'a> LazyFormat<'a> {
     #[inline]
     pub const fn new(yason: &'a Yason, pretty: bool) -> Self {
-        Self { yason, pretty }
+        Self {
+            yason,
+            pretty,
+            truncate: None,
+            insertion_order: false,
+            escape: EscapeProfile::JsonSafe,
+            binary: BinaryFormat::Base64,
+            error: Cell::new(None),
+        }
+    }
+
+    #[inline]
+    pub const fn with_truncate(yason: &'a Yason, pretty: bool, options: TruncateOptions) -> Self {
+        Self {
+            yason,
+            pretty,
+            truncate: Some(options),
+            insertion_order: false,
+            escape: EscapeProfile::JsonSafe,
+            binary: BinaryFormat::Base64,
+            error: Cell::new(None),
+        }
+    }
+
+    #[inline]
+    pub const fn with_insertion_order(yason: &'a Yason, pretty: bool) -> Self {
+        Self {
+            yason,
+            pretty,
+            truncate: None,
+            insertion_order: true,
+            escape: EscapeProfile::JsonSafe,
+            binary: BinaryFormat::Base64,
+            error: Cell::new(None),
+        }
+    }
+}
+
+impl<'a> LazyFormat<'a> {
+    #[inline]
+    pub const fn with_escape(yason: &'a Yason, pretty: bool, profile: EscapeProfile) -> Self {
+        Self {
+            yason,
+            pretty,
+            truncate: None,
+            insertion_order: false,
+            escape: profile,
+            binary: BinaryFormat::Base64,
+            error: Cell::new(None),
+        }
+    }
+
+    #[inline]
+    pub const fn with_binary(yason: &'a Yason, pretty: bool, format: BinaryFormat) -> Self {
+        Self {
+            yason,
+            pretty,
+            truncate: None,
+            insertion_order: false,
+            escape: EscapeProfile::JsonSafe,
+            binary: format,
+            error: Cell::new(None),
+        }
+    }
+}
+
+impl LazyFormat<'_> {
+    /// Takes the error of the last failed formatting, if any.
+    #[inline]
+    pub fn take_error(&self) -> Option<FormatError> {
+        self.error.take()
     }
 }
 
 impl fmt::Display for LazyFormat<'_> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.pretty {
-            let mut fmt = PrettyFormatter::new();
-            fmt.format(self.yason, f).map_err(|_| fmt::Error)
-        } else {
-            let mut fmt = CompactFormatter::new();
-            fmt.format(self.yason, f).map_err(|_| fmt::Error)
+        match self.truncate {
+            Some(options) => self.yason.format_truncated_to(self.pretty, options, f),
+            None if self.insertion_order => self.yason.format_insertion_order_to(self.pretty, f),
+            None if self.escape != EscapeProfile::JsonSafe => self.yason.format_escaped_to(self.pretty, self.escape, f),
+            None if self.binary != BinaryFormat::Base64 => self.yason.format_binary_to(self.pretty, self.binary, f),
+            None => self.yason.format_to(self.pretty, f),
         }
+        .map_err(|e| {
+            self.error.set(Some(e));
+            fmt::Error
+        })
     }
 }
 
@@ -357,6 +782,13 @@ const U1F: &[u8] = b"\\u001F";
 
 const U7F: &[u8] = b"\\u007F";
 
+const U26: &[u8] = b"\\u0026"; // &
+const U3C: &[u8] = b"\\u003C"; // <
+const U3E: &[u8] = b"\\u003E"; // >
+
+const U2028: &[u8] = b"\\u2028"; // line separator
+const U2029: &[u8] = b"\\u2029"; // paragraph separator
+
 // Lookup table of escape sequences. A value of b"x" at index i means that byte
 // i is escaped as "x" in Yason. A value of b"" means that byte i is not escaped.
 static ESCAPE: [&[u8]; 256] = [
@@ -379,14 +811,96 @@ static ESCAPE: [&[u8]; 256] = [
     ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // F
 ];
 
+// Same as `ESCAPE`, and additionally escapes `&`, `<` and `>` so the output can be embedded into HTML.
+static HTML_ESCAPE: [&[u8]; 256] = [
+    //    1    2    3    4    5    6    7    8    9    A    B    C    D    E    F
+    U00, U01, U02, U03, U04, U05, U06, U07, BBB, TTT, NNN, U0B, FFF, RRR, U0E, U0F, // 0
+    U10, U11, U12, U13, U14, U15, U16, U17, U18, U19, U1A, U1B, U1C, U1D, U1E, U1F, // 1
+    ___, ___, QQU, ___, ___, ___, U26, ___, ___, ___, ___, ___, ___, ___, ___, SSS, // 2
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, U3C, ___, U3E, ___, // 3
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // 4
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, BBS, ___, ___, ___, // 5
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // 6
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, U7F, // 7
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // 8
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // 9
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // A
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // B
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // C
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // D
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // E
+    ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, ___, // F
+];
+
+/// Characters escaped in addition to the ones JSON requires.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EscapeProfile {
+    /// Escapes only what JSON requires.
+    JsonSafe,
+    /// Also escapes U+2028 and U+2029, which terminate lines in JavaScript source before ES2019.
+    JsSafe,
+    /// Also escapes U+2028, U+2029, `&`, `<` and `>`, so the output can be embedded into a `<script>` element.
+    HtmlSafe,
+}
+
+impl Default for EscapeProfile {
+    #[inline]
+    fn default() -> Self {
+        EscapeProfile::JsonSafe
+    }
+}
+
+/// How binary values are written, as JSON has no binary type.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BinaryFormat {
+    /// Writes a string of the standard base64 encoding with padding.
+    Base64,
+    /// Writes a string of lowercase hexadecimal digits.
+    Hex,
+    /// Fails with `FormatError::UnsupportedBinary`.
+    Error,
+}
+
+impl Default for BinaryFormat {
+    #[inline]
+    fn default() -> Self {
+        BinaryFormat::Base64
+    }
+}
+
+/// Writes the string escaped as the content of a JSON string, without the surrounding quotes.
+#[inline]
+pub fn format_escaped_str<W: fmt::Write>(value: &str, writer: &mut W) -> FormatResult<()> {
+    format_escaped_str_with(value, EscapeProfile::JsonSafe, writer)
+}
+
+/// Writes the string escaped by the given profile as the content of a JSON string, without the
+/// surrounding quotes.
 #[inline]
-fn format_escaped_str<W: fmt::Write>(value: &str, writer: &mut W) -> FormatResult<()> {
+pub fn format_escaped_str_with<W: fmt::Write>(value: &str, profile: EscapeProfile, writer: &mut W) -> FormatResult<()> {
+    let table = match profile {
+        EscapeProfile::HtmlSafe => &HTML_ESCAPE,
+        EscapeProfile::JsonSafe | EscapeProfile::JsSafe => &ESCAPE,
+    };
+    let escape_separators = profile != EscapeProfile::JsonSafe;
     let bytes = value.as_bytes();
 
     let mut start = 0;
-    for (i, &byte) in bytes.iter().enumerate() {
-        let escape = ESCAPE[byte as usize];
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        let (escape, len) = if escape_separators && byte == 0xE2 && bytes.get(i + 1) == Some(&0x80) {
+            // U+2028 and U+2029 are encoded as E2 80 A8 and E2 80 A9
+            match bytes.get(i + 2) {
+                Some(0xA8) => (U2028, 3),
+                Some(0xA9) => (U2029, 3),
+                _ => (___, 1),
+            }
+        } else {
+            (table[byte as usize], 1)
+        };
         if escape == ___ {
+            i += 1;
             continue;
         }
 
@@ -394,7 +908,8 @@ fn format_escaped_str<W: fmt::Write>(value: &str, writer: &mut W) -> FormatResul
             writer.write_bytes(&bytes[start..i])?;
         }
         writer.write_bytes(escape)?;
-        start = i + 1;
+        i += len;
+        start = i;
     }
 
     if start != bytes.len() {
@@ -413,3 +928,15 @@ trait WriteExt: fmt::Write {
 }
 
 impl<W: fmt::Write> WriteExt for W {}
+
+/// Appends the formatted text to a byte vector, whose existing bytes need not be UTF-8.
+struct VecWriter<'a>(&'a mut Vec<u8>);
+
+impl fmt::Write for VecWriter<'_> {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.try_reserve(s.len()).map_err(|_| fmt::Error)?;
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+//! InsertionOrderFormatter
+
+use crate::format::{BinaryFormat, EscapeProfile, FormatResult, Formatter, Sealed};
+use crate::Object;
+use std::fmt;
+
+/// Formatter which writes the entries of objects in insertion order instead of key order.
+pub struct InsertionOrderFormatter<F: Formatter> {
+    inner: F,
+}
+
+impl<F: Formatter> InsertionOrderFormatter<F> {
+    #[inline]
+    pub(crate) const fn new(inner: F) -> Self {
+        Self { inner }
+    }
+}
+
+impl<F: Formatter> Formatter for InsertionOrderFormatter<F> {
+    #[inline]
+    fn write_object<W: fmt::Write>(&mut self, value: &Object, writer: &mut W) -> FormatResult<()> {
+        self.begin_object(writer)?;
+
+        let mut first = true;
+        for entry in value.lazy_iter_insertion_order()? {
+            let (key, value) = entry?;
+            self.write_object_value(key, &value, first, writer, Sealed)?;
+            first = false;
+        }
+
+        self.end_object(writer)
+    }
+
+    #[inline]
+    fn begin_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.inner.begin_value(writer)
+    }
+
+    #[inline]
+    fn escape_profile(&self) -> EscapeProfile {
+        self.inner.escape_profile()
+    }
+
+    #[inline]
+    fn binary_format(&self) -> BinaryFormat {
+        self.inner.binary_format()
+    }
+
+    forward_hooks!(inner);
+}
@@ -1,12 +1,19 @@
 //! PrettyFormatter
 
-use crate::format::{FormatResult, Formatter, WriteExt};
-use crate::yason::LazyValue;
-use crate::DataType;
+use crate::format::{as_i64, write_decimal, FormatResult, Formatter, WriteExt};
+use crate::Number;
 use std::fmt;
 
+/// The indentation of one nesting level.
+enum Indent<'a> {
+    /// A number of spaces, written from a buffer of spaces.
+    Spaces(usize),
+    /// Any other string, e.g. a tab.
+    Str(&'a str),
+}
+
 struct PrettyOptions<'a> {
-    indent: usize,
+    indent: Indent<'a>,
     newline_in_empty: bool,
     newline_in_nested: bool,
     kv_delimiter: &'a [u8],
@@ -14,7 +21,7 @@ struct PrettyOptions<'a> {
 
 impl<'a> PrettyOptions<'a> {
     #[inline]
-    const fn new(indent: usize, newline_in_empty: bool, newline_in_nested: bool, kv_delimiter: &'a [u8]) -> Self {
+    const fn new(indent: Indent<'a>, newline_in_empty: bool, newline_in_nested: bool, kv_delimiter: &'a [u8]) -> Self {
         Self {
             indent,
             newline_in_empty,
@@ -24,44 +31,66 @@ impl<'a> PrettyOptions<'a> {
     }
 }
 
+/// Formatter which writes JSON indented by two spaces.
 pub struct PrettyFormatter<'a> {
     options: PrettyOptions<'a>,
     cur_indent_level: usize,
     has_value: bool,
+    number_buf: String,
 }
 
 impl<'a> PrettyFormatter<'a> {
+    /// Creates a `PrettyFormatter`.
     #[inline]
-    pub(crate) const fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
-            options: PrettyOptions::new(2, true, true, b" : "),
+            options: PrettyOptions::new(Indent::Spaces(2), true, true, b" : "),
             cur_indent_level: 0,
             has_value: false,
+            number_buf: String::new(),
         }
     }
 }
 
+impl<'a> PrettyFormatter<'a> {
+    /// Creates a `PrettyFormatter` which indents each nesting level by the given string, e.g. `"\t"`
+    /// or four spaces, instead of two spaces.
+    ///
+    /// ```rust
+    /// use yason::{Formatter, PrettyFormatter, YasonBuf};
+    ///
+    /// let yason = YasonBuf::parse(r#"{"a": [1]}"#).unwrap();
+    /// let mut buf = String::new();
+    /// PrettyFormatter::with_indent("\t").format(&yason, &mut buf).unwrap();
+    /// assert_eq!(buf, "{\n\t\"a\" : \n\t[\n\t\t1\n\t]\n}");
+    /// ```
+    #[inline]
+    pub fn with_indent(indent: &'a str) -> Self {
+        let indent = if indent.bytes().all(|b| b == b' ') {
+            Indent::Spaces(indent.len())
+        } else {
+            Indent::Str(indent)
+        };
+        let mut formatter = Self::new();
+        formatter.options.indent = indent;
+        formatter
+    }
+}
+
+impl Default for PrettyFormatter<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Formatter for PrettyFormatter<'_> {
     #[inline]
-    fn write_object_value<W: fmt::Write, const IN_ARRAY: bool>(
-        &mut self,
-        key: &str,
-        value: &LazyValue<IN_ARRAY>,
-        first: bool,
-        writer: &mut W,
-    ) -> FormatResult<()> {
-        self.begin_object_key(first, writer)?;
-        self.write_string(key, writer)?;
-        self.end_object_key(writer)?;
-        self.begin_object_value(writer)?;
-
-        if matches!(value.data_type(), DataType::Object | DataType::Array) && self.options.newline_in_nested {
-            writer.write_bytes(b"\n")?;
-            indent(self.cur_indent_level, self.options.indent, writer)?;
+    fn write_number<W: fmt::Write>(&mut self, value: &Number, writer: &mut W) -> FormatResult<()> {
+        match as_i64(value) {
+            Some(value) => self.write_i64(value, writer),
+            None => write_decimal(value, &mut self.number_buf, writer),
         }
-
-        self.write_lazy_value(value, writer)?;
-        self.end_object_value(writer)
     }
 
     #[inline]
@@ -78,7 +107,7 @@ impl Formatter for PrettyFormatter<'_> {
 
         if self.options.newline_in_empty || self.has_value {
             writer.write_bytes(b"\n")?;
-            indent(self.cur_indent_level, self.options.indent, writer)?;
+            indent(self.cur_indent_level, &self.options.indent, writer)?;
         }
         writer.write_bytes(b"]")?;
         Ok(())
@@ -91,7 +120,7 @@ impl Formatter for PrettyFormatter<'_> {
         } else {
             writer.write_bytes(b",\n")?;
         }
-        indent(self.cur_indent_level, self.options.indent, writer)
+        indent(self.cur_indent_level, &self.options.indent, writer)
     }
 
     #[inline]
@@ -113,7 +142,7 @@ impl Formatter for PrettyFormatter<'_> {
         self.cur_indent_level -= 1;
         if self.options.newline_in_empty || self.has_value {
             writer.write_bytes(b"\n")?;
-            indent(self.cur_indent_level, self.options.indent, writer)?;
+            indent(self.cur_indent_level, &self.options.indent, writer)?;
         }
         writer.write_bytes(b"}")?;
         Ok(())
@@ -126,7 +155,7 @@ impl Formatter for PrettyFormatter<'_> {
         } else {
             writer.write_bytes(b",\n")?;
         }
-        indent(self.cur_indent_level, self.options.indent, writer)
+        indent(self.cur_indent_level, &self.options.indent, writer)
     }
 
     #[inline]
@@ -140,11 +169,35 @@ impl Formatter for PrettyFormatter<'_> {
         self.has_value = true;
         Ok(())
     }
+
+    #[inline]
+    fn begin_nested_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        if self.options.newline_in_nested {
+            writer.write_bytes(b"\n")?;
+            indent(self.cur_indent_level, &self.options.indent, writer)?;
+        }
+        Ok(())
+    }
 }
 
 #[inline]
-fn indent<W: fmt::Write>(level: usize, indent: usize, writer: &mut W) -> FormatResult<()> {
+fn indent<W: fmt::Write>(level: usize, indent: &Indent, writer: &mut W) -> FormatResult<()> {
     const SPACE_BUF: [u8; 200] = [b' '; 200];
-    writer.write_bytes(&SPACE_BUF[..level * indent])?;
+    match indent {
+        Indent::Spaces(width) => {
+            // deep levels are written in several chunks of the buffer
+            let mut len = level * width;
+            while len > 0 {
+                let chunk = len.min(SPACE_BUF.len());
+                writer.write_bytes(&SPACE_BUF[..chunk])?;
+                len -= chunk;
+            }
+        }
+        Indent::Str(s) => {
+            for _ in 0..level {
+                writer.write_str(s)?;
+            }
+        }
+    }
     Ok(())
 }
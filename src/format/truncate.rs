@@ -0,0 +1,174 @@
+//! TruncatedFormatter
+
+use crate::format::{format_escaped_str_with, BinaryFormat, EscapeProfile, FormatResult, Formatter, Sealed};
+use crate::yason::LazyValue;
+use crate::{Array, DataType, Object};
+use std::fmt;
+
+/// Limits applied when formatting a yason for logging, see [`crate::Yason::format_truncated`].
+///
+/// Content beyond the limits is elided and replaced by a marker like `…(1234 more)`, so the output
+/// is no longer valid JSON once anything has been truncated.
+#[derive(Copy, Clone, Debug)]
+pub struct TruncateOptions {
+    /// Maximum nesting depth of objects and arrays, the outermost container is at depth 1.
+    pub max_depth: usize,
+    /// Maximum number of elements written for each array.
+    pub max_array_items: usize,
+    /// Maximum number of characters written for each string value.
+    pub max_string_len: usize,
+}
+
+impl TruncateOptions {
+    /// Creates `TruncateOptions` with the specified limits.
+    #[inline]
+    pub const fn new(max_depth: usize, max_array_items: usize, max_string_len: usize) -> Self {
+        Self {
+            max_depth,
+            max_array_items,
+            max_string_len,
+        }
+    }
+}
+
+impl Default for TruncateOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new(usize::MAX, usize::MAX, usize::MAX)
+    }
+}
+
+pub struct TruncatedFormatter<F: Formatter> {
+    inner: F,
+    options: TruncateOptions,
+    depth: usize,
+}
+
+impl<F: Formatter> TruncatedFormatter<F> {
+    #[inline]
+    pub(crate) const fn new(inner: F, options: TruncateOptions) -> Self {
+        Self {
+            inner,
+            options,
+            depth: 0,
+        }
+    }
+}
+
+impl<F: Formatter> Formatter for TruncatedFormatter<F> {
+    #[inline]
+    fn write_string<W: fmt::Write>(&mut self, value: &str, writer: &mut W) -> FormatResult<()> {
+        let (prefix, remain) = match value.char_indices().nth(self.options.max_string_len) {
+            Some((pos, _)) => (&value[..pos], value[pos..].chars().count()),
+            None => (value, 0),
+        };
+
+        self.begin_string(writer)?;
+        format_escaped_str_with(prefix, self.escape_profile(), writer)?;
+        if remain > 0 {
+            write_elided(remain, writer)?;
+        }
+        self.end_string(writer)
+    }
+
+    #[inline]
+    fn write_object<W: fmt::Write>(&mut self, value: &Object, writer: &mut W) -> FormatResult<()> {
+        self.begin_object(writer)?;
+
+        if self.depth >= self.options.max_depth {
+            let len = value.len()?;
+            if len > 0 {
+                self.begin_object_key(true, writer)?;
+                write_elided(len, writer)?;
+                self.end_object_value(writer)?;
+            }
+            return self.end_object(writer);
+        }
+
+        self.depth += 1;
+        let mut first = true;
+        for entry in value.lazy_iter()? {
+            let (key, value) = entry?;
+            self.write_object_value(key, &value, first, writer, Sealed)?;
+            first = false;
+        }
+        self.depth -= 1;
+
+        self.end_object(writer)
+    }
+
+    #[inline]
+    fn write_object_value<W: fmt::Write, const IN_ARRAY: bool>(
+        &mut self,
+        key: &str,
+        value: &LazyValue<IN_ARRAY>,
+        first: bool,
+        writer: &mut W,
+        _: Sealed,
+    ) -> FormatResult<()> {
+        self.begin_object_key(first, writer)?;
+        // keys are never truncated
+        self.begin_string(writer)?;
+        format_escaped_str_with(key, self.escape_profile(), writer)?;
+        self.end_string(writer)?;
+        self.end_object_key(writer)?;
+        self.begin_object_value(writer)?;
+        if matches!(value.data_type(), DataType::Object | DataType::Array) {
+            self.begin_nested_value(writer)?;
+        }
+        self.write_lazy_value(value, writer, Sealed)?;
+        self.end_object_value(writer)
+    }
+
+    #[inline]
+    fn write_array<W: fmt::Write>(&mut self, value: &Array, writer: &mut W) -> FormatResult<()> {
+        self.begin_array(writer)?;
+
+        let len = value.len()?;
+        if self.depth >= self.options.max_depth {
+            if len > 0 {
+                self.begin_array_value(true, writer)?;
+                write_elided(len, writer)?;
+                self.end_array_value(writer)?;
+            }
+            return self.end_array(writer);
+        }
+
+        self.depth += 1;
+        let shown = len.min(self.options.max_array_items);
+        for (i, val) in value.lazy_iter()?.take(shown).enumerate() {
+            self.write_array_value(&val?, i == 0, writer, Sealed)?;
+        }
+        if shown < len {
+            self.begin_array_value(shown == 0, writer)?;
+            write_elided(len - shown, writer)?;
+            self.end_array_value(writer)?;
+        }
+        self.depth -= 1;
+
+        self.end_array(writer)
+    }
+
+    #[inline]
+    fn begin_value<W: fmt::Write>(&mut self, writer: &mut W) -> FormatResult<()> {
+        self.inner.begin_value(writer)
+    }
+
+    #[inline]
+    fn escape_profile(&self) -> EscapeProfile {
+        self.inner.escape_profile()
+    }
+
+    #[inline]
+    fn binary_format(&self) -> BinaryFormat {
+        self.inner.binary_format()
+    }
+
+    forward_hooks!(inner);
+}
+
+#[inline]
+fn write_elided<W: fmt::Write>(count: usize, writer: &mut W) -> FormatResult<()> {
+    write!(writer, "…({} more)", count)?;
+    Ok(())
+}
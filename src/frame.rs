@@ -0,0 +1,199 @@
+//! Framing of concatenated yason documents.
+//!
+//! Each frame is the size of the document in 4 little-endian bytes followed by the document.
+//! Documents are validated when they are read.
+//!
+//! ```rust
+//! use yason::{frame, YasonBuf};
+//!
+//! let docs = [YasonBuf::parse("[1, 2]").unwrap(), YasonBuf::parse("null").unwrap()];
+//! let mut bytes = Vec::new();
+//! for doc in docs.iter() {
+//!     frame::write(&mut bytes, doc).unwrap();
+//! }
+//!
+//! let read = frame::iter_slice(&bytes).collect::<Result<Vec<_>, _>>().unwrap();
+//! assert_eq!(read, vec![docs[0].as_ref(), docs[1].as_ref()]);
+//! ```
+
+use crate::util::read_fixed;
+use crate::vec::VecExt;
+use crate::{Yason, YasonBuf, YasonError};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read, Write};
+
+/// Size of the length prefix of a frame.
+pub const LENGTH_SIZE: usize = 4;
+
+/// Possible errors that can arise when reading or writing frames.
+#[derive(Debug)]
+pub enum FrameError {
+    IoError(io::Error),
+    YasonError(YasonError),
+    /// The input ends inside a frame.
+    Truncated,
+    /// The document is larger than allowed.
+    TooLarge(usize),
+}
+
+impl Display for FrameError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            FrameError::IoError(e) => write!(f, "{}", e),
+            FrameError::YasonError(e) => write!(f, "{}", e),
+            FrameError::Truncated => write!(f, "input ends inside a frame"),
+            FrameError::TooLarge(e) => write!(f, "frame too large, length is {}", e),
+        }
+    }
+}
+
+impl Error for FrameError {}
+
+impl From<io::Error> for FrameError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        FrameError::IoError(e)
+    }
+}
+
+impl From<YasonError> for FrameError {
+    #[inline]
+    fn from(e: YasonError) -> Self {
+        FrameError::YasonError(e)
+    }
+}
+
+pub type FrameResult<T> = std::result::Result<T, FrameError>;
+
+/// Writes the document as a frame.
+#[inline]
+pub fn write<W: Write>(writer: &mut W, doc: &Yason) -> FrameResult<()> {
+    let bytes = doc.as_bytes();
+    let len = u32::try_from(bytes.len()).map_err(|_| FrameError::TooLarge(bytes.len()))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+/// Reader of frames from an `io::Read`, created by [`FrameReader::new`].
+pub struct FrameReader<R: Read> {
+    reader: R,
+    max_len: usize,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Creates a `FrameReader` which accepts documents of any size.
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self::with_max_len(reader, u32::MAX as usize)
+    }
+
+    /// Creates a `FrameReader` which rejects documents larger than `max_len` before allocating them.
+    #[inline]
+    pub fn with_max_len(reader: R, max_len: usize) -> Self {
+        Self { reader, max_len }
+    }
+
+    /// Reads the next document, returns `None` at the end of the input.
+    #[inline]
+    pub fn read_frame(&mut self) -> FrameResult<Option<YasonBuf>> {
+        let mut len_bytes = [0u8; LENGTH_SIZE];
+        let mut read = 0;
+        while read < LENGTH_SIZE {
+            match self.reader.read(&mut len_bytes[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(FrameError::Truncated),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        if len > self.max_len {
+            return Err(FrameError::TooLarge(len));
+        }
+
+        let mut bytes: Vec<u8> = VecExt::try_with_capacity(len).map_err(YasonError::TryReserveError)?;
+        let read = (&mut self.reader).take(len as u64).read_to_end(&mut bytes)?;
+        if read != len {
+            return Err(FrameError::Truncated);
+        }
+        Yason::from_bytes(&bytes)?;
+        Ok(Some(unsafe { YasonBuf::new_unchecked(bytes) }))
+    }
+
+    /// Returns the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = FrameResult<YasonBuf>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+/// Iterator over the frames of a byte slice, created by [`iter_slice`].
+pub struct SliceFrames<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SliceFrames<'a> {
+    #[inline]
+    fn read_frame(&mut self) -> FrameResult<&'a Yason> {
+        let len = match read_fixed(self.bytes, 0) {
+            Some(len_bytes) => u32::from_le_bytes(len_bytes) as usize,
+            None => return Err(FrameError::Truncated),
+        };
+        let rest = &self.bytes[LENGTH_SIZE..];
+        if rest.len() < len {
+            return Err(FrameError::Truncated);
+        }
+        let (doc, rest) = rest.split_at(len);
+        self.bytes = rest;
+        Ok(Yason::from_bytes(doc)?)
+    }
+
+    /// Returns the bytes after the frames read so far.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a> Iterator for SliceFrames<'a> {
+    type Item = FrameResult<&'a Yason>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let res = self.read_frame();
+        if res.is_err() {
+            // stop after the first error, the following bytes cannot be framed reliably
+            self.bytes = &[];
+        }
+        Some(res)
+    }
+}
+
+/// Returns an iterator over the documents framed in the byte slice, without copying them.
+#[inline]
+pub fn iter_slice(bytes: &[u8]) -> SliceFrames<'_> {
+    SliceFrames { bytes }
+}
+
+/// Returns the number of bytes the document takes as a frame.
+#[inline]
+pub fn frame_len(doc: &Yason) -> usize {
+    LENGTH_SIZE + doc.as_bytes().len()
+}
@@ -1,45 +1,151 @@
 //! Json to Yason
 
 use crate::builder::{ArrBuilder, BuildResult, NumberError, ObjBuilder};
+use crate::limits::MAX_NESTED_DEPTH;
 use crate::{
     ArrayBuilder, ArrayRefBuilder, BuildError, Number, ObjectBuilder, ObjectRefBuilder, Scalar, Yason, YasonBuf,
 };
 use decimal_rs::DecimalParseError;
-use serde_json::{Map, Value};
-use std::fmt::Write;
+use serde_json::Value;
+use std::borrow::Cow;
 use std::str::FromStr;
 
-impl TryFrom<&serde_json::Value> for YasonBuf {
-    type Error = BuildError;
+/// A json value which keeps the text of its numbers and the order of its keys, so that neither
+/// depends on the features of `serde_json`.
+pub(crate) enum Json<'a> {
+    Null,
+    Bool(bool),
+    Number(Cow<'a, str>),
+    String(Cow<'a, str>),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(Cow<'a, str>, Json<'a>)>),
+}
 
+impl<'a> Json<'a> {
+    /// Parses a json text, borrowing the numbers and the strings without escapes from it.
+    ///
+    /// If a key appears more than once in an object, the last value is kept at the position of
+    /// the first one.
     #[inline]
-    fn try_from(value: &serde_json::Value) -> Result<Self, Self::Error> {
-        let mut buf = String::new();
-        match value {
-            Value::Null => Scalar::null(),
-            Value::Bool(val) => Scalar::bool(*val),
-            Value::Number(val) => Scalar::number(number2decimal(val, &mut buf)?),
-            Value::String(val) => Scalar::string(val),
-            Value::Array(val) => {
+    pub(crate) fn parse(input: &'a str) -> BuildResult<Self> {
+        let mut parser = Parser {
+            input,
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.pos != input.len() {
+            return Err(parser.syntax_error());
+        }
+        Ok(value)
+    }
+
+    /// Borrows a `serde_json::Value`, failing if it is nested deeper than a yason can be.
+    fn from_value(value: &'a Value, depth: usize) -> BuildResult<Self> {
+        let json = match value {
+            Value::Null => Json::Null,
+            Value::Bool(val) => Json::Bool(*val),
+            Value::Number(val) => Json::Number(Cow::Owned(val.to_string())),
+            Value::String(val) => Json::String(Cow::Borrowed(val)),
+            Value::Array(_) | Value::Object(_) if depth >= MAX_NESTED_DEPTH => {
+                return Err(BuildError::NestedTooDeeply);
+            }
+            Value::Array(val) => Json::Array(
+                val.iter()
+                    .map(|value| Json::from_value(value, depth + 1))
+                    .collect::<BuildResult<_>>()?,
+            ),
+            Value::Object(val) => Json::Object(
+                val.iter()
+                    .map(|(key, value)| Ok((Cow::Borrowed(key.as_str()), Json::from_value(value, depth + 1)?)))
+                    .collect::<BuildResult<_>>()?,
+            ),
+        };
+        Ok(json)
+    }
+
+    /// Encodes the json value to `YasonBuf`.
+    #[inline]
+    pub(crate) fn to_yason(&self) -> BuildResult<YasonBuf> {
+        match self {
+            Json::Null => Scalar::null(),
+            Json::Bool(val) => Scalar::bool(*val),
+            Json::Number(val) => Scalar::number(number2decimal(val)?),
+            Json::String(val) => Scalar::string(val),
+            Json::Array(val) => {
                 let mut array_builder = ArrayBuilder::try_new(val.len() as u16)?;
-                write_array(&mut array_builder, val, &mut buf)?;
+                write_array(&mut array_builder, val)?;
                 array_builder.finish()
             }
-            Value::Object(val) => {
+            Json::Object(val) => {
                 let mut object_builder = ObjectBuilder::try_new(val.len() as u16, false)?;
-                write_object(&mut object_builder, val, &mut buf)?;
+                write_object(&mut object_builder, val)?;
                 object_builder.finish()
             }
         }
     }
+
+    /// Encodes the json value to `Yason` in the bytes.
+    #[inline]
+    fn write_to<'b>(&self, bytes: &'b mut Vec<u8>) -> BuildResult<&'b Yason> {
+        match self {
+            Json::Null => Scalar::null_with_vec(bytes),
+            Json::Bool(val) => Scalar::bool_with_vec(*val, bytes),
+            Json::Number(val) => Scalar::number_with_vec(number2decimal(val)?, bytes),
+            Json::String(val) => Scalar::string_with_vec(val, bytes),
+            Json::Array(array) => {
+                let mut builder = ArrayRefBuilder::try_new(bytes, array.len() as u16)?;
+                write_array(&mut builder, array)?;
+                builder.finish()
+            }
+            Json::Object(object) => {
+                let mut builder = ObjectRefBuilder::try_new(bytes, object.len() as u16, false)?;
+                write_object(&mut builder, object)?;
+                builder.finish()
+            }
+        }
+    }
+}
+
+impl TryFrom<&serde_json::Value> for YasonBuf {
+    type Error = BuildError;
+
+    #[inline]
+    fn try_from(value: &serde_json::Value) -> Result<Self, Self::Error> {
+        Json::from_value(value, 0)?.to_yason()
+    }
 }
 
 impl YasonBuf {
     /// Parses a json string to `YasonBuf`.
+    ///
+    /// Keys of objects keep the order of the json text, see [`Object::iter_insertion_order`]. If a
+    /// key appears more than once, the last value is kept at the position of the first one.
+    ///
+    /// [`Object::iter_insertion_order`]: crate::Object::iter_insertion_order
     #[inline]
     pub fn parse<T: AsRef<str>>(str: T) -> BuildResult<Self> {
-        let json: Value = serde_json::from_str(str.as_ref()).map_err(BuildError::JsonError)?;
-        YasonBuf::try_from(&json)
+        Json::parse(str.as_ref())?.to_yason()
+    }
+
+    /// Parses a json string to `YasonBuf` like [`parse`], normalizing every key to Unicode NFC
+    /// form, so that keys which differ only by normalization form, e.g. a composed `é` and `e`
+    /// followed by a combining accent, are stored as the same key.
+    ///
+    /// Keys are sorted by their normalized bytes, so lookups must normalize the key as well, see
+    /// [`Object::get_normalized`]. Keys which become equal after normalization are merged like
+    /// duplicate keys of [`parse`], the last value is kept at the position of the first key.
+    ///
+    /// [`parse`]: YasonBuf::parse
+    /// [`Object::get_normalized`]: crate::Object::get_normalized
+    #[cfg(feature = "unicode-normalization")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    #[inline]
+    pub fn parse_with_nfc_keys<T: AsRef<str>>(str: T) -> BuildResult<Self> {
+        let mut json = Json::parse(str.as_ref())?;
+        normalize_keys(&mut json, &mut String::new())?;
+        json.to_yason()
     }
 }
 
@@ -47,52 +153,34 @@ impl Yason {
     /// Parses a json string to `Yason`.
     #[inline]
     pub fn parse_to<T: AsRef<str>>(bytes: &mut Vec<u8>, str: T) -> BuildResult<&Yason> {
-        let mut buf = String::new();
-
-        let json: Value = serde_json::from_str(str.as_ref()).map_err(BuildError::JsonError)?;
-        match &json {
-            Value::Null => Scalar::null_with_vec(bytes),
-            Value::Bool(val) => Scalar::bool_with_vec(*val, bytes),
-            Value::Number(val) => Scalar::number_with_vec(number2decimal(val, &mut buf)?, bytes),
-            Value::String(val) => Scalar::string_with_vec(val, bytes),
-            Value::Array(array) => {
-                let mut builder = ArrayRefBuilder::try_new(bytes, array.len() as u16)?;
-                write_array(&mut builder, array, &mut buf)?;
-                builder.finish()
-            }
-            Value::Object(object) => {
-                let mut builder = ObjectRefBuilder::try_new(bytes, object.len() as u16, false)?;
-                write_object(&mut builder, object, &mut buf)?;
-                builder.finish()
-            }
-        }
+        Json::parse(str.as_ref())?.write_to(bytes)
     }
 }
 
 #[inline]
-fn write_array<T: ArrBuilder>(builder: &mut T, array: &[serde_json::Value], buf: &mut String) -> BuildResult<()> {
+fn write_array<T: ArrBuilder>(builder: &mut T, array: &[Json]) -> BuildResult<()> {
     for value in array {
         match value {
-            Value::Null => {
+            Json::Null => {
                 builder.push_null()?;
             }
-            Value::Bool(val) => {
+            Json::Bool(val) => {
                 builder.push_bool(*val)?;
             }
-            Value::Number(val) => {
-                builder.push_number(number2decimal(val, buf)?)?;
+            Json::Number(val) => {
+                builder.push_number(number2decimal(val)?)?;
             }
-            Value::String(val) => {
+            Json::String(val) => {
                 builder.push_string(val)?;
             }
-            Value::Array(val) => {
+            Json::Array(val) => {
                 let mut array_builder = builder.push_array(val.len() as u16)?;
-                write_array(&mut array_builder, val, buf)?;
+                write_array(&mut array_builder, val)?;
                 array_builder.finish()?;
             }
-            Value::Object(val) => {
+            Json::Object(val) => {
                 let mut object_builder = builder.push_object(val.len() as u16, false)?;
-                write_object(&mut object_builder, val, buf)?;
+                write_object(&mut object_builder, val)?;
                 object_builder.finish()?;
             }
         }
@@ -101,33 +189,29 @@ fn write_array<T: ArrBuilder>(builder: &mut T, array: &[serde_json::Value], buf:
 }
 
 #[inline]
-fn write_object<T: ObjBuilder>(
-    builder: &mut T,
-    object: &Map<String, serde_json::Value>,
-    buf: &mut String,
-) -> BuildResult<()> {
+fn write_object<T: ObjBuilder>(builder: &mut T, object: &[(Cow<str>, Json)]) -> BuildResult<()> {
     for (key, value) in object {
         match value {
-            Value::Null => {
+            Json::Null => {
                 builder.push_null(key)?;
             }
-            Value::Bool(val) => {
+            Json::Bool(val) => {
                 builder.push_bool(key, *val)?;
             }
-            Value::Number(val) => {
-                builder.push_number(key, number2decimal(val, buf)?)?;
+            Json::Number(val) => {
+                builder.push_number(key, number2decimal(val)?)?;
             }
-            Value::String(val) => {
+            Json::String(val) => {
                 builder.push_string(key, val)?;
             }
-            Value::Array(val) => {
+            Json::Array(val) => {
                 let mut array_builder = builder.push_array(key, val.len() as u16)?;
-                write_array(&mut array_builder, val, buf)?;
+                write_array(&mut array_builder, val)?;
                 array_builder.finish()?;
             }
-            Value::Object(val) => {
+            Json::Object(val) => {
                 let mut object_builder = builder.push_object(key, val.len() as u16, false)?;
-                write_object(&mut object_builder, val, buf)?;
+                write_object(&mut object_builder, val)?;
                 object_builder.finish()?;
             }
         }
@@ -135,12 +219,307 @@ fn write_object<T: ObjBuilder>(
     Ok(())
 }
 
+/// A recursive descent parser of json text.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    /// Skips the byte if it is the next one.
+    #[inline]
+    fn eat(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == Some(byte);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips the digits, returning whether there was any.
+    #[inline]
+    fn skip_digits(&mut self) -> bool {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos > start
+    }
+
+    /// The json is invalid, serde_json is asked to parse it again to describe where and why.
+    #[cold]
+    fn syntax_error(&self) -> BuildError {
+        match serde_json::from_str::<Value>(self.input) {
+            Err(e) => BuildError::JsonError(e),
+            Ok(_) => BuildError::JsonError(serde_json::Error::io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid json at byte {}", self.pos),
+            ))),
+        }
+    }
+
+    fn parse_value(&mut self) -> BuildResult<Json<'a>> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.parse_literal("null", Json::Null),
+            Some(b't') => self.parse_literal("true", Json::Bool(true)),
+            Some(b'f') => self.parse_literal("false", Json::Bool(false)),
+            Some(b'"') => Ok(Json::String(self.parse_string()?)),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b'[') => self.parse_array(),
+            Some(b'{') => self.parse_object(),
+            _ => Err(self.syntax_error()),
+        }
+    }
+
+    #[inline]
+    fn parse_literal(&mut self, literal: &str, value: Json<'a>) -> BuildResult<Json<'a>> {
+        if !self.input.as_bytes()[self.pos..].starts_with(literal.as_bytes()) {
+            return Err(self.syntax_error());
+        }
+        self.pos += literal.len();
+        Ok(value)
+    }
+
+    /// Parses a number, whose text is kept as it is to be converted exactly.
+    #[inline]
+    fn parse_number(&mut self) -> BuildResult<Json<'a>> {
+        let start = self.pos;
+        self.eat(b'-');
+        // no leading zeros
+        if !self.eat(b'0') && !self.skip_digits() {
+            return Err(self.syntax_error());
+        }
+        if self.eat(b'.') && !self.skip_digits() {
+            return Err(self.syntax_error());
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'+') {
+                self.eat(b'-');
+            }
+            if !self.skip_digits() {
+                return Err(self.syntax_error());
+            }
+        }
+        Ok(Json::Number(Cow::Borrowed(&self.input[start..self.pos])))
+    }
+
+    /// Parses a string, which is borrowed from the input unless it contains escapes.
+    fn parse_string(&mut self) -> BuildResult<Cow<'a, str>> {
+        self.pos += 1; // opening quote
+        let mut unescaped: Option<String> = None;
+        let mut chunk_start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let string = unescaped.get_or_insert_with(String::new);
+                    string.push_str(&self.input[chunk_start..self.pos]);
+                    self.pos += 1;
+                    string.push(self.parse_escape()?);
+                    chunk_start = self.pos;
+                }
+                Some(0x00..=0x1f) | None => return Err(self.syntax_error()),
+                Some(_) => self.pos += 1,
+            }
+        }
+        let chunk = &self.input[chunk_start..self.pos];
+        self.pos += 1; // closing quote
+        match unescaped {
+            Some(mut string) => {
+                string.push_str(chunk);
+                Ok(Cow::Owned(string))
+            }
+            None => Ok(Cow::Borrowed(chunk)),
+        }
+    }
+
+    /// Parses the escape after a backslash, a surrogate pair of `\u` escapes is one character.
+    #[inline]
+    fn parse_escape(&mut self) -> BuildResult<char> {
+        let ch = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                let code = match self.parse_hex4()? {
+                    high @ 0xd800..=0xdbff => {
+                        if !self.eat(b'\\') || !self.eat(b'u') {
+                            return Err(self.syntax_error());
+                        }
+                        match self.parse_hex4()? {
+                            low @ 0xdc00..=0xdfff => 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00),
+                            _ => return Err(self.syntax_error()),
+                        }
+                    }
+                    code => code,
+                };
+                // a lone trailing surrogate is not a character
+                return char::from_u32(code).ok_or_else(|| self.syntax_error());
+            }
+            _ => return Err(self.syntax_error()),
+        };
+        self.pos += 1;
+        Ok(ch)
+    }
+
+    #[inline]
+    fn parse_hex4(&mut self) -> BuildResult<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.peek().and_then(|b| (b as char).to_digit(16));
+            code = code * 16 + digit.ok_or_else(|| self.syntax_error())?;
+            self.pos += 1;
+        }
+        Ok(code)
+    }
+
+    /// Enters an array or an object, which can be nested as deeply as the builders allow.
+    #[inline]
+    fn enter(&mut self) -> BuildResult<()> {
+        if self.depth >= MAX_NESTED_DEPTH {
+            return Err(BuildError::NestedTooDeeply);
+        }
+        self.depth += 1;
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_array(&mut self) -> BuildResult<Json<'a>> {
+        self.enter()?;
+        let mut array = Vec::new();
+        self.skip_whitespace();
+        if !self.eat(b']') {
+            loop {
+                array.push(self.parse_value()?);
+                self.skip_whitespace();
+                if self.eat(b']') {
+                    break;
+                }
+                if !self.eat(b',') {
+                    return Err(self.syntax_error());
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Json::Array(array))
+    }
+
+    fn parse_object(&mut self) -> BuildResult<Json<'a>> {
+        self.enter()?;
+        let mut object = Vec::new();
+        self.skip_whitespace();
+        if !self.eat(b'}') {
+            loop {
+                self.skip_whitespace();
+                if self.peek() != Some(b'"') {
+                    return Err(self.syntax_error());
+                }
+                let key = self.parse_string()?;
+                self.skip_whitespace();
+                if !self.eat(b':') {
+                    return Err(self.syntax_error());
+                }
+                object.push((key, self.parse_value()?));
+                self.skip_whitespace();
+                if self.eat(b'}') {
+                    break;
+                }
+                if !self.eat(b',') {
+                    return Err(self.syntax_error());
+                }
+            }
+        }
+        dedup_keys(&mut object);
+        self.depth -= 1;
+        Ok(Json::Object(object))
+    }
+}
+
+/// Merges the entries with the same key, keeping the last value at the position of the first key.
+pub(crate) fn dedup_keys(object: &mut Vec<(Cow<str>, Json)>) {
+    if object.len() < 2 {
+        return;
+    }
+    // a stable sort keeps the entries with the same key in the order of the json text
+    let mut order: Vec<usize> = (0..object.len()).collect();
+    order.sort_by(|&a, &b| object[a].0.cmp(&object[b].0));
+
+    let mut removed = vec![false; object.len()];
+    let mut merged = false;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && object[order[end]].0 == object[order[start]].0 {
+            end += 1;
+        }
+        if end - start > 1 {
+            let (first, last) = (order[start], order[end - 1]);
+            object[first].1 = std::mem::replace(&mut object[last].1, Json::Null);
+            for &index in &order[start + 1..end] {
+                removed[index] = true;
+            }
+            merged = true;
+        }
+        start = end;
+    }
+    if merged {
+        let mut index = 0;
+        object.retain(|_| {
+            index += 1;
+            !removed[index - 1]
+        });
+    }
+}
+
+/// Replaces the keys of the objects by their NFC forms, keys which become equal are merged.
+#[cfg(feature = "unicode-normalization")]
+fn normalize_keys(value: &mut Json, key_buf: &mut String) -> BuildResult<()> {
+    match value {
+        Json::Array(array) => {
+            for value in array {
+                normalize_keys(value, key_buf)?;
+            }
+        }
+        Json::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                let normalized = crate::normalize::nfc_key(key, key_buf)?;
+                if normalized != key.as_ref() {
+                    let normalized = normalized.to_string();
+                    *key = Cow::Owned(normalized);
+                }
+                normalize_keys(value, key_buf)?;
+            }
+            dedup_keys(object);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Converts the text of a json number to `Number`, rounding it to the precision of `Number`.
 #[inline]
-fn number2decimal(val: &serde_json::Number, buf: &mut String) -> BuildResult<Number> {
-    buf.clear();
-    buf.try_reserve(256)?;
-    write!(buf, "{}", val).map_err(|_| BuildError::NumberError(NumberError::FormatError))?;
-    Number::from_str(buf.as_str()).map_or_else(
+fn number2decimal(val: &str) -> BuildResult<Number> {
+    Number::from_str(val).map_or_else(
         |e| match e {
             DecimalParseError::Underflow => Ok(Number::ZERO),
             DecimalParseError::Overflow => Err(BuildError::NumberError(NumberError::Overflow)),
@@ -154,28 +533,23 @@ fn number2decimal(val: &serde_json::Number, buf: &mut String) -> BuildResult<Num
 mod tests {
     use super::*;
     use decimal_rs::Decimal;
-    use serde_json::Error;
-    use std::str::FromStr;
 
     #[test]
     fn test_number2decimal() {
         fn assert_number(input: &str, output: &str) {
-            let number: serde_json::Number = serde_json::from_str(input).unwrap();
-            let mut buf = String::with_capacity(256);
-            let decimal = number2decimal(&number, &mut buf).unwrap();
+            let decimal = match Json::parse(input).unwrap() {
+                Json::Number(number) => number2decimal(&number).unwrap(),
+                _ => panic!("expected number"),
+            };
             assert_eq!(decimal, Decimal::from_str(output).unwrap());
         }
 
         fn assert_number_invalid(s: &str) {
-            let number: Result<serde_json::Number, Error> = serde_json::from_str(s);
-            assert!(number.is_err());
+            assert!(matches!(Json::parse(s), Err(BuildError::JsonError(_))));
         }
 
         fn assert_number_overflow(s: &str) {
-            let number: serde_json::Number = serde_json::from_str(s).unwrap();
-            let mut buf = String::with_capacity(256);
-            let decimal = number2decimal(&number, &mut buf);
-            match decimal {
+            match YasonBuf::parse(s) {
                 Err(BuildError::NumberError(NumberError::Overflow)) => {}
                 _ => panic!("expected numeric overflow"),
             };
@@ -187,6 +561,12 @@ mod tests {
         assert_number_invalid("123abc");
         assert_number_invalid("");
         assert_number_invalid("   ");
+        assert_number_invalid("01");
+        assert_number_invalid("1.");
+        assert_number_invalid(".5");
+        assert_number_invalid("-");
+        assert_number_invalid("1e");
+        assert_number_invalid("+1");
 
         assert_number_overflow("1e126");
         assert_number_overflow("1e150");
@@ -5,7 +5,68 @@
 //! ### `serde`
 //!
 //! When this optional dependency is enabled, `YasonBuf` implements the `serde::Serialize` and
-//! `serde::Deserialize` traits.
+//! `serde::Deserialize` traits, and `from_yason_paths` deserializes a struct from the values
+//! selected by path expressions.
+//!
+//! ### `memmap2`
+//!
+//! When this optional dependency is enabled, `MmapYason` maps a file and validates it as a yason.
+//!
+//! ### `testkit`
+//!
+//! When this optional feature is enabled, the `testkit` module provides `proptest` and `arbitrary`
+//! generators of documents and round-trip assertions.
+//!
+//! ### `rayon`
+//!
+//! When this optional dependency is enabled, [`bulk::exists_bitmap`] evaluates the documents in
+//! parallel, and `Array::par_iter` decodes the values of an array in parallel.
+//!
+//! ### `tracing`
+//!
+//! When this optional dependency is enabled, `Object::record_fields` and `Object::record_span`
+//! record selected document fields into `tracing` visitors and spans.
+//!
+//! ### `unicode-normalization`
+//!
+//! When this optional dependency is enabled, `YasonBuf::parse_with_nfc_keys` normalizes keys to
+//! Unicode NFC form, `Object::get_normalized` looks up the NFC form of a key, and
+//! `NfcCollation` compares keys by their NFC forms.
+//!
+//! ### `tokio`
+//!
+//! When this optional dependency is enabled, `YasonBuf::parse_async` parses json incrementally
+//! as it is read from a tokio `AsyncRead`, and `Yason::format_to_async` writes json to a tokio
+//! `AsyncWrite` in chunks.
+//!
+//! ### `wasm`
+//!
+//! When this optional feature is enabled, the `wasm` module exports a `Yason` class to
+//! JavaScript through `wasm-bindgen`, which parses, formats and queries yason in the browser.
+//!
+//! ### `chrono`
+//!
+//! When this optional dependency is enabled, [`Timestamp`], [`Date`], [`ShortDate`] and [`Time`]
+//! convert from and into the date and time types of `chrono`, so they can be pushed and read
+//! without epoch arithmetic.
+//!
+//! ### `itoa`
+//!
+//! When this optional dependency is enabled, integral numbers are formatted with `itoa` instead of
+//! `core::fmt`, which is faster for documents with many integers. The output is the same.
+//!
+//! ### `metrics`
+//!
+//! When this optional feature is enabled, the `metrics` module calls an installed hook on hot
+//! paths, so that building, querying and formatting can be observed through counters.
+//!
+//! ## Thread safety
+//!
+//! [`YasonBuf`], [`Yason`] and every borrowed view of a yason, such as [`Object`], [`Array`],
+//! [`Value`], their iterators and [`ArrayChunk`], are `Send` and `Sync`, because they only hold
+//! bytes or shared references to bytes. So are [`PathExpression`], [`QueryContext`] and the
+//! builders. A [`LazyFormat`] is `Send` but not `Sync`, as it keeps the error of the last
+//! formatting, and so is a [`YasonIndex`], as it caches key tables on lookup. These guarantees are checked at compile time.
 //!
 //! ## Yason binary format
 //!
@@ -17,7 +78,9 @@
 //!     array-type |
 //!     scalar-type |
 //!
-//! object-type ::= 1
+//! object-type ::=
+//!     1 |     // object
+//!     24      // compact object, with 2-byte key offsets
 //! array-type ::= 2
 //! scalar-type ::=
 //!     3 |     // string
@@ -80,6 +143,11 @@
 //! interval-ym ::= int32
 //! interval-dt ::= int64
 //!
+//! // timestamp and date are microseconds since 1970-01-01T00:00:00 without time zone,
+//! // date is truncated to whole seconds, time is microseconds since midnight,
+//! // short-date is days since 1970-01-01 in the proleptic Gregorian calendar,
+//! // interval-ym is a number of months and interval-dt a number of microseconds
+//!
 //! data-length ::= uint8*  // If the high bit of a byte is 1,
 //!                         // the length field is continued in the next byte,
 //!                         // otherwise it is the last byte of the length field.
@@ -95,7 +163,7 @@
 //! size ::= int32  // size indicates total size of object or array
 //! element-count ::= uint16 // number of members in object or array
 //!
-//! key-offset ::= uint32
+//! key-offset ::= uint32 | uint16   // uint16 in compact objects, whose size is at most 65535
 //! key-value ::= key type value
 //! key ::= key-length uint8*
 //! key-length ::= uint16    // key length must be less than 64KB
@@ -109,6 +177,8 @@
 //! outlined-value ::= type value
 //! ```
 //!
+//! The sizes of the fields and the data-length encoding are exposed in the [`raw`] module.
+//!
 //! ## Usage
 //!
 //! ### `Scalar`
@@ -177,25 +247,96 @@
 //!
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
+// All multi-byte fields are read and written byte-wise, never through casted pointers.
+#![deny(clippy::cast_ptr_alignment)]
 
+pub mod analyze;
 mod binary;
+mod budget;
 mod builder;
+pub mod bulk;
+mod collation;
 mod data_type;
+mod error_code;
 mod format;
+pub mod frame;
 mod json;
+pub mod limits;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub mod metrics;
+pub mod ndjson;
+pub mod num;
 mod path;
+pub mod pipeline;
+pub mod raw;
+mod temporal;
+#[cfg(feature = "testkit")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testkit")))]
+pub mod testkit;
+pub mod transform;
 mod util;
 mod vec;
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub mod wasm;
 mod yason;
 
 #[cfg(feature = "serde")]
 mod serde;
 
+#[cfg(feature = "serde")]
+mod de;
+
+#[cfg(feature = "memmap2")]
+mod mmap;
+
+#[cfg(feature = "tracing")]
+mod trace;
+
+#[cfg(feature = "unicode-normalization")]
+mod normalize;
+
+#[cfg(feature = "tokio")]
+mod tokio_io;
+
 pub use self::{
-    builder::{ArrayBuilder, ArrayRefBuilder, BuildError, NumberError, ObjectBuilder, ObjectRefBuilder, Scalar},
+    budget::StepBudget,
+    builder::{
+        ArrBuilder, ArrBuilderDyn, ArrayBuilder, ArrayRefBuilder, BuildError, CapacityHint, DocumentTemplate,
+        EstimateSize, NumberConstraint, NumberError, ObjBuilder, ObjBuilderDyn, ObjectBuilder, ObjectPatchBuilder,
+        ObjectRefBuilder, Scalar, TryIntoNumber,
+    },
+    collation::{BinaryCollation, Collation},
     data_type::{DataType, InvalidDataType},
-    format::FormatError,
-    path::{PathExpression, PathParseError, QueriedValue},
-    yason::{Array, ArrayIter, KeyIter, Object, ObjectIter, Value, ValueIter, Yason, YasonBuf, YasonError},
+    error_code::{ErrorCode, InvalidErrorCode},
+    format::{
+        format_escaped_str, format_escaped_str_with, BinaryFormat, CompactFormatter, EscapeProfile, FormatError,
+        FormatResult, Formatter, LazyFormat, PrettyFormatter, TruncateOptions,
+    },
+    path::{
+        ArrayIndex, ArraySelector, ArraySelectors, DescendantOrder, EmptyReason, OnEmpty, OnError, PathExpression,
+        PathMethod, PathParseError, PathStep, PathSteps, PreparedPath, QueriedValue, QueryContext, QueryOutcome,
+        QueryResult, QueryResultIter, QueryStrategy,
+    },
+    temporal::{Date, DtInterval, ShortDate, Time, Timestamp, YmInterval},
+    util::{are_keys_sorted, key_cmp, sort_keys},
+    yason::{
+        Array, ArrayChunk, ArrayChunks, ArrayElement, ArrayIter, EntryInfo, InsertionOrderIter, KeyIter, LazyValue,
+        LintIssue, LintKind, Object, ObjectIter, RepairReport, TypedArrayIter, Value, ValueIter, Yason, YasonBuf,
+        YasonError, YasonIndex,
+    },
 };
 pub use decimal_rs::Decimal as Number;
+
+#[doc(hidden)]
+pub use self::builder::PushValue;
+
+#[cfg(feature = "serde")]
+pub use self::de::{from_yason_paths, DeserializeError, FieldMap};
+
+#[cfg(feature = "memmap2")]
+pub use self::mmap::MmapYason;
+
+#[cfg(feature = "unicode-normalization")]
+pub use self::normalize::NfcCollation;
@@ -0,0 +1,30 @@
+//! Limits of the encoding.
+//!
+//! Inputs can be checked against them before building, instead of failing in the middle of it. The
+//! nesting depth of a builder is returned by its `depth` method, e.g. [`ArrayBuilder::depth`].
+//!
+//! ```rust
+//! use yason::limits::{MAX_ELEMENT_COUNT, MAX_NESTED_DEPTH};
+//! use yason::{ArrayBuilder, BuildError, Value};
+//!
+//! let values = vec![Value::Null; MAX_ELEMENT_COUNT + 1];
+//! assert!(matches!(ArrayBuilder::from_values(&values), Err(BuildError::TooManyElements(_))));
+//!
+//! let mut builder = ArrayBuilder::try_new(1).unwrap();
+//! let nested = builder.push_array(0).unwrap();
+//! assert_eq!(nested.depth(), 2);
+//! assert!(nested.depth() < MAX_NESTED_DEPTH);
+//! ```
+//!
+//! [`ArrayBuilder::depth`]: crate::ArrayBuilder::depth
+
+pub use crate::binary::MAX_STRING_SIZE;
+
+/// Maximum nesting depth of objects and arrays, a top-level object or array is at depth 1.
+pub const MAX_NESTED_DEPTH: usize = 100;
+
+/// Maximum length of a key in bytes, the largest key length.
+pub const MAX_KEY_LENGTH: usize = u16::MAX as usize;
+
+/// Maximum number of key-values of an object or elements of an array.
+pub const MAX_ELEMENT_COUNT: usize = u16::MAX as usize;
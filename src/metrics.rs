@@ -0,0 +1,139 @@
+//! Hooks for exporting counters of the hot paths, such as the growth of building buffers, the
+//! values visited by path queries and the bytes written by formatting.
+//!
+//! The hooks are only called if the `metrics` feature is enabled, otherwise the calls are compiled
+//! out. A hook is installed once for the whole process by [`set_hook`].
+//!
+//! ```rust
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use yason::metrics::{self, MetricsHook};
+//! use yason::YasonBuf;
+//!
+//! struct Counters {
+//!     format_bytes: AtomicUsize,
+//! }
+//!
+//! impl MetricsHook for Counters {
+//!     fn on_format_bytes(&self, bytes: usize) {
+//!         self.format_bytes.fetch_add(bytes, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! static COUNTERS: Counters = Counters {
+//!     format_bytes: AtomicUsize::new(0),
+//! };
+//!
+//! metrics::set_hook(&COUNTERS).unwrap();
+//! let yason = YasonBuf::parse(r#"{"a": 1}"#).unwrap();
+//! assert_eq!(yason.format(false).to_string(), r#"{"a":1}"#);
+//! assert_eq!(COUNTERS.format_bytes.load(Ordering::Relaxed), 7);
+//! ```
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Receiver of the metrics of yason, all methods do nothing by default.
+///
+/// The methods are called on hot paths from any thread, they should be cheap, e.g. incrementing
+/// atomic counters.
+pub trait MetricsHook: Send + Sync {
+    /// Called when a buffer of building yason grows, with its new capacity in bytes.
+    #[inline]
+    fn on_build_reserve(&self, _capacity: usize) {}
+
+    /// Called for every value visited by a path query.
+    #[inline]
+    fn on_query_node_visited(&self) {}
+
+    /// Called when a yason has been formatted, with the number of bytes written.
+    #[inline]
+    fn on_format_bytes(&self, _bytes: usize) {}
+}
+
+/// The installed hook, a leaked box of the reference.
+static HOOK: AtomicPtr<&'static dyn MetricsHook> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs the hook of the process. Returns `SetHookError` if a hook has been installed.
+pub fn set_hook(hook: &'static dyn MetricsHook) -> Result<(), SetHookError> {
+    let new = Box::into_raw(Box::new(hook));
+    match HOOK.compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            // SAFETY: The box was not installed, it is only owned here.
+            drop(unsafe { Box::from_raw(new) });
+            Err(SetHookError(()))
+        }
+    }
+}
+
+#[inline]
+fn hook() -> Option<&'static dyn MetricsHook> {
+    let hook = HOOK.load(Ordering::Acquire);
+    // SAFETY: An installed hook is never freed.
+    unsafe { hook.as_ref() }.copied()
+}
+
+#[inline]
+pub(crate) fn build_reserve(capacity: usize) {
+    if let Some(hook) = hook() {
+        hook.on_build_reserve(capacity);
+    }
+}
+
+#[inline]
+pub(crate) fn query_node_visited() {
+    if let Some(hook) = hook() {
+        hook.on_query_node_visited();
+    }
+}
+
+/// A writer which reports the number of bytes written through it when it is dropped.
+pub(crate) struct CountingWriter<'a, W: fmt::Write> {
+    writer: &'a mut W,
+    bytes: usize,
+}
+
+impl<'a, W: fmt::Write> CountingWriter<'a, W> {
+    #[inline]
+    pub(crate) fn new(writer: &'a mut W) -> Self {
+        Self { writer, bytes: 0 }
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for CountingWriter<'_, W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.bytes += s.len();
+        self.writer.write_str(s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.bytes += c.len_utf8();
+        self.writer.write_char(c)
+    }
+}
+
+impl<W: fmt::Write> Drop for CountingWriter<'_, W> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(hook) = hook() {
+            hook.on_format_bytes(self.bytes);
+        }
+    }
+}
+
+/// The error returned by [`set_hook`] if a hook has been installed.
+#[derive(Debug)]
+pub struct SetHookError(());
+
+impl Display for SetHookError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "a metrics hook has been installed")
+    }
+}
+
+impl Error for SetHookError {}
@@ -0,0 +1,57 @@
+//! Yason over a memory-mapped file.
+
+use crate::yason::YasonResult;
+use crate::Yason;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+
+/// A yason backed by a read-only memory map, the mapped bytes are validated once when it is created.
+#[cfg_attr(docsrs, doc(cfg(feature = "memmap2")))]
+pub struct MmapYason {
+    mmap: Mmap,
+}
+
+impl MmapYason {
+    /// Creates a `MmapYason` from a memory map, validating the mapped bytes.
+    #[inline]
+    pub fn try_new(mmap: Mmap) -> YasonResult<Self> {
+        Yason::from_bytes(&mmap[..])?;
+        Ok(Self { mmap })
+    }
+
+    /// Maps the whole file and validates it as a yason. An invalid yason is reported as an
+    /// `io::ErrorKind::InvalidData` error.
+    ///
+    /// # Safety
+    ///
+    /// Callers should guarantee the file is not modified while it is mapped, see [`Mmap::map`].
+    #[inline]
+    pub unsafe fn map(file: &File) -> io::Result<Self> {
+        let mmap = Mmap::map(file)?;
+        Self::try_new(mmap).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Returns the underlying memory map.
+    #[inline]
+    pub fn into_inner(self) -> Mmap {
+        self.mmap
+    }
+}
+
+impl Deref for MmapYason {
+    type Target = Yason;
+
+    #[inline]
+    fn deref(&self) -> &Yason {
+        unsafe { Yason::new_unchecked(&self.mmap[..]) }
+    }
+}
+
+impl AsRef<Yason> for MmapYason {
+    #[inline]
+    fn as_ref(&self) -> &Yason {
+        self
+    }
+}
@@ -0,0 +1,125 @@
+//! Conversion between NDJSON (JSON Lines) streams and yason.
+//!
+//! ```rust
+//! use yason::ndjson;
+//!
+//! let input = "{\"a\": 1}\n\n[true, null]\n";
+//! let docs = ndjson::read(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+//! assert_eq!(docs.len(), 2);
+//!
+//! let mut output = Vec::new();
+//! ndjson::write(&mut output, docs.iter().map(|doc| doc.as_ref())).unwrap();
+//! assert_eq!(output, b"{\"a\":1}\n[true,null]\n");
+//! ```
+
+use crate::{BuildError, FormatError, Yason, YasonBuf};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, Write};
+
+/// Possible errors that can arise when reading or writing NDJSON.
+#[derive(Debug)]
+pub enum NdjsonError {
+    IoError(io::Error),
+    /// A line could not be parsed, `line` is 1-based.
+    BuildError {
+        line: usize,
+        error: BuildError,
+    },
+    FormatError(FormatError),
+}
+
+impl Display for NdjsonError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            NdjsonError::IoError(e) => write!(f, "{}", e),
+            NdjsonError::BuildError { line, error } => write!(f, "line {}: {}", line, error),
+            NdjsonError::FormatError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for NdjsonError {}
+
+impl From<io::Error> for NdjsonError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        NdjsonError::IoError(e)
+    }
+}
+
+impl From<FormatError> for NdjsonError {
+    #[inline]
+    fn from(e: FormatError) -> Self {
+        NdjsonError::FormatError(e)
+    }
+}
+
+pub type NdjsonResult<T> = std::result::Result<T, NdjsonError>;
+
+/// Iterator over the documents of an NDJSON stream, created by [`read`].
+pub struct Reader<R: BufRead> {
+    reader: R,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = NdjsonResult<YasonBuf>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.line_number += 1,
+                Err(e) => return Some(Err(NdjsonError::IoError(e))),
+            }
+
+            // blank lines are skipped
+            let line = self.line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let line_number = self.line_number;
+            return Some(YasonBuf::parse(line).map_err(|error| NdjsonError::BuildError {
+                line: line_number,
+                error,
+            }));
+        }
+    }
+}
+
+/// Parses an NDJSON stream into an iterator of `YasonBuf`, one for each non-blank line.
+#[inline]
+pub fn read<R: BufRead>(reader: R) -> Reader<R> {
+    Reader {
+        reader,
+        line: String::new(),
+        line_number: 0,
+    }
+}
+
+/// Writes the documents as NDJSON, each of them is formatted as compact JSON followed by `\n`.
+///
+/// Returns the number of written documents.
+#[inline]
+pub fn write<'a, W, I>(writer: &mut W, docs: I) -> NdjsonResult<usize>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Yason>,
+{
+    let mut buf = String::new();
+    let mut count = 0;
+    for doc in docs {
+        buf.clear();
+        doc.format_to(false, &mut buf)?;
+        buf.push('\n');
+        writer.write_all(buf.as_bytes())?;
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,67 @@
+//! Unicode normalization of keys.
+
+use crate::collation::Collation;
+use crate::yason::YasonResult;
+use crate::{Object, Value, YasonError};
+use std::cmp::Ordering;
+use std::collections::TryReserveError;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// A collation which compares strings by their Unicode NFC forms, so that e.g. a composed `é`
+/// (U+00E9) equals `e` followed by a combining acute accent (U+0065 U+0301).
+///
+/// Strings with equal NFC forms are ordered by their code points after normalization, which is
+/// the binary order of the normalized strings.
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NfcCollation;
+
+impl Collation for NfcCollation {
+    #[inline]
+    fn compare(&self, left: &str, right: &str) -> Ordering {
+        if is_nfc_quick(left.chars()) == IsNormalized::Yes && is_nfc_quick(right.chars()) == IsNormalized::Yes {
+            return left.cmp(right);
+        }
+        left.nfc().cmp(right.nfc())
+    }
+}
+
+impl<'a> Object<'a> {
+    /// Returns the value corresponding to the NFC form of the key, if it exists.
+    ///
+    /// This is a binary search like [`get`], which finds the key only if the keys of the object
+    /// are stored in NFC form, e.g. built by [`YasonBuf::parse_with_nfc_keys`]. For objects with
+    /// keys in other forms use [`get_with_collation`] with [`NfcCollation`], which scans the keys.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let yason = YasonBuf::parse_with_nfc_keys("{\"caf\u{0065}\u{0301}\": 1}").unwrap();
+    /// let object = yason.object().unwrap();
+    /// assert!(object.get("caf\u{00e9}").unwrap().is_some());
+    /// assert!(object.get_normalized("caf\u{0065}\u{0301}").unwrap().is_some());
+    /// ```
+    ///
+    /// [`get`]: Object::get
+    /// [`get_with_collation`]: Object::get_with_collation
+    /// [`YasonBuf::parse_with_nfc_keys`]: crate::YasonBuf::parse_with_nfc_keys
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    #[inline]
+    pub fn get_normalized<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<Value<'a>>> {
+        let mut buf = String::new();
+        let key = nfc_key(key.as_ref(), &mut buf).map_err(YasonError::TryReserveError)?;
+        self.get(key)
+    }
+}
+
+/// Returns the NFC form of the key, which is written into `buf` unless the key is already in NFC form.
+#[inline]
+pub(crate) fn nfc_key<'k>(key: &'k str, buf: &'k mut String) -> Result<&'k str, TryReserveError> {
+    if is_nfc_quick(key.chars()) == IsNormalized::Yes {
+        return Ok(key);
+    }
+    buf.clear();
+    buf.try_reserve(key.len())?;
+    buf.extend(key.nfc());
+    Ok(buf)
+}
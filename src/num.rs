@@ -0,0 +1,103 @@
+//! Numeric comparison and coercion rules.
+//!
+//! Yason stores every number as a decimal [`Number`], integers and floats provided by callers are
+//! coerced to decimals by the rules of this module. Numbers are compared by value regardless of
+//! their scale, so `1`, `1.0` and `1.00` are equal. All comparisons between numbers in yason, such
+//! as [`Yason::equals`] and [`Yason::compare`], follow these rules.
+//!
+//! [`Yason::equals`]: crate::Yason::equals
+//! [`Yason::compare`]: crate::Yason::compare
+
+use crate::Number;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Compares two numbers by value.
+#[inline]
+pub fn compare(left: &Number, right: &Number) -> Ordering {
+    left.cmp(right)
+}
+
+/// Returns whether two numbers have the same value.
+#[inline]
+pub fn equals(left: &Number, right: &Number) -> bool {
+    compare(left, right) == Ordering::Equal
+}
+
+/// Coerces an `i64` to a number, which is always exact.
+#[inline]
+pub fn from_i64(value: i64) -> Number {
+    Number::from(value)
+}
+
+/// Coerces a `u64` to a number, which is always exact.
+#[inline]
+pub fn from_u64(value: u64) -> Number {
+    Number::from(value)
+}
+
+/// Coerces an `f64` to a number.
+///
+/// The shortest decimal representation that round-trips to the same `f64` is used, so `0.1f64`
+/// becomes `0.1` instead of its exact binary expansion. Returns `None` if the value is NaN, infinite,
+/// or out of the range of a number.
+#[inline]
+pub fn from_f64(value: f64) -> Option<Number> {
+    if !value.is_finite() {
+        return None;
+    }
+    Number::from_str(&format!("{:e}", value)).ok()
+}
+
+/// Compares a number with an `i64`.
+#[inline]
+pub fn compare_i64(left: &Number, right: i64) -> Ordering {
+    compare(left, &from_i64(right))
+}
+
+/// Compares a number with an `f64`, returns `None` if the `f64` is NaN.
+///
+/// Infinities and finite values out of the range of a number are ordered by their sign and magnitude.
+#[inline]
+pub fn compare_f64(left: &Number, right: f64) -> Option<Ordering> {
+    if right.is_nan() {
+        return None;
+    }
+
+    if let Some(right) = from_f64(right) {
+        return Some(compare(left, &right));
+    }
+
+    if right.abs() >= 1.0 {
+        // overflow or infinity, greater than any number in magnitude
+        return Some(if right > 0.0 { Ordering::Less } else { Ordering::Greater });
+    }
+
+    // underflow, between zero and any non-zero number in magnitude
+    Some(match compare(left, &Number::ZERO) {
+        Ordering::Equal => {
+            if right > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }
+        ord => ord,
+    })
+}
+
+/// Converts a number to an `i64`, returns `None` if the number has a fractional part or is out of
+/// the range of an `i64`.
+#[inline]
+pub fn to_i64(value: &Number) -> Option<i64> {
+    if !equals(&value.round(0), value) {
+        return None;
+    }
+    i64::try_from(value).ok()
+}
+
+/// Converts a number to the nearest `f64`.
+#[inline]
+pub fn to_f64(value: &Number) -> f64 {
+    f64::from(value)
+}
@@ -0,0 +1,202 @@
+//! Reusable buffers of path expression queries.
+
+use crate::path::{OnEmpty, OnError, PathExpression, QueriedValue, QueryOutcome};
+use crate::yason::YasonResult;
+use crate::{StepBudget, Value, Yason};
+use std::fmt;
+
+/// This type owns the query buffer and the result buffer of path expression queries, so that the
+/// allocations can be reused across queries of different documents.
+///
+/// The queried values borrow the documents of lifetime `'a`. A context can be kept in a pool, e.g.
+/// one per connection, as a `QueryContext<'static>` and turned into a context for the documents at
+/// hand by [`recycle`], which keeps the allocations. Results returned by a query borrow the context
+/// until they are dropped, and are cleared by the next query.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use yason::{PathExpression, QueryContext, YasonBuf};
+///
+/// let mut pooled = QueryContext::new();
+/// let path = PathExpression::from_str("$.a[*]").unwrap();
+/// for (json, expected) in [(r#"{"a": [1, 2]}"#, "[1,2]"), (r#"{"a": [3]}"#, "[3]")] {
+///     let yason = YasonBuf::parse(json).unwrap();
+///     let mut ctx = pooled.recycle();
+///     let mut result = String::new();
+///     ctx.query(&path, &yason, true).unwrap().format_to(false, &mut result).unwrap();
+///     assert_eq!(result, expected);
+///     pooled = ctx.recycle();
+/// }
+/// ```
+///
+/// [`recycle`]: QueryContext::recycle
+#[derive(Default)]
+pub struct QueryContext<'a> {
+    query_buf: Vec<Value<'a>>,
+    result_buf: Vec<u8>,
+}
+
+impl<'a> QueryContext<'a> {
+    /// Creates an empty `QueryContext`, no memory is allocated until the first query.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            query_buf: Vec::new(),
+            result_buf: Vec::new(),
+        }
+    }
+
+    /// Creates a `QueryContext` with room for `values` queried values and `bytes` bytes of result.
+    #[inline]
+    pub fn with_capacity(values: usize, bytes: usize) -> Self {
+        Self {
+            query_buf: Vec::with_capacity(values),
+            result_buf: Vec::with_capacity(bytes),
+        }
+    }
+
+    /// Returns the number of values the query buffer can hold without reallocating.
+    #[inline]
+    pub fn values_capacity(&self) -> usize {
+        self.query_buf.capacity()
+    }
+
+    /// Returns the number of bytes the result buffer can hold without reallocating.
+    #[inline]
+    pub fn bytes_capacity(&self) -> usize {
+        self.result_buf.capacity()
+    }
+
+    /// Clears both buffers and returns them as a context for documents of another lifetime, keeping
+    /// the allocations.
+    #[inline]
+    pub fn recycle<'c>(mut self) -> QueryContext<'c> {
+        self.query_buf.clear();
+        self.result_buf.clear();
+        QueryContext {
+            // The buffer is empty and `Value` has the same layout for any lifetime, so the values
+            // are collected in place into the same allocation.
+            query_buf: self.query_buf.into_iter().map(|_| unreachable!()).collect(),
+            result_buf: self.result_buf,
+        }
+    }
+
+    /// Clears both buffers and shrinks them to at most the given capacities, e.g. before returning
+    /// the context to a pool after an unusually large query.
+    #[inline]
+    pub fn shrink_to(&mut self, values: usize, bytes: usize) {
+        self.query_buf.clear();
+        self.query_buf.shrink_to(values);
+        self.result_buf.clear();
+        self.result_buf.shrink_to(bytes);
+    }
+
+    /// Selects and returns one or more values like [`PathExpression::query`] with both buffers
+    /// provided, so results with array wrapper are returned as `QueriedValue::Yason`.
+    #[inline]
+    pub fn query<'b>(
+        &'b mut self,
+        path: &PathExpression,
+        yason: &'a Yason,
+        with_wrapper: bool,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        Ok(self.query_outcome(path, yason, with_wrapper)?.value)
+    }
+
+    /// Selects and returns one or more values like [`PathExpression::query`] with only the query
+    /// buffer provided, so results with array wrapper are returned as `QueriedValue::ValuesRef`.
+    #[inline]
+    pub fn query_values<'b>(
+        &'b mut self,
+        path: &PathExpression,
+        yason: &'a Yason,
+        with_wrapper: bool,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        path.query(yason, with_wrapper, Some(self.query_buf()), None)
+    }
+
+    /// Selects and returns one or more values like [`PathExpression::query_outcome`].
+    #[inline]
+    pub fn query_outcome<'b>(
+        &'b mut self,
+        path: &PathExpression,
+        yason: &'a Yason,
+        with_wrapper: bool,
+    ) -> YasonResult<QueryOutcome<'a, 'b>> {
+        let (query_buf, result_buf) = self.bufs();
+        path.query_outcome(yason, with_wrapper, Some(query_buf), Some(result_buf))
+    }
+
+    /// Selects and returns one or more values like [`PathExpression::query_with_clauses`].
+    #[inline]
+    pub fn query_with_clauses<'b>(
+        &'b mut self,
+        path: &PathExpression,
+        yason: &'a Yason,
+        with_wrapper: bool,
+        on_empty: OnEmpty<'b>,
+        on_error: OnError<'b>,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        let (query_buf, result_buf) = self.bufs();
+        path.query_with_clauses(
+            yason,
+            with_wrapper,
+            on_empty,
+            on_error,
+            Some(query_buf),
+            Some(result_buf),
+        )
+    }
+
+    /// Returns whether the path expression matches one or more values like
+    /// [`PathExpression::exists`].
+    #[inline]
+    pub fn exists(&mut self, path: &PathExpression, yason: &'a Yason) -> YasonResult<bool> {
+        self.exists_with_budget(path, yason, &mut StepBudget::unlimited())
+    }
+
+    /// Returns whether the path expression matches one or more values like
+    /// [`PathExpression::exists_with_budget`].
+    #[inline]
+    pub fn exists_with_budget(
+        &mut self,
+        path: &PathExpression,
+        yason: &'a Yason,
+        budget: &mut StepBudget,
+    ) -> YasonResult<bool> {
+        path.check_exists()?;
+        path.exists_in(yason, self.query_buf(), budget)
+    }
+
+    #[inline]
+    fn query_buf(&mut self) -> &mut Vec<Value<'a>> {
+        self.query_buf.clear();
+        &mut self.query_buf
+    }
+
+    #[inline]
+    fn bufs(&mut self) -> (&mut Vec<Value<'a>>, &mut Vec<u8>) {
+        self.query_buf.clear();
+        self.result_buf.clear();
+        (&mut self.query_buf, &mut self.result_buf)
+    }
+}
+
+impl fmt::Debug for QueryContext<'_> {
+    // The buffered values are left by the last query, only the capacities are shown.
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryContext")
+            .field("values_capacity", &self.values_capacity())
+            .field("bytes_capacity", &self.bytes_capacity())
+            .finish()
+    }
+}
+
+impl Clone for QueryContext<'_> {
+    /// Returns a new empty context with the same capacities, the buffered values are not cloned.
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::with_capacity(self.values_capacity(), self.bytes_capacity())
+    }
+}
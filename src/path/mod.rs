@@ -1,20 +1,34 @@
 //! Path Expression.
 
-use crate::path::parse::{FuncStep, PathParser, Step};
+use crate::path::parse::{ArrayStep, FuncStep, ObjectStep, PathParser, SingleIndex, SingleStep, Step};
+use std::collections::HashSet;
 use std::fmt;
+use std::ops::Range;
 use std::str::FromStr;
 
-use crate::yason::YasonResult;
-use crate::{ArrayRefBuilder, DataType, Number, Value, Yason, YasonError};
+use crate::yason::{YasonIndex, YasonResult};
+use crate::{
+    ArrayRefBuilder, DataType, Date, DtInterval, Number, ShortDate, StepBudget, Time, Timestamp, Value, Yason,
+    YasonBuf, YasonError, YmInterval,
+};
 
 use crate::format::{CompactFormatter, FormatResult, Formatter, PrettyFormatter};
 use crate::path::query::Selector;
+pub use context::QueryContext;
 pub use parse::PathParseError;
+pub use result::{QueryResult, QueryResultIter};
+pub use steps::{ArrayIndex, ArraySelector, ArraySelectors, PathMethod, PathStep, PathSteps};
 
+mod context;
 mod parse;
 mod query;
+mod result;
+mod steps;
+mod typed;
+mod vars;
 
 /// This type represents result returned by a path expression.
+#[non_exhaustive]
 pub enum QueriedValue<'a, 'b> {
     /// Result returned when no WITH WRAPPER is specified and there is not query result.
     None,
@@ -30,6 +44,9 @@ pub enum QueriedValue<'a, 'b> {
 
     /// Result returned when the user provides a result buffer, whether or not WITH WRAPPER is specified and the query buffer is provided.  
     Yason(&'b Yason),
+
+    /// Result returned by [`PathExpression::query_owned`] when WITH WRAPPER is specified, which owns the encoded values.
+    YasonOwned(YasonBuf),
 }
 
 impl<'a, 'b> QueriedValue<'a, 'b> {
@@ -42,6 +59,89 @@ impl<'a, 'b> QueriedValue<'a, 'b> {
             QueriedValue::Values(values) => values_format_to(values, pretty, writer),
             QueriedValue::ValuesRef(values) => values_format_to(values, pretty, writer),
             QueriedValue::Yason(yason) => yason.format_to(pretty, writer),
+            QueriedValue::YasonOwned(yason) => yason.format_to(pretty, writer),
+        }
+    }
+}
+
+/// The reason why a path expression returns no result.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EmptyReason {
+    /// The path matched nothing, e.g. a key step whose key does not exist or an array index out of bounds.
+    NoMatch,
+    /// A step was applied to a value of an unexpected type, e.g. an object step applied to a scalar.
+    TypeMismatch,
+}
+
+/// This type represents result returned by a path expression together with the reason of an empty result,
+/// which allows callers to implement ON EMPTY / ON ERROR semantics.
+pub struct QueryOutcome<'a, 'b> {
+    /// The queried value.
+    pub value: QueriedValue<'a, 'b>,
+    /// The reason why `value` is `QueriedValue::None`, it is `None` if the result is not empty.
+    pub empty_reason: Option<EmptyReason>,
+}
+
+impl<'a, 'b> QueryOutcome<'a, 'b> {
+    #[inline]
+    const fn new(value: QueriedValue<'a, 'b>, type_mismatch: bool) -> Self {
+        let empty_reason = match value {
+            QueriedValue::None if type_mismatch => Some(EmptyReason::TypeMismatch),
+            QueriedValue::None => Some(EmptyReason::NoMatch),
+            _ => None,
+        };
+        Self { value, empty_reason }
+    }
+
+    /// Returns true if the path expression returns no result.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.empty_reason.is_some()
+    }
+}
+
+/// Behavior of [`PathExpression::query_with_clauses`] when the path expression returns no result,
+/// corresponding to the `ON EMPTY` clause of SQL/JSON.
+#[derive(Copy, Clone, Debug)]
+pub enum OnEmpty<'b> {
+    /// `NULL ON EMPTY`: returns a null value.
+    Null,
+    /// `DEFAULT <value> ON EMPTY`: returns the given value.
+    Default(&'b Yason),
+    /// `ERROR ON EMPTY`: returns `YasonError::EmptyQueryResult`.
+    Error,
+}
+
+/// Behavior of [`PathExpression::query_with_clauses`] when an error occurs during querying,
+/// corresponding to the `ON ERROR` clause of SQL/JSON.
+#[derive(Copy, Clone, Debug)]
+pub enum OnError<'b> {
+    /// `NULL ON ERROR`: returns a null value.
+    Null,
+    /// `DEFAULT <value> ON ERROR`: returns the given value.
+    Default(&'b Yason),
+    /// `ERROR ON ERROR`: returns the error.
+    Error,
+}
+
+impl<'b> OnEmpty<'b> {
+    #[inline]
+    fn apply<'a>(self) -> YasonResult<QueriedValue<'a, 'b>> {
+        match self {
+            OnEmpty::Null => Ok(QueriedValue::Value(Value::Null)),
+            OnEmpty::Default(yason) => Ok(QueriedValue::Yason(yason)),
+            OnEmpty::Error => Err(YasonError::EmptyQueryResult),
+        }
+    }
+}
+
+impl<'b> OnError<'b> {
+    #[inline]
+    fn apply<'a>(self, err: YasonError) -> YasonResult<QueriedValue<'a, 'b>> {
+        match self {
+            OnError::Null => Ok(QueriedValue::Value(Value::Null)),
+            OnError::Default(yason) => Ok(QueriedValue::Yason(yason)),
+            OnError::Error => Err(err),
         }
     }
 }
@@ -56,7 +156,7 @@ impl<'a> AsMut<Vec<Value<'a>>> for QueryBuf<'a, '_> {
     fn as_mut(&mut self) -> &mut Vec<Value<'a>> {
         match self {
             QueryBuf::Owned(buf) => buf,
-            QueryBuf::Borrowed(buf) => *buf,
+            QueryBuf::Borrowed(buf) => buf,
         }
     }
 }
@@ -66,46 +166,243 @@ impl<'a> AsRef<[Value<'a>]> for QueryBuf<'a, '_> {
     fn as_ref(&self) -> &[Value<'a>] {
         match self {
             QueryBuf::Owned(buf) => buf,
-            QueryBuf::Borrowed(buf) => *buf,
+            QueryBuf::Borrowed(buf) => buf,
         }
     }
 }
 
 /// This type represents a path expression.
+///
+/// A key step is either quoted, e.g. `$."first name"`, or unquoted. An unquoted key starts with a
+/// letter, `_` or `$`, followed by letters, digits, `_` and `$`, where letters and digits also
+/// include non-ASCII alphabetic and numeric characters, e.g. `$.foo_bar` or `$.名前`. Other keys,
+/// such as keys containing spaces or escape sequences, must be quoted.
 #[derive(Debug)]
-#[repr(transparent)]
-pub struct PathExpression(Vec<Step>);
+pub struct PathExpression {
+    steps: Vec<Step>,
+    descendant_order: DescendantOrder,
+    strategy: QueryStrategy,
+}
+
+/// A path expression parsed once and reused for many documents, e.g. with the typed accessors
+/// [`PathExpression::get_str`], [`PathExpression::get_i64`] and [`PathExpression::get_bool`].
+pub type PreparedPath = PathExpression;
+
+/// The order in which the descendant steps of a path expression, e.g. `$..name`, select values.
+///
+/// In every order, the values of an object are visited in the order of their keys, i.e. shorter
+/// keys first and keys of the same length in byte order, and array elements in index order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DescendantOrder {
+    /// Depth-first, parents first: the value of the key in an object is selected before the
+    /// matches inside the values of the object. This is the default.
+    PreOrder,
+    /// Depth-first, deepest first: the matches inside the values of an object are selected before
+    /// the value of the key in the object.
+    PostOrder,
+    /// Breadth-first: matches nested in fewer objects and arrays are selected before deeper ones.
+    BreadthFirst,
+}
+
+impl Default for DescendantOrder {
+    #[inline]
+    fn default() -> Self {
+        DescendantOrder::PreOrder
+    }
+}
+
+/// How many values a query without array wrapper looks for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum QueryStrategy {
+    /// Visits every match, so that more than one selected value is reported as
+    /// `YasonError::MultiValuesWithoutWrapper`. This is the default.
+    AllMatches,
+    /// Stops the traversal after the first selected value, e.g. without scanning the rest of the
+    /// document for a descendant step. More matches are not reported as an error.
+    FirstMatch,
+}
+
+impl Default for QueryStrategy {
+    #[inline]
+    fn default() -> Self {
+        QueryStrategy::AllMatches
+    }
+}
 
 impl PathExpression {
     #[inline]
     fn new(steps: Vec<Step>) -> Self {
-        Self(steps)
+        Self {
+            steps,
+            descendant_order: DescendantOrder::default(),
+            strategy: QueryStrategy::default(),
+        }
+    }
+
+    /// Sets the order in which descendant steps select values, see [`DescendantOrder`].
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{DescendantOrder, PathExpression, QueriedValue, YasonBuf};
+    ///
+    /// let yason = YasonBuf::parse(r#"{"a": {"a": {"a": 1}}}"#).unwrap();
+    /// let path = PathExpression::from_str("$..a").unwrap().with_descendant_order(DescendantOrder::PostOrder);
+    /// let mut result = Vec::new();
+    /// let values = path.query(&yason, true, None, Some(&mut result)).unwrap();
+    /// assert!(matches!(values, QueriedValue::Yason(yason) if yason.format(false).to_string() == r#"[1,{"a":1},{"a":{"a":1}}]"#));
+    /// ```
+    #[inline]
+    pub fn with_descendant_order(mut self, order: DescendantOrder) -> Self {
+        self.descendant_order = order;
+        self
+    }
+
+    /// Returns the order in which descendant steps select values.
+    #[inline]
+    pub const fn descendant_order(&self) -> DescendantOrder {
+        self.descendant_order
+    }
+
+    /// Sets how many values a query without array wrapper looks for, see [`QueryStrategy`].
+    /// Queries with array wrapper always select every match.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{Number, PathExpression, QueriedValue, QueryStrategy, Value, YasonBuf};
+    ///
+    /// let yason = YasonBuf::parse(r#"{"a": {"b": 1}, "c": {"b": 2}}"#).unwrap();
+    /// let path = PathExpression::from_str("$..b").unwrap();
+    /// assert!(path.query(&yason, false, None, None).is_err());
+    ///
+    /// let path = path.with_strategy(QueryStrategy::FirstMatch);
+    /// let value = path.query(&yason, false, None, None).unwrap();
+    /// assert!(matches!(value, QueriedValue::Value(Value::Number(n)) if n == Number::from(1)));
+    /// ```
+    #[inline]
+    pub fn with_strategy(mut self, strategy: QueryStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Returns how many values a query without array wrapper looks for.
+    #[inline]
+    pub const fn strategy(&self) -> QueryStrategy {
+        self.strategy
+    }
+
+    /// Returns the maximum number of values to select, after the offset.
+    #[inline]
+    fn select_limit(&self, with_wrapper: bool, limit: usize) -> usize {
+        match self.strategy {
+            QueryStrategy::FirstMatch if !with_wrapper => limit.min(1),
+            _ => limit,
+        }
     }
 }
 
 impl PathExpression {
     #[inline]
-    fn steps(&self) -> &[Step] {
-        &self.0
+    fn inner_steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns the steps of the path expression, starting with the root, i.e. `$` or a variable.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{ArrayIndex, ArraySelector, PathExpression, PathMethod, PathStep};
+    ///
+    /// let path = PathExpression::from_str("$.a[1, last].count()").unwrap();
+    /// let steps: Vec<_> = path.steps().collect();
+    /// assert_eq!(steps.len(), 4);
+    /// assert_eq!(steps[1], PathStep::Key("a"));
+    /// let selectors: Vec<_> = match steps[2].clone() {
+    ///     PathStep::Array(selectors) => selectors.collect(),
+    ///     _ => unreachable!(),
+    /// };
+    /// assert_eq!(
+    ///     selectors,
+    ///     [ArraySelector::Single(ArrayIndex::Index(1)), ArraySelector::Single(ArrayIndex::Last(0))]
+    /// );
+    /// assert_eq!(steps[3], PathStep::Method(PathMethod::Count));
+    /// ```
+    #[inline]
+    pub fn steps(&self) -> PathSteps<'_> {
+        PathSteps::new(&self.steps)
+    }
+
+    /// Returns the number of steps of the path expression, including the root.
+    #[inline]
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns whether the path expression contains a wildcard step, i.e. `.*` or `[*]`.
+    #[inline]
+    pub fn has_wildcard(&self) -> bool {
+        self.steps.iter().any(|step| {
+            matches!(
+                step,
+                Step::Object(ObjectStep::Wildcard) | Step::Array(ArrayStep::Wildcard)
+            )
+        })
+    }
+
+    /// Returns whether the path expression contains a descendant step, e.g. `..key`.
+    #[inline]
+    pub fn has_descendant(&self) -> bool {
+        self.steps.iter().any(|step| matches!(step, Step::Descendent(_)))
+    }
+
+    /// Returns the greatest array index written in the path expression, including the ends of
+    /// ranges, or `None` if there is none.
+    ///
+    /// Indexes counted from the last element, index variables and wildcards are not taken into
+    /// account, as they depend on the queried value or the bound variables.
+    #[inline]
+    pub fn max_array_index(&self) -> Option<usize> {
+        fn single(index: &SingleIndex) -> Option<usize> {
+            match index {
+                SingleIndex::Index(index) => Some(*index),
+                _ => None,
+            }
+        }
+
+        let mut max = None;
+        for step in self.steps.iter() {
+            let index = match step {
+                Step::Array(ArrayStep::Index(index)) => Some(*index),
+                Step::Array(ArrayStep::Range(begin, end)) => single(begin).max(single(end)),
+                Step::Array(ArrayStep::Multiple(steps)) => steps
+                    .iter()
+                    .filter_map(|step| match step {
+                        SingleStep::Single(index) => single(index),
+                        SingleStep::Range(begin, end) => single(begin).max(single(end)),
+                    })
+                    .max(),
+                _ => None,
+            };
+            max = max.max(index);
+        }
+        max
     }
 
     /// Returns whether an item method exists in path expression.
     #[inline]
     pub fn has_method(&self) -> bool {
-        let len = self.0.len();
+        let len = self.steps.len();
         if len <= 1 {
             return false;
         }
-        matches!(self.steps()[len - 1], Step::Func(_))
+        matches!(self.inner_steps()[len - 1], Step::Func(_))
     }
 
     #[inline]
-    fn has_method_count(&self) -> bool {
-        let len = self.0.len();
-        if len <= 1 {
-            return false;
+    fn method(&self) -> Option<&FuncStep> {
+        match self.steps.split_last() {
+            Some((Step::Func(func), steps)) if !steps.is_empty() => Some(func),
+            _ => None,
         }
-        matches!(self.0[len - 1], Step::Func(FuncStep::Count))
     }
 
     /// Selects and returns one or more values according to the path expression.
@@ -117,6 +414,176 @@ impl PathExpression {
         query_buf: Option<&'b mut Vec<Value<'a>>>,
         result_buf: Option<&'b mut Vec<u8>>,
     ) -> YasonResult<QueriedValue<'a, 'b>> {
+        Ok(self.query_outcome(yason, with_wrapper, query_buf, result_buf)?.value)
+    }
+
+    /// Selects and returns one or more values according to the path expression like [`query`],
+    /// with the values of an array wrapper encoded into a new [`YasonBuf`], which does not borrow
+    /// any buffer of the caller, e.g. to keep the result across an `.await` while the buffers are
+    /// reused.
+    ///
+    /// Returns `QueriedValue::YasonOwned` if WITH WRAPPER is specified and any value is selected,
+    /// and `QueriedValue::Value` or `QueriedValue::None` otherwise.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{PathExpression, QueriedValue, YasonBuf};
+    ///
+    /// let yason = YasonBuf::parse(r#"{"a": [1, 2, 3]}"#).unwrap();
+    /// let path = PathExpression::from_str("$.a[1 to 2]").unwrap();
+    /// let result = match path.query_owned(&yason, true).unwrap() {
+    ///     QueriedValue::YasonOwned(result) => result,
+    ///     _ => unreachable!(),
+    /// };
+    /// assert_eq!(result.format(false).to_string(), "[2,3]");
+    /// ```
+    ///
+    /// [`query`]: PathExpression::query
+    #[inline]
+    pub fn query_owned<'a>(&self, yason: &'a Yason, with_wrapper: bool) -> YasonResult<QueriedValue<'a, 'a>> {
+        match self.query(yason, with_wrapper, None, None)? {
+            QueriedValue::Values(values) => {
+                let mut bytes = Vec::new();
+                values_to_yason(&values, &mut bytes)?;
+                Ok(QueriedValue::YasonOwned(unsafe { YasonBuf::new_unchecked(bytes) }))
+            }
+            value => Ok(value),
+        }
+    }
+
+    /// Selects and returns one or more values according to the path expression like [`query`],
+    /// and reports why the result is empty if nothing is returned.
+    ///
+    /// [`query`]: PathExpression::query
+    #[inline]
+    pub fn query_outcome<'a, 'b>(
+        &self,
+        yason: &'a Yason,
+        with_wrapper: bool,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+    ) -> YasonResult<QueryOutcome<'a, 'b>> {
+        self.check_bound()?;
+        self.select(yason, with_wrapper, query_buf, result_buf, &mut StepBudget::unlimited())
+    }
+
+    /// Selects and returns one or more values according to the path expression like [`query`],
+    /// returning `YasonError::Cancelled` once the visited values exceed the budget.
+    ///
+    /// [`query`]: PathExpression::query
+    #[inline]
+    pub fn query_with_budget<'a, 'b>(
+        &self,
+        yason: &'a Yason,
+        with_wrapper: bool,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+        budget: &mut StepBudget,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        self.check_bound()?;
+        Ok(self.select(yason, with_wrapper, query_buf, result_buf, budget)?.value)
+    }
+
+    /// Selects and returns one or more values of the indexed yason like [`query`], looking up the
+    /// keys of objects through the key tables cached by the index, see [`YasonIndex`].
+    ///
+    /// [`query`]: PathExpression::query
+    #[inline]
+    pub fn query_indexed<'a, 'b>(
+        &self,
+        index: &YasonIndex<'a>,
+        with_wrapper: bool,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        self.check_bound()?;
+        let mut budget = StepBudget::unlimited();
+        let outcome = self.select_with_limit(
+            index.yason(),
+            Some(index),
+            with_wrapper,
+            query_buf,
+            result_buf,
+            &mut budget,
+            0,
+            usize::MAX,
+        )?;
+        Ok(outcome.value)
+    }
+
+    /// Selects and returns the values according to the path expression like [`query`], skipping
+    /// the first `offset` values and returning at most `limit` values.
+    ///
+    /// The traversal stops as soon as `limit` values are selected, so the first values of a
+    /// wildcard or descendant query on a large document are selected without visiting the rest of
+    /// it. Without array wrapper, at most one value can be returned, e.g. with `limit` 1 the first
+    /// selected value is returned instead of `YasonError::MultiValuesWithoutWrapper`.
+    ///
+    /// Returns `YasonError::InvalidPathExpression` if the path expression has an item method,
+    /// which applies to all the selected values.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{PathExpression, QueriedValue, YasonBuf};
+    ///
+    /// let yason = YasonBuf::parse(r#"[{"a": 1}, {"a": 2}, {"a": 3}, {"a": 4}]"#).unwrap();
+    /// let path = PathExpression::from_str("$[*].a").unwrap();
+    /// let mut result = Vec::new();
+    /// let page = path.query_with_limit(&yason, true, 1, 2, None, Some(&mut result)).unwrap();
+    /// assert!(matches!(page, QueriedValue::Yason(yason) if yason.format(false).to_string() == "[2,3]"));
+    /// ```
+    ///
+    /// [`query`]: PathExpression::query
+    #[inline]
+    pub fn query_with_limit<'a, 'b>(
+        &self,
+        yason: &'a Yason,
+        with_wrapper: bool,
+        offset: usize,
+        limit: usize,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        self.check_exists()?;
+        let mut budget = StepBudget::unlimited();
+        let outcome = self.select_with_limit(
+            yason,
+            None,
+            with_wrapper,
+            query_buf,
+            result_buf,
+            &mut budget,
+            offset,
+            limit,
+        )?;
+        Ok(outcome.value)
+    }
+
+    #[inline]
+    fn select<'a, 'b>(
+        &self,
+        yason: &'a Yason,
+        with_wrapper: bool,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+        budget: &mut StepBudget,
+    ) -> YasonResult<QueryOutcome<'a, 'b>> {
+        self.select_with_limit(yason, None, with_wrapper, query_buf, result_buf, budget, 0, usize::MAX)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    fn select_with_limit<'a, 'b>(
+        &self,
+        yason: &'a Yason,
+        index: Option<&YasonIndex<'a>>,
+        with_wrapper: bool,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+        budget: &mut StepBudget,
+        offset: usize,
+        limit: usize,
+    ) -> YasonResult<QueryOutcome<'a, 'b>> {
         if self.has_method() && !with_wrapper {
             return Err(YasonError::MultiValuesWithoutWrapper);
         }
@@ -129,9 +596,30 @@ impl PathExpression {
             }
         };
 
-        let mut selector = Selector::new(self.steps(), with_wrapper, query_buf.as_mut(), false);
+        let mut selector = Selector::new(self, with_wrapper, query_buf.as_mut(), false, budget)
+            .with_limit(offset, self.select_limit(with_wrapper, limit));
+        if let Some(index) = index {
+            selector = selector.with_index(index);
+        }
+        if let Some(FuncStep::Count) = self.method() {
+            selector = selector.with_count();
+        }
         selector.query(yason, 1)?;
+        let type_mismatch = selector.type_mismatch();
+        let count = selector.count();
 
+        let value = self.collect(with_wrapper, count, query_buf, result_buf)?;
+        Ok(QueryOutcome::new(value, type_mismatch))
+    }
+
+    #[inline]
+    fn collect<'a, 'b>(
+        &self,
+        with_wrapper: bool,
+        count: Option<usize>,
+        mut query_buf: QueryBuf<'a, 'b>,
+        result_buf: Option<&'b mut Vec<u8>>,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
         if !with_wrapper {
             debug_assert!(query_buf.as_ref().len() <= 1);
             return match query_buf.as_mut().pop() {
@@ -140,11 +628,15 @@ impl PathExpression {
             };
         }
 
-        if self.has_method_count() {
-            let count = query_buf.as_ref().len();
-            let val = Value::Number(Number::from(count));
-            query_buf.as_mut().clear();
-            push_value(query_buf.as_mut(), val)?;
+        match self.method() {
+            Some(FuncStep::Count) => {
+                let count = count.unwrap_or_else(|| query_buf.as_ref().len());
+                let val = Value::Number(Number::from(count));
+                query_buf.as_mut().clear();
+                push_value(query_buf.as_mut(), val)?;
+            }
+            Some(FuncStep::Types) | Some(FuncStep::Distinct) => dedup_scalars(query_buf.as_mut())?,
+            _ => {}
         }
 
         if query_buf.as_ref().is_empty() {
@@ -164,17 +656,136 @@ impl PathExpression {
         }
     }
 
+    /// Selects and returns one or more values according to the path expression like [`query`],
+    /// applying the SQL/JSON `ON EMPTY` and `ON ERROR` clauses.
+    ///
+    /// `on_empty` is applied when the path matches nothing. `on_error` is applied when a step is
+    /// applied to a value of an unexpected type (reported as `YasonError::PathTypeMismatch`) or
+    /// when multiple values are matched without array wrapper. Usage errors, i.e. a path with item
+    /// methods queried without array wrapper or unbound variables, are returned before the query
+    /// like other errors, such as allocation failures or corrupted data.
+    ///
+    /// [`query`]: PathExpression::query
+    #[inline]
+    pub fn query_with_clauses<'a, 'b>(
+        &self,
+        yason: &'a Yason,
+        with_wrapper: bool,
+        on_empty: OnEmpty<'b>,
+        on_error: OnError<'b>,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        if self.has_method() && !with_wrapper {
+            return Err(YasonError::MultiValuesWithoutWrapper);
+        }
+        self.check_bound()?;
+
+        match self.query_outcome(yason, with_wrapper, query_buf, result_buf) {
+            Ok(outcome) => match outcome.empty_reason {
+                None => Ok(outcome.value),
+                Some(EmptyReason::NoMatch) => on_empty.apply(),
+                Some(EmptyReason::TypeMismatch) => on_error.apply(YasonError::PathTypeMismatch),
+            },
+            Err(YasonError::MultiValuesWithoutWrapper) => on_error.apply(YasonError::MultiValuesWithoutWrapper),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Returns true if the data it targets matches one or more values. If no values are matched then it returns false.
     #[inline]
     pub fn exists(&self, yason: &Yason) -> YasonResult<bool> {
+        self.exists_with_budget(yason, &mut StepBudget::unlimited())
+    }
+
+    /// Returns whether the path expression matches one or more values like [`exists`],
+    /// returning `YasonError::Cancelled` once the visited values exceed the budget.
+    ///
+    /// [`exists`]: PathExpression::exists
+    #[inline]
+    pub fn exists_with_budget(&self, yason: &Yason, budget: &mut StepBudget) -> YasonResult<bool> {
+        self.check_exists()?;
+        let mut query_buf = Vec::new();
+        self.exists_in(yason, &mut query_buf, budget)
+    }
+
+    /// Returns `YasonError::InvalidPathExpression` if the path expression has an item method, or
+    /// `YasonError::UnboundVariable` if it refers to variables.
+    #[inline]
+    pub(crate) fn check_exists(&self) -> YasonResult<()> {
         if self.has_method() {
             return Err(YasonError::InvalidPathExpression);
         }
+        self.check_bound()
+    }
 
-        let mut query_buf = Vec::new();
-        let mut selector = Selector::new(self.steps(), true, &mut query_buf, true);
+    /// Returns whether the path expression matches one or more values, reusing `query_buf` as
+    /// scratch space. Callers should check that the path expression has no item method and no
+    /// unbound variables.
+    #[inline]
+    pub(crate) fn exists_in<'a>(
+        &self,
+        yason: &'a Yason,
+        query_buf: &mut Vec<Value<'a>>,
+        budget: &mut StepBudget,
+    ) -> YasonResult<bool> {
+        debug_assert!(!self.has_method());
+        query_buf.clear();
+        let mut selector = Selector::new(self, true, query_buf, true, budget);
         selector.query(yason, 1)
     }
+
+    /// Returns the byte ranges of the values selected by the path expression in the bytes of the
+    /// yason, in the order they are selected, e.g. to read or decompress only the matched parts of
+    /// a stored document.
+    ///
+    /// Every range holds a complete encoded value, which can be read by [`Yason::from_bytes`]. The
+    /// ranges of nested values overlap, e.g. with a descendant step. An inlined bool or null of an
+    /// array is encoded by the first bytes of its value entry.
+    ///
+    /// Returns `YasonError::InvalidPathExpression` if the path expression has an item method.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{PathExpression, Yason, YasonBuf};
+    ///
+    /// let yason = YasonBuf::parse(r#"{"id": 1, "items": [{"sku": "a"}, true]}"#).unwrap();
+    /// let path = PathExpression::from_str("$.items[*]").unwrap();
+    /// let spans = path.query_spans(&yason).unwrap();
+    /// assert_eq!(spans.len(), 2);
+    /// let item = Yason::from_bytes(&yason.as_bytes()[spans[0].clone()]).unwrap();
+    /// assert_eq!(item.format(false).to_string(), r#"{"sku":"a"}"#);
+    /// let item = Yason::from_bytes(&yason.as_bytes()[spans[1].clone()]).unwrap();
+    /// assert_eq!(item.format(false).to_string(), "true");
+    /// ```
+    #[inline]
+    pub fn query_spans(&self, yason: &Yason) -> YasonResult<Vec<Range<usize>>> {
+        self.check_exists()?;
+        let mut spans = Vec::new();
+        let mut query_buf = Vec::new();
+        let mut budget = StepBudget::unlimited();
+        let mut selector = Selector::new(self, true, &mut query_buf, false, &mut budget).with_spans(&mut spans);
+        selector.query(yason, 1)?;
+
+        let base = yason.as_bytes().as_ptr() as usize;
+        for span in spans.iter_mut() {
+            *span = span.start - base..span.end - base;
+        }
+        Ok(spans)
+    }
+
+    /// Appends the addresses of the values selected by the path expression, see
+    /// `LazyValue::address`. Callers should check that the path expression has no item method and
+    /// no unbound variables.
+    #[inline]
+    pub(crate) fn select_addresses(&self, yason: &Yason, addresses: &mut Vec<usize>) -> YasonResult<()> {
+        debug_assert!(!self.has_method());
+        let mut query_buf = Vec::new();
+        let mut budget = StepBudget::unlimited();
+        let mut selector = Selector::new(self, true, &mut query_buf, false, &mut budget).with_addresses(addresses);
+        selector.query(yason, 1)?;
+        Ok(())
+    }
 }
 
 impl FromStr for PathExpression {
@@ -182,7 +793,7 @@ impl FromStr for PathExpression {
 
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parser = PathParser::new(s.as_bytes());
+        let parser = PathParser::new(s);
         parser.parse()
     }
 }
@@ -195,6 +806,47 @@ fn push_value<'a>(buf: &mut Vec<Value<'a>>, value: Value<'a>) -> YasonResult<()>
     Ok(())
 }
 
+/// A scalar value which can be hashed, numbers are equal if they have the same value.
+#[derive(Hash, Eq, PartialEq)]
+enum ScalarKey<'a> {
+    String(&'a str),
+    Number(Number),
+    Bool(bool),
+    Null,
+    Binary(&'a [u8]),
+    Timestamp(Timestamp),
+    Date(Date),
+    ShortDate(ShortDate),
+    Time(Time),
+    IntervalYearMonth(YmInterval),
+    IntervalDayTime(DtInterval),
+}
+
+/// Removes the scalars which are equal to a previous scalar, keeping objects and arrays.
+#[inline]
+fn dedup_scalars(values: &mut Vec<Value>) -> YasonResult<()> {
+    let mut seen = HashSet::new();
+    seen.try_reserve(values.len()).map_err(YasonError::TryReserveError)?;
+    values.retain(|value| {
+        let key = match value {
+            Value::String(str) => ScalarKey::String(str),
+            Value::Number(number) => ScalarKey::Number(*number),
+            Value::Bool(bool) => ScalarKey::Bool(*bool),
+            Value::Null => ScalarKey::Null,
+            Value::Binary(binary) => ScalarKey::Binary(binary),
+            Value::Timestamp(timestamp) => ScalarKey::Timestamp(*timestamp),
+            Value::Date(date) => ScalarKey::Date(*date),
+            Value::ShortDate(short_date) => ScalarKey::ShortDate(*short_date),
+            Value::Time(time) => ScalarKey::Time(*time),
+            Value::IntervalYearMonth(interval) => ScalarKey::IntervalYearMonth(*interval),
+            Value::IntervalDayTime(interval) => ScalarKey::IntervalDayTime(*interval),
+            Value::Object(_) | Value::Array(_) => return true,
+        };
+        seen.insert(key)
+    });
+    Ok(())
+}
+
 #[inline]
 fn values_to_yason<'a>(values: &[Value], bytes: &'a mut Vec<u8>) -> YasonResult<&'a Yason> {
     let mut builder = ArrayRefBuilder::try_new(bytes, values.len() as u16)?;
@@ -206,6 +858,13 @@ fn values_to_yason<'a>(values: &[Value], bytes: &'a mut Vec<u8>) -> YasonResult<
             Value::Number(number) => builder.push_number(number)?,
             Value::Bool(bool) => builder.push_bool(*bool)?,
             Value::Null => builder.push_null()?,
+            Value::Binary(binary) => builder.push_binary(binary)?,
+            Value::Timestamp(timestamp) => builder.push_timestamp(*timestamp)?,
+            Value::Date(date) => builder.push_date(*date)?,
+            Value::ShortDate(short_date) => builder.push_short_date(*short_date)?,
+            Value::Time(time) => builder.push_time(*time)?,
+            Value::IntervalYearMonth(interval) => builder.push_interval_year_month(*interval)?,
+            Value::IntervalDayTime(interval) => builder.push_interval_day_time(*interval)?,
         };
     }
 
@@ -220,9 +879,9 @@ fn values_format_to<W: fmt::Write>(values: &[Value], pretty: bool, writer: &mut
 
     if pretty {
         let mut fmt = PrettyFormatter::new();
-        unsafe { fmt.write_values(values, writer) }
+        fmt.write_values(values, writer)
     } else {
         let mut fmt = CompactFormatter::new();
-        unsafe { fmt.write_values(values, writer) }
+        fmt.write_values(values, writer)
     }
 }
@@ -1,7 +1,7 @@
 //! Path Parser.
 
 use crate::vec::VecExt;
-use crate::PathExpression;
+use crate::{ErrorCode, PathExpression};
 use std::collections::TryReserveError;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
@@ -24,6 +24,8 @@ const TO: &[u8] = b"to";
 const COUNT: &[u8] = b"count";
 const SIZE: &[u8] = b"size";
 const TYPE: &[u8] = b"type";
+const TYPES: &[u8] = b"types";
+const DISTINCT: &[u8] = b"distinct";
 
 /// This type represents error that can arise during parsing path expression.
 #[derive(Debug)]
@@ -39,6 +41,138 @@ impl PathParseError {
     }
 }
 
+impl PathParseError {
+    /// Returns the stable code of the error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        match self.kind {
+            PathParseErrorKind::NotStartWithDollar => ErrorCode::PathNotStartWithDollar,
+            PathParseErrorKind::MissingSquareBracket => ErrorCode::PathMissingSquareBracket,
+            PathParseErrorKind::ArrayStepSyntaxError => ErrorCode::PathArrayStepSyntax,
+            PathParseErrorKind::ArrayIndexTooLong => ErrorCode::PathArrayIndexTooLong,
+            PathParseErrorKind::InvalidEscapeSequence => ErrorCode::PathInvalidEscapeSequence,
+            PathParseErrorKind::UnclosedQuotedStep => ErrorCode::PathUnclosedQuotedStep,
+            PathParseErrorKind::InvalidKeyStep => ErrorCode::PathInvalidKeyStep,
+            PathParseErrorKind::InvalidFunction => ErrorCode::PathInvalidFunction,
+            PathParseErrorKind::UnexpectedCharacterAtEnd => ErrorCode::PathUnexpectedCharacterAtEnd,
+            PathParseErrorKind::InvalidCharacterAtStepStart => ErrorCode::PathInvalidCharacterAtStepStart,
+            PathParseErrorKind::EmptyArrayStep => ErrorCode::PathEmptyArrayStep,
+            PathParseErrorKind::InvalidVariableName => ErrorCode::PathInvalidVariableName,
+            PathParseErrorKind::TryReserveError(_) => ErrorCode::OutOfMemory,
+        }
+    }
+}
+
+impl PathParseError {
+    /// Renders the error for the path `input` which failed to parse: the message, the path, and a
+    /// caret under the position of the error. For common mistakes, such as paths starting with `@`,
+    /// single-quoted keys or keys which need quotes, a corrected path is suggested.
+    ///
+    /// ```rust
+    /// use yason::PathExpression;
+    ///
+    /// let input = "$.first name";
+    /// let err = input.parse::<PathExpression>().unwrap_err();
+    /// assert_eq!(
+    ///     err.render_diagnostic(input),
+    ///     "unexpected characters after end of path at position 9\n\
+    ///      $.first name\n\
+    ///      \x20       ^\n\
+    ///      help: keys with characters other than letters, digits, `_` and `$` must be double-quoted, \
+    ///      try `$.\"first name\"`"
+    /// );
+    /// ```
+    pub fn render_diagnostic(&self, input: &str) -> String {
+        let mut index = self.pos.saturating_sub(1).min(input.len());
+        while !input.is_char_boundary(index) {
+            index -= 1;
+        }
+        // tabs are kept so that the caret lines up with the path
+        let caret: String = input[..index]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let mut diagnostic = format!("{}\n{}\n{}^", self, input, caret);
+        if let Some((help, suggestion)) = self.suggest(input, index) {
+            diagnostic.push_str(&format!("\nhelp: {}, try `{}`", help, suggestion));
+        }
+        diagnostic
+    }
+
+    /// Returns the help message and the corrected path for common mistakes, the corrected path is
+    /// only suggested if it can be parsed.
+    fn suggest(&self, input: &str, index: usize) -> Option<(&'static str, String)> {
+        let (help, suggestion) = if input.contains('\'') {
+            ("keys are quoted with double quotes", double_quote_keys(input)?)
+        } else if input.trim_start().starts_with('@') {
+            let start = input.len() - input.trim_start().len();
+            (
+                "paths start with `$`",
+                format!("{}${}", &input[..start], &input[start + 1..]),
+            )
+        } else if matches!(
+            self.kind,
+            PathParseErrorKind::InvalidKeyStep | PathParseErrorKind::UnexpectedCharacterAtEnd
+        ) {
+            (
+                "keys with characters other than letters, digits, `_` and `$` must be double-quoted",
+                quote_key_at(input, index)?,
+            )
+        } else {
+            return None;
+        };
+
+        if suggestion.parse::<PathExpression>().is_ok() {
+            Some((help, suggestion))
+        } else {
+            None
+        }
+    }
+}
+
+/// Replaces the single-quoted keys of the path, `'key'` or `['key']`, with `."key"`.
+fn double_quote_keys(input: &str) -> Option<String> {
+    let mut fixed = String::with_capacity(input.len() + 2);
+    let mut rest = input;
+    while let Some(open) = rest.find('\'') {
+        let close = open + 1 + rest[open + 1..].find('\'')?;
+        let key = rest[open + 1..close].replace('"', "\\\"");
+
+        let before = rest[..open].trim_end();
+        let after = rest[close + 1..].trim_start();
+        match (before.strip_suffix('['), after.strip_prefix(']')) {
+            (Some(before), Some(after)) => {
+                fixed.push_str(before);
+                fixed.push_str(&format!(".\"{}\"", key));
+                rest = after;
+            }
+            _ => {
+                fixed.push_str(&rest[..open]);
+                fixed.push_str(&format!("\"{}\"", key));
+                rest = &rest[close + 1..];
+            }
+        }
+    }
+    fixed.push_str(rest);
+    Some(fixed)
+}
+
+/// Quotes the key of the object step around `index` of the path, which spans from the last dot
+/// before `index` to the next step.
+fn quote_key_at(input: &str, index: usize) -> Option<String> {
+    let dot = input[..index].rfind('.')?;
+    let end = input[index..]
+        .find(|c| c == '.' || c == '[')
+        .map_or(input.len(), |end| index + end);
+    let key = input[dot + 1..end].trim();
+    // item methods and quoted keys are not keys to quote
+    if key.is_empty() || key.contains(|c| c == '"' || c == '(' || c == ')') {
+        return None;
+    }
+    Some(format!("{}\"{}\"{}", &input[..dot + 1], key, &input[end..]))
+}
+
 impl Display for PathParseError {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -60,6 +194,7 @@ enum PathParseErrorKind {
     UnexpectedCharacterAtEnd,
     InvalidCharacterAtStepStart,
     EmptyArrayStep,
+    InvalidVariableName,
     TryReserveError(TryReserveError),
 }
 
@@ -78,6 +213,7 @@ impl Display for PathParseErrorKind {
             PathParseErrorKind::UnexpectedCharacterAtEnd => write!(f, "unexpected characters after end of path"),
             PathParseErrorKind::InvalidCharacterAtStepStart => write!(f, "invalid character at start of step"),
             PathParseErrorKind::EmptyArrayStep => write!(f, "empty array subscript"),
+            PathParseErrorKind::InvalidVariableName => write!(f, "invalid variable name"),
             PathParseErrorKind::TryReserveError(e) => write!(f, "{}", e),
         }
     }
@@ -87,15 +223,17 @@ impl Error for PathParseError {}
 
 pub type PathParseResult<T> = std::result::Result<T, PathParseError>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum SingleIndex {
     /// \[1]
     Index(usize),
     /// \[last - 1]
     Last(usize),
+    /// \[$var]
+    Variable(String),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum SingleStep {
     /// \[1] \ [last - 1]
     Single(SingleIndex),
@@ -103,12 +241,14 @@ pub enum SingleStep {
     Range(SingleIndex, SingleIndex),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ArrayStep {
     /// \[1]
     Index(usize),
     /// \[last]
     Last(usize),
+    /// \[$var]
+    Variable(String),
     /// \[1 to 4]
     Range(SingleIndex, SingleIndex),
     /// \[1, last, 1 to 4]
@@ -117,7 +257,7 @@ pub enum ArrayStep {
     Wildcard,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ObjectStep {
     /// .key
     Key(String),
@@ -125,17 +265,21 @@ pub enum ObjectStep {
     Wildcard,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum FuncStep {
     Count,
     Size,
     Type,
+    Types,
+    Distinct,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Step {
     /// $
     Root,
+    /// $var
+    Variable(String),
     /// .XXX
     Object(ObjectStep),
     /// \[XXX]
@@ -147,6 +291,7 @@ pub enum Step {
 }
 
 pub struct PathParser<'a> {
+    text: &'a str,
     input: &'a [u8],
     pos: usize,
     path: Vec<Step>,
@@ -154,9 +299,10 @@ pub struct PathParser<'a> {
 
 impl<'a> PathParser<'a> {
     #[inline]
-    pub fn new(input: &'a [u8]) -> Self {
+    pub fn new(text: &'a str) -> Self {
         Self {
-            input,
+            text,
+            input: text.as_bytes(),
             pos: 0,
             path: vec![],
         }
@@ -169,7 +315,13 @@ impl<'a> PathParser<'a> {
         if self.pop() != Some(ROOT) {
             return Err(PathParseError::new(PathParseErrorKind::NotStartWithDollar, self.pos));
         }
-        self.push_step(Step::Root)?;
+        match self.peek() {
+            Some(char) if char.is_ascii_alphabetic() => {
+                let name = self.parse_variable_name()?;
+                self.push_step(Step::Variable(name))?;
+            }
+            _ => self.push_step(Step::Root)?,
+        }
 
         self.skip(|i| i == b' ');
         while !self.exhausted() {
@@ -219,6 +371,7 @@ impl<'a> PathParser<'a> {
                         SingleStep::Single(single_index) => match single_index {
                             SingleIndex::Index(index) => self.push_step(Step::Array(ArrayStep::Index(index)))?,
                             SingleIndex::Last(minus) => self.push_step(Step::Array(ArrayStep::Last(minus)))?,
+                            SingleIndex::Variable(name) => self.push_step(Step::Array(ArrayStep::Variable(name)))?,
                         },
                         SingleStep::Range(begin, end) => self.push_step(Step::Array(ArrayStep::Range(begin, end)))?,
                     };
@@ -305,6 +458,10 @@ impl<'a> PathParser<'a> {
                 let index = self.parse_index()?;
                 Ok(SingleIndex::Index(index))
             }
+            Some(ROOT) => {
+                self.advance(CTRL_CHAR_LEN);
+                Ok(SingleIndex::Variable(self.parse_variable_name()?))
+            }
             None => Err(PathParseError::new(PathParseErrorKind::MissingSquareBracket, self.pos)),
             _ => Err(PathParseError::new(
                 PathParseErrorKind::ArrayStepSyntaxError,
@@ -345,6 +502,23 @@ impl<'a> PathParser<'a> {
         Ok(res)
     }
 
+    /// Parses the name of a variable after `$`, which is a letter followed by letters, digits and
+    /// underscores.
+    #[inline]
+    fn parse_variable_name(&mut self) -> PathParseResult<String> {
+        match self.peek() {
+            Some(char) if char.is_ascii_alphabetic() => {
+                let begin = self.pos;
+                self.skip(|i| i.is_ascii_alphanumeric() || i == b'_');
+                self.create_key::<false>(&self.input[begin..self.pos])
+            }
+            _ => Err(PathParseError::new(
+                PathParseErrorKind::InvalidVariableName,
+                self.pos + 1,
+            )),
+        }
+    }
+
     #[inline]
     fn parse_object_step(&mut self) -> PathParseResult<()> {
         self.eat_whitespaces();
@@ -448,13 +622,15 @@ impl<'a> PathParser<'a> {
         }
     }
 
+    /// Parses an unquoted key, which starts with a letter, `_` or `$`, followed by letters, digits,
+    /// `_` and `$`. Letters and digits include non-ASCII alphabetic and numeric characters.
     #[inline]
     fn parse_unquoted_field_name<const DESCENDENT: bool>(&mut self) -> PathParseResult<()> {
         self.eat_whitespaces();
-        match self.peek() {
-            Some(char) if char.is_ascii_alphabetic() => {
+        match self.peek_char() {
+            Some(char) if is_key_start(char) => {
                 let begin = self.pos;
-                self.skip(|i| i.is_ascii_alphabetic() || i.is_ascii_digit());
+                self.skip_chars(is_key_char);
                 let end = self.pos;
 
                 if DESCENDENT {
@@ -503,6 +679,8 @@ impl<'a> PathParser<'a> {
                 COUNT => self.push_step(Step::Func(FuncStep::Count)),
                 SIZE => self.push_step(Step::Func(FuncStep::Size)),
                 TYPE => self.push_step(Step::Func(FuncStep::Type)),
+                TYPES => self.push_step(Step::Func(FuncStep::Types)),
+                DISTINCT => self.push_step(Step::Func(FuncStep::Distinct)),
                 _ => Err(PathParseError::new(PathParseErrorKind::InvalidFunction, begin_pos)),
             }
         } else {
@@ -559,6 +737,20 @@ impl<'a> PathParser<'a> {
         self.input.get(self.pos).copied()
     }
 
+    #[inline]
+    fn peek_char(&self) -> Option<char> {
+        self.text.get(self.pos..).and_then(|rem| rem.chars().next())
+    }
+
+    #[inline]
+    fn skip_chars<F: Fn(char) -> bool>(&mut self, f: F) {
+        let count = self
+            .text
+            .get(self.pos..)
+            .map_or(0, |rem| rem.chars().take_while(|c| f(*c)).map(char::len_utf8).sum());
+        self.advance(count);
+    }
+
     #[inline]
     fn advance(&mut self, step: usize) {
         self.pos += step;
@@ -584,7 +776,7 @@ impl<'a> PathParser<'a> {
         let str = if CHECK_UTF8 {
             std::str::from_utf8(bytes).map_err(|_| PathParseError::new(PathParseErrorKind::InvalidKeyStep, self.pos))?
         } else {
-            // SAFETY: bytes must only contains [0..9], [a..z] and [A..Z] when CHECK_UTF8 is false.
+            // SAFETY: bytes must be a slice of the input on char boundaries when CHECK_UTF8 is false.
             unsafe { std::str::from_utf8_unchecked(bytes) }
         };
 
@@ -597,6 +789,24 @@ impl<'a> PathParser<'a> {
     }
 }
 
+#[inline]
+fn is_key_start(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_alphabetic() || c == '_' || c == '$'
+    } else {
+        c.is_alphabetic()
+    }
+}
+
+#[inline]
+fn is_key_char(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_alphanumeric() || c == '_' || c == '$'
+    } else {
+        c.is_alphanumeric()
+    }
+}
+
 const __: u8 = 255; // not a hex digit
 
 #[allow(clippy::zero_prefixed_literal)]
@@ -648,7 +858,7 @@ mod tests {
             None => {
                 assert!(path.is_ok());
                 let path = path.unwrap();
-                let fields = path.steps();
+                let fields = path.inner_steps();
                 assert_eq!(fields, expected.unwrap());
             }
         }
@@ -886,6 +1096,39 @@ mod tests {
             Step::Func(FuncStep::Size),
         ];
         assert_path_parse(input, &expected);
+
+        let input = r#"$doc.key[$i]"#;
+        let expected = vec![
+            Step::Variable("doc".to_string()),
+            Step::Object(ObjectStep::Key("key".to_string())),
+            Step::Array(ArrayStep::Variable("i".to_string())),
+        ];
+        assert_path_parse(input, &expected);
+
+        let input = r#"$[ $from_1 to last, $n ]"#;
+        let expected = vec![
+            Step::Root,
+            Step::Array(ArrayStep::Multiple(vec![
+                SingleStep::Range(SingleIndex::Variable("from_1".to_string()), SingleIndex::Last(0)),
+                SingleStep::Single(SingleIndex::Variable("n".to_string())),
+            ])),
+        ];
+        assert_path_parse(input, &expected);
+
+        let input = r#"$a.size()"#;
+        let expected = vec![Step::Variable("a".to_string()), Step::Func(FuncStep::Size)];
+        assert_path_parse(input, &expected);
+
+        let input = r#"$.foo_bar._x1.$id..名前.ключ2"#;
+        let expected = vec![
+            Step::Root,
+            Step::Object(ObjectStep::Key("foo_bar".to_string())),
+            Step::Object(ObjectStep::Key("_x1".to_string())),
+            Step::Object(ObjectStep::Key("$id".to_string())),
+            Step::Descendent("名前".to_string()),
+            Step::Object(ObjectStep::Key("ключ2".to_string())),
+        ];
+        assert_path_parse(input, &expected);
     }
 
     #[test]
@@ -914,6 +1157,12 @@ mod tests {
         assert_path_parse_error(input, PathParseErrorKind::InvalidKeyStep, 3);
         let input = "$.1key";
         assert_path_parse_error(input, PathParseErrorKind::InvalidKeyStep, 3);
+        let input = "$.-key";
+        assert_path_parse_error(input, PathParseErrorKind::InvalidKeyStep, 3);
+        let input = "$.名 前";
+        assert_path_parse_error(input, PathParseErrorKind::UnexpectedCharacterAtEnd, 7);
+        let input = "$.😀";
+        assert_path_parse_error(input, PathParseErrorKind::InvalidKeyStep, 3);
         let input = "$.";
         assert_path_parse_error(input, PathParseErrorKind::InvalidKeyStep, 2);
 
@@ -926,8 +1175,8 @@ mod tests {
         let input = "$.key[last ";
         assert_path_parse_error(input, PathParseErrorKind::MissingSquareBracket, 11);
 
-        let input = "$a.size()";
-        assert_path_parse_error(input, PathParseErrorKind::InvalidCharacterAtStepStart, 2);
+        let input = "$ a.size()";
+        assert_path_parse_error(input, PathParseErrorKind::InvalidCharacterAtStepStart, 3);
         let input = "$\t.size()";
         assert_path_parse_error(input, PathParseErrorKind::InvalidCharacterAtStepStart, 2);
 
@@ -946,6 +1195,13 @@ mod tests {
         let input = "$.key[]";
         assert_path_parse_error(input, PathParseErrorKind::EmptyArrayStep, 6);
 
+        let input = "$.key[$]";
+        assert_path_parse_error(input, PathParseErrorKind::InvalidVariableName, 8);
+        let input = "$.key[$1]";
+        assert_path_parse_error(input, PathParseErrorKind::InvalidVariableName, 8);
+        let input = "$.key[last - $n]";
+        assert_path_parse_error(input, PathParseErrorKind::ArrayStepSyntaxError, 14);
+
         let input = "$.key[12312313131321321231]";
         assert_path_parse_error(input, PathParseErrorKind::ArrayIndexTooLong, 7);
         let input = "$.key[  12312313131321321231]";
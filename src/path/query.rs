@@ -1,43 +1,293 @@
 //! Query by path expression.
 
+use crate::budget::StepBudget;
 use crate::path::parse::{ArrayStep, FuncStep, ObjectStep, SingleIndex, SingleStep, Step};
-use crate::path::push_value;
-use crate::yason::{LazyValue, YasonResult};
+use crate::path::{push_value, DescendantOrder, PathExpression};
+use crate::yason::{Array, LazyArrayIter, LazyObjectValueIter, LazyValue, Object, YasonIndex, YasonResult};
 use crate::{DataType, Number, Value, Yason, YasonError};
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// A pending part of a query. The selector keeps the tasks on an explicit stack instead of
+/// recursing per step and per container, so that deep documents cannot exhaust the call stack.
+/// The tasks are pushed in reverse order, the task on the top is always the next one in document
+/// order.
+enum Task<'a, 'b> {
+    /// Applies the steps from `step_index` to a value.
+    Value(LazyValue<'a, false>, usize),
+    /// Applies the steps from `step_index` to an array element.
+    Element(LazyValue<'a, true>, usize),
+    /// Applies the steps from `step_index` to the remaining values of an object.
+    ObjectValues(LazyObjectValueIter<'a>, usize),
+    /// Applies the steps from `step_index` to the remaining elements of an array.
+    ArrayElements(LazyArrayIter<'a>, usize),
+    /// Applies the steps from `step_index` to the elements of an array in `next..end`.
+    ArrayRange {
+        array: Array<'a>,
+        next: usize,
+        end: usize,
+        step_index: usize,
+    },
+    /// Applies the steps from `step_index` to the elements of an array selected by the remaining
+    /// steps of a multiple array step.
+    ArraySteps {
+        array: Array<'a>,
+        steps: &'b [SingleStep],
+        step_index: usize,
+    },
+    /// Applies the steps after the descendant step at `step_index` to the value of the key in an
+    /// object, after its descendants in post-order.
+    DescendantKey {
+        object: Object<'a>,
+        key: &'b str,
+        step_index: usize,
+    },
+    /// Selects the values of the key in the descendants level by level, with a queue of the
+    /// containers of the next levels. The children of `expand` are queued before the next
+    /// container is visited.
+    BreadthFirst {
+        queue: VecDeque<Value<'a>>,
+        expand: Option<Object<'a>>,
+        key: &'b str,
+        step_index: usize,
+    },
+}
 
 pub struct Selector<'a, 'b> {
     steps: &'b [Step],
+    descendant_order: DescendantOrder,
     with_wrapper: bool,
     query_buf: &'b mut Vec<Value<'a>>,
     for_exists: bool,
+    type_mismatch: bool,
+    count: Option<usize>,
+    budget: &'b mut StepBudget,
+    addresses: Option<&'b mut Vec<usize>>,
+    spans: Option<&'b mut Vec<Range<usize>>>,
+    index: Option<&'b YasonIndex<'a>>,
+    offset: usize,
+    limit: usize,
+    tasks: Vec<Task<'a, 'b>>,
 }
 
 impl<'a, 'b> Selector<'a, 'b> {
     #[inline]
-    pub fn new(steps: &'b [Step], with_wrapper: bool, query_buf: &'b mut Vec<Value<'a>>, for_exists: bool) -> Self {
+    pub fn new(
+        path: &'b PathExpression,
+        with_wrapper: bool,
+        query_buf: &'b mut Vec<Value<'a>>,
+        for_exists: bool,
+        budget: &'b mut StepBudget,
+    ) -> Self {
         Self {
-            steps,
+            steps: path.inner_steps(),
+            descendant_order: path.descendant_order(),
             with_wrapper,
             query_buf,
             for_exists,
+            type_mismatch: false,
+            count: None,
+            budget,
+            addresses: None,
+            spans: None,
+            index: None,
+            offset: 0,
+            limit: usize::MAX,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Skips the first `offset` selected values and stops selecting after `limit` values.
+    #[inline]
+    pub fn with_limit(mut self, offset: usize, limit: usize) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+        self
+    }
+
+    /// Returns whether the traversal can stop after a value is found.
+    #[inline]
+    fn stop(&self, found: bool) -> bool {
+        found && (self.for_exists || self.limit == 0)
+    }
+
+    /// Collects the addresses of the selected values, see `LazyValue::address`, instead of the
+    /// values.
+    #[inline]
+    pub fn with_addresses(mut self, addresses: &'b mut Vec<usize>) -> Self {
+        self.addresses = Some(addresses);
+        self
+    }
+
+    /// Collects the address ranges of the encoded selected values, see `LazyValue::span`, instead
+    /// of the values.
+    #[inline]
+    pub fn with_spans(mut self, spans: &'b mut Vec<Range<usize>>) -> Self {
+        self.spans = Some(spans);
+        self
+    }
+
+    /// Counts the values selected by a trailing `count()` method instead of pushing a placeholder
+    /// for each of them into the query buffer, see [`Selector::count`].
+    #[inline]
+    pub fn with_count(mut self) -> Self {
+        self.count = Some(0);
+        self
+    }
+
+    /// Returns the number of values counted by `count()`, or `None` if counting is not enabled.
+    #[inline]
+    pub fn count(&self) -> Option<usize> {
+        self.count
+    }
+
+    /// Looks up object keys through the cached key tables of the index.
+    #[inline]
+    pub fn with_index(mut self, index: &'b YasonIndex<'a>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    #[inline]
+    fn lazy_get(&self, object: &Object<'a>, key: &str) -> YasonResult<Option<LazyValue<'a, false>>> {
+        match self.index {
+            Some(index) => index.lazy_get(object, key),
+            None => object.lazy_get(key),
         }
     }
 
+    /// Returns whether a step has been applied to a value of an unexpected type, e.g. an object
+    /// step applied to a scalar.
+    #[inline]
+    pub fn type_mismatch(&self) -> bool {
+        self.type_mismatch
+    }
+
     #[inline]
+    fn push_task(&mut self, task: Task<'a, 'b>) -> YasonResult<()> {
+        self.tasks.try_reserve(1).map_err(YasonError::TryReserveError)?;
+        self.tasks.push(task);
+        Ok(())
+    }
+
+    /// Applies the steps from `step_index` to the value, returns whether the traversal stopped
+    /// early after a value was found.
     pub fn query(&mut self, value: &'a Yason, step_index: usize) -> YasonResult<bool> {
-        let lazy_value = LazyValue::try_from(value)?;
-        self.query_internal(lazy_value, step_index)
+        if self.limit == 0 {
+            return Ok(false);
+        }
+        self.tasks.clear();
+        self.push_task(Task::Value(LazyValue::try_from(value)?, step_index))?;
+
+        while let Some(task) = self.tasks.pop() {
+            let found = match task {
+                Task::Value(val, step_index) => self.visit(val, step_index)?,
+                Task::Element(val, step_index) => self.visit(val, step_index)?,
+                Task::ObjectValues(mut iter, step_index) => {
+                    if let Some(val) = iter.next() {
+                        let val = val?;
+                        self.push_task(Task::ObjectValues(iter, step_index))?;
+                        self.push_task(Task::Value(val, step_index))?;
+                    }
+                    false
+                }
+                Task::ArrayElements(mut iter, step_index) => {
+                    if let Some(val) = iter.next() {
+                        let val = val?;
+                        self.push_task(Task::ArrayElements(iter, step_index))?;
+                        self.push_task(Task::Element(val, step_index))?;
+                    }
+                    false
+                }
+                Task::ArrayRange {
+                    array,
+                    next,
+                    end,
+                    step_index,
+                } => {
+                    if next < end {
+                        let val = unsafe { array.lazy_get_unchecked(next)? };
+                        self.push_task(Task::ArrayRange {
+                            array,
+                            next: next + 1,
+                            end,
+                            step_index,
+                        })?;
+                        self.push_task(Task::Element(val, step_index))?;
+                    }
+                    false
+                }
+                Task::ArraySteps {
+                    array,
+                    steps,
+                    step_index,
+                } => {
+                    if let Some((step, steps)) = steps.split_first() {
+                        self.push_task(Task::ArraySteps {
+                            array: array.clone(),
+                            steps,
+                            step_index,
+                        })?;
+                        self.array_single_step(array, step, step_index)?;
+                    }
+                    false
+                }
+                Task::DescendantKey {
+                    object,
+                    key,
+                    step_index,
+                } => {
+                    if let Some(val) = self.lazy_get(&object, key)? {
+                        self.push_task(Task::Value(val, step_index + 1))?;
+                    }
+                    false
+                }
+                Task::BreadthFirst {
+                    queue,
+                    expand,
+                    key,
+                    step_index,
+                } => {
+                    self.descendent_breadth_first(queue, expand, key, step_index)?;
+                    false
+                }
+            };
+            if self.stop(found) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
     }
 
+    /// Applies the step at `step_index` to the value, or selects the value if all steps have been
+    /// applied. Returns whether the value is selected.
     #[inline]
-    fn query_internal<const IN_ARRAY: bool>(
-        &mut self,
-        value: LazyValue<'a, IN_ARRAY>,
-        step_index: usize,
-    ) -> YasonResult<bool> {
+    fn visit<const IN_ARRAY: bool>(&mut self, value: LazyValue<'a, IN_ARRAY>, step_index: usize) -> YasonResult<bool> {
         debug_assert!(step_index <= self.steps.len());
+        self.budget.step()?;
+        #[cfg(feature = "metrics")]
+        crate::metrics::query_node_visited();
 
         if step_index == self.steps.len() {
+            if self.offset > 0 {
+                self.offset -= 1;
+                return Ok(false);
+            }
+            if self.limit == 0 {
+                return Ok(true);
+            }
+            self.limit -= 1;
+            if let Some(addresses) = self.addresses.as_mut() {
+                addresses.try_reserve(1).map_err(YasonError::TryReserveError)?;
+                addresses.push(value.address());
+                return Ok(true);
+            }
+            if let Some(spans) = self.spans.as_mut() {
+                spans.try_reserve(1).map_err(YasonError::TryReserveError)?;
+                spans.push(value.span()?);
+                return Ok(true);
+            }
             if !self.for_exists {
                 if !self.with_wrapper && !self.query_buf.is_empty() {
                     return Err(YasonError::MultiValuesWithoutWrapper);
@@ -50,7 +300,8 @@ impl<'a, 'b> Selector<'a, 'b> {
 
         let cur_step = &self.steps[step_index];
         match cur_step {
-            Step::Root => unreachable!(),
+            // the root is resolved and variables are bound before selecting
+            Step::Root | Step::Variable(_) => unreachable!(),
             Step::Object(obj_step) => match obj_step {
                 ObjectStep::Key(key) => self.object_key_match(value, step_index, key.as_str()),
                 ObjectStep::Wildcard => self.object_wildcard_match(value, step_index),
@@ -61,6 +312,7 @@ impl<'a, 'b> Selector<'a, 'b> {
                 ArrayStep::Range(begin, end) => self.array_range_match(value, step_index, begin, end),
                 ArrayStep::Multiple(arr_steps) => self.array_multi_steps_match(value, step_index, arr_steps),
                 ArrayStep::Wildcard => self.array_wildcard_match(value, step_index),
+                ArrayStep::Variable(_) => unreachable!(),
             },
             Step::Descendent(key) => self.descendent_step_match(value, step_index, key.as_str()),
             Step::Func(func) => self.func_step_match(value, step_index, func),
@@ -77,21 +329,15 @@ impl<'a, 'b> Selector<'a, 'b> {
         match value.data_type() {
             DataType::Object => {
                 let object = unsafe { value.object()? };
-                let val = object.lazy_get(key)?;
-                if let Some(v) = val {
-                    return self.query_internal(v, step_index + 1);
+                if let Some(val) = self.lazy_get(&object, key)? {
+                    self.push_task(Task::Value(val, step_index + 1))?;
                 }
             }
             DataType::Array => {
                 let array = unsafe { value.array()? };
-                for val in array.lazy_iter()? {
-                    let found = self.query_internal(val?, step_index)?;
-                    if self.for_exists && found {
-                        return Ok(true);
-                    }
-                }
+                self.push_task(Task::ArrayElements(array.lazy_iter()?, step_index))?;
             }
-            _ => {}
+            _ => self.type_mismatch = true,
         }
         Ok(false)
     }
@@ -105,23 +351,13 @@ impl<'a, 'b> Selector<'a, 'b> {
         match value.data_type() {
             DataType::Object => {
                 let object = unsafe { value.object()? };
-                for val in object.lazy_value_iter()? {
-                    let found = self.query_internal(val?, step_index + 1)?;
-                    if self.for_exists && found {
-                        return Ok(true);
-                    }
-                }
+                self.push_task(Task::ObjectValues(object.lazy_value_iter()?, step_index + 1))?;
             }
             DataType::Array => {
                 let array = unsafe { value.array()? };
-                for val in array.lazy_iter()? {
-                    let found = self.query_internal(val?, step_index)?;
-                    if self.for_exists && found {
-                        return Ok(true);
-                    }
-                }
+                self.push_task(Task::ArrayElements(array.lazy_iter()?, step_index))?;
             }
-            _ => {}
+            _ => self.type_mismatch = true,
         }
 
         Ok(false)
@@ -139,13 +375,14 @@ impl<'a, 'b> Selector<'a, 'b> {
                 let array = unsafe { value.array()? };
                 if index < array.len()? {
                     let val = unsafe { array.lazy_get_unchecked(index)? };
-                    return self.query_internal(val, step_index + 1);
+                    self.push_task(Task::Element(val, step_index + 1))?;
                 }
             }
             _ => {
                 if index == 0 {
                     return self.non_array_relax_match(value, step_index + 1);
                 }
+                self.type_mismatch = true;
             }
         }
         Ok(false)
@@ -164,13 +401,14 @@ impl<'a, 'b> Selector<'a, 'b> {
                 let len = array.len()?;
                 if len > minus {
                     let val = unsafe { array.lazy_get_unchecked(len - 1 - minus)? };
-                    return self.query_internal(val, step_index + 1);
+                    self.push_task(Task::Element(val, step_index + 1))?;
                 }
             }
             _ => {
                 if minus == 0 {
                     return self.non_array_relax_match(value, step_index + 1);
                 }
+                self.type_mismatch = true;
             }
         }
 
@@ -188,31 +426,44 @@ impl<'a, 'b> Selector<'a, 'b> {
         match value.data_type() {
             DataType::Array => {
                 let array = unsafe { value.array()? };
-                let len = array.len()?;
-                if len == 0 {
-                    return Ok(false);
-                }
-
-                let last = len - 1;
-                if let Some((b, e)) = find_range(begin, end, last) {
-                    for i in b..e + 1 {
-                        let val = unsafe { array.lazy_get_unchecked(i)? };
-                        let found = self.query_internal(val, step_index + 1)?;
-                        if self.for_exists && found {
-                            return Ok(true);
-                        }
-                    }
-                }
+                self.array_range(array, begin, end, step_index + 1)?;
             }
             _ => {
                 if non_array_range_step_relaxed_match(begin, end) {
                     return self.non_array_relax_match(value, step_index + 1);
                 }
+                self.type_mismatch = true;
             }
         }
         Ok(false)
     }
 
+    /// Queues the elements of the array in the range, to apply the steps from `step_index`.
+    #[inline]
+    fn array_range(
+        &mut self,
+        array: Array<'a>,
+        begin: &SingleIndex,
+        end: &SingleIndex,
+        step_index: usize,
+    ) -> YasonResult<()> {
+        let len = array.len()?;
+        if len == 0 {
+            return Ok(());
+        }
+
+        let last = len - 1;
+        if let Some((b, e)) = find_range(begin, end, last) {
+            self.push_task(Task::ArrayRange {
+                array,
+                next: b,
+                end: e + 1,
+                step_index,
+            })?;
+        }
+        Ok(())
+    }
+
     #[inline]
     fn array_multi_steps_match<const IN_ARRAY: bool>(
         &mut self,
@@ -223,61 +474,50 @@ impl<'a, 'b> Selector<'a, 'b> {
         match value.data_type() {
             DataType::Array => {
                 let array = unsafe { value.array()? };
-                let len = array.len()?;
-                if len == 0 {
-                    return Ok(false);
-                }
-
-                let mut arr_steps_index = 0;
-                while arr_steps_index < arr_steps.len() {
-                    let cur_step = &arr_steps[arr_steps_index];
-
-                    match cur_step {
-                        SingleStep::Single(single_index) => match single_index {
-                            SingleIndex::Index(index) => {
-                                if *index < len {
-                                    let val = unsafe { array.lazy_get_unchecked(*index)? };
-                                    let found = self.query_internal(val, step_index + 1)?;
-                                    if self.for_exists && found {
-                                        return Ok(true);
-                                    }
-                                }
-                            }
-                            SingleIndex::Last(minus) => {
-                                if len > *minus {
-                                    let val = unsafe { array.lazy_get_unchecked(len - 1 - minus)? };
-                                    let found = self.query_internal(val, step_index + 1)?;
-                                    if self.for_exists && found {
-                                        return Ok(true);
-                                    }
-                                }
-                            }
-                        },
-                        SingleStep::Range(begin, end) => {
-                            let last = len - 1;
-                            if let Some((b, e)) = find_range(begin, end, last) {
-                                for i in b..e + 1 {
-                                    let val = unsafe { array.lazy_get_unchecked(i)? };
-                                    let found = self.query_internal(val, step_index + 1)?;
-                                    if self.for_exists && found {
-                                        return Ok(true);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    arr_steps_index += 1;
+                if array.len()? > 0 {
+                    self.push_task(Task::ArraySteps {
+                        array,
+                        steps: arr_steps,
+                        step_index: step_index + 1,
+                    })?;
                 }
             }
             _ => {
                 if non_array_multi_steps_relaxed_match(arr_steps) {
                     return self.non_array_relax_match(value, step_index + 1);
                 }
+                self.type_mismatch = true;
             }
         }
         Ok(false)
     }
 
+    /// Queues the elements of the array selected by one of the steps of a multiple array step, to
+    /// apply the steps from `step_index`.
+    #[inline]
+    fn array_single_step(&mut self, array: Array<'a>, step: &'b SingleStep, step_index: usize) -> YasonResult<()> {
+        let len = array.len()?;
+        match step {
+            SingleStep::Single(single_index) => match single_index {
+                SingleIndex::Index(index) => {
+                    if *index < len {
+                        let val = unsafe { array.lazy_get_unchecked(*index)? };
+                        self.push_task(Task::Element(val, step_index))?;
+                    }
+                }
+                SingleIndex::Last(minus) => {
+                    if len > *minus {
+                        let val = unsafe { array.lazy_get_unchecked(len - 1 - minus)? };
+                        self.push_task(Task::Element(val, step_index))?;
+                    }
+                }
+                SingleIndex::Variable(_) => unreachable!(),
+            },
+            SingleStep::Range(begin, end) => self.array_range(array, begin, end, step_index)?,
+        }
+        Ok(())
+    }
+
     #[inline]
     fn array_wildcard_match<const IN_ARRAY: bool>(
         &mut self,
@@ -287,12 +527,7 @@ impl<'a, 'b> Selector<'a, 'b> {
         match value.data_type() {
             DataType::Array => {
                 let array = unsafe { value.array()? };
-                for val in array.lazy_iter()? {
-                    let found = self.query_internal(val?, step_index + 1)?;
-                    if self.for_exists && found {
-                        return Ok(true);
-                    }
-                }
+                self.push_task(Task::ArrayElements(array.lazy_iter()?, step_index + 1))?;
             }
             _ => return self.non_array_relax_match(value, step_index + 1),
         }
@@ -300,6 +535,9 @@ impl<'a, 'b> Selector<'a, 'b> {
         Ok(false)
     }
 
+    /// Skips the array steps which match a non-array value as an array of size 1, and applies the
+    /// following steps to the value. The next step is not an array step, so this never recurses
+    /// more than once.
     #[inline]
     fn non_array_relax_match<const IN_ARRAY: bool>(
         &mut self,
@@ -316,6 +554,7 @@ impl<'a, 'b> Selector<'a, 'b> {
                         if *index == 0 {
                             cur_step_index += 1;
                         } else {
+                            self.type_mismatch = true;
                             return Ok(false);
                         }
                     }
@@ -323,6 +562,7 @@ impl<'a, 'b> Selector<'a, 'b> {
                         if *minus == 0 {
                             cur_step_index += 1;
                         } else {
+                            self.type_mismatch = true;
                             return Ok(false);
                         }
                     }
@@ -330,6 +570,7 @@ impl<'a, 'b> Selector<'a, 'b> {
                         if non_array_range_step_relaxed_match(begin, end) {
                             cur_step_index += 1;
                         } else {
+                            self.type_mismatch = true;
                             return Ok(false);
                         }
                     }
@@ -337,18 +578,20 @@ impl<'a, 'b> Selector<'a, 'b> {
                         if non_array_multi_steps_relaxed_match(steps) {
                             cur_step_index += 1;
                         } else {
+                            self.type_mismatch = true;
                             return Ok(false);
                         }
                     }
                     ArrayStep::Wildcard => {
                         cur_step_index += 1;
                     }
+                    ArrayStep::Variable(_) => unreachable!(),
                 },
-                _ => return self.query_internal(value, cur_step_index),
+                _ => return self.visit(value, cur_step_index),
             }
         }
 
-        self.query_internal(value, cur_step_index)
+        self.visit(value, cur_step_index)
     }
 
     #[inline]
@@ -359,30 +602,38 @@ impl<'a, 'b> Selector<'a, 'b> {
         key: &'b str,
     ) -> YasonResult<bool> {
         match value.data_type() {
+            DataType::Object | DataType::Array if self.descendant_order == DescendantOrder::BreadthFirst => {
+                let mut queue = VecDeque::new();
+                queue.try_reserve(1).map_err(YasonError::TryReserveError)?;
+                queue.push_back(value.value()?);
+                self.push_task(Task::BreadthFirst {
+                    queue,
+                    expand: None,
+                    key,
+                    step_index,
+                })?;
+            }
             DataType::Object => {
                 let object = unsafe { value.object()? };
-                if let Some(val) = object.lazy_get(key)? {
-                    let found = self.query_internal(val, step_index + 1)?;
-                    if self.for_exists && found {
-                        return Ok(true);
-                    }
-                }
-
-                for val in object.lazy_value_iter()? {
-                    let found = self.query_internal(val?, step_index)?;
-                    if self.for_exists && found {
-                        return Ok(true);
+                if self.descendant_order == DescendantOrder::PreOrder {
+                    let val = self.lazy_get(&object, key)?;
+                    self.push_task(Task::ObjectValues(object.lazy_value_iter()?, step_index))?;
+                    if let Some(val) = val {
+                        self.push_task(Task::Value(val, step_index + 1))?;
                     }
+                } else {
+                    let values = object.lazy_value_iter()?;
+                    self.push_task(Task::DescendantKey {
+                        object,
+                        key,
+                        step_index,
+                    })?;
+                    self.push_task(Task::ObjectValues(values, step_index))?;
                 }
             }
             DataType::Array => {
                 let array = unsafe { value.array()? };
-                for val in array.lazy_iter()? {
-                    let found = self.query_internal(val?, step_index)?;
-                    if self.for_exists && found {
-                        return Ok(true);
-                    }
-                }
+                self.push_task(Task::ArrayElements(array.lazy_iter()?, step_index))?;
             }
             _ => {}
         }
@@ -390,6 +641,63 @@ impl<'a, 'b> Selector<'a, 'b> {
         Ok(false)
     }
 
+    /// Continues a breadth-first descendant step until a value of the key is found, whose
+    /// remaining steps are applied before the next container is visited.
+    fn descendent_breadth_first(
+        &mut self,
+        mut queue: VecDeque<Value<'a>>,
+        expand: Option<Object<'a>>,
+        key: &'b str,
+        step_index: usize,
+    ) -> YasonResult<()> {
+        if let Some(object) = expand {
+            for val in object.lazy_value_iter()? {
+                self.enqueue_container(&mut queue, val?)?;
+            }
+        }
+
+        while let Some(container) = queue.pop_front() {
+            match container {
+                Value::Object(object) => {
+                    if let Some(val) = self.lazy_get(&object, key)? {
+                        self.push_task(Task::BreadthFirst {
+                            queue,
+                            expand: Some(object),
+                            key,
+                            step_index,
+                        })?;
+                        return self.push_task(Task::Value(val, step_index + 1));
+                    }
+                    for val in object.lazy_value_iter()? {
+                        self.enqueue_container(&mut queue, val?)?;
+                    }
+                }
+                Value::Array(array) => {
+                    for val in array.lazy_iter()? {
+                        self.enqueue_container(&mut queue, val?)?;
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn enqueue_container<const IN_ARRAY: bool>(
+        &mut self,
+        queue: &mut VecDeque<Value<'a>>,
+        val: LazyValue<'a, IN_ARRAY>,
+    ) -> YasonResult<()> {
+        self.budget.step()?;
+        if matches!(val.data_type(), DataType::Object | DataType::Array) {
+            queue.try_reserve(1).map_err(YasonError::TryReserveError)?;
+            queue.push_back(val.value()?);
+        }
+        Ok(())
+    }
+
     #[inline]
     fn func_step_match<const IN_ARRAY: bool>(
         &mut self,
@@ -400,7 +708,13 @@ impl<'a, 'b> Selector<'a, 'b> {
         debug_assert!(step_index + 1 == self.steps.len());
         debug_assert!(self.with_wrapper);
         let val = match func {
-            FuncStep::Count => Value::Null,
+            FuncStep::Count => match self.count.as_mut() {
+                Some(count) => {
+                    *count += 1;
+                    return Ok(false);
+                }
+                None => Value::Null,
+            },
             FuncStep::Size => {
                 let size = match value.data_type() {
                     DataType::Array => {
@@ -412,10 +726,11 @@ impl<'a, 'b> Selector<'a, 'b> {
 
                 Value::Number(Number::from(size))
             }
-            FuncStep::Type => {
+            FuncStep::Type | FuncStep::Types => {
                 let data_type = value.data_type();
                 Value::String(data_type.name())
             }
+            FuncStep::Distinct => value.value()?,
         };
         push_value(self.query_buf, val)?;
         Ok(false)
@@ -437,6 +752,7 @@ fn non_array_multi_steps_relaxed_match(steps: &[SingleStep]) -> bool {
                         return true;
                     }
                 }
+                SingleIndex::Variable(_) => unreachable!(),
             },
 
             SingleStep::Range(left_field, right_field) => {
@@ -519,5 +835,6 @@ fn find_range(begin: &SingleIndex, end: &SingleIndex, last: usize) -> Option<(us
             let (minus1, minus2) = order(*m1, *m2);
             find_range_by_last(minus1, minus2, last)
         }
+        (SingleIndex::Variable(_), _) | (_, SingleIndex::Variable(_)) => unreachable!(),
     }
 }
@@ -0,0 +1,127 @@
+//! Owned query result.
+
+use crate::path::PathExpression;
+use crate::yason::YasonResult;
+use crate::{Value, Yason};
+use std::ops::Range;
+use std::slice;
+
+/// The values selected by a path expression together with the buffer they are selected from, see
+/// [`PathExpression::query_result`].
+///
+/// Only the byte ranges of the values are kept, every value is read from the buffer when it is
+/// accessed, so that the result can be returned up the stack without cloning the values.
+#[derive(Clone, Debug)]
+pub struct QueryResult<B> {
+    buf: B,
+    spans: Vec<Range<usize>>,
+}
+
+impl<B: AsRef<Yason>> QueryResult<B> {
+    /// Returns the number of selected values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns true if no value is selected.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the selected value at `index` as a yason, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Yason> {
+        let span = self.spans.get(index)?;
+        Some(unsafe { Yason::new_unchecked(&self.buf.as_ref().as_bytes()[span.clone()]) })
+    }
+
+    /// Returns the selected value at `index`, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn value(&self, index: usize) -> Option<YasonResult<Value<'_>>> {
+        self.get(index).map(Value::try_from)
+    }
+
+    /// Returns an iterator over the selected values as yasons, in the order they are selected.
+    #[inline]
+    pub fn iter(&self) -> QueryResultIter<'_> {
+        QueryResultIter {
+            bytes: self.buf.as_ref().as_bytes(),
+            spans: self.spans.iter(),
+        }
+    }
+
+    /// Returns the byte ranges of the selected values in the buffer, see
+    /// [`PathExpression::query_spans`].
+    #[inline]
+    pub fn spans(&self) -> &[Range<usize>] {
+        &self.spans
+    }
+
+    /// Returns the buffer the values are selected from.
+    #[inline]
+    pub fn buffer(&self) -> &B {
+        &self.buf
+    }
+
+    /// Returns the buffer the values are selected from, dropping the result.
+    #[inline]
+    pub fn into_buffer(self) -> B {
+        self.buf
+    }
+}
+
+/// An iterator over the values of a [`QueryResult`].
+#[derive(Clone, Debug)]
+pub struct QueryResultIter<'a> {
+    bytes: &'a [u8],
+    spans: slice::Iter<'a, Range<usize>>,
+}
+
+impl<'a> Iterator for QueryResultIter<'a> {
+    type Item = &'a Yason;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        Some(unsafe { Yason::new_unchecked(&self.bytes[span.clone()]) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl ExactSizeIterator for QueryResultIter<'_> {}
+
+impl PathExpression {
+    /// Selects the values of the path expression like [`query_spans`], and returns them together
+    /// with the buffer, e.g. a [`YasonBuf`] or an `Arc<YasonBuf>`, which is moved into the result.
+    ///
+    /// Returns `YasonError::InvalidPathExpression` if the path expression has an item method.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{PathExpression, QueryResult, YasonBuf};
+    ///
+    /// fn names(doc: &str) -> QueryResult<YasonBuf> {
+    ///     let yason = YasonBuf::parse(doc).unwrap();
+    ///     PathExpression::from_str("$.users[*].name").unwrap().query_result(yason).unwrap()
+    /// }
+    ///
+    /// let result = names(r#"{"users": [{"name": "foo"}, {"name": "bar"}]}"#);
+    /// assert_eq!(result.len(), 2);
+    /// let names: Vec<_> = result.iter().map(|name| name.string().unwrap()).collect();
+    /// assert_eq!(names, ["foo", "bar"]);
+    /// ```
+    ///
+    /// [`query_spans`]: PathExpression::query_spans
+    /// [`YasonBuf`]: crate::YasonBuf
+    #[inline]
+    pub fn query_result<B: AsRef<Yason>>(&self, buf: B) -> YasonResult<QueryResult<B>> {
+        let spans = self.query_spans(buf.as_ref())?;
+        Ok(QueryResult { buf, spans })
+    }
+}
@@ -0,0 +1,181 @@
+//! Read-only view of the steps of a path expression.
+
+use crate::path::parse::{ArrayStep, FuncStep, ObjectStep, SingleIndex, SingleStep, Step};
+use std::iter::FusedIterator;
+use std::slice;
+
+/// A step of a [`PathExpression`](crate::PathExpression), borrowed from the parsed expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathStep<'a> {
+    /// `$`
+    Root,
+    /// `$name`, a variable as the root of the path.
+    Variable(&'a str),
+    /// `.key`
+    Key(&'a str),
+    /// `.*`
+    KeyWildcard,
+    /// `[1]`, `[last - 1]`, `[$i]`, `[1 to 4]` or `[1, last, 1 to 4]`.
+    Array(ArraySelectors<'a>),
+    /// `[*]`
+    ArrayWildcard,
+    /// `..key`
+    Descendant(&'a str),
+    /// `.count()`, `.size()`, etc.
+    Method(PathMethod),
+}
+
+/// An item method at the end of a path expression.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PathMethod {
+    /// `.count()`
+    Count,
+    /// `.size()`
+    Size,
+    /// `.type()`
+    Type,
+    /// `.types()`
+    Types,
+    /// `.distinct()`
+    Distinct,
+}
+
+/// An array index of an array step.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ArrayIndex<'a> {
+    /// `1`
+    Index(usize),
+    /// `last - 1`, counted from the last element.
+    Last(usize),
+    /// `$i`, bound when the path expression is queried.
+    Variable(&'a str),
+}
+
+/// A selector of an array step.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ArraySelector<'a> {
+    /// `1`
+    Single(ArrayIndex<'a>),
+    /// `1 to 4`
+    Range(ArrayIndex<'a>, ArrayIndex<'a>),
+}
+
+/// The selectors of an array step, in the order they are written.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArraySelectors<'a>(Selectors<'a>);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Selectors<'a> {
+    One(Option<ArraySelector<'a>>),
+    Many(&'a [SingleStep]),
+}
+
+impl<'a> Iterator for ArraySelectors<'a> {
+    type Item = ArraySelector<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            Selectors::One(selector) => selector.take(),
+            Selectors::Many(steps) => {
+                let (step, rest) = steps.split_first()?;
+                *steps = rest;
+                Some(match step {
+                    SingleStep::Single(index) => ArraySelector::Single(index_view(index)),
+                    SingleStep::Range(begin, end) => ArraySelector::Range(index_view(begin), index_view(end)),
+                })
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.0 {
+            Selectors::One(selector) => selector.is_some() as usize,
+            Selectors::Many(steps) => steps.len(),
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for ArraySelectors<'_> {}
+
+impl FusedIterator for ArraySelectors<'_> {}
+
+/// An iterator over the steps of a path expression, see
+/// [`PathExpression::steps`](crate::PathExpression::steps).
+#[derive(Clone, Debug)]
+pub struct PathSteps<'a> {
+    steps: slice::Iter<'a, Step>,
+}
+
+impl<'a> PathSteps<'a> {
+    #[inline]
+    pub(crate) fn new(steps: &'a [Step]) -> Self {
+        Self { steps: steps.iter() }
+    }
+}
+
+impl<'a> Iterator for PathSteps<'a> {
+    type Item = PathStep<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.steps.next().map(step_view)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.steps.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for PathSteps<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.steps.next_back().map(step_view)
+    }
+}
+
+impl ExactSizeIterator for PathSteps<'_> {}
+
+impl FusedIterator for PathSteps<'_> {}
+
+fn step_view(step: &Step) -> PathStep<'_> {
+    match step {
+        Step::Root => PathStep::Root,
+        Step::Variable(name) => PathStep::Variable(name),
+        Step::Object(ObjectStep::Key(key)) => PathStep::Key(key),
+        Step::Object(ObjectStep::Wildcard) => PathStep::KeyWildcard,
+        Step::Array(array_step) => array_step_view(array_step),
+        Step::Descendent(key) => PathStep::Descendant(key),
+        Step::Func(func) => PathStep::Method(match func {
+            FuncStep::Count => PathMethod::Count,
+            FuncStep::Size => PathMethod::Size,
+            FuncStep::Type => PathMethod::Type,
+            FuncStep::Types => PathMethod::Types,
+            FuncStep::Distinct => PathMethod::Distinct,
+        }),
+    }
+}
+
+fn array_step_view(step: &ArrayStep) -> PathStep<'_> {
+    let selector = match step {
+        ArrayStep::Index(index) => ArraySelector::Single(ArrayIndex::Index(*index)),
+        ArrayStep::Last(index) => ArraySelector::Single(ArrayIndex::Last(*index)),
+        ArrayStep::Variable(name) => ArraySelector::Single(ArrayIndex::Variable(name)),
+        ArrayStep::Range(begin, end) => ArraySelector::Range(index_view(begin), index_view(end)),
+        ArrayStep::Multiple(steps) => return PathStep::Array(ArraySelectors(Selectors::Many(steps))),
+        ArrayStep::Wildcard => return PathStep::ArrayWildcard,
+    };
+    PathStep::Array(ArraySelectors(Selectors::One(Some(selector))))
+}
+
+#[inline]
+fn index_view(index: &SingleIndex) -> ArrayIndex<'_> {
+    match index {
+        SingleIndex::Index(index) => ArrayIndex::Index(*index),
+        SingleIndex::Last(index) => ArrayIndex::Last(*index),
+        SingleIndex::Variable(name) => ArrayIndex::Variable(name),
+    }
+}
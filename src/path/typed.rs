@@ -0,0 +1,134 @@
+//! Typed accessors of path expressions.
+
+use crate::num::to_i64;
+use crate::path::query::Selector;
+use crate::path::PathExpression;
+use crate::yason::YasonResult;
+use crate::{DataType, StepBudget, Value, Yason, YasonError};
+
+impl PathExpression {
+    /// Selects a single string value, returns `None` if the path matches nothing.
+    ///
+    /// Returns `YasonError::UnexpectedType` if the matched value is not a string,
+    /// `YasonError::MultiValuesWithoutWrapper` if the path matches more than one value,
+    /// `YasonError::ItemMethodNotSupported` if it has an item method, or
+    /// `YasonError::UnboundVariable` if it refers to variables.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{PathExpression, YasonBuf};
+    ///
+    /// let path = PathExpression::from_str("$.user.name").unwrap();
+    /// let yason = YasonBuf::parse(r#"{"user": {"name": "foo"}}"#).unwrap();
+    /// assert_eq!(path.get_str(&yason).unwrap(), Some("foo"));
+    /// ```
+    #[inline]
+    pub fn get_str<'a>(&self, yason: &'a Yason) -> YasonResult<Option<&'a str>> {
+        match self.get(yason)? {
+            Some(Value::String(value)) => Ok(Some(value)),
+            Some(value) => Err(unexpected_type(DataType::String, &value)),
+            None => Ok(None),
+        }
+    }
+
+    /// Selects a single integer value like [`get_str`], returns `YasonError::InvalidInteger` if
+    /// the number has a fractional part or is out of the range of `i64`.
+    ///
+    /// [`get_str`]: PathExpression::get_str
+    #[inline]
+    pub fn get_i64(&self, yason: &Yason) -> YasonResult<Option<i64>> {
+        match self.get(yason)? {
+            Some(Value::Number(value)) => match to_i64(&value) {
+                Some(value) => Ok(Some(value)),
+                None => Err(YasonError::InvalidInteger(value)),
+            },
+            Some(value) => Err(unexpected_type(DataType::Number, &value)),
+            None => Ok(None),
+        }
+    }
+
+    /// Selects a single bool value like [`get_str`].
+    ///
+    /// [`get_str`]: PathExpression::get_str
+    #[inline]
+    pub fn get_bool(&self, yason: &Yason) -> YasonResult<Option<bool>> {
+        match self.get(yason)? {
+            Some(Value::Bool(value)) => Ok(Some(value)),
+            Some(value) => Err(unexpected_type(DataType::Bool, &value)),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn get<'a>(&self, yason: &'a Yason) -> YasonResult<Option<Value<'a>>> {
+        self.check_bound()?;
+        if self.has_method() {
+            return Err(YasonError::ItemMethodNotSupported);
+        }
+
+        // Without array wrapper at most one value is selected, the buffer allocates at most once.
+        let mut query_buf = Vec::new();
+        let mut budget = StepBudget::unlimited();
+        let mut selector = Selector::new(self, false, &mut query_buf, false, &mut budget)
+            .with_limit(0, self.select_limit(false, usize::MAX));
+        selector.query(yason, 1)?;
+        Ok(query_buf.pop())
+    }
+}
+
+impl Yason {
+    /// Selects a single value and returns it as a yason of its own, returns `None` if the path
+    /// matches nothing.
+    ///
+    /// An object or an array is returned as the slice of this yason it is encoded in, like
+    /// [`Object::yason`] and [`Array::yason`], so it can be read, queried and compared as a root
+    /// document without copying. A scalar is encoded into `buf` instead, which is cleared first.
+    ///
+    /// Returns the errors of [`PathExpression::get_str`] except for `YasonError::UnexpectedType`.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use yason::{DataType, PathExpression, YasonBuf};
+    ///
+    /// let yason = YasonBuf::parse(r#"{"user": {"name": "foo", "tags": [1, 2]}}"#).unwrap();
+    /// let mut buf = Vec::new();
+    ///
+    /// let path = PathExpression::from_str("$.user").unwrap();
+    /// let user = yason.sub_yason(&path, &mut buf).unwrap().unwrap();
+    /// assert_eq!(user.data_type().unwrap(), DataType::Object);
+    /// assert_eq!(user.format(false).to_string(), r#"{"name":"foo","tags":[1,2]}"#);
+    ///
+    /// let path = PathExpression::from_str("$.user.name").unwrap();
+    /// let name = yason.sub_yason(&path, &mut buf).unwrap().unwrap();
+    /// assert_eq!(name.string().unwrap(), "foo");
+    /// ```
+    ///
+    /// [`Object::yason`]: crate::Object::yason
+    /// [`Array::yason`]: crate::Array::yason
+    #[inline]
+    pub fn sub_yason<'a>(&'a self, path: &PathExpression, buf: &'a mut Vec<u8>) -> YasonResult<Option<&'a Yason>> {
+        let value = match path.get(self)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let yason = match value {
+            Value::Object(object) => object.yason(),
+            Value::Array(array) => array.yason(),
+            scalar => {
+                buf.clear();
+                scalar.try_to_yason(buf)?;
+                // SAFETY: the scalar has just been encoded into the buffer
+                unsafe { Yason::new_unchecked(buf) }
+            }
+        };
+        Ok(Some(yason))
+    }
+}
+
+#[inline]
+fn unexpected_type(expected: DataType, value: &Value) -> YasonError {
+    YasonError::UnexpectedType {
+        expected,
+        actual: value.data_type(),
+    }
+}
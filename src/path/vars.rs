@@ -0,0 +1,183 @@
+//! Variables bound to path expressions.
+
+use crate::num::to_i64;
+use crate::path::parse::{ArrayStep, SingleIndex, SingleStep, Step};
+use crate::path::{PathExpression, QueriedValue};
+use crate::yason::YasonResult;
+use crate::{DataType, StepBudget, Value, Yason, YasonError};
+use std::collections::HashMap;
+
+impl PathExpression {
+    /// Returns whether the path expression refers to variables, either as root (`$var.key`) or as
+    /// array index (`$.key[$var]`).
+    #[inline]
+    pub fn has_variables(&self) -> bool {
+        self.first_variable().is_some()
+    }
+
+    /// Selects and returns one or more values like [`query`], with variables bound to values,
+    /// like the `PASSING` clause of SQL/JSON.
+    ///
+    /// A path starting with `$var` selects from the value bound to `var` instead of `yason`. An
+    /// array index `$var` must be bound to a non-negative integer. Returns
+    /// `YasonError::UnboundVariable` if a variable is missing from `vars`, or
+    /// `YasonError::InvalidVariable` if an index variable is not a valid array index.
+    ///
+    /// [`query`]: PathExpression::query
+    #[inline]
+    pub fn query_with_vars<'a, 'b>(
+        &self,
+        yason: &'a Yason,
+        vars: &HashMap<&str, &'a Yason>,
+        with_wrapper: bool,
+        query_buf: Option<&'b mut Vec<Value<'a>>>,
+        result_buf: Option<&'b mut Vec<u8>>,
+    ) -> YasonResult<QueriedValue<'a, 'b>> {
+        let root = self.root(yason, vars)?;
+        let mut budget = StepBudget::unlimited();
+        let outcome = match self.bind(vars)? {
+            Some(path) => path.select(root, with_wrapper, query_buf, result_buf, &mut budget)?,
+            None => self.select(root, with_wrapper, query_buf, result_buf, &mut budget)?,
+        };
+        Ok(outcome.value)
+    }
+
+    /// Returns whether the path expression matches one or more values like [`exists`], with
+    /// variables bound to values like [`query_with_vars`].
+    ///
+    /// [`exists`]: PathExpression::exists
+    /// [`query_with_vars`]: PathExpression::query_with_vars
+    #[inline]
+    pub fn exists_with_vars(&self, yason: &Yason, vars: &HashMap<&str, &Yason>) -> YasonResult<bool> {
+        if self.has_method() {
+            return Err(YasonError::InvalidPathExpression);
+        }
+
+        let root = self.root(yason, vars)?;
+        let mut query_buf = Vec::new();
+        match self.bind(vars)? {
+            Some(path) => path.exists_in(root, &mut query_buf, &mut StepBudget::unlimited()),
+            None => self.exists_in(root, &mut query_buf, &mut StepBudget::unlimited()),
+        }
+    }
+
+    /// Returns `YasonError::UnboundVariable` if the path expression refers to variables.
+    #[inline]
+    pub(crate) fn check_bound(&self) -> YasonResult<()> {
+        match self.first_variable() {
+            Some(name) => Err(YasonError::UnboundVariable(name.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    fn first_variable(&self) -> Option<&str> {
+        self.inner_steps().iter().find_map(|step| match step {
+            Step::Variable(name) => Some(name.as_str()),
+            _ => step_variable(step),
+        })
+    }
+
+    /// Returns the value the path expression selects from.
+    #[inline]
+    fn root<'a>(&self, yason: &'a Yason, vars: &HashMap<&str, &'a Yason>) -> YasonResult<&'a Yason> {
+        match &self.inner_steps()[0] {
+            Step::Variable(name) => lookup(vars, name),
+            _ => Ok(yason),
+        }
+    }
+
+    /// Returns the path expression with the root replaced by `$` and the index variables replaced
+    /// by their values, or `None` if there is nothing to replace but the root.
+    #[inline]
+    fn bind(&self, vars: &HashMap<&str, &Yason>) -> YasonResult<Option<PathExpression>> {
+        if self.inner_steps()[1..].iter().all(|step| step_variable(step).is_none()) {
+            return Ok(None);
+        }
+
+        let mut steps = Vec::new();
+        steps
+            .try_reserve(self.inner_steps().len())
+            .map_err(YasonError::TryReserveError)?;
+        steps.push(Step::Root);
+        for step in &self.inner_steps()[1..] {
+            let step = match step {
+                Step::Array(ArrayStep::Variable(name)) => Step::Array(ArrayStep::Index(lookup_index(vars, name)?)),
+                Step::Array(ArrayStep::Range(begin, end)) => {
+                    Step::Array(ArrayStep::Range(bind_index(begin, vars)?, bind_index(end, vars)?))
+                }
+                Step::Array(ArrayStep::Multiple(single_steps)) => {
+                    let mut bound = Vec::new();
+                    bound
+                        .try_reserve(single_steps.len())
+                        .map_err(YasonError::TryReserveError)?;
+                    for single_step in single_steps {
+                        bound.push(match single_step {
+                            SingleStep::Single(index) => SingleStep::Single(bind_index(index, vars)?),
+                            SingleStep::Range(begin, end) => {
+                                SingleStep::Range(bind_index(begin, vars)?, bind_index(end, vars)?)
+                            }
+                        });
+                    }
+                    Step::Array(ArrayStep::Multiple(bound))
+                }
+                _ => step.clone(),
+            };
+            steps.push(step);
+        }
+        Ok(Some(
+            PathExpression::new(steps).with_descendant_order(self.descendant_order),
+        ))
+    }
+}
+
+#[inline]
+fn index_variable(index: &SingleIndex) -> Option<&str> {
+    match index {
+        SingleIndex::Variable(name) => Some(name.as_str()),
+        _ => None,
+    }
+}
+
+/// Returns the first index variable of an array step.
+#[inline]
+fn step_variable(step: &Step) -> Option<&str> {
+    match step {
+        Step::Array(ArrayStep::Variable(name)) => Some(name.as_str()),
+        Step::Array(ArrayStep::Range(begin, end)) => index_variable(begin).or_else(|| index_variable(end)),
+        Step::Array(ArrayStep::Multiple(steps)) => steps.iter().find_map(|step| match step {
+            SingleStep::Single(index) => index_variable(index),
+            SingleStep::Range(begin, end) => index_variable(begin).or_else(|| index_variable(end)),
+        }),
+        _ => None,
+    }
+}
+
+#[inline]
+fn bind_index(index: &SingleIndex, vars: &HashMap<&str, &Yason>) -> YasonResult<SingleIndex> {
+    match index {
+        SingleIndex::Variable(name) => Ok(SingleIndex::Index(lookup_index(vars, name)?)),
+        _ => Ok(index.clone()),
+    }
+}
+
+#[inline]
+fn lookup<'a>(vars: &HashMap<&str, &'a Yason>, name: &str) -> YasonResult<&'a Yason> {
+    vars.get(name)
+        .copied()
+        .ok_or_else(|| YasonError::UnboundVariable(name.to_string()))
+}
+
+/// Returns the array index bound to the variable, which has the same range as an index literal.
+#[inline]
+fn lookup_index(vars: &HashMap<&str, &Yason>, name: &str) -> YasonResult<usize> {
+    let value = lookup(vars, name)?;
+    let index = match value.data_type()? {
+        DataType::Number => to_i64(&value.number()?),
+        _ => None,
+    };
+    match index {
+        Some(index) if (0..=i32::MAX as i64).contains(&index) => Ok(index as usize),
+        _ => Err(YasonError::InvalidVariable(name.to_string())),
+    }
+}
@@ -0,0 +1,465 @@
+//! Transforms composed into a single pass.
+//!
+//! A [`Pipeline`] applies several of the [`transform`](crate::transform)s to a document while it
+//! is copied once, instead of building an intermediate document for every transform.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use std::str::FromStr;
+//! use yason::pipeline::Pipeline;
+//! use yason::{PathExpression, Value, YasonBuf};
+//!
+//! let yason = YasonBuf::parse(r#"{"userName": "foo", "email": "a@b", "phone": null, "tags": [null, 1]}"#).unwrap();
+//! let paths = [PathExpression::from_str("$.email").unwrap()];
+//! let pipeline = Pipeline::new()
+//!     .strip_nulls()
+//!     .rename_keys(|key| match key {
+//!         "userName" => Some(Cow::Borrowed("user_name")),
+//!         _ => None,
+//!     })
+//!     .redact(&paths, Value::String("***"))
+//!     .canonicalize();
+//!
+//! let mut buf = Vec::new();
+//! let result = pipeline.run(&yason, &mut buf).unwrap();
+//! assert_eq!(result.format(false).to_string(), r#"{"tags":[null,1],"email":"***","user_name":"foo"}"#);
+//! assert!(result.is_canonical().unwrap());
+//! ```
+
+use crate::builder::BuildError;
+use crate::limits::MAX_KEY_LENGTH;
+use crate::transform::{push_array_value, push_object_value, select_addresses, write_value};
+use crate::util::key_cmp;
+use crate::yason::LazyValue;
+use crate::{Array, ArrayRefBuilder, Object, ObjectRefBuilder, PathExpression, Scalar, Value, Yason, YasonError};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+/// The kind of a stage of a [`Pipeline`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StageKind {
+    /// See [`Pipeline::strip_nulls`].
+    StripNulls,
+    /// See [`Pipeline::rename_keys`].
+    RenameKeys,
+    /// See [`Pipeline::redact`].
+    Redact,
+}
+
+impl fmt::Display for StageKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageKind::StripNulls => write!(f, "strip_nulls"),
+            StageKind::RenameKeys => write!(f, "rename_keys"),
+            StageKind::Redact => write!(f, "redact"),
+        }
+    }
+}
+
+/// The error returned by [`Pipeline::run`], with the stage which caused it.
+#[derive(Debug)]
+pub struct PipelineError {
+    stage: Option<(usize, StageKind)>,
+    error: YasonError,
+}
+
+impl PipelineError {
+    #[inline]
+    fn new(stage: Option<(usize, StageKind)>, error: YasonError) -> Self {
+        Self { stage, error }
+    }
+
+    /// Returns the index of the stage in the pipeline and its kind, or `None` if the error is not
+    /// caused by a stage, e.g. if the source document is invalid.
+    #[inline]
+    pub fn stage(&self) -> Option<(usize, StageKind)> {
+        self.stage
+    }
+
+    /// Returns the underlying error.
+    #[inline]
+    pub fn error(&self) -> &YasonError {
+        &self.error
+    }
+
+    /// Consumes the error, returning the underlying error.
+    #[inline]
+    pub fn into_error(self) -> YasonError {
+        self.error
+    }
+}
+
+impl fmt::Display for PipelineError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.stage {
+            Some((index, kind)) => write!(f, "stage {} ({}): {}", index, kind, self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl Error for PipelineError {}
+
+impl From<YasonError> for PipelineError {
+    #[inline]
+    fn from(error: YasonError) -> Self {
+        Self::new(None, error)
+    }
+}
+
+impl From<BuildError> for PipelineError {
+    #[inline]
+    fn from(error: BuildError) -> Self {
+        Self::new(None, error.into())
+    }
+}
+
+pub type PipelineResult<T> = std::result::Result<T, PipelineError>;
+
+type KeyMapper<'p> = Box<dyn Fn(&str) -> Option<Cow<str>> + 'p>;
+
+enum Stage<'p> {
+    StripNulls,
+    RenameKeys(KeyMapper<'p>),
+    Redact {
+        paths: &'p [PathExpression],
+        replacement: Value<'p>,
+    },
+}
+
+impl Stage<'_> {
+    #[inline]
+    fn kind(&self) -> StageKind {
+        match self {
+            Stage::StripNulls => StageKind::StripNulls,
+            Stage::RenameKeys(_) => StageKind::RenameKeys,
+            Stage::Redact { .. } => StageKind::Redact,
+        }
+    }
+}
+
+/// A sequence of transforms applied to a document in a single pass over its bytes.
+///
+/// The stages are applied in the order they are added to every key-value of every object, and the
+/// redactions also to every array element, from the root down to the nested values. A later stage
+/// sees the result of the earlier ones, e.g. a value redacted with null is removed by a following
+/// [`strip_nulls`], but not by a preceding one. The paths of a redaction select values of the
+/// source document, by its original keys.
+///
+/// [`strip_nulls`]: Pipeline::strip_nulls
+#[derive(Default)]
+pub struct Pipeline<'p> {
+    stages: Vec<Stage<'p>>,
+    canonical: bool,
+}
+
+impl<'p> Pipeline<'p> {
+    /// Creates a pipeline without stages, which copies documents as they are.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a stage removing the key-values of objects whose value is null. Nulls in arrays are
+    /// kept.
+    #[inline]
+    pub fn strip_nulls(mut self) -> Self {
+        self.stages.push(Stage::StripNulls);
+        self
+    }
+
+    /// Adds a stage renaming keys like [`transform::rename_keys`](crate::transform::rename_keys).
+    ///
+    /// The run fails at this stage with `YasonError::DuplicateKey` if two keys of an object have
+    /// the same name after renaming, and with `YasonError::KeyTooLong` if a new name is longer
+    /// than 65535 bytes.
+    #[inline]
+    pub fn rename_keys<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&str) -> Option<Cow<str>> + 'p,
+    {
+        self.stages.push(Stage::RenameKeys(Box::new(mapper)));
+        self
+    }
+
+    /// Adds a stage replacing the values selected by any of the path expressions with
+    /// `replacement` like [`transform::redact`](crate::transform::redact). The replacement is not
+    /// transformed by the following stages, only its own value is seen by them.
+    ///
+    /// The run fails at this stage with `YasonError::InvalidPathExpression` if a path expression
+    /// has an item method, and with `YasonError::UnboundVariable` if it refers to variables.
+    #[inline]
+    pub fn redact(mut self, paths: &'p [PathExpression], replacement: Value<'p>) -> Self {
+        self.stages.push(Stage::Redact { paths, replacement });
+        self
+    }
+
+    /// Makes the output the canonical form of the transformed document, see
+    /// [`Yason::canonicalize`]. This applies to the whole output, wherever it is called.
+    #[inline]
+    pub fn canonicalize(mut self) -> Self {
+        self.canonical = true;
+        self
+    }
+
+    /// Returns the number of stages.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns true if the pipeline has no stage.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Copies the yason into `out` through the stages and returns the copy.
+    ///
+    /// The paths of the redactions are evaluated first, then the document is copied once. The copy
+    /// is appended to `out`, the bytes appended before an error are kept.
+    pub fn run<'a>(&self, yason: &Yason, out: &'a mut Vec<u8>) -> PipelineResult<&'a Yason> {
+        let mut addresses = Vec::new();
+        addresses
+            .try_reserve(self.stages.len())
+            .map_err(YasonError::TryReserveError)?;
+        for (index, stage) in self.stages.iter().enumerate() {
+            addresses.push(match stage {
+                Stage::Redact { paths, .. } => select_addresses(yason, paths)
+                    .map_err(|e| PipelineError::new(Some((index, StageKind::Redact)), e))?,
+                _ => Vec::new(),
+            });
+        }
+
+        let pass = Pass {
+            stages: &self.stages,
+            addresses,
+            canonical: self.canonical,
+        };
+        let root = LazyValue::try_from(yason)?;
+        let slot = pass.redacted(root.value()?, root.address());
+        let start = out.len();
+        pass.write_slot(&slot, out)?;
+        Ok(unsafe { Yason::new_unchecked(&out[start..]) })
+    }
+}
+
+impl fmt::Debug for Pipeline<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("stages", &self.stages.iter().map(Stage::kind).collect::<Vec<_>>())
+            .field("canonical", &self.canonical)
+            .finish()
+    }
+}
+
+/// A value of the output, either from the source or the replacement of a redaction stage.
+enum Slot<'v> {
+    Source(Value<'v>),
+    Replaced(usize),
+}
+
+/// The state of a run.
+struct Pass<'s, 'p> {
+    stages: &'s [Stage<'p>],
+    /// The sorted addresses of the values selected by every stage, empty if it is no redaction.
+    addresses: Vec<Vec<usize>>,
+    canonical: bool,
+}
+
+impl<'s, 'p> Pass<'s, 'p> {
+    /// Returns a pass without stages, which writes replacements.
+    #[inline]
+    fn plain(&self) -> Pass<'s, 'p> {
+        Pass {
+            stages: &[],
+            addresses: Vec::new(),
+            canonical: self.canonical,
+        }
+    }
+
+    #[inline]
+    fn replacement(&self, index: usize) -> &'s Value<'p> {
+        match &self.stages[index] {
+            Stage::Redact { replacement, .. } => replacement,
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn is_null(&self, slot: &Slot) -> bool {
+        match slot {
+            Slot::Source(value) => matches!(value, Value::Null),
+            Slot::Replaced(index) => matches!(self.replacement(*index), Value::Null),
+        }
+    }
+
+    /// Applies the redaction stages to a value of the source at `address`.
+    #[inline]
+    fn redacted<'v>(&self, value: Value<'v>, address: usize) -> Slot<'v> {
+        for (index, addresses) in self.addresses.iter().enumerate() {
+            if addresses.binary_search(&address).is_ok() {
+                return Slot::Replaced(index);
+            }
+        }
+        Slot::Source(value)
+    }
+
+    /// Returns the key-values of the object after the stages, in insertion order, or in key order
+    /// if the output is canonical.
+    fn object_entries<'v>(&self, object: &Object<'v>) -> PipelineResult<Vec<(Cow<'v, str>, Slot<'v>)>> {
+        let mut entries = Vec::new();
+        entries
+            .try_reserve(object.len()?)
+            .map_err(YasonError::TryReserveError)?;
+        let mut renamed_by = None;
+
+        'entries: for entry in object.lazy_iter_insertion_order()? {
+            let (key, value) = entry?;
+            let address = value.address();
+            let mut key = Cow::Borrowed(key);
+            let mut slot = Slot::Source(value.value()?);
+            for (index, stage) in self.stages.iter().enumerate() {
+                match stage {
+                    Stage::StripNulls => {
+                        if self.is_null(&slot) {
+                            continue 'entries;
+                        }
+                    }
+                    Stage::RenameKeys(mapper) => {
+                        renamed_by = Some(index);
+                        if let Some(new_key) = mapper(&key) {
+                            if new_key.len() > MAX_KEY_LENGTH {
+                                let error = YasonError::KeyTooLong(new_key.len());
+                                return Err(PipelineError::new(Some((index, StageKind::RenameKeys)), error));
+                            }
+                            key = Cow::Owned(new_key.into_owned());
+                        }
+                    }
+                    Stage::Redact { .. } => {
+                        if matches!(slot, Slot::Source(_)) && self.addresses[index].binary_search(&address).is_ok() {
+                            slot = Slot::Replaced(index);
+                        }
+                    }
+                }
+            }
+            entries.push((key, slot));
+        }
+
+        // Keys of the source are distinct, only renaming can make them collide.
+        if let Some(index) = renamed_by {
+            let mut sorted = Vec::new();
+            sorted.try_reserve(entries.len()).map_err(YasonError::TryReserveError)?;
+            sorted.extend(entries.iter().map(|(key, _)| key.as_ref()));
+            sorted.sort_unstable_by(|l, r| key_cmp(l, r));
+            for pair in sorted.windows(2) {
+                if key_cmp(pair[0], pair[1]) == Ordering::Equal {
+                    let error = YasonError::DuplicateKey(pair[0].to_string());
+                    return Err(PipelineError::new(Some((index, StageKind::RenameKeys)), error));
+                }
+            }
+        }
+        if self.canonical {
+            entries.sort_by(|(l, _), (r, _)| key_cmp(l, r));
+        }
+        Ok(entries)
+    }
+
+    fn push_entries(&self, builder: &mut ObjectRefBuilder, entries: &[(Cow<str>, Slot)]) -> PipelineResult<()> {
+        for (key, slot) in entries {
+            match slot {
+                Slot::Source(value) => self.push_object_value(builder, key, value)?,
+                Slot::Replaced(index) => self.plain().push_object_value(builder, key, self.replacement(*index))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn push_elements(&self, builder: &mut ArrayRefBuilder, array: &Array) -> PipelineResult<()> {
+        for value in array.lazy_iter()? {
+            let value = value?;
+            match self.redacted(value.value()?, value.address()) {
+                Slot::Source(value) => self.push_array_value(builder, &value)?,
+                Slot::Replaced(index) => self.plain().push_array_value(builder, self.replacement(index))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn push_object_value(&self, builder: &mut ObjectRefBuilder, key: &str, value: &Value) -> PipelineResult<()> {
+        match value {
+            Value::Object(object) => {
+                let entries = self.object_entries(object)?;
+                let mut nested = builder.push_object(key, entries.len() as u16, self.canonical)?;
+                self.push_entries(&mut nested, &entries)?;
+                nested.finish()?;
+            }
+            Value::Array(array) => {
+                let mut nested = builder.push_array(key, array.len()? as u16)?;
+                self.push_elements(&mut nested, array)?;
+                nested.finish()?;
+            }
+            Value::Number(number) if self.canonical => {
+                builder.push_number(key, number.normalize())?;
+            }
+            _ => push_object_value(builder, key, value)?,
+        }
+        Ok(())
+    }
+
+    fn push_array_value(&self, builder: &mut ArrayRefBuilder, value: &Value) -> PipelineResult<()> {
+        match value {
+            Value::Object(object) => {
+                let entries = self.object_entries(object)?;
+                let mut nested = builder.push_object(entries.len() as u16, self.canonical)?;
+                self.push_entries(&mut nested, &entries)?;
+                nested.finish()?;
+            }
+            Value::Array(array) => {
+                let mut nested = builder.push_array(array.len()? as u16)?;
+                self.push_elements(&mut nested, array)?;
+                nested.finish()?;
+            }
+            Value::Number(number) if self.canonical => {
+                builder.push_number(number.normalize())?;
+            }
+            _ => push_array_value(builder, value)?,
+        }
+        Ok(())
+    }
+
+    /// Appends the root value to the bytes.
+    fn write_slot(&self, slot: &Slot, out: &mut Vec<u8>) -> PipelineResult<()> {
+        let value = match slot {
+            Slot::Source(value) => value,
+            Slot::Replaced(index) => {
+                return self
+                    .plain()
+                    .write_slot(&Slot::Source(self.replacement(*index).clone()), out)
+            }
+        };
+        match value {
+            Value::Object(object) => {
+                let entries = self.object_entries(object)?;
+                let mut builder = ObjectRefBuilder::try_new(out, entries.len() as u16, self.canonical)?;
+                self.push_entries(&mut builder, &entries)?;
+                builder.finish()?;
+            }
+            Value::Array(array) => {
+                let mut builder = ArrayRefBuilder::try_new(out, array.len()? as u16)?;
+                self.push_elements(&mut builder, array)?;
+                builder.finish()?;
+            }
+            Value::Number(number) if self.canonical => {
+                Scalar::number_with_vec(number.normalize(), out)?;
+            }
+            _ => write_value(value, out)?,
+        }
+        Ok(())
+    }
+}
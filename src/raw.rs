@@ -0,0 +1,91 @@
+//! Low-level building blocks of the binary format, for compatible encoders and conformance tests.
+//!
+//! The grammar of values is described in the [crate documentation](crate#yason-binary-format).
+//! All multi-byte fields are little-endian, and all offsets are relative to the position right
+//! after the size field of the enclosing object or array.
+//!
+//! | Part          | Layout                                                                                   |
+//! |---------------|------------------------------------------------------------------------------------------|
+//! | object        | type, size (`i32`), element count (`u16`), key offsets (`u32` each), key-values          |
+//! | compact object| [`COMPACT_OBJECT_TYPE`], size, element count, key offsets (`u16` each), key-values       |
+//! | key-value     | key length (`u16`), key bytes, type, value                                               |
+//! | array         | type, size (`i32`), element count (`u16`), value entries, outlined values                |
+//! | value entry   | type, offset of the outlined value or the inlined bool or null (`u32`)                   |
+//! | string/binary | type, data length (varint), bytes                                                        |
+//! | number        | type, length (`u8`), decimal bytes                                                       |
+//! | header        | [`HEADER_MAGIC`], version (`u8`), flags (`u8`), optional before the value                |
+//! | footer        | CRC32C (`u32`) of the bytes before it, after the value if [`FLAG_CHECKSUM`] is set       |
+//!
+//! The size of an object or an array counts the bytes from the element count to the end of the
+//! value. Key offsets are ordered by key length first, then by key bytes.
+//!
+//! An object with the type byte [`COMPACT_OBJECT_TYPE`] has the same layout with `u16` key
+//! offsets. Readers of the legacy format cannot decode it, so the builders only encode a non-empty
+//! object this way if asked for with `with_compact_objects` and its size is at most
+//! [`MAX_COMPACT_OBJECT_SIZE`]. Readers accept both, and value entries of arrays keep the type of
+//! [`DataType::Object`](crate::DataType::Object) for either.
+//!
+//! ```rust
+//! use yason::raw::{self, COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, OBJECT_SIZE};
+//! use yason::{DataType, ObjectBuilder, YasonBuf};
+//!
+//! let yason = YasonBuf::parse(r#"{"key": "value"}"#).unwrap();
+//! let bytes = yason.as_bytes();
+//! assert_eq!(bytes[0], DataType::Object as u8);
+//! let size = i32::from_le_bytes(bytes[DATA_TYPE_SIZE..DATA_TYPE_SIZE + OBJECT_SIZE].try_into().unwrap());
+//! assert_eq!(size as usize, bytes.len() - DATA_TYPE_SIZE - OBJECT_SIZE);
+//!
+//! let string_pos = bytes.len() - "value".len() - 1;
+//! assert_eq!(raw::decode_varint(bytes, string_pos).unwrap(), (5, 1));
+//!
+//! let mut builder = ObjectBuilder::try_new(1, false).unwrap().with_compact_objects();
+//! builder.push_string("key", "value").unwrap();
+//! let compact = builder.finish().unwrap();
+//! assert_eq!(compact.as_bytes()[0], COMPACT_OBJECT_TYPE);
+//! assert_eq!(compact.as_bytes().len(), bytes.len() - 2);
+//! ```
+
+pub use crate::binary::{
+    ARRAY_SIZE, BOOL_SIZE, CHECKSUM_SIZE, COMPACT_KEY_OFFSET_SIZE, COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, DATE_SIZE,
+    ELEMENT_COUNT_SIZE, FLAG_CHECKSUM, FORMAT_VERSION, HEADER_MAGIC, HEADER_SIZE, INTERVAL_DT_SIZE, INTERVAL_YM_SIZE,
+    KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, LEGACY_FORMAT_VERSION, MAX_COMPACT_OBJECT_SIZE, MAX_DATA_LENGTH_SIZE,
+    MAX_STRING_SIZE, NUMBER_LENGTH_SIZE, OBJECT_SIZE, SHORT_DATE_SIZE, TIMESTAMP_SIZE, TIME_SIZE, VALUE_ENTRY_SIZE,
+};
+
+use crate::builder::BuildResult;
+use crate::util;
+use crate::vec::VecExt;
+use crate::yason::YasonResult;
+use crate::YasonError;
+
+/// Appends the value as a data length, 7 bits per byte from the lowest with the high bit set on
+/// every byte but the last.
+///
+/// Returns `BuildError::StringTooLong` if the value is greater than [`MAX_STRING_SIZE`].
+#[inline]
+pub fn encode_varint(value: u32, buf: &mut Vec<u8>) -> BuildResult<()> {
+    buf.try_reserve(varint_size(value))?;
+    buf.push_data_length(value as usize)
+}
+
+/// Decodes the data length at `pos` of the bytes, returns the value and the number of bytes it
+/// takes.
+///
+/// Returns `YasonError::IndexOutOfBounds` if the bytes end before the last byte of the data
+/// length, or `YasonError::InvalidFormat` if it is longer than [`MAX_DATA_LENGTH_SIZE`] bytes.
+#[inline]
+pub fn decode_varint(bytes: &[u8], pos: usize) -> YasonResult<(u32, usize)> {
+    if pos >= bytes.len() {
+        return Err(YasonError::IndexOutOfBounds {
+            len: bytes.len(),
+            index: pos,
+        });
+    }
+    util::decode_varint(bytes, pos)
+}
+
+/// Returns the number of bytes needed to encode the value as a data length.
+#[inline]
+pub fn varint_size(value: u32) -> usize {
+    util::varint_size(value)
+}
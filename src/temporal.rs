@@ -0,0 +1,597 @@
+//! Temporal values.
+//!
+//! All temporal values are counted in microseconds without a time zone:
+//!
+//! - a timestamp is the number of microseconds since `1970-01-01T00:00:00`,
+//! - a date is a timestamp truncated to whole seconds,
+//! - a short date is the number of days since `1970-01-01`,
+//! - a time is the number of microseconds since midnight,
+//! - an interval year-month ([`YmInterval`]) is a number of months,
+//! - an interval day-time ([`DtInterval`]) is a number of microseconds.
+//!
+//! They are formatted as ISO-8601 strings, e.g. `2024-02-29T13:45:30.5`, `13:45:30`, `P1Y2M` and
+//! `P1DT2H3M4.5S`.
+
+use std::fmt::{self, Display, Formatter, Write};
+
+pub(crate) const MICROS_PER_SECOND: i64 = 1_000_000;
+pub(crate) const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+pub(crate) const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+pub(crate) const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+/// A timestamp without time zone, in microseconds since `1970-01-01T00:00:00`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[repr(transparent)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// Creates a timestamp from the number of microseconds since `1970-01-01T00:00:00`.
+    #[inline]
+    pub const fn from_micros(micros: i64) -> Self {
+        Timestamp(micros)
+    }
+
+    /// Returns the number of microseconds since `1970-01-01T00:00:00`.
+    #[inline]
+    pub const fn micros(self) -> i64 {
+        self.0
+    }
+
+    /// Adds an interval day-time to the timestamp, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_add(self, rhs: DtInterval) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(micros) => Some(Timestamp(micros)),
+            None => None,
+        }
+    }
+
+    /// Subtracts an interval day-time from the timestamp, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_sub(self, rhs: DtInterval) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(micros) => Some(Timestamp(micros)),
+            None => None,
+        }
+    }
+
+    /// Returns the interval elapsed from `earlier` to this timestamp, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_since(self, earlier: Timestamp) -> Option<DtInterval> {
+        match self.0.checked_sub(earlier.0) {
+            Some(micros) => Some(DtInterval(micros)),
+            None => None,
+        }
+    }
+}
+
+impl Display for Timestamp {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_date_time(self.0, f)
+    }
+}
+
+/// A date with a time of day in whole seconds, in microseconds since `1970-01-01T00:00:00`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[repr(transparent)]
+pub struct Date(i64);
+
+impl Date {
+    /// Creates a date from the number of microseconds since `1970-01-01T00:00:00`, the fraction of
+    /// a second is truncated.
+    #[inline]
+    pub const fn from_micros(micros: i64) -> Self {
+        let fraction = micros.rem_euclid(MICROS_PER_SECOND);
+        match micros.checked_sub(fraction) {
+            Some(micros) => Date(micros),
+            // The first second representable is incomplete, rounds up to the next one instead.
+            None => Date(micros + (MICROS_PER_SECOND - fraction)),
+        }
+    }
+
+    /// Returns the number of microseconds since `1970-01-01T00:00:00`, which is always a whole
+    /// number of seconds.
+    #[inline]
+    pub const fn micros(self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for Date {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_date_time(self.0, f)
+    }
+}
+
+/// A calendar date without a time of day, in days since `1970-01-01`.
+///
+/// Negative day numbers are dates before the epoch, e.g. `-1` is `1969-12-31`. Days are counted in
+/// the proleptic Gregorian calendar.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[repr(transparent)]
+pub struct ShortDate(i32);
+
+impl ShortDate {
+    /// Creates a short date from the number of days since `1970-01-01`.
+    #[inline]
+    pub const fn from_days(days: i32) -> Self {
+        ShortDate(days)
+    }
+
+    /// Returns the number of days since `1970-01-01`.
+    #[inline]
+    pub const fn days(self) -> i32 {
+        self.0
+    }
+
+    /// Converts the short date into a date at midnight, returns `None` if it is out of the range of
+    /// `Date`.
+    #[inline]
+    pub const fn to_date(self) -> Option<Date> {
+        match (self.0 as i64).checked_mul(MICROS_PER_DAY) {
+            Some(micros) => Some(Date(micros)),
+            None => None,
+        }
+    }
+}
+
+impl From<Date> for ShortDate {
+    /// Converts a date into a short date, the time of day is truncated.
+    #[inline]
+    fn from(date: Date) -> Self {
+        // The days of any `i64` microseconds always fit in an `i32`.
+        ShortDate(date.0.div_euclid(MICROS_PER_DAY) as i32)
+    }
+}
+
+impl Display for ShortDate {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_date(self.0 as i64, f)
+    }
+}
+
+/// A time of day, in microseconds since midnight.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[repr(transparent)]
+pub struct Time(i64);
+
+impl Time {
+    /// Creates a time from the number of microseconds since midnight, returns `None` if it is not
+    /// less than a day.
+    #[inline]
+    pub const fn from_micros(micros: i64) -> Option<Self> {
+        if micros >= 0 && micros < MICROS_PER_DAY {
+            Some(Time(micros))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of microseconds since midnight.
+    #[inline]
+    pub const fn micros(self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for Time {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_time_of_day(self.0, f)
+    }
+}
+
+/// An interval year-month, in months.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[repr(transparent)]
+pub struct YmInterval(i32);
+
+impl YmInterval {
+    /// Creates an interval of the given number of months.
+    #[inline]
+    pub const fn from_months(months: i32) -> Self {
+        YmInterval(months)
+    }
+
+    /// Creates an interval of the given number of years, returns `None` on overflow.
+    #[inline]
+    pub const fn from_years(years: i32) -> Option<Self> {
+        match years.checked_mul(12) {
+            Some(months) => Some(YmInterval(months)),
+            None => None,
+        }
+    }
+
+    /// Creates an interval of the given years and months, returns `None` on overflow.
+    #[inline]
+    pub const fn new(years: i32, months: i32) -> Option<Self> {
+        match years.checked_mul(12) {
+            Some(y) => match y.checked_add(months) {
+                Some(months) => Some(YmInterval(months)),
+                None => None,
+            },
+            None => None,
+        }
+    }
+
+    /// Returns the total number of months.
+    #[inline]
+    pub const fn months(self) -> i32 {
+        self.0
+    }
+
+    /// Returns the number of whole years, truncated towards zero.
+    #[inline]
+    pub const fn years(self) -> i32 {
+        self.0 / 12
+    }
+
+    /// Adds two intervals, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_add(self, rhs: YmInterval) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(months) => Some(YmInterval(months)),
+            None => None,
+        }
+    }
+
+    /// Subtracts an interval from this one, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_sub(self, rhs: YmInterval) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(months) => Some(YmInterval(months)),
+            None => None,
+        }
+    }
+
+    /// Negates the interval, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_neg(self) -> Option<Self> {
+        match self.0.checked_neg() {
+            Some(months) => Some(YmInterval(months)),
+            None => None,
+        }
+    }
+}
+
+impl From<i32> for YmInterval {
+    #[inline]
+    fn from(months: i32) -> Self {
+        YmInterval(months)
+    }
+}
+
+impl Display for YmInterval {
+    /// Writes the interval as an ISO-8601 duration, e.g. `P1Y2M` or `-P3M`.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.0 < 0 {
+            f.write_char('-')?;
+        }
+        let months = self.0.unsigned_abs();
+        f.write_char('P')?;
+        if months >= 12 {
+            write!(f, "{}Y", months / 12)?;
+        }
+        if months % 12 != 0 || months == 0 {
+            write!(f, "{}M", months % 12)?;
+        }
+        Ok(())
+    }
+}
+
+/// An interval day-time, in microseconds.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[repr(transparent)]
+pub struct DtInterval(i64);
+
+impl DtInterval {
+    /// Creates an interval of the given number of microseconds.
+    #[inline]
+    pub const fn from_micros(micros: i64) -> Self {
+        DtInterval(micros)
+    }
+
+    /// Creates an interval of the given number of seconds, returns `None` on overflow.
+    #[inline]
+    pub const fn from_seconds(seconds: i64) -> Option<Self> {
+        match seconds.checked_mul(MICROS_PER_SECOND) {
+            Some(micros) => Some(DtInterval(micros)),
+            None => None,
+        }
+    }
+
+    /// Creates an interval of the given number of days, returns `None` on overflow.
+    #[inline]
+    pub const fn from_days(days: i64) -> Option<Self> {
+        match days.checked_mul(MICROS_PER_DAY) {
+            Some(micros) => Some(DtInterval(micros)),
+            None => None,
+        }
+    }
+
+    /// Returns the total number of microseconds.
+    #[inline]
+    pub const fn micros(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the number of whole seconds, truncated towards zero.
+    #[inline]
+    pub const fn seconds(self) -> i64 {
+        self.0 / MICROS_PER_SECOND
+    }
+
+    /// Returns the number of whole days, truncated towards zero.
+    #[inline]
+    pub const fn days(self) -> i64 {
+        self.0 / MICROS_PER_DAY
+    }
+
+    /// Adds two intervals, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_add(self, rhs: DtInterval) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(micros) => Some(DtInterval(micros)),
+            None => None,
+        }
+    }
+
+    /// Subtracts an interval from this one, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_sub(self, rhs: DtInterval) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(micros) => Some(DtInterval(micros)),
+            None => None,
+        }
+    }
+
+    /// Negates the interval, returns `None` on overflow.
+    #[inline]
+    pub const fn checked_neg(self) -> Option<Self> {
+        match self.0.checked_neg() {
+            Some(micros) => Some(DtInterval(micros)),
+            None => None,
+        }
+    }
+}
+
+impl From<i64> for DtInterval {
+    #[inline]
+    fn from(micros: i64) -> Self {
+        DtInterval(micros)
+    }
+}
+
+impl Display for DtInterval {
+    /// Writes the interval as an ISO-8601 duration, e.g. `P1DT2H3M4.5S` or `-PT1S`.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.0 < 0 {
+            f.write_char('-')?;
+        }
+        let micros = self.0.unsigned_abs();
+        let (days, rem) = (micros / MICROS_PER_DAY as u64, micros % MICROS_PER_DAY as u64);
+        let (hours, rem) = (rem / MICROS_PER_HOUR as u64, rem % MICROS_PER_HOUR as u64);
+        let (minutes, rem) = (rem / MICROS_PER_MINUTE as u64, rem % MICROS_PER_MINUTE as u64);
+        let (seconds, fraction) = (rem / MICROS_PER_SECOND as u64, rem % MICROS_PER_SECOND as u64);
+
+        f.write_char('P')?;
+        if days != 0 {
+            write!(f, "{}D", days)?;
+        }
+        if rem == 0 && hours == 0 && minutes == 0 && days != 0 {
+            return Ok(());
+        }
+        f.write_char('T')?;
+        if hours != 0 {
+            write!(f, "{}H", hours)?;
+        }
+        if minutes != 0 {
+            write!(f, "{}M", minutes)?;
+        }
+        if rem != 0 || (hours == 0 && minutes == 0) {
+            write!(f, "{}", seconds)?;
+            write_fraction(fraction as u32, f)?;
+            f.write_char('S')?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `YYYY-MM-DDTHH:MM:SS[.ffffff]`, years out of `0000..=9999` are written with a sign.
+fn write_date_time<W: Write>(micros: i64, w: &mut W) -> fmt::Result {
+    write_date(micros.div_euclid(MICROS_PER_DAY), w)?;
+    w.write_char('T')?;
+    write_time_of_day(micros.rem_euclid(MICROS_PER_DAY), w)
+}
+
+/// Writes `YYYY-MM-DD` of the days since `1970-01-01`, years out of `0000..=9999` are written with
+/// a sign.
+fn write_date<W: Write>(days: i64, w: &mut W) -> fmt::Result {
+    let (year, month, day) = civil_from_days(days);
+    if (0..=9999).contains(&year) {
+        write!(w, "{:04}", year)?;
+    } else {
+        write!(w, "{:+05}", year)?;
+    }
+    write!(w, "-{:02}-{:02}", month, day)
+}
+
+/// Writes `HH:MM:SS[.ffffff]` of the microseconds since midnight.
+fn write_time_of_day<W: Write>(micros: i64, w: &mut W) -> fmt::Result {
+    let hours = micros / MICROS_PER_HOUR;
+    let minutes = micros % MICROS_PER_HOUR / MICROS_PER_MINUTE;
+    let seconds = micros % MICROS_PER_MINUTE / MICROS_PER_SECOND;
+    write!(w, "{:02}:{:02}:{:02}", hours, minutes, seconds)?;
+    write_fraction((micros % MICROS_PER_SECOND) as u32, w)
+}
+
+/// Writes the fraction of a second without trailing zeros, nothing if it is zero.
+fn write_fraction<W: Write>(micros: u32, w: &mut W) -> fmt::Result {
+    if micros == 0 {
+        return Ok(());
+    }
+    let mut digits = 6;
+    let mut fraction = micros;
+    while fraction % 10 == 0 {
+        fraction /= 10;
+        digits -= 1;
+    }
+    write!(w, ".{:0width$}", fraction, width = digits)
+}
+
+/// Converts the number of days since `1970-01-01` into a proleptic Gregorian `(year, month, day)`.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+pub(crate) const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use super::{Date, ShortDate, Time, Timestamp, MICROS_PER_DAY, MICROS_PER_SECOND};
+    use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+
+    /// Days from `0001-01-01` to `1970-01-01`.
+    const UNIX_EPOCH_DAYS_FROM_CE: i64 = 719163;
+
+    #[inline]
+    fn micros_of_date_time(dt: &NaiveDateTime) -> i64 {
+        let days = dt.date().num_days_from_ce() as i64 - UNIX_EPOCH_DAYS_FROM_CE;
+        days * MICROS_PER_DAY + micros_of_time(&dt.time())
+    }
+
+    #[inline]
+    fn micros_of_time(t: &NaiveTime) -> i64 {
+        // A leap second is clamped to the last microsecond of its second.
+        let micros = (t.nanosecond() / 1_000).min(999_999);
+        t.num_seconds_from_midnight() as i64 * MICROS_PER_SECOND + micros as i64
+    }
+
+    #[inline]
+    fn date_time_of_micros(micros: i64) -> Option<NaiveDateTime> {
+        let days = micros.div_euclid(MICROS_PER_DAY) + UNIX_EPOCH_DAYS_FROM_CE;
+        let date = NaiveDate::from_num_days_from_ce_opt(i32::try_from(days).ok()?)?;
+        Some(NaiveDateTime::new(
+            date,
+            time_of_micros(micros.rem_euclid(MICROS_PER_DAY)),
+        ))
+    }
+
+    #[inline]
+    fn time_of_micros(micros: i64) -> NaiveTime {
+        let secs = (micros / MICROS_PER_SECOND) as u32;
+        let nanos = (micros % MICROS_PER_SECOND) as u32 * 1_000;
+        // SAFETY: `micros` is always less than a day.
+        NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos).unwrap()
+    }
+
+    impl From<NaiveDateTime> for Timestamp {
+        /// Converts a date time into a timestamp, the nanoseconds are truncated.
+        #[inline]
+        fn from(dt: NaiveDateTime) -> Self {
+            Timestamp::from_micros(micros_of_date_time(&dt))
+        }
+    }
+
+    impl From<DateTime<Utc>> for Timestamp {
+        /// Converts a UTC date time into a timestamp, the nanoseconds are truncated.
+        #[inline]
+        fn from(dt: DateTime<Utc>) -> Self {
+            Timestamp::from(dt.naive_utc())
+        }
+    }
+
+    impl Timestamp {
+        /// Converts the timestamp into a date time, returns `None` if it is out of the range of
+        /// `NaiveDateTime`.
+        #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+        #[inline]
+        pub fn to_naive(self) -> Option<NaiveDateTime> {
+            date_time_of_micros(self.0)
+        }
+
+        /// Converts the timestamp into a UTC date time, returns `None` if it is out of the range of
+        /// `DateTime<Utc>`.
+        #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+        #[inline]
+        pub fn to_utc(self) -> Option<DateTime<Utc>> {
+            self.to_naive().map(|dt| Utc.from_utc_datetime(&dt))
+        }
+    }
+
+    impl From<NaiveDateTime> for Date {
+        /// Converts a date time into a date, the fraction of a second is truncated.
+        #[inline]
+        fn from(dt: NaiveDateTime) -> Self {
+            Date::from_micros(micros_of_date_time(&dt))
+        }
+    }
+
+    impl From<NaiveDate> for Date {
+        /// Converts a calendar date into a date at midnight.
+        #[inline]
+        fn from(date: NaiveDate) -> Self {
+            let days = date.num_days_from_ce() as i64 - UNIX_EPOCH_DAYS_FROM_CE;
+            Date::from_micros(days * MICROS_PER_DAY)
+        }
+    }
+
+    impl Date {
+        /// Converts the date into a date time, returns `None` if it is out of the range of
+        /// `NaiveDateTime`.
+        #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+        #[inline]
+        pub fn to_naive(self) -> Option<NaiveDateTime> {
+            date_time_of_micros(self.0)
+        }
+    }
+
+    impl From<NaiveDate> for ShortDate {
+        /// Converts a calendar date into a short date.
+        #[inline]
+        fn from(date: NaiveDate) -> Self {
+            // The range of `NaiveDate` is far narrower than `i32` days.
+            ShortDate::from_days((date.num_days_from_ce() as i64 - UNIX_EPOCH_DAYS_FROM_CE) as i32)
+        }
+    }
+
+    impl ShortDate {
+        /// Converts the short date into a calendar date, returns `None` if it is out of the range of
+        /// `NaiveDate`.
+        #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+        #[inline]
+        pub fn to_naive(self) -> Option<NaiveDate> {
+            let days = self.0 as i64 + UNIX_EPOCH_DAYS_FROM_CE;
+            NaiveDate::from_num_days_from_ce_opt(i32::try_from(days).ok()?)
+        }
+    }
+
+    impl From<NaiveTime> for Time {
+        /// Converts a time of day into a time, the nanoseconds are truncated.
+        #[inline]
+        fn from(t: NaiveTime) -> Self {
+            Time(micros_of_time(&t))
+        }
+    }
+
+    impl Time {
+        /// Converts the time into a time of day.
+        #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+        #[inline]
+        pub fn to_naive(self) -> NaiveTime {
+            time_of_micros(self.0)
+        }
+    }
+}
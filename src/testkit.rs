@@ -0,0 +1,170 @@
+//! Generators and round-trip assertions for testing code built on top of yason.
+//!
+//! Documents are generated as `serde_json::Value` trees, which can be encoded by
+//! `YasonBuf::try_from` and compared after decoding.
+//!
+//! ```rust
+//! use proptest::prelude::*;
+//! use yason::testkit::{self, GenOptions};
+//!
+//! proptest!(|(doc in testkit::document(GenOptions::default()))| {
+//!     testkit::assert_round_trip(&doc);
+//! });
+//! ```
+
+use crate::{Number, Yason, YasonBuf};
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+/// Limits of the generated documents.
+#[derive(Copy, Clone, Debug)]
+pub struct GenOptions {
+    /// Maximum nesting depth of objects and arrays.
+    pub max_depth: u32,
+    /// Desired total number of nodes of a document.
+    pub max_size: u32,
+    /// Maximum number of elements of each object or array.
+    pub max_width: usize,
+    /// Maximum number of characters of each key or string.
+    pub max_string_len: usize,
+}
+
+impl GenOptions {
+    /// Creates `GenOptions` with the specified limits.
+    #[inline]
+    pub const fn new(max_depth: u32, max_size: u32, max_width: usize, max_string_len: usize) -> Self {
+        Self {
+            max_depth,
+            max_size,
+            max_width,
+            max_string_len,
+        }
+    }
+}
+
+impl Default for GenOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new(4, 64, 8, 16)
+    }
+}
+
+/// Returns a proptest strategy which generates documents within the given limits.
+pub fn document(options: GenOptions) -> BoxedStrategy<Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        (any::<i64>(), 0..=16u32).prop_map(|(int, scale)| number_value(int, scale)),
+        string(options.max_string_len).prop_map(Value::String),
+    ];
+
+    let max_width = options.max_width;
+    let max_string_len = options.max_string_len;
+    leaf.prop_recursive(options.max_depth, options.max_size, max_width as u32, move |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..=max_width).prop_map(Value::Array),
+            proptest::collection::btree_map(string(max_string_len), inner, 0..=max_width)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+    .boxed()
+}
+
+#[inline]
+fn string(max_len: usize) -> impl Strategy<Value = String> {
+    proptest::collection::vec(any::<char>(), 0..=max_len).prop_map(String::from_iter)
+}
+
+/// Generates a document within the given limits from unstructured data.
+pub fn arbitrary_document(u: &mut Unstructured<'_>, options: GenOptions) -> arbitrary::Result<Value> {
+    let mut size = options.max_size;
+    arbitrary_value(u, &options, options.max_depth, &mut size)
+}
+
+fn arbitrary_value(
+    u: &mut Unstructured<'_>,
+    options: &GenOptions,
+    depth: u32,
+    size: &mut u32,
+) -> arbitrary::Result<Value> {
+    *size = size.saturating_sub(1);
+    let kinds = if depth > 0 && *size > 0 { 6 } else { 4 };
+    let value = match u.choose_index(kinds)? {
+        0 => Value::Null,
+        1 => Value::Bool(bool::arbitrary(u)?),
+        2 => number_value(i64::arbitrary(u)?, u.int_in_range(0..=16)?),
+        3 => Value::String(arbitrary_string(u, options.max_string_len)?),
+        4 => {
+            let len = u.int_in_range(0..=options.max_width)?;
+            let mut array = Vec::with_capacity(len);
+            for _ in 0..len.min(*size as usize) {
+                array.push(arbitrary_value(u, options, depth - 1, size)?);
+            }
+            Value::Array(array)
+        }
+        _ => {
+            let len = u.int_in_range(0..=options.max_width)?;
+            let mut object = Map::new();
+            for _ in 0..len.min(*size as usize) {
+                let key = arbitrary_string(u, options.max_string_len)?;
+                let value = arbitrary_value(u, options, depth - 1, size)?;
+                object.insert(key, value);
+            }
+            Value::Object(object)
+        }
+    };
+    Ok(value)
+}
+
+#[inline]
+fn arbitrary_string(u: &mut Unstructured<'_>, max_len: usize) -> arbitrary::Result<String> {
+    let len = u.int_in_range(0..=max_len)?;
+    let mut str = String::with_capacity(len);
+    for _ in 0..len {
+        str.push(char::arbitrary(u)?);
+    }
+    Ok(str)
+}
+
+#[inline]
+fn number_value(int: i64, scale: u32) -> Value {
+    let number = Number::from_str(&format!("{}e-{}", int, scale)).expect("number should be in range");
+    let number = serde_json::Number::from_str(&number.to_string()).expect("number should be valid json");
+    Value::Number(number)
+}
+
+/// A document generated with the default [`GenOptions`], for use as a fuzz target input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArbitraryDocument(pub Value);
+
+impl<'a> Arbitrary<'a> for ArbitraryDocument {
+    #[inline]
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_document(u, GenOptions::default()).map(ArbitraryDocument)
+    }
+}
+
+/// Encodes the document to yason and asserts that it survives formatting and parsing again.
+///
+/// Returns the encoded yason.
+pub fn assert_round_trip(doc: &Value) -> YasonBuf {
+    let yason = YasonBuf::try_from(doc).expect("document should be encoded");
+    assert_yason_round_trip(&yason);
+    yason
+}
+
+/// Asserts that the yason is equal to itself after formatting and parsing again, both in compact and pretty
+/// format.
+pub fn assert_yason_round_trip(yason: &Yason) {
+    for pretty in [false, true] {
+        let text = yason.format(pretty).to_string();
+        let parsed = YasonBuf::parse(&text).unwrap_or_else(|e| panic!("failed to parse {:?}: {}", text, e));
+        assert!(
+            yason.equals(&parsed).expect("yason should be valid"),
+            "round trip mismatch: {}",
+            text
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! Asynchronous parsing and formatting over tokio io.
+
+use crate::format::{CompactFormatter, FormatResult, Formatter, PrettyFormatter, Sealed};
+use crate::json::{dedup_keys, Json};
+use crate::limits::MAX_NESTED_DEPTH;
+use crate::{BuildError, DataType, Yason, YasonBuf};
+use std::borrow::Cow;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// The input is read in chunks of this size, and the formatted bytes are written once the buffer
+/// grows beyond it.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+impl YasonBuf {
+    /// Reads a json string from the reader and parses it to `YasonBuf` like [`YasonBuf::parse`],
+    /// without blocking the thread while waiting for the input.
+    ///
+    /// The json text is parsed incrementally as it is read in chunks, so the whole text is never
+    /// held in memory, only the parsed values and a token split by the end of a chunk. An invalid
+    /// json is reported as an `io::ErrorKind::InvalidData` error.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let mut input = &br#"{"a": [1, 2], "b": null}"#[..];
+    /// let yason = YasonBuf::parse_async(&mut input).await.unwrap();
+    /// assert_eq!(yason.format(false).to_string(), r#"{"a":[1,2],"b":null}"#);
+    /// # });
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    #[inline]
+    pub async fn parse_async<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<YasonBuf> {
+        let mut parser = IncrementalParser::new();
+        let mut chunk = vec![0; CHUNK_SIZE];
+        loop {
+            let len = reader.read(&mut chunk).await?;
+            if len == 0 {
+                break;
+            }
+            parser.feed(&chunk[..len], false)?;
+        }
+        parser.finish()?.to_yason().map_err(invalid_data)
+    }
+}
+
+impl Yason {
+    /// Formats the yason as a compact or pretty string to the writer, without blocking the thread
+    /// while the writer is not ready.
+    ///
+    /// The output is written in chunks between the entries of the outermost object or array, so
+    /// at most about one of its entries is buffered in memory at a time. The writer is flushed at
+    /// the end. An invalid yason is reported as an `io::ErrorKind::InvalidData` error.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let yason = YasonBuf::parse(r#"{"a": [1, 2], "b": null}"#).unwrap();
+    /// let mut output = Vec::new();
+    /// yason.format_to_async(false, &mut output).await.unwrap();
+    /// assert_eq!(output, br#"{"a":[1,2],"b":null}"#);
+    /// # });
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    #[inline]
+    pub async fn format_to_async<W: AsyncWrite + Unpin>(&self, pretty: bool, writer: &mut W) -> io::Result<()> {
+        if pretty {
+            format_chunked(self, &mut PrettyFormatter::new(), writer).await
+        } else {
+            format_chunked(self, &mut CompactFormatter::new(), writer).await
+        }
+    }
+}
+
+/// What the parser expects as the next token.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Expect {
+    Value,
+    ValueOrEnd,
+    Key,
+    KeyOrEnd,
+    Colon,
+    CommaOrEnd,
+    Nothing,
+}
+
+/// An array or an object which is being parsed.
+enum Container {
+    Array(Vec<Json<'static>>),
+    Object {
+        entries: Vec<(Cow<'static, str>, Json<'static>)>,
+        key: Option<Cow<'static, str>>,
+    },
+}
+
+/// A json parser which is fed the text in chunks. Complete tokens are parsed as soon as they are
+/// fed, only a token split by the end of a chunk is kept until the next chunk.
+struct IncrementalParser {
+    pending: Vec<u8>,
+    /// Number of bytes consumed before `pending`.
+    offset: usize,
+    stack: Vec<Container>,
+    expect: Expect,
+    root: Option<Json<'static>>,
+}
+
+impl IncrementalParser {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            offset: 0,
+            stack: Vec::new(),
+            expect: Expect::Value,
+            root: None,
+        }
+    }
+
+    /// Parses the complete tokens of the chunk, given `eof`, a number or a literal at the end is
+    /// complete as well.
+    fn feed(&mut self, chunk: &[u8], eof: bool) -> io::Result<()> {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(chunk);
+        let mut pos = 0;
+        loop {
+            while let Some(b' ' | b'\t' | b'\n' | b'\r') = pending.get(pos) {
+                pos += 1;
+            }
+            if pos == pending.len() {
+                break;
+            }
+            let end = match token_end(&pending, pos, eof) {
+                Some(end) => end,
+                None => break,
+            };
+            self.token(&pending[pos..end], self.offset + pos)?;
+            pos = end;
+        }
+        pending.drain(..pos);
+        self.offset += pos;
+        self.pending = pending;
+        Ok(())
+    }
+
+    /// Parses the rest of the text, which must have completed the root value.
+    #[inline]
+    fn finish(mut self) -> io::Result<Json<'static>> {
+        self.feed(&[], true)?;
+        match self.root {
+            Some(root) if self.pending.is_empty() => Ok(root),
+            _ => Err(invalid_data(format!("unexpected end of json at byte {}", self.offset))),
+        }
+    }
+
+    fn token(&mut self, token: &[u8], pos: usize) -> io::Result<()> {
+        match (token[0], self.expect) {
+            (b'[', Expect::Value | Expect::ValueOrEnd) => self.open(Container::Array(Vec::new()), Expect::ValueOrEnd),
+            (b'{', Expect::Value | Expect::ValueOrEnd) => self.open(
+                Container::Object {
+                    entries: Vec::new(),
+                    key: None,
+                },
+                Expect::KeyOrEnd,
+            ),
+            (b']', Expect::ValueOrEnd | Expect::CommaOrEnd)
+                if matches!(self.stack.last(), Some(Container::Array(_))) =>
+            {
+                self.close();
+                Ok(())
+            }
+            (b'}', Expect::KeyOrEnd | Expect::CommaOrEnd)
+                if matches!(self.stack.last(), Some(Container::Object { .. })) =>
+            {
+                self.close();
+                Ok(())
+            }
+            (b':', Expect::Colon) => {
+                self.expect = Expect::Value;
+                Ok(())
+            }
+            (b',', Expect::CommaOrEnd) => {
+                self.expect = match self.stack.last() {
+                    Some(Container::Object { .. }) => Expect::Key,
+                    _ => Expect::Value,
+                };
+                Ok(())
+            }
+            (b'[' | b'{' | b']' | b'}' | b':' | b',', _) => Err(unexpected(pos)),
+            (_, Expect::Key | Expect::KeyOrEnd) => match scalar(token)? {
+                Json::String(key) => {
+                    if let Some(Container::Object { key: pending_key, .. }) = self.stack.last_mut() {
+                        *pending_key = Some(key);
+                    }
+                    self.expect = Expect::Colon;
+                    Ok(())
+                }
+                _ => Err(unexpected(pos)),
+            },
+            (_, Expect::Value | Expect::ValueOrEnd) => {
+                let value = scalar(token)?;
+                self.push_value(value);
+                Ok(())
+            }
+            _ => Err(unexpected(pos)),
+        }
+    }
+
+    #[inline]
+    fn open(&mut self, container: Container, expect: Expect) -> io::Result<()> {
+        if self.stack.len() >= MAX_NESTED_DEPTH {
+            return Err(invalid_data(BuildError::NestedTooDeeply));
+        }
+        self.stack.push(container);
+        self.expect = expect;
+        Ok(())
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        let value = match self.stack.pop() {
+            Some(Container::Array(array)) => Json::Array(array),
+            Some(Container::Object { mut entries, .. }) => {
+                dedup_keys(&mut entries);
+                Json::Object(entries)
+            }
+            None => return,
+        };
+        self.push_value(value);
+    }
+
+    #[inline]
+    fn push_value(&mut self, value: Json<'static>) {
+        match self.stack.last_mut() {
+            Some(Container::Array(array)) => array.push(value),
+            Some(Container::Object { entries, key }) => entries.push((key.take().unwrap_or_default(), value)),
+            None => {
+                self.root = Some(value);
+                self.expect = Expect::Nothing;
+                return;
+            }
+        }
+        self.expect = Expect::CommaOrEnd;
+    }
+}
+
+/// Returns the end of the token at `start`, or `None` if the token may continue in the next chunk.
+#[inline]
+fn token_end(bytes: &[u8], start: usize, eof: bool) -> Option<usize> {
+    match bytes[start] {
+        b'"' => {
+            let mut pos = start + 1;
+            loop {
+                match bytes.get(pos) {
+                    Some(b'"') => return Some(pos + 1),
+                    Some(b'\\') => pos += 2,
+                    Some(_) => pos += 1,
+                    None => return None,
+                }
+            }
+        }
+        b'[' | b'{' | b']' | b'}' | b':' | b',' => Some(start + 1),
+        _ => {
+            // numbers and literals, which are checked by parsing them
+            let len = bytes[start..]
+                .iter()
+                .position(|b| !matches!(b, b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'-' | b'+' | b'.'));
+            match len {
+                Some(len) => Some(start + len.max(1)),
+                None if eof => Some(bytes.len()),
+                None => None,
+            }
+        }
+    }
+}
+
+/// Parses a string, number or literal token.
+#[inline]
+fn scalar(token: &[u8]) -> io::Result<Json<'static>> {
+    let text = std::str::from_utf8(token).map_err(invalid_data)?;
+    let value = match Json::parse(text).map_err(invalid_data)? {
+        Json::Null => Json::Null,
+        Json::Bool(val) => Json::Bool(val),
+        Json::Number(val) => Json::Number(Cow::Owned(val.into_owned())),
+        Json::String(val) => Json::String(Cow::Owned(val.into_owned())),
+        Json::Array(_) | Json::Object(_) => return Err(invalid_data("invalid json token")),
+    };
+    Ok(value)
+}
+
+#[inline]
+fn unexpected(pos: usize) -> io::Error {
+    invalid_data(format!("unexpected token at byte {} of json", pos))
+}
+
+async fn format_chunked<F, W>(yason: &Yason, formatter: &mut F, writer: &mut W) -> io::Result<()>
+where
+    F: Formatter,
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = String::new();
+    match yason.data_type().map_err(invalid_data)? {
+        DataType::Object => {
+            let object = yason.object().map_err(invalid_data)?;
+            check(formatter.begin_value(&mut buf))?;
+            check(formatter.begin_object(&mut buf))?;
+            for (i, entry) in object.lazy_iter().map_err(invalid_data)?.enumerate() {
+                let (key, value) = entry.map_err(invalid_data)?;
+                check(formatter.write_object_value(key, &value, i == 0, &mut buf, Sealed))?;
+                write_chunk(&mut buf, writer).await?;
+            }
+            check(formatter.end_object(&mut buf))?;
+        }
+        DataType::Array => {
+            let array = yason.array().map_err(invalid_data)?;
+            check(formatter.begin_value(&mut buf))?;
+            check(formatter.begin_array(&mut buf))?;
+            for (i, value) in array.lazy_iter().map_err(invalid_data)?.enumerate() {
+                let value = value.map_err(invalid_data)?;
+                check(formatter.write_array_value(&value, i == 0, &mut buf, Sealed))?;
+                write_chunk(&mut buf, writer).await?;
+            }
+            check(formatter.end_array(&mut buf))?;
+        }
+        _ => check(formatter.format(yason, &mut buf))?,
+    }
+    writer.write_all(buf.as_bytes()).await?;
+    writer.flush().await
+}
+
+/// Writes the buffered output once it is large enough.
+#[inline]
+async fn write_chunk<W: AsyncWrite + Unpin>(buf: &mut String, writer: &mut W) -> io::Result<()> {
+    if buf.len() >= CHUNK_SIZE {
+        writer.write_all(buf.as_bytes()).await?;
+        buf.clear();
+    }
+    Ok(())
+}
+
+#[inline]
+fn check(result: FormatResult<()>) -> io::Result<()> {
+    result.map_err(invalid_data)
+}
+
+#[inline]
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,142 @@
+//! Recording document fields into `tracing` spans and events.
+
+use crate::num::to_i64;
+use crate::yason::YasonResult;
+use crate::{Object, Scalar, Value, Yason};
+use tracing::field::{display, FieldSet, Visit};
+use tracing::Span;
+
+impl Yason {
+    /// Records the fields of the object like [`Object::record_fields`]. Returns `YasonError` if the
+    /// yason is not an object.
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    #[inline]
+    pub fn record_fields(&self, fields: &FieldSet, visitor: &mut dyn Visit) -> YasonResult<()> {
+        self.object()?.record_fields(fields, visitor)
+    }
+
+    /// Records the fields of the object into a span like [`Object::record_span`]. Returns
+    /// `YasonError` if the yason is not an object.
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    #[inline]
+    pub fn record_span(&self, span: &Span) -> YasonResult<()> {
+        self.object()?.record_span(span)
+    }
+}
+
+impl<'a> Object<'a> {
+    /// Records the value of every field in `fields` which names a key of the object, so that
+    /// structured logs include selected document fields without formatting the whole document.
+    ///
+    /// A field name with dots, e.g. `user.name`, selects a key of nested objects. Strings, bools
+    /// and integral numbers are recorded by the typed methods of `visitor`, other numbers, nulls,
+    /// binaries, temporal values, objects and arrays are recorded by `record_debug` with their JSON
+    /// text. Fields which do not name a key are skipped.
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    #[inline]
+    pub fn record_fields(&self, fields: &FieldSet, visitor: &mut dyn Visit) -> YasonResult<()> {
+        for field in fields {
+            let value = match self.field_value(field.name())? {
+                Some(value) => value,
+                None => continue,
+            };
+            match &value {
+                Value::String(s) => visitor.record_str(&field, s),
+                Value::Bool(b) => visitor.record_bool(&field, *b),
+                Value::Number(n) => match to_i64(n) {
+                    Some(i) => visitor.record_i64(&field, i),
+                    None => visitor.record_debug(&field, &format_args!("{}", n)),
+                },
+                Value::Null => visitor.record_debug(&field, &format_args!("null")),
+                Value::Object(object) => {
+                    visitor.record_debug(&field, &format_args!("{}", object.yason().format(false)))
+                }
+                Value::Array(array) => visitor.record_debug(&field, &format_args!("{}", array.yason().format(false))),
+                Value::Binary(b) => visitor.record_debug(&field, &format_args!("{}", Scalar::binary(b)?.format(false))),
+                Value::Timestamp(t) => {
+                    visitor.record_debug(&field, &format_args!("{}", Scalar::timestamp(*t)?.format(false)))
+                }
+                Value::Date(d) => visitor.record_debug(&field, &format_args!("{}", Scalar::date(*d)?.format(false))),
+                Value::ShortDate(d) => {
+                    visitor.record_debug(&field, &format_args!("{}", Scalar::short_date(*d)?.format(false)))
+                }
+                Value::Time(t) => visitor.record_debug(&field, &format_args!("{}", Scalar::time(*t)?.format(false))),
+                Value::IntervalYearMonth(m) => visitor.record_debug(
+                    &field,
+                    &format_args!("{}", Scalar::interval_year_month(*m)?.format(false)),
+                ),
+                Value::IntervalDayTime(m) => visitor.record_debug(
+                    &field,
+                    &format_args!("{}", Scalar::interval_day_time(*m)?.format(false)),
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the value of every field of the span which names a key of the object, e.g. fields
+    /// declared as `tracing::field::Empty`, like [`record_fields`].
+    ///
+    /// ```rust
+    /// use tracing::field::Empty;
+    /// use yason::YasonBuf;
+    ///
+    /// let yason = YasonBuf::parse(r#"{"id": 7, "user": {"name": "foo"}, "payload": [1, 2, 3]}"#).unwrap();
+    /// let span = tracing::info_span!("request", id = Empty, user.name = Empty);
+    /// yason.record_span(&span).unwrap();
+    /// ```
+    ///
+    /// [`record_fields`]: Object::record_fields
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    #[inline]
+    pub fn record_span(&self, span: &Span) -> YasonResult<()> {
+        let metadata = match span.metadata() {
+            Some(metadata) => metadata,
+            None => return Ok(()),
+        };
+        for field in metadata.fields() {
+            let value = match self.field_value(field.name())? {
+                Some(value) => value,
+                None => continue,
+            };
+            match &value {
+                Value::String(s) => span.record(&field, *s),
+                Value::Bool(b) => span.record(&field, *b),
+                Value::Number(n) => match to_i64(n) {
+                    Some(i) => span.record(&field, i),
+                    None => span.record(&field, display(n)),
+                },
+                Value::Null => span.record(&field, display("null")),
+                Value::Object(object) => span.record(&field, display(object.yason().format(false))),
+                Value::Array(array) => span.record(&field, display(array.yason().format(false))),
+                Value::Binary(b) => span.record(&field, display(Scalar::binary(b)?.format(false))),
+                Value::Timestamp(t) => span.record(&field, display(Scalar::timestamp(*t)?.format(false))),
+                Value::Date(d) => span.record(&field, display(Scalar::date(*d)?.format(false))),
+                Value::ShortDate(d) => span.record(&field, display(Scalar::short_date(*d)?.format(false))),
+                Value::Time(t) => span.record(&field, display(Scalar::time(*t)?.format(false))),
+                Value::IntervalYearMonth(m) => {
+                    span.record(&field, display(Scalar::interval_year_month(*m)?.format(false)))
+                }
+                Value::IntervalDayTime(m) => span.record(&field, display(Scalar::interval_day_time(*m)?.format(false))),
+            };
+        }
+        Ok(())
+    }
+
+    /// Returns the value of a dotted field name, or `None` if a key is missing or a parent is not
+    /// an object.
+    #[inline]
+    fn field_value(&self, name: &str) -> YasonResult<Option<Value<'a>>> {
+        let mut value = Value::Object(self.clone());
+        for key in name.split('.') {
+            value = match value {
+                Value::Object(object) => match object.get(key)? {
+                    Some(value) => value,
+                    None => return Ok(None),
+                },
+                _ => return Ok(None),
+            };
+        }
+        Ok(Some(value))
+    }
+}
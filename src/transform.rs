@@ -0,0 +1,562 @@
+//! Transforms which rewrite whole documents.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use yason::{transform, YasonBuf};
+//!
+//! let yason = YasonBuf::parse(r#"{"userId": 1, "userName": "foo", "tags": [{"tagName": "bar"}]}"#).unwrap();
+//! let mut buf = Vec::new();
+//! let renamed = transform::rename_keys(&yason, |key| match key {
+//!     "userId" => Some(Cow::Borrowed("user_id")),
+//!     "userName" => Some(Cow::Borrowed("user_name")),
+//!     "tagName" => Some(Cow::Borrowed("tag_name")),
+//!     _ => None,
+//! }, &mut buf).unwrap();
+//! assert_eq!(renamed.object().unwrap().string("user_name").unwrap(), Some("foo"));
+//! ```
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use yason::{transform, PathExpression, Value, YasonBuf};
+//!
+//! let yason = YasonBuf::parse(r#"{"name": "foo", "cards": [{"no": "1234", "cvv": 567}], "id": 1}"#).unwrap();
+//! let paths = [PathExpression::from_str("$.name").unwrap(), PathExpression::from_str("$.cards[*]").unwrap()];
+//! let mut buf = Vec::new();
+//! let redacted = transform::redact(&yason, &paths, &Value::String("***"), &mut buf).unwrap();
+//! assert_eq!(redacted.format(false).to_string(), r#"{"id":1,"name":"***","cards":["***"]}"#);
+//! ```
+
+use crate::limits::MAX_KEY_LENGTH;
+use crate::util::key_cmp;
+use crate::vec::VecExt;
+use crate::yason::{LazyValue, YasonResult};
+use crate::{
+    ArrBuilder, Array, ArrayRefBuilder, DataType, ObjBuilder, Object, ObjectRefBuilder, PathExpression, Scalar, Value,
+    Yason, YasonError,
+};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+/// Copies the yason into `out`, renaming the keys of all the objects, including the nested ones,
+/// and returns the copy.
+///
+/// `mapper` returns the new name of a key, or `None` to keep it. The key-offset tables of the copy
+/// are sorted by the new names, and the key-values of each object keep their insertion order.
+/// Returns `YasonError::DuplicateKey` if two keys of an object have the same name after renaming,
+/// and `YasonError::KeyTooLong` if a new name is longer than 65535 bytes.
+///
+/// The copy is appended to `out`, a scalar yason is copied as it is.
+#[inline]
+pub fn rename_keys<'a, F>(yason: &Yason, mapper: F, out: &'a mut Vec<u8>) -> YasonResult<&'a Yason>
+where
+    F: Fn(&str) -> Option<Cow<str>>,
+{
+    match yason.data_type()? {
+        DataType::Object => {
+            let object = yason.object()?;
+            let keys = rename_object_keys(&object, &mapper)?;
+            let mut builder = ObjectRefBuilder::try_new(out, keys.len() as u16, false)?;
+            push_object_entries(&mut builder, &object, &keys, &mapper)?;
+            Ok(builder.finish()?)
+        }
+        DataType::Array => {
+            let array = yason.array()?;
+            let mut builder = ArrayRefBuilder::try_new(out, array.len()? as u16)?;
+            push_array_values(&mut builder, array.iter()?, &mapper)?;
+            Ok(builder.finish()?)
+        }
+        _ => {
+            let start = out.len();
+            let value = yason.value()?;
+            out.try_extend_from_slice(value.as_bytes())
+                .map_err(YasonError::TryReserveError)?;
+            Ok(unsafe { Yason::new_unchecked(&out[start..]) })
+        }
+    }
+}
+
+/// Returns the new keys of the object in insertion order, after checking that they are distinct
+/// and not too long.
+fn rename_object_keys<'a, F>(object: &Object<'a>, mapper: &F) -> YasonResult<Vec<Cow<'a, str>>>
+where
+    F: Fn(&str) -> Option<Cow<str>>,
+{
+    let mut keys = Vec::new();
+    keys.try_reserve(object.len()?).map_err(YasonError::TryReserveError)?;
+    for entry in object.iter_insertion_order()? {
+        let (key, _) = entry?;
+        let key = mapper(key).unwrap_or(Cow::Borrowed(key));
+        if key.len() > MAX_KEY_LENGTH {
+            return Err(YasonError::KeyTooLong(key.len()));
+        }
+        keys.push(key);
+    }
+
+    let mut sorted = Vec::new();
+    sorted.try_reserve(keys.len()).map_err(YasonError::TryReserveError)?;
+    sorted.extend(keys.iter().map(|key| key.as_ref()));
+    sorted.sort_unstable_by(|l, r| key_cmp(l, r));
+    for pair in sorted.windows(2) {
+        if key_cmp(pair[0], pair[1]) == Ordering::Equal {
+            return Err(YasonError::DuplicateKey(pair[0].to_string()));
+        }
+    }
+    Ok(keys)
+}
+
+fn push_object_entries<B, F>(builder: &mut B, object: &Object, keys: &[Cow<str>], mapper: &F) -> YasonResult<()>
+where
+    B: ObjBuilder,
+    F: Fn(&str) -> Option<Cow<str>>,
+{
+    for (entry, key) in object.iter_insertion_order()?.zip(keys) {
+        let (_, value) = entry?;
+        let key = key.as_ref();
+        match value {
+            Value::Object(object) => {
+                let keys = rename_object_keys(&object, mapper)?;
+                let mut nested = builder.push_object(key, keys.len() as u16, false)?;
+                push_object_entries(&mut nested, &object, &keys, mapper)?;
+                nested.finish()?;
+            }
+            Value::Array(array) => {
+                let mut nested = builder.push_array(key, array.len()? as u16)?;
+                push_array_values(&mut nested, array.iter()?, mapper)?;
+                nested.finish()?;
+            }
+            Value::String(s) => {
+                builder.push_string(key, s)?;
+            }
+            Value::Number(number) => {
+                builder.push_number(key, number)?;
+            }
+            Value::Bool(b) => {
+                builder.push_bool(key, b)?;
+            }
+            Value::Null => {
+                builder.push_null(key)?;
+            }
+            Value::Binary(b) => {
+                builder.push_binary(key, b)?;
+            }
+            Value::Timestamp(timestamp) => {
+                builder.push_timestamp(key, timestamp)?;
+            }
+            Value::Date(date) => {
+                builder.push_date(key, date)?;
+            }
+            Value::ShortDate(short_date) => {
+                builder.push_short_date(key, short_date)?;
+            }
+            Value::Time(time) => {
+                builder.push_time(key, time)?;
+            }
+            Value::IntervalYearMonth(months) => {
+                builder.push_interval_year_month(key, months)?;
+            }
+            Value::IntervalDayTime(micros) => {
+                builder.push_interval_day_time(key, micros)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn push_array_values<'a, B, I, F>(builder: &mut B, values: I, mapper: &F) -> YasonResult<()>
+where
+    B: ArrBuilder,
+    I: Iterator<Item = YasonResult<Value<'a>>>,
+    F: Fn(&str) -> Option<Cow<str>>,
+{
+    for value in values {
+        match value? {
+            Value::Object(object) => {
+                let keys = rename_object_keys(&object, mapper)?;
+                let mut nested = builder.push_object(keys.len() as u16, false)?;
+                push_object_entries(&mut nested, &object, &keys, mapper)?;
+                nested.finish()?;
+            }
+            Value::Array(array) => {
+                let mut nested = builder.push_array(array.len()? as u16)?;
+                push_array_values(&mut nested, array.iter()?, mapper)?;
+                nested.finish()?;
+            }
+            Value::String(s) => {
+                builder.push_string(s)?;
+            }
+            Value::Number(number) => {
+                builder.push_number(number)?;
+            }
+            Value::Bool(b) => {
+                builder.push_bool(b)?;
+            }
+            Value::Null => {
+                builder.push_null()?;
+            }
+            Value::Binary(b) => {
+                builder.push_binary(b)?;
+            }
+            Value::Timestamp(timestamp) => {
+                builder.push_timestamp(timestamp)?;
+            }
+            Value::Date(date) => {
+                builder.push_date(date)?;
+            }
+            Value::ShortDate(short_date) => {
+                builder.push_short_date(short_date)?;
+            }
+            Value::Time(time) => {
+                builder.push_time(time)?;
+            }
+            Value::IntervalYearMonth(months) => {
+                builder.push_interval_year_month(months)?;
+            }
+            Value::IntervalDayTime(micros) => {
+                builder.push_interval_day_time(micros)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copies the yason into `out`, replacing every value selected by any of the path expressions with
+/// `replacement`, and returns the copy, e.g. to mask personal data before logging or exporting a
+/// document.
+///
+/// Objects and arrays which contain no selected value are copied byte-for-byte, only the containers
+/// on the way to a selected value are rebuilt, keeping the insertion order of their key-values. If a
+/// value and some of its descendants are selected, the value is replaced as a whole.
+///
+/// Returns `YasonError::InvalidPathExpression` if a path expression has an item method, and
+/// `YasonError::UnboundVariable` if it refers to variables. The copy is appended to `out`.
+#[inline]
+pub fn redact<'a>(
+    yason: &Yason,
+    paths: &[PathExpression],
+    replacement: &Value,
+    out: &'a mut Vec<u8>,
+) -> YasonResult<&'a Yason> {
+    let addresses = select_addresses(yason, paths)?;
+    let start = out.len();
+    let root = LazyValue::try_from(yason)?;
+    if addresses.binary_search(&root.address()).is_ok() {
+        write_value(replacement, out)?;
+    } else {
+        match root.value()? {
+            Value::Object(object) if contains_address(&addresses, object.yason()) => {
+                let mut builder = ObjectRefBuilder::try_new(out, object.len()? as u16, false)?;
+                redact_object_entries(&mut builder, &object, &addresses, replacement)?;
+                builder.finish()?;
+            }
+            Value::Array(array) if contains_address(&addresses, array.yason()) => {
+                let mut builder = ArrayRefBuilder::try_new(out, array.len()? as u16)?;
+                redact_array_values(&mut builder, &array, &addresses, replacement)?;
+                builder.finish()?;
+            }
+            value => write_value(&value, out)?,
+        }
+    }
+    Ok(unsafe { Yason::new_unchecked(&out[start..]) })
+}
+
+/// Copies the yason into `out`, keeping only the values selected by any of the path expressions
+/// and the objects and arrays on the way to them, and returns the copy. This is the inverse of
+/// [`redact`], e.g. to return only the fields a client may see.
+///
+/// A selected value is copied as a whole. The containers on the way to a selected value are rebuilt
+/// with only the key-values and elements which are or contain a selected value, keeping their
+/// insertion order, so the elements of arrays are renumbered. If nothing is selected, the copy is
+/// an empty object or array, or null if the yason is a scalar.
+///
+/// Returns `YasonError::InvalidPathExpression` if a path expression has an item method, and
+/// `YasonError::UnboundVariable` if it refers to variables. The copy is appended to `out`.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use yason::{transform, PathExpression, YasonBuf};
+///
+/// let yason = YasonBuf::parse(r#"{"id": 1, "user": {"name": "foo", "email": "a@b"}, "tags": ["x", "y"]}"#).unwrap();
+/// let paths = [PathExpression::from_str("$.id").unwrap(), PathExpression::from_str("$.user.name").unwrap()];
+/// let mut buf = Vec::new();
+/// let projected = transform::project(&yason, &paths, &mut buf).unwrap();
+/// assert_eq!(projected.format(false).to_string(), r#"{"id":1,"user":{"name":"foo"}}"#);
+/// ```
+#[inline]
+pub fn project<'a>(yason: &Yason, paths: &[PathExpression], out: &'a mut Vec<u8>) -> YasonResult<&'a Yason> {
+    let addresses = select_addresses(yason, paths)?;
+    let start = out.len();
+    let root = LazyValue::try_from(yason)?;
+    if addresses.binary_search(&root.address()).is_ok() {
+        write_value(&root.value()?, out)?;
+    } else {
+        match root.value()? {
+            Value::Object(object) => {
+                let count = projected_entry_count(&object, &addresses)?;
+                let mut builder = ObjectRefBuilder::try_new(out, count, false)?;
+                project_object_entries(&mut builder, &object, &addresses)?;
+                builder.finish()?;
+            }
+            Value::Array(array) => {
+                let count = projected_element_count(&array, &addresses)?;
+                let mut builder = ArrayRefBuilder::try_new(out, count)?;
+                project_array_values(&mut builder, &array, &addresses)?;
+                builder.finish()?;
+            }
+            _ => {
+                Scalar::null_with_vec(out)?;
+            }
+        }
+    }
+    Ok(unsafe { Yason::new_unchecked(&out[start..]) })
+}
+
+/// Returns the sorted addresses of the values selected by any of the path expressions.
+#[inline]
+pub(crate) fn select_addresses(yason: &Yason, paths: &[PathExpression]) -> YasonResult<Vec<usize>> {
+    let mut addresses = Vec::new();
+    for path in paths {
+        path.check_exists()?;
+        path.select_addresses(yason, &mut addresses)?;
+    }
+    addresses.sort_unstable();
+    addresses.dedup();
+    Ok(addresses)
+}
+
+/// Returns whether the value is selected or contains a selected value.
+#[inline]
+fn is_projected<const IN_ARRAY: bool>(value: &LazyValue<IN_ARRAY>, addresses: &[usize]) -> YasonResult<bool> {
+    if addresses.binary_search(&value.address()).is_ok() {
+        return Ok(true);
+    }
+    Ok(match value.value()? {
+        Value::Object(object) => contains_address(addresses, object.yason()),
+        Value::Array(array) => contains_address(addresses, array.yason()),
+        _ => false,
+    })
+}
+
+#[inline]
+fn projected_entry_count(object: &Object, addresses: &[usize]) -> YasonResult<u16> {
+    let mut count = 0;
+    for entry in object.lazy_iter_insertion_order()? {
+        let (_, value) = entry?;
+        count += is_projected(&value, addresses)? as u16;
+    }
+    Ok(count)
+}
+
+#[inline]
+fn projected_element_count(array: &Array, addresses: &[usize]) -> YasonResult<u16> {
+    let mut count = 0;
+    for value in array.lazy_iter()? {
+        count += is_projected(&value?, addresses)? as u16;
+    }
+    Ok(count)
+}
+
+fn project_object_entries(builder: &mut ObjectRefBuilder, object: &Object, addresses: &[usize]) -> YasonResult<()> {
+    for entry in object.lazy_iter_insertion_order()? {
+        let (key, value) = entry?;
+        if addresses.binary_search(&value.address()).is_ok() {
+            push_object_value(builder, key, &value.value()?)?;
+            continue;
+        }
+        match value.value()? {
+            Value::Object(object) if contains_address(addresses, object.yason()) => {
+                let count = projected_entry_count(&object, addresses)?;
+                let mut nested = builder.push_object(key, count, false)?;
+                project_object_entries(&mut nested, &object, addresses)?;
+                nested.finish()?;
+            }
+            Value::Array(array) if contains_address(addresses, array.yason()) => {
+                let count = projected_element_count(&array, addresses)?;
+                let mut nested = builder.push_array(key, count)?;
+                project_array_values(&mut nested, &array, addresses)?;
+                nested.finish()?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn project_array_values(builder: &mut ArrayRefBuilder, array: &Array, addresses: &[usize]) -> YasonResult<()> {
+    for value in array.lazy_iter()? {
+        let value = value?;
+        if addresses.binary_search(&value.address()).is_ok() {
+            push_array_value(builder, &value.value()?)?;
+            continue;
+        }
+        match value.value()? {
+            Value::Object(object) if contains_address(addresses, object.yason()) => {
+                let count = projected_entry_count(&object, addresses)?;
+                let mut nested = builder.push_object(count, false)?;
+                project_object_entries(&mut nested, &object, addresses)?;
+                nested.finish()?;
+            }
+            Value::Array(array) if contains_address(addresses, array.yason()) => {
+                let count = projected_element_count(&array, addresses)?;
+                let mut nested = builder.push_array(count)?;
+                project_array_values(&mut nested, &array, addresses)?;
+                nested.finish()?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether any of the sorted addresses is in the bytes of the yason.
+#[inline]
+fn contains_address(addresses: &[usize], yason: &Yason) -> bool {
+    let bytes = yason.as_bytes();
+    let start = bytes.as_ptr() as usize;
+    let index = addresses.partition_point(|address| *address < start);
+    index < addresses.len() && addresses[index] < start + bytes.len()
+}
+
+fn redact_object_entries(
+    builder: &mut ObjectRefBuilder,
+    object: &Object,
+    addresses: &[usize],
+    replacement: &Value,
+) -> YasonResult<()> {
+    for entry in object.lazy_iter_insertion_order()? {
+        let (key, value) = entry?;
+        if addresses.binary_search(&value.address()).is_ok() {
+            push_object_value(builder, key, replacement)?;
+            continue;
+        }
+        match value.value()? {
+            Value::Object(object) if contains_address(addresses, object.yason()) => {
+                let mut nested = builder.push_object(key, object.len()? as u16, false)?;
+                redact_object_entries(&mut nested, &object, addresses, replacement)?;
+                nested.finish()?;
+            }
+            Value::Array(array) if contains_address(addresses, array.yason()) => {
+                let mut nested = builder.push_array(key, array.len()? as u16)?;
+                redact_array_values(&mut nested, &array, addresses, replacement)?;
+                nested.finish()?;
+            }
+            value => push_object_value(builder, key, &value)?,
+        }
+    }
+    Ok(())
+}
+
+fn redact_array_values(
+    builder: &mut ArrayRefBuilder,
+    array: &Array,
+    addresses: &[usize],
+    replacement: &Value,
+) -> YasonResult<()> {
+    for value in array.lazy_iter()? {
+        let value = value?;
+        if addresses.binary_search(&value.address()).is_ok() {
+            push_array_value(builder, replacement)?;
+            continue;
+        }
+        match value.value()? {
+            Value::Object(object) if contains_address(addresses, object.yason()) => {
+                let mut nested = builder.push_object(object.len()? as u16, false)?;
+                redact_object_entries(&mut nested, &object, addresses, replacement)?;
+                nested.finish()?;
+            }
+            Value::Array(array) if contains_address(addresses, array.yason()) => {
+                let mut nested = builder.push_array(array.len()? as u16)?;
+                redact_array_values(&mut nested, &array, addresses, replacement)?;
+                nested.finish()?;
+            }
+            value => push_array_value(builder, &value)?,
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn push_object_value(builder: &mut ObjectRefBuilder, key: &str, value: &Value) -> YasonResult<()> {
+    match value {
+        Value::Object(object) => unsafe { builder.push_object_or_array(key, object.yason(), DataType::Object)? },
+        Value::Array(array) => unsafe { builder.push_object_or_array(key, array.yason(), DataType::Array)? },
+        Value::String(s) => builder.push_string(key, s)?,
+        Value::Number(number) => builder.push_number(key, number)?,
+        Value::Bool(b) => builder.push_bool(key, *b)?,
+        Value::Null => builder.push_null(key)?,
+        Value::Binary(b) => builder.push_binary(key, b)?,
+        Value::Timestamp(timestamp) => builder.push_timestamp(key, *timestamp)?,
+        Value::Date(date) => builder.push_date(key, *date)?,
+        Value::ShortDate(short_date) => builder.push_short_date(key, *short_date)?,
+        Value::Time(time) => builder.push_time(key, *time)?,
+        Value::IntervalYearMonth(months) => builder.push_interval_year_month(key, *months)?,
+        Value::IntervalDayTime(micros) => builder.push_interval_day_time(key, *micros)?,
+    };
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn push_array_value(builder: &mut ArrayRefBuilder, value: &Value) -> YasonResult<()> {
+    match value {
+        Value::Object(object) => unsafe { builder.push_object_or_array(object.yason(), DataType::Object)? },
+        Value::Array(array) => unsafe { builder.push_object_or_array(array.yason(), DataType::Array)? },
+        Value::String(s) => builder.push_string(s)?,
+        Value::Number(number) => builder.push_number(number)?,
+        Value::Bool(b) => builder.push_bool(*b)?,
+        Value::Null => builder.push_null()?,
+        Value::Binary(b) => builder.push_binary(b)?,
+        Value::Timestamp(timestamp) => builder.push_timestamp(*timestamp)?,
+        Value::Date(date) => builder.push_date(*date)?,
+        Value::ShortDate(short_date) => builder.push_short_date(*short_date)?,
+        Value::Time(time) => builder.push_time(*time)?,
+        Value::IntervalYearMonth(months) => builder.push_interval_year_month(*months)?,
+        Value::IntervalDayTime(micros) => builder.push_interval_day_time(*micros)?,
+    };
+    Ok(())
+}
+
+/// Appends the encoded value to the bytes.
+#[inline]
+pub(crate) fn write_value(value: &Value, bytes: &mut Vec<u8>) -> YasonResult<()> {
+    match value {
+        Value::Object(object) => bytes
+            .try_extend_from_slice(object.yason().as_bytes())
+            .map_err(YasonError::TryReserveError)?,
+        Value::Array(array) => bytes
+            .try_extend_from_slice(array.yason().as_bytes())
+            .map_err(YasonError::TryReserveError)?,
+        Value::String(s) => {
+            Scalar::string_with_vec(s, bytes)?;
+        }
+        Value::Number(number) => {
+            Scalar::number_with_vec(number, bytes)?;
+        }
+        Value::Bool(b) => {
+            Scalar::bool_with_vec(*b, bytes)?;
+        }
+        Value::Null => {
+            Scalar::null_with_vec(bytes)?;
+        }
+        Value::Binary(b) => {
+            Scalar::binary_with_vec(b, bytes)?;
+        }
+        Value::Timestamp(timestamp) => {
+            Scalar::timestamp_with_vec(*timestamp, bytes)?;
+        }
+        Value::Date(date) => {
+            Scalar::date_with_vec(*date, bytes)?;
+        }
+        Value::ShortDate(short_date) => {
+            Scalar::short_date_with_vec(*short_date, bytes)?;
+        }
+        Value::Time(time) => {
+            Scalar::time_with_vec(*time, bytes)?;
+        }
+        Value::IntervalYearMonth(months) => {
+            Scalar::interval_year_month_with_vec(*months, bytes)?;
+        }
+        Value::IntervalDayTime(micros) => {
+            Scalar::interval_day_time_with_vec(*micros, bytes)?;
+        }
+    }
+    Ok(())
+}
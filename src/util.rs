@@ -6,8 +6,32 @@ use crate::yason::YasonResult;
 use crate::YasonError;
 use std::cmp::Ordering;
 
+/// Compares two object keys in the canonical key order of yason: shorter keys first, and keys of
+/// the same length by their bytes.
+///
+/// The key-offset table of every object is sorted in this order, which lookups rely on. Writers
+/// producing yason without the builders, and builders given `key_sorted = true`, must push keys
+/// in this order.
+///
+/// ```rust
+/// use std::cmp::Ordering;
+/// use yason::key_cmp;
+///
+/// assert_eq!(key_cmp("b", "aa"), Ordering::Less);
+/// assert_eq!(key_cmp("ab", "aa"), Ordering::Greater);
+///
+/// let mut keys = vec!["name", "id", "b", "a"];
+/// keys.sort_by(|left, right| key_cmp(left, right));
+/// assert_eq!(keys, ["a", "b", "id", "name"]);
+/// ```
 #[inline]
-pub fn cmp_key(left: &str, right: &str) -> Ordering {
+pub fn key_cmp(left: &str, right: &str) -> Ordering {
+    key_bytes_cmp(left.as_bytes(), right.as_bytes())
+}
+
+/// Compares the bytes of two keys like [`key_cmp`].
+#[inline]
+pub(crate) fn key_bytes_cmp(left: &[u8], right: &[u8]) -> Ordering {
     match left.len().cmp(&right.len()) {
         Ordering::Equal => left.cmp(right),
         Ordering::Greater => Ordering::Greater,
@@ -15,6 +39,40 @@ pub fn cmp_key(left: &str, right: &str) -> Ordering {
     }
 }
 
+/// Sorts key-value pairs by their keys in the order of [`key_cmp`], so that they can be pushed into
+/// a builder given `key_sorted = true`, which is faster than inserting every key into the
+/// key-offset table. The sort is stable, pairs with equal keys keep their order and must be
+/// deduplicated before they are pushed.
+///
+/// ```rust
+/// use yason::{are_keys_sorted, sort_keys, ObjectBuilder};
+///
+/// let mut pairs = vec![("name", "foo"), ("id", "1"), ("b", "x")];
+/// assert!(!are_keys_sorted(&pairs));
+/// sort_keys(&mut pairs);
+/// assert!(are_keys_sorted(&pairs));
+///
+/// let mut builder = ObjectBuilder::try_new(pairs.len() as u16, true).unwrap();
+/// for (key, value) in pairs.iter() {
+///     builder.push_string(key, value).unwrap();
+/// }
+/// assert_eq!(builder.finish().unwrap().format(false).to_string(), r#"{"b":"x","id":"1","name":"foo"}"#);
+/// ```
+#[inline]
+pub fn sort_keys<K: AsRef<str>, V>(pairs: &mut [(K, V)]) {
+    pairs.sort_by(|(left, _), (right, _)| key_cmp(left.as_ref(), right.as_ref()));
+}
+
+/// Returns true if the key-value pairs are sorted by their keys in the order of [`key_cmp`], as a
+/// builder given `key_sorted = true` requires. Equal keys are not sorted, as such a builder rejects
+/// a key equal to the previous one.
+#[inline]
+pub fn are_keys_sorted<K: AsRef<str>, V>(pairs: &[(K, V)]) -> bool {
+    pairs
+        .windows(2)
+        .all(|pair| key_cmp(pair[0].0.as_ref(), pair[1].0.as_ref()) == Ordering::Less)
+}
+
 #[inline]
 pub fn encode_varint(mut value: u32, buf: &mut Vec<u8>) {
     if value < 0x80 {
@@ -50,10 +108,29 @@ pub fn encode_varint(mut value: u32, buf: &mut Vec<u8>) {
     buf.push_bytes(bytes);
 }
 
+/// Returns the number of bytes needed to encode the value as a varint.
 #[inline]
-pub fn decode_varint(buf: &[u8], index: usize) -> YasonResult<(u32, usize)> {
-    debug_assert!(index < buf.len());
+pub fn varint_size(value: u32) -> usize {
+    match value {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        0x4000..=0x1f_ffff => 3,
+        _ => 4,
+    }
+}
 
+/// Reads the `N` bytes at `pos`, returns `None` if any of them is out of bounds.
+///
+/// Every fixed-size field of the binary format is read through this function, so that reading
+/// corrupted bytes returns an error instead of panicking.
+#[inline]
+pub fn read_fixed<const N: usize>(buf: &[u8], pos: usize) -> Option<[u8; N]> {
+    let end = pos.checked_add(N)?;
+    buf.get(pos..end)?.try_into().ok()
+}
+
+#[inline]
+pub fn decode_varint(buf: &[u8], index: usize) -> YasonResult<(u32, usize)> {
     let mut data_length: u32 = 0;
     for i in 0..MAX_DATA_LENGTH_SIZE {
         // Get the next 7 bits of the length.
@@ -72,12 +149,25 @@ pub fn decode_varint(buf: &[u8], index: usize) -> YasonResult<(u32, usize)> {
             return Ok((data_length, i + 1));
         }
     }
-    unreachable!("data length read error");
+    Err(YasonError::InvalidFormat("data length is longer than 4 bytes"))
+}
+
+#[inline]
+pub fn try_collect<T, I>(len: usize, iter: I) -> YasonResult<Vec<T>>
+where
+    I: Iterator<Item = YasonResult<T>>,
+{
+    let mut vec = Vec::new();
+    vec.try_reserve(len).map_err(YasonError::TryReserveError)?;
+    for item in iter {
+        vec.push(item?);
+    }
+    Ok(vec)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::util::{decode_varint, encode_varint};
+    use crate::util::{decode_varint, encode_varint, varint_size};
 
     fn assert_varint(value: u32, expected: &[u8]) {
         let mut buf = Vec::with_capacity(4);
@@ -87,6 +177,7 @@ mod tests {
         let (val, len) = decode_varint(&buf, 0).unwrap();
         assert_eq!(val, value);
         assert_eq!(len, expected.len());
+        assert_eq!(varint_size(value), expected.len());
     }
 
     #[test]
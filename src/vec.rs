@@ -10,9 +10,11 @@ use std::mem::size_of;
 
 pub trait VecExt: Sized {
     fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError>;
+    fn try_reserve_bytes(&mut self, additional: usize) -> Result<(), TryReserveError>;
     fn push_u8(&mut self, val: u8);
     fn push_u16(&mut self, val: u16);
     fn push_i32(&mut self, val: i32);
+    fn push_i64(&mut self, val: i64);
     fn push_data_type(&mut self, data_type: DataType);
     fn write_data_type_by_pos(&mut self, data_type: DataType, type_pos: usize);
     fn push_str(&mut self, s: &str);
@@ -25,6 +27,7 @@ pub trait VecExt: Sized {
     fn push_data_length(&mut self, length: usize) -> BuildResult<()>;
     fn push_key(&mut self, s: &str);
     fn push_string(&mut self, s: &str) -> BuildResult<()>;
+    fn push_binary(&mut self, bytes: &[u8]) -> BuildResult<()>;
     fn push_number(&mut self, value: &Number);
     fn try_extend_from_slice(&mut self, other: &[u8]) -> Result<(), TryReserveError>;
 }
@@ -33,10 +36,22 @@ impl VecExt for Vec<u8> {
     #[inline]
     fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
         let mut vec = Vec::new();
-        vec.try_reserve(capacity)?;
+        vec.try_reserve_bytes(capacity)?;
         Ok(vec)
     }
 
+    #[inline]
+    fn try_reserve_bytes(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        #[cfg(feature = "metrics")]
+        let capacity = self.capacity();
+        self.try_reserve(additional)?;
+        #[cfg(feature = "metrics")]
+        if self.capacity() != capacity {
+            crate::metrics::build_reserve(self.capacity());
+        }
+        Ok(())
+    }
+
     #[inline]
     fn push_u8(&mut self, val: u8) {
         debug_assert!(size_of::<u8>() <= self.capacity() - self.len());
@@ -55,6 +70,12 @@ impl VecExt for Vec<u8> {
         self.extend_from_slice(&val.to_le_bytes());
     }
 
+    #[inline]
+    fn push_i64(&mut self, val: i64) {
+        debug_assert!(size_of::<i64>() <= self.capacity() - self.len());
+        self.extend_from_slice(&val.to_le_bytes());
+    }
+
     #[inline]
     fn push_data_type(&mut self, data_type: DataType) {
         self.push_u8(data_type as u8);
@@ -76,27 +97,24 @@ impl VecExt for Vec<u8> {
     fn skip_size(&mut self) {
         let new_len = self.len() + OBJECT_SIZE;
         debug_assert!(new_len <= self.capacity());
-        unsafe {
-            self.set_len(new_len);
-        }
+        // the skipped bytes are zeroed and overwritten later
+        self.resize(new_len, 0);
     }
 
     #[inline]
     fn skip_key_offset(&mut self, element_count: usize) {
         let new_len = self.len() + element_count * KEY_OFFSET_SIZE;
         debug_assert!(new_len <= self.capacity());
-        unsafe {
-            self.set_len(new_len);
-        }
+        // the skipped bytes are zeroed and overwritten later
+        self.resize(new_len, 0);
     }
 
     #[inline]
     fn skip_value_entry(&mut self, element_count: usize) {
         let new_len = self.len() + element_count * VALUE_ENTRY_SIZE;
         debug_assert!(new_len <= self.capacity());
-        unsafe {
-            self.set_len(new_len);
-        }
+        // the skipped bytes are zeroed and overwritten later
+        self.resize(new_len, 0);
     }
 
     #[inline]
@@ -141,22 +159,25 @@ impl VecExt for Vec<u8> {
         Ok(())
     }
 
+    #[inline]
+    fn push_binary(&mut self, bytes: &[u8]) -> BuildResult<()> {
+        self.push_data_length(bytes.len())?;
+        self.push_bytes(bytes);
+        Ok(())
+    }
+
     #[inline]
     fn push_number(&mut self, value: &Number) {
         let length_pos = self.len();
         let value_pos = length_pos + NUMBER_LENGTH_SIZE;
         let new_len = value_pos + MAX_BINARY_SIZE;
         debug_assert!(NUMBER_LENGTH_SIZE + MAX_BINARY_SIZE <= self.capacity() - self.len());
-        unsafe {
-            self.set_len(new_len);
-        }
+        self.resize(new_len, 0);
         let bytes = &mut self[value_pos..value_pos + MAX_BINARY_SIZE];
         // SAFETY: Because we have ensured that the memory is sufficient before encoding.
         let size = value.compact_encode(bytes).expect("failed to encode number");
         self[length_pos] = size as u8;
-        unsafe {
-            self.set_len(value_pos + size);
-        }
+        self.truncate(value_pos + size);
     }
 
     #[inline]
@@ -0,0 +1,137 @@
+//! JavaScript bindings for WebAssembly.
+//!
+//! The bindings are generated by `wasm-bindgen` and export a `Yason` class to JavaScript, so
+//! browser tooling can decode and inspect yason payloads with the same implementation as the
+//! database:
+//!
+//! ```js
+//! import { Yason } from "yason";
+//!
+//! const yason = Yason.fromBytes(payload);
+//! console.log(yason.format(true));
+//! console.log(yason.query("$.items[*].price", true));
+//! ```
+//!
+//! Values are converted into JavaScript values directly: objects and arrays become plain objects
+//! and arrays, binaries become `Uint8Array`s, and temporal values become their ISO-8601 strings.
+//! A number with more significant digits than a JavaScript number can hold loses precision on the
+//! way out, use [`JsYason::format`] to get the exact text. JavaScript values are converted into
+//! yason through `JSON.stringify`.
+
+use crate::{PathExpression, QueriedValue, Value, Yason, YasonBuf};
+use js_sys::{Array, Object, Reflect, Uint8Array, JSON};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+/// A yason exported to JavaScript as the `Yason` class.
+#[wasm_bindgen(js_name = Yason)]
+pub struct JsYason {
+    inner: YasonBuf,
+}
+
+#[wasm_bindgen(js_class = Yason)]
+impl JsYason {
+    /// Parses a json string into a yason.
+    #[inline]
+    pub fn parse(json: &str) -> Result<JsYason, JsError> {
+        let inner = YasonBuf::parse(json)?;
+        Ok(JsYason { inner })
+    }
+
+    /// Converts a JavaScript value into a yason, the value must be serializable by
+    /// `JSON.stringify`.
+    #[wasm_bindgen(js_name = fromJs)]
+    pub fn from_js(value: &JsValue) -> Result<JsYason, JsError> {
+        let json = JSON::stringify(value)
+            .map_err(|_| JsError::new("value cannot be converted to json"))?
+            .as_string()
+            .ok_or_else(|| JsError::new("value cannot be converted to json"))?;
+        Self::parse(&json)
+    }
+
+    /// Creates a yason from its binary form, returns an error if the bytes are not a valid yason.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<JsYason, JsError> {
+        // SAFETY: the bytes are validated before the yason is used.
+        unsafe { Yason::new_unchecked(bytes) }.validate()?;
+        // SAFETY: the bytes are a valid yason.
+        let inner = unsafe { YasonBuf::new_unchecked(bytes.to_vec()) };
+        Ok(JsYason { inner })
+    }
+
+    /// Returns the binary form of the yason as a `Uint8Array`.
+    #[wasm_bindgen(js_name = toBytes)]
+    #[inline]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.inner.as_bytes().to_vec()
+    }
+
+    /// Formats the yason as a compact or pretty json string.
+    pub fn format(&self, pretty: bool) -> Result<String, JsError> {
+        let mut s = String::new();
+        self.inner.format_to(pretty, &mut s)?;
+        Ok(s)
+    }
+
+    /// Converts the yason into a JavaScript value.
+    #[wasm_bindgen(js_name = toJs)]
+    pub fn to_js(&self) -> Result<JsValue, JsError> {
+        value_to_js(&Value::try_from(self.inner.as_ref())?)
+    }
+
+    /// Selects values by a path expression and returns them as a JavaScript value, or `undefined`
+    /// if nothing is selected. With `with_wrapper`, the values are returned as an array.
+    pub fn query(&self, path: &str, with_wrapper: bool) -> Result<JsValue, JsError> {
+        let path = PathExpression::from_str(path)?;
+        match path.query(&self.inner, with_wrapper, None, None)? {
+            QueriedValue::None => Ok(JsValue::UNDEFINED),
+            QueriedValue::Value(value) => value_to_js(&value),
+            QueriedValue::Values(values) => values_to_js(&values),
+            QueriedValue::ValuesRef(values) => values_to_js(values),
+            QueriedValue::Yason(yason) => value_to_js(&Value::try_from(yason)?),
+            QueriedValue::YasonOwned(yason) => value_to_js(&Value::try_from(yason.as_ref())?),
+        }
+    }
+}
+
+fn value_to_js(value: &Value) -> Result<JsValue, JsError> {
+    let js = match value {
+        Value::Object(object) => {
+            let js = Object::new();
+            for entry in object.iter()? {
+                let (key, value) = entry?;
+                Reflect::set(&js, &JsValue::from_str(key), &value_to_js(&value)?)
+                    .map_err(|_| JsError::new("cannot set a property of an object"))?;
+            }
+            js.into()
+        }
+        Value::Array(array) => {
+            let js = Array::new();
+            for value in array.iter()? {
+                js.push(&value_to_js(&value?)?);
+            }
+            js.into()
+        }
+        Value::String(value) => JsValue::from_str(value),
+        Value::Number(value) => JsValue::from_f64(f64::from(value)),
+        Value::Bool(value) => JsValue::from_bool(*value),
+        Value::Null => JsValue::NULL,
+        Value::Binary(value) => Uint8Array::from(*value).into(),
+        Value::Timestamp(value) => JsValue::from_str(&value.to_string()),
+        Value::Date(value) => JsValue::from_str(&value.to_string()),
+        Value::ShortDate(value) => JsValue::from_str(&value.to_string()),
+        Value::Time(value) => JsValue::from_str(&value.to_string()),
+        Value::IntervalYearMonth(value) => JsValue::from_str(&value.to_string()),
+        Value::IntervalDayTime(value) => JsValue::from_str(&value.to_string()),
+    };
+    Ok(js)
+}
+
+#[inline]
+fn values_to_js(values: &[Value]) -> Result<JsValue, JsError> {
+    let js = Array::new();
+    for value in values {
+        js.push(&value_to_js(value)?);
+    }
+    Ok(js.into())
+}
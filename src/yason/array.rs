@@ -1,9 +1,13 @@
 //! Array manipulation.
 
-use crate::binary::{ARRAY_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, OBJECT_SIZE, VALUE_ENTRY_SIZE};
+use crate::binary::{ARRAY_SIZE, BOOL_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, OBJECT_SIZE, VALUE_ENTRY_SIZE};
+use crate::util::try_collect;
 use crate::yason::object::Object;
+use crate::yason::EntryInfo;
 use crate::yason::{LazyValue, Value, Yason, YasonError, YasonResult};
-use crate::{DataType, Number};
+use crate::{num, Collation, DataType, Date, DtInterval, Number, ShortDate, StepBudget, Time, Timestamp, YmInterval};
+use std::cmp::Ordering;
+use std::marker::PhantomData;
 
 /// An array in yason binary format.
 #[derive(Clone, Debug)]
@@ -17,6 +21,51 @@ impl<'a> Array<'a> {
         ArrayIter::try_new(self.0)
     }
 
+    /// Collects the values of the array into a `Vec`.
+    #[inline]
+    pub fn to_vec(&self) -> YasonResult<Vec<Value<'a>>> {
+        try_collect(self.len()?, self.iter()?)
+    }
+
+    /// Gets an iterator over the elements of an array of one type, which reads the value entries
+    /// directly without going through `Value`.
+    ///
+    /// The iterator yields `YasonError::UnexpectedType` for the elements of other types.
+    ///
+    /// ```rust
+    /// use yason::{YasonBuf, YasonError};
+    ///
+    /// let yason = YasonBuf::parse(r#"["a", "b", 1]"#).unwrap();
+    /// let array = yason.array().unwrap();
+    /// let mut iter = array.iter_of::<&str>().unwrap();
+    /// assert_eq!(iter.next().unwrap().unwrap(), "a");
+    /// assert_eq!(iter.next().unwrap().unwrap(), "b");
+    /// assert!(matches!(iter.next(), Some(Err(YasonError::UnexpectedType { .. }))));
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[inline]
+    pub fn iter_of<T: ArrayElement<'a>>(&self) -> YasonResult<TypedArrayIter<'a, T>> {
+        TypedArrayIter::try_new(self.0)
+    }
+
+    /// Gets an iterator over the elements of an array of strings, see [`Array::iter_of`].
+    #[inline]
+    pub fn iter_strings(&self) -> YasonResult<TypedArrayIter<'a, &'a str>> {
+        self.iter_of()
+    }
+
+    /// Gets an iterator over the elements of an array of numbers, see [`Array::iter_of`].
+    #[inline]
+    pub fn iter_numbers(&self) -> YasonResult<TypedArrayIter<'a, Number>> {
+        self.iter_of()
+    }
+
+    /// Gets an iterator over the elements of an array of bools, see [`Array::iter_of`].
+    #[inline]
+    pub fn iter_bools(&self) -> YasonResult<TypedArrayIter<'a, bool>> {
+        self.iter_of()
+    }
+
     #[inline]
     pub(crate) fn lazy_iter(&self) -> YasonResult<LazyArrayIter<'a>> {
         LazyArrayIter::try_new(self.0)
@@ -40,7 +89,7 @@ impl<'a> Array<'a> {
     }
 
     #[inline]
-    pub fn yason(&self) -> &Yason {
+    pub fn yason(&self) -> &'a Yason {
         self.0
     }
 
@@ -75,6 +124,30 @@ impl<'a> Array<'a> {
         self.read_value(index)
     }
 
+    /// Returns where and how the element at the given index is encoded, e.g. to patch the array at
+    /// byte level. Several value entries may share one outlined value in a deduplicated array.
+    #[inline]
+    pub fn entry_info(&self, index: usize) -> YasonResult<EntryInfo> {
+        self.check_index(index)?;
+        let (data_type, value_entry_pos) = unsafe { self.read_type_and_value_entry_pos(index)? };
+        let info = match data_type {
+            DataType::Bool => EntryInfo::new(
+                data_type,
+                true,
+                Some(value_entry_pos),
+                value_entry_pos,
+                DATA_TYPE_SIZE + BOOL_SIZE,
+            ),
+            DataType::Null => EntryInfo::new(data_type, true, Some(value_entry_pos), value_entry_pos, DATA_TYPE_SIZE),
+            _ => {
+                let value_pos = self.read_value_pos(value_entry_pos)?;
+                let end = self.0.skip_value(value_pos)?;
+                EntryInfo::new(data_type, false, Some(value_entry_pos), value_pos, end - value_pos)
+            }
+        };
+        Ok(info)
+    }
+
     /// Gets the element's type at the given index.
     #[inline]
     pub fn type_of(&self, index: usize) -> YasonResult<DataType> {
@@ -123,6 +196,28 @@ impl<'a> Array<'a> {
         self.read_string(value_entry_pos)
     }
 
+    /// Gets the length in bytes of a string value if the element at the given index has the correct
+    /// type, returns `YasonError` otherwise. Only the length header of the string is read.
+    #[inline]
+    pub fn string_len(&self, index: usize) -> YasonResult<usize> {
+        self.check_index(index)?;
+        let value_entry_pos = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + index * VALUE_ENTRY_SIZE;
+        self.0.check_type(value_entry_pos, DataType::String)?;
+        let value_pos = self.read_value_pos(value_entry_pos)?;
+        self.0.read_string_len(value_pos)
+    }
+
+    /// Gets at most the first `n` chars of a string value if the element at the given index has the
+    /// correct type, returns `YasonError` otherwise. The rest of the string is not read.
+    #[inline]
+    pub fn string_prefix(&self, index: usize, n: usize) -> YasonResult<&'a str> {
+        self.check_index(index)?;
+        let value_entry_pos = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + index * VALUE_ENTRY_SIZE;
+        self.0.check_type(value_entry_pos, DataType::String)?;
+        let value_pos = self.read_value_pos(value_entry_pos)?;
+        self.0.read_string_prefix(value_pos, n)
+    }
+
     /// Gets a number value if the element at the given index has the correct type, returns `YasonError` otherwise.
     #[inline]
     pub fn number(&self, index: usize) -> YasonResult<Number> {
@@ -141,17 +236,134 @@ impl<'a> Array<'a> {
         self.read_bool(value_entry_pos)
     }
 
+    /// Gets the bytes of a binary value without copying if the element at the given index has the
+    /// correct type, returns `YasonError` otherwise.
+    #[inline]
+    pub fn binary(&self, index: usize) -> YasonResult<&'a [u8]> {
+        self.check_index(index)?;
+        let value_entry_pos = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + index * VALUE_ENTRY_SIZE;
+        self.0.check_type(value_entry_pos, DataType::Binary)?;
+        self.read_binary(value_entry_pos)
+    }
+
+    /// Gets a timestamp if the element at the given index has the correct type, returns `YasonError`
+    /// otherwise.
+    #[inline]
+    pub fn timestamp(&self, index: usize) -> YasonResult<Timestamp> {
+        let value_pos = self.scalar_pos(index, DataType::Timestamp)?;
+        self.0.read_timestamp(value_pos)
+    }
+
+    /// Gets a date if the element at the given index has the correct type, returns `YasonError`
+    /// otherwise.
+    #[inline]
+    pub fn date(&self, index: usize) -> YasonResult<Date> {
+        let value_pos = self.scalar_pos(index, DataType::Date)?;
+        self.0.read_date(value_pos)
+    }
+
+    /// Gets a short date if the element at the given index has the correct type, returns `YasonError`
+    /// otherwise.
+    #[inline]
+    pub fn short_date(&self, index: usize) -> YasonResult<ShortDate> {
+        let value_pos = self.scalar_pos(index, DataType::ShortDate)?;
+        self.0.read_short_date(value_pos)
+    }
+
+    /// Gets a time if the element at the given index has the correct type, returns `YasonError`
+    /// otherwise.
+    #[inline]
+    pub fn time(&self, index: usize) -> YasonResult<Time> {
+        let value_pos = self.scalar_pos(index, DataType::Time)?;
+        self.0.read_time(value_pos)
+    }
+
+    /// Gets the number of months of an interval year-month if the element at the given index has the correct type, returns `YasonError`
+    /// otherwise.
+    #[inline]
+    pub fn interval_year_month(&self, index: usize) -> YasonResult<YmInterval> {
+        let value_pos = self.scalar_pos(index, DataType::IntervalYearMonth)?;
+        self.0.read_interval_year_month(value_pos)
+    }
+
+    /// Gets the number of microseconds of an interval day-time if the element at the given index has the correct type, returns `YasonError`
+    /// otherwise.
+    #[inline]
+    pub fn interval_day_time(&self, index: usize) -> YasonResult<DtInterval> {
+        let value_pos = self.scalar_pos(index, DataType::IntervalDayTime)?;
+        self.0.read_interval_day_time(value_pos)
+    }
+
+    /// Decodes an array of numbers into a `Vec` of decimals.
+    ///
+    /// Returns `YasonError::UnexpectedType` if any element is not a number.
+    #[inline]
+    pub fn to_decimal_vec(&self) -> YasonResult<Vec<Number>> {
+        self.collect_numbers(Ok)
+    }
+
+    /// Decodes an array of numbers into a `Vec` of the nearest `f64`s.
+    ///
+    /// Returns `YasonError::UnexpectedType` if any element is not a number.
+    #[inline]
+    pub fn to_f64_vec(&self) -> YasonResult<Vec<f64>> {
+        self.collect_numbers(|number| Ok(num::to_f64(&number)))
+    }
+
+    /// Decodes an array of integers into a `Vec` of `i64`s.
+    ///
+    /// Returns `YasonError::UnexpectedType` if any element is not a number, or
+    /// `YasonError::InvalidInteger` if a number has a fractional part or is out of the range of `i64`.
+    #[inline]
+    pub fn to_i64_vec(&self) -> YasonResult<Vec<i64>> {
+        self.collect_numbers(|number| num::to_i64(&number).ok_or(YasonError::InvalidInteger(number)))
+    }
+
+    /// Binary searches the array for a string under the given collation.
+    ///
+    /// The array must consist of strings sorted by the collation. If the value is found then
+    /// `Ok(Ok(index))` is returned, otherwise `Ok(Err(index))` is returned, where the index is
+    /// where the value could be inserted. An error is returned if a visited element is not a string.
+    #[inline]
+    pub fn binary_search_str<C: Collation>(&self, value: &str, collation: &C) -> YasonResult<Result<usize, usize>> {
+        let mut left = 0;
+        let mut right = self.len()?;
+        while left < right {
+            let mid = left + (right - left) / 2;
+            match collation.compare(self.string(mid)?, value) {
+                Ordering::Less => left = mid + 1,
+                Ordering::Greater => right = mid,
+                Ordering::Equal => return Ok(Ok(mid)),
+            }
+        }
+        Ok(Err(left))
+    }
+
     #[inline]
-    pub(crate) fn equals<T: AsRef<Array<'a>>>(&self, other: T) -> YasonResult<bool> {
+    pub(crate) fn equals<T: AsRef<Array<'a>>>(&self, other: T, budget: &mut StepBudget) -> YasonResult<bool> {
         let other = other.as_ref();
 
         if self.len()? != other.len()? {
             return Ok(false);
         }
 
+        // compares the scalars and the element counts of all the values before descending into any
+        // nested container, so that unequal arrays are decided early
+        let mut has_container = false;
         for (l_value, r_value) in self.lazy_iter()?.zip(other.lazy_iter()?) {
-            let res = l_value?.equals(r_value?)?;
-            if !res {
+            let l_value = l_value?;
+            budget.step()?;
+            if !l_value.equals_shallow(&r_value?)? {
+                return Ok(false);
+            }
+            has_container |= matches!(l_value.data_type(), DataType::Object | DataType::Array);
+        }
+        if !has_container {
+            return Ok(true);
+        }
+
+        for (l_value, r_value) in self.lazy_iter()?.zip(other.lazy_iter()?) {
+            if !l_value?.equals_children(&r_value?, budget)? {
                 return Ok(false);
             }
         }
@@ -169,6 +381,24 @@ impl<'a> Array<'a> {
         Ok((data_type, value_entry_pos))
     }
 
+    /// Decodes every element as a number by reading the value entries directly, without going through `Value`.
+    #[inline]
+    fn collect_numbers<T, F>(&self, f: F) -> YasonResult<Vec<T>>
+    where
+        F: Fn(Number) -> YasonResult<T>,
+    {
+        let len = self.len()?;
+        let mut vec = Vec::new();
+        vec.try_reserve(len).map_err(YasonError::TryReserveError)?;
+        let mut value_entry_pos = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE;
+        for _ in 0..len {
+            self.0.check_type(value_entry_pos, DataType::Number)?;
+            vec.push(f(self.read_number(value_entry_pos)?)?);
+            value_entry_pos += VALUE_ENTRY_SIZE;
+        }
+        Ok(vec)
+    }
+
     #[inline]
     fn check_index(&self, index: usize) -> YasonResult<()> {
         let element_count = self.len()?;
@@ -182,11 +412,20 @@ impl<'a> Array<'a> {
     }
 
     #[inline]
-    fn read_value_pos(&self, value_entry_pos: usize) -> YasonResult<usize> {
+    pub(crate) fn read_value_pos(&self, value_entry_pos: usize) -> YasonResult<usize> {
         let value_offset = self.0.read_u32(value_entry_pos + DATA_TYPE_SIZE)? as usize;
         Ok(value_offset + DATA_TYPE_SIZE + ARRAY_SIZE)
     }
 
+    /// Returns the position of the scalar at the given index if it has the expected type.
+    #[inline]
+    fn scalar_pos(&self, index: usize, expected: DataType) -> YasonResult<usize> {
+        self.check_index(index)?;
+        let value_entry_pos = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + index * VALUE_ENTRY_SIZE;
+        self.0.check_type(value_entry_pos, expected)?;
+        self.read_value_pos(value_entry_pos)
+    }
+
     #[inline]
     fn read_size(&self, value_pos: usize) -> YasonResult<i32> {
         let size_pos = value_pos + DATA_TYPE_SIZE;
@@ -227,6 +466,12 @@ impl<'a> Array<'a> {
         Ok(self.0.read_u8(value_entry_pos + DATA_TYPE_SIZE)? == 1)
     }
 
+    #[inline]
+    pub(crate) fn read_binary(&self, value_entry_pos: usize) -> YasonResult<&'a [u8]> {
+        let value_pos = self.read_value_pos(value_entry_pos)?;
+        self.0.read_binary(value_pos)
+    }
+
     #[inline]
     fn read_value(&self, index: usize) -> YasonResult<Value<'a>> {
         let (data_type, value_entry_pos) = unsafe { self.read_type_and_value_entry_pos(index)? };
@@ -238,6 +483,17 @@ impl<'a> Array<'a> {
             DataType::Number => Value::Number(self.read_number(value_entry_pos)?),
             DataType::Bool => Value::Bool(self.read_bool(value_entry_pos)?),
             DataType::Null => Value::Null,
+            DataType::Binary => Value::Binary(self.read_binary(value_entry_pos)?),
+            DataType::Timestamp => Value::Timestamp(self.0.read_timestamp(self.read_value_pos(value_entry_pos)?)?),
+            DataType::Date => Value::Date(self.0.read_date(self.read_value_pos(value_entry_pos)?)?),
+            DataType::ShortDate => Value::ShortDate(self.0.read_short_date(self.read_value_pos(value_entry_pos)?)?),
+            DataType::Time => Value::Time(self.0.read_time(self.read_value_pos(value_entry_pos)?)?),
+            DataType::IntervalYearMonth => {
+                Value::IntervalYearMonth(self.0.read_interval_year_month(self.read_value_pos(value_entry_pos)?)?)
+            }
+            DataType::IntervalDayTime => {
+                Value::IntervalDayTime(self.0.read_interval_day_time(self.read_value_pos(value_entry_pos)?)?)
+            }
         };
         Ok(value)
     }
@@ -267,6 +523,12 @@ impl<'a> ArrayIter<'a> {
             index: 0,
         })
     }
+
+    /// Creates an iterator over the elements in `index..len`.
+    #[inline]
+    pub(crate) fn with_range(array: Array<'a>, index: usize, len: usize) -> ArrayIter<'a> {
+        Self { array, len, index }
+    }
 }
 
 impl<'a> Iterator for ArrayIter<'a> {
@@ -284,6 +546,126 @@ impl<'a> Iterator for ArrayIter<'a> {
     }
 }
 
+mod private {
+    pub trait Sealed {}
+}
+
+/// Scalar types which can be read from the elements of an array by [`Array::iter_of`].
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait ArrayElement<'a>: private::Sealed + Sized {
+    /// The type of the elements.
+    const DATA_TYPE: DataType;
+
+    #[doc(hidden)]
+    fn read(array: &Array<'a>, value_entry_pos: usize) -> YasonResult<Self>;
+}
+
+macro_rules! impl_array_element {
+    ($ty: ty, $data_type: expr, $read: ident) => {
+        impl private::Sealed for $ty {}
+
+        impl<'a> ArrayElement<'a> for $ty {
+            const DATA_TYPE: DataType = $data_type;
+
+            #[inline]
+            fn read(array: &Array<'a>, value_entry_pos: usize) -> YasonResult<Self> {
+                array.0.$read(array.read_value_pos(value_entry_pos)?)
+            }
+        }
+    };
+}
+
+impl private::Sealed for &str {}
+
+impl<'a> ArrayElement<'a> for &'a str {
+    const DATA_TYPE: DataType = DataType::String;
+
+    #[inline]
+    fn read(array: &Array<'a>, value_entry_pos: usize) -> YasonResult<Self> {
+        array.read_string(value_entry_pos)
+    }
+}
+
+impl private::Sealed for &[u8] {}
+
+impl<'a> ArrayElement<'a> for &'a [u8] {
+    const DATA_TYPE: DataType = DataType::Binary;
+
+    #[inline]
+    fn read(array: &Array<'a>, value_entry_pos: usize) -> YasonResult<Self> {
+        array.read_binary(value_entry_pos)
+    }
+}
+
+impl private::Sealed for bool {}
+
+impl<'a> ArrayElement<'a> for bool {
+    const DATA_TYPE: DataType = DataType::Bool;
+
+    #[inline]
+    fn read(array: &Array<'a>, value_entry_pos: usize) -> YasonResult<Self> {
+        array.read_bool(value_entry_pos)
+    }
+}
+
+impl_array_element!(Number, DataType::Number, read_number);
+impl_array_element!(Timestamp, DataType::Timestamp, read_timestamp);
+impl_array_element!(Date, DataType::Date, read_date);
+impl_array_element!(ShortDate, DataType::ShortDate, read_short_date);
+impl_array_element!(Time, DataType::Time, read_time);
+impl_array_element!(YmInterval, DataType::IntervalYearMonth, read_interval_year_month);
+impl_array_element!(DtInterval, DataType::IntervalDayTime, read_interval_day_time);
+
+/// An iterator over the elements of an array of one type, see [`Array::iter_of`].
+pub struct TypedArrayIter<'a, T> {
+    array: Array<'a>,
+    value_entry_pos: usize,
+    end: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: ArrayElement<'a>> TypedArrayIter<'a, T> {
+    #[inline]
+    fn try_new(yason: &'a Yason) -> YasonResult<TypedArrayIter<'a, T>> {
+        let array = Array(yason);
+        let value_entry_pos = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE;
+        Ok(Self {
+            end: value_entry_pos + array.len()? * VALUE_ENTRY_SIZE,
+            array,
+            value_entry_pos,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<'a, T: ArrayElement<'a>> Iterator for TypedArrayIter<'a, T> {
+    type Item = YasonResult<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.value_entry_pos < self.end {
+            let value_entry_pos = self.value_entry_pos;
+            self.value_entry_pos += VALUE_ENTRY_SIZE;
+            let value = match self.array.0.check_type(value_entry_pos, T::DATA_TYPE) {
+                Ok(()) => T::read(&self.array, value_entry_pos),
+                Err(e) => Err(e),
+            };
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.value_entry_pos) / VALUE_ENTRY_SIZE;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: ArrayElement<'a>> ExactSizeIterator for TypedArrayIter<'a, T> {}
+
 pub struct LazyArrayIter<'a> {
     array: Array<'a>,
     len: usize,
@@ -0,0 +1,259 @@
+//! Canonical form of yason.
+
+use crate::transform::{push_array_value, push_object_value};
+use crate::yason::{Array, Object, Value, Yason, YasonBuf, YasonError, YasonResult};
+use crate::{ArrBuilder, ArrayBuilder, ArrayRefBuilder, ObjBuilder, ObjectBuilder, ObjectRefBuilder, Scalar};
+
+impl Yason {
+    /// Returns the canonical form of the yason.
+    ///
+    /// Two yason are equal by [`Yason::equals`] if and only if their canonical forms have identical
+    /// bytes, so the canonical bytes can be signed, hashed or used as a deduplication key instead of
+    /// the text. In the canonical form:
+    ///
+    /// - the key-values of every object are stored in the order of the keys, so the key-offset table
+    ///   and the key-values have the same order,
+    /// - every array element has its own value, nothing is shared like in [`Yason::deduplicate`],
+    /// - data lengths are encoded with the fewest bytes,
+    /// - numbers are normalized, i.e. trailing zeros of the fraction are removed and negative zero
+    ///   becomes zero, e.g. `1.50` is stored as `1.5` and `1e2` as `100`,
+    /// - there is no header and no checksum footer.
+    ///
+    /// Returns `YasonError::DuplicateKey` if an object has duplicate keys, which never happens in
+    /// a valid yason.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let left = YasonBuf::parse(r#"{"b": [1.50, true], "a": 1e2}"#).unwrap();
+    /// let right = YasonBuf::parse(r#"{"a": 100, "b": [1.5, true]}"#).unwrap();
+    /// assert_eq!(left.canonicalize().unwrap().as_bytes(), right.canonicalize().unwrap().as_bytes());
+    /// ```
+    #[inline]
+    pub fn canonicalize(&self) -> YasonResult<YasonBuf> {
+        let res = match Value::try_from(self.value()?)? {
+            Value::Object(object) => {
+                let mut builder = ObjectBuilder::try_new(object.len()? as u16, true)?;
+                push_object_entries(&mut builder, &object)?;
+                builder.finish()?
+            }
+            Value::Array(array) => {
+                let mut builder = ArrayBuilder::try_new(array.len()? as u16)?;
+                push_array_values(&mut builder, &array)?;
+                builder.finish()?
+            }
+            Value::String(s) => Scalar::string(s)?,
+            Value::Number(number) => Scalar::number(number.normalize())?,
+            Value::Bool(b) => Scalar::bool(b)?,
+            Value::Null => Scalar::null()?,
+            Value::Binary(b) => Scalar::binary(b)?,
+            Value::Timestamp(timestamp) => Scalar::timestamp(timestamp)?,
+            Value::Date(date) => Scalar::date(date)?,
+            Value::ShortDate(short_date) => Scalar::short_date(short_date)?,
+            Value::Time(time) => Scalar::time(time)?,
+            Value::IntervalYearMonth(months) => Scalar::interval_year_month(months)?,
+            Value::IntervalDayTime(micros) => Scalar::interval_day_time(micros)?,
+        };
+        Ok(res)
+    }
+
+    /// Returns whether the yason is in its canonical form, see [`Yason::canonicalize`].
+    #[inline]
+    pub fn is_canonical(&self) -> YasonResult<bool> {
+        Ok(self.canonicalize()?.as_bytes() == self.as_bytes())
+    }
+
+    /// Returns the canonical form of the yason with the elements of every array sorted by their
+    /// own canonical bytes, so two yason have identical unordered forms if and only if they are
+    /// equal when arrays are compared as multisets.
+    #[inline]
+    pub(crate) fn unordered_form(&self) -> YasonResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        push_unordered(&Value::try_from(self.value()?)?, &mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+fn push_object_entries<B: ObjBuilder>(builder: &mut B, object: &Object) -> YasonResult<()> {
+    let mut prev_key = None;
+    for entry in object.iter()? {
+        let (key, value) = entry?;
+        if prev_key == Some(key) {
+            return Err(YasonError::DuplicateKey(key.to_string()));
+        }
+        prev_key = Some(key);
+
+        match value {
+            Value::Object(object) => {
+                let mut nested = builder.push_object(key, object.len()? as u16, true)?;
+                push_object_entries(&mut nested, &object)?;
+                nested.finish()?;
+            }
+            Value::Array(array) => {
+                let mut nested = builder.push_array(key, array.len()? as u16)?;
+                push_array_values(&mut nested, &array)?;
+                nested.finish()?;
+            }
+            Value::String(s) => {
+                builder.push_string(key, s)?;
+            }
+            Value::Number(number) => {
+                builder.push_number(key, number.normalize())?;
+            }
+            Value::Bool(b) => {
+                builder.push_bool(key, b)?;
+            }
+            Value::Null => {
+                builder.push_null(key)?;
+            }
+            Value::Binary(b) => {
+                builder.push_binary(key, b)?;
+            }
+            Value::Timestamp(timestamp) => {
+                builder.push_timestamp(key, timestamp)?;
+            }
+            Value::Date(date) => {
+                builder.push_date(key, date)?;
+            }
+            Value::ShortDate(short_date) => {
+                builder.push_short_date(key, short_date)?;
+            }
+            Value::Time(time) => {
+                builder.push_time(key, time)?;
+            }
+            Value::IntervalYearMonth(months) => {
+                builder.push_interval_year_month(key, months)?;
+            }
+            Value::IntervalDayTime(micros) => {
+                builder.push_interval_day_time(key, micros)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn push_array_values<B: ArrBuilder>(builder: &mut B, array: &Array) -> YasonResult<()> {
+    for value in array.iter()? {
+        match value? {
+            Value::Object(object) => {
+                let mut nested = builder.push_object(object.len()? as u16, true)?;
+                push_object_entries(&mut nested, &object)?;
+                nested.finish()?;
+            }
+            Value::Array(array) => {
+                let mut nested = builder.push_array(array.len()? as u16)?;
+                push_array_values(&mut nested, &array)?;
+                nested.finish()?;
+            }
+            Value::String(s) => {
+                builder.push_string(s)?;
+            }
+            Value::Number(number) => {
+                builder.push_number(number.normalize())?;
+            }
+            Value::Bool(b) => {
+                builder.push_bool(b)?;
+            }
+            Value::Null => {
+                builder.push_null()?;
+            }
+            Value::Binary(b) => {
+                builder.push_binary(b)?;
+            }
+            Value::Timestamp(timestamp) => {
+                builder.push_timestamp(timestamp)?;
+            }
+            Value::Date(date) => {
+                builder.push_date(date)?;
+            }
+            Value::ShortDate(short_date) => {
+                builder.push_short_date(short_date)?;
+            }
+            Value::Time(time) => {
+                builder.push_time(time)?;
+            }
+            Value::IntervalYearMonth(months) => {
+                builder.push_interval_year_month(months)?;
+            }
+            Value::IntervalDayTime(micros) => {
+                builder.push_interval_day_time(micros)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn push_unordered(value: &Value, bytes: &mut Vec<u8>) -> YasonResult<()> {
+    match value {
+        Value::Object(object) => {
+            let mut builder = ObjectRefBuilder::try_new(bytes, object.len()? as u16, true)?;
+            let mut prev_key = None;
+            let mut nested = Vec::new();
+            for entry in object.iter()? {
+                let (key, value) = entry?;
+                if prev_key == Some(key) {
+                    return Err(YasonError::DuplicateKey(key.to_string()));
+                }
+                prev_key = Some(key);
+
+                nested.clear();
+                push_unordered(&value, &mut nested)?;
+                // SAFETY: the bytes are built above.
+                let value = Value::try_from(unsafe { Yason::new_unchecked(&nested) })?;
+                push_object_value(&mut builder, key, &value)?;
+            }
+            builder.finish()?;
+        }
+        Value::Array(array) => {
+            let mut elements = Vec::with_capacity(array.len()?);
+            for value in array.iter()? {
+                let mut element = Vec::new();
+                push_unordered(&value?, &mut element)?;
+                elements.push(element);
+            }
+            elements.sort_unstable();
+
+            let mut builder = ArrayRefBuilder::try_new(bytes, elements.len() as u16)?;
+            for element in elements.iter() {
+                // SAFETY: the bytes are built above.
+                let value = Value::try_from(unsafe { Yason::new_unchecked(element) })?;
+                push_array_value(&mut builder, &value)?;
+            }
+            builder.finish()?;
+        }
+        Value::String(s) => {
+            Scalar::string_with_vec(s, bytes)?;
+        }
+        Value::Number(number) => {
+            Scalar::number_with_vec(number.normalize(), bytes)?;
+        }
+        Value::Bool(b) => {
+            Scalar::bool_with_vec(*b, bytes)?;
+        }
+        Value::Null => {
+            Scalar::null_with_vec(bytes)?;
+        }
+        Value::Binary(b) => {
+            Scalar::binary_with_vec(b, bytes)?;
+        }
+        Value::Timestamp(timestamp) => {
+            Scalar::timestamp_with_vec(*timestamp, bytes)?;
+        }
+        Value::Date(date) => {
+            Scalar::date_with_vec(*date, bytes)?;
+        }
+        Value::ShortDate(short_date) => {
+            Scalar::short_date_with_vec(*short_date, bytes)?;
+        }
+        Value::Time(time) => {
+            Scalar::time_with_vec(*time, bytes)?;
+        }
+        Value::IntervalYearMonth(months) => {
+            Scalar::interval_year_month_with_vec(*months, bytes)?;
+        }
+        Value::IntervalDayTime(micros) => {
+            Scalar::interval_day_time_with_vec(*micros, bytes)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Checksum footer of yason.
+
+use crate::binary::{CHECKSUM_SIZE, FLAG_CHECKSUM, HEADER_MAGIC};
+use crate::builder::BuildResult;
+use crate::yason::{Yason, YasonBuf, YasonError, YasonResult};
+
+impl Yason {
+    /// Returns true if the value is followed by a checksum footer.
+    #[inline]
+    pub fn has_checksum(&self) -> YasonResult<bool> {
+        Ok(self.skip_value(self.header_len())? + CHECKSUM_SIZE == self.bytes.len())
+    }
+
+    /// Verifies the CRC32C footer against the bytes before it, so torn writes and corrupted bytes
+    /// can be detected before the value is read.
+    ///
+    /// Returns `YasonError::ChecksumMismatch` if the footer differs, or `YasonError::InvalidFormat`
+    /// if there is no footer.
+    #[inline]
+    pub fn verify_checksum(&self) -> YasonResult<()> {
+        let end = self.skip_value(self.header_len())?;
+        if end + CHECKSUM_SIZE != self.bytes.len() {
+            return Err(YasonError::InvalidFormat("missing checksum"));
+        }
+        self.verify_checksum_at(end)
+    }
+
+    #[inline]
+    pub(crate) fn verify_checksum_at(&self, end: usize) -> YasonResult<()> {
+        let expected = self.read_u32(end)?;
+        let actual = crc32c::crc32c(&self.bytes[..end]);
+        if expected != actual {
+            return Err(YasonError::ChecksumMismatch { expected, actual });
+        }
+        Ok(())
+    }
+}
+
+impl YasonBuf {
+    /// Appends a CRC32C footer of the bytes before it, an existing footer is replaced.
+    ///
+    /// The footer is kept by copies of the whole document, such as [`Yason::to_yason_buf`], but
+    /// not by values derived from it, such as objects pushed into a builder.
+    ///
+    /// Returns an error if the end of the value cannot be found, e.g. the bytes are corrupted.
+    #[inline]
+    pub fn add_checksum(&mut self) -> YasonResult<()> {
+        let end = self.skip_value(self.header_len())?;
+        self.bytes.truncate(end);
+        if self.has_header() {
+            self.bytes[HEADER_MAGIC.len() + 1] |= FLAG_CHECKSUM;
+        }
+        Ok(push_checksum(&mut self.bytes, 0)?)
+    }
+}
+
+/// Appends the CRC32C footer of the bytes starting at `start`.
+#[inline]
+pub(crate) fn push_checksum(bytes: &mut Vec<u8>, start: usize) -> BuildResult<()> {
+    let checksum = crc32c::crc32c(&bytes[start..]);
+    bytes.try_reserve(CHECKSUM_SIZE)?;
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Chunked iteration of arrays.
+
+use crate::yason::array::{Array, ArrayIter};
+use crate::yason::{Value, YasonError, YasonResult};
+
+impl<'a> Array<'a> {
+    /// Returns an iterator over `chunk_size` elements of the array at a time, the last chunk has
+    /// fewer elements if the length is not divisible by `chunk_size`.
+    ///
+    /// The chunks are views of the array which borrow the underlying buffer rather than the
+    /// iterator, so they can be sent to other threads and processed independently.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let yason = YasonBuf::parse("[1, 2, 3, 4, 5]").unwrap();
+    /// let array = yason.array().unwrap();
+    /// let lens = array.chunks(2).unwrap().map(|chunk| chunk.len()).collect::<Vec<_>>();
+    /// assert_eq!(lens, [2, 2, 1]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    #[inline]
+    pub fn chunks(&self, chunk_size: usize) -> YasonResult<ArrayChunks<'a>> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        Ok(ArrayChunks {
+            array: self.clone(),
+            index: 0,
+            len: self.len()?,
+            chunk_size,
+        })
+    }
+
+    /// Returns a parallel iterator over the values of the array, which are decoded on the threads
+    /// of the rayon pool.
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    #[inline]
+    pub fn par_iter(&self) -> YasonResult<impl rayon::iter::IndexedParallelIterator<Item = YasonResult<Value<'a>>>> {
+        use rayon::prelude::*;
+
+        let array = self.clone();
+        Ok((0..self.len()?)
+            .into_par_iter()
+            .map(move |index| unsafe { array.get_unchecked(index) }))
+    }
+}
+
+/// A view of consecutive elements of an array, returned by [`Array::chunks`].
+#[derive(Clone, Debug)]
+pub struct ArrayChunk<'a> {
+    array: Array<'a>,
+    start: usize,
+    len: usize,
+}
+
+impl<'a> ArrayChunk<'a> {
+    /// Returns the array which the chunk is a view of.
+    #[inline]
+    pub fn array(&self) -> &Array<'a> {
+        &self.array
+    }
+
+    /// Returns the index of the first element of the chunk in the array.
+    #[inline]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the number of elements in the chunk.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the chunk contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the element at the given index of the chunk.
+    #[inline]
+    pub fn get(&self, index: usize) -> YasonResult<Value<'a>> {
+        if index >= self.len {
+            return Err(YasonError::IndexOutOfBounds { len: self.len, index });
+        }
+        unsafe { self.array.get_unchecked(self.start + index) }
+    }
+
+    /// Gets an iterator over the elements of the chunk.
+    #[inline]
+    pub fn iter(&self) -> ArrayIter<'a> {
+        ArrayIter::with_range(self.array.clone(), self.start, self.start + self.len)
+    }
+}
+
+/// An iterator over the chunks of an array, returned by [`Array::chunks`].
+#[derive(Clone, Debug)]
+pub struct ArrayChunks<'a> {
+    array: Array<'a>,
+    index: usize,
+    len: usize,
+    chunk_size: usize,
+}
+
+impl<'a> Iterator for ArrayChunks<'a> {
+    type Item = ArrayChunk<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+        let len = self.chunk_size.min(self.len - self.index);
+        let chunk = ArrayChunk {
+            array: self.array.clone(),
+            start: self.index,
+            len,
+        };
+        self.index += len;
+        Some(chunk)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        let count = (remaining + self.chunk_size - 1) / self.chunk_size;
+        (count, Some(count))
+    }
+}
+
+impl<'a> ExactSizeIterator for ArrayChunks<'a> {}
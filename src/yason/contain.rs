@@ -0,0 +1,106 @@
+//! Containment of yason.
+
+use std::cmp::Ordering;
+
+use crate::yason::{Array, Object, Value, Yason, YasonResult};
+use crate::{num, BinaryCollation, StepBudget};
+
+impl Yason {
+    /// Returns whether the yason contains the needle, with the containment semantics of the
+    /// `@>` operator of JSONB, i.e. `a <@ b` is `b.contains_document(a)`:
+    ///
+    /// - a scalar contains an equal scalar of the same type, including binaries and temporal values,
+    /// - an object contains an object if every key of the needle is present and its value contains
+    ///   the value of the needle, other keys are ignored,
+    /// - an array contains an array if every element of the needle is contained in some element,
+    ///   regardless of order and duplicates,
+    /// - as a special case, an array at the top level contains a scalar that is one of its elements.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let doc = YasonBuf::parse(r#"{"name": "a", "tags": ["x", "y"], "dims": {"w": 1, "h": 2}}"#).unwrap();
+    /// let needle = YasonBuf::parse(r#"{"tags": ["y"], "dims": {"h": 2}}"#).unwrap();
+    /// assert!(doc.contains_document(&needle).unwrap());
+    ///
+    /// let needle = YasonBuf::parse(r#"{"tags": "y"}"#).unwrap();
+    /// assert!(!doc.contains_document(&needle).unwrap());
+    /// ```
+    #[inline]
+    pub fn contains_document<T: AsRef<Yason>>(&self, needle: T) -> YasonResult<bool> {
+        self.contains_document_with_budget(needle, &mut StepBudget::unlimited())
+    }
+
+    /// Returns whether the yason contains the needle like [`Yason::contains_document`], returning
+    /// `YasonError::Cancelled` once the compared values exceed the budget.
+    #[inline]
+    pub fn contains_document_with_budget<T: AsRef<Yason>>(
+        &self,
+        needle: T,
+        budget: &mut StepBudget,
+    ) -> YasonResult<bool> {
+        let value = Value::try_from(self.value()?)?;
+        let needle = Value::try_from(needle.as_ref().value()?)?;
+        match (&value, &needle) {
+            (Value::Array(array), needle) if !matches!(needle, Value::Object(_) | Value::Array(_)) => {
+                array_contains_scalar(array, needle, budget)
+            }
+            _ => contains(&value, &needle, budget),
+        }
+    }
+}
+
+fn contains(value: &Value, needle: &Value, budget: &mut StepBudget) -> YasonResult<bool> {
+    budget.step()?;
+    match (value, needle) {
+        (Value::Object(object), Value::Object(needle)) => object_contains(object, needle, budget),
+        (Value::Array(array), Value::Array(needle)) => array_contains(array, needle, budget),
+        _ => Ok(scalar_equals(value, needle)),
+    }
+}
+
+#[inline]
+fn scalar_equals(value: &Value, needle: &Value) -> bool {
+    match (value, needle) {
+        (Value::Number(left), Value::Number(right)) => num::equals(left, right),
+        _ => value.compare_with(needle, &BinaryCollation) == Some(Ordering::Equal),
+    }
+}
+
+fn object_contains(object: &Object, needle: &Object, budget: &mut StepBudget) -> YasonResult<bool> {
+    if needle.len()? > object.len()? {
+        return Ok(false);
+    }
+
+    for entry in needle.iter()? {
+        let (key, needle_value) = entry?;
+        match object.get(key)? {
+            Some(value) if contains(&value, &needle_value, budget)? => {}
+            _ => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+fn array_contains(array: &Array, needle: &Array, budget: &mut StepBudget) -> YasonResult<bool> {
+    'needle: for needle_value in needle.iter()? {
+        let needle_value = needle_value?;
+        for value in array.iter()? {
+            if contains(&value?, &needle_value, budget)? {
+                continue 'needle;
+            }
+        }
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+fn array_contains_scalar(array: &Array, needle: &Value, budget: &mut StepBudget) -> YasonResult<bool> {
+    for value in array.iter()? {
+        budget.step()?;
+        if scalar_equals(&value?, needle) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,190 @@
+//! Deduplication of repeated array elements.
+
+use crate::binary::{
+    ARRAY_SIZE, BOOL_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, MAX_DATA_LENGTH_SIZE,
+    NUMBER_LENGTH_SIZE, OBJECT_SIZE, VALUE_ENTRY_SIZE,
+};
+use crate::builder::finish_object;
+use crate::vec::VecExt;
+use crate::yason::{Array, Object, Value, Yason, YasonBuf, YasonError, YasonResult};
+use crate::{DataType, Scalar};
+use decimal_rs::MAX_BINARY_SIZE;
+use std::collections::HashMap;
+
+/// Identity of an outlined value which can be shared by several value entries.
+#[derive(PartialEq, Eq, Hash)]
+enum SharedKey<'a> {
+    String(&'a str),
+    Binary(&'a [u8]),
+    Container(&'a [u8]),
+}
+
+impl Yason {
+    /// Returns a copy of the yason in which identical strings, binaries, objects and arrays of the same array
+    /// are stored only once.
+    ///
+    /// The elements of an array are located through their value entries, so several entries can refer
+    /// to one outlined value and the result is read like any other yason. Values of an object are
+    /// stored right after their keys, and nested objects and arrays must remain readable as standalone
+    /// slices, so values are never shared across containers.
+    #[inline]
+    pub fn deduplicate(&self) -> YasonResult<YasonBuf> {
+        let mut bytes: Vec<u8> =
+            VecExt::try_with_capacity(self.as_bytes().len()).map_err(YasonError::TryReserveError)?;
+        match self.data_type()? {
+            DataType::Object => write_object(unsafe { &self.object_unchecked()? }, &mut bytes)?,
+            DataType::Array => write_array(unsafe { &self.array_unchecked()? }, &mut bytes)?,
+            _ => bytes.push_bytes(self.value()?.as_bytes()),
+        }
+        Ok(unsafe { YasonBuf::new_unchecked(bytes) })
+    }
+}
+
+fn write_value(value: &Value, bytes: &mut Vec<u8>) -> YasonResult<()> {
+    match value {
+        Value::Object(object) => write_object(object, bytes)?,
+        Value::Array(array) => write_array(array, bytes)?,
+        Value::String(s) => {
+            bytes
+                .try_reserve(DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + s.len())
+                .map_err(YasonError::TryReserveError)?;
+            bytes.push_data_type(DataType::String);
+            bytes.push_string(s)?;
+        }
+        Value::Number(number) => {
+            bytes
+                .try_reserve(DATA_TYPE_SIZE + NUMBER_LENGTH_SIZE + MAX_BINARY_SIZE)
+                .map_err(YasonError::TryReserveError)?;
+            bytes.push_data_type(DataType::Number);
+            bytes.push_number(number);
+        }
+        Value::Bool(b) => {
+            bytes
+                .try_reserve(DATA_TYPE_SIZE + BOOL_SIZE)
+                .map_err(YasonError::TryReserveError)?;
+            bytes.push_data_type(DataType::Bool);
+            bytes.push_u8(*b as u8);
+        }
+        Value::Null => {
+            bytes.try_reserve(DATA_TYPE_SIZE).map_err(YasonError::TryReserveError)?;
+            bytes.push_data_type(DataType::Null);
+        }
+        Value::Binary(b) => {
+            bytes
+                .try_reserve(DATA_TYPE_SIZE + MAX_DATA_LENGTH_SIZE + b.len())
+                .map_err(YasonError::TryReserveError)?;
+            bytes.push_data_type(DataType::Binary);
+            bytes.push_binary(b)?;
+        }
+        Value::Timestamp(timestamp) => {
+            Scalar::timestamp_with_vec(*timestamp, bytes)?;
+        }
+        Value::Date(date) => {
+            Scalar::date_with_vec(*date, bytes)?;
+        }
+        Value::ShortDate(short_date) => {
+            Scalar::short_date_with_vec(*short_date, bytes)?;
+        }
+        Value::Time(time) => {
+            Scalar::time_with_vec(*time, bytes)?;
+        }
+        Value::IntervalYearMonth(months) => {
+            Scalar::interval_year_month_with_vec(*months, bytes)?;
+        }
+        Value::IntervalDayTime(micros) => {
+            Scalar::interval_day_time_with_vec(*micros, bytes)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_object(object: &Object, bytes: &mut Vec<u8>) -> YasonResult<()> {
+    let len = object.len()?;
+    bytes
+        .try_reserve(DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + KEY_OFFSET_SIZE * len)
+        .map_err(YasonError::TryReserveError)?;
+    bytes.push_data_type(DataType::Object); // type
+    bytes.skip_size(); // size
+    let start_pos = bytes.len();
+    bytes.push_u16(len as u16); // element-count
+    let key_offset_pos = bytes.len();
+    bytes.skip_key_offset(len); // key-offset
+
+    // keep the insertion order of key-values
+    for index in object.insertion_order()? {
+        let (key, value) = object.get_nth(index)?;
+        let key_offset = bytes.len() - start_pos;
+        bytes.write_offset(key_offset as u32, key_offset_pos + index * KEY_OFFSET_SIZE);
+
+        bytes
+            .try_reserve(KEY_LENGTH_SIZE + key.len())
+            .map_err(YasonError::TryReserveError)?;
+        bytes.push_key(key);
+        write_value(&value, bytes)?;
+    }
+
+    finish_object(bytes, start_pos, len, object.is_compact());
+    Ok(())
+}
+
+fn write_array(array: &Array, bytes: &mut Vec<u8>) -> YasonResult<()> {
+    let len = array.len()?;
+    bytes
+        .try_reserve(DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE * len)
+        .map_err(YasonError::TryReserveError)?;
+    bytes.push_data_type(DataType::Array); // type
+    bytes.skip_size(); // size
+    let start_pos = bytes.len();
+    bytes.push_u16(len as u16); // element-count
+    let mut value_entry_pos = bytes.len();
+    bytes.skip_value_entry(len); // value-entry
+
+    let mut shared = HashMap::new();
+    for value in array.iter()? {
+        let value = value?;
+        bytes.write_data_type_by_pos(value.data_type(), value_entry_pos);
+        let offset_pos = value_entry_pos + DATA_TYPE_SIZE;
+        value_entry_pos += VALUE_ENTRY_SIZE;
+
+        let key = match &value {
+            // bool and null are inlined
+            Value::Bool(b) => {
+                bytes.write_offset(*b as u32, offset_pos);
+                continue;
+            }
+            Value::Null => {
+                bytes.write_offset(0, offset_pos);
+                continue;
+            }
+            Value::String(s) => Some(SharedKey::String(s)),
+            Value::Binary(b) => Some(SharedKey::Binary(b)),
+            Value::Object(object) => Some(SharedKey::Container(object.yason().as_bytes())),
+            Value::Array(array) => Some(SharedKey::Container(array.yason().as_bytes())),
+            // the same number may be encoded with different scales, so numbers are not shared
+            Value::Number(_) => None,
+            // temporal values are hardly larger than a value entry, so they are not shared
+            Value::Timestamp(_)
+            | Value::Date(_)
+            | Value::ShortDate(_)
+            | Value::Time(_)
+            | Value::IntervalYearMonth(_)
+            | Value::IntervalDayTime(_) => None,
+        };
+
+        if let Some(offset) = key.as_ref().and_then(|key| shared.get(key)) {
+            bytes.write_offset(*offset, offset_pos);
+            continue;
+        }
+
+        let offset = (bytes.len() - start_pos) as u32;
+        bytes.write_offset(offset, offset_pos);
+        write_value(&value, bytes)?;
+        if let Some(key) = key {
+            shared.insert(key, offset);
+        }
+    }
+
+    let total_size = bytes.len() - start_pos;
+    bytes.write_total_size(total_size as i32, start_pos - ARRAY_SIZE);
+    Ok(())
+}
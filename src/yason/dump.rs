@@ -0,0 +1,295 @@
+//! Annotated dump of the yason binary layout.
+
+use crate::binary::{
+    ARRAY_SIZE, CHECKSUM_SIZE, COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, DATE_SIZE, ELEMENT_COUNT_SIZE, HEADER_MAGIC,
+    HEADER_SIZE, INTERVAL_DT_SIZE, INTERVAL_YM_SIZE, KEY_LENGTH_SIZE, NUMBER_LENGTH_SIZE, OBJECT_SIZE, SHORT_DATE_SIZE,
+    TIMESTAMP_SIZE, TIME_SIZE, VALUE_ENTRY_SIZE,
+};
+use crate::format::{format_escaped_str, FormatResult};
+use crate::util::decode_varint;
+use crate::yason::{Yason, YasonError};
+use crate::{DataType, Number};
+use decimal_rs::MAX_BINARY_SIZE;
+use std::fmt;
+
+/// Maximum number of bytes shown on a line, longer fields are cut.
+const MAX_LINE_BYTES: usize = 8;
+/// Maximum number of chars shown for a string or a key.
+const MAX_TEXT_CHARS: usize = 32;
+const INDENT: &str = "  ";
+
+impl Yason {
+    /// Writes an annotated hexdump of the binary layout, one field per line: the offset, the bytes
+    /// and what they encode, such as type tags, sizes, key-offset tables and value entries.
+    ///
+    /// Object members are shown in storage order and gaps between them are reported, so the dump
+    /// also helps to inspect bytes which are not a valid yason, up to the first unreadable field.
+    ///
+    /// ```text
+    /// 000000  01                          object
+    /// 000001  0a 00 00 00                   size 10
+    /// 000005  01 00                         element count 1
+    /// 000007  06 00 00 00                   key offset [0] 6 -> 00000b
+    /// 00000b  01 00 61                      key "a"
+    /// 00000e  06                            null
+    /// ```
+    #[inline]
+    pub fn dump_layout<W: fmt::Write>(&self, writer: &mut W) -> FormatResult<()> {
+        let mut dumper = Dumper { yason: self, writer };
+        let mut pos = 0;
+        if self.has_header() {
+            let version = self.read_u8(HEADER_MAGIC.len())?;
+            let flags = self.read_u8(HEADER_MAGIC.len() + 1)?;
+            dumper.line(
+                0,
+                HEADER_SIZE,
+                0,
+                format_args!("header version {} flags {:#04x}", version, flags),
+            )?;
+            pos = HEADER_SIZE;
+        }
+        let end = dumper.value(pos, 0)?;
+        let len = self.bytes.len();
+        if end + CHECKSUM_SIZE == len {
+            let checksum = self.read_u32(end)?;
+            dumper.line(end, CHECKSUM_SIZE, 0, format_args!("checksum {:#010x}", checksum))?;
+        } else if end < len {
+            dumper.line(end, len - end, 0, format_args!("trailing {} bytes", len - end))?;
+        }
+        Ok(())
+    }
+}
+
+struct Dumper<'a, W: fmt::Write> {
+    yason: &'a Yason,
+    writer: &'a mut W,
+}
+
+impl<W: fmt::Write> Dumper<'_, W> {
+    fn line(&mut self, pos: usize, len: usize, depth: usize, annotation: fmt::Arguments) -> FormatResult<()> {
+        let bytes = self.yason.slice(pos, pos + len)?;
+        write!(self.writer, "{:06x} ", pos)?;
+        let mut width = 0;
+        for byte in bytes.iter().take(MAX_LINE_BYTES) {
+            write!(self.writer, " {:02x}", byte)?;
+            width += 3;
+        }
+        if bytes.len() > MAX_LINE_BYTES {
+            self.writer.write_str(" ..")?;
+            width += 3;
+        }
+        for _ in width..(MAX_LINE_BYTES + 1) * 3 + 2 {
+            self.writer.write_char(' ')?;
+        }
+        for _ in 0..depth {
+            self.writer.write_str(INDENT)?;
+        }
+        self.writer.write_fmt(annotation)?;
+        self.writer.write_char('\n')?;
+        Ok(())
+    }
+
+    /// Dumps the field from `pos` to `end`, whose text starts at `text_pos`.
+    fn text(&mut self, pos: usize, text_pos: usize, end: usize, depth: usize, label: &str) -> FormatResult<()> {
+        let bytes = self.yason.slice(text_pos, end)?;
+        let text = std::str::from_utf8(bytes).map_err(|_| YasonError::InvalidFormat("text is not valid UTF-8"))?;
+        let mut escaped = String::new();
+        let cut = text.char_indices().nth(MAX_TEXT_CHARS).map(|(i, _)| i);
+        format_escaped_str(&text[..cut.unwrap_or(text.len())], &mut escaped)?;
+        let ellipsis = if cut.is_some() { ".." } else { "" };
+        self.line(
+            pos,
+            end - pos,
+            depth,
+            format_args!("{}\"{}\"{}", label, escaped, ellipsis),
+        )
+    }
+
+    /// Dumps the value starting at the type tag at `pos`, returns the position after it.
+    fn value(&mut self, pos: usize, depth: usize) -> FormatResult<usize> {
+        let data_type = self.yason.read_type(pos)?;
+        let name = match self.yason.get(pos)? {
+            COMPACT_OBJECT_TYPE => "compact object",
+            _ => data_type.name(),
+        };
+        self.line(pos, DATA_TYPE_SIZE, depth, format_args!("{}", name))?;
+        self.content(pos + DATA_TYPE_SIZE, data_type, depth + 1)
+    }
+
+    /// Dumps the value after its type tag, returns the position after it.
+    fn content(&mut self, pos: usize, data_type: DataType, depth: usize) -> FormatResult<usize> {
+        match data_type {
+            DataType::Object => self.object(pos, depth),
+            DataType::Array => self.array(pos, depth),
+            DataType::String => {
+                self.yason.get(pos)?;
+                let (len, len_size) = decode_varint(&self.yason.bytes, pos)?;
+                self.line(pos, len_size, depth, format_args!("data length {}", len))?;
+                let end = pos + len_size + len as usize;
+                self.text(pos + len_size, pos + len_size, end, depth, "")?;
+                Ok(end)
+            }
+            DataType::Number => {
+                let len = self.yason.read_u8(pos)? as usize;
+                self.line(pos, NUMBER_LENGTH_SIZE, depth, format_args!("number length {}", len))?;
+                if len == 0 || len > MAX_BINARY_SIZE {
+                    return Err(YasonError::InvalidFormat("invalid number length").into());
+                }
+                let bytes = self
+                    .yason
+                    .slice(pos + NUMBER_LENGTH_SIZE, pos + NUMBER_LENGTH_SIZE + len)?;
+                let number = Number::decode(bytes);
+                self.line(pos + NUMBER_LENGTH_SIZE, len, depth, format_args!("{}", number))?;
+                Ok(pos + NUMBER_LENGTH_SIZE + len)
+            }
+            DataType::Bool => {
+                let value = self.yason.read_u8(pos)?;
+                self.line(pos, 1, depth, format_args!("{}", value == 1))?;
+                Ok(pos + 1)
+            }
+            DataType::Null => Ok(pos),
+            DataType::Binary => {
+                self.yason.get(pos)?;
+                let (len, len_size) = decode_varint(&self.yason.bytes, pos)?;
+                self.line(pos, len_size, depth, format_args!("data length {}", len))?;
+                let end = pos + len_size + len as usize;
+                self.line(pos + len_size, len as usize, depth, format_args!("binary data"))?;
+                Ok(end)
+            }
+            DataType::Timestamp => {
+                let value = self.yason.read_timestamp(pos - DATA_TYPE_SIZE)?;
+                self.line(pos, TIMESTAMP_SIZE, depth, format_args!("{}", value))?;
+                Ok(pos + TIMESTAMP_SIZE)
+            }
+            DataType::Date => {
+                let value = self.yason.read_date(pos - DATA_TYPE_SIZE)?;
+                self.line(pos, DATE_SIZE, depth, format_args!("{}", value))?;
+                Ok(pos + DATE_SIZE)
+            }
+            DataType::ShortDate => {
+                let value = self.yason.read_short_date(pos - DATA_TYPE_SIZE)?;
+                self.line(pos, SHORT_DATE_SIZE, depth, format_args!("{}", value))?;
+                Ok(pos + SHORT_DATE_SIZE)
+            }
+            DataType::Time => {
+                let value = self.yason.read_time(pos - DATA_TYPE_SIZE)?;
+                self.line(pos, TIME_SIZE, depth, format_args!("{}", value))?;
+                Ok(pos + TIME_SIZE)
+            }
+            DataType::IntervalYearMonth => {
+                let value = self.yason.read_interval_year_month(pos - DATA_TYPE_SIZE)?;
+                self.line(pos, INTERVAL_YM_SIZE, depth, format_args!("{}", value))?;
+                Ok(pos + INTERVAL_YM_SIZE)
+            }
+            DataType::IntervalDayTime => {
+                let value = self.yason.read_interval_day_time(pos - DATA_TYPE_SIZE)?;
+                self.line(pos, INTERVAL_DT_SIZE, depth, format_args!("{}", value))?;
+                Ok(pos + INTERVAL_DT_SIZE)
+            }
+        }
+    }
+
+    fn object(&mut self, pos: usize, depth: usize) -> FormatResult<usize> {
+        let size = self.yason.read_size(pos)?;
+        self.line(pos, OBJECT_SIZE, depth, format_args!("size {}", size))?;
+        let start_pos = pos + OBJECT_SIZE;
+        let end = start_pos + size;
+        let count = self.yason.read_u16(start_pos)? as usize;
+        self.line(
+            start_pos,
+            ELEMENT_COUNT_SIZE,
+            depth,
+            format_args!("element count {}", count),
+        )?;
+
+        let offset_size = self.yason.key_offset_size(pos - DATA_TYPE_SIZE)?;
+        let mut key_positions = Vec::with_capacity(count);
+        for i in 0..count {
+            let offset_pos = start_pos + ELEMENT_COUNT_SIZE + i * offset_size;
+            let offset = self.yason.read_key_offset(offset_pos, offset_size)? as usize;
+            let key_pos = start_pos + offset;
+            let annotation = format_args!("key offset [{}] {} -> {:06x}", i, offset, key_pos);
+            self.line(offset_pos, offset_size, depth, annotation)?;
+            key_positions.push(key_pos);
+        }
+
+        // key-values are shown in storage order
+        key_positions.sort_unstable();
+        let mut next = start_pos + ELEMENT_COUNT_SIZE + count * offset_size;
+        for key_pos in key_positions {
+            self.gap(next, key_pos, depth)?;
+            let key_end = key_pos + KEY_LENGTH_SIZE + self.yason.read_u16(key_pos)? as usize;
+            self.text(key_pos, key_pos + KEY_LENGTH_SIZE, key_end, depth, "key ")?;
+            next = self.value(key_end, depth)?;
+        }
+        self.gap(next, end, depth)?;
+        Ok(end)
+    }
+
+    fn array(&mut self, pos: usize, depth: usize) -> FormatResult<usize> {
+        let size = self.yason.read_size(pos)?;
+        self.line(pos, ARRAY_SIZE, depth, format_args!("size {}", size))?;
+        let start_pos = pos + ARRAY_SIZE;
+        let end = start_pos + size;
+        let count = self.yason.read_u16(start_pos)? as usize;
+        self.line(
+            start_pos,
+            ELEMENT_COUNT_SIZE,
+            depth,
+            format_args!("element count {}", count),
+        )?;
+
+        let mut outlined = Vec::with_capacity(count);
+        for i in 0..count {
+            let entry_pos = start_pos + ELEMENT_COUNT_SIZE + i * VALUE_ENTRY_SIZE;
+            let data_type = self.yason.read_type(entry_pos)?;
+            let inlined = self.yason.read_u32(entry_pos + DATA_TYPE_SIZE)?;
+            match data_type {
+                DataType::Bool => {
+                    let annotation = format_args!("entry [{}] {} {}", i, data_type.name(), inlined == 1);
+                    self.line(entry_pos, VALUE_ENTRY_SIZE, depth, annotation)?;
+                }
+                DataType::Null => {
+                    let annotation = format_args!("entry [{}] {}", i, data_type.name());
+                    self.line(entry_pos, VALUE_ENTRY_SIZE, depth, annotation)?;
+                }
+                _ => {
+                    let value_pos = start_pos + inlined as usize;
+                    let annotation = format_args!(
+                        "entry [{}] {} offset {} -> {:06x}",
+                        i,
+                        data_type.name(),
+                        inlined,
+                        value_pos
+                    );
+                    self.line(entry_pos, VALUE_ENTRY_SIZE, depth, annotation)?;
+                    outlined.push(value_pos);
+                }
+            }
+        }
+
+        // several entries may share one outlined value
+        outlined.sort_unstable();
+        outlined.dedup();
+        let mut next = start_pos + ELEMENT_COUNT_SIZE + count * VALUE_ENTRY_SIZE;
+        for value_pos in outlined {
+            self.gap(next, value_pos, depth)?;
+            next = self.value(value_pos, depth)?;
+        }
+        self.gap(next, end, depth)?;
+        Ok(end)
+    }
+
+    fn gap(&mut self, from: usize, to: usize, depth: usize) -> FormatResult<()> {
+        if from < to {
+            self.line(from, to - from, depth, format_args!("gap of {} bytes", to - from))?;
+        } else if from > to {
+            self.writer.write_str("       ")?;
+            for _ in 0..(MAX_LINE_BYTES + 1) * 3 + 2 + depth * INDENT.len() {
+                self.writer.write_char(' ')?;
+            }
+            writeln!(self.writer, "overlap of {} bytes", from - to)?;
+        }
+        Ok(())
+    }
+}
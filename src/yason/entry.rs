@@ -0,0 +1,79 @@
+//! Layout of the values of arrays and objects.
+
+use crate::DataType;
+use std::ops::Range;
+
+/// Where and how a value of an array or an object is encoded, see [`Array::entry_info`] and
+/// [`Object::entry_info`].
+///
+/// Offsets are relative to the bytes of the array or object, i.e. `yason().as_bytes()`, which
+/// starts at the type of the container.
+///
+/// [`Array::entry_info`]: crate::Array::entry_info
+/// [`Object::entry_info`]: crate::Object::entry_info
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EntryInfo {
+    data_type: DataType,
+    inlined: bool,
+    entry_offset: Option<usize>,
+    offset: usize,
+    len: usize,
+}
+
+impl EntryInfo {
+    #[inline]
+    pub(crate) const fn new(
+        data_type: DataType,
+        inlined: bool,
+        entry_offset: Option<usize>,
+        offset: usize,
+        len: usize,
+    ) -> Self {
+        Self {
+            data_type,
+            inlined,
+            entry_offset,
+            offset,
+            len,
+        }
+    }
+
+    /// Returns the type of the value.
+    #[inline]
+    pub const fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    /// Returns true if the value is stored in its value entry instead of after the value entries,
+    /// which is the case of bools and nulls in arrays.
+    #[inline]
+    pub const fn is_inlined(&self) -> bool {
+        self.inlined
+    }
+
+    /// Returns the offset of the value entry in an array, or `None` for a value of an object, which
+    /// has no value entry.
+    #[inline]
+    pub const fn entry_offset(&self) -> Option<usize> {
+        self.entry_offset
+    }
+
+    /// Returns the offset of the encoded value, starting at its type. An inlined value starts at
+    /// its value entry, whose type and first byte are also a valid encoding of the value.
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the length of the encoded value, including its type.
+    #[inline]
+    pub const fn encoded_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the range of the encoded value.
+    #[inline]
+    pub const fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+}
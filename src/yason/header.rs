@@ -0,0 +1,95 @@
+//! Version header of yason.
+
+use crate::binary::{FLAG_CHECKSUM, FORMAT_VERSION, HEADER_MAGIC, HEADER_SIZE, LEGACY_FORMAT_VERSION};
+use crate::yason::{Yason, YasonBuf, YasonError, YasonResult};
+
+impl Yason {
+    /// Returns the version of the binary format written in the header, or 0 if the bytes have no
+    /// header. The current version is 1.
+    #[inline]
+    pub fn format_version(&self) -> YasonResult<u8> {
+        if self.has_header() {
+            self.read_u8(HEADER_MAGIC.len())
+        } else {
+            Ok(LEGACY_FORMAT_VERSION)
+        }
+    }
+
+    /// Returns true if the bytes start with a version header.
+    #[inline]
+    pub fn has_header(&self) -> bool {
+        self.bytes.starts_with(&HEADER_MAGIC)
+    }
+
+    /// Returns the position of the value, right after the header if there is one.
+    #[inline]
+    pub(crate) fn header_len(&self) -> usize {
+        if self.has_header() {
+            HEADER_SIZE
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    pub(crate) fn header_flags(&self) -> YasonResult<u8> {
+        if self.has_header() {
+            self.read_u8(HEADER_MAGIC.len() + 1)
+        } else {
+            Ok(0)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn validate_header(&self) -> YasonResult<()> {
+        if !self.has_header() {
+            return Ok(());
+        }
+        let version = self.format_version()?;
+        if version == LEGACY_FORMAT_VERSION || version > FORMAT_VERSION {
+            return Err(YasonError::InvalidFormat("unsupported format version"));
+        }
+        if self.header_flags()? & !FLAG_CHECKSUM != 0 {
+            return Err(YasonError::InvalidFormat("unknown format flags"));
+        }
+        Ok(())
+    }
+
+    /// Returns the value without the header and the checksum footer.
+    #[inline]
+    pub(crate) fn value(&self) -> YasonResult<&Yason> {
+        let start = self.header_len();
+        let end = self.skip_value(start)?;
+        if start == 0 && end == self.bytes.len() {
+            return Ok(self);
+        }
+        Ok(unsafe { Yason::new_unchecked(self.slice(start, end)?) })
+    }
+}
+
+impl YasonBuf {
+    /// Prepends a header with the current format version, an existing header is updated.
+    ///
+    /// Readers of this version accept bytes with or without a header, but older readers reject
+    /// bytes with a header.
+    ///
+    /// Returns an error if the bytes are corrupted, so that it is unknown whether a checksum must be
+    /// kept.
+    #[inline]
+    pub fn add_header(&mut self) -> YasonResult<()> {
+        let checksum = self.has_checksum()?;
+        if !self.has_header() {
+            self.bytes
+                .try_reserve(HEADER_SIZE)
+                .map_err(YasonError::TryReserveError)?;
+            self.bytes.splice(0..0, HEADER_MAGIC.iter().copied().chain([0, 0]));
+        }
+        self.bytes[HEADER_MAGIC.len()] = FORMAT_VERSION;
+        self.bytes[HEADER_MAGIC.len() + 1] = 0;
+        if checksum {
+            // the checksum covers the header
+            self.add_checksum()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Key lookup cache of a yason.
+
+use crate::yason::{LazyValue, Object, Value, Yason, YasonResult};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Objects with fewer keys are looked up by binary search, which is cheaper than hashing the key.
+const MIN_INDEXED_KEYS: usize = 8;
+
+/// A cache of the key tables of the objects of a yason, which speeds up repeated navigation of the
+/// same document, e.g. evaluating several path expressions against every row.
+///
+/// The first lookup of a key in an object decodes all of its keys into a hash table, so that the
+/// following lookups in that object, by this index or by [`PathExpression::query_indexed`], skip
+/// the binary search of the key-offset table. Objects with few keys are not cached. Objects which
+/// do not belong to the indexed yason are looked up as usual.
+///
+/// The cache grows with the objects visited and is kept until the index is dropped or cleared. An
+/// index is `Send` but not `Sync`, it is meant to be created per document and thread.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use yason::{PathExpression, YasonBuf};
+///
+/// let yason = YasonBuf::parse(r#"{"a": 1, "b": 2, "c": 3, "d": 4, "e": 5, "f": 6, "g": 7, "h": {"x": 8}}"#).unwrap();
+/// let index = yason.index();
+/// for (path, expected) in [("$.a", "1"), ("$.h.x", "8"), ("$.g", "7")] {
+///     let path = PathExpression::from_str(path).unwrap();
+///     let mut result = String::new();
+///     path.query_indexed(&index, false, None, None).unwrap().format_to(false, &mut result).unwrap();
+///     assert_eq!(result, expected);
+/// }
+/// assert_eq!(index.cached_objects(), 1);
+/// ```
+///
+/// [`PathExpression::query_indexed`]: crate::PathExpression::query_indexed
+pub struct YasonIndex<'a> {
+    yason: &'a Yason,
+    // Key tables by the address of the object, the value positions are relative to the object.
+    objects: RefCell<HashMap<usize, HashMap<&'a str, usize>>>,
+}
+
+impl<'a> YasonIndex<'a> {
+    #[inline]
+    fn new(yason: &'a Yason) -> Self {
+        Self {
+            yason,
+            objects: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the indexed yason.
+    #[inline]
+    pub fn yason(&self) -> &'a Yason {
+        self.yason
+    }
+
+    /// Returns the number of objects whose key tables are cached.
+    #[inline]
+    pub fn cached_objects(&self) -> usize {
+        self.objects.borrow().len()
+    }
+
+    /// Drops the cached key tables.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.objects.get_mut().clear();
+    }
+
+    /// Returns the value corresponding to the key in the object like [`Object::get`], using the
+    /// cached key table of the object if it belongs to the indexed yason.
+    #[inline]
+    pub fn get<T: AsRef<str>>(&self, object: &Object<'a>, key: T) -> YasonResult<Option<Value<'a>>> {
+        match self.lazy_get(object, key.as_ref())? {
+            Some(value) => Ok(Some(value.value()?)),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn lazy_get(&self, object: &Object<'a>, key: &str) -> YasonResult<Option<LazyValue<'a, false>>> {
+        let address = object.yason().as_bytes().as_ptr() as usize;
+        if !self.contains(address) || object.len()? < MIN_INDEXED_KEYS {
+            return object.lazy_get(key);
+        }
+
+        let mut objects = self.objects.borrow_mut();
+        let keys = match objects.get(&address) {
+            Some(keys) => keys,
+            None => {
+                let keys = object.key_table()?;
+                objects.entry(address).or_insert(keys)
+            }
+        };
+        match keys.get(key) {
+            Some(value_pos) => Ok(Some(object.lazy_value_at(*value_pos)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns whether the address is inside the bytes of the indexed yason.
+    #[inline]
+    fn contains(&self, address: usize) -> bool {
+        let bytes = self.yason.as_bytes();
+        let start = bytes.as_ptr() as usize;
+        address >= start && address < start + bytes.len()
+    }
+}
+
+impl fmt::Debug for YasonIndex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("YasonIndex")
+            .field("cached_objects", &self.cached_objects())
+            .finish()
+    }
+}
+
+impl Yason {
+    /// Creates a [`YasonIndex`] of the yason, which caches the key tables of its objects to speed
+    /// up repeated lookups and path queries.
+    #[inline]
+    pub fn index(&self) -> YasonIndex<'_> {
+        YasonIndex::new(self)
+    }
+}
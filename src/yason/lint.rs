@@ -0,0 +1,460 @@
+//! Strict structural checks of yason.
+
+use crate::binary::{
+    ARRAY_SIZE, CHECKSUM_SIZE, COMPACT_KEY_OFFSET_SIZE, COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, DATE_SIZE,
+    ELEMENT_COUNT_SIZE, FLAG_CHECKSUM, FORMAT_VERSION, HEADER_MAGIC, HEADER_SIZE, INTERVAL_DT_SIZE, INTERVAL_YM_SIZE,
+    KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, LEGACY_FORMAT_VERSION, NUMBER_LENGTH_SIZE, OBJECT_SIZE, SHORT_DATE_SIZE,
+    TIMESTAMP_SIZE, TIME_SIZE, VALUE_ENTRY_SIZE,
+};
+use crate::limits::MAX_NESTED_DEPTH;
+use crate::util::{decode_varint, key_cmp, read_fixed, varint_size};
+use crate::yason::Yason;
+use crate::DataType;
+use decimal_rs::MAX_BINARY_SIZE;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// Kinds of structural problems found by [`Yason::lint`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LintKind {
+    /// Bytes inside a container which belong to no value.
+    UnusedBytes(usize),
+    /// A data length encoded in more bytes than needed.
+    OverlongVarint,
+    InvalidDataType(u8),
+    NegativeSize,
+    /// A field which ends past its container or the buffer.
+    OutOfBounds,
+    /// A key offset pointing into the key-offset table, or a value offset pointing into the
+    /// value entries.
+    BackwardOffset,
+    /// Values of a container which share bytes.
+    Overlap(usize),
+    UnsortedKeys,
+    DuplicateKey,
+    InvalidUtf8,
+    InvalidBool,
+    InvalidNumberLength,
+    /// The type of an outlined value differs from its value entry.
+    TypeMismatch,
+    NestedTooDeeply,
+    UnsupportedVersion(u8),
+    UnknownFlags(u8),
+    MissingChecksum,
+    ChecksumMismatch,
+    TrailingBytes(usize),
+}
+
+impl LintKind {
+    /// Returns true if the problem makes the yason invalid, false if it is only a warning about
+    /// bytes which are readable but not what the builders write.
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        !matches!(self, LintKind::UnusedBytes(_) | LintKind::OverlongVarint)
+    }
+}
+
+impl Display for LintKind {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LintKind::UnusedBytes(len) => write!(f, "{} unused bytes", len),
+            LintKind::OverlongVarint => write!(f, "overlong data length"),
+            LintKind::InvalidDataType(e) => write!(f, "invalid data type value '{}'", e),
+            LintKind::NegativeSize => write!(f, "negative size"),
+            LintKind::OutOfBounds => write!(f, "out of bounds"),
+            LintKind::BackwardOffset => write!(f, "offset points backwards into the header"),
+            LintKind::Overlap(len) => write!(f, "values overlap by {} bytes", len),
+            LintKind::UnsortedKeys => write!(f, "keys are not sorted"),
+            LintKind::DuplicateKey => write!(f, "duplicate key"),
+            LintKind::InvalidUtf8 => write!(f, "invalid UTF-8"),
+            LintKind::InvalidBool => write!(f, "invalid bool value"),
+            LintKind::InvalidNumberLength => write!(f, "invalid number length"),
+            LintKind::TypeMismatch => write!(f, "value type differs from its entry"),
+            LintKind::NestedTooDeeply => write!(f, "nested too deeply"),
+            LintKind::UnsupportedVersion(e) => write!(f, "unsupported format version {}", e),
+            LintKind::UnknownFlags(e) => write!(f, "unknown format flags {:#04x}", e),
+            LintKind::MissingChecksum => write!(f, "missing checksum"),
+            LintKind::ChecksumMismatch => write!(f, "checksum mismatch"),
+            LintKind::TrailingBytes(len) => write!(f, "{} trailing bytes after the value", len),
+        }
+    }
+}
+
+/// A structural problem found by [`Yason::lint`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintIssue {
+    pos: usize,
+    kind: LintKind,
+}
+
+impl LintIssue {
+    /// Returns the position of the field with the problem.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    pub fn kind(&self) -> &LintKind {
+        &self.kind
+    }
+
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        self.kind.is_error()
+    }
+}
+
+impl Display for LintIssue {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let severity = if self.is_error() { "error" } else { "warning" };
+        write!(f, "{:06x}: {}: {}", self.pos, severity, self.kind)
+    }
+}
+
+impl Yason {
+    /// Walks the whole buffer and returns every structural problem found, ordered by position.
+    ///
+    /// Unlike [`Yason::validate`], this does not stop at the first problem: a value which cannot
+    /// be read is skipped and its siblings are still checked. Besides errors, warnings are
+    /// reported for bytes which are readable but not what the builders write, such as unused gaps
+    /// and overlong data lengths. Returns an empty `Vec` if the yason is written canonically.
+    #[inline]
+    pub fn lint(&self) -> Vec<LintIssue> {
+        let mut linter = Linter {
+            bytes: &self.bytes,
+            issues: Vec::new(),
+        };
+        linter.lint_document();
+        linter.issues.sort_by_key(|issue| issue.pos);
+        linter.issues
+    }
+}
+
+struct Linter<'a> {
+    bytes: &'a [u8],
+    issues: Vec<LintIssue>,
+}
+
+impl Linter<'_> {
+    #[inline]
+    fn report(&mut self, pos: usize, kind: LintKind) {
+        self.issues.push(LintIssue { pos, kind })
+    }
+
+    #[inline]
+    fn u8(&self, pos: usize) -> Option<u8> {
+        self.bytes.get(pos).copied()
+    }
+
+    #[inline]
+    fn u16(&self, pos: usize) -> Option<u16> {
+        read_fixed(self.bytes, pos).map(u16::from_le_bytes)
+    }
+
+    #[inline]
+    fn u32(&self, pos: usize) -> Option<u32> {
+        read_fixed(self.bytes, pos).map(u32::from_le_bytes)
+    }
+
+    fn lint_document(&mut self) {
+        let mut start = 0;
+        let mut flags = 0;
+        if self.bytes.starts_with(&HEADER_MAGIC) {
+            start = HEADER_SIZE;
+            match (self.u8(HEADER_MAGIC.len()), self.u8(HEADER_MAGIC.len() + 1)) {
+                (Some(version), Some(header_flags)) => {
+                    if version == LEGACY_FORMAT_VERSION || version > FORMAT_VERSION {
+                        self.report(HEADER_MAGIC.len(), LintKind::UnsupportedVersion(version));
+                    }
+                    if header_flags & !FLAG_CHECKSUM != 0 {
+                        self.report(HEADER_MAGIC.len() + 1, LintKind::UnknownFlags(header_flags));
+                    }
+                    flags = header_flags;
+                }
+                _ => {
+                    self.report(0, LintKind::OutOfBounds);
+                    return;
+                }
+            }
+        }
+
+        let end = match self.lint_value(start, self.bytes.len(), 0) {
+            Some(end) => end,
+            None => return,
+        };
+        let len = self.bytes.len();
+        if end + CHECKSUM_SIZE == len {
+            if self.u32(end) != Some(crc32c::crc32c(&self.bytes[..end])) {
+                self.report(end, LintKind::ChecksumMismatch);
+            }
+        } else {
+            if flags & FLAG_CHECKSUM != 0 {
+                self.report(end, LintKind::MissingChecksum);
+            }
+            if end < len {
+                self.report(end, LintKind::TrailingBytes(len - end));
+            }
+        }
+    }
+
+    /// Checks the value starting at `pos` which must end before `limit`, returns the position after
+    /// it, or `None` if its extent cannot be read.
+    fn lint_value(&mut self, pos: usize, limit: usize, depth: usize) -> Option<usize> {
+        let data_type = match self.u8(pos) {
+            Some(data_type) if pos < limit => data_type,
+            _ => {
+                self.report(pos, LintKind::OutOfBounds);
+                return None;
+            }
+        };
+        let data_type = match DataType::try_from(data_type) {
+            Ok(data_type) => data_type,
+            Err(_) => {
+                self.report(pos, LintKind::InvalidDataType(data_type));
+                return None;
+            }
+        };
+        let value_pos = pos + DATA_TYPE_SIZE;
+        let end = match data_type {
+            DataType::Object | DataType::Array => {
+                let size = match self.u32(value_pos) {
+                    Some(size) => size as i32,
+                    None => {
+                        self.report(value_pos, LintKind::OutOfBounds);
+                        return None;
+                    }
+                };
+                if size < 0 {
+                    self.report(value_pos, LintKind::NegativeSize);
+                    return None;
+                }
+                let end = value_pos + OBJECT_SIZE + size as usize;
+                if end > limit {
+                    self.report(value_pos, LintKind::OutOfBounds);
+                    return None;
+                }
+                if depth >= MAX_NESTED_DEPTH {
+                    self.report(pos, LintKind::NestedTooDeeply);
+                } else if data_type == DataType::Object {
+                    self.lint_object(pos, end, depth);
+                } else {
+                    self.lint_array(pos, end, depth);
+                }
+                end
+            }
+            DataType::String | DataType::Binary => {
+                let (length, length_size) = match self.u8(value_pos).map(|_| decode_varint(self.bytes, value_pos)) {
+                    Some(Ok(res)) => res,
+                    _ => {
+                        self.report(value_pos, LintKind::OutOfBounds);
+                        return None;
+                    }
+                };
+                if length_size > varint_size(length) {
+                    self.report(value_pos, LintKind::OverlongVarint);
+                }
+                let string_pos = value_pos + length_size;
+                let end = string_pos + length as usize;
+                if end > limit {
+                    self.report(value_pos, LintKind::OutOfBounds);
+                    return None;
+                }
+                if data_type == DataType::String && std::str::from_utf8(&self.bytes[string_pos..end]).is_err() {
+                    self.report(string_pos, LintKind::InvalidUtf8);
+                }
+                end
+            }
+            DataType::Timestamp
+            | DataType::Date
+            | DataType::ShortDate
+            | DataType::Time
+            | DataType::IntervalYearMonth
+            | DataType::IntervalDayTime => {
+                let size = match data_type {
+                    DataType::Timestamp => TIMESTAMP_SIZE,
+                    DataType::Date => DATE_SIZE,
+                    DataType::ShortDate => SHORT_DATE_SIZE,
+                    DataType::Time => TIME_SIZE,
+                    DataType::IntervalYearMonth => INTERVAL_YM_SIZE,
+                    _ => INTERVAL_DT_SIZE,
+                };
+                let end = value_pos + size;
+                if end > limit {
+                    self.report(value_pos, LintKind::OutOfBounds);
+                    return None;
+                }
+                end
+            }
+            DataType::Number => {
+                let length = match self.u8(value_pos) {
+                    Some(length) => length as usize,
+                    None => {
+                        self.report(value_pos, LintKind::OutOfBounds);
+                        return None;
+                    }
+                };
+                let end = value_pos + NUMBER_LENGTH_SIZE + length;
+                if end > limit {
+                    self.report(value_pos, LintKind::OutOfBounds);
+                    return None;
+                }
+                if length == 0 || length > MAX_BINARY_SIZE {
+                    self.report(value_pos, LintKind::InvalidNumberLength);
+                }
+                end
+            }
+            DataType::Bool => {
+                let end = value_pos + 1;
+                match self.u8(value_pos) {
+                    Some(_) if end > limit => {
+                        self.report(value_pos, LintKind::OutOfBounds);
+                        return None;
+                    }
+                    Some(value) if value > 1 => self.report(value_pos, LintKind::InvalidBool),
+                    Some(_) => {}
+                    None => {
+                        self.report(value_pos, LintKind::OutOfBounds);
+                        return None;
+                    }
+                }
+                end
+            }
+            DataType::Null => value_pos,
+        };
+        Some(end)
+    }
+
+    fn lint_object(&mut self, pos: usize, end: usize, depth: usize) {
+        let start_pos = pos + DATA_TYPE_SIZE + OBJECT_SIZE;
+        let count = match self.u16(start_pos) {
+            Some(count) if start_pos + ELEMENT_COUNT_SIZE <= end => count as usize,
+            _ => {
+                self.report(start_pos, LintKind::OutOfBounds);
+                return;
+            }
+        };
+        let compact = self.u8(pos) == Some(COMPACT_OBJECT_TYPE);
+        let offset_size = if compact {
+            COMPACT_KEY_OFFSET_SIZE
+        } else {
+            KEY_OFFSET_SIZE
+        };
+        let header_end = start_pos + ELEMENT_COUNT_SIZE + count * offset_size;
+        if header_end > end {
+            self.report(start_pos, LintKind::OutOfBounds);
+            return;
+        }
+
+        let mut spans = Vec::with_capacity(count);
+        let mut prev_key: Option<&str> = None;
+        for i in 0..count {
+            let offset_pos = start_pos + ELEMENT_COUNT_SIZE + i * offset_size;
+            let key_offset = if compact {
+                self.u16(offset_pos).map(u32::from)
+            } else {
+                self.u32(offset_pos)
+            };
+            let key_pos = start_pos + key_offset.unwrap_or_default() as usize;
+            if key_pos < header_end {
+                self.report(offset_pos, LintKind::BackwardOffset);
+                continue;
+            }
+            let key_end = match self.u16(key_pos) {
+                Some(len) if key_pos + KEY_LENGTH_SIZE + len as usize <= end => {
+                    key_pos + KEY_LENGTH_SIZE + len as usize
+                }
+                _ => {
+                    self.report(offset_pos, LintKind::OutOfBounds);
+                    continue;
+                }
+            };
+
+            match std::str::from_utf8(&self.bytes[key_pos + KEY_LENGTH_SIZE..key_end]) {
+                Ok(key) => {
+                    if let Some(prev_key) = prev_key {
+                        match key_cmp(prev_key, key) {
+                            Ordering::Less => {}
+                            Ordering::Equal => self.report(offset_pos, LintKind::DuplicateKey),
+                            Ordering::Greater => self.report(offset_pos, LintKind::UnsortedKeys),
+                        }
+                    }
+                    prev_key = Some(key);
+                }
+                Err(_) => self.report(key_pos + KEY_LENGTH_SIZE, LintKind::InvalidUtf8),
+            }
+
+            match self.lint_value(key_end, end, depth + 1) {
+                Some(value_end) => spans.push((key_pos, value_end)),
+                None => spans.push((key_pos, key_end)),
+            }
+        }
+        self.lint_spans(spans, header_end, end);
+    }
+
+    fn lint_array(&mut self, pos: usize, end: usize, depth: usize) {
+        let start_pos = pos + DATA_TYPE_SIZE + ARRAY_SIZE;
+        let count = match self.u16(start_pos) {
+            Some(count) if start_pos + ELEMENT_COUNT_SIZE <= end => count as usize,
+            _ => {
+                self.report(start_pos, LintKind::OutOfBounds);
+                return;
+            }
+        };
+        let header_end = start_pos + ELEMENT_COUNT_SIZE + count * VALUE_ENTRY_SIZE;
+        if header_end > end {
+            self.report(start_pos, LintKind::OutOfBounds);
+            return;
+        }
+
+        let mut spans = Vec::with_capacity(count);
+        for i in 0..count {
+            let entry_pos = start_pos + ELEMENT_COUNT_SIZE + i * VALUE_ENTRY_SIZE;
+            let data_type = self.bytes[entry_pos];
+            let inlined = self.u32(entry_pos + DATA_TYPE_SIZE).unwrap_or_default();
+            match DataType::try_from(data_type) {
+                Err(_) => self.report(entry_pos, LintKind::InvalidDataType(data_type)),
+                Ok(DataType::Bool) if inlined > 1 => self.report(entry_pos + DATA_TYPE_SIZE, LintKind::InvalidBool),
+                Ok(DataType::Bool) | Ok(DataType::Null) => {}
+                Ok(data_type) => {
+                    let value_pos = start_pos + inlined as usize;
+                    if value_pos < header_end {
+                        self.report(entry_pos + DATA_TYPE_SIZE, LintKind::BackwardOffset);
+                        continue;
+                    }
+                    // entries may share one outlined value, it is checked once
+                    if spans.iter().any(|&(pos, _)| pos == value_pos) {
+                        continue;
+                    }
+                    let actual = self.u8(value_pos).map(DataType::try_from);
+                    if actual.map_or(false, |t| !matches!(t, Ok(t) if t == data_type)) && value_pos < end {
+                        self.report(entry_pos, LintKind::TypeMismatch);
+                    }
+                    if let Some(value_end) = self.lint_value(value_pos, end, depth + 1) {
+                        spans.push((value_pos, value_end));
+                    }
+                }
+            }
+        }
+        self.lint_spans(spans, header_end, end);
+    }
+
+    /// Reports the gaps and overlaps between the values of a container.
+    fn lint_spans(&mut self, mut spans: Vec<(usize, usize)>, header_end: usize, end: usize) {
+        spans.sort_unstable();
+        let mut next = header_end;
+        for (start, span_end) in spans {
+            match start.cmp(&next) {
+                Ordering::Greater => self.report(next, LintKind::UnusedBytes(start - next)),
+                Ordering::Less => self.report(start, LintKind::Overlap(next - start)),
+                Ordering::Equal => {}
+            }
+            next = next.max(span_end);
+        }
+        if next < end {
+            self.report(next, LintKind::UnusedBytes(end - next));
+        }
+    }
+}
@@ -1,25 +1,60 @@
 //! Yason manipulation.
 
 mod array;
+mod canonical;
+mod checksum;
+mod chunk;
+mod contain;
+mod dedup;
+mod dump;
+mod entry;
+mod header;
+mod index;
+mod lint;
 mod object;
-
-pub use crate::yason::array::{Array, ArrayIter};
-pub use crate::yason::object::{KeyIter, Object, ObjectIter, ValueIter};
-
-use crate::binary::{ARRAY_SIZE, DATA_TYPE_SIZE, NUMBER_LENGTH_SIZE, OBJECT_SIZE};
-use crate::format::{CompactFormatter, FormatResult, Formatter, LazyFormat, PrettyFormatter};
-use crate::util::decode_varint;
-use crate::{BuildError, DataType, Number, Scalar};
+mod repair;
+mod update;
+mod validate;
+
+pub(crate) use crate::yason::array::LazyArrayIter;
+pub use crate::yason::array::{Array, ArrayElement, ArrayIter, TypedArrayIter};
+pub(crate) use crate::yason::checksum::push_checksum;
+pub use crate::yason::chunk::{ArrayChunk, ArrayChunks};
+pub use crate::yason::entry::EntryInfo;
+pub use crate::yason::index::YasonIndex;
+pub use crate::yason::lint::{LintIssue, LintKind};
+pub(crate) use crate::yason::object::LazyObjectValueIter;
+pub use crate::yason::object::{InsertionOrderIter, KeyIter, Object, ObjectIter, ValueIter};
+pub use crate::yason::repair::RepairReport;
+
+use crate::binary::{
+    ARRAY_SIZE, BOOL_SIZE, COMPACT_KEY_OFFSET_SIZE, COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, DATE_SIZE, INTERVAL_DT_SIZE,
+    INTERVAL_YM_SIZE, KEY_OFFSET_SIZE, NUMBER_LENGTH_SIZE, OBJECT_SIZE, SHORT_DATE_SIZE, TIMESTAMP_SIZE, TIME_SIZE,
+};
+use crate::builder::BuildResult;
+use crate::format::{
+    format_compact_scalar, BinaryFormat, BinaryFormatter, BudgetFormatter, CompactFormatter, EscapeFormatter,
+    EscapeProfile, FormatResult, Formatter, InsertionOrderFormatter, LazyFormat, PrettyFormatter, TruncateOptions,
+    TruncatedFormatter,
+};
+use crate::num;
+use crate::util::{decode_varint, read_fixed};
+use crate::{
+    BinaryCollation, BuildError, Collation, DataType, Date, DtInterval, Number, Scalar, ShortDate, StepBudget, Time,
+    Timestamp, YmInterval,
+};
 use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::collections::TryReserveError;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Display;
-use std::mem::size_of;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
 
 /// Possible errors that can arise during accessing.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum YasonError {
     IndexOutOfBounds { len: usize, index: usize },
     UnexpectedType { expected: DataType, actual: DataType },
@@ -27,6 +62,17 @@ pub enum YasonError {
     MultiValuesWithoutWrapper,
     TryReserveError(TryReserveError),
     InvalidPathExpression,
+    EmptyQueryResult,
+    PathTypeMismatch,
+    Cancelled,
+    InvalidFormat(&'static str),
+    ChecksumMismatch { expected: u32, actual: u32 },
+    InvalidInteger(Number),
+    UnboundVariable(String),
+    InvalidVariable(String),
+    DuplicateKey(String),
+    KeyTooLong(usize),
+    ItemMethodNotSupported,
 }
 
 impl fmt::Display for YasonError {
@@ -45,6 +91,25 @@ impl fmt::Display for YasonError {
             }
             YasonError::TryReserveError(e) => write!(f, "{}", e),
             YasonError::InvalidPathExpression => write!(f, "invalid path expression"),
+            YasonError::EmptyQueryResult => write!(f, "path expression returns no result"),
+            YasonError::PathTypeMismatch => write!(f, "path step is applied to a value of unexpected type"),
+            YasonError::Cancelled => write!(f, "operation cancelled because the step budget is exhausted"),
+            YasonError::InvalidFormat(e) => write!(f, "invalid yason: {}", e),
+            YasonError::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "checksum mismatch, expect {:#010x}, but actual {:#010x}",
+                    expected, actual
+                )
+            }
+            YasonError::InvalidInteger(e) => write!(f, "number '{}' is not a 64-bit integer", e),
+            YasonError::UnboundVariable(name) => write!(f, "path variable '${}' is not bound", name),
+            YasonError::InvalidVariable(name) => write!(f, "path variable '${}' is not a valid array index", name),
+            YasonError::DuplicateKey(key) => write!(f, "duplicate key '{}'", key),
+            YasonError::KeyTooLong(len) => write!(f, "key too long, length is {}", len),
+            YasonError::ItemMethodNotSupported => {
+                write!(f, "item methods are not supported when selecting a single value")
+            }
         }
     }
 }
@@ -64,7 +129,7 @@ impl Error for YasonError {}
 pub type YasonResult<T> = std::result::Result<T, YasonError>;
 
 /// An owned `Yason` value, backed by a buffer of bytes in yason binary format.
-/// This can be created from a Vec<u8>.
+/// This can be created from a `Vec<u8>`.
 #[derive(Debug, Clone)]
 #[repr(transparent)]
 pub struct YasonBuf {
@@ -83,15 +148,59 @@ impl YasonBuf {
         YasonBuf { bytes }
     }
 
+    /// Creates a `YasonBuf` holding a null value, with at least the specified capacity reserved.
+    ///
+    /// The reserved buffer can be taken by [`YasonBuf::into_bytes`] and filled by a builder such as
+    /// [`crate::ObjectBuilder::try_new_in`] without reallocation.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> BuildResult<Self> {
+        let mut bytes = Vec::new();
+        bytes.try_reserve(capacity.max(DATA_TYPE_SIZE))?;
+        Scalar::null_with_vec(&mut bytes)?;
+        Ok(YasonBuf { bytes })
+    }
+
     #[inline]
     pub fn clone_from_yason(&mut self, yason: &Yason) {
         self.bytes.clear();
         self.bytes.extend_from_slice(yason.as_bytes())
     }
+
+    /// Converts the `YasonBuf` into its underlying bytes, reclaiming the allocation.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the capacity of the underlying buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Shrinks the capacity of the underlying buffer as much as possible.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.bytes.shrink_to_fit()
+    }
+
+    /// Returns a mutable reference to the underlying bytes.
+    ///
+    /// # Safety
+    ///
+    /// Callers should guarantee the bytes are still a valid `YASON` when the reference is dropped.
+    #[inline]
+    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+        &mut self.bytes
+    }
 }
 
 /// A slice of `Yason` value. This can be created from a [`YasonBuf`] or any type the contains
 /// valid bytes in yason binary format.
+///
+/// Reading never panics: every field is read with bounds checks, and sizes, counts or offsets
+/// pointing out of the bytes are returned as `YasonError`. Bytes from an untrusted source should
+/// still be checked with [`Yason::from_bytes`], which never panics either.
 #[derive(Debug)]
 #[repr(transparent)]
 pub struct Yason {
@@ -137,6 +246,13 @@ impl AsRef<Yason> for YasonBuf {
     }
 }
 
+impl AsRef<Yason> for Arc<YasonBuf> {
+    #[inline]
+    fn as_ref(&self) -> &Yason {
+        self
+    }
+}
+
 impl Yason {
     /// Creates a new `Yason` from the reference of `[u8]`.
     ///
@@ -162,87 +278,150 @@ impl Yason {
 
     #[inline]
     pub fn data_type(&self) -> YasonResult<DataType> {
-        let data_type = self.get(0)?;
-        DataType::try_from(data_type).map_err(|_| YasonError::InvalidDataType(data_type))
+        self.read_type(self.header_len())
     }
 
     /// If `Yason` is `Object`, return its value. Returns `YasonError` otherwise.
     #[inline]
-    pub fn object(&self) -> YasonResult<Object> {
-        self.check_type(0, DataType::Object)?;
+    pub fn object(&self) -> YasonResult<Object<'_>> {
+        self.check_type(self.header_len(), DataType::Object)?;
         unsafe { self.object_unchecked() }
     }
 
     #[inline]
-    pub(crate) unsafe fn object_unchecked(&self) -> YasonResult<Object> {
+    pub(crate) unsafe fn object_unchecked(&self) -> YasonResult<Object<'_>> {
         debug_assert!(self.data_type()? == DataType::Object);
-        Ok(Object::new_unchecked(self))
+        Ok(Object::new_unchecked(self.value()?))
     }
 
     /// If `Yason` is `Array`, return its value. Returns `YasonError` otherwise.
     #[inline]
-    pub fn array(&self) -> YasonResult<Array> {
-        self.check_type(0, DataType::Array)?;
+    pub fn array(&self) -> YasonResult<Array<'_>> {
+        self.check_type(self.header_len(), DataType::Array)?;
         unsafe { self.array_unchecked() }
     }
 
     #[inline]
-    pub(crate) unsafe fn array_unchecked(&self) -> YasonResult<Array> {
+    pub(crate) unsafe fn array_unchecked(&self) -> YasonResult<Array<'_>> {
         debug_assert!(self.data_type()? == DataType::Array);
-        Ok(Array::new_unchecked(self))
+        Ok(Array::new_unchecked(self.value()?))
     }
 
     /// If `Yason` is `String`, return its value. Returns `YasonError` otherwise.
     #[inline]
     pub fn string(&self) -> YasonResult<&str> {
-        self.check_type(0, DataType::String)?;
+        self.check_type(self.header_len(), DataType::String)?;
         unsafe { self.string_unchecked() }
     }
 
     #[inline]
     pub(crate) unsafe fn string_unchecked(&self) -> YasonResult<&str> {
         debug_assert!(self.data_type()? == DataType::String);
-        self.read_string(0)
+        self.read_string(self.header_len())
     }
 
     /// If `Yason` is `Number`, return its value. Returns `YasonError` otherwise.
     #[inline]
     pub fn number(&self) -> YasonResult<Number> {
-        self.check_type(0, DataType::Number)?;
+        self.check_type(self.header_len(), DataType::Number)?;
         unsafe { self.number_unchecked() }
     }
 
     #[inline]
     pub(crate) unsafe fn number_unchecked(&self) -> YasonResult<Number> {
         debug_assert!(self.data_type()? == DataType::Number);
-        self.read_number(0)
+        self.read_number(self.header_len())
     }
 
     /// If `Yason` is `Bool`, return its value. Returns `YasonError` otherwise.
     #[inline]
     pub fn bool(&self) -> YasonResult<bool> {
-        self.check_type(0, DataType::Bool)?;
+        self.check_type(self.header_len(), DataType::Bool)?;
         unsafe { self.bool_unchecked() }
     }
 
     #[inline]
     pub(crate) unsafe fn bool_unchecked(&self) -> YasonResult<bool> {
         debug_assert!(self.data_type()? == DataType::Bool);
-        self.read_bool(0)
+        self.read_bool(self.header_len())
+    }
+
+    /// If `Yason` is `Binary`, return its bytes without copying. Returns `YasonError` otherwise.
+    #[inline]
+    pub fn binary(&self) -> YasonResult<&[u8]> {
+        self.check_type(self.header_len(), DataType::Binary)?;
+        unsafe { self.binary_unchecked() }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn binary_unchecked(&self) -> YasonResult<&[u8]> {
+        debug_assert!(self.data_type()? == DataType::Binary);
+        self.read_binary(self.header_len())
+    }
+
+    /// If `Yason` is `Timestamp`, return its value. Returns `YasonError` otherwise.
+    #[inline]
+    pub fn timestamp(&self) -> YasonResult<Timestamp> {
+        self.check_type(self.header_len(), DataType::Timestamp)?;
+        self.read_timestamp(self.header_len())
+    }
+
+    /// If `Yason` is `Date`, return its value. Returns `YasonError` otherwise.
+    #[inline]
+    pub fn date(&self) -> YasonResult<Date> {
+        self.check_type(self.header_len(), DataType::Date)?;
+        self.read_date(self.header_len())
+    }
+
+    /// If `Yason` is `ShortDate`, return its value. Returns `YasonError` otherwise.
+    #[inline]
+    pub fn short_date(&self) -> YasonResult<ShortDate> {
+        self.check_type(self.header_len(), DataType::ShortDate)?;
+        self.read_short_date(self.header_len())
+    }
+
+    /// If `Yason` is `Time`, return its value. Returns `YasonError` otherwise.
+    #[inline]
+    pub fn time(&self) -> YasonResult<Time> {
+        self.check_type(self.header_len(), DataType::Time)?;
+        self.read_time(self.header_len())
+    }
+
+    /// If `Yason` is `IntervalYearMonth`, return its number of months. Returns `YasonError` otherwise.
+    #[inline]
+    pub fn interval_year_month(&self) -> YasonResult<YmInterval> {
+        self.check_type(self.header_len(), DataType::IntervalYearMonth)?;
+        self.read_interval_year_month(self.header_len())
+    }
+
+    /// If `Yason` is `IntervalDayTime`, return its number of microseconds. Returns `YasonError`
+    /// otherwise.
+    #[inline]
+    pub fn interval_day_time(&self) -> YasonResult<DtInterval> {
+        self.check_type(self.header_len(), DataType::IntervalDayTime)?;
+        self.read_interval_day_time(self.header_len())
     }
 
     /// If `Yason` is `Null`, return true. Returns false otherwise.
     #[inline]
     pub fn is_null(&self) -> YasonResult<bool> {
-        self.is_type(0, DataType::Null as u8)
+        self.is_type(self.header_len(), DataType::Null as u8)
     }
 
     /// Formats the yason as a compact or pretty string.
     #[inline]
-    pub fn format(&self, pretty: bool) -> impl Display + '_ {
+    pub fn format(&self, pretty: bool) -> LazyFormat<'_> {
         LazyFormat::new(self, pretty)
     }
 
+    /// Formats the yason as a pretty string.
+    #[inline]
+    pub fn to_pretty_string(&self) -> FormatResult<String> {
+        let mut buf = String::new();
+        self.format_to(true, &mut buf)?;
+        Ok(buf)
+    }
+
     /// Formats the yason as a compact or pretty string to a provided buffer.
     #[inline]
     pub fn format_to<W: fmt::Write>(&self, pretty: bool, buf: &mut W) -> FormatResult<()> {
@@ -255,22 +434,319 @@ impl Yason {
         }
     }
 
+    /// Formats the yason as compact JSON like [`Yason::format_to`], with a fast path for scalars
+    /// which skips the formatter state and allocates nothing, e.g. for formatting many scalar
+    /// values selected by a query. Objects and arrays are formatted by a [`CompactFormatter`].
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let mut buf = String::new();
+    /// for input in ["1.5", r#""a\"b""#, "null"] {
+    ///     YasonBuf::parse(input).unwrap().format_compact_scalar(&mut buf).unwrap();
+    ///     buf.push(' ');
+    /// }
+    /// assert_eq!(buf, r#"1.5 "a\"b" null "#);
+    /// ```
+    #[inline]
+    pub fn format_compact_scalar<W: fmt::Write>(&self, buf: &mut W) -> FormatResult<()> {
+        format_compact_scalar(self, buf)
+    }
+
+    /// Formats the yason as compact or pretty UTF-8 text appended to a byte buffer, e.g. an output
+    /// buffer of a network protocol, see [`Formatter::format_to_vec`].
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let yason = YasonBuf::parse(r#"{"a": [1, "b"]}"#).unwrap();
+    /// let mut buf = b"data: ".to_vec();
+    /// yason.format_to_vec(false, &mut buf).unwrap();
+    /// assert_eq!(buf, br#"data: {"a":[1,"b"]}"#);
+    /// ```
+    #[inline]
+    pub fn format_to_vec(&self, pretty: bool, buf: &mut Vec<u8>) -> FormatResult<()> {
+        if pretty {
+            PrettyFormatter::new().format_to_vec(self, buf)
+        } else {
+            CompactFormatter::new().format_to_vec(self, buf)
+        }
+    }
+
+    /// Formats the yason as a compact or pretty string to a provided buffer like [`Yason::format_to`],
+    /// returning `YasonError::Cancelled` once the written values exceed the budget.
+    #[inline]
+    pub fn format_to_with_budget<W: fmt::Write>(
+        &self,
+        pretty: bool,
+        budget: &mut StepBudget,
+        buf: &mut W,
+    ) -> FormatResult<()> {
+        if pretty {
+            let mut fmt = BudgetFormatter::new(PrettyFormatter::new(), budget);
+            fmt.format(self, buf)
+        } else {
+            let mut fmt = BudgetFormatter::new(CompactFormatter::new(), budget);
+            fmt.format(self, buf)
+        }
+    }
+
+    /// Formats the yason as a compact or pretty string, eliding content beyond the given limits.
+    /// This is intended for logging and error messages.
+    #[inline]
+    pub fn format_truncated(&self, pretty: bool, options: TruncateOptions) -> LazyFormat<'_> {
+        LazyFormat::with_truncate(self, pretty, options)
+    }
+
+    /// Formats the yason as a compact or pretty string to a provided buffer, eliding content
+    /// beyond the given limits.
+    #[inline]
+    pub fn format_truncated_to<W: fmt::Write>(
+        &self,
+        pretty: bool,
+        options: TruncateOptions,
+        buf: &mut W,
+    ) -> FormatResult<()> {
+        if pretty {
+            let mut fmt = TruncatedFormatter::new(PrettyFormatter::new(), options);
+            fmt.format(self, buf)
+        } else {
+            let mut fmt = TruncatedFormatter::new(CompactFormatter::new(), options);
+            fmt.format(self, buf)
+        }
+    }
+
+    /// Formats the yason as a compact or pretty string, writing the entries of objects in insertion
+    /// order instead of key order.
+    #[inline]
+    pub fn format_insertion_order(&self, pretty: bool) -> LazyFormat<'_> {
+        LazyFormat::with_insertion_order(self, pretty)
+    }
+
+    /// Formats the yason as a compact or pretty string to a provided buffer, writing the entries of
+    /// objects in insertion order instead of key order.
+    #[inline]
+    pub fn format_insertion_order_to<W: fmt::Write>(&self, pretty: bool, buf: &mut W) -> FormatResult<()> {
+        if pretty {
+            let mut fmt = InsertionOrderFormatter::new(PrettyFormatter::new());
+            fmt.format(self, buf)
+        } else {
+            let mut fmt = InsertionOrderFormatter::new(CompactFormatter::new());
+            fmt.format(self, buf)
+        }
+    }
+
+    /// Formats the yason as a compact or pretty string, escaping strings with the given profile.
+    #[inline]
+    pub fn format_escaped(&self, pretty: bool, profile: EscapeProfile) -> LazyFormat<'_> {
+        LazyFormat::with_escape(self, pretty, profile)
+    }
+
+    /// Formats the yason as a compact or pretty string to a provided buffer, escaping strings with
+    /// the given profile.
+    #[inline]
+    pub fn format_escaped_to<W: fmt::Write>(
+        &self,
+        pretty: bool,
+        profile: EscapeProfile,
+        buf: &mut W,
+    ) -> FormatResult<()> {
+        if pretty {
+            let mut fmt = EscapeFormatter::new(PrettyFormatter::new(), profile);
+            fmt.format(self, buf)
+        } else {
+            let mut fmt = EscapeFormatter::new(CompactFormatter::new(), profile);
+            fmt.format(self, buf)
+        }
+    }
+
+    /// Formats the yason as a compact or pretty string, writing binary values with the given format.
+    ///
+    /// ```rust
+    /// use yason::{BinaryFormat, ObjectBuilder};
+    ///
+    /// let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    /// builder.push_binary("key", b"\x01\xff").unwrap();
+    /// let yason = builder.finish().unwrap();
+    /// assert_eq!(yason.format(false).to_string(), r#"{"key":"Af8="}"#);
+    /// assert_eq!(yason.format_binary(false, BinaryFormat::Hex).to_string(), r#"{"key":"01ff"}"#);
+    /// ```
+    #[inline]
+    pub fn format_binary(&self, pretty: bool, format: BinaryFormat) -> LazyFormat<'_> {
+        LazyFormat::with_binary(self, pretty, format)
+    }
+
+    /// Formats the yason as a compact or pretty string to a provided buffer, writing binary values
+    /// with the given format.
+    #[inline]
+    pub fn format_binary_to<W: fmt::Write>(&self, pretty: bool, format: BinaryFormat, buf: &mut W) -> FormatResult<()> {
+        if pretty {
+            let mut fmt = BinaryFormatter::new(PrettyFormatter::new(), format);
+            fmt.format(self, buf)
+        } else {
+            let mut fmt = BinaryFormatter::new(CompactFormatter::new(), format);
+            fmt.format(self, buf)
+        }
+    }
+
+    /// Returns the position right after the value starting at `pos`, which must point to the type
+    /// of the value. Only the size headers are read, so objects and arrays are skipped without
+    /// walking their elements.
+    #[inline]
+    pub fn skip_value(&self, pos: usize) -> YasonResult<usize> {
+        let value_pos = pos + DATA_TYPE_SIZE;
+        let end = match self.read_type(pos)? {
+            DataType::Object => value_pos + OBJECT_SIZE + self.read_size(value_pos)?,
+            DataType::Array => value_pos + ARRAY_SIZE + self.read_size(value_pos)?,
+            DataType::String | DataType::Binary => {
+                self.get(value_pos)?;
+                let (data_length, data_length_len) = decode_varint(&self.bytes, value_pos)?;
+                value_pos + data_length_len + data_length as usize
+            }
+            DataType::Number => value_pos + NUMBER_LENGTH_SIZE + self.read_u8(value_pos)? as usize,
+            DataType::Bool => value_pos + BOOL_SIZE,
+            DataType::Null => value_pos,
+            DataType::Timestamp => value_pos + TIMESTAMP_SIZE,
+            DataType::Date => value_pos + DATE_SIZE,
+            DataType::ShortDate => value_pos + SHORT_DATE_SIZE,
+            DataType::Time => value_pos + TIME_SIZE,
+            DataType::IntervalYearMonth => value_pos + INTERVAL_YM_SIZE,
+            DataType::IntervalDayTime => value_pos + INTERVAL_DT_SIZE,
+        };
+        if end > self.bytes.len() {
+            return Err(YasonError::IndexOutOfBounds {
+                len: self.bytes.len(),
+                index: end,
+            });
+        }
+        Ok(end)
+    }
+
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
         &self.bytes
     }
 
+    /// Compares two scalar yason values, see [`Value::compare_with`].
+    #[inline]
+    pub fn compare<T: AsRef<Yason>>(&self, other: T) -> YasonResult<Option<Ordering>> {
+        self.compare_with(other, &BinaryCollation)
+    }
+
+    /// Compares two scalar yason values, strings are compared by the given collation.
+    /// See [`Value::compare_with`].
+    #[inline]
+    pub fn compare_with<T: AsRef<Yason>, C: Collation>(
+        &self,
+        other: T,
+        collation: &C,
+    ) -> YasonResult<Option<Ordering>> {
+        let left = LazyValue::try_from(self)?.value()?;
+        let right = LazyValue::try_from(other.as_ref())?.value()?;
+        Ok(left.compare_with(&right, collation))
+    }
+
     /// Returns whether two Yason are equal.
+    ///
+    /// The bytes are compared first, and the structures are compared only if the bytes differ.
     #[inline]
     pub fn equals<T: AsRef<Yason>>(&self, other: T) -> YasonResult<bool> {
         let other = other.as_ref();
-        if self.bytes.len() != other.bytes.len() || self.data_type()? != other.data_type()? {
+        if self.equals_bytes(other) {
+            return Ok(true);
+        }
+        self.equals_structural(other)
+    }
+
+    /// Returns whether two Yason have identical bytes.
+    ///
+    /// Identical bytes always mean equal values, but equal values may be encoded differently,
+    /// e.g. the key-values of an object built with `key_sorted = false` are stored in insertion order.
+    #[inline]
+    pub fn equals_bytes<T: AsRef<Yason>>(&self, other: T) -> bool {
+        self.bytes == other.as_ref().bytes
+    }
+
+    /// Returns whether two Yason are equal by walking both of them, without comparing the bytes first.
+    #[inline]
+    pub fn equals_structural<T: AsRef<Yason>>(&self, other: T) -> YasonResult<bool> {
+        self.equals_structural_with_budget(other.as_ref(), &mut StepBudget::unlimited())
+    }
+
+    /// Returns whether two Yason are equal like [`Yason::equals`], returning `YasonError::Cancelled`
+    /// once the compared values exceed the budget.
+    #[inline]
+    pub fn equals_with_budget<T: AsRef<Yason>>(&self, other: T, budget: &mut StepBudget) -> YasonResult<bool> {
+        let other = other.as_ref();
+        if self.equals_bytes(other) {
+            return Ok(true);
+        }
+        self.equals_structural_with_budget(other, budget)
+    }
+
+    /// Returns whether two Yason are equal like [`Yason::equals`], or `None` if the comparison
+    /// visits more than `max_steps` values before it is decided, e.g. to cap the cost of comparing
+    /// large documents in query filters.
+    ///
+    /// Unequal documents are usually decided within a few steps, because the element counts and
+    /// the scalars of a container are compared before descending into its nested containers.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let left = YasonBuf::parse(r#"{"a": [[1, 2], [3, 4]], "b": 1}"#).unwrap();
+    /// let right = YasonBuf::parse(r#"{"a": [[1, 2], [3, 4]], "b": 2}"#).unwrap();
+    /// assert_eq!(left.equals_within(&right, 3).unwrap(), Some(false));
+    /// assert_eq!(left.equals_within(&left.to_yason_buf().unwrap(), 3).unwrap(), Some(true));
+    /// ```
+    #[inline]
+    pub fn equals_within<T: AsRef<Yason>>(&self, other: T, max_steps: usize) -> YasonResult<Option<bool>> {
+        match self.equals_with_budget(other, &mut StepBudget::new(max_steps)) {
+            Ok(equal) => Ok(Some(equal)),
+            Err(YasonError::Cancelled) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns whether two Yason are equal when arrays are compared as multisets, i.e. like
+    /// [`Yason::equals`] but ignoring the order of array elements at any depth, e.g. for test
+    /// assertions and idempotency checks where producers do not guarantee array order.
+    ///
+    /// Both yason are converted into canonical forms whose array elements are sorted, so the cost
+    /// is proportional to the size of the yason rather than the number of compared pairs.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let left = YasonBuf::parse(r#"{"tags": ["b", "a", "b"], "items": [{"id": 2}, {"id": 1}]}"#).unwrap();
+    /// let right = YasonBuf::parse(r#"{"items": [{"id": 1}, {"id": 2}], "tags": ["b", "b", "a"]}"#).unwrap();
+    /// assert!(left.equals_unordered(&right).unwrap());
+    ///
+    /// let other = YasonBuf::parse(r#"{"items": [{"id": 1}, {"id": 2}], "tags": ["a", "a", "b"]}"#).unwrap();
+    /// assert!(!left.equals_unordered(&other).unwrap());
+    /// ```
+    #[inline]
+    pub fn equals_unordered<T: AsRef<Yason>>(&self, other: T) -> YasonResult<bool> {
+        let other = other.as_ref();
+        if self.equals_bytes(other) {
+            return Ok(true);
+        }
+        if self.data_type()? != other.data_type()? {
+            return Ok(false);
+        }
+        Ok(self.unordered_form()? == other.unordered_form()?)
+    }
+
+    #[inline]
+    fn equals_structural_with_budget(&self, other: &Yason, budget: &mut StepBudget) -> YasonResult<bool> {
+        // equal values may have different sizes, e.g. when one of them is deduplicated
+        if self.data_type()? != other.data_type()? {
             return Ok(false);
         }
 
         let left = LazyValue::try_from(self)?;
         let right = LazyValue::try_from(other)?;
-        left.equals(right)
+        left.equals(right, budget)
     }
 }
 
@@ -297,6 +773,14 @@ impl Yason {
         })
     }
 
+    #[inline]
+    fn read_fixed<const N: usize>(&self, index: usize) -> YasonResult<[u8; N]> {
+        read_fixed(&self.bytes, index).ok_or(YasonError::IndexOutOfBounds {
+            len: self.bytes.len(),
+            index: index.saturating_add(N),
+        })
+    }
+
     #[inline]
     fn read_type(&self, index: usize) -> YasonResult<DataType> {
         let data_type = self.get(index)?;
@@ -305,15 +789,24 @@ impl Yason {
 
     #[inline]
     fn is_type(&self, index: usize, data_type: u8) -> YasonResult<bool> {
-        Ok(self.get(index)? == data_type)
+        let byte = self.get(index)?;
+        Ok(byte == data_type || (byte == COMPACT_OBJECT_TYPE && data_type == DataType::Object as u8))
     }
 
     #[inline]
     fn read_i32(&self, index: usize) -> YasonResult<i32> {
-        let end = index + size_of::<i32>();
-        let bytes = self.slice(index, end)?;
-        // SAFETY: The `bytes` must be valid because the `slice()` always takes 4 bytes.
-        Ok(i32::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(i32::from_le_bytes(self.read_fixed(index)?))
+    }
+
+    #[inline]
+    fn read_i64(&self, index: usize) -> YasonResult<i64> {
+        Ok(i64::from_le_bytes(self.read_fixed(index)?))
+    }
+
+    #[inline]
+    fn read_size(&self, index: usize) -> YasonResult<usize> {
+        let size = self.read_i32(index)?;
+        usize::try_from(size).map_err(|_| YasonError::InvalidFormat("negative size"))
     }
 
     #[inline]
@@ -323,30 +816,43 @@ impl Yason {
 
     #[inline]
     fn read_u16(&self, index: usize) -> YasonResult<u16> {
-        let end = index + size_of::<u16>();
-        let bytes = self.slice(index, end)?;
-        // SAFETY: The `bytes` must be valid because the `slice()` always takes 2 bytes.
-        Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(u16::from_le_bytes(self.read_fixed(index)?))
     }
 
     #[inline]
     fn read_u32(&self, index: usize) -> YasonResult<u32> {
-        let end = index + size_of::<u32>();
-        let bytes = self.slice(index, end)?;
-        // SAFETY: The `bytes` must be valid because the `slice()` always takes 4 bytes.
-        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(u32::from_le_bytes(self.read_fixed(index)?))
+    }
+
+    /// Returns the size of the entries of the key-offset table of the object at `index`.
+    #[inline]
+    fn key_offset_size(&self, index: usize) -> YasonResult<usize> {
+        match self.get(index)? {
+            COMPACT_OBJECT_TYPE => Ok(COMPACT_KEY_OFFSET_SIZE),
+            _ => Ok(KEY_OFFSET_SIZE),
+        }
     }
 
+    /// Reads a key offset at `index`, whose size is given by [`Yason::key_offset_size`].
     #[inline]
-    fn read_object(&self, index: usize) -> YasonResult<Object> {
-        let size = self.read_i32(index + DATA_TYPE_SIZE)? as usize + DATA_TYPE_SIZE + OBJECT_SIZE;
+    fn read_key_offset(&self, index: usize, size: usize) -> YasonResult<u32> {
+        if size == COMPACT_KEY_OFFSET_SIZE {
+            Ok(self.read_u16(index)? as u32)
+        } else {
+            self.read_u32(index)
+        }
+    }
+
+    #[inline]
+    fn read_object(&self, index: usize) -> YasonResult<Object<'_>> {
+        let size = self.read_size(index + DATA_TYPE_SIZE)? + DATA_TYPE_SIZE + OBJECT_SIZE;
         let yason = unsafe { Yason::new_unchecked(self.slice(index, size + index)?) };
         Ok(unsafe { Object::new_unchecked(yason) })
     }
 
     #[inline]
-    fn read_array(&self, index: usize) -> YasonResult<Array> {
-        let size = self.read_i32(index + DATA_TYPE_SIZE)? as usize + DATA_TYPE_SIZE + ARRAY_SIZE;
+    fn read_array(&self, index: usize) -> YasonResult<Array<'_>> {
+        let size = self.read_size(index + DATA_TYPE_SIZE)? + DATA_TYPE_SIZE + ARRAY_SIZE;
         let yason = unsafe { Yason::new_unchecked(self.slice(index, size + index)?) };
         Ok(unsafe { Array::new_unchecked(yason) })
     }
@@ -361,6 +867,48 @@ impl Yason {
         Ok(string)
     }
 
+    #[inline]
+    fn read_binary(&self, index: usize) -> YasonResult<&[u8]> {
+        let index = index + DATA_TYPE_SIZE;
+        let (data_length, data_length_len) = decode_varint(&self.bytes, index)?;
+        let end = index + data_length_len + data_length as usize;
+        self.slice(index + data_length_len, end)
+    }
+
+    /// Reads the length in bytes of the string at `index` from its varint header only.
+    #[inline]
+    fn read_string_len(&self, index: usize) -> YasonResult<usize> {
+        let (data_length, _) = decode_varint(&self.bytes, index + DATA_TYPE_SIZE)?;
+        Ok(data_length as usize)
+    }
+
+    /// Reads at most the first `n` chars of the string at `index`, only touching the bytes of
+    /// these chars.
+    #[inline]
+    fn read_string_prefix(&self, index: usize, n: usize) -> YasonResult<&str> {
+        let index = index + DATA_TYPE_SIZE;
+        let (data_length, data_length_len) = decode_varint(&self.bytes, index)?;
+        let start = index + data_length_len;
+        let end = start + data_length as usize;
+
+        let mut pos = start;
+        let mut chars = 0;
+        while pos < end {
+            // a byte that is not a continuation byte starts a char
+            if self.get(pos)? & 0xc0 != 0x80 {
+                if chars == n {
+                    break;
+                }
+                chars += 1;
+            }
+            pos += 1;
+        }
+
+        let bytes = self.slice(start, pos)?;
+        let string = unsafe { std::str::from_utf8_unchecked(bytes) };
+        Ok(string)
+    }
+
     #[inline]
     fn read_number(&self, index: usize) -> YasonResult<Number> {
         let index = index + DATA_TYPE_SIZE;
@@ -375,6 +923,37 @@ impl Yason {
         Ok(self.read_u8(index + DATA_TYPE_SIZE)? == 1)
     }
 
+    #[inline]
+    fn read_timestamp(&self, index: usize) -> YasonResult<Timestamp> {
+        Ok(Timestamp::from_micros(self.read_i64(index + DATA_TYPE_SIZE)?))
+    }
+
+    #[inline]
+    fn read_date(&self, index: usize) -> YasonResult<Date> {
+        Ok(Date::from_micros(self.read_i64(index + DATA_TYPE_SIZE)?))
+    }
+
+    #[inline]
+    fn read_short_date(&self, index: usize) -> YasonResult<ShortDate> {
+        Ok(ShortDate::from_days(self.read_i32(index + DATA_TYPE_SIZE)?))
+    }
+
+    #[inline]
+    fn read_time(&self, index: usize) -> YasonResult<Time> {
+        let micros = self.read_i64(index + DATA_TYPE_SIZE)?;
+        Time::from_micros(micros).ok_or(YasonError::InvalidFormat("time out of range"))
+    }
+
+    #[inline]
+    fn read_interval_year_month(&self, index: usize) -> YasonResult<YmInterval> {
+        Ok(YmInterval::from_months(self.read_i32(index + DATA_TYPE_SIZE)?))
+    }
+
+    #[inline]
+    fn read_interval_day_time(&self, index: usize) -> YasonResult<DtInterval> {
+        Ok(DtInterval::from_micros(self.read_i64(index + DATA_TYPE_SIZE)?))
+    }
+
     #[inline]
     fn check_type(&self, index: usize, expected: DataType) -> YasonResult<()> {
         if !self.is_type(index, expected as u8)? {
@@ -388,6 +967,22 @@ impl Yason {
     }
 }
 
+/// Formats the yason as a compact string.
+impl Display for Yason {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.format_to(false, f).map_err(|_| fmt::Error)
+    }
+}
+
+/// Formats the yason as a compact string.
+impl Display for YasonBuf {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_ref(), f)
+    }
+}
+
 impl PartialEq for Yason {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -413,6 +1008,13 @@ pub enum Value<'a> {
     Number(Number),
     Bool(bool),
     Null,
+    Binary(&'a [u8]),
+    Timestamp(Timestamp),
+    Date(Date),
+    ShortDate(ShortDate),
+    Time(Time),
+    IntervalYearMonth(YmInterval),
+    IntervalDayTime(DtInterval),
 }
 
 impl<'a> Value<'a> {
@@ -425,6 +1027,61 @@ impl<'a> Value<'a> {
             Value::Number(_) => DataType::Number,
             Value::Bool(_) => DataType::Bool,
             Value::Null => DataType::Null,
+            Value::Binary(_) => DataType::Binary,
+            Value::Timestamp(_) => DataType::Timestamp,
+            Value::Date(_) => DataType::Date,
+            Value::ShortDate(_) => DataType::ShortDate,
+            Value::Time(_) => DataType::Time,
+            Value::IntervalYearMonth(_) => DataType::IntervalYearMonth,
+            Value::IntervalDayTime(_) => DataType::IntervalDayTime,
+        }
+    }
+
+    /// Compares two scalar values, strings are compared by the given collation, binaries byte by
+    /// byte and temporal values chronologically.
+    ///
+    /// Returns `None` if the values have different types or either of them is an object or an array,
+    /// which are not comparable.
+    #[inline]
+    pub fn compare_with<C: Collation>(&self, other: &Value, collation: &C) -> Option<Ordering> {
+        match (self, other) {
+            (Value::String(left), Value::String(right)) => Some(collation.compare(left, right)),
+            (Value::Number(left), Value::Number(right)) => Some(num::compare(left, right)),
+            (Value::Bool(left), Value::Bool(right)) => Some(left.cmp(right)),
+            (Value::Null, Value::Null) => Some(Ordering::Equal),
+            (Value::Binary(left), Value::Binary(right)) => Some(left.cmp(right)),
+            (Value::Timestamp(left), Value::Timestamp(right)) => Some(left.cmp(right)),
+            (Value::Date(left), Value::Date(right)) => Some(left.cmp(right)),
+            (Value::ShortDate(left), Value::ShortDate(right)) => Some(left.cmp(right)),
+            (Value::Time(left), Value::Time(right)) => Some(left.cmp(right)),
+            (Value::IntervalYearMonth(left), Value::IntervalYearMonth(right)) => Some(left.cmp(right)),
+            (Value::IntervalDayTime(left), Value::IntervalDayTime(right)) => Some(left.cmp(right)),
+            _ => None,
+        }
+    }
+
+    /// Returns whether two values are equal: objects with the same keys and equal values, arrays
+    /// with equal elements in the same order, and scalars of the same type that compare equal,
+    /// e.g. numbers regardless of their scale.
+    ///
+    /// ```rust
+    /// use yason::{Number, Value, YasonBuf};
+    ///
+    /// let left = YasonBuf::parse(r#"{"a": [1, 2.0]}"#).unwrap();
+    /// let right = YasonBuf::parse(r#"{"a": [1.00, 2]}"#).unwrap();
+    /// let left = Value::try_from(left.as_ref()).unwrap();
+    /// assert!(left.equals(&Value::try_from(right.as_ref()).unwrap()).unwrap());
+    /// assert_eq!(Value::Number(Number::from(1)), Value::Number(Number::from(1)));
+    /// assert_ne!(Value::Number(Number::from(1)), Value::String("1"));
+    /// ```
+    #[inline]
+    pub fn equals(&self, other: &Value) -> YasonResult<bool> {
+        let mut budget = StepBudget::unlimited();
+        match (self, other) {
+            (Value::Object(left), Value::Object(right)) => left.equals(right, &mut budget),
+            (Value::Array(left), Value::Array(right)) => left.equals(right, &mut budget),
+            (Value::Number(left), Value::Number(right)) => Ok(num::equals(left, right)),
+            _ => Ok(self.compare_with(other, &BinaryCollation) == Some(Ordering::Equal)),
         }
     }
 
@@ -437,6 +1094,13 @@ impl<'a> Value<'a> {
             Value::Number(num) => Ok(Scalar::number_with_vec(num, buf)?),
             Value::Bool(bool) => Ok(Scalar::bool_with_vec(*bool, buf)?),
             Value::Null => Ok(Scalar::null_with_vec(buf)?),
+            Value::Binary(bytes) => Ok(Scalar::binary_with_vec(bytes, buf)?),
+            Value::Timestamp(timestamp) => Ok(Scalar::timestamp_with_vec(*timestamp, buf)?),
+            Value::Date(date) => Ok(Scalar::date_with_vec(*date, buf)?),
+            Value::ShortDate(short_date) => Ok(Scalar::short_date_with_vec(*short_date, buf)?),
+            Value::Time(time) => Ok(Scalar::time_with_vec(*time, buf)?),
+            Value::IntervalYearMonth(interval) => Ok(Scalar::interval_year_month_with_vec(*interval, buf)?),
+            Value::IntervalDayTime(interval) => Ok(Scalar::interval_day_time_with_vec(*interval, buf)?),
         }
     }
 
@@ -461,26 +1125,74 @@ impl<'a> Value<'a> {
                 let mut fmt = CompactFormatter::new();
                 fmt.write_null(writer)
             }
+            Value::Binary(bytes) => {
+                let mut fmt = CompactFormatter::new();
+                fmt.write_binary(bytes, writer)
+            }
+            Value::Timestamp(timestamp) => {
+                let mut fmt = CompactFormatter::new();
+                fmt.write_timestamp(*timestamp, writer)
+            }
+            Value::Date(date) => {
+                let mut fmt = CompactFormatter::new();
+                fmt.write_date(*date, writer)
+            }
+            Value::ShortDate(short_date) => {
+                let mut fmt = CompactFormatter::new();
+                fmt.write_short_date(*short_date, writer)
+            }
+            Value::Time(time) => {
+                let mut fmt = CompactFormatter::new();
+                fmt.write_time(*time, writer)
+            }
+            Value::IntervalYearMonth(interval) => {
+                let mut fmt = CompactFormatter::new();
+                fmt.write_interval_year_month(*interval, writer)
+            }
+            Value::IntervalDayTime(interval) => {
+                let mut fmt = CompactFormatter::new();
+                fmt.write_interval_day_time(*interval, writer)
+            }
         }
     }
 }
 
+impl PartialEq for Value<'_> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other).expect("an error occurred when comparing values")
+    }
+}
+
 impl<'a> TryFrom<&'a Yason> for Value<'a> {
     type Error = YasonError;
 
     #[inline]
     fn try_from(yason: &'a Yason) -> Result<Self, Self::Error> {
         match yason.data_type()? {
-            DataType::Object => Ok(Value::Object(unsafe { Object::new_unchecked(yason) })),
-            DataType::Array => Ok(Value::Array(unsafe { Array::new_unchecked(yason) })),
+            DataType::Object => Ok(Value::Object(unsafe { yason.object_unchecked()? })),
+            DataType::Array => Ok(Value::Array(unsafe { yason.array_unchecked()? })),
             DataType::String => Ok(Value::String(unsafe { yason.string_unchecked()? })),
             DataType::Number => Ok(Value::Number(unsafe { yason.number_unchecked()? })),
             DataType::Bool => Ok(Value::Bool(unsafe { yason.bool_unchecked()? })),
             DataType::Null => Ok(Value::Null),
+            DataType::Binary => Ok(Value::Binary(unsafe { yason.binary_unchecked()? })),
+            DataType::Timestamp => Ok(Value::Timestamp(yason.read_timestamp(yason.header_len())?)),
+            DataType::Date => Ok(Value::Date(yason.read_date(yason.header_len())?)),
+            DataType::ShortDate => Ok(Value::ShortDate(yason.read_short_date(yason.header_len())?)),
+            DataType::Time => Ok(Value::Time(yason.read_time(yason.header_len())?)),
+            DataType::IntervalYearMonth => Ok(Value::IntervalYearMonth(
+                yason.read_interval_year_month(yason.header_len())?,
+            )),
+            DataType::IntervalDayTime => Ok(Value::IntervalDayTime(
+                yason.read_interval_day_time(yason.header_len())?,
+            )),
         }
     }
 }
 
+/// A value of a yason which is decoded on demand, created from a `Yason` by `LazyValue::try_from`.
+///
 /// IN_ARRAY: this parameter indicates whether this value is in an array and affects how data is
 /// read from value_pos (related to `Yason binary format`).
 /// Note:
@@ -492,6 +1204,14 @@ pub struct LazyValue<'a, const IN_ARRAY: bool> {
     value_pos: usize,
 }
 
+// Lazy values are evaluated by the selectors of path expressions on any thread.
+#[allow(dead_code)]
+fn assert_lazy_value_send_sync() {
+    fn send_sync<T: Send + Sync>() {}
+    send_sync::<LazyValue<'_, true>>();
+    send_sync::<LazyValue<'_, false>>();
+}
+
 impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
     #[inline]
     const fn new(yason: &'a Yason, ty: DataType, value_pos: usize) -> Self {
@@ -503,6 +1223,35 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
         self.ty
     }
 
+    /// Returns the address of the encoded value, or of its value entry if it is in an array, which
+    /// identifies the value among all the values of a document.
+    #[inline]
+    pub(crate) fn address(&self) -> usize {
+        self.yason.as_bytes().as_ptr() as usize + self.value_pos
+    }
+
+    /// Returns the addresses of the first byte and past the last byte of the encoded value. An
+    /// inlined bool or null of an array is encoded by the type and the first byte of its value
+    /// entry, which is also a valid encoding of the value.
+    #[inline]
+    pub(crate) fn span(&self) -> YasonResult<Range<usize>> {
+        let (start, end) = if IN_ARRAY {
+            match self.ty {
+                DataType::Bool => (self.value_pos, self.value_pos + DATA_TYPE_SIZE + BOOL_SIZE),
+                DataType::Null => (self.value_pos, self.value_pos + DATA_TYPE_SIZE),
+                _ => {
+                    let pos = unsafe { Array::new_unchecked(self.yason).read_value_pos(self.value_pos)? };
+                    (pos, self.yason.skip_value(pos)?)
+                }
+            }
+        } else {
+            (self.value_pos, self.yason.skip_value(self.value_pos)?)
+        };
+        let base = self.yason.as_bytes().as_ptr() as usize;
+        Ok(base + start..base + end)
+    }
+
+    /// Decodes the value, the values of an object or an array are decoded when they are read.
     #[inline]
     pub fn value(&self) -> YasonResult<Value<'a>> {
         let res = unsafe {
@@ -513,6 +1262,17 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
                 DataType::Number => Value::Number(self.number()?),
                 DataType::Bool => Value::Bool(self.bool()?),
                 DataType::Null => Value::Null,
+                DataType::Binary => Value::Binary(self.binary()?),
+                DataType::Timestamp => Value::Timestamp(self.yason.read_timestamp(self.scalar_pos()?)?),
+                DataType::Date => Value::Date(self.yason.read_date(self.scalar_pos()?)?),
+                DataType::ShortDate => Value::ShortDate(self.yason.read_short_date(self.scalar_pos()?)?),
+                DataType::Time => Value::Time(self.yason.read_time(self.scalar_pos()?)?),
+                DataType::IntervalYearMonth => {
+                    Value::IntervalYearMonth(self.yason.read_interval_year_month(self.scalar_pos()?)?)
+                }
+                DataType::IntervalDayTime => {
+                    Value::IntervalDayTime(self.yason.read_interval_day_time(self.scalar_pos()?)?)
+                }
             }
         };
 
@@ -520,7 +1280,7 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
     }
 
     #[inline]
-    pub unsafe fn object(&self) -> YasonResult<Object<'a>> {
+    pub(crate) unsafe fn object(&self) -> YasonResult<Object<'a>> {
         debug_assert!(self.ty == DataType::Object);
         if IN_ARRAY {
             Array::new_unchecked(self.yason).read_object(self.value_pos)
@@ -530,7 +1290,7 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
     }
 
     #[inline]
-    pub unsafe fn array(&self) -> YasonResult<Array<'a>> {
+    pub(crate) unsafe fn array(&self) -> YasonResult<Array<'a>> {
         debug_assert!(self.ty == DataType::Array);
         if IN_ARRAY {
             Array::new_unchecked(self.yason).read_array(self.value_pos)
@@ -540,7 +1300,7 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
     }
 
     #[inline]
-    pub unsafe fn string(&self) -> YasonResult<&'a str> {
+    pub(crate) unsafe fn string(&self) -> YasonResult<&'a str> {
         debug_assert!(self.ty == DataType::String);
         if IN_ARRAY {
             Array::new_unchecked(self.yason).read_string(self.value_pos)
@@ -550,7 +1310,7 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
     }
 
     #[inline]
-    pub unsafe fn number(&self) -> YasonResult<Number> {
+    pub(crate) unsafe fn number(&self) -> YasonResult<Number> {
         debug_assert!(self.ty == DataType::Number);
         if IN_ARRAY {
             Array::new_unchecked(self.yason).read_number(self.value_pos)
@@ -560,7 +1320,7 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
     }
 
     #[inline]
-    pub unsafe fn bool(&self) -> YasonResult<bool> {
+    pub(crate) unsafe fn bool(&self) -> YasonResult<bool> {
         debug_assert!(self.ty == DataType::Bool);
         if IN_ARRAY {
             Array::new_unchecked(self.yason).read_bool(self.value_pos)
@@ -569,19 +1329,84 @@ impl<'a, const IN_ARRAY: bool> LazyValue<'a, IN_ARRAY> {
         }
     }
 
+    /// Returns the position of the encoded scalar, following the value entry if it is in an array.
+    #[inline]
+    unsafe fn scalar_pos(&self) -> YasonResult<usize> {
+        if IN_ARRAY {
+            Array::new_unchecked(self.yason).read_value_pos(self.value_pos)
+        } else {
+            Ok(self.value_pos)
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn binary(&self) -> YasonResult<&'a [u8]> {
+        debug_assert!(self.ty == DataType::Binary);
+        if IN_ARRAY {
+            Array::new_unchecked(self.yason).read_binary(self.value_pos)
+        } else {
+            self.yason.read_binary(self.value_pos)
+        }
+    }
+
+    /// Returns whether two values are equal like [`Value::equals`], without decoding the values
+    /// of containers, returning `YasonError::Cancelled` once the compared values exceed the budget.
+    ///
+    /// ```rust
+    /// use yason::{LazyValue, StepBudget, YasonBuf};
+    ///
+    /// let left = YasonBuf::parse(r#"{"a": [1, 2.0], "b": null}"#).unwrap();
+    /// let right = YasonBuf::parse(r#"{"b": null, "a": [1, 2]}"#).unwrap();
+    /// let left = LazyValue::try_from(left.as_ref()).unwrap();
+    /// let right = LazyValue::try_from(right.as_ref()).unwrap();
+    /// assert!(left.equals(right, &mut StepBudget::unlimited()).unwrap());
+    /// ```
     #[inline]
-    pub fn equals(&self, other: LazyValue<IN_ARRAY>) -> YasonResult<bool> {
-        if self.data_type() != other.data_type() || self.yason.bytes.len() != other.yason.bytes.len() {
+    pub fn equals(&self, other: LazyValue<IN_ARRAY>, budget: &mut StepBudget) -> YasonResult<bool> {
+        budget.step()?;
+        if !self.equals_shallow(&other)? {
+            return Ok(false);
+        }
+        self.equals_children(&other, budget)
+    }
+
+    /// Compares the data types, the scalars, and the element counts of objects and arrays, without
+    /// descending into the containers.
+    #[inline]
+    fn equals_shallow(&self, other: &LazyValue<IN_ARRAY>) -> YasonResult<bool> {
+        if self.data_type() != other.data_type() {
             return Ok(false);
         }
 
         match self.data_type() {
-            DataType::Object => unsafe { self.object()?.equals(other.object()?) },
-            DataType::Array => unsafe { self.array()?.equals(other.array()?) },
+            DataType::Object => unsafe { Ok(self.object()?.len()? == other.object()?.len()?) },
+            DataType::Array => unsafe { Ok(self.array()?.len()? == other.array()?.len()?) },
             DataType::String => unsafe { Ok(self.string()?.eq(other.string()?)) },
-            DataType::Number => unsafe { Ok(self.number()?.eq(&other.number()?)) },
+            DataType::Number => unsafe { Ok(num::equals(&self.number()?, &other.number()?)) },
             DataType::Bool => unsafe { Ok(self.bool()?.eq(&other.bool()?)) },
             DataType::Null => Ok(true),
+            DataType::Binary => unsafe { Ok(self.binary()?.eq(other.binary()?)) },
+            DataType::Timestamp
+            | DataType::Date
+            | DataType::ShortDate
+            | DataType::Time
+            | DataType::IntervalYearMonth
+            | DataType::IntervalDayTime => {
+                let ordering = self.value()?.compare_with(&other.value()?, &BinaryCollation);
+                Ok(ordering == Some(Ordering::Equal))
+            }
+        }
+    }
+
+    /// Compares the elements of two objects or arrays which are equal by [`equals_shallow`].
+    ///
+    /// [`equals_shallow`]: LazyValue::equals_shallow
+    #[inline]
+    fn equals_children(&self, other: &LazyValue<IN_ARRAY>, budget: &mut StepBudget) -> YasonResult<bool> {
+        match self.data_type() {
+            DataType::Object => unsafe { self.object()?.equals(other.object()?, budget) },
+            DataType::Array => unsafe { self.array()?.equals(other.array()?, budget) },
+            _ => Ok(true),
         }
     }
 }
@@ -591,6 +1416,7 @@ impl<'a> TryFrom<&'a Yason> for LazyValue<'a, false> {
 
     #[inline]
     fn try_from(yason: &'a Yason) -> Result<Self, Self::Error> {
+        let yason = yason.value()?;
         let data_type = yason.data_type()?;
         Ok(Self {
             yason,
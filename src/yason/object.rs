@@ -1,9 +1,13 @@
 //! Object manipulation.
 
-use crate::binary::{DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_LENGTH_SIZE, KEY_OFFSET_SIZE, OBJECT_SIZE};
+use crate::binary::{COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_LENGTH_SIZE, OBJECT_SIZE};
+use crate::util::{key_cmp, try_collect};
 use crate::yason::array::Array;
-use crate::yason::{LazyValue, Value, Yason, YasonResult};
-use crate::{DataType, Number};
+use crate::yason::{EntryInfo, LazyValue, Value, Yason, YasonError, YasonResult};
+use crate::{Collation, DataType, Date, DtInterval, Number, ShortDate, StepBudget, Time, Timestamp, YmInterval};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::iter::FusedIterator;
 
 /// An object in yason binary format.
 #[derive(Clone, Debug)]
@@ -22,6 +26,20 @@ impl<'a> Object<'a> {
         LazyObjectIter::try_new(self.0)
     }
 
+    /// Gets an iterator over the entries of the object in the order they were inserted.
+    ///
+    /// Key-values are stored in insertion order while the key-offset table is sorted by key, so the
+    /// order is recovered by sorting the key offsets, which allocates a temporary buffer.
+    #[inline]
+    pub fn iter_insertion_order(&self) -> YasonResult<InsertionOrderIter<'a>> {
+        InsertionOrderIter::try_new(self.0)
+    }
+
+    #[inline]
+    pub(crate) fn lazy_iter_insertion_order(&self) -> YasonResult<LazyInsertionOrderIter<'a>> {
+        LazyInsertionOrderIter::try_new(self.0)
+    }
+
     /// Gets an iterator over the keys of the object.
     #[inline]
     pub fn key_iter(&self) -> YasonResult<KeyIter<'a>> {
@@ -34,6 +52,51 @@ impl<'a> Object<'a> {
         ValueIter::try_new(self.0)
     }
 
+    /// Collects the keys of the object into a `Vec`.
+    #[inline]
+    pub fn keys_to_vec(&self) -> YasonResult<Vec<&'a str>> {
+        try_collect(self.len()?, self.key_iter()?)
+    }
+
+    /// Collects the values of the object into a `Vec`.
+    #[inline]
+    pub fn values_to_vec(&self) -> YasonResult<Vec<Value<'a>>> {
+        try_collect(self.len()?, self.value_iter()?)
+    }
+
+    /// Collects the entries of the object into a `BTreeMap`.
+    ///
+    /// It decodes every value once, so it pays off for small objects which are looked up by many
+    /// keys, each [`get`] binary searches the key-offset table instead.
+    ///
+    /// [`get`]: Object::get
+    #[inline]
+    pub fn to_map(&self) -> YasonResult<BTreeMap<&'a str, Value<'a>>> {
+        let mut map = BTreeMap::new();
+        for entry in self.iter()? {
+            let (key, value) = entry?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+
+    /// Collects the entries of the object into a `HashMap`, the capacity is reserved upfront.
+    ///
+    /// See [`to_map`] for when to prefer it over [`get`].
+    ///
+    /// [`to_map`]: Object::to_map
+    /// [`get`]: Object::get
+    #[inline]
+    pub fn to_hashmap(&self) -> YasonResult<HashMap<&'a str, Value<'a>>> {
+        let mut map = HashMap::new();
+        map.try_reserve(self.len()?).map_err(YasonError::TryReserveError)?;
+        for entry in self.iter()? {
+            let (key, value) = entry?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+
     #[inline]
     pub(crate) fn lazy_value_iter(&self) -> YasonResult<LazyObjectValueIter<'a>> {
         LazyObjectValueIter::try_new(self.0)
@@ -61,12 +124,42 @@ impl<'a> Object<'a> {
         self.0
     }
 
+    /// Returns whether the object is encoded with 2-byte key offsets.
+    #[inline]
+    pub(crate) fn is_compact(&self) -> bool {
+        self.0.as_bytes().first() == Some(&COMPACT_OBJECT_TYPE)
+    }
+
     /// Returns true if the object contains no elements.
     #[inline]
     pub fn is_empty(&self) -> YasonResult<bool> {
         Ok(self.len()? == 0)
     }
 
+    /// Returns the value corresponding to the key under the given collation, if it exists.
+    ///
+    /// Keys are stored in binary order, so the object is scanned if the key is not found by binary
+    /// search. If several keys are equal to `key` under the collation, the first one in key order is used.
+    #[inline]
+    pub fn get_with_collation<T: AsRef<str>, C: Collation>(
+        &self,
+        key: T,
+        collation: &C,
+    ) -> YasonResult<Option<Value<'a>>> {
+        let key = key.as_ref();
+        if let Some(value) = self.get(key)? {
+            return Ok(Some(value));
+        }
+
+        for index in 0..self.len()? {
+            let (cur_key, value_pos) = unsafe { self.read_nth_key_and_value_pos(index)? };
+            if collation.compare(cur_key, key) == Ordering::Equal {
+                return Ok(Some(self.read_value(value_pos)?));
+            }
+        }
+        Ok(None)
+    }
+
     /// Returns the value corresponding to the key, if it exists.
     #[inline]
     pub fn get<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<Value<'a>>> {
@@ -80,10 +173,23 @@ impl<'a> Object<'a> {
 
     #[inline]
     pub(crate) fn lazy_get<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<LazyValue<'a, false>>> {
+        let found = self.find_key(key.as_ref())?;
+        if let Some(value_pos) = found {
+            return Ok(Some(self.lazy_value_at(value_pos)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Returns where and how the value corresponding to the key is encoded, if it exists. Values of
+    /// objects are never inlined, they follow their keys.
+    #[inline]
+    pub fn entry_info<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<EntryInfo>> {
         let found = self.find_key(key.as_ref())?;
         if let Some(value_pos) = found {
             let data_type = self.0.read_type(value_pos)?;
-            return Ok(Some(LazyValue::new(self.0, data_type, value_pos)));
+            let end = self.0.skip_value(value_pos)?;
+            return Ok(Some(EntryInfo::new(data_type, false, None, value_pos, end - value_pos)));
         }
 
         Ok(None)
@@ -117,6 +223,26 @@ impl<'a> Object<'a> {
         self.is_type(key, DataType::Null)
     }
 
+    /// Returns whether the keys of the object are sorted by [`key_cmp`] without duplicates, as the
+    /// lookups by key require. Nested objects are not checked, see [`Yason::validate`] to check a
+    /// whole document, e.g. one produced by an external writer.
+    ///
+    /// [`key_cmp`]: crate::key_cmp
+    #[inline]
+    pub fn is_sorted(&self) -> YasonResult<bool> {
+        let mut prev_key = None;
+        for key in self.key_iter()? {
+            let key = key?;
+            if let Some(prev_key) = prev_key {
+                if key_cmp(prev_key, key) != Ordering::Less {
+                    return Ok(false);
+                }
+            }
+            prev_key = Some(key);
+        }
+        Ok(true)
+    }
+
     /// Returns true if the object contains a value for the specified key.
     #[inline]
     pub fn contains_key<T: AsRef<str>>(&self, key: T) -> YasonResult<bool> {
@@ -124,6 +250,50 @@ impl<'a> Object<'a> {
         Ok(found.is_some())
     }
 
+    /// Returns true if the object contains the key, like the `?` operator of JSONB.
+    #[inline]
+    pub fn exists_key<T: AsRef<str>>(&self, key: T) -> YasonResult<bool> {
+        self.contains_key(key)
+    }
+
+    /// Returns true if the object contains any of the keys, like the `?|` operator of JSONB.
+    ///
+    /// The keys are sorted and matched against the key-offset table in a single walk, instead of a
+    /// binary search per key.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let yason = YasonBuf::parse(r#"{"a": 1, "bb": 2, "ccc": 3}"#).unwrap();
+    /// let object = yason.object().unwrap();
+    /// assert!(object.exists_any(&["x", "bb"]).unwrap());
+    /// assert!(!object.exists_any(&["x", "y"]).unwrap());
+    /// assert!(object.exists_all(&["ccc", "a"]).unwrap());
+    /// assert!(!object.exists_all(&["ccc", "x"]).unwrap());
+    /// ```
+    #[inline]
+    pub fn exists_any<T: AsRef<str>>(&self, keys: &[T]) -> YasonResult<bool> {
+        let mut any = false;
+        self.walk_keys(keys, |found| {
+            any = found;
+            !found
+        })?;
+        Ok(any)
+    }
+
+    /// Returns true if the object contains all of the keys, like the `?&` operator of JSONB.
+    ///
+    /// The keys are matched in a single walk like [`Object::exists_any`].
+    #[inline]
+    pub fn exists_all<T: AsRef<str>>(&self, keys: &[T]) -> YasonResult<bool> {
+        let mut all = true;
+        self.walk_keys(keys, |found| {
+            all = found;
+            found
+        })?;
+        Ok(all)
+    }
+
     /// Gets an object for this key if it exists and has the correct type, returns `None` if this
     /// key does not exist, returns `YasonError` otherwise.
     #[inline]
@@ -160,6 +330,41 @@ impl<'a> Object<'a> {
         Ok(None)
     }
 
+    /// Gets the length in bytes of a string value for this key if it exists and has the correct
+    /// type, returns `None` if this key does not exist, returns `YasonError` otherwise. Only the
+    /// length header of the string is read, so it is cheap even for large strings.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let yason = YasonBuf::parse(r#"{"text": "héllo world"}"#).unwrap();
+    /// let object = yason.object().unwrap();
+    /// assert_eq!(object.string_len("text").unwrap(), Some(12));
+    /// assert_eq!(object.string_prefix("text", 5).unwrap(), Some("héllo"));
+    /// ```
+    #[inline]
+    pub fn string_len<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<usize>> {
+        let found = self.check_key(key.as_ref(), DataType::String)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_string_len(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    /// Gets at most the first `n` chars of a string value for this key if it exists and has the
+    /// correct type, returns `None` if this key does not exist, returns `YasonError` otherwise.
+    /// The rest of the string is not read.
+    #[inline]
+    pub fn string_prefix<T: AsRef<str>>(&self, key: T, n: usize) -> YasonResult<Option<&'a str>> {
+        let found = self.check_key(key.as_ref(), DataType::String)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_string_prefix(value_pos, n)?));
+        }
+        Ok(None)
+    }
+
     /// Gets a number value for this key if it exists and has the correct type, returns `None`
     /// if this key does not exist, returns `YasonError` otherwise.
     #[inline]
@@ -184,22 +389,118 @@ impl<'a> Object<'a> {
         Ok(None)
     }
 
+    /// Gets the bytes of a binary value without copying for this key if it exists and has the
+    /// correct type, returns `None` if this key does not exist, returns `YasonError` otherwise.
+    #[inline]
+    pub fn binary<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<&'a [u8]>> {
+        let found = self.check_key(key.as_ref(), DataType::Binary)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_binary(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    /// Gets a timestamp for this key if it exists and has the correct type, returns `None` if this key
+    /// does not exist, returns `YasonError` otherwise.
+    #[inline]
+    pub fn timestamp<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<Timestamp>> {
+        let found = self.check_key(key.as_ref(), DataType::Timestamp)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_timestamp(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    /// Gets a date for this key if it exists and has the correct type, returns `None` if this key
+    /// does not exist, returns `YasonError` otherwise.
+    #[inline]
+    pub fn date<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<Date>> {
+        let found = self.check_key(key.as_ref(), DataType::Date)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_date(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    /// Gets a short date for this key if it exists and has the correct type, returns `None` if this key
+    /// does not exist, returns `YasonError` otherwise.
+    #[inline]
+    pub fn short_date<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<ShortDate>> {
+        let found = self.check_key(key.as_ref(), DataType::ShortDate)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_short_date(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    /// Gets a time for this key if it exists and has the correct type, returns `None` if this key
+    /// does not exist, returns `YasonError` otherwise.
+    #[inline]
+    pub fn time<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<Time>> {
+        let found = self.check_key(key.as_ref(), DataType::Time)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_time(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    /// Gets the number of months of an interval year-month for this key if it exists and has the correct type, returns `None` if this key
+    /// does not exist, returns `YasonError` otherwise.
     #[inline]
-    pub(crate) fn equals<T: AsRef<Object<'a>>>(&self, other: T) -> YasonResult<bool> {
+    pub fn interval_year_month<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<YmInterval>> {
+        let found = self.check_key(key.as_ref(), DataType::IntervalYearMonth)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_interval_year_month(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    /// Gets the number of microseconds of an interval day-time for this key if it exists and has the correct type, returns `None` if this key
+    /// does not exist, returns `YasonError` otherwise.
+    #[inline]
+    pub fn interval_day_time<T: AsRef<str>>(&self, key: T) -> YasonResult<Option<DtInterval>> {
+        let found = self.check_key(key.as_ref(), DataType::IntervalDayTime)?;
+
+        if let Some(value_pos) = found {
+            return Ok(Some(self.0.read_interval_day_time(value_pos)?));
+        }
+        Ok(None)
+    }
+
+    #[inline]
+    pub(crate) fn equals<T: AsRef<Object<'a>>>(&self, other: T, budget: &mut StepBudget) -> YasonResult<bool> {
         let other = other.as_ref();
         if self.len()? != other.len()? {
             return Ok(false);
         }
 
+        // compares the keys, the scalars and the element counts of all the entries before descending
+        // into any nested container, so that unequal objects are decided early
+        let mut has_container = false;
         for (l_entry, r_entry) in self.lazy_iter()?.zip(other.lazy_iter()?) {
             let (l_key, l_value) = l_entry?;
             let (r_key, r_value) = r_entry?;
 
-            if l_key != r_key {
+            budget.step()?;
+            if l_key != r_key || !l_value.equals_shallow(&r_value)? {
                 return Ok(false);
             }
-            let res = l_value.equals(r_value)?;
-            if !res {
+            has_container |= matches!(l_value.data_type(), DataType::Object | DataType::Array);
+        }
+        if !has_container {
+            return Ok(true);
+        }
+
+        for (l_entry, r_entry) in self.lazy_iter()?.zip(other.lazy_iter()?) {
+            let (_, l_value) = l_entry?;
+            let (_, r_value) = r_entry?;
+            if !l_value.equals_children(&r_value, budget)? {
                 return Ok(false);
             }
         }
@@ -226,9 +527,13 @@ impl<'a> Object<'a> {
         Ok((key, key_pos + len))
     }
 
+    /// Reads the entry at the given index of the key-offset table, which holds `u16` offsets in a
+    /// compact object.
     #[inline]
-    fn read_key_offset(&self, offset_pos: usize) -> YasonResult<u32> {
-        self.0.read_u32(offset_pos)
+    fn read_key_offset(&self, index: usize) -> YasonResult<u32> {
+        let size = self.0.key_offset_size(0)?;
+        let table_pos = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE;
+        self.0.read_key_offset(table_pos + index * size, size)
     }
 
     #[inline]
@@ -241,6 +546,13 @@ impl<'a> Object<'a> {
             DataType::Number => Value::Number(self.0.read_number(value_pos)?),
             DataType::Bool => Value::Bool(self.0.read_bool(value_pos)?),
             DataType::Null => Value::Null,
+            DataType::Binary => Value::Binary(self.0.read_binary(value_pos)?),
+            DataType::Timestamp => Value::Timestamp(self.0.read_timestamp(value_pos)?),
+            DataType::Date => Value::Date(self.0.read_date(value_pos)?),
+            DataType::ShortDate => Value::ShortDate(self.0.read_short_date(value_pos)?),
+            DataType::Time => Value::Time(self.0.read_time(value_pos)?),
+            DataType::IntervalYearMonth => Value::IntervalYearMonth(self.0.read_interval_year_month(value_pos)?),
+            DataType::IntervalDayTime => Value::IntervalDayTime(self.0.read_interval_day_time(value_pos)?),
         };
         Ok(value)
     }
@@ -252,8 +564,7 @@ impl<'a> Object<'a> {
 
         while left < right {
             let mid = left + (right - left) / 2;
-            let key_offset_pos = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + mid * KEY_OFFSET_SIZE;
-            let key_offset = self.read_key_offset(key_offset_pos)?;
+            let key_offset = self.read_key_offset(mid)?;
             let (cur_key, value_pos) = self.read_key(key_offset as usize)?;
             if cur_key.len() < key.len() {
                 left = mid + 1;
@@ -270,6 +581,36 @@ impl<'a> Object<'a> {
         Ok(None)
     }
 
+    /// Walks the sorted and deduplicated keys along the key-offset table, calling `f` with whether
+    /// each key exists until it returns false.
+    fn walk_keys<T: AsRef<str>, F: FnMut(bool) -> bool>(&self, keys: &[T], mut f: F) -> YasonResult<()> {
+        let mut keys: Vec<&str> = keys.iter().map(AsRef::as_ref).collect();
+        keys.sort_unstable_by(|left, right| key_cmp(left, right));
+        keys.dedup();
+
+        let len = self.len()?;
+        let mut index = 0;
+        for key in keys {
+            let mut found = false;
+            while index < len {
+                let (cur_key, _) = unsafe { self.read_nth_key_and_value_pos(index)? };
+                match key_cmp(cur_key, key) {
+                    Ordering::Less => index += 1,
+                    Ordering::Equal => {
+                        index += 1;
+                        found = true;
+                        break;
+                    }
+                    Ordering::Greater => break,
+                }
+            }
+            if !f(found) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn check_key(&self, key: &str, expected: DataType) -> YasonResult<Option<usize>> {
         let found = self.find_key(key.as_ref())?;
@@ -280,11 +621,62 @@ impl<'a> Object<'a> {
         Ok(None)
     }
 
+    /// Returns the indexes of the key-offset table ordered by the positions of their key-values.
+    #[inline]
+    pub(crate) fn insertion_order(&self) -> YasonResult<Vec<usize>> {
+        let len = self.len()?;
+        let mut entries = Vec::new();
+        entries.try_reserve(len).map_err(YasonError::TryReserveError)?;
+        for index in 0..len {
+            entries.push((unsafe { self.nth_key_offset(index)? }, index));
+        }
+        entries.sort_unstable();
+
+        let mut indexes = Vec::new();
+        indexes.try_reserve(len).map_err(YasonError::TryReserveError)?;
+        indexes.extend(entries.into_iter().map(|(_, index)| index));
+        Ok(indexes)
+    }
+
+    /// Decodes the keys of the object with the positions of their values.
+    pub(crate) fn key_table(&self) -> YasonResult<HashMap<&'a str, usize>> {
+        let len = self.len()?;
+        let mut keys = HashMap::new();
+        keys.try_reserve(len).map_err(YasonError::TryReserveError)?;
+        for index in 0..len {
+            let (key, value_pos) = unsafe { self.read_nth_key_and_value_pos(index)? };
+            keys.insert(key, value_pos);
+        }
+        Ok(keys)
+    }
+
+    #[inline]
+    pub(crate) fn lazy_value_at(&self, value_pos: usize) -> YasonResult<LazyValue<'a, false>> {
+        let data_type = self.0.read_type(value_pos)?;
+        Ok(LazyValue::new(self.0, data_type, value_pos))
+    }
+
+    /// Returns the entry at the given index of the key-offset table.
+    #[inline]
+    pub(crate) fn get_nth(&self, index: usize) -> YasonResult<(&'a str, Value<'a>)> {
+        let (key, value_pos) = unsafe { self.read_nth_key_and_value_pos(index)? };
+        Ok((key, self.read_value(value_pos)?))
+    }
+
+    /// Returns the key of the entry at the given index of the key-offset table, with the positions
+    /// of its key, its value and the end of its value.
+    #[inline]
+    pub(crate) fn nth_entry_range(&self, index: usize) -> YasonResult<(&'a str, usize, usize, usize)> {
+        let key_offset = unsafe { self.nth_key_offset(index)? } as usize;
+        let (key, value_pos) = self.read_key(key_offset)?;
+        let end = self.0.skip_value(value_pos)?;
+        Ok((key, key_offset + DATA_TYPE_SIZE + OBJECT_SIZE, value_pos, end))
+    }
+
     #[inline]
     unsafe fn nth_key_offset(&self, index: usize) -> YasonResult<u32> {
         debug_assert!(index < self.len()?);
-        let key_offset_pos = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + index * KEY_OFFSET_SIZE;
-        self.read_key_offset(key_offset_pos)
+        self.read_key_offset(index)
     }
 
     #[inline]
@@ -314,59 +706,144 @@ impl<'a> AsRef<Object<'a>> for Object<'a> {
     }
 }
 
-/// An iterator over the object's entries.
-pub struct ObjectIter<'a> {
+/// The remaining entries of an object iterator, which are read through the key-offset table, so
+/// they can be taken from both ends and skipped in constant time.
+struct Entries<'a> {
     object: Object<'a>,
-    len: usize,
     index: usize,
+    end: usize,
 }
 
-impl<'a> ObjectIter<'a> {
+impl<'a> Entries<'a> {
     #[inline]
     fn try_new(yason: &'a Yason) -> YasonResult<Self> {
         let object = Object(yason);
         Ok(Self {
-            len: object.len()?,
+            end: object.len()?,
             object,
             index: 0,
         })
     }
 
     #[inline]
-    fn next_entry(&mut self) -> YasonResult<(&'a str, Value<'a>)> {
-        let (key, value_pos) = unsafe { self.object.read_nth_key_and_value_pos(self.index)? };
+    fn entry(&self, index: usize) -> YasonResult<(&'a str, Value<'a>)> {
+        let (key, value_pos) = unsafe { self.object.read_nth_key_and_value_pos(index)? };
         let value = self.object.read_value(value_pos)?;
         Ok((key, value))
     }
 
     #[inline]
-    fn next_key(&mut self) -> YasonResult<&'a str> {
-        Ok(unsafe { self.object.read_nth_key_and_value_pos(self.index)?.0 })
+    fn key(&self, index: usize) -> YasonResult<&'a str> {
+        Ok(unsafe { self.object.read_nth_key_and_value_pos(index)?.0 })
     }
 
     #[inline]
-    fn next_value(&mut self) -> YasonResult<Value<'a>> {
-        let value_pos = unsafe { self.object.read_nth_value_pos(self.index)? };
+    fn value(&self, index: usize) -> YasonResult<Value<'a>> {
+        let value_pos = unsafe { self.object.read_nth_value_pos(index)? };
         let value = self.object.read_value(value_pos)?;
         Ok(value)
     }
-}
 
-impl<'a> Iterator for ObjectIter<'a> {
-    type Item = YasonResult<(&'a str, Value<'a>)>;
+    #[inline]
+    fn len(&self) -> usize {
+        self.end - self.index
+    }
 
+    /// Skips `n` entries from the front and returns the index of the next one.
     #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.len {
-            let entry = self.next_entry();
-            self.index += 1;
-            Some(entry)
+    fn nth_index(&mut self, n: usize) -> Option<usize> {
+        if n < self.len() {
+            self.index += n + 1;
+            Some(self.index - 1)
         } else {
+            self.index = self.end;
             None
         }
     }
+
+    /// Skips `n` entries from the back and returns the index of the next one.
+    #[inline]
+    fn nth_back_index(&mut self, n: usize) -> Option<usize> {
+        if n < self.len() {
+            self.end -= n + 1;
+            Some(self.end)
+        } else {
+            self.end = self.index;
+            None
+        }
+    }
+}
+
+macro_rules! impl_entries_iter {
+    ($iter: ident, $item: ty, $read: ident) => {
+        impl<'a> $iter<'a> {
+            #[inline]
+            fn try_new(yason: &'a Yason) -> YasonResult<Self> {
+                Ok(Self {
+                    inner: Entries::try_new(yason)?,
+                })
+            }
+        }
+
+        impl<'a> Iterator for $iter<'a> {
+            type Item = YasonResult<$item>;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.nth(0)
+            }
+
+            #[inline]
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                let index = self.inner.nth_index(n)?;
+                Some(self.inner.$read(index))
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.inner.len(), Some(self.inner.len()))
+            }
+
+            #[inline]
+            fn count(self) -> usize {
+                self.inner.len()
+            }
+
+            #[inline]
+            fn last(mut self) -> Option<Self::Item> {
+                self.next_back()
+            }
+        }
+
+        impl<'a> DoubleEndedIterator for $iter<'a> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.nth_back(0)
+            }
+
+            #[inline]
+            fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+                let index = self.inner.nth_back_index(n)?;
+                Some(self.inner.$read(index))
+            }
+        }
+
+        impl<'a> ExactSizeIterator for $iter<'a> {}
+
+        impl<'a> FusedIterator for $iter<'a> {}
+    };
 }
 
+/// An iterator over the object's entries.
+///
+/// The iterator is double-ended, and `nth` skips entries in constant time through the key-offset
+/// table.
+pub struct ObjectIter<'a> {
+    inner: Entries<'a>,
+}
+
+impl_entries_iter!(ObjectIter, (&'a str, Value<'a>), entry);
+
 pub struct LazyObjectIter<'a> {
     object: Object<'a>,
     len: usize,
@@ -406,64 +883,80 @@ impl<'a> Iterator for LazyObjectIter<'a> {
     }
 }
 
-/// An iterator over the object's keys.
-pub struct KeyIter<'a> {
-    inner: ObjectIter<'a>,
+/// An iterator over the object's entries in insertion order.
+pub struct InsertionOrderIter<'a> {
+    object: Object<'a>,
+    indexes: std::vec::IntoIter<usize>,
 }
 
-impl<'a> KeyIter<'a> {
+impl<'a> InsertionOrderIter<'a> {
     #[inline]
     fn try_new(yason: &'a Yason) -> YasonResult<Self> {
+        let object = Object(yason);
         Ok(Self {
-            inner: ObjectIter::try_new(yason)?,
+            indexes: object.insertion_order()?.into_iter(),
+            object,
         })
     }
 }
 
-impl<'a> Iterator for KeyIter<'a> {
-    type Item = YasonResult<&'a str>;
+impl<'a> Iterator for InsertionOrderIter<'a> {
+    type Item = YasonResult<(&'a str, Value<'a>)>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.inner.index < self.inner.len {
-            let key = self.inner.next_key();
-            self.inner.index += 1;
-            Some(key)
-        } else {
-            None
-        }
+        let index = self.indexes.next()?;
+        Some(self.object.get_nth(index))
     }
 }
 
-/// An iterator over the object's values.
-pub struct ValueIter<'a> {
-    inner: ObjectIter<'a>,
+pub struct LazyInsertionOrderIter<'a> {
+    object: Object<'a>,
+    indexes: std::vec::IntoIter<usize>,
 }
 
-impl<'a> ValueIter<'a> {
+impl<'a> LazyInsertionOrderIter<'a> {
     #[inline]
     fn try_new(yason: &'a Yason) -> YasonResult<Self> {
+        let object = Object(yason);
         Ok(Self {
-            inner: ObjectIter::try_new(yason)?,
+            indexes: object.insertion_order()?.into_iter(),
+            object,
         })
     }
+
+    #[inline]
+    fn next_entry(&self, index: usize) -> YasonResult<(&'a str, LazyValue<'a, false>)> {
+        let (key, value_pos) = unsafe { self.object.read_nth_key_and_value_pos(index)? };
+        let data_type = self.object.0.read_type(value_pos)?;
+        Ok((key, LazyValue::new(self.object.0, data_type, value_pos)))
+    }
 }
 
-impl<'a> Iterator for ValueIter<'a> {
-    type Item = YasonResult<Value<'a>>;
+impl<'a> Iterator for LazyInsertionOrderIter<'a> {
+    type Item = YasonResult<(&'a str, LazyValue<'a, false>)>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.inner.index < self.inner.len {
-            let key = self.inner.next_value();
-            self.inner.index += 1;
-            Some(key)
-        } else {
-            None
-        }
+        let index = self.indexes.next()?;
+        Some(self.next_entry(index))
     }
 }
 
+/// An iterator over the object's keys, which is double-ended like [`ObjectIter`].
+pub struct KeyIter<'a> {
+    inner: Entries<'a>,
+}
+
+impl_entries_iter!(KeyIter, &'a str, key);
+
+/// An iterator over the object's values, which is double-ended like [`ObjectIter`].
+pub struct ValueIter<'a> {
+    inner: Entries<'a>,
+}
+
+impl_entries_iter!(ValueIter, Value<'a>, value);
+
 pub struct LazyObjectValueIter<'a> {
     object: Object<'a>,
     len: usize,
@@ -0,0 +1,170 @@
+//! Best-effort repair of yason.
+
+use crate::binary::{
+    ARRAY_SIZE, CHECKSUM_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_LENGTH_SIZE, OBJECT_SIZE, VALUE_ENTRY_SIZE,
+};
+use crate::util::key_cmp;
+use crate::yason::{LintKind, Yason, YasonBuf, YasonError, YasonResult};
+use crate::DataType;
+
+/// The repairs made by [`YasonBuf::try_new_lossy`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepairReport {
+    truncated_bytes: usize,
+    resorted_objects: usize,
+}
+
+impl RepairReport {
+    /// Returns the number of trailing bytes after the value, or after its checksum, which were
+    /// removed.
+    #[inline]
+    pub fn truncated_bytes(&self) -> usize {
+        self.truncated_bytes
+    }
+
+    /// Returns the number of objects whose key-offset table was rebuilt in key order.
+    #[inline]
+    pub fn resorted_objects(&self) -> usize {
+        self.resorted_objects
+    }
+
+    /// Returns true if nothing was repaired.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.truncated_bytes == 0 && self.resorted_objects == 0
+    }
+}
+
+impl YasonBuf {
+    /// Creates a `YasonBuf` from bytes like [`Yason::from_bytes`], repairing the problems which can
+    /// be fixed without guessing, e.g. bytes written by an older buggy writer:
+    ///
+    /// - trailing bytes after the value are removed, a valid checksum right after the value is
+    ///   kept,
+    /// - the key-offset table of an object whose keys are not sorted is rebuilt in key order, and
+    ///   the checksum, if any, is updated.
+    ///
+    /// Repairs are only made if every other part of the bytes is readable, see [`Yason::lint`].
+    /// Returns `YasonError` if the bytes are still invalid after the repairs, e.g. with duplicate
+    /// keys or a checksum mismatch.
+    ///
+    /// ```rust
+    /// use yason::YasonBuf;
+    ///
+    /// let mut bytes = YasonBuf::parse(r#"{"a": 1}"#).unwrap().into_bytes();
+    /// bytes.extend_from_slice(b"garbage");
+    /// let (yason, report) = YasonBuf::try_new_lossy(bytes).unwrap();
+    /// assert_eq!(yason.format(false).to_string(), r#"{"a":1}"#);
+    /// assert_eq!(report.truncated_bytes(), 7);
+    /// ```
+    pub fn try_new_lossy(mut bytes: Vec<u8>) -> YasonResult<(YasonBuf, RepairReport)> {
+        if bytes.is_empty() {
+            return Err(YasonError::IndexOutOfBounds { len: 0, index: 0 });
+        }
+
+        let mut report = RepairReport::default();
+        let issues = unsafe { Yason::new_unchecked(&bytes) }.lint();
+        let repairable = issues.iter().filter(|issue| issue.is_error()).all(|issue| {
+            matches!(
+                issue.kind(),
+                LintKind::UnsortedKeys | LintKind::TrailingBytes(_) | LintKind::MissingChecksum
+            )
+        });
+
+        if repairable {
+            let trailing = issues.iter().find_map(|issue| match issue.kind() {
+                LintKind::TrailingBytes(_) => Some(issue.pos()),
+                _ => None,
+            });
+            if let Some(end) = trailing {
+                let len = if has_checksum_at(&bytes, end) {
+                    end + CHECKSUM_SIZE
+                } else {
+                    end
+                };
+                report.truncated_bytes = bytes.len() - len;
+                bytes.truncate(len);
+            }
+
+            if issues.iter().any(|issue| *issue.kind() == LintKind::UnsortedKeys) {
+                let yason = unsafe { Yason::new_unchecked(&bytes) };
+                let end = yason.skip_value(yason.header_len())?;
+                let mut tables = Vec::new();
+                yason.collect_unsorted(yason.header_len(), &mut tables)?;
+
+                report.resorted_objects = tables.len();
+                for (pos, offset_size, offsets) in tables {
+                    for (i, offset) in offsets.iter().enumerate() {
+                        let offset_pos = pos + i * offset_size;
+                        // the offsets of a compact object fit in its narrower entries
+                        bytes[offset_pos..offset_pos + offset_size]
+                            .copy_from_slice(&offset.to_le_bytes()[..offset_size]);
+                    }
+                }
+                // A checksum is only left at the end if it was valid before the repair.
+                if end + CHECKSUM_SIZE == bytes.len() {
+                    let checksum = crc32c::crc32c(&bytes[..end]);
+                    bytes[end..].copy_from_slice(&checksum.to_le_bytes());
+                }
+            }
+        }
+
+        unsafe { Yason::new_unchecked(&bytes) }.validate()?;
+        Ok((unsafe { YasonBuf::new_unchecked(bytes) }, report))
+    }
+}
+
+/// Returns whether a valid checksum of the bytes before `end` follows them.
+#[inline]
+fn has_checksum_at(bytes: &[u8], end: usize) -> bool {
+    match bytes.get(end..end + CHECKSUM_SIZE) {
+        Some(checksum) => checksum == crc32c::crc32c(&bytes[..end]).to_le_bytes(),
+        None => false,
+    }
+}
+
+impl Yason {
+    /// Collects the position of the key-offset table, the size of its entries and the sorted key
+    /// offsets of every object whose keys are not sorted, in the value starting at `pos`.
+    fn collect_unsorted(&self, pos: usize, tables: &mut Vec<(usize, usize, Vec<u32>)>) -> YasonResult<()> {
+        match self.read_type(pos)? {
+            DataType::Object => {
+                let start_pos = pos + DATA_TYPE_SIZE + OBJECT_SIZE;
+                let count = self.read_u16(start_pos)? as usize;
+                let table_pos = start_pos + ELEMENT_COUNT_SIZE;
+                let offset_size = self.key_offset_size(pos)?;
+
+                let mut entries = Vec::new();
+                entries.try_reserve(count).map_err(YasonError::TryReserveError)?;
+                for i in 0..count {
+                    let offset = self.read_key_offset(table_pos + i * offset_size, offset_size)?;
+                    let key_pos = start_pos + offset as usize;
+                    let key_end = key_pos + KEY_LENGTH_SIZE + self.read_u16(key_pos)? as usize;
+                    let key = std::str::from_utf8(self.slice(key_pos + KEY_LENGTH_SIZE, key_end)?)
+                        .map_err(|_| YasonError::InvalidFormat("key is not valid UTF-8"))?;
+                    entries.push((key, offset));
+                    self.collect_unsorted(key_end, tables)?;
+                }
+
+                if entries.windows(2).any(|w| key_cmp(w[0].0, w[1].0).is_gt()) {
+                    entries.sort_by(|left, right| key_cmp(left.0, right.0));
+                    let offsets = entries.into_iter().map(|(_, offset)| offset).collect();
+                    tables.push((table_pos, offset_size, offsets));
+                }
+            }
+            DataType::Array => {
+                let count_pos = pos + DATA_TYPE_SIZE + ARRAY_SIZE;
+                let count = self.read_u16(count_pos)? as usize;
+                for i in 0..count {
+                    let entry_pos = count_pos + ELEMENT_COUNT_SIZE + i * VALUE_ENTRY_SIZE;
+                    if matches!(self.read_type(entry_pos)?, DataType::Object | DataType::Array) {
+                        let offset = self.read_u32(entry_pos + DATA_TYPE_SIZE)? as usize;
+                        self.collect_unsorted(pos + DATA_TYPE_SIZE + ARRAY_SIZE + offset, tables)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
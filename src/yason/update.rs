@@ -0,0 +1,50 @@
+//! In-place updates of yason.
+
+use crate::binary::{ARRAY_SIZE, CHECKSUM_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, VALUE_ENTRY_SIZE};
+use crate::vec::VecExt;
+use crate::yason::{YasonBuf, YasonError, YasonResult};
+use crate::DataType;
+
+impl YasonBuf {
+    /// Sets the element at the given index of an array to a bool, by overwriting its value entry.
+    ///
+    /// Bool and null elements are inlined in their value entries, so they can be updated without
+    /// moving any other bytes, e.g. for flags updated at a high frequency. A checksum footer is
+    /// updated as well.
+    ///
+    /// Returns `YasonError::UnexpectedType` if the value is not an array or the element is neither
+    /// a bool nor a null, or `YasonError::IndexOutOfBounds` if the index is out of bounds.
+    #[inline]
+    pub fn array_set_bool(&mut self, index: usize, value: bool) -> YasonResult<()> {
+        self.set_inlined(index, DataType::Bool, value as u32)
+    }
+
+    /// Sets the element at the given index of an array to null, see [`YasonBuf::array_set_bool`].
+    #[inline]
+    pub fn array_set_null(&mut self, index: usize) -> YasonResult<()> {
+        self.set_inlined(index, DataType::Null, 0)
+    }
+
+    #[inline]
+    fn set_inlined(&mut self, index: usize, data_type: DataType, inlined: u32) -> YasonResult<()> {
+        let actual = self.array()?.type_of(index)?;
+        if !matches!(actual, DataType::Bool | DataType::Null) {
+            return Err(YasonError::UnexpectedType {
+                expected: data_type,
+                actual,
+            });
+        }
+
+        let value_pos = self.header_len();
+        let value_entry_pos = value_pos + DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + index * VALUE_ENTRY_SIZE;
+        self.bytes.write_data_type_by_pos(data_type, value_entry_pos);
+        self.bytes.write_offset(inlined, value_entry_pos + DATA_TYPE_SIZE);
+
+        let end = self.skip_value(value_pos)?;
+        if end + CHECKSUM_SIZE == self.bytes.len() {
+            let checksum = crc32c::crc32c(&self.bytes[..end]);
+            self.bytes[end..].copy_from_slice(&checksum.to_le_bytes());
+        }
+        Ok(())
+    }
+}
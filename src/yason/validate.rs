@@ -0,0 +1,165 @@
+//! Yason validation.
+
+use crate::binary::{
+    ARRAY_SIZE, CHECKSUM_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, FLAG_CHECKSUM, KEY_LENGTH_SIZE, NUMBER_LENGTH_SIZE,
+    OBJECT_SIZE, VALUE_ENTRY_SIZE,
+};
+use crate::limits::MAX_NESTED_DEPTH;
+use crate::temporal::MICROS_PER_SECOND;
+use crate::util::{decode_varint, key_cmp};
+use crate::yason::{Yason, YasonError, YasonResult};
+use crate::DataType;
+use decimal_rs::MAX_BINARY_SIZE;
+use std::cmp::Ordering;
+
+impl Yason {
+    /// Creates a `Yason` from bytes after checking they are a valid yason.
+    #[inline]
+    pub fn from_bytes<B: AsRef<[u8]> + ?Sized>(bytes: &B) -> YasonResult<&Yason> {
+        if bytes.as_ref().is_empty() {
+            return Err(YasonError::IndexOutOfBounds { len: 0, index: 0 });
+        }
+        let yason = unsafe { Yason::new_unchecked(bytes) };
+        yason.validate()?;
+        Ok(yason)
+    }
+
+    /// Checks that the bytes are a valid yason: every size, count and offset stays inside the
+    /// value it belongs to, strings and keys are UTF-8, and keys are sorted without duplicates.
+    /// The version header is checked if there is one, and if the value is followed by a checksum
+    /// footer, the checksum is verified as well.
+    ///
+    /// The whole document is walked, so this is linear in its size.
+    #[inline]
+    pub fn validate(&self) -> YasonResult<()> {
+        self.validate_header()?;
+        let end = self.validate_value(self.header_len(), 0)?;
+        if end + CHECKSUM_SIZE == self.bytes.len() {
+            return self.verify_checksum_at(end);
+        }
+        if self.header_flags()? & FLAG_CHECKSUM != 0 {
+            return Err(YasonError::InvalidFormat("missing checksum"));
+        }
+        if end != self.bytes.len() {
+            return Err(YasonError::InvalidFormat("trailing bytes after the value"));
+        }
+        Ok(())
+    }
+
+    fn validate_value(&self, pos: usize, depth: usize) -> YasonResult<usize> {
+        let end = self.skip_value(pos)?;
+        let value_pos = pos + DATA_TYPE_SIZE;
+        match self.read_type(pos)? {
+            DataType::Object => self.validate_object(pos, end, depth)?,
+            DataType::Array => self.validate_array(pos, end, depth)?,
+            DataType::String => {
+                let (_, data_length_len) = decode_varint(&self.bytes, value_pos)?;
+                std::str::from_utf8(self.slice(value_pos + data_length_len, end)?)
+                    .map_err(|_| YasonError::InvalidFormat("string is not valid UTF-8"))?;
+            }
+            DataType::Number => {
+                let len = end - value_pos - NUMBER_LENGTH_SIZE;
+                if len == 0 || len > MAX_BINARY_SIZE {
+                    return Err(YasonError::InvalidFormat("invalid number length"));
+                }
+            }
+            DataType::Bool => {
+                if self.read_u8(value_pos)? > 1 {
+                    return Err(YasonError::InvalidFormat("invalid bool value"));
+                }
+            }
+            DataType::Date => {
+                if self.read_i64(value_pos)? % MICROS_PER_SECOND != 0 {
+                    return Err(YasonError::InvalidFormat("date with a fraction of a second"));
+                }
+            }
+            DataType::Time => {
+                self.read_time(pos)?;
+            }
+            DataType::Null
+            | DataType::Binary
+            | DataType::Timestamp
+            | DataType::ShortDate
+            | DataType::IntervalYearMonth
+            | DataType::IntervalDayTime => {}
+        }
+        Ok(end)
+    }
+
+    fn validate_object(&self, pos: usize, end: usize, depth: usize) -> YasonResult<()> {
+        if depth >= MAX_NESTED_DEPTH {
+            return Err(YasonError::InvalidFormat("nested too deeply"));
+        }
+
+        let start_pos = pos + DATA_TYPE_SIZE + OBJECT_SIZE;
+        let count = self.read_u16(start_pos)? as usize;
+        let offset_size = self.key_offset_size(pos)?;
+        let header_end = start_pos + ELEMENT_COUNT_SIZE + count * offset_size;
+        if header_end > end {
+            return Err(YasonError::InvalidFormat("object element count exceeds its size"));
+        }
+
+        let mut prev_key: Option<&str> = None;
+        for i in 0..count {
+            let offset_pos = start_pos + ELEMENT_COUNT_SIZE + i * offset_size;
+            let key_pos = start_pos + self.read_key_offset(offset_pos, offset_size)? as usize;
+            if key_pos < header_end || key_pos + KEY_LENGTH_SIZE > end {
+                return Err(YasonError::InvalidFormat("key offset out of object"));
+            }
+            let key_end = key_pos + KEY_LENGTH_SIZE + self.read_u16(key_pos)? as usize;
+            if key_end > end {
+                return Err(YasonError::InvalidFormat("key out of object"));
+            }
+            let key = std::str::from_utf8(self.slice(key_pos + KEY_LENGTH_SIZE, key_end)?)
+                .map_err(|_| YasonError::InvalidFormat("key is not valid UTF-8"))?;
+            if let Some(prev_key) = prev_key {
+                if key_cmp(prev_key, key) != Ordering::Less {
+                    return Err(YasonError::InvalidFormat("keys are not sorted or duplicated"));
+                }
+            }
+            prev_key = Some(key);
+
+            if key_end >= end || self.validate_value(key_end, depth + 1)? > end {
+                return Err(YasonError::InvalidFormat("value out of object"));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_array(&self, pos: usize, end: usize, depth: usize) -> YasonResult<()> {
+        if depth >= MAX_NESTED_DEPTH {
+            return Err(YasonError::InvalidFormat("nested too deeply"));
+        }
+
+        let count_pos = pos + DATA_TYPE_SIZE + ARRAY_SIZE;
+        let count = self.read_u16(count_pos)? as usize;
+        let header_end = count_pos + ELEMENT_COUNT_SIZE + count * VALUE_ENTRY_SIZE;
+        if header_end > end {
+            return Err(YasonError::InvalidFormat("array element count exceeds its size"));
+        }
+
+        for i in 0..count {
+            let entry_pos = count_pos + ELEMENT_COUNT_SIZE + i * VALUE_ENTRY_SIZE;
+            let data_type = self.read_type(entry_pos)?;
+            let inlined = self.read_u32(entry_pos + DATA_TYPE_SIZE)?;
+            match data_type {
+                // bool and null are inlined
+                DataType::Bool if inlined > 1 => return Err(YasonError::InvalidFormat("invalid bool value")),
+                DataType::Bool | DataType::Null => {}
+                _ => {
+                    let value_pos = pos + DATA_TYPE_SIZE + ARRAY_SIZE + inlined as usize;
+                    if value_pos < header_end || value_pos >= end {
+                        return Err(YasonError::InvalidFormat("value offset out of array"));
+                    }
+                    if self.read_type(value_pos)? != data_type {
+                        return Err(YasonError::InvalidFormat("value type differs from its entry"));
+                    }
+                    if self.validate_value(value_pos, depth + 1)? > end {
+                        return Err(YasonError::InvalidFormat("value out of array"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
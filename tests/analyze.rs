@@ -0,0 +1,121 @@
+//! Analyze tests
+
+use yason::analyze;
+use yason::{DataType, Number, ObjectBuilder, YasonBuf};
+
+#[test]
+fn test_key_histogram() {
+    let docs = [
+        YasonBuf::parse(r#"{"id": 1, "name": "a", "tags": ["x", "y"]}"#).unwrap(),
+        YasonBuf::parse(r#"{"id": 2, "name": null, "my key": {"id": true}}"#).unwrap(),
+        YasonBuf::parse(r#"{"id": "3", "tags": []}"#).unwrap(),
+    ];
+    let histogram = analyze::key_histogram(docs.iter().map(|doc| doc.as_ref())).unwrap();
+    assert_eq!(histogram.documents(), 3);
+
+    let paths: Vec<&str> = histogram.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "$",
+            "$.\"my key\"",
+            "$.\"my key\".id",
+            "$.id",
+            "$.name",
+            "$.tags",
+            "$.tags[*]"
+        ]
+    );
+
+    let root = histogram.get("$").unwrap();
+    assert_eq!(root.count(), 3);
+    assert_eq!(root.types().collect::<Vec<_>>(), vec![(DataType::Object, 3)]);
+
+    let id = histogram.get("$.id").unwrap();
+    assert_eq!(id.count(), 3);
+    assert_eq!(id.documents(), 3);
+    assert_eq!(id.type_count(DataType::Number), 2);
+    assert_eq!(id.type_count(DataType::String), 1);
+    assert_eq!(id.type_count(DataType::Bool), 0);
+
+    let name = histogram.get("$.name").unwrap();
+    assert_eq!(name.documents(), 2);
+    // "a" takes a type, a length and a byte, null takes a type
+    assert_eq!(name.total_size(), 4);
+    assert_eq!(name.average_size(), 2.0);
+
+    let tags = histogram.get("$.tags[*]").unwrap();
+    assert_eq!(tags.count(), 2);
+    assert_eq!(tags.documents(), 1);
+
+    assert_eq!(histogram.get("$.\"my key\".id").unwrap().type_count(DataType::Bool), 1);
+    assert!(histogram.get("$.missing").is_none());
+
+    assert_eq!(id.objects(), 3);
+    assert_eq!(id.duplicates(), 0);
+    assert_eq!(root.objects(), 0);
+    assert_eq!(tags.objects(), 0);
+}
+
+#[test]
+fn test_key_histogram_duplicate_keys() {
+    let mut builder = ObjectBuilder::try_new(3, false).unwrap();
+    builder.push_number("id", Number::from(1)).unwrap();
+    builder.push_number("id", Number::from(2)).unwrap();
+    builder.push_string("name", "a").unwrap();
+    let duplicated = builder.finish().unwrap();
+    let docs = [duplicated, YasonBuf::parse(r#"{"name": "b"}"#).unwrap()];
+
+    let histogram = analyze::key_histogram(docs.iter().map(|doc| doc.as_ref())).unwrap();
+    let id = histogram.get("$.id").unwrap();
+    assert_eq!(id.count(), 2);
+    assert_eq!(id.documents(), 1);
+    assert_eq!(id.objects(), 1);
+    assert_eq!(id.duplicates(), 1);
+    let name = histogram.get("$.name").unwrap();
+    assert_eq!(name.objects(), 2);
+    assert_eq!(name.duplicates(), 0);
+
+    // a duplicated key found in one of two objects is not required
+    let schema = analyze::infer_schema(docs.iter().map(|doc| doc.as_ref())).unwrap();
+    let schema = schema.object().unwrap();
+    let required = schema.array("required").unwrap().unwrap();
+    assert_eq!(required.len().unwrap(), 1);
+    assert_eq!(required.string(0).unwrap(), "name");
+}
+
+#[test]
+fn test_infer_schema() {
+    let docs = [
+        YasonBuf::parse(r#"{"id": 1, "name": "a", "tags": ["x", 2.5]}"#).unwrap(),
+        YasonBuf::parse(r#"{"id": -3, "name": null}"#).unwrap(),
+    ];
+    let schema = analyze::infer_schema(docs.iter().map(|doc| doc.as_ref())).unwrap();
+    let expected = YasonBuf::parse(
+        r#"{
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "id": {"type": "number", "minimum": -3, "maximum": 1},
+                "name": {"type": ["string", "null"]},
+                "tags": {
+                    "type": "array",
+                    "items": {"type": ["string", "number"], "minimum": 2.5, "maximum": 2.5}
+                }
+            },
+            "required": ["id", "name"]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(schema, expected);
+
+    let histogram = analyze::key_histogram(docs.iter().map(|doc| doc.as_ref())).unwrap();
+    assert_eq!(histogram.get("$.id").unwrap().min_number(), Some(&Number::from(-3)));
+    assert_eq!(histogram.get("$.name").unwrap().max_number(), None);
+
+    let schema = analyze::infer_schema(std::iter::empty()).unwrap();
+    assert_eq!(
+        schema,
+        YasonBuf::parse(r#"{"$schema": "https://json-schema.org/draft/2020-12/schema"}"#).unwrap()
+    );
+}
@@ -1,6 +1,11 @@
 //! Array builder tests.
 
-use yason::{ArrayBuilder, ArrayRefBuilder, BuildError, DataType, Number, Value, Yason, YasonBuf};
+use std::str::FromStr;
+use yason::limits::MAX_NESTED_DEPTH;
+use yason::{
+    ArrayBuilder, ArrayRefBuilder, BuildError, DataType, ErrorCode, EstimateSize, Number, ObjectBuilder, Value, Yason,
+    YasonBuf, YasonError,
+};
 
 fn assert_string<T: AsRef<str>>(input: Value, expected: T) {
     if let Value::String(value) = input {
@@ -176,9 +181,11 @@ fn test_array_nested_depth() {
             cur_depth: usize,
             total_depth: usize,
         ) -> Option<BuildError> {
+            let builder = builder.unwrap();
+            assert_eq!(builder.depth(), cur_depth);
             if cur_depth < total_depth {
-                let nested_builder = builder.unwrap().push_array(1);
-                return if cur_depth < 100 {
+                let nested_builder = builder.push_array(1);
+                return if cur_depth < MAX_NESTED_DEPTH {
                     inner(Ok(&mut nested_builder.unwrap()), cur_depth + 1, total_depth)
                 } else {
                     nested_builder.err()
@@ -204,3 +211,481 @@ fn test_array_nested_depth() {
     assert_nested_depth(101, Some(BuildError::NestedTooDeeply));
     assert_nested_depth(102, Some(BuildError::NestedTooDeeply));
 }
+
+#[test]
+fn test_array_from_values() {
+    let mut array_builder = ArrayBuilder::try_new(1).unwrap();
+    array_builder.push_bool(true).unwrap();
+    let array = array_builder.finish().unwrap();
+
+    let mut object_builder = ObjectBuilder::try_new(1, true).unwrap();
+    object_builder.push_string("key", "value").unwrap();
+    let object = object_builder.finish().unwrap();
+
+    let values = [
+        Value::Number(Number::from(123)),
+        Value::String("abc"),
+        Value::Null,
+        Value::Bool(false),
+        Value::Array(array.array().unwrap()),
+        Value::Object(object.object().unwrap()),
+    ];
+    let yason = ArrayBuilder::from_values(&values).unwrap();
+    assert_array(&yason);
+    assert!(yason.equals(create_yason()).unwrap());
+
+    let empty = ArrayBuilder::from_values(&[]).unwrap();
+    assert_eq!(empty.array().unwrap().len().unwrap(), 0);
+
+    let values = vec![Value::Null; u16::MAX as usize + 1];
+    assert!(matches!(
+        ArrayBuilder::from_values(&values),
+        Err(BuildError::TooManyElements(65536))
+    ));
+}
+
+#[test]
+fn test_array_with_capacity() {
+    let values = [Value::String("abc"), Value::Null, Value::Number(Number::from(123))];
+    let capacity = values.estimate_size();
+
+    let mut builder = ArrayBuilder::try_new_with_capacity(3, capacity).unwrap();
+    builder.push_string("abc").unwrap();
+    builder.push_null().unwrap();
+    builder.push_number(Number::from(123)).unwrap();
+    let yason = builder.finish().unwrap();
+    assert!(yason.as_bytes().len() <= capacity);
+    assert!(yason.equals(ArrayBuilder::from_values(&values).unwrap()).unwrap());
+}
+
+#[test]
+fn test_array_reset() {
+    let mut builder = ArrayBuilder::try_new(1).unwrap();
+    builder.push_string("abc").unwrap();
+    let expected = builder.finish_ref().unwrap().to_owned();
+    assert!(matches!(
+        builder.push_bool(true),
+        Err(BuildError::InnerUncompletedError)
+    ));
+
+    for _ in 0..3 {
+        builder.reset(1).unwrap();
+        builder.push_string("abc").unwrap();
+        assert_eq!(builder.finish_ref().unwrap(), expected.as_ref());
+    }
+
+    builder.reset(2).unwrap();
+    builder.push_bool(false).unwrap();
+    builder.push_bool(true).unwrap();
+    let yason = builder.finish().unwrap();
+    assert_eq!(yason.array().unwrap().len().unwrap(), 2);
+
+    let mut builder = ArrayBuilder::try_new_in(yason.as_bytes().to_vec(), 1).unwrap();
+    builder.push_string("abc").unwrap();
+    assert_eq!(builder.finish().unwrap(), expected);
+}
+
+#[test]
+fn test_array_set() {
+    let mut builder = ArrayBuilder::try_new_set(10, true).unwrap();
+    builder.push_string("a").unwrap().push_number(Number::from(1)).unwrap();
+    builder.push_bool(true).unwrap().push_null().unwrap();
+    let mut nested = builder.push_array(1).unwrap();
+    nested.push_string("a").unwrap();
+    nested.finish().unwrap();
+    builder
+        .push_string("a")
+        .unwrap()
+        .push_number(Number::from_str("1.00").unwrap())
+        .unwrap();
+    builder
+        .push_bool(true)
+        .unwrap()
+        .push_null()
+        .unwrap()
+        .push_bool(false)
+        .unwrap();
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"["a",1,true,null,["a"],false]"#);
+
+    let mut builder = ArrayBuilder::try_new_set(3, false).unwrap();
+    builder.push_number(Number::from_str("2.50").unwrap()).unwrap();
+    let err = match builder.push_number(Number::from_str("2.5").unwrap()) {
+        Err(err) => err,
+        Ok(_) => panic!("duplicate value is pushed"),
+    };
+    assert!(matches!(err, BuildError::DuplicateValue));
+    assert_eq!(err.code(), ErrorCode::DuplicateValue);
+    builder.push_null().unwrap().push_string("2.5").unwrap();
+    assert_eq!(
+        builder.finish_ref().unwrap().format(false).to_string(),
+        r#"[2.5,null,"2.5"]"#
+    );
+
+    builder.reset(2).unwrap();
+    builder.push_string("b").unwrap();
+    assert!(matches!(builder.push_string("b"), Err(BuildError::DuplicateValue)));
+    assert!(matches!(
+        builder.finish(),
+        Err(BuildError::InconsistentElementCount { expected: 2, actual: 1 })
+    ));
+}
+
+#[test]
+fn test_array_deduplicate() {
+    let input = r#"{"rows": [
+        {"status": "OK", "tags": ["a", "b"]},
+        {"status": "OK", "tags": ["a", "b"]},
+        "pending", "pending", 1.0, 1, true, null,
+        {"status": "FAILED", "tags": ["a", "b"]}
+    ]}"#;
+    let yason = YasonBuf::parse(input).unwrap();
+    let deduplicated = yason.deduplicate().unwrap();
+    assert!(deduplicated.as_bytes().len() < yason.as_bytes().len());
+    assert!(deduplicated.equals(&yason).unwrap());
+    assert_eq!(deduplicated.format(false).to_string(), yason.format(false).to_string());
+
+    let rows = deduplicated.object().unwrap().array("rows").unwrap().unwrap();
+    assert_eq!(rows.string(3).unwrap(), "pending");
+    let row = rows.object(1).unwrap();
+    assert_eq!(row.string("status").unwrap(), Some("OK"));
+
+    let scalar = YasonBuf::parse("\"abc\"").unwrap();
+    assert_eq!(scalar.deduplicate().unwrap(), scalar);
+}
+
+#[test]
+fn test_array_to_vec() {
+    let yason = create_yason();
+    let values = yason.array().unwrap().to_vec().unwrap();
+    assert_eq!(values.len(), 6);
+    assert_number(values[0].clone(), Number::from(123));
+    assert_string(values[1].clone(), "abc");
+    assert_null(values[2].clone());
+    assert_bool(values[3].clone(), false);
+}
+
+#[test]
+fn test_array_to_typed_vec() {
+    let yason = YasonBuf::parse("[1, -2, 3.0, 9223372036854775807]").unwrap();
+    let array = yason.array().unwrap();
+    assert_eq!(array.to_i64_vec().unwrap(), vec![1, -2, 3, i64::MAX]);
+    assert_eq!(array.to_f64_vec().unwrap(), vec![1.0, -2.0, 3.0, 9223372036854775807.0]);
+    let decimals = array.to_decimal_vec().unwrap();
+    assert_eq!(decimals.len(), 4);
+    assert_eq!(decimals[2].to_string(), "3");
+
+    let yason = YasonBuf::parse("[0.5, 1e-3]").unwrap();
+    let array = yason.array().unwrap();
+    assert_eq!(array.to_f64_vec().unwrap(), vec![0.5, 0.001]);
+    assert!(matches!(array.to_i64_vec(), Err(YasonError::InvalidInteger(_))));
+
+    let yason = YasonBuf::parse("[9223372036854775808]").unwrap();
+    assert!(matches!(
+        yason.array().unwrap().to_i64_vec(),
+        Err(YasonError::InvalidInteger(_))
+    ));
+
+    let yason = YasonBuf::parse(r#"[1, "2", 3]"#).unwrap();
+    assert!(matches!(
+        yason.array().unwrap().to_f64_vec(),
+        Err(YasonError::UnexpectedType {
+            expected: DataType::Number,
+            actual: DataType::String
+        })
+    ));
+    let yason = YasonBuf::parse("[1, null]").unwrap();
+    assert!(yason.array().unwrap().to_decimal_vec().is_err());
+
+    let yason = YasonBuf::parse("[]").unwrap();
+    assert!(yason.array().unwrap().to_i64_vec().unwrap().is_empty());
+}
+
+#[test]
+fn test_array_set_inlined() {
+    let mut yason = YasonBuf::parse(r#"[true, null, 1, "a"]"#).unwrap();
+    yason.array_set_bool(0, false).unwrap();
+    yason.array_set_bool(1, true).unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"[false,true,1,"a"]"#);
+    yason.array_set_null(0).unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"[null,true,1,"a"]"#);
+    assert_eq!(yason, YasonBuf::parse(r#"[null, true, 1, "a"]"#).unwrap());
+
+    assert!(matches!(
+        yason.array_set_bool(2, true),
+        Err(YasonError::UnexpectedType {
+            expected: DataType::Bool,
+            actual: DataType::Number
+        })
+    ));
+    assert!(matches!(
+        yason.array_set_null(3),
+        Err(YasonError::UnexpectedType {
+            expected: DataType::Null,
+            actual: DataType::String
+        })
+    ));
+    assert!(matches!(
+        yason.array_set_bool(4, true),
+        Err(YasonError::IndexOutOfBounds { len: 4, index: 4 })
+    ));
+    let mut object = YasonBuf::parse(r#"{"a": true}"#).unwrap();
+    assert!(matches!(
+        object.array_set_bool(0, true),
+        Err(YasonError::UnexpectedType {
+            expected: DataType::Array,
+            actual: DataType::Object
+        })
+    ));
+
+    yason.add_header().unwrap();
+    yason.add_checksum().unwrap();
+    yason.array_set_bool(0, true).unwrap();
+    yason.verify_checksum().unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"[true,true,1,"a"]"#);
+}
+
+#[test]
+fn test_array_chunks() {
+    let yason: &'static YasonBuf = Box::leak(Box::new(YasonBuf::parse("[1, 2, 3, 4, 5, 6, 7]").unwrap()));
+    let array = yason.array().unwrap();
+
+    let chunks = array.chunks(3).unwrap();
+    assert_eq!(chunks.len(), 3);
+    let handles = chunks
+        .map(|chunk| {
+            std::thread::spawn(move || {
+                let mut sum = Number::ZERO;
+                for value in chunk.iter() {
+                    if let Value::Number(number) = value.unwrap() {
+                        sum += number;
+                    }
+                }
+                (chunk.start(), chunk.len(), sum)
+            })
+        })
+        .collect::<Vec<_>>();
+    let sums = handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>();
+    assert_eq!(
+        sums,
+        [
+            (0, 3, Number::from(6)),
+            (3, 3, Number::from(15)),
+            (6, 1, Number::from(7))
+        ]
+    );
+
+    let chunk = array.chunks(4).unwrap().nth(1).unwrap();
+    assert_number(chunk.get(2).unwrap(), Number::from(7));
+    assert!(matches!(
+        chunk.get(3),
+        Err(YasonError::IndexOutOfBounds { len: 3, index: 3 })
+    ));
+    assert_eq!(array.chunks(7).unwrap().count(), 1);
+    assert_eq!(array.chunks(100).unwrap().next().unwrap().len(), 7);
+
+    let empty = YasonBuf::parse("[]").unwrap();
+    assert!(empty.array().unwrap().chunks(2).unwrap().next().is_none());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_array_par_iter() {
+    use rayon::prelude::*;
+
+    let json = format!("[{}]", (0..1000).map(|i| i.to_string()).collect::<Vec<_>>().join(","));
+    let yason = YasonBuf::parse(json).unwrap();
+    let sum = yason
+        .array()
+        .unwrap()
+        .par_iter()
+        .unwrap()
+        .map(|value| match value.unwrap() {
+            Value::Number(number) => number,
+            _ => unreachable!(),
+        })
+        .reduce(|| Number::ZERO, |l, r| l + r);
+    assert_eq!(sum, Number::from(499500));
+}
+
+#[test]
+fn test_string_len_and_prefix() {
+    let long = "x".repeat(1000);
+    let yason = YasonBuf::parse(format!(r#"["{}", "ünï", true]"#, long)).unwrap();
+    let array = yason.array().unwrap();
+
+    assert_eq!(array.string_len(0).unwrap(), 1000);
+    assert_eq!(array.string_len(1).unwrap(), 5);
+    assert!(array.string_len(2).is_err());
+    assert!(array.string_len(3).is_err());
+
+    assert_eq!(array.string_prefix(0, 3).unwrap(), "xxx");
+    assert_eq!(array.string_prefix(1, 2).unwrap(), "ün");
+    assert_eq!(array.string_prefix(1, 10).unwrap(), "ünï");
+    assert!(array.string_prefix(2, 1).is_err());
+}
+
+#[test]
+fn test_binary() {
+    let mut builder = ArrayBuilder::try_new(2).unwrap();
+    builder.push_binary(b"\x00yason").unwrap();
+    builder.push_string("abc").unwrap();
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+
+    let array = yason.array().unwrap();
+    assert_eq!(array.type_of(0).unwrap(), DataType::Binary);
+    assert_eq!(array.binary(0).unwrap(), b"\x00yason");
+    assert!(array.binary(1).is_err());
+    assert!(array.binary(2).is_err());
+    assert!(array.string(0).is_err());
+    assert!(matches!(array.get(0).unwrap(), Value::Binary(b"\x00yason")));
+}
+
+#[test]
+fn test_typed_iter() {
+    let yason = YasonBuf::parse(r#"["a", "", "xyz"]"#).unwrap();
+    let array = yason.array().unwrap();
+    let iter = array.iter_strings().unwrap();
+    assert_eq!(iter.len(), 3);
+    let strings: Vec<_> = iter.map(|s| s.unwrap()).collect();
+    assert_eq!(strings, ["a", "", "xyz"]);
+    assert!(matches!(
+        array.iter_numbers().unwrap().next(),
+        Some(Err(YasonError::UnexpectedType { .. }))
+    ));
+
+    let yason = YasonBuf::parse("[1, -2.5, 1e10]").unwrap();
+    let numbers: Vec<_> = yason
+        .array()
+        .unwrap()
+        .iter_numbers()
+        .unwrap()
+        .map(|n| n.unwrap())
+        .collect();
+    assert_eq!(numbers, yason.array().unwrap().to_decimal_vec().unwrap());
+
+    let yason = YasonBuf::parse("[true, false, null, true]").unwrap();
+    let array = yason.array().unwrap();
+    let bools: Vec<_> = array.iter_bools().unwrap().map(|b| b.ok()).collect();
+    assert_eq!(bools, [Some(true), Some(false), None, Some(true)]);
+
+    let mut builder = ArrayBuilder::try_new(2).unwrap();
+    builder.push_binary([1, 2]).unwrap();
+    builder.push_binary([]).unwrap();
+    let yason = builder.finish().unwrap();
+    let binaries: Vec<_> = yason
+        .array()
+        .unwrap()
+        .iter_of::<&[u8]>()
+        .unwrap()
+        .map(|b| b.unwrap())
+        .collect();
+    assert_eq!(binaries, [&[1, 2][..], &[]]);
+
+    let yason = YasonBuf::parse("[]").unwrap();
+    assert!(yason.array().unwrap().iter_strings().unwrap().next().is_none());
+}
+
+#[test]
+fn test_entry_info() {
+    let yason = YasonBuf::parse(r#"{"a": [true, null, "xyz", 1.5], "b": {"c": false}}"#).unwrap();
+    let object = yason.object().unwrap();
+    let array = object.array("a").unwrap().unwrap();
+    let bytes = array.yason().as_bytes();
+
+    let info = array.entry_info(0).unwrap();
+    assert_eq!(info.data_type(), DataType::Bool);
+    assert!(info.is_inlined());
+    assert_eq!(info.entry_offset(), Some(info.offset()));
+    assert_eq!(info.encoded_len(), 2);
+    assert!(unsafe { Yason::new_unchecked(&bytes[info.range()]) }.bool().unwrap());
+
+    let info = array.entry_info(1).unwrap();
+    assert_eq!(info.data_type(), DataType::Null);
+    assert!(info.is_inlined());
+    assert_eq!(info.encoded_len(), 1);
+
+    let info = array.entry_info(2).unwrap();
+    assert_eq!(info.data_type(), DataType::String);
+    assert!(!info.is_inlined());
+    assert!(info.offset() > info.entry_offset().unwrap());
+    assert_eq!(
+        unsafe { Yason::new_unchecked(&bytes[info.range()]) }.string().unwrap(),
+        "xyz"
+    );
+
+    let info = array.entry_info(3).unwrap();
+    let number = unsafe { Yason::new_unchecked(&bytes[info.range()]) }.number().unwrap();
+    assert_eq!(number, Number::from_str("1.5").unwrap());
+
+    assert!(matches!(
+        array.entry_info(4),
+        Err(YasonError::IndexOutOfBounds { len: 4, index: 4 })
+    ));
+
+    let info = object.entry_info("b").unwrap().unwrap();
+    assert_eq!(info.data_type(), DataType::Object);
+    assert!(!info.is_inlined());
+    assert_eq!(info.entry_offset(), None);
+    let nested = unsafe { Yason::new_unchecked(&object.yason().as_bytes()[info.range()]) };
+    assert_eq!(nested.format(false).to_string(), r#"{"c":false}"#);
+    assert!(object.entry_info("d").unwrap().is_none());
+}
+
+#[test]
+fn test_array_bulk_push() {
+    let strings = ["a", "bc", ""];
+    let numbers = [Number::from(1), Number::from_str("-2.5").unwrap()];
+    let bools = [true, false];
+
+    let mut builder = ArrayBuilder::try_new(8).unwrap();
+    builder.push_strings(&strings).unwrap();
+    builder.push_null().unwrap();
+    builder.push_numbers(&numbers).unwrap().push_bools(&bools).unwrap();
+    let bulk = builder.finish().unwrap();
+    assert_eq!(
+        bulk.format(false).to_string(),
+        r#"["a","bc","",null,1,-2.5,true,false]"#
+    );
+
+    let mut builder = ArrayBuilder::try_new(8).unwrap();
+    for s in strings.iter() {
+        builder.push_string(s).unwrap();
+    }
+    builder.push_null().unwrap();
+    for n in numbers.iter() {
+        builder.push_number(n).unwrap();
+    }
+    for b in bools.iter() {
+        builder.push_bool(*b).unwrap();
+    }
+    assert_eq!(builder.finish().unwrap(), bulk);
+
+    // nothing is pushed if there are too many values
+    let mut builder = ArrayBuilder::try_new(2).unwrap();
+    builder.push_bool(true).unwrap();
+    let err = builder.push_strings(&strings).err().unwrap();
+    assert!(matches!(
+        err,
+        BuildError::InconsistentElementCount { expected: 2, actual: 4 }
+    ));
+    builder.push_strings(&["x"]).unwrap();
+    assert_eq!(builder.finish().unwrap().format(false).to_string(), r#"[true,"x"]"#);
+
+    // sets still check every value
+    let mut builder = ArrayBuilder::try_new_set(3, true).unwrap();
+    builder
+        .push_numbers(&[Number::from(1), Number::from_str("1.0").unwrap(), Number::from(2)])
+        .unwrap();
+    assert_eq!(builder.finish().unwrap().format(false).to_string(), "[1,2]");
+
+    let mut builder = ArrayBuilder::try_new(2).unwrap();
+    let mut inner = builder.push_array(1).unwrap();
+    inner.push_bools(&[true]).unwrap();
+    assert!(matches!(
+        builder.push_bools(&[false]),
+        Err(BuildError::InnerUncompletedError)
+    ));
+}
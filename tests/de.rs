@@ -0,0 +1,120 @@
+//! Projection deserializer tests
+
+#![cfg(feature = "serde")]
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use yason::{from_yason_paths, DeserializeError, FieldMap, PathExpression, YasonBuf, YasonError};
+
+fn path(text: &str) -> PathExpression {
+    PathExpression::from_str(text).unwrap()
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Status {
+    Active,
+    Disabled,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Order<'a> {
+    id: u64,
+    customer: &'a str,
+    total: f64,
+    status: Status,
+    items: Vec<Item>,
+    tags: BTreeMap<String, bool>,
+    note: Option<String>,
+    #[serde(default)]
+    priority: i32,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    sku: String,
+    qty: u32,
+}
+
+#[test]
+fn test_from_yason_paths() {
+    let yason = YasonBuf::parse(
+        r#"{
+            "order": {"id": 18446744073709551615, "status": "Active", "total": 12.5, "note": null},
+            "customer": {"name": "foo", "history": [1, 2, 3]},
+            "lines": [{"sku": "a", "qty": 1}, {"sku": "b", "qty": 2}],
+            "flags": {"gift": true, "rush": false},
+            "blob": "ignored"
+        }"#,
+    )
+    .unwrap();
+    let fields = FieldMap::new()
+        .with_field("id", path("$.order.id"))
+        .with_field("customer", path("$.customer.name"))
+        .with_field("total", path("$.order.total"))
+        .with_field("status", path("$.order.status"))
+        .with_field("items", path("$.lines"))
+        .with_field("tags", path("$.flags"))
+        .with_field("note", path("$.order.note"))
+        .with_field("priority", path("$.order.priority"));
+    assert_eq!(fields.len(), 8);
+
+    let order: Order = from_yason_paths(&yason, &fields).unwrap();
+    assert_eq!(
+        order,
+        Order {
+            id: u64::MAX,
+            customer: "foo",
+            total: 12.5,
+            status: Status::Active,
+            items: vec![
+                Item {
+                    sku: "a".to_string(),
+                    qty: 1
+                },
+                Item {
+                    sku: "b".to_string(),
+                    qty: 2
+                },
+            ],
+            tags: [("gift".to_string(), true), ("rush".to_string(), false)]
+                .into_iter()
+                .collect(),
+            note: None,
+            priority: 0,
+        }
+    );
+}
+
+#[test]
+fn test_from_yason_paths_errors() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Id {
+        id: i64,
+    }
+
+    let yason = YasonBuf::parse(r#"{"a": [{"id": 1}, {"id": "2"}]}"#).unwrap();
+
+    // a required field whose path selects nothing
+    let fields = FieldMap::new().with_field("id", path("$.b"));
+    let err = from_yason_paths::<Id>(&yason, &fields).unwrap_err();
+    assert!(matches!(err, DeserializeError::Custom(msg) if msg == "missing field `id`"));
+
+    // a value of the wrong type
+    let fields = FieldMap::new().with_field("id", path("$.a[1].id"));
+    assert!(matches!(
+        from_yason_paths::<Id>(&yason, &fields),
+        Err(DeserializeError::Custom(_))
+    ));
+
+    // a path selecting more than one value
+    let fields = FieldMap::new().with_field("id", path("$.a[*].id"));
+    assert!(matches!(
+        from_yason_paths::<Id>(&yason, &fields),
+        Err(DeserializeError::Yason(YasonError::MultiValuesWithoutWrapper))
+    ));
+
+    let fields = FieldMap::new().with_field("id", path("$.a[0].id"));
+    assert_eq!(from_yason_paths::<Id>(&yason, &fields).unwrap().id, 1);
+}
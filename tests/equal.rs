@@ -1,6 +1,11 @@
 //! Yason cmp tests
 
-use yason::YasonBuf;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use yason::{
+    num, ArrayBuilder, BinaryCollation, Collation, Date, Number, ObjectBuilder, Scalar, StepBudget, Timestamp, Value,
+    YasonBuf, YasonError,
+};
 
 fn assert_equal(left: &str, right: &str, expected: bool) {
     let left = YasonBuf::parse(left).unwrap();
@@ -37,3 +42,326 @@ fn test_yason_equal() {
         false,
     )
 }
+
+#[test]
+fn test_yason_equals_bytes_and_structural() {
+    let mut sorted = ObjectBuilder::try_new(2, true).unwrap();
+    sorted.push_bool("a", true).unwrap();
+    sorted.push_null("bb").unwrap();
+    let sorted = sorted.finish().unwrap();
+
+    let mut unsorted = ObjectBuilder::try_new(2, false).unwrap();
+    unsorted.push_null("bb").unwrap();
+    unsorted.push_bool("a", true).unwrap();
+    let unsorted = unsorted.finish().unwrap();
+
+    assert!(sorted.equals_bytes(&sorted));
+    assert!(sorted.equals_structural(&sorted).unwrap());
+
+    assert!(!sorted.equals_bytes(&unsorted));
+    assert!(sorted.equals_structural(&unsorted).unwrap());
+    assert!(sorted.equals(&unsorted).unwrap());
+
+    let other = YasonBuf::parse(r#"{"a": false, "bb": null}"#).unwrap();
+    assert!(!sorted.equals_bytes(&other));
+    assert!(!sorted.equals_structural(&other).unwrap());
+    assert!(!sorted.equals(&other).unwrap());
+}
+
+#[test]
+fn test_equals_with_budget() {
+    let left = YasonBuf::parse(r#"{"a": [1, 2, 3], "b": "x"}"#).unwrap();
+    let right = ObjectBuilder::from_pairs(&[]).unwrap();
+    let mut budget = StepBudget::new(1);
+    assert!(!left.equals_with_budget(&right, &mut budget).unwrap());
+
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap();
+    builder.push_string("b", "x").unwrap();
+    let mut array = builder.push_array("a", 3).unwrap();
+    array.push_number(Number::from(1)).unwrap();
+    array.push_number(Number::from(2)).unwrap();
+    array.push_number(Number::from(3)).unwrap();
+    array.finish().unwrap();
+    let right = builder.finish().unwrap();
+
+    let mut budget = StepBudget::new(3);
+    assert!(matches!(
+        left.equals_with_budget(&right, &mut budget),
+        Err(YasonError::Cancelled)
+    ));
+    let mut budget = StepBudget::new(6);
+    assert!(left.equals_with_budget(&right, &mut budget).unwrap());
+    assert_eq!(budget.remaining(), 0);
+}
+
+#[test]
+fn test_equals_short_circuit() {
+    // the later scalar is compared before descending into the nested arrays
+    let left = YasonBuf::parse(r#"{"a": [[1, 2, 3], [4, 5, 6]], "b": 1}"#).unwrap();
+    let right = YasonBuf::parse(r#"{"a": [[1, 2, 3], [4, 5, 6]], "b": 2}"#).unwrap();
+    let mut budget = StepBudget::new(3);
+    assert!(!left.equals_with_budget(&right, &mut budget).unwrap());
+    assert_eq!(budget.remaining(), 0);
+
+    // the element counts of the nested arrays are compared before their elements
+    let right = YasonBuf::parse(r#"{"a": [[1, 2, 3], [4, 5]], "b": 1}"#).unwrap();
+    let mut budget = StepBudget::new(5);
+    assert!(!left.equals_with_budget(&right, &mut budget).unwrap());
+
+    let right = YasonBuf::parse(r#"{"a": [[1, 2, 3], [4, 5, 7]], "b": 1}"#).unwrap();
+    assert!(!left.equals(&right).unwrap());
+    assert!(!right.equals(&left).unwrap());
+}
+
+#[test]
+fn test_equals_within() {
+    let left = YasonBuf::parse(r#"{"a": [1, 2, 3], "b": "x"}"#).unwrap();
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap();
+    builder.push_string("b", "x").unwrap();
+    let mut array = builder.push_array("a", 3).unwrap();
+    array.push_number(Number::from(1)).unwrap();
+    array.push_number(Number::from(2)).unwrap();
+    array.push_number(Number::from(3)).unwrap();
+    array.finish().unwrap();
+    let right = builder.finish().unwrap();
+
+    assert_eq!(left.equals_within(&right, 5).unwrap(), None);
+    assert_eq!(left.equals_within(&right, 6).unwrap(), Some(true));
+    // identical bytes are equal without any step
+    assert_eq!(left.equals_within(&left, 0).unwrap(), Some(true));
+
+    let other = YasonBuf::parse(r#"{"a": [1, 2, 3], "b": "y"}"#).unwrap();
+    assert_eq!(left.equals_within(&other, 3).unwrap(), Some(false));
+}
+
+#[test]
+fn test_value_equals() {
+    let left = YasonBuf::parse(r#"{"a": [1, "x", {"b": null}], "c": true}"#).unwrap();
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap();
+    builder.push_bool("c", true).unwrap();
+    let mut array = builder.push_array("a", 3).unwrap();
+    array.push_number(Number::from_str("1.00").unwrap()).unwrap();
+    array.push_string("x").unwrap();
+    let mut object = array.push_object(1, true).unwrap();
+    object.push_null("b").unwrap();
+    object.finish().unwrap();
+    array.finish().unwrap();
+    let right = builder.finish().unwrap();
+
+    let left = Value::try_from(left.as_ref()).unwrap();
+    let right = Value::try_from(right.as_ref()).unwrap();
+    assert!(left.equals(&right).unwrap());
+    assert_eq!(left, right);
+
+    let other = YasonBuf::parse(r#"{"a": [1, "x", {"b": false}], "c": true}"#).unwrap();
+    assert_ne!(left, Value::try_from(other.as_ref()).unwrap());
+    let array = YasonBuf::parse(r#"[1, "x", {"b": null}]"#).unwrap();
+    assert_ne!(left, Value::try_from(array.as_ref()).unwrap());
+
+    assert_eq!(Value::Null, Value::Null);
+    assert_eq!(Value::Binary(b"ab"), Value::Binary(b"ab"));
+    assert_ne!(Value::Bool(true), Value::Bool(false));
+    assert_ne!(Value::String("1"), Value::Number(Number::from(1)));
+    assert_ne!(Value::Null, Value::Bool(false));
+}
+
+struct CaseInsensitive;
+
+impl Collation for CaseInsensitive {
+    fn compare(&self, left: &str, right: &str) -> Ordering {
+        left.to_lowercase().cmp(&right.to_lowercase())
+    }
+}
+
+#[test]
+fn test_compare_with_collation() {
+    let a = YasonBuf::parse(r#""abc""#).unwrap();
+    let b = YasonBuf::parse(r#""ABD""#).unwrap();
+    assert_eq!(a.compare(&b).unwrap(), Some(Ordering::Greater));
+    assert_eq!(a.compare_with(&b, &CaseInsensitive).unwrap(), Some(Ordering::Less));
+
+    let one = YasonBuf::parse("1").unwrap();
+    let two = YasonBuf::parse("2.0").unwrap();
+    assert_eq!(one.compare(&two).unwrap(), Some(Ordering::Less));
+    assert_eq!(one.compare(&a).unwrap(), None);
+    let array = YasonBuf::parse("[1]").unwrap();
+    assert_eq!(array.compare(&array).unwrap(), None);
+
+    let object = YasonBuf::parse(r#"{"Name": "abc", "id": 1}"#).unwrap();
+    let object = object.object().unwrap();
+    assert!(object.get("name").unwrap().is_none());
+    assert!(object.get_with_collation("name", &BinaryCollation).unwrap().is_none());
+    assert!(matches!(
+        object.get_with_collation("name", &CaseInsensitive).unwrap(),
+        Some(Value::String("abc"))
+    ));
+
+    let array = YasonBuf::parse(r#"["apple", "Banana", "cherry"]"#).unwrap();
+    let array = array.array().unwrap();
+    assert_eq!(array.binary_search_str("banana", &CaseInsensitive).unwrap(), Ok(1));
+    assert_eq!(array.binary_search_str("blueberry", &CaseInsensitive).unwrap(), Err(2));
+    let mixed = YasonBuf::parse(r#"["a", 1, "c"]"#).unwrap();
+    assert!(mixed.array().unwrap().binary_search_str("b", &BinaryCollation).is_err());
+}
+
+#[test]
+fn test_num_coercion() {
+    let one = Number::from(1);
+    let one_scaled: Number = "1.00".parse().unwrap();
+    assert!(num::equals(&one, &one_scaled));
+    assert_equal("1", "1.00", true);
+    assert_eq!(
+        YasonBuf::parse("1")
+            .unwrap()
+            .compare(YasonBuf::parse("1.0").unwrap())
+            .unwrap(),
+        Some(Ordering::Equal)
+    );
+
+    assert_eq!(num::compare_i64(&one_scaled, 1), Ordering::Equal);
+    assert_eq!(num::compare_i64(&one, 2), Ordering::Less);
+    assert_eq!(num::from_u64(u64::MAX).to_string(), "18446744073709551615");
+
+    assert_eq!(num::from_f64(0.1).unwrap().to_string(), "0.1");
+    assert!(num::from_f64(f64::NAN).is_none());
+    assert!(num::from_f64(1e300).is_none());
+    assert_eq!(num::compare_f64(&one, 1.0), Some(Ordering::Equal));
+    assert_eq!(num::compare_f64(&one, 0.5), Some(Ordering::Greater));
+    assert_eq!(num::compare_f64(&one, f64::NAN), None);
+    assert_eq!(num::compare_f64(&one, f64::INFINITY), Some(Ordering::Less));
+    assert_eq!(num::compare_f64(&one, -1e300), Some(Ordering::Greater));
+    assert_eq!(num::compare_f64(&Number::ZERO, 1e-300), Some(Ordering::Less));
+    assert_eq!(num::compare_f64(&one, -1e-300), Some(Ordering::Greater));
+}
+
+#[test]
+fn test_canonicalize() {
+    let canonical = |yason: &YasonBuf| yason.canonicalize().unwrap().into_bytes();
+
+    let mut builder = ObjectBuilder::try_new(3, false).unwrap();
+    builder.push_number("b", "1.50".parse::<Number>().unwrap()).unwrap();
+    builder.push_string("aa", "x").unwrap();
+    builder.push_number("c", "-0".parse::<Number>().unwrap()).unwrap();
+    let mut left = builder.finish().unwrap();
+    let right = YasonBuf::parse(r#"{"aa": "x", "b": 1.5, "c": 0}"#).unwrap();
+    assert!(!left.equals_bytes(&right));
+    assert_eq!(canonical(&left), canonical(&right));
+
+    // the header, the checksum footer and shared values are not part of the canonical form
+    left.add_header().unwrap();
+    left.add_checksum().unwrap();
+    assert_eq!(canonical(&left), canonical(&right));
+    let array = YasonBuf::parse(r#"[{"k": "long value"}, {"k": "long value"}, 1e2, 100]"#).unwrap();
+    let deduplicated = array.deduplicate().unwrap();
+    assert_ne!(deduplicated.as_bytes(), array.as_bytes());
+    assert_eq!(canonical(&deduplicated), canonical(&array));
+
+    let canonical_yason = array.canonicalize().unwrap();
+    assert!(canonical_yason.is_canonical().unwrap());
+    assert!(!left.is_canonical().unwrap());
+    assert!(canonical_yason.equals(&array).unwrap());
+
+    let other = YasonBuf::parse(r#"{"aa": "x", "b": 1.51, "c": 0}"#).unwrap();
+    assert_ne!(canonical(&other), canonical(&right));
+    let scalar = YasonBuf::parse("12.3400").unwrap();
+    assert_eq!(canonical(&scalar), canonical(&YasonBuf::parse("12.34").unwrap()));
+}
+
+#[test]
+fn test_equals_unordered() {
+    let assert_unordered = |left: &str, right: &str, expected: bool| {
+        let left = YasonBuf::parse(left).unwrap();
+        let right = YasonBuf::parse(right).unwrap();
+        assert_eq!(left.equals_unordered(&right).unwrap(), expected);
+        assert_eq!(right.equals_unordered(&left).unwrap(), expected);
+    };
+
+    assert_unordered("[1, 2, 3]", "[3, 1, 2]", true);
+    assert_unordered("[1, 1, 2]", "[1, 2, 2]", false);
+    assert_unordered("[1, 2]", "[1, 2, 2]", false);
+    assert_unordered("[1.50, 2]", "[2, 1.5]", true);
+    assert_unordered(r#"[[1, 2], {"a": [3, 4]}]"#, r#"[{"a": [4, 3]}, [2, 1]]"#, true);
+    assert_unordered(
+        r#"{"a": [true, null], "b": "x"}"#,
+        r#"{"b": "x", "a": [null, true]}"#,
+        true,
+    );
+    assert_unordered(r#"{"a": [true, null]}"#, r#"{"a": [null, false]}"#, false);
+    assert_unordered(r#"{"a": 1}"#, "[1]", false);
+    assert_unordered(r#""abc""#, r#""abc""#, true);
+    assert_unordered("[]", "[]", true);
+}
+
+#[test]
+fn test_contains_document() {
+    let assert_contains = |doc: &str, needle: &str, expected: bool| {
+        let doc = YasonBuf::parse(doc).unwrap();
+        let needle = YasonBuf::parse(needle).unwrap();
+        assert_eq!(doc.contains_document(&needle).unwrap(), expected);
+    };
+
+    assert_contains(r#""foo""#, r#""foo""#, true);
+    assert_contains("1.50", "1.5", true);
+    assert_contains("1", "2", false);
+    assert_contains("[1, 2, 3]", "[3, 1]", true);
+    assert_contains("[1, 2, 3]", "[1, 1]", true);
+    assert_contains("[1, 2, 3]", "[1, 4]", false);
+    assert_contains("[1, 2, 3]", "[]", true);
+    assert_contains("[1, [2, 3]]", "[[3]]", true);
+    assert_contains("[1, [2, 3]]", "[3]", false);
+    assert_contains(r#"["foo", "bar"]"#, r#""foo""#, true);
+    assert_contains(r#"{"a": ["foo"]}"#, r#"{"a": "foo"}"#, false);
+    assert_contains(r#"{"a": 1, "b": {"c": 2, "d": 3}}"#, r#"{"b": {"d": 3}}"#, true);
+    assert_contains(r#"{"a": 1, "b": {"c": 2, "d": 3}}"#, r#"{"b": {"d": 4}}"#, false);
+    assert_contains(r#"{"a": 1}"#, r#"{"a": 1, "b": 2}"#, false);
+    assert_contains(r#"{"a": 1}"#, "{}", true);
+    assert_contains(r#"{"a": 1}"#, "[]", false);
+    assert_contains("[]", "{}", false);
+    assert_contains(r#"[{"a": 1, "b": 2}, {"a": 3}]"#, r#"[{"a": 3}, {"b": 2}]"#, true);
+
+    let doc = YasonBuf::parse("[1, 2, 3, 4]").unwrap();
+    let needle = YasonBuf::parse("[4]").unwrap();
+    let result = doc.contains_document_with_budget(&needle, &mut StepBudget::new(3));
+    assert!(matches!(result, Err(YasonError::Cancelled)));
+}
+
+#[test]
+fn test_contains_document_binary_and_temporal() {
+    let mut builder = ObjectBuilder::try_new(3, false).unwrap();
+    builder.push_binary("bin", [1u8, 2]).unwrap();
+    builder.push_date("date", Date::from_micros(0)).unwrap();
+    let mut array = builder.push_array("list", 2).unwrap();
+    array.push_binary([3u8]).unwrap();
+    array.push_timestamp(Timestamp::from_micros(1)).unwrap();
+    array.finish().unwrap();
+    let doc = builder.finish().unwrap();
+
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    builder.push_binary("bin", [1u8, 2]).unwrap();
+    assert!(doc.contains_document(builder.finish().unwrap()).unwrap());
+
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    builder.push_binary("bin", [1u8]).unwrap();
+    assert!(!doc.contains_document(builder.finish().unwrap()).unwrap());
+
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    builder.push_date("date", Date::from_micros(0)).unwrap();
+    assert!(doc.contains_document(builder.finish().unwrap()).unwrap());
+
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    builder.push_timestamp("date", Timestamp::from_micros(0)).unwrap();
+    assert!(!doc.contains_document(builder.finish().unwrap()).unwrap());
+
+    let mut builder = ArrayBuilder::try_new(2).unwrap();
+    builder.push_binary([3u8]).unwrap();
+    builder.push_timestamp(Timestamp::from_micros(1)).unwrap();
+    let list = builder.finish().unwrap();
+    assert!(list.contains_document(Scalar::binary([3u8]).unwrap()).unwrap());
+    assert!(list
+        .contains_document(Scalar::timestamp(Timestamp::from_micros(1)).unwrap())
+        .unwrap());
+    assert!(!list.contains_document(Scalar::binary([4u8]).unwrap()).unwrap());
+    assert!(!list
+        .contains_document(Scalar::date(Date::from_micros(0)).unwrap())
+        .unwrap());
+}
@@ -1,6 +1,12 @@
 //! Yason format tests
 
-use yason::YasonBuf;
+use std::fmt::{self, Write};
+use yason::raw::{ARRAY_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, VALUE_ENTRY_SIZE};
+use yason::{
+    format_escaped_str, format_escaped_str_with, BinaryFormat, CompactFormatter, DataType, EscapeProfile, FormatError,
+    FormatResult, Formatter, Number, ObjectBuilder, PrettyFormatter, Scalar, StepBudget, Timestamp, TruncateOptions,
+    Value, Yason, YasonBuf, YasonError,
+};
 
 fn assert_fmt(input: &str, expected: &str, pretty: bool) {
     let yason_buf = YasonBuf::parse(input).unwrap();
@@ -240,3 +246,392 @@ fn test_pretty_fmt() {
         );
     }
 }
+
+fn assert_truncated_fmt(input: &str, options: TruncateOptions, expected: &str, pretty: bool) {
+    let yason_buf = YasonBuf::parse(input).unwrap();
+    let yason = yason_buf.as_ref();
+    assert_eq!(format!("{}", yason.format_truncated(pretty, options)), expected)
+}
+
+#[test]
+fn test_truncated_fmt() {
+    let unlimited = TruncateOptions::default();
+    assert_truncated_fmt(
+        r#"{"key1": [1, 2, 3], "key2": "abcdef"}"#,
+        unlimited,
+        r#"{"key1":[1,2,3],"key2":"abcdef"}"#,
+        false,
+    );
+
+    // string
+    {
+        let options = TruncateOptions::new(usize::MAX, usize::MAX, 3);
+        assert_truncated_fmt(r#""abcdef""#, options, r#""abc…(3 more)""#, false);
+        assert_truncated_fmt(r#""abc""#, options, r#""abc""#, false);
+        assert_truncated_fmt(r#""测试测试""#, options, r#""测试测…(1 more)""#, false);
+        assert_truncated_fmt(
+            r#"{"abcdef": "abcdef"}"#,
+            options,
+            r#"{"abcdef":"abc…(3 more)"}"#,
+            false,
+        );
+    }
+
+    // array items
+    {
+        let options = TruncateOptions::new(usize::MAX, 2, usize::MAX);
+        assert_truncated_fmt(r#"[1, 2, 3, 4, 5]"#, options, r#"[1,2,…(3 more)]"#, false);
+        assert_truncated_fmt(r#"[1, 2]"#, options, r#"[1,2]"#, false);
+        assert_truncated_fmt(
+            r#"[[1, 2, 3], 2, 3]"#,
+            options,
+            r#"[[1,2,…(1 more)],2,…(1 more)]"#,
+            false,
+        );
+        assert_truncated_fmt(r#"[1, 2, 3]"#, options, "[\n  1,\n  2,\n  …(1 more)\n]", true);
+
+        let options = TruncateOptions::new(usize::MAX, 0, usize::MAX);
+        assert_truncated_fmt(r#"[1, 2, 3]"#, options, r#"[…(3 more)]"#, false);
+        assert_truncated_fmt(r#"[]"#, options, r#"[]"#, false);
+    }
+
+    // depth
+    {
+        let options = TruncateOptions::new(1, usize::MAX, usize::MAX);
+        assert_truncated_fmt(
+            r#"{"key1": {"key": 1}, "key2": [1, 2], "key3": {}, "key4": 1}"#,
+            options,
+            r#"{"key1":{…(1 more)},"key2":[…(2 more)],"key3":{},"key4":1}"#,
+            false,
+        );
+        assert_truncated_fmt(
+            r#"{"key1": {"key": 1}}"#,
+            options,
+            "{\n  \"key1\" : \n  {\n    …(1 more)\n  }\n}",
+            true,
+        );
+
+        let options = TruncateOptions::new(0, usize::MAX, usize::MAX);
+        assert_truncated_fmt(r#"[1, 2]"#, options, r#"[…(2 more)]"#, false);
+        assert_truncated_fmt(r#"123"#, options, r#"123"#, false);
+    }
+}
+
+#[test]
+fn test_format_with_budget() {
+    let yason = YasonBuf::parse(r#"{"a": [1, 2, 3], "b": "x"}"#).unwrap();
+
+    let mut buf = String::new();
+    let mut budget = StepBudget::new(6);
+    yason.format_to_with_budget(false, &mut budget, &mut buf).unwrap();
+    assert_eq!(buf, r#"{"a":[1,2,3],"b":"x"}"#);
+
+    let mut buf = String::new();
+    let mut budget = StepBudget::new(5);
+    let res = yason.format_to_with_budget(true, &mut budget, &mut buf);
+    assert!(matches!(res, Err(FormatError::YasonError(YasonError::Cancelled))));
+}
+
+#[test]
+fn test_display() {
+    let yason = YasonBuf::parse(r#"{"a": [1, 2], "b": "x"}"#).unwrap();
+    assert_eq!(yason.to_string(), r#"{"a":[1,2],"b":"x"}"#);
+    assert_eq!(yason.as_ref().to_string(), r#"{"a":[1,2],"b":"x"}"#);
+    assert_eq!(
+        yason.to_pretty_string().unwrap(),
+        "{\n  \"a\" : \n  [\n    1,\n    2\n  ],\n  \"b\" : \"x\"\n}"
+    );
+
+    struct FailingWriter;
+
+    impl fmt::Write for FailingWriter {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let lazy = yason.format(false);
+    assert!(lazy.take_error().is_none());
+    assert!(write!(FailingWriter, "{}", lazy).is_err());
+    assert!(matches!(lazy.take_error(), Some(FormatError::FmtError(_))));
+    assert!(lazy.take_error().is_none());
+}
+
+#[test]
+fn test_custom_formatter() {
+    struct QuotedNumberFormatter;
+
+    impl Formatter for QuotedNumberFormatter {
+        fn write_number<W: fmt::Write>(&mut self, value: &Number, writer: &mut W) -> FormatResult<()> {
+            write!(writer, "\"{}\"", value)?;
+            Ok(())
+        }
+    }
+
+    let yason = YasonBuf::parse(r#"{"a": [1, 2.5], "b": "x\n"}"#).unwrap();
+
+    let mut buf = String::new();
+    QuotedNumberFormatter.format(&yason, &mut buf).unwrap();
+    assert_eq!(buf, r#"{"a":["1","2.5"],"b":"x\n"}"#);
+
+    let mut buf = String::new();
+    CompactFormatter::new().format(&yason, &mut buf).unwrap();
+    assert_eq!(buf, yason.format(false).to_string());
+
+    let mut buf = String::new();
+    PrettyFormatter::new().format(&yason, &mut buf).unwrap();
+    assert_eq!(buf, yason.format(true).to_string());
+
+    let values = [Value::Number(Number::from(1)), Value::Null, Value::String("s")];
+    let mut buf = String::new();
+    QuotedNumberFormatter.write_values(&values, &mut buf).unwrap();
+    assert_eq!(buf, r#"["1",null,"s"]"#);
+
+    let mut buf = String::new();
+    format_escaped_str("a\"b", &mut buf).unwrap();
+    assert_eq!(buf, r#"a\"b"#);
+}
+
+#[test]
+fn test_escape_profile() {
+    let yason = YasonBuf::parse(r#"{"<k>": "</script>&\u2028\u2029\u2027\"é"}"#).unwrap();
+
+    let json = yason.format_escaped(false, EscapeProfile::JsonSafe).to_string();
+    assert_eq!(json, yason.format(false).to_string());
+    assert_eq!(json, "{\"<k>\":\"</script>&\u{2028}\u{2029}\u{2027}\\\"é\"}");
+
+    let js = yason.format_escaped(false, EscapeProfile::JsSafe).to_string();
+    assert_eq!(js, "{\"<k>\":\"</script>&\\u2028\\u2029\u{2027}\\\"é\"}");
+
+    let html = yason.format_escaped(false, EscapeProfile::HtmlSafe).to_string();
+    assert_eq!(
+        html,
+        "{\"\\u003Ck\\u003E\":\"\\u003C/script\\u003E\\u0026\\u2028\\u2029\u{2027}\\\"é\"}"
+    );
+    assert_eq!(YasonBuf::parse(&html).unwrap(), yason);
+
+    let mut buf = String::new();
+    yason
+        .format_escaped_to(true, EscapeProfile::HtmlSafe, &mut buf)
+        .unwrap();
+    assert_eq!(YasonBuf::parse(&buf).unwrap(), yason);
+
+    let mut buf = String::new();
+    format_escaped_str_with("a\u{2028}", EscapeProfile::JsSafe, &mut buf).unwrap();
+    assert_eq!(buf, "a\\u2028");
+}
+
+#[test]
+fn test_dump_layout() {
+    let yason = YasonBuf::parse(r#"{"a": [true, "xy"], "b": 1}"#).unwrap();
+    let mut dump = String::new();
+    yason.dump_layout(&mut dump).unwrap();
+    let expected = r#"000000  01                          object
+000001  28 00 00 00                   size 40
+000005  02 00                         element count 2
+000007  0a 00 00 00                   key offset [0] 10 -> 00000f
+00000b  22 00 00 00                   key offset [1] 34 -> 000027
+00000f  01 00 61                      key "a"
+000012  02                            array
+000013  10 00 00 00                     size 16
+000017  02 00                           element count 2
+000019  05 01 00 00 00                  entry [0] boolean true
+00001e  03 0c 00 00 00                  entry [1] string offset 12 -> 000023
+000023  03                              string
+000024  02                                data length 2
+000025  78 79                             "xy"
+000027  01 00 62                      key "b"
+00002a  04                            number
+00002b  01                              number length 1
+00002c  01                              1
+"#;
+    assert_eq!(dump, expected);
+
+    // truncated bytes are dumped up to the first unreadable field
+    let mut dump = String::new();
+    let truncated = unsafe { yason::Yason::new_unchecked(&yason.as_bytes()[..0x24]) };
+    assert!(truncated.dump_layout(&mut dump).is_err());
+    assert!(dump.ends_with("000023  03                              string\n"));
+}
+
+#[test]
+fn test_number_fmt() {
+    let json = "[0, -0, 1, -1, 100, 1e3, 1.0, 1.50, -2.5e-3, 9223372036854775807, -9223372036854775808, \
+                9223372036854775808, 123456789012345678901234567890, -1.2345678901234567890123456789e-100, \
+                9.87654321e120, 0.00000000000000000000000000000000000000000012345]";
+    let yason = YasonBuf::parse(json).unwrap();
+    let array = yason.array().unwrap();
+    // the formatters reuse their scratch buffers across numbers
+    let mut compact = CompactFormatter::new();
+    let mut pretty = PrettyFormatter::new();
+    for value in array.iter().unwrap() {
+        let number = match value.unwrap() {
+            Value::Number(number) => number,
+            _ => unreachable!(),
+        };
+        let mut expected = String::new();
+        number.format_to_json(&mut expected).unwrap();
+        let mut buf = String::new();
+        compact.write_number(&number, &mut buf).unwrap();
+        assert_eq!(buf, expected);
+        buf.clear();
+        pretty.write_number(&number, &mut buf).unwrap();
+        assert_eq!(buf, expected);
+    }
+
+    let mut buf = String::new();
+    yason.format_to(false, &mut buf).unwrap();
+    assert!(buf.ends_with(",-1.2345678901234567890123456789E-100,9.87654321E+120,1.2345E-43]"));
+
+    struct HexFormatter;
+
+    impl Formatter for HexFormatter {
+        fn write_i64<W: fmt::Write>(&mut self, value: i64, writer: &mut W) -> FormatResult<()> {
+            write!(writer, "\"{:#x}\"", value)?;
+            Ok(())
+        }
+    }
+
+    let yason = YasonBuf::parse("[255, 1.5, 18446744073709551616]").unwrap();
+    let mut buf = String::new();
+    HexFormatter.format(&yason, &mut buf).unwrap();
+    assert_eq!(buf, r#"["0xff",1.5,18446744073709551616]"#);
+
+    let mut buf = String::new();
+    let mut formatter = CompactFormatter::new();
+    formatter.write_i64(i64::MIN, &mut buf).unwrap();
+    assert_eq!(buf, "-9223372036854775808");
+}
+
+#[test]
+fn test_binary_fmt() {
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap();
+    builder.push_binary("a", b"yason").unwrap();
+    let mut array = builder.push_array("b", 2).unwrap();
+    array.push_binary([]).unwrap();
+    array.push_binary([0xfbu8, 0xff]).unwrap();
+    array.finish().unwrap();
+    let yason = builder.finish().unwrap();
+
+    assert_eq!(yason.format(false).to_string(), r#"{"a":"eWFzb24=","b":["","+/8="]}"#);
+    assert_eq!(
+        yason.format_binary(false, BinaryFormat::Hex).to_string(),
+        r#"{"a":"7961736f6e","b":["","fbff"]}"#
+    );
+    assert_eq!(
+        yason.format_binary(true, BinaryFormat::Base64).to_string(),
+        "{\n  \"a\" : \"eWFzb24=\",\n  \"b\" : \n  [\n    \"\",\n    \"+/8=\"\n  ]\n}"
+    );
+
+    let mut s = String::new();
+    let err = yason.format_binary_to(false, BinaryFormat::Error, &mut s).unwrap_err();
+    assert!(matches!(err, FormatError::UnsupportedBinary));
+
+    let yason = YasonBuf::parse(r#"{"a":"text"}"#).unwrap();
+    assert_eq!(
+        yason.format_binary(false, BinaryFormat::Error).to_string(),
+        r#"{"a":"text"}"#
+    );
+}
+
+#[test]
+fn test_pretty_indent() {
+    // nested single-element arrays around 1, deeper than the builders allow
+    const DEPTH: usize = 150;
+    const HEADER_SIZE: usize = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE;
+    let leaf = YasonBuf::parse("1").unwrap();
+    let mut bytes = Vec::new();
+    for level in 0..DEPTH {
+        let inner_size = (DEPTH - 1 - level) * HEADER_SIZE + leaf.as_bytes().len();
+        let inner_type = if level + 1 < DEPTH {
+            DataType::Array
+        } else {
+            DataType::Number
+        };
+        bytes.push(DataType::Array as u8);
+        bytes.extend_from_slice(&((ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE + inner_size) as i32).to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.push(inner_type as u8);
+        bytes.extend_from_slice(&((ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE) as u32).to_le_bytes());
+    }
+    bytes.extend_from_slice(leaf.as_bytes());
+    let yason = unsafe { Yason::new_unchecked(&bytes) };
+
+    let mut buf = String::new();
+    PrettyFormatter::new().format(yason, &mut buf).unwrap();
+    let deepest = format!("\n{}1\n", " ".repeat(2 * DEPTH));
+    assert!(buf.contains(&deepest));
+    assert!(buf.ends_with("\n  ]\n]"));
+
+    let mut buf = String::new();
+    PrettyFormatter::with_indent("\t").format(yason, &mut buf).unwrap();
+    let deepest = format!("\n{}1\n", "\t".repeat(DEPTH));
+    assert!(buf.contains(&deepest));
+
+    let yason = YasonBuf::parse(r#"{"a": {"b": [1, {}]}}"#).unwrap();
+    let mut buf = String::new();
+    PrettyFormatter::with_indent("    ").format(&yason, &mut buf).unwrap();
+    assert_eq!(
+        buf,
+        "{\n    \"a\" : \n    {\n        \"b\" : \n        [\n            1,\n            {\n            }\n        ]\n    }\n}"
+    );
+}
+
+#[test]
+fn test_format_to_vec() {
+    let inputs = [
+        r#"{"a": [1, 2.5, "x\ny"], "b": {"c": null, "d": true}, "名前": "値"}"#,
+        r#"[]"#,
+        r#""abc""#,
+    ];
+    for input in inputs {
+        let yason = YasonBuf::parse(input).unwrap();
+        for pretty in [false, true] {
+            let mut expected = String::new();
+            yason.format_to(pretty, &mut expected).unwrap();
+
+            let mut buf = vec![0xff];
+            yason.format_to_vec(pretty, &mut buf).unwrap();
+            assert_eq!(buf[0], 0xff);
+            assert_eq!(&buf[1..], expected.as_bytes());
+        }
+
+        let mut buf = Vec::new();
+        PrettyFormatter::new().format_to_vec(&yason, &mut buf).unwrap();
+        assert_eq!(buf, yason.to_pretty_string().unwrap().into_bytes());
+    }
+}
+
+#[test]
+fn test_format_compact_scalar() {
+    let inputs = [
+        "0",
+        "-9223372036854775808",
+        "12345678901234567890123",
+        "-0.000123",
+        "1.5e-30",
+        r#""a\"b\u0001\n""#,
+        r#""名前""#,
+        "true",
+        "false",
+        "null",
+        r#"{"a": [1, 2.5]}"#,
+        "[]",
+    ];
+    for input in inputs {
+        let yason = YasonBuf::parse(input).unwrap();
+        let mut buf = String::new();
+        yason.format_compact_scalar(&mut buf).unwrap();
+        assert_eq!(buf, yason.format(false).to_string());
+    }
+
+    let scalars = [
+        Scalar::binary(b"\x00\xff").unwrap(),
+        Scalar::timestamp(Timestamp::from_micros(1_709_214_330_500_000)).unwrap(),
+    ];
+    for yason in scalars.iter() {
+        let mut buf = String::new();
+        yason.format_compact_scalar(&mut buf).unwrap();
+        assert_eq!(buf, yason.format(false).to_string());
+    }
+}
@@ -0,0 +1,73 @@
+//! Framing tests
+
+use yason::frame::{self, FrameError, FrameReader};
+use yason::{YasonBuf, YasonError};
+
+fn framed(docs: &[YasonBuf]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for doc in docs {
+        frame::write(&mut bytes, doc).unwrap();
+    }
+    bytes
+}
+
+#[test]
+fn test_frame_read_write() {
+    let docs = vec![
+        YasonBuf::parse(r#"{"a": [1, "x"], "b": null}"#).unwrap(),
+        YasonBuf::parse("\"s\"").unwrap(),
+        YasonBuf::parse("[]").unwrap(),
+    ];
+    let bytes = framed(&docs);
+    let len: usize = docs.iter().map(|doc| frame::frame_len(doc)).sum();
+    assert_eq!(bytes.len(), len);
+    assert_eq!(
+        &bytes[..frame::LENGTH_SIZE],
+        &(docs[0].as_bytes().len() as u32).to_le_bytes()
+    );
+
+    let sliced = frame::iter_slice(&bytes).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(sliced, docs.iter().map(|doc| doc.as_ref()).collect::<Vec<_>>());
+
+    let read = FrameReader::new(bytes.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(read, docs);
+
+    assert_eq!(frame::iter_slice(&[]).count(), 0);
+    assert!(FrameReader::new(&[][..]).read_frame().unwrap().is_none());
+}
+
+#[test]
+fn test_frame_errors() {
+    let docs = vec![YasonBuf::parse("[1, 2]").unwrap(), YasonBuf::parse("true").unwrap()];
+    let bytes = framed(&docs);
+
+    // truncated inside the length and inside the document
+    for end in [bytes.len() - 1, frame::frame_len(&docs[0]) + 2] {
+        let mut iter = frame::iter_slice(&bytes[..end]);
+        assert!(iter.next().unwrap().is_ok());
+        assert!(matches!(iter.next().unwrap(), Err(FrameError::Truncated)));
+        assert!(iter.next().is_none());
+
+        let mut reader = FrameReader::new(&bytes[..end]);
+        assert!(reader.read_frame().unwrap().is_some());
+        assert!(matches!(reader.read_frame(), Err(FrameError::Truncated)));
+    }
+
+    // invalid document
+    let mut invalid = bytes.clone();
+    invalid[frame::LENGTH_SIZE] = 0xff;
+    assert!(matches!(
+        frame::iter_slice(&invalid).next().unwrap(),
+        Err(FrameError::YasonError(YasonError::InvalidDataType(_)))
+    ));
+    assert!(matches!(
+        FrameReader::new(invalid.as_slice()).next().unwrap(),
+        Err(FrameError::YasonError(_))
+    ));
+
+    // too large
+    let mut reader = FrameReader::with_max_len(bytes.as_slice(), 4);
+    assert!(matches!(reader.read_frame(), Err(FrameError::TooLarge(len)) if len == docs[0].as_bytes().len()));
+}
@@ -2,7 +2,7 @@
 
 use std::cmp::Ordering;
 use std::str::FromStr;
-use yason::{Array, DataType, Number, Object, Value, YasonBuf};
+use yason::{Array, BuildError, DataType, Number, Object, Value, Yason, YasonBuf};
 
 fn assert_scalar(input: &str, expected: &str, expected_type: DataType) {
     let yason = YasonBuf::parse(input).unwrap();
@@ -134,6 +134,13 @@ fn assert_value(value: Value, expected: &mut TestValue) {
         Value::Number(val) => assert_eq!(val, Number::from_str(expected.scalar()).unwrap()),
         Value::Bool(val) => assert_eq!(val, bool::from_str(expected.scalar()).unwrap()),
         Value::Null => assert_eq!("null", expected.scalar()),
+        Value::Binary(_)
+        | Value::Timestamp(_)
+        | Value::Date(_)
+        | Value::ShortDate(_)
+        | Value::Time(_)
+        | Value::IntervalYearMonth(_)
+        | Value::IntervalDayTime(_) => unreachable!(),
     }
 }
 
@@ -222,3 +229,102 @@ fn test_object() {
     assert_eq!(yason.data_type().unwrap(), DataType::Object);
     assert_object(yason.object().unwrap(), &mut TestValue::Object(expected));
 }
+
+#[test]
+fn test_parse() {
+    // escapes, including a surrogate pair
+    assert_scalar(
+        r#""\/\b\f\u00e9\ud83d\ude00""#,
+        "/\u{8}\u{c}\u{e9}\u{1f600}",
+        DataType::String,
+    );
+    assert_scalar(" \t\r\n-0.5E+2 ", "-50", DataType::Number);
+
+    // numbers are exact without going through f64
+    assert_scalar("18446744073709551617", "18446744073709551617", DataType::Number);
+    assert_scalar("0.1000000000000000000001", "0.1000000000000000000001", DataType::Number);
+    let yason = YasonBuf::parse(r#"[9007199254740993, {"x": 1.00000000000000000001}]"#).unwrap();
+    assert_eq!(
+        yason.format(false).to_string(),
+        "[9007199254740993,{\"x\":1.00000000000000000001}]"
+    );
+
+    let mut bytes = Vec::new();
+    let yason = Yason::parse_to(&mut bytes, r#"{"b": [true], "a": null}"#).unwrap();
+    assert_eq!(
+        yason.format_insertion_order(false).to_string(),
+        r#"{"b":[true],"a":null}"#
+    );
+
+    for input in [
+        "",
+        "nul",
+        "[1,]",
+        "[1 2]",
+        r#"{"a" 1}"#,
+        r#"{"a": 1,}"#,
+        r#"{1: 1}"#,
+        r#""\x""#,
+        r#""\ud83d""#,
+        r#""\ude00""#,
+        r#""\ud83d\u0041""#,
+        "\"a\u{1}\"",
+        "\"abc",
+        "1 2",
+    ] {
+        let err = YasonBuf::parse(input).unwrap_err();
+        assert!(matches!(err, BuildError::JsonError(_)), "{:?}: {}", input, err);
+    }
+
+    let nested = format!("{}{}", "[".repeat(101), "]".repeat(101));
+    assert!(matches!(YasonBuf::parse(nested), Err(BuildError::NestedTooDeeply)));
+    let nested = format!("{}{}", "[".repeat(100), "]".repeat(100));
+    YasonBuf::parse(nested).unwrap();
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn test_parse_with_nfc_keys() {
+    use yason::{NfcCollation, Value};
+
+    let composed = "caf\u{00e9}";
+    let decomposed = "cafe\u{0301}";
+    let json = format!(r#"{{"{}": 1, "plain": 2}}"#, decomposed);
+
+    let raw = YasonBuf::parse(&json).unwrap();
+    let object = raw.object().unwrap();
+    assert!(object.get(composed).unwrap().is_none());
+    assert!(object.get_normalized(composed).unwrap().is_none());
+    assert!(matches!(
+        object.get_with_collation(composed, &NfcCollation).unwrap(),
+        Some(Value::Number(_))
+    ));
+
+    let normalized = YasonBuf::parse_with_nfc_keys(&json).unwrap();
+    let object = normalized.object().unwrap();
+    assert!(object.get(composed).unwrap().is_some());
+    assert!(object.get(decomposed).unwrap().is_none());
+    assert!(object.get_normalized(composed).unwrap().is_some());
+    assert!(object.get_normalized(decomposed).unwrap().is_some());
+    assert!(object.get_normalized("plain").unwrap().is_some());
+    assert_eq!(
+        normalized.format(false).to_string(),
+        format!(r#"{{"{}":1,"plain":2}}"#, composed)
+    );
+
+    let nested = YasonBuf::parse_with_nfc_keys(format!(r#"[{{"a": {{"{}": null}}}}]"#, decomposed)).unwrap();
+    let inner = nested.array().unwrap().object(0).unwrap().object("a").unwrap().unwrap();
+    assert!(inner.get(composed).unwrap().is_some());
+
+    // keys colliding after normalization are merged, the last value wins
+    let json = "{\"\u{e9}\": 1, \"x\": 2, \"e\u{301}\": 3}";
+    let merged = YasonBuf::parse_with_nfc_keys(json).unwrap();
+    merged.validate().unwrap();
+    let object = merged.object().unwrap();
+    assert_eq!(object.len().unwrap(), 2);
+    assert_eq!(object.number("\u{e9}").unwrap(), Some(3.into()));
+    assert_eq!(
+        merged.format_insertion_order(false).to_string(),
+        "{\"\u{e9}\":3,\"x\":2}"
+    );
+}
@@ -0,0 +1,75 @@
+//! Compile-time checks of Send and Sync
+
+use yason::bulk::BitVec;
+use yason::{
+    Array, ArrayBuilder, ArrayChunk, ArrayChunks, ArrayIter, ArrayRefBuilder, BuildError, DocumentTemplate,
+    EmptyReason, InsertionOrderIter, KeyIter, LazyFormat, LintIssue, Number, Object, ObjectBuilder, ObjectIter,
+    ObjectPatchBuilder, ObjectRefBuilder, PathExpression, QueriedValue, QueryContext, QueryOutcome, StepBudget,
+    TypedArrayIter, Value, ValueIter, Yason, YasonBuf, YasonError, YasonIndex,
+};
+
+fn send_sync<T: Send + Sync + ?Sized>() {}
+
+fn send<T: Send + ?Sized>() {}
+
+#[test]
+fn test_views_send_sync() {
+    send_sync::<Yason>();
+    send_sync::<YasonBuf>();
+    send_sync::<Object<'_>>();
+    send_sync::<Array<'_>>();
+    send_sync::<Value<'_>>();
+    send_sync::<ObjectIter<'_>>();
+    send_sync::<KeyIter<'_>>();
+    send_sync::<ValueIter<'_>>();
+    send_sync::<InsertionOrderIter<'_>>();
+    send_sync::<ArrayIter<'_>>();
+    send_sync::<TypedArrayIter<'_, &str>>();
+    send_sync::<TypedArrayIter<'_, Number>>();
+    send_sync::<ArrayChunk<'_>>();
+    send_sync::<ArrayChunks<'_>>();
+    send_sync::<LintIssue>();
+}
+
+#[test]
+fn test_query_send_sync() {
+    send_sync::<PathExpression>();
+    send_sync::<QueryContext>();
+    send_sync::<QueriedValue<'_, '_>>();
+    send_sync::<QueryOutcome<'_, '_>>();
+    send_sync::<EmptyReason>();
+    send_sync::<StepBudget>();
+    send_sync::<BitVec>();
+}
+
+#[test]
+fn test_builders_send_sync() {
+    send_sync::<ObjectBuilder<'_>>();
+    send_sync::<ObjectRefBuilder<'_>>();
+    send_sync::<ArrayBuilder<'_>>();
+    send_sync::<ArrayRefBuilder<'_>>();
+    send_sync::<ObjectPatchBuilder<'_>>();
+    send_sync::<DocumentTemplate>();
+}
+
+#[test]
+fn test_errors_send_sync() {
+    send_sync::<YasonError>();
+    send_sync::<BuildError>();
+}
+
+#[test]
+fn test_lazy_format_send() {
+    send::<LazyFormat<'_>>();
+}
+
+#[test]
+fn test_index_send() {
+    send::<YasonIndex<'_>>();
+}
+
+#[cfg(feature = "memmap2")]
+#[test]
+fn test_mmap_send_sync() {
+    send_sync::<yason::MmapYason>();
+}
@@ -0,0 +1,63 @@
+//! Metrics hook tests
+
+#![cfg(feature = "metrics")]
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use yason::metrics::{self, MetricsHook};
+use yason::{ArrayBuilder, PathExpression, YasonBuf};
+
+#[derive(Default)]
+struct Counters {
+    reserves: AtomicUsize,
+    capacity: AtomicUsize,
+    visited: AtomicUsize,
+    format_bytes: AtomicUsize,
+}
+
+impl MetricsHook for Counters {
+    fn on_build_reserve(&self, capacity: usize) {
+        self.reserves.fetch_add(1, Ordering::Relaxed);
+        self.capacity.fetch_max(capacity, Ordering::Relaxed);
+    }
+
+    fn on_query_node_visited(&self) {
+        self.visited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_format_bytes(&self, bytes: usize) {
+        self.format_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+static COUNTERS: Counters = Counters {
+    reserves: AtomicUsize::new(0),
+    capacity: AtomicUsize::new(0),
+    visited: AtomicUsize::new(0),
+    format_bytes: AtomicUsize::new(0),
+};
+
+#[test]
+fn test_metrics_hook() {
+    metrics::set_hook(&COUNTERS).unwrap();
+    assert!(metrics::set_hook(&COUNTERS).is_err());
+
+    let mut builder = ArrayBuilder::try_new_with_capacity(2, 32).unwrap();
+    assert_eq!(COUNTERS.reserves.load(Ordering::Relaxed), 1);
+    builder.push_string("a".repeat(100)).unwrap().push_null().unwrap();
+    let yason = builder.finish().unwrap();
+    assert_eq!(COUNTERS.reserves.load(Ordering::Relaxed), 2);
+    assert!(COUNTERS.capacity.load(Ordering::Relaxed) >= yason.as_bytes().len());
+
+    let yason = YasonBuf::parse(r#"{"a": [1, 2, {"b": 3}], "c": 4}"#).unwrap();
+    let path = PathExpression::from_str("$.a[*]").unwrap();
+    assert!(path.exists(&yason).unwrap());
+    let visited = COUNTERS.visited.load(Ordering::Relaxed);
+    assert!(visited >= 3);
+    path.query(&yason, true, None, None).unwrap();
+    assert!(COUNTERS.visited.load(Ordering::Relaxed) >= visited + 5);
+
+    let before = COUNTERS.format_bytes.load(Ordering::Relaxed);
+    let text = yason.format(true).to_string();
+    assert_eq!(COUNTERS.format_bytes.load(Ordering::Relaxed), before + text.len());
+}
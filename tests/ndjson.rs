@@ -0,0 +1,51 @@
+//! NDJSON tests
+
+use std::io::{self, BufReader, Read};
+use yason::ndjson::{self, NdjsonError};
+use yason::{BuildError, YasonBuf};
+
+#[test]
+fn test_ndjson_read_write() {
+    let input = "{\"a\": [1, \"x\\ny\"]}\r\n  \n\"s\"\n123\n[true, null]";
+    let docs = ndjson::read(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(docs.len(), 4);
+    assert_eq!(docs[0], YasonBuf::parse(r#"{"a": [1, "x\ny"]}"#).unwrap());
+    assert_eq!(docs[3], YasonBuf::parse("[true, null]").unwrap());
+
+    let mut output = Vec::new();
+    let count = ndjson::write(&mut output, docs.iter().map(|doc| doc.as_ref())).unwrap();
+    assert_eq!(count, 4);
+    assert_eq!(
+        String::from_utf8(output.clone()).unwrap(),
+        "{\"a\":[1,\"x\\ny\"]}\n\"s\"\n123\n[true,null]\n"
+    );
+
+    let again = ndjson::read(output.as_slice()).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(again, docs);
+}
+
+#[test]
+fn test_ndjson_errors() {
+    let mut iter = ndjson::read("1\n\n{\"a\":\n2\n".as_bytes());
+    assert!(iter.next().unwrap().is_ok());
+    assert!(matches!(
+        iter.next().unwrap(),
+        Err(NdjsonError::BuildError {
+            line: 3,
+            error: BuildError::JsonError(_)
+        })
+    ));
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().is_none());
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "broken"))
+        }
+    }
+
+    let mut iter = ndjson::read(BufReader::new(FailingReader));
+    assert!(matches!(iter.next().unwrap(), Err(NdjsonError::IoError(_))));
+}
@@ -1,6 +1,14 @@
 //! Object builder tests.
 
-use yason::{BuildError, DataType, Number, ObjectBuilder, ObjectRefBuilder, Value, Yason, YasonBuf};
+use std::cmp::Ordering;
+use std::str::FromStr;
+use yason::raw::{DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, KEY_OFFSET_SIZE, OBJECT_SIZE};
+use yason::{
+    are_keys_sorted, key_cmp, sort_keys, yason_array, yason_object, ArrBuilderDyn, ArrayBuilder, BuildError,
+    CapacityHint, DataType, DocumentTemplate, ErrorCode, EstimateSize, Number, NumberConstraint, NumberError,
+    ObjBuilder, ObjBuilderDyn, ObjectBuilder, ObjectPatchBuilder, ObjectRefBuilder, Scalar, Timestamp, Value, Yason,
+    YasonBuf, YasonError,
+};
 
 fn assert_string<T: AsRef<str>>(input: Value, expected: T) {
     if let Value::String(value) = input {
@@ -246,3 +254,912 @@ fn test_object_nested_depth() {
     assert_nested_depth(101, Some(BuildError::NestedTooDeeply));
     assert_nested_depth(102, Some(BuildError::NestedTooDeeply));
 }
+
+#[test]
+fn test_object_from_pairs() {
+    let mut array_builder = ArrayBuilder::try_new(1).unwrap();
+    array_builder.push_bool(true).unwrap();
+    let array = array_builder.finish().unwrap();
+
+    let mut object_builder = ObjectBuilder::try_new(1, true).unwrap();
+    object_builder.push_bool("key", true).unwrap();
+    let object = object_builder.finish().unwrap();
+
+    let pairs = [
+        ("id", Value::Number(Number::from(1))),
+        ("name", Value::String("abc")),
+        ("child", Value::Bool(false)),
+        ("phone", Value::Null),
+        ("array", Value::Array(array.array().unwrap())),
+        ("object", Value::Object(object.object().unwrap())),
+    ];
+    let yason = ObjectBuilder::from_pairs(&pairs).unwrap();
+    assert_object(&yason);
+    assert_eq!(yason, create_yason());
+
+    let empty = ObjectBuilder::from_pairs(&[]).unwrap();
+    assert_eq!(empty.object().unwrap().len().unwrap(), 0);
+}
+
+#[test]
+fn test_object_with_capacity() {
+    let pairs = [("name", Value::String("abc")), ("id", Value::Number(Number::from(1)))];
+    let capacity = pairs.estimate_size();
+
+    let mut builder = ObjectBuilder::try_new_with_capacity(2, false, capacity).unwrap();
+    builder.push_string("name", "abc").unwrap();
+    builder.push_number("id", Number::from(1)).unwrap();
+    let yason = builder.finish().unwrap();
+    assert!(yason.as_bytes().len() <= capacity);
+    assert_eq!(yason, ObjectBuilder::from_pairs(&pairs).unwrap());
+}
+
+#[test]
+fn test_object_reset() {
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    builder.push_string("name", "abc").unwrap();
+    let expected = builder.finish_ref().unwrap().to_owned();
+    assert!(matches!(
+        builder.push_null("phone"),
+        Err(BuildError::InnerUncompletedError)
+    ));
+
+    for _ in 0..3 {
+        builder.reset(1, false).unwrap();
+        builder.push_string("name", "abc").unwrap();
+        assert_eq!(builder.finish_ref().unwrap(), expected.as_ref());
+    }
+
+    builder.reset(2, true).unwrap();
+    builder.push_null("a").unwrap();
+    builder.push_bool("b", true).unwrap();
+    let yason = builder.finish().unwrap();
+    assert_eq!(yason.object().unwrap().len().unwrap(), 2);
+
+    let mut builder = ObjectBuilder::try_new_in(yason.as_bytes().to_vec(), 1, false).unwrap();
+    builder.push_string("name", "abc").unwrap();
+    assert_eq!(builder.finish().unwrap(), expected);
+}
+
+#[test]
+fn test_yason_buf_bytes() {
+    let yason = YasonBuf::with_capacity(64).unwrap();
+    assert_eq!(yason.data_type().unwrap(), DataType::Null);
+    let capacity = yason.capacity();
+    assert!(capacity >= 64);
+
+    let mut builder = ObjectBuilder::try_new_in(yason.into_bytes(), 1, false).unwrap();
+    builder.push_string("name", "abc").unwrap();
+    let mut yason = builder.finish().unwrap();
+    assert_eq!(yason.capacity(), capacity);
+    assert_string(yason.object().unwrap().get("name").unwrap().unwrap(), "abc");
+
+    yason.shrink_to_fit();
+    assert_eq!(yason.capacity(), yason.as_bytes().len());
+
+    let mut frame = vec![0xff];
+    let bytes = yason.clone().into_bytes();
+    frame.extend_from_slice(&bytes);
+    assert_eq!(unsafe { Yason::new_unchecked(&frame[1..]) }, yason.as_ref());
+
+    unsafe { yason.as_mut_vec() }.truncate(0);
+    unsafe { yason.as_mut_vec() }.extend_from_slice(&bytes);
+    assert_eq!(yason.as_bytes(), bytes.as_slice());
+}
+
+#[test]
+fn test_object_unaligned() {
+    for prefix in 0..8 {
+        let mut bytes = vec![0xff; prefix];
+        let mut builder = ObjectRefBuilder::try_new(&mut bytes, 3, false).unwrap();
+        builder.push_string("ccc", "3").unwrap();
+        builder.push_number("a", Number::from(1)).unwrap();
+        let mut nested = builder.push_object("bb", 1, false).unwrap();
+        nested.push_bool("x", true).unwrap();
+        nested.finish().unwrap();
+        builder.check_key_offsets().unwrap();
+        let yason = builder.finish().unwrap();
+
+        let object = yason.object().unwrap();
+        let keys: Vec<&str> = object.key_iter().unwrap().map(|key| key.unwrap()).collect();
+        assert_eq!(keys, vec!["a", "bb", "ccc"]);
+        assert_string(object.get("ccc").unwrap().unwrap(), "3");
+        assert_eq!(&bytes[..prefix], vec![0xff; prefix].as_slice());
+    }
+}
+
+#[test]
+fn test_object_check_key_offsets() {
+    let mut builder = ObjectBuilder::try_new(3, true).unwrap();
+    builder.check_key_offsets().unwrap();
+    builder.push_null("a").unwrap();
+    builder.check_key_offsets().unwrap();
+    builder.push_null("bb").unwrap();
+    builder.check_key_offsets().unwrap();
+    builder.push_null("ccc").unwrap();
+    builder.check_key_offsets().unwrap();
+    builder.finish().unwrap();
+
+    let mut bytes = vec![0xff; 3];
+    let mut builder = ObjectRefBuilder::try_new(&mut bytes, 2, false).unwrap();
+    builder.push_null("b").unwrap();
+    builder.push_null("a").unwrap();
+    builder.check_key_offsets().unwrap();
+    builder.finish().unwrap();
+
+    assert_eq!(BuildError::InvalidKeyOffset.code(), ErrorCode::InvalidKeyOffset);
+}
+
+fn push_nested_dyn(builder: &mut dyn ObjBuilderDyn, depth: usize) {
+    builder.push_number_dyn("depth", &Number::from(depth as u64)).unwrap();
+    if depth == 0 {
+        builder.push_null_dyn("child").unwrap();
+        return;
+    }
+
+    let mut array_builder = builder.push_array_dyn("child", 2).unwrap();
+    push_array_dyn(&mut array_builder);
+    array_builder.finish().unwrap();
+}
+
+fn push_array_dyn(builder: &mut dyn ArrBuilderDyn) {
+    builder.push_string_dyn("abc").unwrap();
+    builder.push_bool_dyn(true).unwrap();
+}
+
+#[test]
+fn test_object_builder_dyn() {
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap();
+    push_nested_dyn(&mut builder, 1);
+    let yason = builder.finish().unwrap();
+    let object = yason.object().unwrap();
+    assert_eq!(object.number("depth").unwrap(), Some(Number::from(1)));
+    let child = object.array("child").unwrap().unwrap();
+    assert_eq!(child.string(0).unwrap(), "abc");
+    assert!(child.bool(1).unwrap());
+
+    let mut bytes = Vec::new();
+    let mut builder = ObjectRefBuilder::try_new(&mut bytes, 2, false).unwrap();
+    push_nested_dyn(&mut builder, 0);
+    let yason = builder.finish().unwrap();
+    assert!(yason.object().unwrap().is_null("child").unwrap().unwrap());
+
+    // both traits in scope with a generic builder
+    fn push_generic<B: ObjBuilder>(builder: &mut B) {
+        builder.push_string("a", "abc").unwrap();
+        builder.push_bool_dyn("b", true).unwrap();
+    }
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap();
+    push_generic(&mut builder);
+    let yason = builder.finish().unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"{"a":"abc","b":true}"#);
+}
+
+#[test]
+fn test_object_insertion_order() {
+    let mut builder = ObjectBuilder::try_new(4, false).unwrap();
+    builder.push_string("name", "abc").unwrap();
+    builder.push_number("id", Number::from(1)).unwrap();
+    let mut inner = builder.push_object("child", 2, false).unwrap();
+    inner.push_bool("z", true).unwrap();
+    inner.push_null("a").unwrap();
+    inner.finish().unwrap();
+    builder.push_null("b").unwrap();
+    let yason = builder.finish().unwrap();
+
+    let object = yason.object().unwrap();
+    let keys: Vec<_> = object.iter_insertion_order().unwrap().map(|e| e.unwrap().0).collect();
+    assert_eq!(keys, ["name", "id", "child", "b"]);
+    let keys: Vec<_> = object.key_iter().unwrap().map(|k| k.unwrap()).collect();
+    assert_eq!(keys, ["b", "id", "name", "child"]);
+
+    assert_eq!(
+        yason.format_insertion_order(false).to_string(),
+        r#"{"name":"abc","id":1,"child":{"z":true,"a":null},"b":null}"#
+    );
+    assert_eq!(
+        yason.format(false).to_string(),
+        r#"{"b":null,"id":1,"name":"abc","child":{"a":null,"z":true}}"#
+    );
+
+    let pairs = [("z", Value::Null), ("a", Value::Bool(true))];
+    let yason = ObjectBuilder::from_pairs(&pairs).unwrap();
+    assert_eq!(
+        yason.format_insertion_order(false).to_string(),
+        r#"{"z":null,"a":true}"#
+    );
+    assert_eq!(
+        yason.deduplicate().unwrap().format_insertion_order(false).to_string(),
+        r#"{"z":null,"a":true}"#
+    );
+
+    // parsed objects keep the key order of the json text
+    let input = r#"{"name":"abc","id":1,"child":{"z":true,"a":[{"y":1,"x":2}]},"b":null}"#;
+    let yason = YasonBuf::parse(input).unwrap();
+    assert_eq!(yason.format_insertion_order(false).to_string(), input);
+    let keys: Vec<_> = yason
+        .object()
+        .unwrap()
+        .iter_insertion_order()
+        .unwrap()
+        .map(|e| e.unwrap().0)
+        .collect();
+    assert_eq!(keys, ["name", "id", "child", "b"]);
+    let yason = YasonBuf::parse(r#"{"b":1,"a":2,"b":3}"#).unwrap();
+    assert_eq!(yason.format_insertion_order(false).to_string(), r#"{"b":3,"a":2}"#);
+}
+
+#[test]
+fn test_object_patch() {
+    let base =
+        YasonBuf::parse(r#"{"id": 1, "name": "abc", "tags": ["a", "b"], "child": {"x": null}, "tmp": true}"#).unwrap();
+    let object = base.object().unwrap();
+    let tags = base.object().unwrap().array("tags").unwrap().unwrap();
+    let child = base.object().unwrap().object("child").unwrap().unwrap();
+
+    // untouched
+    let builder = ObjectPatchBuilder::try_new(&object).unwrap();
+    assert_eq!(builder.finish().unwrap().as_bytes(), base.as_bytes());
+
+    let mut builder = ObjectPatchBuilder::try_new(&object).unwrap();
+    builder
+        .set("name", Value::String("defg"))
+        .unwrap()
+        .delete("tmp")
+        .unwrap()
+        .delete("missing")
+        .unwrap()
+        .rename("child", "parent")
+        .unwrap()
+        .rename("missing", "other")
+        .unwrap()
+        .set("new", Value::Object(child.clone()))
+        .unwrap()
+        .rename("new", "id")
+        .unwrap();
+    let patched = builder.finish().unwrap();
+
+    // parsed keys are inserted in the order of the json text
+    let pairs = [
+        ("name", Value::String("defg")),
+        ("tags", Value::Array(tags)),
+        ("parent", Value::Object(child.clone())),
+        ("id", Value::Object(child)),
+    ];
+    assert_eq!(
+        patched.as_bytes(),
+        ObjectBuilder::from_pairs(&pairs).unwrap().as_bytes()
+    );
+    assert_eq!(
+        patched.format_insertion_order(false).to_string(),
+        r#"{"name":"defg","tags":["a","b"],"parent":{"x":null},"id":{"x":null}}"#
+    );
+
+    let mut builder = ObjectPatchBuilder::try_new(&object).unwrap();
+    for key in ["id", "name", "tags", "child", "tmp"] {
+        builder.delete(key).unwrap();
+    }
+    assert_eq!(builder.finish().unwrap(), ObjectBuilder::from_pairs(&[]).unwrap());
+}
+
+#[test]
+fn test_object_to_vec() {
+    let yason = create_yason();
+    let object = yason.object().unwrap();
+    assert_eq!(
+        object.keys_to_vec().unwrap(),
+        ["id", "name", "array", "child", "phone", "object"]
+    );
+    let values = object.values_to_vec().unwrap();
+    assert_eq!(values.len(), 6);
+    assert_number(values[0].clone(), Number::from(1));
+    assert_string(values[1].clone(), "abc");
+    assert_null(values[4].clone());
+}
+
+#[test]
+fn test_object_little_endian() {
+    // The encoding must be identical on every host, multi-byte fields are always little-endian.
+    let yason = YasonBuf::parse(r#"{"a": [true, "xy", null], "bc": null}"#).unwrap();
+    #[rustfmt::skip]
+    let expected: &[u8] = &[
+        1, 44, 0, 0, 0,                 // object, size
+        2, 0,                           // element count
+        10, 0, 0, 0, 39, 0, 0, 0,       // key offsets
+        1, 0, b'a',                     // key
+        2, 21, 0, 0, 0,                 // array, size
+        3, 0,                           // element count
+        5, 1, 0, 0, 0,                  // inlined bool
+        3, 17, 0, 0, 0,                 // string offset
+        6, 0, 0, 0, 0,                  // inlined null
+        3, 2, b'x', b'y',               // outlined string
+        2, 0, b'b', b'c',               // key
+        6,                              // null
+    ];
+    assert_eq!(yason.as_bytes(), expected);
+}
+
+#[test]
+fn test_skip_value() {
+    let inputs = [
+        r#"{"a": [1, {"b": "c"}], "d": 1.5}"#,
+        "[]",
+        r#""string""#,
+        "123",
+        "true",
+        "null",
+    ];
+    let mut bytes = Vec::new();
+    for input in inputs.iter() {
+        bytes.extend_from_slice(YasonBuf::parse(input).unwrap().as_bytes());
+    }
+
+    let stream = unsafe { Yason::new_unchecked(&bytes) };
+    let mut pos = 0;
+    for input in inputs.iter() {
+        let end = stream.skip_value(pos).unwrap();
+        let yason = unsafe { Yason::new_unchecked(&bytes[pos..end]) };
+        assert_eq!(yason, YasonBuf::parse(input).unwrap().as_ref());
+        pos = end;
+    }
+    assert_eq!(pos, bytes.len());
+    assert!(stream.skip_value(pos).is_err());
+
+    let truncated = unsafe { Yason::new_unchecked(&bytes[..bytes.len() - 2]) };
+    assert!(truncated.skip_value(0).is_ok());
+    let truncated = unsafe { Yason::new_unchecked(&bytes[..10]) };
+    assert!(truncated.skip_value(0).is_err());
+}
+
+#[test]
+fn test_object_macro() {
+    let name = String::from("abc");
+    let array = yason_array![true].unwrap();
+    let object = yason_object! { "key" => true }.unwrap();
+    let yason = yason_object! {
+        "id" => 1,
+        "name" => &name,
+        "child" => false,
+        "phone" => null,
+        "array" => [true],
+        "object" => { "key" => true },
+    }
+    .unwrap();
+    assert_object(&yason);
+    assert_eq!(yason, create_yason());
+    assert_eq!(
+        array,
+        yason
+            .object()
+            .unwrap()
+            .array("array")
+            .unwrap()
+            .unwrap()
+            .yason()
+            .to_owned()
+    );
+    assert_eq!(
+        object.as_bytes(),
+        yason
+            .object()
+            .unwrap()
+            .object("object")
+            .unwrap()
+            .unwrap()
+            .yason()
+            .as_bytes()
+    );
+
+    let options = yason_object! { "some" => Some(Number::from(2)), "none" => None::<bool> }.unwrap();
+    assert_eq!(options.format(false).to_string(), r#"{"none":null,"some":2}"#);
+
+    let nested = yason_array![[], {}, [[1, -2], { "a" => u64::MAX }], 3 + 4].unwrap();
+    assert_eq!(
+        nested.format(false).to_string(),
+        r#"[[],{},[[1,-2],{"a":18446744073709551615}],7]"#
+    );
+
+    let empty = yason_object! {}.unwrap();
+    assert_eq!(empty.object().unwrap().len().unwrap(), 0);
+}
+
+#[test]
+fn test_object_iter_double_ended() {
+    let yason = YasonBuf::parse(r#"{"a": 1, "bb": 2, "ccc": 3, "dddd": 4, "eeeee": 5}"#).unwrap();
+    let object = yason.object().unwrap();
+
+    let keys = object.key_iter().unwrap().rev().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(keys, ["eeeee", "dddd", "ccc", "bb", "a"]);
+
+    let mut iter = object.key_iter().unwrap();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.nth(1).unwrap().unwrap(), "bb");
+    assert_eq!(iter.next_back().unwrap().unwrap(), "eeeee");
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.nth_back(1).unwrap().unwrap(), "ccc");
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+
+    let mut iter = object.iter().unwrap();
+    assert!(iter.nth(5).is_none());
+    assert!(iter.next().is_none());
+
+    // the last two entries
+    let mut iter = object.iter().unwrap();
+    let last = iter.nth_back(1).unwrap().unwrap();
+    assert_eq!(last.0, "dddd");
+    assert_number(last.1, Number::from(4));
+    assert_eq!(iter.len(), 3);
+    assert_eq!(object.iter().unwrap().last().unwrap().unwrap().0, "eeeee");
+    assert_eq!(object.iter().unwrap().skip(3).count(), 2);
+
+    let mut values = object.value_iter().unwrap();
+    assert_number(values.nth(2).unwrap().unwrap(), Number::from(3));
+    assert_number(values.next_back().unwrap().unwrap(), Number::from(5));
+    assert_eq!(values.size_hint(), (1, Some(1)));
+}
+
+#[test]
+fn test_push_number_with() {
+    let number = |s: &str| s.parse::<Number>().unwrap();
+    let constraint = NumberConstraint::new(5, 2);
+
+    let mut builder = ObjectBuilder::try_new(3, false).unwrap();
+    builder.push_number_with("a", number("123.456"), &constraint).unwrap();
+    builder.push_number_with("b", number("-999.994"), &constraint).unwrap();
+    let mut array = builder.push_array("c", 2).unwrap();
+    array.push_number_with(number("0.001"), &constraint).unwrap();
+    assert!(matches!(
+        array.push_number_with(number("-999.995"), &constraint),
+        Err(BuildError::NumberOutOfRange { precision: 5, scale: 2 })
+    ));
+    array.push_number_with(Number::from(7), &constraint).unwrap();
+    array.finish().unwrap();
+    let yason = builder.finish().unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"{"a":123.46,"b":-999.99,"c":[0,7]}"#);
+
+    // a negative scale rounds before the decimal point
+    let constraint = NumberConstraint::new(2, -2);
+    let mut builder = ArrayBuilder::try_new(1).unwrap();
+    builder.push_number_with(number("1249"), &constraint).unwrap();
+    assert_eq!(builder.finish().unwrap().format(false).to_string(), "[1200]");
+    assert!(constraint.apply(&number("9950")).is_err());
+    assert_eq!(constraint.apply(&number("9949")).unwrap(), number("9900"));
+}
+
+#[test]
+fn test_exists_keys() {
+    let yason = YasonBuf::parse(r#"{"ccc": 3, "a": 1, "dd": 4, "bb": 2}"#).unwrap();
+    let object = yason.object().unwrap();
+
+    assert!(object.exists_key("dd").unwrap());
+    assert!(!object.exists_key("d").unwrap());
+
+    assert!(object.exists_any(&["zzzz", "a"]).unwrap());
+    assert!(object.exists_any(&["ccc"]).unwrap());
+    assert!(!object.exists_any(&["b", "cc", "zzzz"]).unwrap());
+    assert!(!object.exists_any::<&str>(&[]).unwrap());
+
+    assert!(object.exists_all(&["dd", "a", "ccc", "bb"]).unwrap());
+    assert!(object.exists_all(&["bb", "bb", "a"]).unwrap());
+    assert!(!object.exists_all(&["a", "bb", "e"]).unwrap());
+    assert!(object.exists_all::<&str>(&[]).unwrap());
+
+    let keys = vec!["bb".to_string(), "x".to_string()];
+    assert!(object.exists_any(&keys).unwrap());
+    assert!(!object.exists_all(&keys).unwrap());
+
+    let empty = YasonBuf::parse("{}").unwrap();
+    assert!(!empty.object().unwrap().exists_any(&["a"]).unwrap());
+}
+
+#[test]
+fn test_string_len_and_prefix() {
+    let yason = YasonBuf::parse(r#"{"empty": "", "text": "日本語 text", "num": 1}"#).unwrap();
+    let object = yason.object().unwrap();
+
+    assert_eq!(object.string_len("empty").unwrap(), Some(0));
+    assert_eq!(object.string_len("text").unwrap(), Some(14));
+    assert_eq!(object.string_len("missing").unwrap(), None);
+    assert!(object.string_len("num").is_err());
+
+    assert_eq!(object.string_prefix("empty", 3).unwrap(), Some(""));
+    assert_eq!(object.string_prefix("text", 0).unwrap(), Some(""));
+    assert_eq!(object.string_prefix("text", 2).unwrap(), Some("日本"));
+    assert_eq!(object.string_prefix("text", 5).unwrap(), Some("日本語 t"));
+    assert_eq!(object.string_prefix("text", 100).unwrap(), Some("日本語 text"));
+    assert_eq!(object.string_prefix("missing", 1).unwrap(), None);
+    assert!(object.string_prefix("num", 1).is_err());
+}
+
+#[test]
+fn test_binary() {
+    let mut builder = ObjectBuilder::try_new(3, false).unwrap();
+    builder.push_binary("bin", [0u8, 1, 0xff]).unwrap();
+    builder.push_binary("empty", []).unwrap();
+    builder.push_string("str", "abc").unwrap();
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+
+    let object = yason.object().unwrap();
+    assert_eq!(object.type_of("bin").unwrap(), Some(DataType::Binary));
+    assert_eq!(object.binary("bin").unwrap(), Some(&[0u8, 1, 0xff][..]));
+    assert_eq!(object.binary("empty").unwrap(), Some(&[][..]));
+    assert_eq!(object.binary("missing").unwrap(), None);
+    assert!(object.binary("str").is_err());
+    assert!(object.string("bin").is_err());
+
+    match object.get("bin").unwrap().unwrap() {
+        Value::Binary(bytes) => assert_eq!(bytes, [0u8, 1, 0xff]),
+        _ => panic!("type inconsistency"),
+    }
+}
+
+#[test]
+fn test_to_map() {
+    let yason = YasonBuf::parse(r#"{"name": "abc", "age": 12, "tags": [1, 2], "nested": {"x": null}}"#).unwrap();
+    let object = yason.object().unwrap();
+
+    let map = object.to_map().unwrap();
+    assert_eq!(map.len(), 4);
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        ["age", "name", "nested", "tags"]
+    );
+    assert_string(map["name"].clone(), "abc");
+    assert!(matches!(&map["age"], Value::Number(n) if *n == Number::from(12)));
+    assert!(matches!(&map["tags"], Value::Array(a) if a.len().unwrap() == 2));
+    assert!(matches!(&map["nested"], Value::Object(o) if o.len().unwrap() == 1));
+
+    let hashmap = object.to_hashmap().unwrap();
+    assert_eq!(hashmap.len(), 4);
+    assert!(hashmap.capacity() >= 4);
+    assert_string(hashmap["name"].clone(), "abc");
+    assert!(!hashmap.contains_key("missing"));
+
+    let empty = YasonBuf::parse("{}").unwrap();
+    assert!(empty.object().unwrap().to_map().unwrap().is_empty());
+    assert!(empty.object().unwrap().to_hashmap().unwrap().is_empty());
+}
+
+fn build_document(name: &str, id: i64, tag: Option<&str>, flag: bool, key: &str) -> YasonBuf {
+    let mut builder = ObjectBuilder::try_new(4, false).unwrap();
+    builder.push_string("name", name).unwrap();
+    builder.push_number("id", Number::from(id)).unwrap();
+    let mut array = builder.push_array("tags", 4).unwrap();
+    array.push_bool(flag).unwrap();
+    match tag {
+        Some(tag) => array.push_string(tag).unwrap(),
+        None => array.push_null().unwrap(),
+    };
+    let mut inner = array.push_object(1, false).unwrap();
+    inner.push_string("k", key).unwrap();
+    inner.finish().unwrap();
+    array.push_array(0).unwrap().finish().unwrap();
+    array.finish().unwrap();
+    builder.push_object("child", 0, false).unwrap().finish().unwrap();
+    builder.finish().unwrap()
+}
+
+#[test]
+fn test_document_template() {
+    let sample = build_document("", 0, None, false, "");
+    let template = DocumentTemplate::try_new(&sample).unwrap();
+    assert_eq!(template.slot_count(), 5);
+    let nulls = template.fill(&vec![Value::Null; 5]).unwrap();
+    assert_eq!(
+        nulls.format_insertion_order(false).to_string(),
+        r#"{"name":null,"id":null,"tags":[null,null,{"k":null},[]],"child":{}}"#
+    );
+
+    // filled documents are encoded exactly like built ones
+    let rows = [
+        ("abc", 1, Some("x"), true, "value"),
+        ("", -20, None, false, ""),
+        ("long name", 300, Some("tag"), true, "v"),
+    ];
+    for (name, id, tag, flag, key) in rows.iter() {
+        let values = [
+            Value::String(name),
+            Value::Number(Number::from(*id)),
+            Value::Bool(*flag),
+            tag.map(Value::String).unwrap_or(Value::Null),
+            Value::String(key),
+        ];
+        let yason = template.fill(&values).unwrap();
+        assert_eq!(yason.as_bytes(), build_document(name, *id, *tag, *flag, key).as_bytes());
+        Yason::from_bytes(yason.as_bytes()).unwrap();
+    }
+
+    // slots are not typed
+    let nested = YasonBuf::parse(r#"{"x": [1]}"#).unwrap();
+    let values = [
+        Value::Bool(false),
+        Value::Object(nested.object().unwrap()),
+        Value::Number(Number::from(2)),
+        Value::Bool(true),
+        Value::Null,
+    ];
+    let yason = template.fill(&values).unwrap();
+    Yason::from_bytes(yason.as_bytes()).unwrap();
+    assert_eq!(
+        yason.format_insertion_order(false).to_string(),
+        r#"{"name":false,"id":{"x":[1]},"tags":[2,true,{"k":null},[]],"child":{}}"#
+    );
+
+    assert!(matches!(
+        template.fill(&vec![Value::Null; 4]),
+        Err(BuildError::InconsistentSlotCount { expected: 5, actual: 4 })
+    ));
+
+    let scalar = YasonBuf::parse("1").unwrap();
+    let template = DocumentTemplate::try_new(&scalar).unwrap();
+    assert_eq!(template.slot_count(), 1);
+    let yason = template.fill(&[Value::String("s")]).unwrap();
+    assert_eq!(yason.string().unwrap(), "s");
+}
+
+#[test]
+fn test_key_order() {
+    assert_eq!(key_cmp("z", "aa"), Ordering::Less);
+    assert_eq!(key_cmp("ab", "aa"), Ordering::Greater);
+    assert_eq!(key_cmp("ab", "ab"), Ordering::Equal);
+
+    let yason = YasonBuf::parse(r#"{"bb": 1, "a": {"d": 2, "c": 3}}"#).unwrap();
+    let object = yason.object().unwrap();
+    assert!(object.is_sorted().unwrap());
+    assert!(object.object("a").unwrap().unwrap().is_sorted().unwrap());
+
+    // swaps the key offsets of "a" and "bb"
+    let mut bytes = yason.as_bytes().to_vec();
+    let table = DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE;
+    let (first, second) = bytes[table..table + 2 * KEY_OFFSET_SIZE].split_at_mut(KEY_OFFSET_SIZE);
+    first.swap_with_slice(second);
+    let unsorted = unsafe { Yason::new_unchecked(&bytes) };
+    assert!(!unsorted.object().unwrap().is_sorted().unwrap());
+    assert!(unsorted.validate().is_err());
+
+    let mut builder = ObjectBuilder::try_new(3, true).unwrap();
+    builder.push_bool("b", true).unwrap();
+    builder.push_bool("aa", true).unwrap();
+    match builder.push_bool("a", true) {
+        Err(err) => {
+            assert!(matches!(err, BuildError::UnsortedKeys));
+            assert_eq!(err.code(), ErrorCode::UnsortedKeys);
+        }
+        Ok(_) => unreachable!(),
+    }
+    builder.push_bool("ab", true).unwrap();
+    let yason = builder.finish().unwrap();
+    assert!(yason.object().unwrap().is_sorted().unwrap());
+}
+
+#[test]
+fn test_push_yason() {
+    let mut number = Scalar::number(Number::from_str("1.50").unwrap()).unwrap();
+    number.add_header().unwrap();
+    number.add_checksum().unwrap();
+    let nested = YasonBuf::parse(r#"{"b": [1, true]}"#).unwrap();
+    let scalars = [
+        Scalar::string("abc").unwrap(),
+        number,
+        Scalar::bool(true).unwrap(),
+        Scalar::null().unwrap(),
+        Scalar::timestamp(Timestamp::from_micros(1)).unwrap(),
+        nested,
+    ];
+
+    let mut builder = ObjectBuilder::try_new(scalars.len() as u16, false).unwrap();
+    for (i, scalar) in scalars.iter().enumerate() {
+        builder.push_yason(format!("k{}", i), scalar).unwrap();
+    }
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+    let expected =
+        YasonBuf::parse(r#"{"k0": "abc", "k1": 1.5, "k2": true, "k3": null, "k5": {"b": [1, true]}}"#).unwrap();
+    let object = yason.object().unwrap();
+    assert_eq!(object.len().unwrap(), 6);
+    assert_eq!(object.timestamp("k4").unwrap(), Some(Timestamp::from_micros(1)));
+    for key in ["k0", "k1", "k2", "k3", "k5"] {
+        let left = object.get(key).unwrap().unwrap();
+        let right = expected.object().unwrap().get(key).unwrap().unwrap();
+        assert_eq!(left, right);
+    }
+
+    let mut builder = ArrayBuilder::try_new(scalars.len() as u16).unwrap();
+    for scalar in scalars.iter() {
+        builder.push_yason_dyn(scalar).unwrap();
+    }
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+    assert_eq!(
+        yason.format(false).to_string(),
+        r#"["abc",1.5,true,null,"1970-01-01T00:00:00.000001",{"b":[1,true]}]"#
+    );
+
+    let mut builder = ArrayBuilder::try_new_set(2, true).unwrap();
+    builder.push_number(Number::from(1)).unwrap();
+    builder
+        .push_yason(&Scalar::number(Number::from_str("1.0").unwrap()).unwrap())
+        .unwrap();
+    assert_eq!(builder.finish().unwrap().format(false).to_string(), "[1]");
+
+    let invalid = unsafe { Yason::new_unchecked(&[0xff]) };
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    match builder.push_yason("k", invalid) {
+        Err(err) => {
+            assert!(matches!(
+                err,
+                BuildError::InvalidYason(YasonError::InvalidDataType(0xff))
+            ));
+            assert_eq!(err.code(), ErrorCode::InvalidYason);
+        }
+        Ok(_) => unreachable!(),
+    }
+    let mut builder = ArrayBuilder::try_new(1).unwrap();
+    assert!(matches!(builder.push_yason(invalid), Err(BuildError::InvalidYason(_))));
+}
+
+#[test]
+fn test_push_native_number() {
+    let mut builder = ObjectBuilder::try_new(6, true).unwrap();
+    builder.push_number("a", 1u8).unwrap();
+    builder.push_number("b", -2i64).unwrap();
+    builder.push_number("c", u128::MAX / 1_000_000).unwrap();
+    builder.push_number("d", 0.1f32).unwrap();
+    builder.push_number("e", 1.5e-7f64).unwrap();
+    let number = Number::from(3);
+    let number_ref: &Number = &number;
+    builder.push_number("f", number_ref).unwrap();
+    let yason = builder.finish().unwrap();
+    assert_eq!(
+        yason.format(false).to_string(),
+        r#"{"a":1,"b":-2,"c":340282366920938463463374607431768,"d":0.1,"e":0.00000015,"f":3}"#
+    );
+
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    let err = builder.push_number("a", f64::NAN).err().unwrap();
+    assert!(matches!(err, BuildError::NumberError(NumberError::NotFinite)));
+    assert_eq!(err.code(), ErrorCode::NumberNotFinite);
+    assert!(matches!(
+        builder.push_number("a", f64::INFINITY),
+        Err(BuildError::NumberError(NumberError::NotFinite))
+    ));
+    assert!(matches!(
+        builder.push_number("a", u128::MAX),
+        Err(BuildError::NumberError(NumberError::Overflow))
+    ));
+    assert!(matches!(
+        builder.push_number("a", 1e200f64),
+        Err(BuildError::NumberError(NumberError::Overflow))
+    ));
+
+    let mut builder = ArrayBuilder::try_new(2).unwrap();
+    builder.push_number(7usize).unwrap();
+    builder.push_number(-0.25f64).unwrap();
+    assert_eq!(builder.finish().unwrap().format(false).to_string(), "[7,-0.25]");
+
+    let scalar = Scalar::number(42i32).unwrap();
+    assert_eq!(scalar.format(false).to_string(), "42");
+}
+
+#[test]
+fn test_object_bulk_push() {
+    let strings = [("b", "x"), ("a", "y")];
+    let numbers = [("d", Number::from(1)), ("c", Number::from(2))];
+    let bools = [("e", true)];
+
+    let mut builder = ObjectBuilder::try_new(5, false).unwrap();
+    builder.push_strings(&strings).unwrap();
+    builder.push_numbers(&numbers).unwrap().push_bools(&bools).unwrap();
+    let bulk = builder.finish().unwrap();
+    assert_eq!(
+        bulk.format(false).to_string(),
+        r#"{"a":"y","b":"x","c":2,"d":1,"e":true}"#
+    );
+
+    let mut builder = ObjectBuilder::try_new(5, false).unwrap();
+    for (key, value) in strings.iter() {
+        builder.push_string(key, value).unwrap();
+    }
+    for (key, value) in numbers.iter() {
+        builder.push_number(key, value).unwrap();
+    }
+    builder.push_bool("e", true).unwrap();
+    assert_eq!(builder.finish().unwrap(), bulk);
+
+    let mut builder = ObjectBuilder::try_new(2, true).unwrap();
+    builder.push_bools(&[("a", true), ("b", false)]).unwrap();
+    assert_eq!(
+        builder.finish().unwrap().format(false).to_string(),
+        r#"{"a":true,"b":false}"#
+    );
+
+    let mut builder = ObjectBuilder::try_new(2, true).unwrap();
+    assert!(matches!(
+        builder.push_bools(&[("b", true), ("a", false)]),
+        Err(BuildError::UnsortedKeys)
+    ));
+
+    // nothing is pushed if there are too many key-values
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    assert!(matches!(
+        builder.push_strings(&strings),
+        Err(BuildError::InconsistentElementCount { expected: 1, actual: 2 })
+    ));
+    builder.push_strings(&[(String::from("k"), String::from("v"))]).unwrap();
+    assert_eq!(builder.finish().unwrap().format(false).to_string(), r#"{"k":"v"}"#);
+}
+
+#[test]
+fn test_capacity_hint() {
+    let hint = CapacityHint::default();
+    assert_eq!(hint, CapacityHint::new());
+    assert_eq!((hint.key_size(), hint.value_size()), (8, 8));
+    assert_eq!(hint.object_capacity(0), 7);
+    assert_eq!(hint.object_capacity(2), 7 + 2 * 22);
+    assert_eq!(hint.array_capacity(0), 7);
+    assert_eq!(hint.array_capacity(2), 7 + 2 * 13);
+    let tuned = hint.with_key_size(2).with_value_size(20);
+    assert_eq!(tuned.object_capacity(1), 7 + 28);
+    assert_eq!(tuned.array_capacity(1), 7 + 25);
+
+    // a tiny object does not reserve more than it needs
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    builder.push_bool("a", true).unwrap();
+    let yason = builder.finish().unwrap();
+    assert_eq!(yason.capacity(), hint.object_capacity(1));
+
+    // a medium object is built without reallocating
+    let mut builder = ObjectBuilder::try_new(40, false).unwrap();
+    for i in 0..40 {
+        builder.push_string(format!("key{:02}", i), "value").unwrap();
+    }
+    let yason = builder.finish().unwrap();
+    assert_eq!(yason.capacity(), hint.object_capacity(40));
+
+    let mut builder = ArrayBuilder::try_new(40).unwrap();
+    for i in 0..40 {
+        builder.push_number(i).unwrap();
+    }
+    let yason = builder.finish().unwrap();
+    assert!(yason.as_bytes().len() <= hint.array_capacity(40));
+}
+
+#[test]
+fn test_sort_keys() {
+    let mut pairs = vec![
+        (String::from("name"), 1),
+        (String::from("b"), 2),
+        (String::from("id"), 3),
+        (String::from("a"), 4),
+        (String::from("b"), 5),
+    ];
+    assert!(!are_keys_sorted(&pairs));
+    sort_keys(&mut pairs);
+    // equal keys are not sorted
+    assert!(!are_keys_sorted(&pairs));
+    let keys: Vec<&str> = pairs.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["a", "b", "b", "id", "name"]);
+    // the sort is stable
+    assert_eq!((pairs[1].1, pairs[2].1), (2, 5));
+
+    pairs.dedup_by(|right, left| left.0 == right.0);
+    assert!(are_keys_sorted(&pairs));
+    let mut builder = ObjectBuilder::try_new(pairs.len() as u16, true).unwrap();
+    for (key, value) in pairs.iter() {
+        builder.push_number(key, *value).unwrap();
+    }
+    let yason = builder.finish().unwrap();
+    assert!(yason.object().unwrap().is_sorted().unwrap());
+    assert_eq!(yason.format(false).to_string(), r#"{"a":4,"b":2,"id":3,"name":1}"#);
+
+    let empty: [(&str, ()); 0] = [];
+    assert!(are_keys_sorted(&empty));
+    assert!(!are_keys_sorted(&[("aa", ()), ("b", ())]));
+
+    let mut builder = ObjectBuilder::try_new(2, true).unwrap();
+    builder.push_number("a", 1).unwrap();
+    assert!(matches!(builder.push_number("a", 2), Err(BuildError::UnsortedKeys)));
+}
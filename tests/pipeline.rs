@@ -0,0 +1,115 @@
+//! Pipeline tests.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+use yason::pipeline::{Pipeline, StageKind};
+use yason::{transform, PathExpression, Value, YasonBuf, YasonError};
+
+fn upper_case(key: &str) -> Option<Cow<'_, str>> {
+    Some(Cow::Owned(key.to_ascii_uppercase()))
+}
+
+fn run(pipeline: &Pipeline, input: &str) -> String {
+    let yason = YasonBuf::parse(input).unwrap();
+    let mut buf = Vec::new();
+    pipeline.run(&yason, &mut buf).unwrap().format(false).to_string()
+}
+
+#[test]
+fn test_pipeline() {
+    let input = r#"{"a": 1.50, "b": null, "c": {"d": null, "e": [null, {"f": null, "g": 2}]}, "h": "x"}"#;
+
+    let pipeline = Pipeline::new();
+    assert!(pipeline.is_empty());
+    assert_eq!(
+        run(&pipeline, input),
+        YasonBuf::parse(input).unwrap().format(false).to_string()
+    );
+
+    let pipeline = Pipeline::new().strip_nulls();
+    assert_eq!(pipeline.len(), 1);
+    assert_eq!(run(&pipeline, input), r#"{"a":1.5,"c":{"e":[null,{"g":2}]},"h":"x"}"#);
+
+    // the same result as the transforms one after another
+    let paths = [
+        PathExpression::from_str("$.c.e[1]").unwrap(),
+        PathExpression::from_str("$.h").unwrap(),
+    ];
+    let pipeline = Pipeline::new()
+        .rename_keys(upper_case)
+        .redact(&paths, Value::Bool(true));
+    let yason = YasonBuf::parse(input).unwrap();
+    let mut redacted = Vec::new();
+    let redacted = transform::redact(&yason, &paths, &Value::Bool(true), &mut redacted).unwrap();
+    let mut expected = Vec::new();
+    let expected = transform::rename_keys(redacted, upper_case, &mut expected).unwrap();
+    assert_eq!(run(&pipeline, input), expected.format(false).to_string());
+
+    // canonical output
+    let pipeline = Pipeline::new().canonicalize();
+    let mut buf = Vec::new();
+    let canonical = pipeline.run(&yason, &mut buf).unwrap();
+    assert_eq!(canonical.as_bytes(), yason.canonicalize().unwrap().as_bytes());
+
+    let pipeline = Pipeline::new().strip_nulls().rename_keys(upper_case).canonicalize();
+    let mut buf = Vec::new();
+    let result = pipeline.run(&yason, &mut buf).unwrap();
+    assert!(result.is_canonical().unwrap());
+    assert_eq!(
+        result.format(false).to_string(),
+        r#"{"A":1.5,"C":{"E":[null,{"G":2}]},"H":"x"}"#
+    );
+}
+
+#[test]
+fn test_pipeline_stage_order() {
+    let input = r#"{"a": 1, "b": [2, 3]}"#;
+    let paths = [
+        PathExpression::from_str("$.a").unwrap(),
+        PathExpression::from_str("$.b[0]").unwrap(),
+    ];
+
+    // a later stage sees the replacement
+    let pipeline = Pipeline::new().redact(&paths, Value::Null).strip_nulls();
+    assert_eq!(run(&pipeline, input), r#"{"b":[null,3]}"#);
+    let pipeline = Pipeline::new().strip_nulls().redact(&paths, Value::Null);
+    assert_eq!(run(&pipeline, input), r#"{"a":null,"b":[null,3]}"#);
+
+    // paths select by the keys of the source
+    let pipeline = Pipeline::new()
+        .rename_keys(upper_case)
+        .redact(&paths, Value::String("*"));
+    assert_eq!(run(&pipeline, input), r#"{"A":"*","B":["*",3]}"#);
+
+    // the first redaction of a value wins
+    let all = [PathExpression::from_str("$").unwrap()];
+    let pipeline = Pipeline::new()
+        .redact(&all, Value::Number(1.into()))
+        .redact(&paths, Value::Null);
+    assert_eq!(run(&pipeline, input), "1");
+    assert_eq!(run(&pipeline, "true"), "1");
+}
+
+#[test]
+fn test_pipeline_errors() {
+    let yason = YasonBuf::parse(r#"{"a": 1, "A": 2}"#).unwrap();
+
+    let pipeline = Pipeline::new().strip_nulls().rename_keys(upper_case);
+    let mut buf = Vec::new();
+    let err = pipeline.run(&yason, &mut buf).unwrap_err();
+    assert_eq!(err.stage(), Some((1, StageKind::RenameKeys)));
+    assert!(matches!(err.error(), YasonError::DuplicateKey(key) if key == "A"));
+    assert_eq!(err.to_string(), "stage 1 (rename_keys): duplicate key 'A'");
+
+    let long_key = "k".repeat(65536);
+    let pipeline = Pipeline::new().rename_keys(|_| Some(Cow::Owned(long_key.clone())));
+    let err = pipeline.run(&yason, &mut buf).unwrap_err();
+    assert_eq!(err.stage(), Some((0, StageKind::RenameKeys)));
+    assert!(matches!(err.into_error(), YasonError::KeyTooLong(65536)));
+
+    let paths = [PathExpression::from_str("$.a.size()").unwrap()];
+    let pipeline = Pipeline::new().strip_nulls().strip_nulls().redact(&paths, Value::Null);
+    let err = pipeline.run(&yason, &mut buf).unwrap_err();
+    assert_eq!(err.stage(), Some((2, StageKind::Redact)));
+    assert!(matches!(err.error(), YasonError::InvalidPathExpression));
+}
@@ -1,45 +1,12 @@
 //! Query by PathExpression tests
 
-use yason::{DataType, PathExpression, QueriedValue, Value, YasonBuf, YasonError};
-
-fn assert_eq(left: &Value, right: &Value) {
-    assert_eq!(left.data_type(), right.data_type());
-
-    match left.data_type() {
-        DataType::Object => match (left, right) {
-            (Value::Object(l_o), Value::Object(r_o)) => {
-                for (l, r) in l_o.iter().unwrap().zip(r_o.iter().unwrap()) {
-                    let (k_l, v_l) = l.unwrap();
-                    let (k_r, v_r) = r.unwrap();
-                    assert_eq!(k_l, k_r);
-                    assert_eq(&v_l, &v_r);
-                }
-            }
-            _ => unreachable!(),
-        },
-        DataType::Array => match (left, right) {
-            (Value::Array(l_a), Value::Array(r_a)) => {
-                for (l, r) in l_a.iter().unwrap().zip(r_a.iter().unwrap()) {
-                    assert_eq(&l.unwrap(), &r.unwrap());
-                }
-            }
-            _ => unreachable!(),
-        },
-        DataType::String => match (left, right) {
-            (Value::String(l), Value::String(r)) => assert_eq!(l, r),
-            _ => unreachable!(),
-        },
-        DataType::Number => match (left, right) {
-            (Value::Number(l), Value::Number(r)) => assert_eq!(l, r),
-            _ => unreachable!(),
-        },
-        DataType::Bool => match (left, right) {
-            (Value::Bool(l), Value::Bool(r)) => assert_eq!(l, r),
-            _ => unreachable!(),
-        },
-        DataType::Null => {}
-    }
-}
+use std::str::FromStr;
+use std::sync::Arc;
+use yason::{
+    ArrayIndex, ArraySelector, DataType, DescendantOrder, EmptyReason, ErrorCode, Number, ObjectBuilder, OnEmpty,
+    OnError, PathExpression, PathMethod, PathStep, PreparedPath, QueriedValue, QueryContext, QueryResult,
+    QueryStrategy, StepBudget, Value, Yason, YasonBuf, YasonError,
+};
 
 fn assert_inner(input: &str, path: &str, expected: Option<&str>, with_wrapper: bool, to_yason: bool, error: bool) {
     let yason_buf = YasonBuf::parse(input).unwrap();
@@ -64,7 +31,7 @@ fn assert_inner(input: &str, path: &str, expected: Option<&str>, with_wrapper: b
                 match res {
                     QueriedValue::Yason(yason) => {
                         let res_value = Value::try_from(yason).unwrap();
-                        assert_eq(&res_value, &expected_value);
+                        assert_eq!(res_value, expected_value);
                     }
                     _ => unreachable!(),
                 }
@@ -74,7 +41,7 @@ fn assert_inner(input: &str, path: &str, expected: Option<&str>, with_wrapper: b
                         assert_eq!(array.len().unwrap(), values.len());
                         let iter = array.iter().unwrap();
                         for (id, value) in iter.enumerate() {
-                            assert_eq(&value.unwrap(), &values[id]);
+                            assert_eq!(value.unwrap(), values[id]);
                         }
                     }
                     _ => unreachable!(),
@@ -96,7 +63,7 @@ fn assert_inner(input: &str, path: &str, expected: Option<&str>, with_wrapper: b
 
             match res {
                 QueriedValue::Value(value) => {
-                    assert_eq(&value, &expected_value);
+                    assert_eq!(value, expected_value);
                 }
                 _ => unreachable!(),
             }
@@ -327,6 +294,12 @@ fn test_query_error() {
 
     let path = "$.key1.type()";
     assert_query_error(input, path);
+
+    let path = "$.key1.types()";
+    assert_query_error(input, path);
+
+    let path = "$.key1.distinct()";
+    assert_query_error(input, path);
 }
 
 #[test]
@@ -454,6 +427,18 @@ fn test_query_with_wrapper() {
     let expected = r#"["null", "object", "array", "number"]"#;
     assert_query_with_wrapper(input, path, Some(expected));
 
+    let path = "$.key4[0 to 4, 1].types()";
+    let expected = r#"["number", "boolean", "null", "object", "array"]"#;
+    assert_query_with_wrapper(input, path, Some(expected));
+
+    let input = r#"[1, "a", 1.0, null, true, "a", [1], [1], {"b": 2}, null, 2, true]"#;
+    let path = "$[*].distinct()";
+    let expected = r#"[1, "a", null, true, [1], [1], {"b": 2}, 2]"#;
+    assert_query_with_wrapper(input, path, Some(expected));
+
+    let path = "$[100].distinct()";
+    assert_query_with_wrapper(input, path, None);
+
     let input = r#"[{"key": [{"key": [{"key": [{"key": 123}]}]}]}]"#;
     let path = r#"$.key.key.key.key"#;
     let expected = r#"[123]"#;
@@ -954,3 +939,904 @@ mod test_queried_value_format_to {
         assert_queried_yason(input, path, compact, pretty);
     }
 }
+
+#[test]
+fn test_query_outcome() {
+    fn assert(input: &str, path: &str, with_wrapper: bool, expected: Option<EmptyReason>) {
+        let yason_buf = YasonBuf::parse(input).unwrap();
+        let path = str::parse::<PathExpression>(path).unwrap();
+
+        let outcome = path
+            .query_outcome(yason_buf.as_ref(), with_wrapper, None, None)
+            .unwrap();
+        assert_eq!(outcome.empty_reason, expected);
+        assert_eq!(outcome.is_empty(), matches!(outcome.value, QueriedValue::None));
+    }
+
+    let input = r#"{"key1": 123, "key2": [1, 2], "key3": {"key1": "abc"}}"#;
+
+    assert(input, "$.key1", false, None);
+    assert(input, "$.key2[*]", true, None);
+    assert(input, "$.key10", false, Some(EmptyReason::NoMatch));
+    assert(input, "$.key2[5]", false, Some(EmptyReason::NoMatch));
+    assert(input, "$.key3.key2", true, Some(EmptyReason::NoMatch));
+
+    assert(input, "$.key1.key2", false, Some(EmptyReason::TypeMismatch));
+    assert(input, "$.key1.*", true, Some(EmptyReason::TypeMismatch));
+    assert(input, "$.key1[1]", false, Some(EmptyReason::TypeMismatch));
+    assert(input, "$.key3[last - 1]", false, Some(EmptyReason::TypeMismatch));
+    assert(input, "$.key1[0]", false, None);
+    assert(input, "$.key2[*].key1", true, Some(EmptyReason::TypeMismatch));
+    assert(input, "$.key1.count()", true, None);
+}
+
+#[test]
+fn test_query_with_clauses() {
+    fn query(
+        input: &str,
+        path: &str,
+        with_wrapper: bool,
+        on_empty: OnEmpty,
+        on_error: OnError,
+    ) -> Result<String, YasonError> {
+        let yason_buf = YasonBuf::parse(input).unwrap();
+        let path = str::parse::<PathExpression>(path).unwrap();
+
+        let value = path.query_with_clauses(yason_buf.as_ref(), with_wrapper, on_empty, on_error, None, None)?;
+        let mut res = String::new();
+        value.format_to(false, &mut res).unwrap();
+        Ok(res)
+    }
+
+    let input = r#"{"key1": 123, "key2": [1, 2]}"#;
+    let default = YasonBuf::parse(r#""default""#).unwrap();
+
+    let res = query(input, "$.key1", false, OnEmpty::Error, OnError::Error).unwrap();
+    assert_eq!(res, "123");
+    let res = query(input, "$.key2[*]", true, OnEmpty::Error, OnError::Error).unwrap();
+    assert_eq!(res, "[1,2]");
+
+    // on empty
+    let res = query(input, "$.key3", false, OnEmpty::Null, OnError::Error).unwrap();
+    assert_eq!(res, "null");
+    let res = query(input, "$.key3", false, OnEmpty::Default(&default), OnError::Error).unwrap();
+    assert_eq!(res, r#""default""#);
+    let res = query(input, "$.key3", false, OnEmpty::Error, OnError::Null);
+    assert!(matches!(res, Err(YasonError::EmptyQueryResult)));
+
+    // on error
+    let res = query(input, "$.key1.key3", false, OnEmpty::Error, OnError::Null).unwrap();
+    assert_eq!(res, "null");
+    let res = query(input, "$.key1.key3", false, OnEmpty::Null, OnError::Default(&default)).unwrap();
+    assert_eq!(res, r#""default""#);
+    let res = query(input, "$.key1.key3", false, OnEmpty::Null, OnError::Error);
+    assert!(matches!(res, Err(YasonError::PathTypeMismatch)));
+    let res = query(input, "$.key2[*]", false, OnEmpty::Null, OnError::Default(&default)).unwrap();
+    assert_eq!(res, r#""default""#);
+    let res = query(input, "$.key2[*]", false, OnEmpty::Null, OnError::Error);
+    assert!(matches!(res, Err(YasonError::MultiValuesWithoutWrapper)));
+
+    // usage errors are not handled by on error
+    let res = query(input, "$.key1.size()", false, OnEmpty::Null, OnError::Null);
+    assert!(matches!(res, Err(YasonError::MultiValuesWithoutWrapper)));
+    let res = query(input, "$.key1.size()", true, OnEmpty::Null, OnError::Null).unwrap();
+    assert_eq!(res, "[1]");
+    let res = query(input, "$.key2[$idx]", false, OnEmpty::Null, OnError::Default(&default));
+    assert!(matches!(res, Err(YasonError::UnboundVariable(_))));
+}
+
+#[test]
+fn test_query_with_budget() {
+    let yason = YasonBuf::parse(r#"{"a": [{"b": 1}, {"b": 2}, {"b": 3}]}"#).unwrap();
+    let path = "$..b".parse::<PathExpression>().unwrap();
+
+    let mut budget = StepBudget::unlimited();
+    let value = path.query_with_budget(&yason, true, None, None, &mut budget).unwrap();
+    assert!(matches!(value, QueriedValue::Values(ref values) if values.len() == 3));
+    assert!(budget.remaining() < usize::MAX);
+
+    let mut budget = StepBudget::new(3);
+    let res = path.query_with_budget(&yason, true, None, None, &mut budget);
+    assert!(matches!(res, Err(YasonError::Cancelled)));
+    assert_eq!(budget.remaining(), 0);
+
+    let mut budget = StepBudget::new(3);
+    let path = "$.a[2].b".parse::<PathExpression>().unwrap();
+    assert!(matches!(
+        path.exists_with_budget(&yason, &mut budget),
+        Err(YasonError::Cancelled)
+    ));
+    let mut budget = StepBudget::new(4);
+    assert!(path.exists_with_budget(&yason, &mut budget).unwrap());
+}
+
+#[test]
+fn test_exists_bitmap() {
+    let docs = (0..150)
+        .map(|i| {
+            let json = if i % 3 == 0 {
+                format!(r#"{{"a": {{"b": {}}}}}"#, i)
+            } else {
+                format!(r#"{{"a": [{}]}}"#, i)
+            };
+            YasonBuf::parse(json).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let path = PathExpression::from_str("$.a.b").unwrap();
+    let bitmap = yason::bulk::exists_bitmap(&path, &docs).unwrap();
+    assert_eq!(bitmap.len(), 150);
+    assert_eq!(bitmap.count_ones(), 50);
+    assert_eq!(bitmap.as_words().len(), 3);
+    for (i, doc) in docs.iter().enumerate() {
+        assert_eq!(bitmap.get(i), Some(path.exists(doc).unwrap()));
+    }
+    assert_eq!(bitmap.get(150), None);
+    assert_eq!(
+        bitmap.iter_ones().collect::<Vec<_>>(),
+        (0..150).filter(|i| i % 3 == 0).collect::<Vec<_>>()
+    );
+
+    let refs = docs.iter().map(|doc| doc.as_ref()).collect::<Vec<_>>();
+    assert_eq!(yason::bulk::exists_bitmap(&path, &refs).unwrap(), bitmap);
+    assert!(yason::bulk::exists_bitmap(&path, &refs[..0]).unwrap().is_empty());
+
+    let path = PathExpression::from_str("$.a.size()").unwrap();
+    assert!(matches!(
+        yason::bulk::exists_bitmap(&path, &docs),
+        Err(YasonError::InvalidPathExpression)
+    ));
+}
+
+#[test]
+fn test_query_context() {
+    fn assert_send<T: Send>() {}
+    assert_send::<QueryContext>();
+
+    let mut pooled = QueryContext::new();
+    let path = PathExpression::from_str("$.a[*]").unwrap();
+    let expected = [r#"[1,2,3]"#, r#"[4]"#, ""];
+    for (json, expected) in [r#"{"a": [1, 2, 3]}"#, r#"{"a": [4]}"#, r#"{"b": 5}"#]
+        .iter()
+        .zip(expected)
+    {
+        let yason = YasonBuf::parse(json).unwrap();
+        let mut ctx = pooled.recycle();
+        let mut result = String::new();
+        let value = ctx.query(&path, &yason, true).unwrap();
+        assert!(matches!(value, QueriedValue::Yason(_) | QueriedValue::None));
+        value.format_to(false, &mut result).unwrap();
+        assert_eq!(result, expected);
+
+        let value = ctx.query_values(&path, &yason, true).unwrap();
+        match value {
+            QueriedValue::ValuesRef(values) => {
+                assert_eq!(values.len(), json.matches(|c: char| c.is_ascii_digit()).count())
+            }
+            QueriedValue::None => assert!(expected.is_empty()),
+            _ => unreachable!(),
+        }
+        pooled = ctx.recycle();
+    }
+    // recycling keeps the allocations
+    let values_capacity = pooled.values_capacity();
+    let bytes_capacity = pooled.bytes_capacity();
+    assert!(values_capacity >= 3);
+    assert!(bytes_capacity > 0);
+    let mut ctx = pooled.recycle();
+    assert_eq!(ctx.values_capacity(), values_capacity);
+    assert_eq!(ctx.bytes_capacity(), bytes_capacity);
+
+    let yason = YasonBuf::parse(r#"{"a": [1], "b": {}}"#).unwrap();
+    let path = PathExpression::from_str("$.b.c").unwrap();
+    let outcome = ctx.query_outcome(&path, &yason, false).unwrap();
+    assert_eq!(outcome.empty_reason, Some(EmptyReason::NoMatch));
+    let value = ctx
+        .query_with_clauses(&path, &yason, false, OnEmpty::Null, OnError::Error)
+        .unwrap();
+    assert!(matches!(value, QueriedValue::Value(Value::Null)));
+    assert!(!ctx.exists(&path, &yason).unwrap());
+    assert!(ctx
+        .exists(&PathExpression::from_str("$.a[0]").unwrap(), &yason)
+        .unwrap());
+    assert!(matches!(
+        ctx.exists(&PathExpression::from_str("$.a.size()").unwrap(), &yason),
+        Err(YasonError::InvalidPathExpression)
+    ));
+
+    let mut cloned = ctx.clone();
+    assert!(cloned.values_capacity() >= 3);
+    cloned.shrink_to(0, 0);
+    assert_eq!(cloned.values_capacity(), 0);
+    assert_eq!(cloned.bytes_capacity(), 0);
+}
+
+#[test]
+fn test_query_with_vars() {
+    use std::collections::HashMap;
+
+    fn query(path: &str, yason: &YasonBuf, vars: &HashMap<&str, &yason::Yason>) -> Result<String, YasonError> {
+        let path = PathExpression::from_str(path).unwrap();
+        let mut result = String::new();
+        path.query_with_vars(yason, vars, true, None, None)?
+            .format_to(false, &mut result)
+            .unwrap();
+        Ok(result)
+    }
+
+    let yason = YasonBuf::parse(r#"{"a": [10, 20, 30, 40]}"#).unwrap();
+    let other = YasonBuf::parse(r#"{"b": {"c": true}}"#).unwrap();
+    let one = YasonBuf::parse("1").unwrap();
+    let two = YasonBuf::parse("2.0").unwrap();
+    let half = YasonBuf::parse("1.5").unwrap();
+    let negative = YasonBuf::parse("-1").unwrap();
+    let string = YasonBuf::parse(r#""1""#).unwrap();
+    let mut vars = HashMap::new();
+    vars.insert("doc", other.as_ref());
+    vars.insert("i", one.as_ref());
+    vars.insert("j", two.as_ref());
+    vars.insert("half", half.as_ref());
+    vars.insert("neg", negative.as_ref());
+    vars.insert("str", string.as_ref());
+
+    assert_eq!(query("$.a[$i]", &yason, &vars).unwrap(), "[20]");
+    assert_eq!(query("$.a[$i to $j]", &yason, &vars).unwrap(), "[20,30]");
+    assert_eq!(
+        query("$.a[$j, 0, $i to last]", &yason, &vars).unwrap(),
+        "[30,10,20,30,40]"
+    );
+    assert_eq!(query("$doc.b.c", &yason, &vars).unwrap(), "[true]");
+    assert_eq!(query("$doc.b[$i]", &yason, &vars).unwrap(), "");
+    assert_eq!(query("$doc.size()", &yason, &vars).unwrap(), "[1]");
+    assert_eq!(query("$.a[0]", &yason, &vars).unwrap(), "[10]");
+
+    assert!(matches!(query("$x.a", &yason, &vars), Err(YasonError::UnboundVariable(name)) if name == "x"));
+    assert!(matches!(query("$.a[$x]", &yason, &vars), Err(YasonError::UnboundVariable(name)) if name == "x"));
+    for name in ["half", "neg", "str"] {
+        let path = format!("$.a[{}]", "$".to_string() + name);
+        assert!(matches!(query(&path, &yason, &vars), Err(YasonError::InvalidVariable(n)) if n == name));
+    }
+
+    let path = PathExpression::from_str("$.a[$i]").unwrap();
+    assert!(path.has_variables());
+    assert!(!PathExpression::from_str("$.a[1]").unwrap().has_variables());
+    assert!(path.exists_with_vars(&yason, &vars).unwrap());
+    assert!(!PathExpression::from_str("$doc.b.d")
+        .unwrap()
+        .exists_with_vars(&yason, &vars)
+        .unwrap());
+    assert!(matches!(path.exists(&yason), Err(YasonError::UnboundVariable(name)) if name == "i"));
+    assert!(matches!(
+        path.query(&yason, true, None, None),
+        Err(YasonError::UnboundVariable(name)) if name == "i"
+    ));
+}
+
+#[test]
+fn test_query_unquoted_keys() {
+    let yason = YasonBuf::parse(r#"{"foo_bar": 1, "$id": 2, "名前": {"ключ": 3}, "_": [4]}"#).unwrap();
+    for (path, expected) in [
+        ("$.foo_bar", "1"),
+        ("$.$id", "2"),
+        ("$.名前.ключ", "3"),
+        ("$..ключ", "3"),
+        ("$._[0]", "4"),
+    ] {
+        let path = PathExpression::from_str(path).unwrap();
+        let mut result = String::new();
+        path.query(&yason, false, None, None)
+            .unwrap()
+            .format_to(false, &mut result)
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+}
+
+#[test]
+fn test_typed_get() {
+    let yason = YasonBuf::parse(r#"{"id": 7, "ratio": 0.5, "ok": true, "name": "foo", "tags": ["a", "b"]}"#).unwrap();
+    let path = |s: &str| PreparedPath::from_str(s).unwrap();
+
+    assert_eq!(path("$.id").get_i64(&yason).unwrap(), Some(7));
+    assert_eq!(path("$.ok").get_bool(&yason).unwrap(), Some(true));
+    assert_eq!(path("$.name").get_str(&yason).unwrap(), Some("foo"));
+    assert_eq!(path("$.tags[1]").get_str(&yason).unwrap(), Some("b"));
+    assert_eq!(path("$.missing").get_i64(&yason).unwrap(), None);
+    assert_eq!(path("$.tags[5]").get_str(&yason).unwrap(), None);
+
+    assert!(matches!(
+        path("$.name").get_i64(&yason),
+        Err(YasonError::UnexpectedType {
+            expected: DataType::Number,
+            actual: DataType::String
+        })
+    ));
+    assert!(matches!(
+        path("$.tags").get_str(&yason),
+        Err(YasonError::UnexpectedType {
+            expected: DataType::String,
+            actual: DataType::Array
+        })
+    ));
+    assert!(matches!(
+        path("$.ratio").get_i64(&yason),
+        Err(YasonError::InvalidInteger(_))
+    ));
+    assert!(matches!(
+        path("$.tags[*]").get_str(&yason),
+        Err(YasonError::MultiValuesWithoutWrapper)
+    ));
+    assert!(matches!(
+        path("$.tags.size()").get_i64(&yason),
+        Err(YasonError::ItemMethodNotSupported)
+    ));
+    assert!(matches!(
+        path("$.name.type()").get_str(&yason),
+        Err(YasonError::ItemMethodNotSupported)
+    ));
+    assert!(matches!(
+        path("$.tags.count()").get_bool(&yason),
+        Err(YasonError::ItemMethodNotSupported)
+    ));
+    assert_eq!(
+        YasonError::ItemMethodNotSupported.code(),
+        ErrorCode::ItemMethodNotSupported
+    );
+    assert!(matches!(
+        path("$v.id").get_i64(&yason),
+        Err(YasonError::UnboundVariable(_))
+    ));
+}
+
+#[test]
+fn test_query_owned() {
+    let yason = YasonBuf::parse(r#"{"a": [1, {"b": "x"}, 3], "c": null}"#).unwrap();
+    let path = PathExpression::from_str("$.a[*]").unwrap();
+    let owned = match path.query_owned(&yason, true).unwrap() {
+        QueriedValue::YasonOwned(owned) => owned,
+        _ => unreachable!(),
+    };
+
+    // the result buffer can be reused while the owned result is kept
+    let mut result = Vec::new();
+    let mut query_buf = Vec::new();
+    for _ in 0..2 {
+        match path
+            .query(&yason, true, Some(&mut query_buf), Some(&mut result))
+            .unwrap()
+        {
+            QueriedValue::Yason(yason) => assert_eq!(yason, owned.as_ref()),
+            _ => unreachable!(),
+        }
+    }
+    assert_eq!(owned.format(false).to_string(), r#"[1,{"b":"x"},3]"#);
+
+    let mut s = String::new();
+    path.query_owned(&yason, true)
+        .unwrap()
+        .format_to(false, &mut s)
+        .unwrap();
+    assert_eq!(s, r#"[1,{"b":"x"},3]"#);
+
+    let path = PathExpression::from_str("$.c").unwrap();
+    assert!(matches!(
+        path.query_owned(&yason, false).unwrap(),
+        QueriedValue::Value(Value::Null)
+    ));
+    let path = PathExpression::from_str("$.d").unwrap();
+    assert!(matches!(path.query_owned(&yason, true).unwrap(), QueriedValue::None));
+    let path = PathExpression::from_str("$.a[*]").unwrap();
+    assert!(matches!(
+        path.query_owned(&yason, false),
+        Err(YasonError::MultiValuesWithoutWrapper)
+    ));
+}
+
+#[test]
+fn test_query_spans() {
+    let mut yason = YasonBuf::parse(r#"{"a": [1, null, "x", false, {"b": 2}], "c": {"b": [true]}}"#).unwrap();
+    let assert_spans = |yason: &Yason, path: &str, expected: &[&str]| {
+        let path = PathExpression::from_str(path).unwrap();
+        let spans = path.query_spans(yason).unwrap();
+        let values: Vec<String> = spans
+            .into_iter()
+            .map(|span| {
+                let value = Yason::from_bytes(&yason.as_bytes()[span]).unwrap();
+                value.format(false).to_string()
+            })
+            .collect();
+        assert_eq!(values, expected);
+    };
+
+    assert_spans(&yason, "$.a[*]", &["1", "null", r#""x""#, "false", r#"{"b":2}"#]);
+    assert_spans(&yason, "$..b", &["2", "[true]"]);
+    assert_spans(&yason, "$.c.b[0]", &["true"]);
+    assert_spans(&yason, "$", &[r#"{"a":[1,null,"x",false,{"b":2}],"c":{"b":[true]}}"#]);
+    assert_spans(&yason, "$.d", &[]);
+
+    // the ranges are relative to the whole bytes, including the header
+    yason.add_header().unwrap();
+    assert_spans(&yason, "$.a[1 to 2]", &["null", r#""x""#]);
+
+    let path = PathExpression::from_str("$.a.size()").unwrap();
+    assert!(matches!(
+        path.query_spans(&yason),
+        Err(YasonError::InvalidPathExpression)
+    ));
+}
+
+#[test]
+fn test_query_with_limit() {
+    let yason = YasonBuf::parse(r#"{"a": [{"k": 1}, {"k": 2}, {"b": {"k": 3}}, {"k": 4}], "k": 0}"#).unwrap();
+    let path = |s: &str| PathExpression::from_str(s).unwrap();
+    let page = |path: &PathExpression, offset: usize, limit: usize| {
+        let mut result = Vec::new();
+        match path
+            .query_with_limit(&yason, true, offset, limit, None, Some(&mut result))
+            .unwrap()
+        {
+            QueriedValue::Yason(yason) => yason.format(false).to_string(),
+            QueriedValue::None => String::new(),
+            _ => unreachable!(),
+        }
+    };
+
+    let descendant = path("$..k");
+    assert_eq!(page(&descendant, 0, usize::MAX), "[0,1,2,3,4]");
+    assert_eq!(page(&descendant, 0, 2), "[0,1]");
+    assert_eq!(page(&descendant, 2, 2), "[2,3]");
+    assert_eq!(page(&descendant, 4, 2), "[4]");
+    assert_eq!(page(&descendant, 5, 2), "");
+    assert_eq!(page(&descendant, 0, 0), "");
+    assert_eq!(page(&path("$.a[*].k"), 1, 10), "[2,4]");
+
+    // the first value is returned without array wrapper
+    let mut query_buf = Vec::new();
+    let first = path("$.a[*].k")
+        .query_with_limit(&yason, false, 0, 1, Some(&mut query_buf), None)
+        .unwrap();
+    assert!(matches!(first, QueriedValue::Value(Value::Number(n)) if n == Number::from(1)));
+    assert!(matches!(
+        path("$.a[*].k").query_with_limit(&yason, false, 0, 2, None, None),
+        Err(YasonError::MultiValuesWithoutWrapper)
+    ));
+    assert!(matches!(
+        path("$.a.size()").query_with_limit(&yason, true, 0, 1, None, None),
+        Err(YasonError::InvalidPathExpression)
+    ));
+}
+
+#[test]
+fn test_descendant_order() {
+    let yason = YasonBuf::parse(r#"{"k": 1, "bb": {"k": 5}, "c": [{"k": 4}], "a": {"b": {"k": 3}, "k": 2}}"#).unwrap();
+    let query = |order: DescendantOrder| {
+        let path = PathExpression::from_str("$..k").unwrap().with_descendant_order(order);
+        let mut result = Vec::new();
+        match path.query(&yason, true, None, Some(&mut result)).unwrap() {
+            QueriedValue::Yason(yason) => yason.format(false).to_string(),
+            _ => unreachable!(),
+        }
+    };
+
+    // the values of an object are visited in key order: "a", "c", "k", "bb"
+    assert_eq!(
+        PathExpression::from_str("$..k").unwrap().descendant_order(),
+        DescendantOrder::PreOrder
+    );
+    assert_eq!(query(DescendantOrder::PreOrder), "[1,2,3,4,5]");
+    assert_eq!(query(DescendantOrder::PostOrder), "[3,2,4,5,1]");
+    assert_eq!(query(DescendantOrder::BreadthFirst), "[1,2,5,3,4]");
+
+    // the order is kept when variables are bound, and applies to every descendant step
+    let path = PathExpression::from_str("$..b..k")
+        .unwrap()
+        .with_descendant_order(DescendantOrder::BreadthFirst);
+    let vars = std::collections::HashMap::new();
+    let mut result = Vec::new();
+    match path
+        .query_with_vars(&yason, &vars, true, None, Some(&mut result))
+        .unwrap()
+    {
+        QueriedValue::Yason(yason) => assert_eq!(yason.format(false).to_string(), "[3]"),
+        _ => unreachable!(),
+    }
+
+    let path = PathExpression::from_str("$..k")
+        .unwrap()
+        .with_descendant_order(DescendantOrder::BreadthFirst);
+    let mut result = Vec::new();
+    let page = path
+        .query_with_limit(&yason, true, 0, 3, None, Some(&mut result))
+        .unwrap();
+    assert!(matches!(page, QueriedValue::Yason(yason) if yason.format(false).to_string() == "[1,2,5]"));
+    assert!(path.exists(&yason).unwrap());
+}
+
+#[test]
+fn test_error_code() {
+    let err = PathExpression::from_str("a.b").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::PathNotStartWithDollar);
+
+    let yason = YasonBuf::parse(r#"{"a": [1, 2]}"#).unwrap();
+    let path = PathExpression::from_str("$.a[*]").unwrap();
+    let err = path.query(&yason, false, None, None).err().unwrap();
+    assert_eq!(err.code(), ErrorCode::MultiValuesWithoutWrapper);
+
+    for value in 0..1000 {
+        if let Ok(code) = ErrorCode::try_from(value) {
+            assert_eq!(i32::from(code), value);
+        }
+    }
+    assert!(ErrorCode::try_from(0).is_err());
+}
+
+#[test]
+fn test_path_parse_diagnostic() {
+    let diagnostic = |input: &str| PathExpression::from_str(input).unwrap_err().render_diagnostic(input);
+
+    assert_eq!(
+        diagnostic("  @.a"),
+        "path must start with a dollar sign ($) character at position 3\n  @.a\n  ^\n\
+         help: paths start with `$`, try `  $.a`"
+    );
+    assert_eq!(
+        diagnostic("$.a['b c'][0]"),
+        "array step contains unexpected characters at position 5\n$.a['b c'][0]\n    ^\n\
+         help: keys are quoted with double quotes, try `$.a.\"b c\"[0]`"
+    );
+    assert_eq!(
+        diagnostic("$.'it\"s'"),
+        "key step contains unexpected characters at position 3\n$.'it\"s'\n  ^\n\
+         help: keys are quoted with double quotes, try `$.\"it\\\"s\"`"
+    );
+    assert_eq!(
+        diagnostic("$.1st.name"),
+        "key step contains unexpected characters at position 3\n$.1st.name\n  ^\n\
+         help: keys with characters other than letters, digits, `_` and `$` must be double-quoted, \
+         try `$.\"1st\".name`"
+    );
+    assert_eq!(
+        diagnostic("$.名前 x"),
+        "unexpected characters after end of path at position 10\n$.名前 x\n     ^\n\
+         help: keys with characters other than letters, digits, `_` and `$` must be double-quoted, \
+         try `$.\"名前 x\"`"
+    );
+    // no suggestion for mistakes other than quotes
+    assert_eq!(
+        diagnostic("$[0]\t("),
+        "invalid character at start of step at position 6\n$[0]\t(\n    \t^"
+    );
+    assert_eq!(
+        diagnostic("$.size().a"),
+        "unexpected characters after end of path at position 9\n$.size().a\n        ^"
+    );
+}
+
+#[test]
+fn test_binary_query() {
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    let mut array = builder.push_array("a", 2).unwrap();
+    array.push_binary([1u8, 2]).unwrap();
+    array.push_binary([1u8, 2]).unwrap();
+    array.finish().unwrap();
+    let yason = builder.finish().unwrap();
+
+    let query = |path: &str, with_wrapper: bool| {
+        let path = PathExpression::from_str(path).unwrap();
+        let mut s = String::new();
+        path.query(&yason, with_wrapper, None, None)
+            .unwrap()
+            .format_to(false, &mut s)
+            .unwrap();
+        s
+    };
+    assert_eq!(query("$.a[0]", false), r#""AQI=""#);
+    assert_eq!(query("$.a[0].type()", true), r#"["binary"]"#);
+    assert_eq!(query("$.a[*].distinct()", true), r#"["AQI="]"#);
+}
+
+#[test]
+fn test_query_indexed() {
+    let json = r#"{
+        "k1": 1, "k2": 2, "k3": 3, "k4": 4, "k5": 5, "k6": 6, "k7": 7,
+        "small": {"x": "small"},
+        "rows": [
+            {"a": 1, "b": 2, "c": 3, "d": 4, "e": 5, "f": 6, "g": 7, "x": "row0"},
+            {"a": 1, "b": 2, "c": 3, "d": 4, "e": 5, "f": 6, "g": 7, "x": "row1"}
+        ]
+    }"#;
+    let yason = YasonBuf::parse(json).unwrap();
+    let index = yason.index();
+    assert_eq!(index.cached_objects(), 0);
+
+    let paths = [
+        "$.k7",
+        "$.small.x",
+        "$.rows[*].x",
+        "$.rows[1].g",
+        "$..x",
+        "$.missing",
+        "$.rows.x",
+        "$.rows[*].x.type()",
+    ];
+    for _ in 0..2 {
+        for path in paths.iter() {
+            let path = PathExpression::from_str(path).unwrap();
+            let mut expected = String::new();
+            let mut actual = String::new();
+            path.query(&yason, true, None, None)
+                .unwrap()
+                .format_to(false, &mut expected)
+                .unwrap();
+            path.query_indexed(&index, true, None, None)
+                .unwrap()
+                .format_to(false, &mut actual)
+                .unwrap();
+            assert_eq!(actual, expected, "{:?}", path);
+        }
+    }
+    // The root and both rows, the small object is looked up by binary search.
+    assert_eq!(index.cached_objects(), 3);
+
+    let path = PathExpression::from_str("$.rows[*].x").unwrap();
+    assert!(matches!(
+        path.query_indexed(&index, false, None, None),
+        Err(YasonError::MultiValuesWithoutWrapper)
+    ));
+    let path = PathExpression::from_str("$.k1").unwrap();
+    assert!(
+        matches!(path.query_indexed(&index, false, None, None).unwrap(), QueriedValue::Value(Value::Number(n)) if n == Number::from(1))
+    );
+
+    let root = yason.object().unwrap();
+    assert!(matches!(index.get(&root, "k3").unwrap(), Some(Value::Number(n)) if n == Number::from(3)));
+    assert!(index.get(&root, "k8").unwrap().is_none());
+
+    // Objects of another yason are looked up without being cached.
+    let other = YasonBuf::parse(r#"{"a": 1, "b": 2, "c": 3, "d": 4, "e": 5, "f": 6, "g": 7, "h": 8}"#).unwrap();
+    let other = other.object().unwrap();
+    assert!(matches!(index.get(&other, "h").unwrap(), Some(Value::Number(n)) if n == Number::from(8)));
+    assert_eq!(index.cached_objects(), 3);
+
+    let mut index = index;
+    index.clear();
+    assert_eq!(index.cached_objects(), 0);
+}
+
+#[test]
+fn test_query_deep_document() {
+    use yason::raw::{ARRAY_SIZE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE, VALUE_ENTRY_SIZE};
+
+    // nested single-element arrays, deeper than the builders allow, around {"x": 1}
+    const DEPTH: usize = 100_000;
+    const HEADER_SIZE: usize = DATA_TYPE_SIZE + ARRAY_SIZE + ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE;
+    let leaf = YasonBuf::parse(r#"{"x": 1}"#).unwrap();
+    let mut bytes = Vec::with_capacity(DEPTH * HEADER_SIZE + leaf.as_bytes().len());
+    for level in 0..DEPTH {
+        let inner_size = (DEPTH - 1 - level) * HEADER_SIZE + leaf.as_bytes().len();
+        let inner_type = if level + 1 < DEPTH {
+            DataType::Array
+        } else {
+            DataType::Object
+        };
+        bytes.push(DataType::Array as u8);
+        bytes.extend_from_slice(&((ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE + inner_size) as i32).to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.push(inner_type as u8);
+        bytes.extend_from_slice(&((ELEMENT_COUNT_SIZE + VALUE_ENTRY_SIZE) as u32).to_le_bytes());
+    }
+    bytes.extend_from_slice(leaf.as_bytes());
+    let yason = unsafe { Yason::new_unchecked(&bytes) };
+
+    for order in [
+        DescendantOrder::PreOrder,
+        DescendantOrder::PostOrder,
+        DescendantOrder::BreadthFirst,
+    ] {
+        let path = PathExpression::from_str("$..x").unwrap().with_descendant_order(order);
+        let mut result = Vec::new();
+        match path.query(yason, true, None, Some(&mut result)).unwrap() {
+            QueriedValue::Yason(yason) => assert_eq!(yason.format(false).to_string(), "[1]"),
+            _ => unreachable!(),
+        }
+        assert!(path.exists(yason).unwrap());
+    }
+
+    let path = PathExpression::from_str("$.*.y").unwrap();
+    assert!(!path.exists(yason).unwrap());
+}
+
+#[test]
+fn test_query_count() {
+    let mut json = String::from("[");
+    for i in 0..10_000 {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str(&format!(r#"{{"id": {}, "child": {{"id": {}}}}}"#, i, -i));
+    }
+    json.push(']');
+    let yason = YasonBuf::parse(&json).unwrap();
+
+    fn count(yason: &Yason, path: &str) -> Value<'static> {
+        let path = PathExpression::from_str(path).unwrap();
+        let mut query_buf = Vec::new();
+        let value = match path.query(yason, true, Some(&mut query_buf), None).unwrap() {
+            // only the count itself is kept in the query buffer
+            QueriedValue::ValuesRef(values) => {
+                assert_eq!(values.len(), 1);
+                values[0].clone()
+            }
+            _ => unreachable!(),
+        };
+        match value {
+            Value::Number(number) => Value::Number(number),
+            _ => unreachable!(),
+        }
+    }
+
+    assert_eq!(count(&yason, "$[*].count()"), Value::Number(Number::from(10_000)));
+    assert_eq!(count(&yason, "$..id.count()"), Value::Number(Number::from(20_000)));
+    assert_eq!(count(&yason, "$[*].child.count()"), Value::Number(Number::from(10_000)));
+    assert_eq!(count(&yason, "$[*].none.count()"), Value::Number(Number::from(0)));
+}
+
+#[test]
+fn test_query_first_match() {
+    let yason = YasonBuf::parse(r#"{"a": {"id": 1}, "b": [{"id": 2}, {"id": 3}], "c": {"name": "x"}}"#).unwrap();
+
+    let path = PathExpression::from_str("$..id").unwrap();
+    assert_eq!(path.strategy(), QueryStrategy::AllMatches);
+    assert!(matches!(
+        path.query(&yason, false, None, None),
+        Err(YasonError::MultiValuesWithoutWrapper)
+    ));
+
+    let path = path.with_strategy(QueryStrategy::FirstMatch);
+    assert!(matches!(
+        path.query(&yason, false, None, None).unwrap(),
+        QueriedValue::Value(Value::Number(n)) if n == Number::from(1)
+    ));
+    assert_eq!(path.get_i64(&yason).unwrap(), Some(1));
+    // the array wrapper still selects every match
+    let mut result = Vec::new();
+    match path.query(&yason, true, None, Some(&mut result)).unwrap() {
+        QueriedValue::Yason(yason) => assert_eq!(yason.format(false).to_string(), "[1,2,3]"),
+        _ => unreachable!(),
+    }
+
+    // the traversal stops right after the first match
+    let mut budget = StepBudget::new(4);
+    assert!(matches!(
+        path.query_with_budget(&yason, false, None, None, &mut budget).unwrap(),
+        QueriedValue::Value(Value::Number(n)) if n == Number::from(1)
+    ));
+
+    let path = PathExpression::from_str("$.b[*].id")
+        .unwrap()
+        .with_strategy(QueryStrategy::FirstMatch);
+    assert!(matches!(
+        path.query(&yason, false, None, None).unwrap(),
+        QueriedValue::Value(Value::Number(n)) if n == Number::from(2)
+    ));
+    let path = PathExpression::from_str("$..none")
+        .unwrap()
+        .with_strategy(QueryStrategy::FirstMatch);
+    assert!(matches!(
+        path.query(&yason, false, None, None).unwrap(),
+        QueriedValue::None
+    ));
+}
+
+#[test]
+fn test_path_introspection() {
+    let path = PathExpression::from_str("$.a.*[2 to 5, last - 1, $i]..b[7].size()").unwrap();
+    assert_eq!(path.step_count(), 7);
+    assert!(path.has_wildcard());
+    assert!(path.has_descendant());
+    assert!(path.has_method());
+    assert_eq!(path.max_array_index(), Some(7));
+
+    let steps: Vec<_> = path.steps().collect();
+    assert_eq!(steps.len(), path.steps().len());
+    assert_eq!(steps[0], PathStep::Root);
+    assert_eq!(steps[1], PathStep::Key("a"));
+    assert_eq!(steps[2], PathStep::KeyWildcard);
+    match steps[3].clone() {
+        PathStep::Array(selectors) => {
+            assert_eq!(selectors.len(), 3);
+            assert_eq!(
+                selectors.collect::<Vec<_>>(),
+                [
+                    ArraySelector::Range(ArrayIndex::Index(2), ArrayIndex::Index(5)),
+                    ArraySelector::Single(ArrayIndex::Last(1)),
+                    ArraySelector::Single(ArrayIndex::Variable("i")),
+                ]
+            );
+        }
+        _ => unreachable!(),
+    }
+    assert_eq!(steps[4], PathStep::Descendant("b"));
+    assert_eq!(steps[6], PathStep::Method(PathMethod::Size));
+    assert_eq!(path.steps().next_back(), Some(PathStep::Method(PathMethod::Size)));
+
+    let path = PathExpression::from_str("$x.a[*][last]").unwrap();
+    assert_eq!(path.steps().next(), Some(PathStep::Variable("x")));
+    assert_eq!(path.steps().nth(2), Some(PathStep::ArrayWildcard));
+    assert!(path.has_wildcard());
+    assert!(!path.has_descendant());
+    assert!(!path.has_method());
+    assert_eq!(path.max_array_index(), None);
+
+    let path = PathExpression::from_str("$[3 to last]").unwrap();
+    assert_eq!(path.step_count(), 2);
+    assert!(!path.has_wildcard());
+    assert_eq!(path.max_array_index(), Some(3));
+}
+
+#[test]
+fn test_query_result() {
+    fn select(path: &str, doc: &str) -> QueryResult<Arc<YasonBuf>> {
+        let yason = Arc::new(YasonBuf::parse(doc).unwrap());
+        PathExpression::from_str(path).unwrap().query_result(yason).unwrap()
+    }
+
+    let result = select("$.a[*]", r#"{"a": [{"b": 1}, true, null, "x"], "c": 2}"#);
+    assert_eq!(result.len(), 4);
+    assert!(!result.is_empty());
+    assert_eq!(result.iter().len(), 4);
+    let formatted: Vec<_> = result.iter().map(|yason| yason.format(false).to_string()).collect();
+    assert_eq!(formatted, [r#"{"b":1}"#, "true", "null", r#""x""#]);
+    assert!(matches!(result.value(1), Some(Ok(Value::Bool(true)))));
+    assert!(matches!(result.value(3), Some(Ok(Value::String("x")))));
+    assert!(result.get(4).is_none());
+    assert!(result.value(4).is_none());
+    for (yason, span) in result.iter().zip(result.spans()) {
+        assert_eq!(yason.as_bytes(), &result.buffer().as_bytes()[span.clone()]);
+    }
+
+    let buffer = Arc::clone(result.buffer());
+    drop(result);
+    assert_eq!(Arc::strong_count(&buffer), 1);
+
+    let result = select("$.b", r#"{"a": 1}"#);
+    assert!(result.is_empty());
+    assert_eq!(result.into_buffer().format(false).to_string(), r#"{"a":1}"#);
+
+    let yason = YasonBuf::parse(r#"[1, 2]"#).unwrap();
+    let path = PathExpression::from_str("$.size()").unwrap();
+    assert!(matches!(
+        path.query_result(yason),
+        Err(YasonError::InvalidPathExpression)
+    ));
+}
+
+#[test]
+fn test_sub_yason() {
+    let yason = YasonBuf::parse(r#"{"a": {"b": [1, {"c": true}, "x"]}, "n": null}"#).unwrap();
+    let path = |s: &str| PathExpression::from_str(s).unwrap();
+    let mut buf = Vec::new();
+
+    let inner = yason.sub_yason(&path("$.a.b"), &mut buf).unwrap().unwrap();
+    assert_eq!(inner.data_type().unwrap(), DataType::Array);
+    assert_eq!(inner.format(false).to_string(), r#"[1,{"c":true},"x"]"#);
+    // the sub-yason is a root document of its own
+    inner.validate().unwrap();
+    assert_eq!(path("$[1].c").get_bool(inner).unwrap(), Some(true));
+    let range = yason.as_bytes().as_ptr_range();
+    assert!(range.contains(&inner.as_bytes().as_ptr()));
+
+    let nested = yason.sub_yason(&path("$.a.b[1]"), &mut buf).unwrap().unwrap();
+    assert_eq!(nested.object().unwrap().bool("c").unwrap(), Some(true));
+
+    let scalar = yason.sub_yason(&path("$.a.b[2]"), &mut buf).unwrap().unwrap();
+    assert_eq!(scalar.string().unwrap(), "x");
+    let scalar = yason.sub_yason(&path("$.n"), &mut buf).unwrap().unwrap();
+    assert!(scalar.is_null().unwrap());
+
+    assert!(yason.sub_yason(&path("$.missing"), &mut buf).unwrap().is_none());
+    assert!(matches!(
+        yason.sub_yason(&path("$.a.b[*]"), &mut buf),
+        Err(YasonError::MultiValuesWithoutWrapper)
+    ));
+}
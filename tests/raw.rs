@@ -0,0 +1,167 @@
+//! Binary format tests
+
+use yason::raw::{
+    self, ARRAY_SIZE, CHECKSUM_SIZE, COMPACT_KEY_OFFSET_SIZE, COMPACT_OBJECT_TYPE, DATA_TYPE_SIZE, ELEMENT_COUNT_SIZE,
+    FLAG_CHECKSUM, FORMAT_VERSION, HEADER_MAGIC, HEADER_SIZE, KEY_LENGTH_SIZE, KEY_OFFSET_SIZE,
+    MAX_COMPACT_OBJECT_SIZE, MAX_DATA_LENGTH_SIZE, MAX_STRING_SIZE, OBJECT_SIZE, VALUE_ENTRY_SIZE,
+};
+use yason::{ArrayBuilder, BuildError, DataType, ObjectBuilder, Value, YasonBuf, YasonError};
+
+#[test]
+fn test_varint() {
+    let cases: [(u32, &[u8]); 6] = [
+        (0, &[0]),
+        (127, &[127]),
+        (128, &[128, 1]),
+        (16383, &[255, 127]),
+        (16384, &[128, 128, 1]),
+        (MAX_STRING_SIZE as u32, &[255, 255, 255, 127]),
+    ];
+    for (value, expected) in cases.iter() {
+        let mut buf = vec![0xaa];
+        raw::encode_varint(*value, &mut buf).unwrap();
+        assert_eq!(&buf[1..], *expected);
+        assert_eq!(raw::varint_size(*value), expected.len());
+        assert_eq!(raw::decode_varint(&buf, 1).unwrap(), (*value, expected.len()));
+    }
+
+    let mut buf = Vec::new();
+    assert!(matches!(
+        raw::encode_varint(MAX_STRING_SIZE as u32 + 1, &mut buf),
+        Err(BuildError::StringTooLong(_))
+    ));
+    assert!(buf.is_empty());
+
+    assert!(matches!(
+        raw::decode_varint(&[0x80, 0x80], 0),
+        Err(YasonError::IndexOutOfBounds { len: 2, index: 2 })
+    ));
+    assert!(matches!(
+        raw::decode_varint(&[1], 1),
+        Err(YasonError::IndexOutOfBounds { len: 1, index: 1 })
+    ));
+    assert!(matches!(
+        raw::decode_varint(&[0x80; MAX_DATA_LENGTH_SIZE + 1], 0),
+        Err(YasonError::InvalidFormat(_))
+    ));
+}
+
+#[test]
+fn test_layout() {
+    let yason = YasonBuf::parse(r#"{"a": [true, "xy"]}"#).unwrap();
+    let bytes = yason.as_bytes();
+    let read_u32 = |pos: usize| u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+
+    // objects keep the legacy layout with 4-byte key offsets by default
+    assert_eq!(bytes[0], DataType::Object as u8);
+    let start = DATA_TYPE_SIZE + OBJECT_SIZE;
+    assert_eq!(read_u32(DATA_TYPE_SIZE), bytes.len() - start);
+    assert_eq!(u16::from_le_bytes([bytes[start], bytes[start + 1]]), 1);
+
+    let key_pos = start + read_u32(start + ELEMENT_COUNT_SIZE);
+    assert_eq!(key_pos, start + ELEMENT_COUNT_SIZE + KEY_OFFSET_SIZE);
+    assert_eq!(&bytes[key_pos..key_pos + KEY_LENGTH_SIZE + 1], &[1, 0, b'a']);
+
+    let array_pos = key_pos + KEY_LENGTH_SIZE + 1;
+    assert_eq!(bytes[array_pos], DataType::Array as u8);
+    let array_start = array_pos + DATA_TYPE_SIZE + ARRAY_SIZE;
+    let entries = array_start + ELEMENT_COUNT_SIZE;
+    // bool is inlined, string is outlined
+    assert_eq!(bytes[entries], DataType::Bool as u8);
+    assert_eq!(read_u32(entries + DATA_TYPE_SIZE), 1);
+    assert_eq!(bytes[entries + VALUE_ENTRY_SIZE], DataType::String as u8);
+    let string_pos = array_start + read_u32(entries + VALUE_ENTRY_SIZE + DATA_TYPE_SIZE);
+    assert_eq!(bytes[string_pos], DataType::String as u8);
+    assert_eq!(raw::decode_varint(bytes, string_pos + DATA_TYPE_SIZE).unwrap(), (2, 1));
+    assert_eq!(&bytes[string_pos + DATA_TYPE_SIZE + 1..], b"xy");
+
+    let mut yason = ObjectBuilder::try_new(0, false)
+        .unwrap()
+        .with_checksum()
+        .finish()
+        .unwrap();
+    assert_eq!(
+        yason.as_bytes().len(),
+        DATA_TYPE_SIZE + OBJECT_SIZE + ELEMENT_COUNT_SIZE + CHECKSUM_SIZE
+    );
+    yason.add_header().unwrap();
+    assert!(yason.as_bytes().starts_with(&HEADER_MAGIC));
+    assert_eq!(yason.as_bytes()[HEADER_MAGIC.len()], FORMAT_VERSION);
+    assert_eq!(yason.as_bytes()[HEADER_MAGIC.len() + 1], FLAG_CHECKSUM);
+    assert_eq!(yason.as_bytes()[HEADER_SIZE], DataType::Object as u8);
+}
+
+#[test]
+fn test_compact_object() {
+    assert!(matches!(DataType::try_from(COMPACT_OBJECT_TYPE), Ok(DataType::Object)));
+
+    // one key "a" with a string value, the size of the compact object is 11 bytes more than the string
+    let build = |len: usize| {
+        let mut builder = ObjectBuilder::try_new(1, false).unwrap().with_compact_objects();
+        builder.push_string("a", "x".repeat(len)).unwrap();
+        builder.finish().unwrap()
+    };
+    let fits = MAX_COMPACT_OBJECT_SIZE - 11;
+    for (len, compact) in [(fits, true), (fits + 1, false)] {
+        let yason = build(len);
+        let bytes = yason.as_bytes();
+        let (type_byte, offset_size) = if compact {
+            (COMPACT_OBJECT_TYPE, COMPACT_KEY_OFFSET_SIZE)
+        } else {
+            (DataType::Object as u8, KEY_OFFSET_SIZE)
+        };
+        assert_eq!(bytes[0], type_byte);
+        let size = i32::from_le_bytes(bytes[DATA_TYPE_SIZE..DATA_TYPE_SIZE + OBJECT_SIZE].try_into().unwrap());
+        assert_eq!(
+            size as usize,
+            ELEMENT_COUNT_SIZE + offset_size + KEY_LENGTH_SIZE + 1 + 1 + 3 + len
+        );
+        yason.validate().unwrap();
+        assert_eq!(yason.data_type().unwrap(), DataType::Object);
+        assert!(matches!(yason.object().unwrap().get("a").unwrap(), Some(Value::String(s)) if s.len() == len));
+    }
+
+    // a compact object nested in an array and in a large object
+    let mut builder = ArrayBuilder::try_new(2).unwrap().with_compact_objects();
+    builder.push_string("x".repeat(MAX_COMPACT_OBJECT_SIZE)).unwrap();
+    let mut object = builder.push_object(1, true).unwrap();
+    object.push_bool("b", true).unwrap();
+    object.finish().unwrap();
+    let array = builder.finish().unwrap();
+    let mut builder = ObjectBuilder::try_new(2, true).unwrap().with_compact_objects();
+    builder.push_yason("a", &array).unwrap();
+    let mut object = builder.push_object("b", 1, true).unwrap();
+    object.push_null("c").unwrap();
+    object.finish().unwrap();
+    let yason = builder.finish().unwrap();
+    assert_eq!(yason.as_bytes()[0], DataType::Object as u8);
+    yason.validate().unwrap();
+    let object = yason.object().unwrap();
+    let nested = object.array("a").unwrap().unwrap().object(1).unwrap();
+    assert_eq!(nested.yason().as_bytes()[0], COMPACT_OBJECT_TYPE);
+    assert!(matches!(nested.get("b").unwrap(), Some(Value::Bool(true))));
+    assert_eq!(
+        object.object("b").unwrap().unwrap().yason().as_bytes()[0],
+        COMPACT_OBJECT_TYPE
+    );
+    assert_eq!(object.is_type("b", DataType::Object).unwrap(), Some(true));
+
+    // nested objects are compact only if asked for
+    let mut builder = ArrayBuilder::try_new(1).unwrap();
+    let mut object = builder.push_object(1, true).unwrap();
+    object.push_null("a").unwrap();
+    object.finish().unwrap();
+    let array = builder.finish().unwrap();
+    let object = array.array().unwrap().object(0).unwrap();
+    assert_eq!(object.yason().as_bytes()[0], DataType::Object as u8);
+
+    // the option is kept by reset, and deduplicate keeps the layout
+    let mut builder = ObjectBuilder::try_new(1, true).unwrap().with_compact_objects();
+    builder.push_null("a").unwrap();
+    builder.finish_ref().unwrap();
+    builder.reset(1, true).unwrap();
+    builder.push_null("b").unwrap();
+    let compact = builder.finish_ref().unwrap();
+    assert_eq!(compact.as_bytes()[0], COMPACT_OBJECT_TYPE);
+    assert_eq!(compact.deduplicate().unwrap().as_bytes(), compact.as_bytes());
+}
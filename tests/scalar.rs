@@ -86,3 +86,16 @@ fn test_null() {
     assert_eq!(yason.data_type().unwrap(), DataType::Null);
     assert!(yason.is_null().unwrap());
 }
+
+#[test]
+fn test_binary() {
+    let yason = Scalar::binary([1u8, 2, 3]).unwrap();
+    assert_eq!(yason.data_type().unwrap(), DataType::Binary);
+    assert_eq!(yason.binary().unwrap(), [1u8, 2, 3]);
+    assert!(yason.string().is_err());
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(128);
+    let yason = Scalar::binary_with_vec([], &mut bytes).unwrap();
+    assert_eq!(yason.data_type().unwrap(), DataType::Binary);
+    assert!(yason.binary().unwrap().is_empty());
+}
@@ -0,0 +1,339 @@
+//! Temporal value tests
+
+use std::str::FromStr;
+use yason::{
+    yason_object, ArrayBuilder, DataType, Date, DtInterval, ObjectBuilder, PathExpression, Scalar, ShortDate, Time,
+    Timestamp, Value, Yason, YmInterval,
+};
+
+// 2024-02-29T13:45:30.5
+const MICROS: i64 = 1_709_214_330_500_000;
+
+#[test]
+fn test_temporal_display() {
+    assert_eq!(Timestamp::from_micros(MICROS).to_string(), "2024-02-29T13:45:30.5");
+    assert_eq!(Timestamp::from_micros(0).to_string(), "1970-01-01T00:00:00");
+    assert_eq!(Timestamp::from_micros(-1).to_string(), "1969-12-31T23:59:59.999999");
+    assert_eq!(
+        Timestamp::from_micros(253_402_300_800_000_000).to_string(),
+        "+10000-01-01T00:00:00"
+    );
+
+    let date = Date::from_micros(MICROS);
+    assert_eq!(date.to_string(), "2024-02-29T13:45:30");
+    assert_eq!(date.micros(), MICROS - 500_000);
+    assert_eq!(Date::from_micros(-1).to_string(), "1969-12-31T23:59:59");
+
+    assert_eq!(Time::from_micros(49_530_500_000).unwrap().to_string(), "13:45:30.5");
+    assert_eq!(Time::from_micros(0).unwrap().to_string(), "00:00:00");
+    assert!(Time::from_micros(86_400_000_000).is_none());
+    assert!(Time::from_micros(-1).is_none());
+}
+
+#[test]
+fn test_temporal_object() {
+    let mut builder = ObjectBuilder::try_new(5, false).unwrap();
+    builder.push_timestamp("ts", Timestamp::from_micros(MICROS)).unwrap();
+    builder.push_date("date", Date::from_micros(MICROS)).unwrap();
+    builder
+        .push_time("time", Time::from_micros(49_530_500_000).unwrap())
+        .unwrap();
+    builder.push_interval_year_month("ym", 14).unwrap();
+    builder.push_interval_day_time("dt", 93_784_500_000).unwrap();
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+
+    let object = yason.object().unwrap();
+    assert_eq!(object.type_of("ts").unwrap(), Some(DataType::Timestamp));
+    assert_eq!(object.timestamp("ts").unwrap(), Some(Timestamp::from_micros(MICROS)));
+    assert_eq!(object.date("date").unwrap(), Some(Date::from_micros(MICROS)));
+    assert_eq!(object.time("time").unwrap(), Time::from_micros(49_530_500_000));
+    assert_eq!(
+        object.interval_year_month("ym").unwrap(),
+        Some(YmInterval::from_months(14))
+    );
+    assert_eq!(
+        object.interval_day_time("dt").unwrap(),
+        Some(DtInterval::from_micros(93_784_500_000))
+    );
+    assert_eq!(object.timestamp("missing").unwrap(), None);
+    assert!(object.timestamp("date").is_err());
+    assert!(matches!(object.get("ym").unwrap(), Some(Value::IntervalYearMonth(m)) if m.months() == 14));
+
+    assert_eq!(
+        yason.format(false).to_string(),
+        r#"{"dt":"P1DT2H3M4.5S","ts":"2024-02-29T13:45:30.5","ym":"P1Y2M","date":"2024-02-29T13:45:30","time":"13:45:30.5"}"#
+    );
+
+    let path = PathExpression::from_str("$.*.type()").unwrap();
+    let mut s = String::new();
+    path.query(&yason, true, None, None)
+        .unwrap()
+        .format_to(false, &mut s)
+        .unwrap();
+    assert_eq!(
+        s,
+        r#"["daysecondInterval","timestamp","yearmonthInterval","date","time"]"#
+    );
+}
+
+#[test]
+fn test_temporal_array() {
+    let mut builder = ArrayBuilder::try_new(6).unwrap();
+    builder.push_timestamp(Timestamp::from_micros(-1)).unwrap();
+    builder.push_date(Date::from_micros(0)).unwrap();
+    builder.push_time(Time::from_micros(1).unwrap()).unwrap();
+    builder.push_interval_year_month(-3).unwrap();
+    builder.push_interval_day_time(0).unwrap();
+    builder.push_interval_day_time(-1_000_000).unwrap();
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+
+    let array = yason.array().unwrap();
+    assert_eq!(array.timestamp(0).unwrap(), Timestamp::from_micros(-1));
+    assert_eq!(array.date(1).unwrap(), Date::from_micros(0));
+    assert_eq!(array.time(2).unwrap(), Time::from_micros(1).unwrap());
+    assert_eq!(array.interval_year_month(3).unwrap(), YmInterval::from_months(-3));
+    assert_eq!(array.interval_day_time(5).unwrap(), DtInterval::from_micros(-1_000_000));
+    assert!(array.timestamp(1).is_err());
+    assert!(array.timestamp(6).is_err());
+    let intervals: Vec<_> = array
+        .iter_of::<DtInterval>()
+        .unwrap()
+        .skip(4)
+        .map(|v| v.unwrap())
+        .collect();
+    assert_eq!(
+        intervals,
+        [DtInterval::from_micros(0), DtInterval::from_micros(-1_000_000)]
+    );
+
+    assert_eq!(
+        yason.format(false).to_string(),
+        r#"["1969-12-31T23:59:59.999999","1970-01-01T00:00:00","00:00:00.000001","-P3M","PT0S","-PT1S"]"#
+    );
+
+    let values: Vec<Value> = array.iter().unwrap().map(|v| v.unwrap()).collect();
+    assert!(values[0]
+        .compare_with(&values[0], &yason::BinaryCollation)
+        .unwrap()
+        .is_eq());
+    assert!(values[4]
+        .compare_with(&values[5], &yason::BinaryCollation)
+        .unwrap()
+        .is_gt());
+    assert!(values[3].compare_with(&values[4], &yason::BinaryCollation).is_none());
+}
+
+#[test]
+fn test_temporal_scalar() {
+    let yason = Scalar::timestamp(Timestamp::from_micros(MICROS)).unwrap();
+    assert_eq!(yason.data_type().unwrap(), DataType::Timestamp);
+    assert_eq!(yason.timestamp().unwrap(), Timestamp::from_micros(MICROS));
+    assert!(yason.date().is_err());
+
+    assert_eq!(
+        Scalar::interval_year_month(YmInterval::from_months(0))
+            .unwrap()
+            .format(false)
+            .to_string(),
+        r#""P0M""#
+    );
+    assert_eq!(
+        Scalar::interval_year_month(YmInterval::from_years(1).unwrap())
+            .unwrap()
+            .format(false)
+            .to_string(),
+        r#""P1Y""#
+    );
+    assert_eq!(
+        Scalar::interval_day_time(DtInterval::from_days(1).unwrap())
+            .unwrap()
+            .format(false)
+            .to_string(),
+        r#""P1D""#
+    );
+    assert_eq!(
+        Scalar::interval_day_time(DtInterval::from_seconds(7_200).unwrap())
+            .unwrap()
+            .format(false)
+            .to_string(),
+        r#""PT2H""#
+    );
+
+    let yason = yason_object! {
+        "ts" => Timestamp::from_micros(0),
+        "time" => Time::from_micros(0),
+    }
+    .unwrap();
+    assert_eq!(
+        yason.format(false).to_string(),
+        r#"{"ts":"1970-01-01T00:00:00","time":"00:00:00"}"#
+    );
+}
+
+#[test]
+fn test_interval() {
+    assert_eq!(YmInterval::from_months(14).to_string(), "P1Y2M");
+    assert_eq!(YmInterval::from_months(-3).to_string(), "-P3M");
+    assert_eq!(YmInterval::default().to_string(), "P0M");
+    assert_eq!(YmInterval::new(2, -1).unwrap(), YmInterval::from_months(23));
+    assert_eq!(YmInterval::from_months(-27).years(), -2);
+    assert!(YmInterval::from_years(i32::MAX).is_none());
+    assert!(YmInterval::new(i32::MAX / 12, 12).is_none());
+    assert_eq!(
+        YmInterval::from_months(5).checked_sub(YmInterval::from_months(7)),
+        Some(YmInterval::from_months(-2))
+    );
+    assert!(YmInterval::from_months(i32::MAX)
+        .checked_add(YmInterval::from_months(1))
+        .is_none());
+    assert!(YmInterval::from_months(i32::MIN).checked_neg().is_none());
+    assert!(YmInterval::from_months(-1) < YmInterval::from_months(0));
+
+    let dt = DtInterval::from_micros(93_784_500_000);
+    assert_eq!(dt.to_string(), "P1DT2H3M4.5S");
+    assert_eq!(dt.days(), 1);
+    assert_eq!(dt.seconds(), 93_784);
+    assert_eq!(DtInterval::default().to_string(), "PT0S");
+    assert_eq!(DtInterval::from_seconds(-1).unwrap().to_string(), "-PT1S");
+    assert_eq!(DtInterval::from_seconds(90).unwrap().to_string(), "PT1M30S");
+    assert_eq!(DtInterval::from_days(-2).unwrap().to_string(), "-P2D");
+    assert!(DtInterval::from_days(i64::MAX / 1_000).is_none());
+    assert_eq!(
+        dt.checked_add(DtInterval::from_seconds(-4).unwrap()),
+        Some(DtInterval::from_micros(93_780_500_000))
+    );
+    assert_eq!(dt.checked_neg().unwrap().to_string(), "-P1DT2H3M4.5S");
+    assert!(DtInterval::from_micros(i64::MIN)
+        .checked_sub(DtInterval::from_micros(1))
+        .is_none());
+    assert!(DtInterval::from_seconds(1) > DtInterval::from_seconds(0));
+
+    let start = Timestamp::from_micros(MICROS);
+    let end = start.checked_add(DtInterval::from_days(1).unwrap()).unwrap();
+    assert_eq!(end.to_string(), "2024-03-01T13:45:30.5");
+    assert_eq!(end.checked_since(start), DtInterval::from_days(1));
+    assert_eq!(end.checked_sub(DtInterval::from_days(1).unwrap()), Some(start));
+    assert!(Timestamp::from_micros(i64::MAX)
+        .checked_add(DtInterval::from_micros(1))
+        .is_none());
+
+    let yason = yason_object! {
+        "ym" => YmInterval::from_months(14),
+        "dt" => dt,
+    }
+    .unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"{"dt":"P1DT2H3M4.5S","ym":"P1Y2M"}"#);
+}
+
+#[test]
+fn test_short_date() {
+    // 2024-02-29 is 19782 days after 1970-01-01.
+    let date = ShortDate::from_days(19_782);
+    assert_eq!(date.to_string(), "2024-02-29");
+    assert_eq!(ShortDate::from_days(0).to_string(), "1970-01-01");
+    assert_eq!(ShortDate::from_days(-1).to_string(), "1969-12-31");
+    assert_eq!(ShortDate::from_days(-719_529).to_string(), "-0001-12-31");
+    assert_eq!(ShortDate::from_days(i32::MAX).to_string(), "+5881580-07-11");
+
+    assert_eq!(ShortDate::from(Date::from_micros(MICROS)), date);
+    assert_eq!(ShortDate::from(Date::from_micros(-1)), ShortDate::from_days(-1));
+    assert_eq!(date.to_date().unwrap().to_string(), "2024-02-29T00:00:00");
+    assert!(ShortDate::from_days(i32::MAX).to_date().is_none());
+
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap();
+    builder.push_short_date("d", date).unwrap();
+    builder.push_short_date("e", Date::from_micros(0)).unwrap();
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+    let object = yason.object().unwrap();
+    assert_eq!(object.type_of("d").unwrap(), Some(DataType::ShortDate));
+    assert_eq!(object.short_date("d").unwrap(), Some(date));
+    assert_eq!(object.short_date("e").unwrap(), Some(ShortDate::from_days(0)));
+    assert!(object.date("d").is_err());
+    assert_eq!(
+        yason.format(false).to_string(),
+        r#"{"d":"2024-02-29","e":"1970-01-01"}"#
+    );
+
+    let path = PathExpression::from_str("$.d.type()").unwrap();
+    let mut s = String::new();
+    path.query(&yason, true, None, None)
+        .unwrap()
+        .format_to(false, &mut s)
+        .unwrap();
+    assert_eq!(s, r#"["shortDate"]"#);
+
+    let mut builder = ArrayBuilder::try_new(2).unwrap();
+    builder.push_short_date(ShortDate::from_days(-1)).unwrap();
+    builder.push_date(Date::from_micros(0)).unwrap();
+    let yason = builder.finish().unwrap();
+    yason.validate().unwrap();
+    let array = yason.array().unwrap();
+    assert_eq!(array.short_date(0).unwrap(), ShortDate::from_days(-1));
+    assert!(array.short_date(1).is_err());
+
+    let yason = Scalar::short_date(date).unwrap();
+    assert_eq!(yason.short_date().unwrap(), date);
+    assert_eq!(yason.format(false).to_string(), r#""2024-02-29""#);
+
+    let yason = yason_object! { "d" => date }.unwrap();
+    assert_eq!(yason.format(false).to_string(), r#"{"d":"2024-02-29"}"#);
+}
+
+#[test]
+fn test_temporal_validate() {
+    let mut bytes = vec![DataType::Time as u8];
+    bytes.extend_from_slice(&86_400_000_000i64.to_le_bytes());
+    let yason = unsafe { Yason::new_unchecked(&bytes) };
+    assert!(yason.validate().is_err());
+    assert!(yason.time().is_err());
+
+    let mut bytes = vec![DataType::Date as u8];
+    bytes.extend_from_slice(&1i64.to_le_bytes());
+    assert!(unsafe { Yason::new_unchecked(&bytes) }.validate().is_err());
+
+    let bytes = [DataType::Timestamp as u8, 0, 0, 0];
+    assert!(unsafe { Yason::new_unchecked(&bytes) }.validate().is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_temporal_chrono() {
+    use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+
+    let naive = NaiveDate::from_ymd_opt(2024, 2, 29)
+        .unwrap()
+        .and_hms_micro_opt(13, 45, 30, 500_000)
+        .unwrap();
+    let timestamp = Timestamp::from(naive);
+    assert_eq!(timestamp.micros(), MICROS);
+    assert_eq!(timestamp.to_naive(), Some(naive));
+    assert_eq!(timestamp.to_utc(), Some(Utc.from_utc_datetime(&naive)));
+    assert_eq!(Timestamp::from(Utc.from_utc_datetime(&naive)), timestamp);
+    assert_eq!(
+        Timestamp::from_micros(-1).to_naive().unwrap().to_string(),
+        "1969-12-31 23:59:59.999999"
+    );
+    assert_eq!(Timestamp::from_micros(i64::MAX).to_naive(), None);
+
+    let date = Date::from(naive);
+    assert_eq!(date.to_naive(), Some(naive.date().and_hms_opt(13, 45, 30).unwrap()));
+    assert_eq!(Date::from(naive.date()).to_string(), "2024-02-29T00:00:00");
+
+    let short_date = ShortDate::from(naive.date());
+    assert_eq!(short_date, ShortDate::from_days(19_782));
+    assert_eq!(short_date.to_naive(), Some(naive.date()));
+    assert_eq!(ShortDate::from_days(i32::MIN).to_naive(), None);
+
+    let time = NaiveTime::from_hms_micro_opt(13, 45, 30, 500_000).unwrap();
+    assert_eq!(Time::from(time).micros(), 49_530_500_000);
+    assert_eq!(Time::from(time).to_naive(), time);
+
+    let mut builder = ObjectBuilder::try_new(1, false).unwrap();
+    builder.push_timestamp("ts", naive).unwrap();
+    let yason = builder.finish().unwrap();
+    let read = yason.object().unwrap().timestamp("ts").unwrap().unwrap();
+    assert_eq!(read.to_naive(), Some(naive));
+}
@@ -0,0 +1,28 @@
+//! Yason testkit tests
+
+#![cfg(feature = "testkit")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+use yason::testkit::{self, ArbitraryDocument, GenOptions};
+
+proptest! {
+    #[test]
+    fn test_document_round_trip(doc in testkit::document(GenOptions::default())) {
+        testkit::assert_round_trip(&doc);
+    }
+}
+
+#[test]
+fn test_arbitrary_round_trip() {
+    let data: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+    let mut u = Unstructured::new(&data);
+    while !u.is_empty() {
+        let ArbitraryDocument(doc) = ArbitraryDocument::arbitrary(&mut u).unwrap();
+        testkit::assert_round_trip(&doc);
+    }
+
+    let mut u = Unstructured::new(&data);
+    let doc = testkit::arbitrary_document(&mut u, GenOptions::new(0, 1, 0, 0)).unwrap();
+    assert!(!doc.is_array() && !doc.is_object());
+}
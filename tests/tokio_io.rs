@@ -0,0 +1,121 @@
+//! Asynchronous io tests
+
+#![cfg(feature = "tokio")]
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+use yason::YasonBuf;
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Reads at most `chunk_size` bytes at a time.
+struct Chunked<'a> {
+    input: &'a [u8],
+    chunk_size: usize,
+}
+
+impl AsyncRead for Chunked<'_> {
+    fn poll_read(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let len = self.input.len().min(self.chunk_size).min(buf.remaining());
+        buf.put_slice(&self.input[..len]);
+        self.input = &self.input[len..];
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn parse_chunked(json: &str, chunk_size: usize) -> io::Result<YasonBuf> {
+    let mut reader = Chunked {
+        input: json.as_bytes(),
+        chunk_size,
+    };
+    block_on(YasonBuf::parse_async(&mut reader))
+}
+
+#[test]
+fn test_parse_async() {
+    let large = format!(
+        "[{}]",
+        (0..2000)
+            .map(|i| format!(r#"{{"id": {}, "name": "item {}"}}"#, i, i))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let jsons = [
+        r#"{"a": [1, 2, {"b": "c"}], "d": null, "e": {}}"#,
+        r#" [[], {}, "x\"y\\", -1.5e-3, true, false] "#,
+        "{\"caf\\u00e9\": \"\\ud83d\\ude00 caf\u{e9}\", \"b\": 1, \"caf\u{e9}\": 12345678901234567890123}",
+        r#""scalar""#,
+        "123.456",
+        "null",
+        "[]",
+        "{}",
+        large.as_str(),
+    ];
+    for json in jsons {
+        let expected = YasonBuf::parse(json).unwrap();
+        for chunk_size in [1, 2, 3, 7, 8192] {
+            let yason = parse_chunked(json, chunk_size).unwrap();
+            assert_eq!(yason.as_bytes(), expected.as_bytes(), "{}", json);
+        }
+    }
+
+    let nested = format!("{}{}", "[".repeat(101), "]".repeat(101));
+    for json in [
+        "",
+        " ",
+        "{",
+        "[1,]",
+        "[1 2]",
+        r#"{"a" 1}"#,
+        r#"{"a": 1,}"#,
+        r#"{1: 1}"#,
+        "tru",
+        "truex",
+        "01",
+        "1 2",
+        r#""abc"#,
+        r#"["\ud83d"]"#,
+        "[1]]",
+        "}",
+        nested.as_str(),
+    ] {
+        for chunk_size in [1, 3, 8192] {
+            let err = parse_chunked(json, chunk_size).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", json);
+        }
+    }
+}
+
+#[test]
+fn test_format_to_async() {
+    let large = format!(
+        "[{}]",
+        (0..2000)
+            .map(|i| format!(r#"{{"id": {}, "name": "item {}"}}"#, i, i))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let jsons = [
+        r#"{"a": [1, 2, {"b": "c"}], "d": null, "e": {}}"#,
+        r#"[[], {}, "x", 1.5, true]"#,
+        r#""scalar""#,
+        "[]",
+        "{}",
+        large.as_str(),
+    ];
+    for json in jsons {
+        let yason = YasonBuf::parse(json).unwrap();
+        for pretty in [false, true] {
+            let mut output = Vec::new();
+            block_on(yason.format_to_async(pretty, &mut output)).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), yason.format(pretty).to_string());
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Tracing integration tests
+
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+use tracing::field::{Empty, Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use yason::YasonBuf;
+
+#[derive(Default)]
+struct Recorder(Vec<String>);
+
+impl Visit for Recorder {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.push(format!("{}=i64:{}", field.name(), value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.push(format!("{}=bool:{}", field.name(), value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push(format!("{}=str:{}", field.name(), value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push(format!("{}=debug:{:?}", field.name(), value));
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingSubscriber(Arc<Mutex<Recorder>>);
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, values: &Record<'_>) {
+        values.record(&mut *self.0.lock().unwrap());
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+const DOC: &str = r#"{"id": 7, "ok": true, "ratio": 0.5, "none": null, "user": {"name": "foo"}, "tags": [1, 2]}"#;
+
+#[test]
+fn test_record_span() {
+    let yason = YasonBuf::parse(DOC).unwrap();
+    let subscriber = RecordingSubscriber::default();
+    tracing::subscriber::with_default(subscriber.clone(), || {
+        let span = tracing::info_span!(
+            "request",
+            id = Empty,
+            ok = Empty,
+            ratio = Empty,
+            none = Empty,
+            user.name = Empty,
+            user.age = Empty,
+            tags = Empty,
+            missing = Empty,
+        );
+        yason.record_span(&span).unwrap();
+    });
+    let recorded = &subscriber.0.lock().unwrap().0;
+    assert_eq!(
+        recorded,
+        &[
+            "id=i64:7",
+            "ok=bool:true",
+            "ratio=debug:0.5",
+            "none=debug:null",
+            "user.name=str:foo",
+            "tags=debug:[1,2]",
+        ]
+    );
+}
+
+#[test]
+fn test_record_fields() {
+    let yason = YasonBuf::parse(DOC).unwrap();
+    tracing::subscriber::with_default(RecordingSubscriber::default(), || {
+        let span = tracing::info_span!("request", user = Empty, ok = Empty, id.value = Empty);
+        let mut recorder = Recorder::default();
+        yason
+            .record_fields(span.metadata().unwrap().fields(), &mut recorder)
+            .unwrap();
+        assert_eq!(recorder.0, [r#"user=debug:{"name":"foo"}"#, "ok=bool:true"]);
+    });
+
+    let array = YasonBuf::parse("[1]").unwrap();
+    let span = tracing::Span::none();
+    assert!(array.record_span(&span).is_err());
+}
@@ -0,0 +1,186 @@
+//! Transform tests.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+use yason::{transform, ObjectBuilder, PathExpression, Value, YasonBuf, YasonError};
+
+fn snake_case(key: &str) -> Option<Cow<'_, str>> {
+    if !key.chars().any(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let mut renamed = String::new();
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            renamed.push('_');
+            renamed.push(c.to_ascii_lowercase());
+        } else {
+            renamed.push(c);
+        }
+    }
+    Some(Cow::Owned(renamed))
+}
+
+#[test]
+fn test_rename_keys() {
+    let yason = YasonBuf::parse(
+        r#"{"userId": 1, "b": [{"firstName": "x", "zz": null}, [{"aB": true}], 2.5], "c": {"deepKey": {"k": "v"}}}"#,
+    )
+    .unwrap();
+    let mut buf = vec![0xff];
+    let renamed = transform::rename_keys(&yason, snake_case, &mut buf).unwrap();
+    let expected = YasonBuf::parse(
+        r#"{"user_id": 1, "b": [{"first_name": "x", "zz": null}, [{"a_b": true}], 2.5], "c": {"deep_key": {"k": "v"}}}"#,
+    )
+    .unwrap();
+    assert!(renamed.equals(&expected).unwrap());
+    renamed.validate().unwrap();
+    assert_eq!(buf[0], 0xff);
+
+    // the key-values keep their insertion order
+    let mut builder = ObjectBuilder::try_new(3, false).unwrap();
+    builder.push_bool("zKey", true).unwrap();
+    builder.push_bool("a", false).unwrap();
+    builder.push_null("mm").unwrap();
+    let yason = builder.finish().unwrap();
+    let mut buf = Vec::new();
+    let renamed = transform::rename_keys(&yason, snake_case, &mut buf).unwrap();
+    renamed.validate().unwrap();
+    let keys = renamed
+        .object()
+        .unwrap()
+        .iter_insertion_order()
+        .unwrap()
+        .map(|entry| entry.unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, ["z_key", "a", "mm"]);
+
+    // scalars are copied as they are
+    let yason = YasonBuf::parse(r#""fooBar""#).unwrap();
+    let mut buf = Vec::new();
+    let renamed = transform::rename_keys(&yason, snake_case, &mut buf).unwrap();
+    assert_eq!(renamed.string().unwrap(), "fooBar");
+}
+
+#[test]
+fn test_rename_keys_error() {
+    let yason = YasonBuf::parse(r#"{"a": {"fooBar": 1, "foo_bar": 2}}"#).unwrap();
+    let mut buf = Vec::new();
+    assert!(matches!(
+        transform::rename_keys(&yason, snake_case, &mut buf),
+        Err(YasonError::DuplicateKey(key)) if key == "foo_bar"
+    ));
+
+    let yason = YasonBuf::parse(r#"[{"a": 1}]"#).unwrap();
+    let long = "k".repeat(u16::MAX as usize + 1);
+    let mut buf = Vec::new();
+    assert!(matches!(
+        transform::rename_keys(&yason, |_| Some(Cow::Owned(long.clone())), &mut buf),
+        Err(YasonError::KeyTooLong(65536))
+    ));
+}
+
+fn paths(paths: &[&str]) -> Vec<PathExpression> {
+    paths
+        .iter()
+        .map(|path| PathExpression::from_str(path).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_redact() {
+    let yason = YasonBuf::parse(
+        r#"{"user": {"name": "foo", "email": "a@b", "age": 30}, "items": [true, {"ssn": "x"}, null, 1.5], "ok": [1, 2]}"#,
+    )
+    .unwrap();
+    let mask = Value::String("***");
+
+    let mut buf = Vec::new();
+    let redacted = transform::redact(
+        &yason,
+        &paths(&["$.user.email", "$..ssn", "$.items[0, 2]"]),
+        &mask,
+        &mut buf,
+    )
+    .unwrap();
+    redacted.validate().unwrap();
+    let expected = YasonBuf::parse(
+        r#"{"user": {"name": "foo", "email": "***", "age": 30}, "items": ["***", {"ssn": "***"}, "***", 1.5], "ok": [1, 2]}"#,
+    )
+    .unwrap();
+    assert!(redacted.equals(&expected).unwrap());
+
+    // a selected container is replaced as a whole, even if its descendants are selected too
+    let mut buf = Vec::new();
+    let redacted = transform::redact(&yason, &paths(&["$.user.name", "$.user"]), &Value::Null, &mut buf).unwrap();
+    let expected =
+        YasonBuf::parse(r#"{"user": null, "items": [true, {"ssn": "x"}, null, 1.5], "ok": [1, 2]}"#).unwrap();
+    assert!(redacted.equals(&expected).unwrap());
+
+    // the untouched containers are copied byte-for-byte
+    let ok = yason.object().unwrap().array("ok").unwrap().unwrap();
+    let copied = redacted.object().unwrap().array("ok").unwrap().unwrap();
+    assert_eq!(copied.yason().as_bytes(), ok.yason().as_bytes());
+
+    let mut buf = vec![1, 2, 3];
+    let redacted = transform::redact(&yason, &paths(&["$.missing", "$.ok[5]"]), &mask, &mut buf).unwrap();
+    assert_eq!(redacted.as_bytes(), yason.as_bytes());
+    assert_eq!(&buf[..3], &[1, 2, 3]);
+
+    let mut buf = Vec::new();
+    let redacted = transform::redact(&yason, &paths(&["$"]), &mask, &mut buf).unwrap();
+    assert_eq!(redacted.string().unwrap(), "***");
+
+    let mut buf = Vec::new();
+    assert!(matches!(
+        transform::redact(&yason, &paths(&["$.user.count()"]), &mask, &mut buf),
+        Err(YasonError::InvalidPathExpression)
+    ));
+}
+
+#[test]
+fn test_project() {
+    let yason = YasonBuf::parse(
+        r#"{"user": {"name": "foo", "email": "a@b", "age": 30}, "items": [true, {"ssn": "x", "n": 1}, null, 1.5], "ok": [1, 2]}"#,
+    )
+    .unwrap();
+
+    let mut buf = Vec::new();
+    let projected = transform::project(
+        &yason,
+        &paths(&["$.user.email", "$..ssn", "$.items[0, 2]", "$.ok"]),
+        &mut buf,
+    )
+    .unwrap();
+    projected.validate().unwrap();
+    let expected =
+        YasonBuf::parse(r#"{"user": {"email": "a@b"}, "items": [true, {"ssn": "x"}, null], "ok": [1, 2]}"#).unwrap();
+    assert!(projected.equals(&expected).unwrap());
+
+    // a selected container is kept as a whole, even if its descendants are selected too
+    let mut buf = Vec::new();
+    let projected = transform::project(&yason, &paths(&["$.user.name", "$.user"]), &mut buf).unwrap();
+    let user = yason.object().unwrap().object("user").unwrap().unwrap();
+    let copied = projected.object().unwrap().object("user").unwrap().unwrap();
+    assert_eq!(projected.object().unwrap().len().unwrap(), 1);
+    assert_eq!(copied.yason().as_bytes(), user.yason().as_bytes());
+
+    let mut buf = vec![1, 2, 3];
+    let projected = transform::project(&yason, &paths(&["$.missing", "$.ok[5]"]), &mut buf).unwrap();
+    assert_eq!(projected.format(false).to_string(), "{}");
+    assert_eq!(&buf[..3], &[1, 2, 3]);
+
+    let mut buf = Vec::new();
+    let projected = transform::project(&yason, &paths(&["$"]), &mut buf).unwrap();
+    assert_eq!(projected.as_bytes(), yason.as_bytes());
+
+    let scalar = YasonBuf::parse("1").unwrap();
+    let mut buf = Vec::new();
+    let projected = transform::project(&scalar, &paths(&["$.a"]), &mut buf).unwrap();
+    assert!(projected.is_null().unwrap());
+
+    let mut buf = Vec::new();
+    assert!(matches!(
+        transform::project(&yason, &paths(&["$.user.count()"]), &mut buf),
+        Err(YasonError::InvalidPathExpression)
+    ));
+}
@@ -0,0 +1,374 @@
+//! Yason validation tests
+
+use std::str::FromStr;
+use yason::raw::COMPACT_OBJECT_TYPE;
+use yason::{ArrayBuilder, DataType, LintKind, ObjectBuilder, PathExpression, Value, Yason, YasonBuf, YasonError};
+
+fn assert_invalid(bytes: &[u8]) {
+    assert!(
+        matches!(
+            Yason::from_bytes(bytes),
+            Err(YasonError::InvalidFormat(_))
+                | Err(YasonError::IndexOutOfBounds { .. })
+                | Err(YasonError::InvalidDataType(_))
+        ),
+        "{:?} should be invalid",
+        bytes
+    );
+}
+
+#[test]
+fn test_validate() {
+    let inputs = [
+        r#"{"a": [true, "xy", null, 1.5, {"b": []}], "bc": null, "d": {}}"#,
+        "[]",
+        r#""""#,
+        "-12.5e10",
+        "false",
+        "null",
+    ];
+    for input in inputs.iter() {
+        let yason = YasonBuf::parse(input).unwrap();
+        let validated = Yason::from_bytes(yason.as_bytes()).unwrap();
+        assert_eq!(validated, yason.as_ref());
+        assert!(yason.deduplicate().unwrap().validate().is_ok());
+    }
+
+    // every truncation of a valid yason is invalid
+    let yason = YasonBuf::parse(inputs[0]).unwrap();
+    let bytes = yason.as_bytes();
+    for len in 0..bytes.len() {
+        assert_invalid(&bytes[..len]);
+    }
+
+    // trailing bytes
+    let mut trailing = bytes.to_vec();
+    trailing.push(6);
+    assert_invalid(&trailing);
+
+    // unsorted keys
+    let yason = YasonBuf::parse(r#"{"a": 1, "b": 2}"#).unwrap();
+    let mut swapped = yason.as_bytes().to_vec();
+    let (first, second) = (swapped[7..11].to_vec(), swapped[11..15].to_vec());
+    swapped[7..11].copy_from_slice(&second);
+    swapped[11..15].copy_from_slice(&first);
+    assert_invalid(&swapped);
+
+    // invalid UTF-8 string, bool and type
+    assert_invalid(&[3, 1, 0xff]);
+    assert_invalid(&[3, 0xff, 0xff, 0xff, 0xff, 0]);
+    assert_invalid(&[5, 2]);
+    assert_invalid(&[0]);
+    // negative size
+    assert_invalid(&[2, 0xff, 0xff, 0xff, 0xff, 0, 0]);
+}
+
+#[test]
+fn test_checksum() {
+    let mut builder = ObjectBuilder::try_new(2, false).unwrap().with_checksum();
+    builder.push_string("b", "text").unwrap();
+    let mut array = builder.push_array("a", 2).unwrap();
+    array.push_bool(true).unwrap();
+    array.push_null().unwrap();
+    array.finish().unwrap();
+    let yason = builder.finish().unwrap();
+
+    let expected = YasonBuf::parse(r#"{"a": [true, null], "b": "text"}"#).unwrap();
+    assert_eq!(yason.as_bytes().len(), expected.as_bytes().len() + 4);
+    assert!(yason.has_checksum().unwrap());
+    assert!(!expected.has_checksum().unwrap());
+    yason.verify_checksum().unwrap();
+    assert!(matches!(expected.verify_checksum(), Err(YasonError::InvalidFormat(_))));
+    assert_eq!(yason, expected);
+    assert_eq!(yason.format(false).to_string(), r#"{"a":[true,null],"b":"text"}"#);
+
+    // the footer is checked when the bytes are opened
+    let opened = Yason::from_bytes(yason.as_bytes()).unwrap();
+    assert_eq!(opened, expected.as_ref());
+
+    // a flipped bit is detected
+    let mut corrupted = yason.as_bytes().to_vec();
+    let len = corrupted.len();
+    corrupted[len - 6] ^= 0x01;
+    assert!(matches!(
+        Yason::from_bytes(&corrupted),
+        Err(YasonError::ChecksumMismatch { .. })
+    ));
+
+    // the footer is not copied into a parent value
+    let parent = ArrayBuilder::from_values(&[Value::try_from(yason.as_ref()).unwrap()]).unwrap();
+    Yason::from_bytes(parent.as_bytes()).unwrap();
+
+    // a footer can be added to any value
+    let mut scalar = YasonBuf::parse("\"s\"").unwrap();
+    scalar.add_checksum().unwrap();
+    scalar.add_checksum().unwrap();
+    assert_eq!(scalar.as_bytes().len(), 3 + 4);
+    Yason::from_bytes(scalar.as_bytes()).unwrap().verify_checksum().unwrap();
+
+    // a truncated value is reported instead of panicking
+    let mut truncated = unsafe { YasonBuf::new_unchecked(vec![DataType::String as u8, 5, b'a']) };
+    assert!(truncated.add_checksum().is_err());
+}
+
+#[test]
+fn test_format_version() {
+    let legacy = YasonBuf::parse(r#"{"a": [1, "x"], "b": true}"#).unwrap();
+    assert_eq!(legacy.format_version().unwrap(), 0);
+    assert!(!legacy.has_header());
+
+    let mut versioned = legacy.clone();
+    versioned.add_header().unwrap();
+    versioned.add_header().unwrap();
+    assert_eq!(versioned.format_version().unwrap(), 1);
+    assert!(versioned.has_header());
+    assert_eq!(versioned.as_bytes().len(), legacy.as_bytes().len() + 5);
+    assert_eq!(&versioned.as_bytes()[5..], legacy.as_bytes());
+
+    // readers skip the header
+    assert_eq!(versioned, legacy);
+    assert_eq!(versioned.data_type().unwrap(), DataType::Object);
+    assert_eq!(versioned.object().unwrap().len().unwrap(), 2);
+    assert_eq!(versioned.format(false).to_string(), r#"{"a":[1,"x"],"b":true}"#);
+    Yason::from_bytes(versioned.as_bytes()).unwrap();
+
+    let mut scalar = YasonBuf::parse("\"s\"").unwrap();
+    scalar.add_header().unwrap();
+    assert_eq!(scalar.string().unwrap(), "s");
+    assert!(!scalar.is_null().unwrap());
+    let parent = ArrayBuilder::from_values(&[Value::try_from(scalar.as_ref()).unwrap()]).unwrap();
+    assert_eq!(parent.format(false).to_string(), r#"["s"]"#);
+
+    // a truncated value is reported instead of panicking
+    let mut truncated = unsafe { YasonBuf::new_unchecked(vec![DataType::String as u8, 5, b'a']) };
+    assert!(truncated.add_header().is_err());
+
+    // the checksum covers the header and is flagged in it
+    versioned.add_checksum().unwrap();
+    versioned.verify_checksum().unwrap();
+    Yason::from_bytes(versioned.as_bytes()).unwrap();
+    let mut corrupted = versioned.as_bytes().to_vec();
+    corrupted[3] = 1;
+    corrupted[4] = 0;
+    assert!(matches!(
+        Yason::from_bytes(&corrupted),
+        Err(YasonError::ChecksumMismatch { .. })
+    ));
+    let len = corrupted.len();
+    corrupted[4] = 1;
+    assert!(matches!(
+        Yason::from_bytes(&corrupted[..len - 4]),
+        Err(YasonError::InvalidFormat(_))
+    ));
+
+    // unknown versions and flags are rejected
+    let mut unknown = scalar.as_bytes().to_vec();
+    unknown[3] = 2;
+    assert_invalid(&unknown);
+    unknown[3] = 1;
+    unknown[4] = 0x80;
+    assert_invalid(&unknown);
+}
+
+fn lint(bytes: &[u8]) -> Vec<(usize, LintKind)> {
+    let yason = unsafe { Yason::new_unchecked(bytes) };
+    yason
+        .lint()
+        .into_iter()
+        .map(|issue| (issue.pos(), issue.kind().clone()))
+        .collect()
+}
+
+#[test]
+fn test_lint() {
+    let inputs = [
+        r#"{"a": [true, "xy", null, 1.5, {"b": []}], "bc": null, "d": {}}"#,
+        r#"["x", "x", [1, 2]]"#,
+        "\"s\"",
+        "null",
+    ];
+    for input in inputs.iter() {
+        let mut yason = YasonBuf::parse(input).unwrap();
+        assert!(yason.lint().is_empty());
+        assert!(yason.deduplicate().unwrap().lint().is_empty());
+        yason.add_header().unwrap();
+        yason.add_checksum().unwrap();
+        assert!(yason.lint().is_empty());
+    }
+
+    let yason = YasonBuf::parse(r#"{"a": null, "b": true}"#).unwrap();
+    let bytes = yason.as_bytes();
+
+    // every problem is reported instead of the first one
+    let mut broken = bytes.to_vec();
+    broken[7..11].copy_from_slice(&[0, 0, 0, 0]);
+    broken[23] = 2;
+    let issues = lint(&broken);
+    assert_eq!(
+        issues,
+        vec![
+            (7, LintKind::BackwardOffset),
+            (15, LintKind::UnusedBytes(4)),
+            (23, LintKind::InvalidBool),
+        ]
+    );
+    assert!(Yason::from_bytes(&broken).is_err());
+
+    let mut unsorted = bytes.to_vec();
+    unsorted[7..15].rotate_left(4);
+    assert_eq!(lint(&unsorted), vec![(11, LintKind::UnsortedKeys)]);
+
+    let mut gap = bytes.to_vec();
+    gap[1] += 2;
+    gap.extend_from_slice(&[0, 0]);
+    let issues = unsafe { Yason::new_unchecked(&gap) }.lint();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].kind(), &LintKind::UnusedBytes(2));
+    assert!(!issues[0].is_error());
+    assert_eq!(issues[0].to_string(), "000018: warning: 2 unused bytes");
+
+    let overlong = [3, 0x82, 0x00, b'x', b'y'];
+    assert_eq!(lint(&overlong), vec![(1, LintKind::OverlongVarint)]);
+    Yason::from_bytes(&overlong).unwrap();
+
+    let mut trailing = bytes.to_vec();
+    trailing.push(0);
+    assert_eq!(lint(&trailing), vec![(24, LintKind::TrailingBytes(1))]);
+    assert_eq!(lint(&[0xff]), vec![(0, LintKind::InvalidDataType(0xff))]);
+}
+
+#[test]
+fn test_try_new_lossy() {
+    let yason = YasonBuf::parse(r#"{"a": null, "bc": [{"x": 1, "yz": 2}]}"#).unwrap();
+    let bytes = yason.as_bytes();
+
+    let (repaired, report) = YasonBuf::try_new_lossy(bytes.to_vec()).unwrap();
+    assert_eq!(repaired, yason);
+    assert!(report.is_empty());
+
+    // trailing bytes are removed
+    let mut trailing = bytes.to_vec();
+    trailing.extend_from_slice(b"garbage");
+    let (repaired, report) = YasonBuf::try_new_lossy(trailing).unwrap();
+    assert_eq!(repaired.as_bytes(), bytes);
+    assert_eq!(report.truncated_bytes(), 7);
+    assert_eq!(report.resorted_objects(), 0);
+
+    // unsorted keys are sorted, in nested objects too
+    let mut unsorted = bytes.to_vec();
+    unsorted[7..15].rotate_left(4);
+    let nested = unsorted.windows(3).position(|w| w == [1, 0, b'x']).unwrap();
+    let table = nested - 8;
+    unsorted[table..table + 8].rotate_left(4);
+    assert!(Yason::from_bytes(&unsorted).is_err());
+    let (repaired, report) = YasonBuf::try_new_lossy(unsorted).unwrap();
+    assert_eq!(repaired.as_bytes(), bytes);
+    assert_eq!(report.resorted_objects(), 2);
+    assert_eq!(report.truncated_bytes(), 0);
+
+    // a valid checksum is kept and updated
+    let mut checksummed = YasonBuf::parse(r#"{"a": null, "b": true}"#).unwrap();
+    let mut broken = checksummed.as_bytes().to_vec();
+    broken[7..15].rotate_left(4);
+    let mut broken = unsafe { YasonBuf::new_unchecked(broken) };
+    broken.add_checksum().unwrap();
+    let mut broken = broken.into_bytes();
+    broken.push(0);
+    checksummed.add_checksum().unwrap();
+    let (repaired, report) = YasonBuf::try_new_lossy(broken).unwrap();
+    assert_eq!(repaired.as_bytes(), checksummed.as_bytes());
+    repaired.verify_checksum().unwrap();
+    assert_eq!(report.truncated_bytes(), 1);
+    assert_eq!(report.resorted_objects(), 1);
+
+    // other problems are not repaired
+    let mut corrupted = checksummed.as_bytes().to_vec();
+    corrupted[23] = 2;
+    assert!(YasonBuf::try_new_lossy(corrupted).is_err());
+    assert!(YasonBuf::try_new_lossy(bytes[..bytes.len() - 1].to_vec()).is_err());
+    assert!(YasonBuf::try_new_lossy(vec![]).is_err());
+}
+
+/// A xorshift generator, so that the fuzz tests are reproducible without extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Runs the bytes through every checked entry point, and reads them fully if they are valid.
+fn check_no_panic(bytes: &[u8]) {
+    if !bytes.is_empty() {
+        let _ = unsafe { Yason::new_unchecked(bytes) }.lint();
+    }
+    let _ = YasonBuf::try_new_lossy(bytes.to_vec());
+    if let Ok(yason) = Yason::from_bytes(bytes) {
+        let _ = yason.format(true).to_string();
+        let _ = yason.deduplicate();
+        let _ = yason.contains_document(yason);
+        let _ = yason.equals_structural(yason);
+        if let Ok(canonical) = yason.canonicalize() {
+            let _ = yason.equals(&canonical);
+            let _ = canonical.contains_document(yason);
+        }
+        for path in ["$..b", "$.*[*]"] {
+            let path = PathExpression::from_str(path).unwrap();
+            let _ = path.query(yason, true, None, Some(&mut Vec::new()));
+        }
+    }
+}
+
+/// The type bytes of all data types, including the compact object.
+const DATA_TYPES: [u8; 14] = [1, 2, 3, 4, 5, 6, 17, 18, 19, 20, 21, 22, 23, COMPACT_OBJECT_TYPE];
+
+#[test]
+fn test_fuzz_random_bytes() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..10_000 {
+        let len = rng.below(64);
+        let mut bytes: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+        // start with a valid data type now and then to get past the first byte
+        if !bytes.is_empty() && rng.below(2) == 0 {
+            bytes[0] = DATA_TYPES[rng.below(DATA_TYPES.len())];
+        }
+        check_no_panic(&bytes);
+    }
+}
+
+#[test]
+fn test_fuzz_mutated_bytes() {
+    let input = r#"{"a": [true, "xy", null, 1.5, {"b": []}], "bc": null, "d": {"e": [1, [2, "z"]]}}"#;
+    let mut seeds = vec![YasonBuf::parse(input).unwrap().into_bytes()];
+    let mut checksummed = YasonBuf::parse(input).unwrap();
+    checksummed.add_checksum().unwrap();
+    seeds.push(checksummed.into_bytes());
+
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for seed in seeds.iter() {
+        for _ in 0..10_000 {
+            let mut bytes = seed.clone();
+            for _ in 0..1 + rng.below(4) {
+                let pos = rng.below(bytes.len());
+                match rng.below(4) {
+                    0 => bytes[pos] = rng.next() as u8,
+                    1 => bytes[pos] ^= 1 << rng.below(8),
+                    2 => bytes[pos] = [0x00, 0x7f, 0x80, 0xff][rng.below(4)],
+                    _ => bytes.truncate(pos),
+                }
+                if bytes.is_empty() {
+                    break;
+                }
+            }
+            check_no_panic(&bytes);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! WebAssembly binding tests, only the paths that do not call into JavaScript run on the host
+
+#![cfg(feature = "wasm")]
+
+use yason::wasm::JsYason;
+use yason::YasonBuf;
+
+#[test]
+fn test_wasm_parse_and_format() {
+    let yason = JsYason::parse(r#"{"b": [1, true, null], "a": "x"}"#).unwrap();
+    assert_eq!(yason.format(false).unwrap(), r#"{"a":"x","b":[1,true,null]}"#);
+
+    let expected = YasonBuf::parse(r#"{"b": [1, true, null], "a": "x"}"#).unwrap();
+    assert_eq!(yason.to_bytes(), expected.as_bytes());
+
+    let from_bytes = JsYason::from_bytes(expected.as_bytes()).unwrap();
+    assert_eq!(from_bytes.format(true).unwrap(), expected.format(true).to_string());
+}